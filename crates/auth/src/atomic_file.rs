@@ -0,0 +1,62 @@
+use std::{io::Write, path::Path};
+
+use rand::RngCore;
+
+/// Atomically replaces the file, the content is written to a temporary file next to it which is then renamed over it
+pub fn write_safe(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    write(path, content, false)
+}
+
+/// Like [`write_safe`], but the file is only readable by the current user
+pub fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    write(path, content, true)
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn write(path: &Path, content: &[u8], private: bool) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let mut temp = path.to_path_buf();
+    temp.add_extension(format!("{}", rand::thread_rng().next_u32()));
+    temp.add_extension("new");
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = (|| {
+        let mut temp_file = options.open(&temp)?;
+        temp_file.write_all(content)?;
+        temp_file.sync_all()?;
+        drop(temp_file);
+        std::fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn replaces_without_leftovers() {
+        let dir = TempDir::new("atomic_file");
+        let path = dir.join("nested").join("file.json");
+
+        write_safe(&path, b"first").unwrap();
+        write_safe(&path, b"second").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    }
+}
@@ -52,6 +52,12 @@ impl AuthStageWithData {
 }
 
 impl AccountCredentials {
+    /// Returns the cached access token if it hasn't expired, without touching any other stage
+    pub fn fresh_access_token(&self) -> Option<MinecraftAccessToken> {
+        let access_token = self.access_token.as_ref()?;
+        (Utc::now() < access_token.expiry).then(|| MinecraftAccessToken(Arc::clone(&access_token.token)))
+    }
+
     pub fn stage(&mut self) -> AuthStageWithData {
         let now = Utc::now();
 
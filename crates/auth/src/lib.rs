@@ -1,6 +1,9 @@
+pub mod atomic_file;
 pub mod authenticator;
 pub mod constants;
 pub mod credentials;
 pub mod models;
 pub mod secret;
 pub mod serve_redirect;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
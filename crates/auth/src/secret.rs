@@ -1,3 +1,11 @@
+use std::{collections::HashMap, num::NonZeroU32, path::PathBuf, sync::{atomic::{AtomicBool, Ordering}, Mutex}};
+
+use rand::RngCore;
+use ring::{aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN}, pbkdf2};
+use uuid::Uuid;
+
+use crate::credentials::AccountCredentials;
+
 pub use inner::*;
 
 #[derive(thiserror::Error, Debug)]
@@ -12,6 +20,8 @@ pub enum SecretStorageError {
     UnknownError,
     #[error("Not unique")]
     NotUnique,
+    #[error("Encryption error")]
+    EncryptionError,
     #[cfg(target_os = "windows")]
     #[error("Windows error: {0}")]
     WindowsError(#[from] windows::core::Error),
@@ -109,6 +119,33 @@ mod inner {
             keyring.delete(&attributes).await?;
             Ok(())
         }
+
+        pub async fn read_secret(&self, key: &str) -> Result<Option<String>, SecretStorageError> {
+            let keyring = self.keyring.as_ref()?;
+            keyring.unlock().await?;
+
+            let attributes = vec![("service", "pandora-launcher"), ("secret", key)];
+
+            let items = keyring.search_items(&attributes).await?;
+            let Some(item) = items.first() else {
+                return Ok(None);
+            };
+            let raw = item.secret().await?;
+            Ok(Some(String::from_utf8(raw.to_vec()).map_err(|_| SecretStorageError::SerializationError)?))
+        }
+
+        pub async fn write_secret(&self, key: &str, value: Option<&str>) -> Result<(), SecretStorageError> {
+            let keyring = self.keyring.as_ref()?;
+            keyring.unlock().await?;
+
+            let attributes = vec![("service", "pandora-launcher"), ("secret", key)];
+
+            match value {
+                Some(value) => keyring.create_item("Pandora Launcher", &attributes, value.as_bytes(), true).await?,
+                None => keyring.delete(&attributes).await?,
+            }
+            Ok(())
+        }
     }
 }
 
@@ -122,45 +159,78 @@ mod inner {
 
     pub struct PlatformSecretStorage;
 
-    impl PlatformSecretStorage {
-        pub async fn new() -> Result<Self, SecretStorageError> {
-            Ok(Self)
-        }
+    fn read_raw(target: String) -> Result<Option<Vec<u8>>, SecretStorageError> {
+        let mut target_name: Vec<u16> = target.encode_utf16().chain(std::iter::once(0)).collect();
 
-        pub async fn read_credentials(&self, uuid: Uuid) -> Result<Option<AccountCredentials>, SecretStorageError> {
-            let target_name = format!("PandoraLauncher_MinecraftAccount_{}", uuid.as_hyphenated());
+        let mut credentials: *mut CREDENTIALW = std::ptr::null_mut();
 
-            fn read<T: for<'a> serde::Deserialize<'a>>(target: String) -> Result<Option<T>, SecretStorageError> {
-                let mut target_name: Vec<u16> = target.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            let result = CredReadW(
+                windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
+                CRED_TYPE_GENERIC,
+                None,
+                &mut credentials,
+            );
 
-                let mut credentials: *mut CREDENTIALW = std::ptr::null_mut();
+            if let Err(error) = result {
+                const ERROR_NOT_FOUND: windows::core::HRESULT =
+                    windows::core::HRESULT::from_win32(windows::Win32::Foundation::ERROR_NOT_FOUND.0);
+                if error.code() == ERROR_NOT_FOUND {
+                    return Ok(None);
+                }
+                return Err(error.into());
+            }
 
-                unsafe {
-                    let result = CredReadW(
-                        windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
-                        CRED_TYPE_GENERIC,
-                        None,
-                        &mut credentials,
-                    );
-
-                    if let Err(error) = result {
-                        const ERROR_NOT_FOUND: windows::core::HRESULT =
-                            windows::core::HRESULT::from_win32(windows::Win32::Foundation::ERROR_NOT_FOUND.0);
-                        if error.code() == ERROR_NOT_FOUND {
-                            return Ok(None);
-                        }
-                        return Err(error.into());
-                    }
+            let Some(credentials) = credentials.as_mut() else {
+                return Ok(None);
+            };
 
-                    let Some(credentials) = credentials.as_mut() else {
-                        return Ok(None);
-                    };
+            let raw =
+                std::slice::from_raw_parts(credentials.CredentialBlob, credentials.CredentialBlobSize as usize);
+            Ok(Some(raw.to_vec()))
+        }
+    }
 
-                    let raw =
-                        std::slice::from_raw_parts(credentials.CredentialBlob, credentials.CredentialBlobSize as usize);
-                    Ok(Some(serde_json::from_slice(&raw).map_err(|_| SecretStorageError::SerializationError)?))
-                }
+    fn read<T: for<'a> serde::Deserialize<'a>>(target: String) -> Result<Option<T>, SecretStorageError> {
+        let Some(raw) = read_raw(target)? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&raw).map_err(|_| SecretStorageError::SerializationError)?))
+    }
+
+    fn write_inner(target: String, bytes: Option<Vec<u8>>) -> Result<(), SecretStorageError> {
+        let mut target_name: Vec<u16> = target.encode_utf16().chain(std::iter::once(0)).collect();
+
+        if let Some(mut bytes) = bytes {
+            let credentials = CREDENTIALW {
+                Flags: CRED_FLAGS(0),
+                Type: CRED_TYPE_GENERIC,
+                TargetName: windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
+                CredentialBlobSize: bytes.len() as u32,
+                CredentialBlob: bytes.as_mut_ptr(),
+                Persist: CRED_PERSIST_LOCAL_MACHINE,
+                ..CREDENTIALW::default()
+            };
+
+            unsafe { Ok(CredWriteW(&credentials, 0)?) }
+        } else {
+            unsafe {
+                Ok(CredDeleteW(
+                    windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
+                    CRED_TYPE_GENERIC,
+                    None,
+                )?)
             }
+        }
+    }
+
+    impl PlatformSecretStorage {
+        pub async fn new() -> Result<Self, SecretStorageError> {
+            Ok(Self)
+        }
+
+        pub async fn read_credentials(&self, uuid: Uuid) -> Result<Option<AccountCredentials>, SecretStorageError> {
+            let target_name = format!("PandoraLauncher_MinecraftAccount_{}", uuid.as_hyphenated());
 
             let mut account = AccountCredentials::default();
 
@@ -179,32 +249,6 @@ mod inner {
             uuid: Uuid,
             credentials: &AccountCredentials,
         ) -> Result<(), SecretStorageError> {
-            fn write_inner(target: String, bytes: Option<Vec<u8>>) -> Result<(), SecretStorageError> {
-                let mut target_name: Vec<u16> = target.encode_utf16().chain(std::iter::once(0)).collect();
-
-                if let Some(mut bytes) = bytes {
-                    let credentials = CREDENTIALW {
-                        Flags: CRED_FLAGS(0),
-                        Type: CRED_TYPE_GENERIC,
-                        TargetName: windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
-                        CredentialBlobSize: bytes.len() as u32,
-                        CredentialBlob: bytes.as_mut_ptr(),
-                        Persist: CRED_PERSIST_LOCAL_MACHINE,
-                        ..CREDENTIALW::default()
-                    };
-
-                    unsafe { Ok(CredWriteW(&credentials, 0)?) }
-                } else {
-                    unsafe {
-                        Ok(CredDeleteW(
-                            windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
-                            CRED_TYPE_GENERIC,
-                            None,
-                        )?)
-                    }
-                }
-            }
-
             fn write(target: String, data: Option<&impl serde::Serialize>) -> Result<(), SecretStorageError> {
                 let bytes = data
                     .map(|v| serde_json::to_vec(v).map_err(|_| SecretStorageError::SerializationError))
@@ -241,6 +285,25 @@ mod inner {
 
             Ok(())
         }
+
+        pub async fn read_secret(&self, key: &str) -> Result<Option<String>, SecretStorageError> {
+            let Some(raw) = read_raw(format!("PandoraLauncher_Secret_{}", key))? else {
+                return Ok(None);
+            };
+            Ok(Some(String::from_utf8(raw).map_err(|_| SecretStorageError::SerializationError)?))
+        }
+
+        pub async fn write_secret(&self, key: &str, value: Option<&str>) -> Result<(), SecretStorageError> {
+            let target = format!("PandoraLauncher_Secret_{}", key);
+            match value {
+                Some(value) => write_inner(target, Some(value.as_bytes().to_vec())),
+                // Deleting a secret that was never written isn't an error
+                None => match read_raw(target.clone())? {
+                    Some(_) => write_inner(target, None),
+                    None => Ok(()),
+                },
+            }
+        }
     }
 }
 
@@ -305,5 +368,368 @@ mod inner {
             item.delete();
             Ok(())
         }
+
+        pub async fn read_secret(&self, key: &str) -> Result<Option<String>, SecretStorageError> {
+            let account = format!("secret-{}", key);
+            match self.keychain.find_generic_password("com.moulberry.pandoralauncher", &account) {
+                Ok((data, _)) => Ok(Some(String::from_utf8(data.to_vec()).map_err(|_| SecretStorageError::SerializationError)?)),
+                Err(error) if error.code() == security_framework_sys::base::errSecItemNotFound => Ok(None),
+                Err(error) => Err(error.into()),
+            }
+        }
+
+        pub async fn write_secret(&self, key: &str, value: Option<&str>) -> Result<(), SecretStorageError> {
+            let account = format!("secret-{}", key);
+            match value {
+                Some(value) => {
+                    self.keychain.set_generic_password("com.moulberry.pandoralauncher", &account, value.as_bytes())?;
+                },
+                None => match self.keychain.find_generic_password("com.moulberry.pandoralauncher", &account) {
+                    Ok((_, item)) => item.delete(),
+                    Err(error) if error.code() == security_framework_sys::base::errSecItemNotFound => {},
+                    Err(error) => return Err(error.into()),
+                },
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Where account credentials are kept, the platform secret storage unless running as a portable install
+pub enum SecretStorage {
+    Platform(PlatformSecretStorage),
+    File(FileSecretStorage),
+}
+
+impl SecretStorage {
+    pub async fn read_credentials(&self, uuid: Uuid) -> Result<Option<AccountCredentials>, SecretStorageError> {
+        match self {
+            SecretStorage::Platform(storage) => storage.read_credentials(uuid).await,
+            SecretStorage::File(storage) => storage.read_credentials(uuid),
+        }
+    }
+
+    pub async fn write_credentials(&self, uuid: Uuid, credentials: &AccountCredentials) -> Result<(), SecretStorageError> {
+        match self {
+            SecretStorage::Platform(storage) => storage.write_credentials(uuid, credentials).await,
+            SecretStorage::File(storage) => storage.write_credentials(uuid, credentials),
+        }
+    }
+
+    pub async fn delete_credentials(&self, uuid: Uuid) -> Result<(), SecretStorageError> {
+        match self {
+            SecretStorage::Platform(storage) => storage.delete_credentials(uuid).await,
+            SecretStorage::File(storage) => storage.delete_credentials(uuid),
+        }
+    }
+
+    /// Reads a secret of the launcher itself (e.g. the proxy password) that isn't tied to an account
+    pub async fn read_secret(&self, key: &str) -> Result<Option<String>, SecretStorageError> {
+        match self {
+            SecretStorage::Platform(storage) => storage.read_secret(key).await,
+            SecretStorage::File(storage) => storage.read_secret(key),
+        }
+    }
+
+    /// Stores a secret of the launcher itself, or removes it if the value is `None`
+    pub async fn write_secret(&self, key: &str, value: Option<&str>) -> Result<(), SecretStorageError> {
+        match self {
+            SecretStorage::Platform(storage) => storage.write_secret(key, value).await,
+            SecretStorage::File(storage) => storage.write_secret(key, value),
+        }
+    }
+}
+
+/// Credentials for portable installs, which shouldn't leave anything behind in the secret storage of the machine.
+///
+/// Credentials are only kept in memory unless the user agreed to saving them and the launcher lock is enabled, in
+/// which case they're written next to the launcher encrypted with a key derived from the lock secret. The file can
+/// only be read once the launcher has been unlocked
+pub struct FileSecretStorage {
+    path: PathBuf,
+    save_to_file: AtomicBool,
+    /// Unknown until the launcher is unlocked, nothing is read from or written to the file before that
+    key: Mutex<Option<FileKey>>,
+    /// Account credentials by hyphenated UUID and other secrets by `secret:<key>`
+    credentials: Mutex<HashMap<String, serde_json::Value>>,
+}
+
+impl FileSecretStorage {
+    pub fn new(path: PathBuf, save_to_file: bool) -> Self {
+        Self {
+            path,
+            save_to_file: AtomicBool::new(save_to_file),
+            key: Mutex::new(None),
+            credentials: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads the saved credentials with the launcher lock secret. Credentials written before unlocking take precedence
+    pub async fn unlock(&self, secret: String) -> Result<(), SecretStorageError> {
+        if self.key.lock().unwrap().is_some() {
+            return Ok(());
+        }
+
+        let file = match std::fs::read(&self.path) {
+            Ok(file) => Some(file),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(_) => return Err(SecretStorageError::IoError),
+        };
+        let salt = file.as_deref().and_then(FileKey::salt_of).unwrap_or_else(FileKey::random_salt);
+        let key = FileKey::derive(secret, salt).await?;
+
+        if let Some(file) = file && self.save_to_file.load(Ordering::SeqCst) {
+            match key.open(file) {
+                Ok(saved) => {
+                    let mut credentials = self.credentials.lock().unwrap();
+                    for (key, value) in saved {
+                        credentials.entry(key).or_insert(value);
+                    }
+                },
+                Err(error) => log::warn!("Unable to read saved credentials, accounts will need to log in again: {error}"),
+            }
+        }
+
+        *self.key.lock().unwrap() = Some(key);
+        Ok(())
+    }
+
+    /// Encrypts the saved credentials for a new launcher lock secret. Without a lock they can't be protected, so
+    /// they're removed from disk and only kept in memory
+    pub async fn set_lock_secret(&self, secret: Option<String>) -> Result<(), SecretStorageError> {
+        let key = match secret {
+            Some(secret) => Some(FileKey::derive(secret, FileKey::random_salt()).await?),
+            None => None,
+        };
+        let has_key = key.is_some();
+        *self.key.lock().unwrap() = key;
+
+        if has_key {
+            self.save(&self.credentials.lock().unwrap())
+        } else {
+            self.remove_file()
+        }
+    }
+
+    /// Starts or stops saving credentials to disk. Stopping removes the saved credentials, but keeps them in memory
+    /// so the accounts stay logged in until the launcher is closed
+    pub fn set_save_to_file(&self, save_to_file: bool) -> Result<(), SecretStorageError> {
+        if self.save_to_file.swap(save_to_file, Ordering::SeqCst) == save_to_file {
+            return Ok(());
+        }
+
+        if save_to_file {
+            self.save(&self.credentials.lock().unwrap())
+        } else {
+            self.remove_file()
+        }
+    }
+
+    fn read_credentials(&self, uuid: Uuid) -> Result<Option<AccountCredentials>, SecretStorageError> {
+        let Some(value) = self.credentials.lock().unwrap().get(&uuid.as_hyphenated().to_string()).cloned() else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_value(value).map_err(|_| SecretStorageError::SerializationError)?))
+    }
+
+    fn write_credentials(&self, uuid: Uuid, credentials: &AccountCredentials) -> Result<(), SecretStorageError> {
+        let value = serde_json::to_value(credentials).map_err(|_| SecretStorageError::SerializationError)?;
+        let mut all_credentials = self.credentials.lock().unwrap();
+        all_credentials.insert(uuid.as_hyphenated().to_string(), value);
+        self.save(&all_credentials)
+    }
+
+    fn delete_credentials(&self, uuid: Uuid) -> Result<(), SecretStorageError> {
+        let mut all_credentials = self.credentials.lock().unwrap();
+        if all_credentials.remove(&uuid.as_hyphenated().to_string()).is_none() {
+            return Ok(());
+        }
+        self.save(&all_credentials)
+    }
+
+    fn read_secret(&self, key: &str) -> Result<Option<String>, SecretStorageError> {
+        let all_credentials = self.credentials.lock().unwrap();
+        match all_credentials.get(&format!("secret:{}", key)) {
+            Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(SecretStorageError::SerializationError),
+            None => Ok(None),
+        }
+    }
+
+    fn write_secret(&self, key: &str, value: Option<&str>) -> Result<(), SecretStorageError> {
+        let mut all_credentials = self.credentials.lock().unwrap();
+        let key = format!("secret:{}", key);
+        match value {
+            Some(value) => {
+                all_credentials.insert(key, serde_json::Value::String(value.to_string()));
+            },
+            None => {
+                if all_credentials.remove(&key).is_none() {
+                    return Ok(());
+                }
+            },
+        }
+        self.save(&all_credentials)
+    }
+
+    fn save(&self, credentials: &HashMap<String, serde_json::Value>) -> Result<(), SecretStorageError> {
+        if !self.save_to_file.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let key = self.key.lock().unwrap();
+        let Some(key) = key.as_ref() else {
+            return Ok(());
+        };
+
+        let data = serde_json::to_vec(credentials).map_err(|_| SecretStorageError::SerializationError)?;
+        crate::atomic_file::write_private(&self.path, &key.seal(data)?).map_err(|_| SecretStorageError::IoError)
+    }
+
+    fn remove_file(&self) -> Result<(), SecretStorageError> {
+        match std::fs::remove_file(&self.path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(SecretStorageError::IoError),
+            _ => Ok(()),
+        }
+    }
+}
+
+const KEY_SALT_LEN: usize = 16;
+const KEY_ITERATIONS: NonZeroU32 = NonZeroU32::new(100_000).unwrap();
+
+/// Encrypts the credentials file, the salt the key was derived with and the nonce are stored in front of the data
+struct FileKey {
+    salt: [u8; KEY_SALT_LEN],
+    key: LessSafeKey,
+}
+
+impl FileKey {
+    async fn derive(secret: String, salt: [u8; KEY_SALT_LEN]) -> Result<Self, SecretStorageError> {
+        tokio::task::spawn_blocking(move || Self::derive_blocking(&secret, salt))
+            .await
+            .map_err(|_| SecretStorageError::UnknownError)?
+    }
+
+    fn derive_blocking(secret: &str, salt: [u8; KEY_SALT_LEN]) -> Result<Self, SecretStorageError> {
+        let mut key = [0_u8; 32];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, KEY_ITERATIONS, &salt, secret.as_bytes(), &mut key);
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &key).map_err(|_| SecretStorageError::EncryptionError)?;
+        Ok(Self { salt, key: LessSafeKey::new(key) })
+    }
+
+    fn random_salt() -> [u8; KEY_SALT_LEN] {
+        let mut salt = [0_u8; KEY_SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        salt
+    }
+
+    fn salt_of(file: &[u8]) -> Option<[u8; KEY_SALT_LEN]> {
+        file.get(..KEY_SALT_LEN)?.try_into().ok()
+    }
+
+    fn seal(&self, mut data: Vec<u8>) -> Result<Vec<u8>, SecretStorageError> {
+        let mut nonce = [0_u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        self.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| SecretStorageError::EncryptionError)?;
+
+        let mut file = self.salt.to_vec();
+        file.extend_from_slice(&nonce);
+        file.extend_from_slice(&data);
+        Ok(file)
+    }
+
+    fn open(&self, mut file: Vec<u8>) -> Result<HashMap<String, serde_json::Value>, SecretStorageError> {
+        if file.len() < KEY_SALT_LEN + NONCE_LEN || file[..KEY_SALT_LEN] != self.salt {
+            return Err(SecretStorageError::EncryptionError);
+        }
+        let mut encrypted = file.split_off(KEY_SALT_LEN + NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = file[KEY_SALT_LEN..].try_into().map_err(|_| SecretStorageError::EncryptionError)?;
+
+        let decrypted = self.key.open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut encrypted)
+            .map_err(|_| SecretStorageError::EncryptionError)?;
+        serde_json::from_slice(decrypted).map_err(|_| SecretStorageError::SerializationError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn encryption_round_trip() {
+        let key = FileKey::derive_blocking("1234", FileKey::random_salt()).unwrap();
+        let data = br#"{"account":{"msa_refresh":"M.C123"}}"#.to_vec();
+        let file = key.seal(data).unwrap();
+        assert!(!file.windows(6).any(|window| window == b"M.C123"));
+        assert_eq!(key.open(file.clone()).unwrap().len(), 1);
+
+        let other_key = FileKey::derive_blocking("4321", FileKey::salt_of(&file).unwrap()).unwrap();
+        assert!(other_key.open(file).is_err());
+        assert!(key.open(vec![0; 4]).is_err());
+    }
+
+    #[tokio::test]
+    async fn saved_credentials_are_read_back_after_unlocking() {
+        let dir = TempDir::new("secret");
+        let path = dir.join("credentials.bin");
+        let uuid = Uuid::new_v4();
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        storage.unlock("1234".into()).await.unwrap();
+        let credentials = AccountCredentials {
+            msa_refresh: Some("M.C123".into()),
+            ..AccountCredentials::default()
+        };
+        storage.write_credentials(uuid, &credentials).unwrap();
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        assert!(storage.read_credentials(uuid).unwrap().is_none());
+        storage.unlock("1234".into()).await.unwrap();
+        let read = storage.read_credentials(uuid).unwrap().unwrap();
+        assert_eq!(read.msa_refresh.as_deref(), Some("M.C123"));
+        storage.delete_credentials(uuid).unwrap();
+        assert!(storage.read_credentials(uuid).unwrap().is_none());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_secret_reads_nothing() {
+        let dir = TempDir::new("secret");
+        let path = dir.join("credentials.bin");
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        storage.unlock("1234".into()).await.unwrap();
+        storage.write_secret("proxy_password", Some("hunter2")).unwrap();
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        storage.unlock("4321".into()).await.unwrap();
+        assert!(storage.read_secret("proxy_password").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn changing_the_lock_secret_reencrypts() {
+        let dir = TempDir::new("secret");
+        let path = dir.join("credentials.bin");
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        storage.unlock("1234".into()).await.unwrap();
+        storage.write_secret("proxy_password", Some("hunter2")).unwrap();
+        storage.set_lock_secret(Some("5678".into())).await.unwrap();
+
+        let storage = FileSecretStorage::new(path.clone(), true);
+        storage.unlock("5678".into()).await.unwrap();
+        assert_eq!(storage.read_secret("proxy_password").unwrap().as_deref(), Some("hunter2"));
+
+        storage.set_lock_secret(None).await.unwrap();
+        assert!(!path.exists());
+        assert_eq!(storage.read_secret("proxy_password").unwrap().as_deref(), Some("hunter2"));
+        storage.write_secret("proxy_password", Some("hunter3")).unwrap();
+        assert!(!path.exists());
     }
 }
@@ -1,7 +1,9 @@
-use std::sync::Arc;
+use std::{path::{Path, PathBuf}, sync::Arc};
 
-use auth::models::{MinecraftAccessToken, MinecraftProfileResponse};
-use bridge::{account::Account, message::MessageToFrontend};
+use auth::{credentials::AccountCredentials, models::{MinecraftAccessToken, MinecraftProfileResponse}, secret::SecretStorageError};
+use bridge::{account::{Account, AccountTokenState}, message::MessageToFrontend};
+use chrono::Utc;
+use image::DynamicImage;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -10,6 +12,14 @@ pub struct MinecraftLoginInfo {
     pub uuid: Uuid,
     pub username: Arc<str>,
     pub access_token: Option<MinecraftAccessToken>,
+    /// The authentication servers couldn't be reached, so the account is being used without logging in
+    pub offline_fallback: bool,
+}
+
+pub enum LoginFlowResult {
+    LoggedIn(MinecraftProfileResponse, MinecraftAccessToken),
+    /// A connection error occurred while logging in, the cached access token is kept if it's still fresh
+    Unreachable(Option<MinecraftAccessToken>),
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -19,13 +29,14 @@ pub struct BackendAccountInfo {
 }
 
 impl BackendAccountInfo {
-    pub fn create_update_message(&self) -> MessageToFrontend {
+    pub fn create_update_message(&self, heads: &mut AccountHeads) -> MessageToFrontend {
         let mut accounts = Vec::with_capacity(self.accounts.len());
         for (uuid, account) in &self.accounts {
             accounts.push(Account {
                 uuid: *uuid,
                 username: account.username.clone(),
-                head: account.head.clone(),
+                head: heads.get(*uuid),
+                offline: account.offline,
             });
         }
         accounts.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.username, &b.username));
@@ -41,7 +52,12 @@ pub struct BackendAccount {
     pub username: Arc<str>,
     #[serde(default)]
     pub offline: bool,
-    pub head: Option<Arc<[u8]>>,
+    /// Heads used to be embedded in accounts.json, they are moved into [`AccountHeads`] on load
+    #[serde(default, rename = "head", skip_serializing)]
+    pub legacy_head: Option<Arc<[u8]>>,
+    /// Kept so the head can be rendered again without logging in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skin_url: Option<Arc<str>>,
 }
 
 impl BackendAccount {
@@ -49,7 +65,147 @@ impl BackendAccount {
         Self {
             username: profile.name.clone(),
             offline: false,
-            head: None,
+            legacy_head: None,
+            skin_url: None,
+        }
+    }
+}
+
+/// Describes how usable the stored credentials of an account are, without refreshing them
+pub fn token_state(credentials: Result<Option<AccountCredentials>, SecretStorageError>) -> AccountTokenState {
+    let credentials = match credentials {
+        Ok(Some(credentials)) => credentials,
+        Ok(None) => return AccountTokenState::LoginRequired,
+        Err(error) => {
+            log::warn!("Unable to read credentials from keychain: {error}");
+            return AccountTokenState::Unknown;
+        },
+    };
+
+    if let Some(access_token) = &credentials.access_token && Utc::now() < access_token.expiry {
+        AccountTokenState::Valid { expires_at: access_token.expiry.into() }
+    } else if credentials.msa_refresh.is_some() {
+        AccountTokenState::Refreshable
+    } else {
+        AccountTokenState::LoginRequired
+    }
+}
+
+/// Crops the face out of a skin, with the hat layer on top of it unless `overlay` is false
+pub fn render_head(skin: &DynamicImage, overlay: bool) -> DynamicImage {
+    // HD skins are a multiple of 64 pixels wide
+    let scale = (skin.width() / 64).max(1);
+    let mut head = skin.crop_imm(8 * scale, 8 * scale, 8 * scale, 8 * scale);
+    if !overlay {
+        return head;
+    }
+
+    let hat = skin.crop_imm(40 * scale, 8 * scale, 8 * scale, 8 * scale);
+
+    // Like the game, ignore the hat layer of legacy 64x32 skins when it has no transparency,
+    // many of those skins filled it with a solid colour
+    let legacy = skin.height() * 2 == skin.width();
+    if legacy && hat.to_rgba8().pixels().all(|pixel| pixel[3] >= 128) {
+        return head;
+    }
+
+    image::imageops::overlay(&mut head, &hat, 0, 0);
+    head
+}
+
+/// Account heads are stored as separate PNG files so that accounts.json stays small
+pub struct AccountHeads {
+    dir: Arc<Path>,
+    heads: FxHashMap<Uuid, Option<Arc<[u8]>>>,
+}
+
+impl AccountHeads {
+    pub fn new(dir: Arc<Path>) -> Self {
+        Self {
+            dir,
+            heads: FxHashMap::default(),
+        }
+    }
+
+    pub fn get(&mut self, uuid: Uuid) -> Option<Arc<[u8]>> {
+        let dir = &self.dir;
+        self.heads.entry(uuid).or_insert_with(|| {
+            std::fs::read(Self::head_path(dir, uuid)).ok().map(Arc::from)
+        }).clone()
+    }
+
+    /// Returns false if the head was unchanged
+    pub fn set(&mut self, uuid: Uuid, head: Arc<[u8]>) -> bool {
+        if self.get(uuid).is_some_and(|existing| existing == head) {
+            return false;
+        }
+
+        crate::write_behind::global().enqueue(Self::head_path(&self.dir, uuid).into(), head.to_vec());
+        self.heads.insert(uuid, Some(head));
+        true
+    }
+
+    pub fn remove(&mut self, uuid: Uuid) {
+        self.heads.remove(&uuid);
+        let path = Self::head_path(&self.dir, uuid);
+        crate::write_behind::global().cancel(&path);
+        _ = std::fs::remove_file(path);
+    }
+
+    /// Moves heads that are still embedded in accounts.json into separate files
+    pub fn migrate_legacy(&mut self, info: &mut BackendAccountInfo) {
+        for (uuid, account) in &mut info.accounts {
+            if let Some(head) = account.legacy_head.take() {
+                self.set(*uuid, head);
+            }
         }
     }
+
+    fn head_path(dir: &Path, uuid: Uuid) -> PathBuf {
+        dir.join(format!("{}.png", uuid.as_hyphenated()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use auth::models::TokenWithExpiry;
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    fn credentials(expires_in: Option<TimeDelta>, refresh: bool) -> AccountCredentials {
+        AccountCredentials {
+            msa_refresh: refresh.then(|| "refresh".into()),
+            access_token: expires_in.map(|expires_in| TokenWithExpiry {
+                token: "access".into(),
+                expiry: Utc::now() + expires_in,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unexpired_access_token_is_valid() {
+        let state = token_state(Ok(Some(credentials(Some(TimeDelta::hours(1)), false))));
+        let AccountTokenState::Valid { expires_at } = state else {
+            panic!("expected a valid token, got {state:?}");
+        };
+        assert!(expires_at > SystemTime::now());
+    }
+
+    #[test]
+    fn expired_access_token_needs_refresh_or_login() {
+        let expired = Some(TimeDelta::minutes(-1));
+        assert_eq!(token_state(Ok(Some(credentials(expired, true)))), AccountTokenState::Refreshable);
+        assert_eq!(token_state(Ok(Some(credentials(expired, false)))), AccountTokenState::LoginRequired);
+        assert_eq!(token_state(Ok(Some(credentials(None, true)))), AccountTokenState::Refreshable);
+    }
+
+    #[test]
+    fn missing_or_unreadable_credentials() {
+        assert_eq!(token_state(Ok(None)), AccountTokenState::LoginRequired);
+        assert_eq!(token_state(Err(SecretStorageError::AccessDenied)), AccountTokenState::Unknown);
+    }
 }
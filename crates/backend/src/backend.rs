@@ -1,58 +1,94 @@
 use std::{
-    collections::{HashMap, HashSet}, io::Cursor, path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime}
+    collections::{HashMap, HashSet}, io::Cursor, path::{Path, PathBuf}, sync::{atomic::AtomicBool, Arc}, time::{Duration, Instant, SystemTime}
 };
 
 use auth::{
     authenticator::{Authenticator, MsaAuthorizationError, XboxAuthenticateError},
     credentials::{AccountCredentials, AUTH_STAGE_COUNT},
     models::{MinecraftAccessToken, MinecraftProfileResponse, SkinState},
-    secret::{PlatformSecretStorage, SecretStorageError},
+    secret::{FileSecretStorage, PlatformSecretStorage, SecretStorage, SecretStorageError},
     serve_redirect::{self, ProcessAuthorizationError},
 };
 use bridge::{
-    handle::{BackendHandle, BackendReceiver, FrontendHandle}, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{InstanceID, InstanceContentSummary, InstanceServerSummary, InstanceWorldSummary, ContentType}, message::MessageToFrontend, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
+    handle::{BackendHandle, BackendReceiver, FrontendHandle}, keep_alive::KeepAliveHandle, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{InstanceID, InstanceContentSummary, InstanceServerSummary, InstanceWorldSummary, ContentType}, message::{FileWatchDebugInfo, FileWatchEntry, MessageToFrontend}, modal_action::{BackgroundTaskKind, ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
 };
+use futures::StreamExt;
 use indexmap::IndexSet;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use reqwest::{StatusCode, redirect::Policy};
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{backend_config::BackendConfig, instance::InstanceConfiguration, loader::Loader, modrinth::ModrinthSideRequirement};
+use schema::{backend_config::{BackendConfig, BackendSettings, MirrorConfig, ProxyConfig}, following::FollowedProjects, instance::InstanceConfiguration, launch_history::LaunchOutcome, loader::Loader, modrinth::{ModrinthProjectVersionsRequest, ModrinthSideRequirement}};
 use sha1::{Digest, Sha1};
+use strum::IntoEnumIterator;
 use tokio::sync::{mpsc::Receiver, OnceCell};
 use ustr::Ustr;
 use uuid::Uuid;
 
 use crate::{
-    account::{BackendAccountInfo, MinecraftLoginInfo}, directories::LauncherDirectories, id_slab::IdSlab, instance::{Instance, ContentFolder}, launch::Launcher, metadata::{items::MinecraftVersionManifestMetadataItem, manager::MetadataManager}, mod_metadata::ModMetadataManager, persistent::Persistent
+    account::{AccountHeads, BackendAccountInfo, LoginFlowResult, MinecraftLoginInfo}, directories::LauncherDirectories, id_slab::IdSlab, install_queue::InstallQueue, instance::{Instance, ContentFolder, InstanceLoadError}, launch::Launcher, metadata::{items::{FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, ModrinthProjectVersionsMetadataItem, NeoforgeInstallerMavenMetadataItem}, manager::MetadataManager}, mod_metadata::ModMetadataManager, modpack::ModpackFile, persistent::Persistent, process_monitor::ProcessMonitor, proxy::ProxyPassword, write_behind
 };
 
-pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHandle, recv: BackendReceiver) {
+/// Instance folders read at the same time during startup, mostly bound by disk access
+const MAX_CONCURRENT_INSTANCE_LOADS: usize = 8;
+
+/// How often instances whose folders aren't watched are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often followed Modrinth projects are checked for new versions
+const FOLLOWED_PROJECTS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const USER_AGENT: &str = "PandoraLauncher/0.1.0 (https://github.com/Moulberry/PandoraLauncher)";
+
+fn http_client_builder(settings: &BackendSettings) -> reqwest::ClientBuilder {
+    reqwest::ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs.max(1) as u64))
+        .read_timeout(Duration::from_secs(settings.read_timeout_secs.max(1) as u64))
+        .redirect(Policy::none())
+        .use_rustls_tls()
+        .user_agent(USER_AGENT)
+}
+
+pub(crate) fn redirecting_http_client_builder() -> reqwest::ClientBuilder {
+    reqwest::ClientBuilder::new()
+        .use_rustls_tls()
+        .user_agent(USER_AGENT)
+}
+
+/// Returns the proxy configuration and its password, which is filled in once it's been read from the secret storage.
+/// The frontend needs both for its own HTTP client
+pub fn start(launcher_dir: PathBuf, portable: bool, send: FrontendHandle, self_handle: BackendHandle, recv: BackendReceiver) -> (ProxyConfig, ProxyPassword) {
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(1)
         .enable_all()
         .build()
         .expect("Failed to initialize Tokio runtime");
 
-    let http_client = reqwest::ClientBuilder::new()
-        .connect_timeout(Duration::from_secs(15))
-        .read_timeout(Duration::from_secs(15))
-        .redirect(Policy::none())
-        .use_rustls_tls()
-        .user_agent("PandoraLauncher/0.1.0 (https://github.com/Moulberry/PandoraLauncher)")
-        .build()
-        .unwrap();
+    let directories = Arc::new(LauncherDirectories::new(launcher_dir, portable));
 
-    let redirecting_http_client = reqwest::ClientBuilder::new()
-        .use_rustls_tls()
-        .user_agent("PandoraLauncher/0.1.0 (https://github.com/Moulberry/PandoraLauncher)")
-        .build()
-        .unwrap();
+    // Load config
+    let mut config: Persistent<BackendConfig> = Persistent::load(directories.config_json.clone())
+        .with_write_behind(write_behind::global().clone());
+    let config_lock = config.get().launcher_lock.clone();
+    let launcher_locked = config_lock.is_some();
+
+    let secret_storage = Arc::new(OnceCell::new());
+    let proxy = config.get().proxy.clone();
+    let proxy_password = ProxyPassword::default();
+
+    let settings = config.get().settings;
+    let http_client = crate::proxy::build_client(|| http_client_builder(&settings), &proxy, &proxy_password);
+    let redirecting_http_client = crate::proxy::build_client(redirecting_http_client_builder, &proxy, &proxy_password);
 
-    let directories = Arc::new(LauncherDirectories::new(launcher_dir));
+    let mirrors = Arc::new(RwLock::new(config.get().mirrors.clone()));
+    let settings = Arc::new(RwLock::new(settings));
 
     let meta = Arc::new(MetadataManager::new(
         http_client.clone(),
+        mirrors.clone(),
+        config.get().metadata_cache,
         directories.metadata_dir.clone(),
+        directories.local_versions_dir.clone(),
+        send.clone(),
     ));
 
     let (watcher_tx, watcher_rx) = tokio::sync::mpsc::channel::<notify_debouncer_full::DebounceEventResult>(64);
@@ -74,6 +110,8 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
         watching: HashMap::new(),
         symlink_src_to_links: Default::default(),
         symlink_link_to_src: Default::default(),
+        event_counts: Default::default(),
+        unattributed_errors: 0,
     };
 
     // Create initial directories
@@ -81,10 +119,15 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
     state_file_watching.watch_filesystem(directories.root_launcher_dir.clone(), WatchTarget::RootDir);
 
     // Load accounts
-    let account_info = Persistent::load(directories.accounts_json.clone());
+    let mut account_info: Persistent<BackendAccountInfo> = Persistent::load(directories.accounts_json.clone())
+        .with_write_behind(write_behind::global().clone());
+    let mut account_heads = AccountHeads::new(directories.account_heads_dir.clone());
+    if account_info.get().accounts.values().any(|account| account.legacy_head.is_some()) {
+        account_info.modify(|info| account_heads.migrate_legacy(info));
+    }
 
-    // Load config
-    let config = Persistent::load(directories.config_json.clone());
+    let followed_projects: Persistent<FollowedProjects> = Persistent::load(directories.followed_projects_json.clone())
+        .with_write_behind(write_behind::global().clone());
 
     let mut state = BackendState {
         self_handle,
@@ -95,24 +138,55 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
         instance_state: Arc::new(RwLock::new(state_instances)),
         file_watching: Arc::new(RwLock::new(state_file_watching)),
         directories: Arc::clone(&directories),
-        launcher: Launcher::new(meta, directories, send),
+        launcher: Launcher::new(meta, directories, send, mirrors.clone(), settings.clone()),
+        mirrors,
+        settings,
         mod_metadata_manager: Arc::new(mod_metadata_manager),
         account_info: Arc::new(RwLock::new(account_info)),
+        account_heads: Arc::new(RwLock::new(account_heads)),
         config: Arc::new(RwLock::new(config)),
-        secret_storage: Arc::new(OnceCell::new()),
+        followed_projects: Arc::new(RwLock::new(followed_projects)),
+        launcher_locked: Arc::new(AtomicBool::new(launcher_locked)),
+        secret_storage,
+        proxy_password: proxy_password.clone(),
         head_cache: Default::default(),
+        install_queue: Default::default(),
+        process_monitor: Default::default(),
+        followed_check: Default::default(),
+        lan_watchers: Default::default(),
     };
 
     log::debug!("Doing initial backend load");
 
     runtime.block_on(async {
-        state.send.send(state.account_info.write().get().create_update_message());
+        state.send_accounts_update();
+        state.send.send(MessageToFrontend::BackendSettingsUpdated { settings: *state.settings.read() });
+        state.send_followed_projects_update();
+        state.send.send(crate::launcher_lock::create_update_message(config_lock.as_ref(), launcher_locked));
         state.load_all_instances().await;
+        if !state.config.write().get().disable_launcher_update_check {
+            state.check_launcher_update();
+        }
+        state.load_proxy_password();
     });
 
     runtime.spawn(state.start(recv, watcher_rx));
 
     std::mem::forget(runtime);
+
+    (proxy, proxy_password)
+}
+
+/// Portable installs keep credentials themselves instead of using the platform secret storage
+pub(crate) async fn open_secret_storage(directories: &LauncherDirectories, save_to_file: bool) -> Result<SecretStorage, SecretStorageError> {
+    if directories.portable {
+        Ok(SecretStorage::File(FileSecretStorage::new(
+            directories.portable_credentials.to_path_buf(),
+            save_to_file,
+        )))
+    } else {
+        PlatformSecretStorage::new().await.map(SecretStorage::Platform)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -140,6 +214,9 @@ pub struct BackendStateFileWatching {
     watching: HashMap<Arc<Path>, WatchTarget>,
     symlink_src_to_links: HashMap<Arc<Path>, IndexSet<Arc<Path>>>,
     symlink_link_to_src: HashMap<Arc<Path>, Arc<Path>>,
+    /// Events and errors received per watched path, only used for debugging
+    event_counts: HashMap<Arc<Path>, (usize, usize)>,
+    unattributed_errors: usize,
 }
 
 #[derive(Clone)]
@@ -148,6 +225,8 @@ pub struct BackendState {
     pub send: FrontendHandle,
     pub http_client: reqwest::Client,
     pub redirecting_http_client: reqwest::Client,
+    pub mirrors: Arc<RwLock<MirrorConfig>>,
+    pub settings: Arc<RwLock<BackendSettings>>,
     pub meta: Arc<MetadataManager>,
     pub instance_state: Arc<RwLock<BackendStateInstances>>,
     pub file_watching: Arc<RwLock<BackendStateFileWatching>>,
@@ -155,9 +234,35 @@ pub struct BackendState {
     pub launcher: Launcher,
     pub mod_metadata_manager: Arc<ModMetadataManager>,
     pub account_info: Arc<RwLock<Persistent<BackendAccountInfo>>>,
+    pub account_heads: Arc<RwLock<AccountHeads>>,
     pub config: Arc<RwLock<Persistent<BackendConfig>>>,
-    pub secret_storage: Arc<OnceCell<Result<PlatformSecretStorage, SecretStorageError>>>,
-    pub head_cache: Arc<RwLock<FxHashMap<Arc<str>, HeadCacheEntry>>>
+    pub followed_projects: Arc<RwLock<Persistent<FollowedProjects>>>,
+    pub launcher_locked: Arc<AtomicBool>,
+    pub secret_storage: Arc<OnceCell<Result<SecretStorage, SecretStorageError>>>,
+    pub proxy_password: ProxyPassword,
+    pub head_cache: Arc<RwLock<FxHashMap<Arc<str>, HeadCacheEntry>>>,
+    pub install_queue: Arc<InstallQueue>,
+    pub process_monitor: Arc<Mutex<ProcessMonitor>>,
+    pub followed_check: Arc<Mutex<FollowedCheck>>,
+    /// Pages showing LAN games, which are listened for while any of them is still open
+    pub lan_watchers: Arc<Mutex<Vec<KeepAliveHandle>>>,
+}
+
+/// Whether followed projects are being checked for new versions
+#[derive(Default)]
+pub struct FollowedCheck {
+    running: bool,
+    /// Set when a check was requested while one was running
+    again: bool,
+}
+
+/// Allows checking again if the check panics
+struct FollowedCheckGuard(Arc<Mutex<FollowedCheck>>);
+
+impl Drop for FollowedCheckGuard {
+    fn drop(&mut self) {
+        *self.0.lock() = FollowedCheck::default();
+    }
 }
 
 pub enum HeadCacheEntry {
@@ -170,6 +275,48 @@ pub enum HeadCacheEntry {
     Failed,
 }
 
+/// Lists the folders in the instances directory, least recently used first
+fn sorted_instance_paths(instances_dir: &Path) -> Vec<PathBuf> {
+    let mut paths_with_time = Vec::new();
+
+    for entry in std::fs::read_dir(instances_dir).unwrap() {
+        let Ok(entry) = entry else {
+            log::warn!("Error reading directory in instances folder: {:?}", entry.unwrap_err());
+            continue;
+        };
+
+        let path = entry.path();
+
+        let mut time = SystemTime::UNIX_EPOCH;
+        if let Ok(metadata) = path.metadata() {
+            if let Ok(created) = metadata.created() {
+                time = time.max(created);
+            }
+            if let Ok(modified) = metadata.modified() {
+                time = time.max(modified);
+            }
+        }
+
+        // options.txt exists in every minecraft version, so we use its
+        // modified time to determine the latest instance as well
+        let mut options_txt = path.join(".minecraft");
+        options_txt.push("options.txt");
+        if let Ok(metadata) = options_txt.metadata() {
+            if let Ok(created) = metadata.created() {
+                time = time.max(created);
+            }
+            if let Ok(modified) = metadata.modified() {
+                time = time.max(modified);
+            }
+        }
+
+        paths_with_time.push((path, time));
+    }
+
+    paths_with_time.sort_by_key(|(_, time)| *time);
+    paths_with_time.into_iter().map(|(path, _)| path).collect()
+}
+
 impl BackendState {
     async fn start(self, recv: BackendReceiver, watcher_rx: Receiver<notify_debouncer_full::DebounceEventResult>) {
         log::info!("Starting backend");
@@ -180,49 +327,30 @@ impl BackendState {
         self.handle(recv, watcher_rx).await;
     }
 
+    pub async fn secret_storage(&self) -> Result<&SecretStorage, &SecretStorageError> {
+        let save_to_file = self.config.write().get().portable_credentials_consent;
+        self.secret_storage.get_or_init(|| open_secret_storage(&self.directories, save_to_file)).await.as_ref()
+    }
+
     pub async fn load_all_instances(&mut self) {
         log::info!("Loading all instances");
 
-        let mut paths_with_time = Vec::new();
-
         self.file_watching.write().watch_filesystem(self.directories.instances_dir.clone(), WatchTarget::InstancesDir);
-        for entry in std::fs::read_dir(&self.directories.instances_dir).unwrap() {
-            let Ok(entry) = entry else {
-                log::warn!("Error reading directory in instances folder: {:?}", entry.unwrap_err());
-                continue;
-            };
 
-            let path = entry.path();
+        let instances_dir = self.directories.instances_dir.clone();
+        let paths = tokio::task::spawn_blocking(move || sorted_instance_paths(&instances_dir)).await.unwrap();
 
-            let mut time = SystemTime::UNIX_EPOCH;
-            if let Ok(metadata) = path.metadata() {
-                if let Ok(created) = metadata.created() {
-                    time = time.max(created);
-                }
-                if let Ok(modified) = metadata.modified() {
-                    time = time.max(modified);
-                }
-            }
-
-            // options.txt exists in every minecraft version, so we use its
-            // modified time to determine the latest instance as well
-            let mut options_txt = path.join(".minecraft");
-            options_txt.push("options.txt");
-            if let Ok(metadata) = options_txt.metadata() {
-                if let Ok(created) = metadata.created() {
-                    time = time.max(created);
-                }
-                if let Ok(modified) = metadata.modified() {
-                    time = time.max(modified);
-                }
-            }
+        // Folders are read in parallel, but added in order so the most recently played instance still ends up on top
+        let mut loaded = futures::stream::iter(paths)
+            .map(|path| tokio::task::spawn_blocking(move || {
+                let instance = Instance::load_from_folder(&path);
+                (path, instance)
+            }))
+            .buffered(MAX_CONCURRENT_INSTANCE_LOADS);
 
-            paths_with_time.push((path, time));
-        }
-
-        paths_with_time.sort_by_key(|(_, time)| *time);
-        for (path, _) in paths_with_time {
-            let success = self.load_instance_from_path(&path, true, false);
+        while let Some(result) = loaded.next().await {
+            let (path, instance) = result.unwrap();
+            let success = self.add_loaded_instance(&path, instance, true, false);
             if !success {
                 self.file_watching.write().watch_filesystem(path.into(), WatchTarget::InvalidInstanceDir);
             }
@@ -234,14 +362,20 @@ impl BackendState {
 
         let mut instance_state = self.instance_state.write();
 
-        if let Some(instance) = instance_state.instances.remove(id) {
+        if let Some(mut instance) = instance_state.instances.remove(id) {
+            instance.cancel_loads();
             self.send.send(MessageToFrontend::InstanceRemoved { id });
             self.send.send_info(format!("Instance '{}' removed", instance.name));
         }
     }
 
-    pub fn load_instance_from_path(&mut self, path: &Path, mut show_errors: bool, show_success: bool) -> bool {
+    pub fn load_instance_from_path(&mut self, path: &Path, show_errors: bool, show_success: bool) -> bool {
         let instance = Instance::load_from_folder(&path);
+        self.add_loaded_instance(path, instance, show_errors, show_success)
+    }
+
+    fn add_loaded_instance(&mut self, path: &Path, instance: Result<Instance, InstanceLoadError>, mut show_errors: bool, show_success: bool) -> bool {
+        let file_watching_disabled = self.config.write().get().disable_file_watching;
 
         let instance_id = {
             let mut instance_state_guard = self.instance_state.write();
@@ -281,6 +415,9 @@ impl BackendState {
             let generation = instance_state.instances_generation;
             instance_state.instances_generation = instance_state.instances_generation.wrapping_add(1);
 
+            instance.reattach(&mut self.process_monitor.lock());
+            instance.file_watching = instance.should_watch_files(file_watching_disabled);
+
             let instance = instance_state.instances.insert(move |index| {
                 let instance_id = InstanceID {
                     index,
@@ -304,6 +441,10 @@ impl BackendState {
                 resource_packs_state: Arc::clone(&instance.content_state[ContentFolder::ResourcePacks].load_state),
             };
             self.send.send(message);
+            self.send.send(instance.create_file_watching_message());
+            if instance.reattached.is_some() {
+                self.send.send(instance.create_modify_message());
+            }
 
             instance_state.instance_by_path.insert(path.to_owned(), instance.id);
 
@@ -319,6 +460,19 @@ impl BackendState {
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         tokio::pin!(interval);
 
+        let mut poll_interval = tokio::time::interval(POLL_INTERVAL);
+        poll_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        tokio::pin!(poll_interval);
+        // A poll of a slow network drive may still be running at the next interval
+        let mut poll_task: Option<tokio::task::JoinHandle<()>> = None;
+        // LAN games are only listened for while an instance is running or the frontend is showing them
+        let mut lan_task: Option<tokio::task::JoinHandle<()>> = None;
+
+        // The first tick completes immediately, which also does the check on startup
+        let mut followed_interval = tokio::time::interval(FOLLOWED_PROJECTS_INTERVAL);
+        followed_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        tokio::pin!(followed_interval);
+
         loop {
             tokio::select! {
                 message = backend_recv.recv() => {
@@ -339,6 +493,28 @@ impl BackendState {
                 },
                 _ = interval.tick() => {
                     self.handle_tick().await;
+
+                    let any_running = self.instance_state.read().instances.iter().any(|instance| instance.is_running());
+                    let watched = {
+                        let mut lan_watchers = self.lan_watchers.lock();
+                        lan_watchers.retain(KeepAliveHandle::is_alive);
+                        !lan_watchers.is_empty()
+                    };
+                    let listen = any_running || watched;
+                    if listen && lan_task.is_none() {
+                        lan_task = Some(tokio::task::spawn(crate::lan_discovery::listen(self.send.clone())));
+                    } else if !listen && let Some(task) = lan_task.take() {
+                        task.abort();
+                        self.send.send(MessageToFrontend::LanGamesUpdated { games: Arc::from([]) });
+                    }
+                },
+                _ = poll_interval.tick() => {
+                    if poll_task.as_ref().is_none_or(|task| task.is_finished()) {
+                        poll_task = Some(tokio::task::spawn(self.clone().poll_unwatched_instances()));
+                    }
+                },
+                _ = followed_interval.tick() => {
+                    self.check_followed_projects();
                 }
             }
         }
@@ -348,17 +524,104 @@ impl BackendState {
         self.meta.expire().await;
 
         let mut instance_state = self.instance_state.write();
+        let mut process_monitor = self.process_monitor.lock();
+        let mut running = Vec::new();
         for instance in instance_state.instances.iter_mut() {
-            if let Some(child) = &mut instance.child
-                && !matches!(child.try_wait(), Ok(None))
-            {
-                log::debug!("Child process is no longer alive");
-                instance.child = None;
-                self.send.send(instance.create_modify_message());
+            // None while the process is running, otherwise its exit code if known
+            let exited = if let Some(child) = &mut instance.child {
+                match child.try_wait() {
+                    Ok(None) => None,
+                    Ok(Some(status)) => Some(status.code()),
+                    Err(_) => Some(None),
+                }
+            } else if let Some(record) = instance.reattached {
+                // Adopted processes aren't children of this launcher, so their exit code can't be read
+                (!process_monitor.is_running(record)).then_some(None)
+            } else {
+                continue;
+            };
+
+            let Some(exit_code) = exited else {
+                if instance.stop_deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                    log::info!("Instance didn't stop within the grace period, killing it");
+                    if let Err(error) = instance.kill(&mut process_monitor) {
+                        log::error!("Failed to kill instance: {:?}", error);
+                    }
+                    instance.finish_launch_history(LaunchOutcome::Killed, None);
+                    self.send.send(instance.create_modify_message());
+                } else if let Some(pid) = instance.pid() {
+                    running.push((instance.id, pid));
+                }
+                continue;
+            };
+
+            log::debug!("Child process is no longer alive");
+            let reattached = instance.reattached.is_some();
+            let stopped = instance.stop_deadline.is_some();
+            instance.clear_process();
+
+            // Exiting because of the stop signal isn't a crash, whatever the exit code
+            let outcome = if exit_code == Some(0) || stopped || reattached {
+                LaunchOutcome::Exited
+            } else {
+                LaunchOutcome::Crashed
+            };
+            instance.finish_launch_history(outcome, exit_code);
+
+            self.send.send(instance.create_modify_message());
+        }
+        drop(instance_state);
+
+        let pids: Vec<u32> = running.iter().map(|(_, pid)| *pid).collect();
+        let usages = process_monitor.sample(&pids);
+        for ((id, _), usage) in running.into_iter().zip(usages) {
+            if let Some(usage) = usage {
+                self.send.send(MessageToFrontend::InstanceResourceUsageUpdated { id, usage });
             }
         }
     }
 
+    /// Instances whose folders aren't watched are checked for changes by comparing fingerprints of their folders
+    async fn poll_unwatched_instances(self) {
+        let polled: Vec<(InstanceID, Vec<Arc<Path>>)> = self.instance_state.read().instances.iter()
+            .filter(|instance| !instance.file_watching)
+            .map(|instance| (instance.id, instance.polled_paths()))
+            .collect();
+        if polled.is_empty() {
+            return;
+        }
+
+        let polled = tokio::task::spawn_blocking(move || {
+            polled.into_iter().map(|(id, paths)| {
+                let fingerprints: Vec<_> = paths.into_iter().map(|path| {
+                    let fingerprint = Instance::poll_fingerprint(&path);
+                    (path, fingerprint)
+                }).collect();
+                (id, fingerprints)
+            }).collect::<Vec<_>>()
+        }).await.unwrap();
+
+        let mut changed = false;
+        let mut instance_state = self.instance_state.write();
+        for (id, fingerprints) in polled {
+            let Some(instance) = instance_state.instances.get_mut(id) else {
+                continue;
+            };
+            // Watching was turned back on while polling
+            if instance.file_watching {
+                continue;
+            }
+            for (path, fingerprint) in fingerprints {
+                changed |= instance.update_polled(path, fingerprint);
+            }
+        }
+        drop(instance_state);
+
+        if changed {
+            self.send.send(MessageToFrontend::Refresh);
+        }
+    }
+
     pub async fn login(
         &self,
         credentials: &mut AccountCredentials,
@@ -543,31 +806,58 @@ impl BackendState {
             return;
         };
 
+        let mut account_info = self.account_info.write();
+        if let Some(account) = account_info.get().accounts.get(&profile.id) && account.skin_url.as_ref() != Some(&skin.url) {
+            account_info.modify(|info| {
+                if let Some(account) = info.accounts.get_mut(&profile.id) {
+                    account.skin_url = Some(skin.url.clone());
+                }
+            });
+        }
+        drop(account_info);
+
+        self.update_head(profile.id, skin.url);
+    }
+
+    /// Renders all account heads again, accounts that haven't logged in since skin urls were
+    /// saved keep their head until they log in next
+    pub fn regenerate_account_heads(&self) {
+        self.head_cache.write().clear();
+
+        let skins: Vec<(Uuid, Arc<str>)> = self.account_info.write().get().accounts.iter()
+            .filter_map(|(uuid, account)| Some((*uuid, account.skin_url.clone()?)))
+            .collect();
+        for (uuid, skin_url) in skins {
+            self.update_head(uuid, skin_url);
+        }
+    }
+
+    fn update_head(&self, uuid: Uuid, skin_url: Arc<str>) {
         let mut head_cache = self.head_cache.write();
-        if let Some(existing) = head_cache.get_mut(&skin.url) {
+        if let Some(existing) = head_cache.get_mut(&skin_url) {
             match existing {
                 HeadCacheEntry::Pending { accounts } => {
-                    accounts.push(profile.id);
+                    accounts.push(uuid);
                 },
                 HeadCacheEntry::Success { head } => {
                     let head = head.clone();
                     drop(head_cache);
-                    self.account_info.write().modify(move |account_info| {
-                        if let Some(account) = account_info.accounts.get_mut(&profile.id) {
-                            account.head = Some(head);
-                        }
-                    });
+                    if self.account_heads.write().set(uuid, head) {
+                        self.send_accounts_update();
+                    }
                 },
                 HeadCacheEntry::Failed => {}
             }
             return;
         }
 
-        head_cache.insert(skin.url.clone(), HeadCacheEntry::Pending { accounts: vec![profile.id] });
+        head_cache.insert(skin_url.clone(), HeadCacheEntry::Pending { accounts: vec![uuid] });
 
         let head_cache = self.head_cache.clone();
         let account_info = self.account_info.clone();
-        let skin_url = skin.url;
+        let account_heads = self.account_heads.clone();
+        let config = self.config.clone();
+        let send = self.send.clone();
 
         let http_client = self.http_client.clone();
 
@@ -583,16 +873,15 @@ impl BackendState {
                 head_cache.write().insert(skin_url.clone(), HeadCacheEntry::Failed);
                 return;
             };
-            let Ok(mut image) = image::load_from_memory(&bytes) else {
+            let Ok(image) = image::load_from_memory(&bytes) else {
                 log::warn!("Image load error for skin from {}", skin_url);
                 head_cache.write().insert(skin_url.clone(), HeadCacheEntry::Failed);
                 return;
             };
 
-            let mut head = image.crop(8, 8, 8, 8);
-            let head_overlay = image.crop(40, 8, 8, 8);
-
-            image::imageops::overlay(&mut head, &head_overlay, 0, 0);
+            // Read after downloading, in case the setting changed in the meantime
+            let overlay = !config.write().get().hide_head_overlay;
+            let head = crate::account::render_head(&image, overlay);
 
             let mut head_bytes = Vec::new();
             let mut cursor = Cursor::new(&mut head_bytes);
@@ -621,16 +910,116 @@ impl BackendState {
             }
 
             let mut account_info = account_info.write();
-            account_info.modify(move |info| {
-                for uuid in accounts {
-                    if let Some(account) = info.accounts.get_mut(&uuid) {
-                        account.head = Some(head_png.clone());
-                    }
+            let mut account_heads = account_heads.write();
+
+            let info = account_info.get();
+            let mut changed = false;
+            for uuid in accounts {
+                if info.accounts.contains_key(&uuid) {
+                    changed |= account_heads.set(uuid, head_png.clone());
                 }
-            });
+            }
+
+            if changed {
+                send.send(info.create_update_message(&mut account_heads));
+            }
         });
     }
 
+    pub fn check_launcher_update(&self) {
+        let http_client = self.http_client.clone();
+        let send = self.send.clone();
+        tokio::task::spawn(async move {
+            match crate::launcher_update::check(&http_client).await {
+                Ok(Some(update)) => send.send(MessageToFrontend::LauncherUpdateAvailable { update }),
+                Ok(None) => {},
+                Err(error) => log::warn!("Unable to check for launcher updates: {}", error),
+            }
+        });
+    }
+
+    pub fn load_proxy_password(&self) {
+        if !self.config.write().get().proxy.password_saved {
+            return;
+        }
+
+        let state = self.clone();
+        tokio::task::spawn(async move {
+            match state.secret_storage().await {
+                Ok(storage) => crate::proxy::load_password(&state.proxy_password, storage).await,
+                Err(error) => log::error!("Unable to access the secret storage for the proxy password: {error}"),
+            }
+        });
+    }
+
+    pub fn send_followed_projects_update(&self) {
+        let followed = self.followed_projects.write().get().clone();
+        self.send.send(MessageToFrontend::FollowedProjectsUpdated { followed });
+    }
+
+    /// Fetches the versions of every followed project. If a check is already running, it checks again once it's done
+    /// so projects followed in the meantime aren't missed
+    pub fn check_followed_projects(&self) {
+        {
+            let mut check = self.followed_check.lock();
+            if check.running {
+                check.again = true;
+                return;
+            }
+            check.running = true;
+        }
+
+        let state = self.clone();
+        tokio::task::spawn(async move {
+            let _guard = FollowedCheckGuard(state.followed_check.clone());
+
+            let mut changed = false;
+            loop {
+                let project_ids: Vec<Arc<str>> = state.followed_projects.write().get().projects.iter()
+                    .map(|project| project.project_id.clone())
+                    .collect();
+
+                for project_id in project_ids {
+                    let request = ModrinthProjectVersionsRequest {
+                        project_id: project_id.clone(),
+                        game_versions: None,
+                        loaders: None,
+                    };
+                    let versions = match state.meta.fetch_with_keepalive(&ModrinthProjectVersionsMetadataItem(&request), true).await.0 {
+                        Ok(versions) => versions,
+                        Err(error) => {
+                            log::warn!("Unable to check followed project {} for new versions: {}", project_id, error);
+                            continue;
+                        },
+                    };
+
+                    // The project may have been unfollowed while its versions were loading
+                    let now = chrono::Utc::now().timestamp_millis();
+                    state.followed_projects.write().modify(|followed| {
+                        if let Some(project) = followed.get_mut(&project_id) {
+                            changed |= project.set_versions(&versions.0, now);
+                        }
+                    });
+                }
+
+                let mut check = state.followed_check.lock();
+                if !std::mem::take(&mut check.again) {
+                    check.running = false;
+                    break;
+                }
+            }
+
+            if changed {
+                state.send_followed_projects_update();
+            }
+        });
+    }
+
+    pub fn send_accounts_update(&self) {
+        let message = self.account_info.write().get().create_update_message(&mut self.account_heads.write());
+        self.send.send(message);
+    }
+
     pub async fn prelaunch(&self, id: InstanceID, modal_action: &ModalAction) -> Vec<PathBuf> {
         self.prelaunch_apply_syncing(id);
         self.prelaunch_apply_modpacks(id, modal_action).await
@@ -741,13 +1130,14 @@ impl BackendState {
             }
         }
 
-        let dot_minecraft_path = if let Some(instance) = self.instance_state.read().instances.get(id) {
-            instance.dot_minecraft_path.clone()
+        let (root_path, dot_minecraft_path) = if let Some(instance) = self.instance_state.read().instances.get(id) {
+            (instance.root_path.clone(), instance.dot_minecraft_path.clone())
         } else {
             return Vec::new();
         };
 
         let mut add_mods = Vec::new();
+        let mut modpack_files = Vec::new();
 
         for modpack_install in modpack_installs {
             let overrides = modpack_install.overrides;
@@ -773,10 +1163,11 @@ impl BackendState {
                         let _ = std::fs::hard_link(path, hidden_dest_path);
                     }
                 } else {
-                    let dest_path = dest_path.to_path(&dot_minecraft_path);
-
-                    let _ = std::fs::create_dir_all(dest_path.parent().unwrap());
-                    let _ = std::fs::copy(path, dest_path);
+                    modpack_files.push(ModpackFile {
+                        path: dest_path,
+                        source: path,
+                        sha1: expected_hash,
+                    });
                 }
             }
 
@@ -788,11 +1179,10 @@ impl BackendState {
                 tracker.notify();
 
                 let tracker = &tracker;
-                let dot_minecraft_path = &dot_minecraft_path;
                 let mod_dir = &mod_dir;
                 let futures = overrides.iter().map(|(dest_path, file)| async move {
                     let file2 = file.clone();
-                    let expected_hash = tokio::task::spawn_blocking(move || {
+                    let expected_hash: [u8; 20] = tokio::task::spawn_blocking(move || {
                         let mut hasher = Sha1::new();
                         hasher.update(&file2);
                         hasher.finalize().into()
@@ -805,38 +1195,51 @@ impl BackendState {
                         let _ = tokio::fs::write(&path, file).await;
                     }
 
+                    let mut result = (None, None);
                     if dest_path.starts_with("mods") && let Some(extension) = dest_path.extension() && extension == "jar" {
                         if loader_supports_add_mods {
-                            return Some(path);
+                            result.0 = Some(path);
                         } else if let Some(filename) = dest_path.file_name() {
                             let filename = format!(".pandora.{filename}");
                             let hidden_dest_path = mod_dir.join(filename);
                             let _ = std::fs::hard_link(path, hidden_dest_path);
                         }
                     } else {
-                        let dest_path = dest_path.to_path(&dot_minecraft_path);
-
-                        let _ = std::fs::create_dir_all(dest_path.parent().unwrap());
-                        let _ = tokio::fs::copy(path, dest_path).await;
+                        result.1 = Some(ModpackFile {
+                            path: dest_path.clone(),
+                            source: path,
+                            sha1: expected_hash,
+                        });
                     }
                     tracker.add_count(1);
                     tracker.notify();
-                    None
+                    result
                 });
 
-                add_mods.extend(futures::future::join_all(futures).await.into_iter().flatten());
+                for (add_mod, modpack_file) in futures::future::join_all(futures).await {
+                    add_mods.extend(add_mod);
+                    modpack_files.extend(modpack_file);
+                }
 
                 tracker.set_finished(ProgressTrackerFinishType::Fast);
             }
         }
 
+        let result = tokio::task::spawn_blocking(move || {
+            crate::modpack::apply_files(&root_path, &dot_minecraft_path, &modpack_files)
+        }).await.unwrap();
+        if let Err(error) = result {
+            modal_action.set_error_message(format!("Unable to copy modpack files, the previous files were restored: {error}").into());
+            return Vec::new();
+        }
+
         add_mods.sort();
         add_mods.dedup();
         add_mods
     }
 
     pub async fn load_instance_servers(self, id: InstanceID) -> Option<Arc<[InstanceServerSummary]>> {
-        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) && instance.file_watching {
             let mut file_watching = self.file_watching.write();
             if !instance.watching_dot_minecraft {
                 instance.watching_dot_minecraft = true;
@@ -862,11 +1265,10 @@ impl BackendState {
         }
 
         result.map(|(servers, _)| servers)
-
     }
 
     pub async fn load_instance_content(self, id: InstanceID, folder: ContentFolder) -> Option<Arc<[InstanceContentSummary]>> {
-        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) && instance.file_watching {
             let mut file_watching = self.file_watching.write();
             if !instance.watching_dot_minecraft {
                 instance.watching_dot_minecraft = true;
@@ -887,6 +1289,16 @@ impl BackendState {
         let result = Instance::load_content(self.instance_state.clone(), id, &self.mod_metadata_manager, folder).await;
 
         if let Some((content, newly_loaded)) = result.clone() && newly_loaded {
+            let meta = self.meta.clone();
+            let mod_metadata_manager = self.mod_metadata_manager.clone();
+            let send = self.send.clone();
+            let summaries: Vec<_> = content.iter().map(|summary| summary.content_summary.clone()).collect();
+            tokio::task::spawn(async move {
+                if mod_metadata_manager.lookup_modrinth_projects(&meta, summaries.into_iter()).await {
+                    send.send(MessageToFrontend::Refresh);
+                }
+            });
+
             match folder {
                 ContentFolder::Mods => {
                     self.send.send(MessageToFrontend::InstanceModsUpdated {
@@ -907,7 +1319,7 @@ impl BackendState {
     }
 
     pub async fn load_instance_worlds(self, id: InstanceID) -> Option<Arc<[InstanceWorldSummary]>> {
-        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) && instance.file_watching {
             let mut file_watching = self.file_watching.write();
             if !instance.watching_dot_minecraft {
                 instance.watching_dot_minecraft = true;
@@ -923,7 +1335,23 @@ impl BackendState {
             }
         }
 
-        let result = Instance::load_worlds(self.instance_state.clone(), id).await;
+        let send = self.send.clone();
+        // Only large saves folders report progress, so only they are shown as a background task.
+        // The closure is dropped once loading finishes, removing the task
+        let background_task = Mutex::new(None);
+        let result = Instance::load_worlds(self.instance_state.clone(), id, move |worlds, total| {
+            let mut background_task = background_task.lock();
+            let (tracker, _) = background_task.get_or_insert_with(|| {
+                let tracker = ProgressTracker::new("Loading worlds".into(), send.clone());
+                let guard = tracker.register_background(BackgroundTaskKind::WorldLoad, None);
+                (tracker, guard)
+            });
+            tracker.set_count(worlds.len());
+            tracker.set_total(total);
+            tracker.notify();
+
+            send.send(MessageToFrontend::InstanceWorldsUpdatedPartial { id, worlds, total });
+        }).await;
 
         if let Some((worlds, newly_loaded)) = result.clone() && newly_loaded {
             self.send.send(MessageToFrontend::InstanceWorldsUpdated {
@@ -931,37 +1359,85 @@ impl BackendState {
                 worlds: Arc::clone(&worlds)
             });
 
-            let mut file_watching = self.file_watching.write();
-            for summary in worlds.iter() {
-                file_watching.watch_filesystem(summary.level_path.clone(), WatchTarget::InstanceWorldDir {
-                    id,
-                });
+            let watching = self.instance_state.read().instances.get(id).is_some_and(|instance| instance.file_watching);
+            if watching {
+                let mut file_watching = self.file_watching.write();
+                for summary in worlds.iter() {
+                    file_watching.watch_filesystem(summary.level_path.clone(), WatchTarget::InstanceWorldDir {
+                        id,
+                    });
+                }
             }
         }
 
         result.map(|(worlds, _)| worlds)
     }
 
+    /// Starts or stops watching the folders of the instance after its setting or the global setting changed
+    pub fn update_instance_file_watching(&self, id: InstanceID) {
+        let file_watching_disabled = self.config.write().get().disable_file_watching;
+
+        let mut instance_state = self.instance_state.write();
+        let Some(instance) = instance_state.instances.get_mut(id) else {
+            return;
+        };
+
+        let watching = instance.should_watch_files(file_watching_disabled);
+        if instance.file_watching == watching {
+            return;
+        }
+        instance.file_watching = watching;
+
+        if watching {
+            // The folders are watched again once their data is reloaded
+            instance.clear_polled();
+        } else {
+            self.file_watching.write().unwatch_instance_contents(id);
+            instance.watching_dot_minecraft = false;
+            instance.watching_saves_dir = false;
+            instance.watching_server_dat = false;
+            for folder in ContentFolder::iter() {
+                instance.content_state[folder].watching_path = false;
+            }
+        }
+
+        // Changes made while switching over would be missed otherwise
+        instance.mark_all_dirty();
+
+        self.send.send(instance.create_file_watching_message());
+        self.send.send(MessageToFrontend::Refresh);
+    }
+
     pub async fn create_instance_sanitized(&self, name: &str, version: &str, loader: Loader) -> Option<PathBuf> {
-        let mut name = sanitize_filename::sanitize_with_options(name, sanitize_filename::Options { windows: true, ..Default::default() });
+        self.create_instance_sanitized_from_configuration(name, InstanceConfiguration::new(Ustr::from(version), loader)).await
+    }
 
+    pub async fn create_instance_sanitized_from_configuration(&self, name: &str, configuration: InstanceConfiguration) -> Option<PathBuf> {
+        let name = sanitize_filename::sanitize_with_options(name, sanitize_filename::Options { windows: true, ..Default::default() });
+        let name = self.unused_instance_name(name);
+
+        return self.create_instance_from_configuration(&name, configuration).await;
+    }
+
+    fn unused_instance_name(&self, name: String) -> String {
         if self.instance_state.read().instances.iter().any(|i| i.name == name) {
-            let original_name = name.clone();
             for i in 1..32 {
-                let new_name = format!("{original_name} ({i})");
+                let new_name = format!("{name} ({i})");
                 if !self.instance_state.read().instances.iter().any(|i| i.name == new_name) {
-                    name = new_name;
-                    break;
+                    return new_name;
                 }
             }
         }
-
-        return self.create_instance(&name, version, loader).await;
+        name
     }
 
     pub async fn create_instance(&self, name: &str, version: &str, loader: Loader) -> Option<PathBuf> {
+        self.create_instance_from_configuration(name, InstanceConfiguration::new(Ustr::from(version), loader)).await
+    }
+
+    pub async fn create_instance_from_configuration(&self, name: &str, instance_info: InstanceConfiguration) -> Option<PathBuf> {
         log::info!("Creating instance {name}");
-        if loader == Loader::Unknown {
+        if instance_info.loader == Loader::Unknown {
             self.send.send_warning(format!("Unable to create instance, unknown loader"));
             return None;
         }
@@ -984,21 +1460,107 @@ impl BackendState {
 
         let _ = tokio::fs::create_dir_all(&instance_dir).await;
 
-        let instance_info = InstanceConfiguration {
-            minecraft_version: Ustr::from(version),
-            loader,
-            preferred_loader_version: None,
-            memory: None,
-            jvm_flags: None,
-            jvm_binary: None,
-        };
-
         let info_path = instance_dir.join("info_v1.json");
         crate::write_safe(&info_path, serde_json::to_string(&instance_info).unwrap().as_bytes()).unwrap();
 
+        self.add_default_resource_packs(&instance_dir);
+
         Some(instance_dir.clone())
     }
 
+    /// Copies the resource packs that new instances start with out of the content library and enables them
+    fn add_default_resource_packs(&self, instance_dir: &Path) {
+        let packs = self.config.write().get().default_resource_packs.clone();
+        if packs.is_empty() {
+            return;
+        }
+
+        let dot_minecraft = instance_dir.join(".minecraft");
+        let resourcepacks_dir = dot_minecraft.join("resourcepacks");
+        let _ = std::fs::create_dir_all(&resourcepacks_dir);
+
+        let mut added = Vec::new();
+        for pack in packs {
+            let mut hash = [0u8; 20];
+            if hex::decode_to_slice(&pack.sha1, &mut hash).is_err() || !crate::is_single_component_path(&pack.filename) {
+                continue;
+            }
+
+            let extension = Path::new(&pack.filename).extension().and_then(|extension| extension.to_str());
+            let source = crate::create_content_library_path(&self.directories.content_library_dir, hash, extension);
+            if !source.is_file() {
+                self.send.send_warning(format!("Default resource pack {} is no longer in the content library, mark it as default again", pack.filename));
+                continue;
+            }
+
+            let target = resourcepacks_dir.join(&pack.filename);
+            if std::fs::hard_link(&source, &target).is_err() && let Err(err) = std::fs::copy(&source, &target) {
+                log::warn!("Unable to add default resource pack {}: {}", pack.filename, err);
+                continue;
+            }
+            added.push(pack.filename);
+        }
+
+        if added.is_empty() {
+            return;
+        }
+
+        let options_path = crate::game_options::options_path(&dot_minecraft);
+        let contents = std::fs::read_to_string(&options_path).unwrap_or_default();
+        let new_contents = crate::game_options::add_resource_packs(&contents, &added);
+        if let Err(err) = crate::write_safe(&options_path, new_contents.as_bytes()) {
+            log::warn!("Unable to enable default resource packs in options.txt: {}", err);
+        }
+    }
+
+    /// Resolves the loader version that would be used when launching without a preferred loader version
+    pub async fn latest_loader_version(&self, configuration: &InstanceConfiguration) -> Option<Ustr> {
+        match configuration.loader {
+            Loader::Vanilla | Loader::Unknown => None,
+            Loader::Fabric => {
+                let manifest = self.meta.fetch(&FabricLoaderManifestMetadataItem).await.ok()?;
+                manifest.0.iter().find(|v| v.stable).or(manifest.0.first()).map(|v| v.version)
+            },
+            Loader::Forge => {
+                let versions = self.meta.fetch(&ForgeInstallerMavenMetadataItem).await.ok()?;
+                crate::launch::latest_forgelike_loader_version(configuration.minecraft_version, &versions.0, false)
+            },
+            Loader::NeoForge => {
+                let versions = self.meta.fetch(&NeoforgeInstallerMavenMetadataItem).await.ok()?;
+                crate::launch::latest_forgelike_loader_version(configuration.minecraft_version, &versions.0, true)
+            },
+        }
+    }
+
+    /// Resolves the oldest loader version for the instance's Minecraft version that's at least `minimum`
+    pub async fn oldest_loader_version_satisfying(&self, configuration: &InstanceConfiguration, minimum: &str) -> Option<Ustr> {
+        fn forgelike_versions(minecraft_version: Ustr, versions: &[Ustr], neoforge_versioning: bool) -> Vec<Ustr> {
+            let prefix = crate::launch::forgelike_version_prefix(minecraft_version, neoforge_versioning);
+            versions.iter()
+                .filter(|version| schema::forge::VersionFragment::string_to_parts(version).starts_with(&prefix))
+                .copied()
+                .collect()
+        }
+
+        let available = match configuration.loader {
+            Loader::Vanilla | Loader::Unknown => return None,
+            Loader::Fabric => {
+                let manifest = self.meta.fetch(&FabricLoaderManifestMetadataItem).await.ok()?;
+                manifest.0.iter().filter(|v| v.stable).map(|v| v.version).collect()
+            },
+            Loader::Forge => {
+                let versions = self.meta.fetch(&ForgeInstallerMavenMetadataItem).await.ok()?;
+                forgelike_versions(configuration.minecraft_version, &versions.0, false)
+            },
+            Loader::NeoForge => {
+                let versions = self.meta.fetch(&NeoforgeInstallerMavenMetadataItem).await.ok()?;
+                forgelike_versions(configuration.minecraft_version, &versions.0, true)
+            },
+        };
+
+        crate::loader_requirements::oldest_satisfying(available, &configuration.minecraft_version, minimum)
+    }
+
     pub async fn rename_instance(&self, id: InstanceID, name: &str) {
         if !crate::is_single_component_path(&name) {
             self.send.send_warning(format!("Unable to rename instance, name must not be a path: {}", name));
@@ -1015,7 +1577,14 @@ impl BackendState {
 
         let new_instance_dir = self.directories.instances_dir.join(name);
 
+        // Queued writes still point into the old folder
+        _ = tokio::task::spawn_blocking(write_behind::flush_global).await;
+
         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.is_running() {
+                self.send.send_warning("Can't rename instance while it's running");
+                return;
+            }
             let result = std::fs::rename(&instance.root_path, new_instance_dir);
             if let Err(err) = result {
                 self.send.send_error(format!("Unable to rename instance folder: {}", err));
@@ -1023,6 +1592,122 @@ impl BackendState {
         }
     }
 
+    pub async fn duplicate_instance(&self, id: InstanceID) {
+        let Some((name, running)) = self.instance_state.read().instances.get(id).map(|instance| (instance.name, instance.is_running())) else {
+            return;
+        };
+
+        // The game keeps writing to its worlds, a copy could mix files from different saves
+        if running {
+            self.send.send_error("Can't duplicate an instance while it's running");
+            return;
+        }
+
+        if let Err(err) = self.copy_instance(id, format!("{name} (Copy)")).await {
+            self.send.send_error(format!("Unable to duplicate instance: {}", err));
+        }
+    }
+
+    /// Copies the instance folder into a new instance, a number is added to the name if it's already used
+    pub async fn copy_instance(&self, id: InstanceID, new_name: String) -> anyhow::Result<()> {
+        let (name, root_path) = if let Some(instance) = self.instance_state.read().instances.get(id) {
+            (instance.name, instance.root_path.clone())
+        } else {
+            anyhow::bail!("unknown instance");
+        };
+
+        let new_name = self.unused_instance_name(new_name);
+        let new_instance_dir = self.directories.instances_dir.join(&new_name);
+        if new_instance_dir.exists() {
+            anyhow::bail!("{} already exists", new_name);
+        }
+
+        log::info!("Copying instance {name} to {new_name}");
+
+        tokio::task::spawn_blocking(move || {
+            let result = copy_instance_dir(&root_path, &new_instance_dir, true);
+            if result.is_err() {
+                _ = std::fs::remove_dir_all(&new_instance_dir);
+            }
+            result
+        }).await??;
+
+        Ok(())
+    }
+
+    /// Creates a vanilla instance on the release matching the DataVersion of the world and moves the world into it,
+    /// or copies it when `keep_copy` is set
+    pub async fn split_world_into_instance(&self, id: InstanceID, level_path: Arc<Path>, name: &str, keep_copy: bool) -> anyhow::Result<()> {
+        let (saves_path, running) = if let Some(instance) = self.instance_state.read().instances.get(id) {
+            (instance.saves_path.clone(), instance.is_running())
+        } else {
+            anyhow::bail!("unknown instance");
+        };
+        if !level_path.starts_with(&saves_path) {
+            anyhow::bail!("world isn't part of this instance");
+        }
+        // The game keeps writing to the world, a copy could mix files from different saves
+        if running {
+            anyhow::bail!("can't move or copy a world out of an instance while it's running");
+        }
+        if std::fs::symlink_metadata(&saves_path).is_ok_and(|metadata| metadata.is_symlink()) {
+            anyhow::bail!("worlds are synced between instances, disable syncing the saves folder first");
+        }
+        let Some(folder_name) = level_path.file_name().map(|name| name.to_owned()) else {
+            anyhow::bail!("invalid world folder");
+        };
+
+        let world_version = {
+            let level_path = level_path.clone();
+            tokio::task::spawn_blocking(move || crate::instance::load_world_version(&level_path)).await??
+        };
+        let (world_version, data_version) = world_version;
+        let Some(data_version) = data_version else {
+            anyhow::bail!("the world doesn't store the version it was played in, it's from before 1.9");
+        };
+
+        // Version names can be changed by custom clients, so the release is found by DataVersion. Releases
+        // newer than the table are matched by name instead
+        let manifest = self.meta.fetch(&MinecraftVersionManifestMetadataItem).await?;
+        let version = match crate::data_versions::release_for_data_version(data_version) {
+            Some(release) => release.to_string(),
+            None => match world_version {
+                Some(version) if manifest.versions.iter().any(|link| link.id.as_str() == version) => version,
+                Some(version) => anyhow::bail!("{} (DataVersion {}) isn't a known Minecraft version", version, data_version),
+                None => anyhow::bail!("DataVersion {} isn't a known Minecraft version", data_version),
+            },
+        };
+
+        let Some(instance_dir) = self.create_instance_sanitized(name, &version, Loader::Vanilla).await else {
+            anyhow::bail!("unable to create instance");
+        };
+        let target = instance_dir.join(".minecraft").join("saves").join(&folder_name);
+
+        log::info!("{} world {:?} into new instance on {}", if keep_copy { "Copying" } else { "Moving" }, folder_name, version);
+
+        let result = tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(target.parent().unwrap())?;
+            // Renaming fails when the instances are on different drives, e.g. a symlinked instance folder
+            if !keep_copy && std::fs::rename(&level_path, &target).is_ok() {
+                return Ok(());
+            }
+            let result = copy_instance_dir(&level_path, &target, false);
+            if result.is_err() {
+                _ = std::fs::remove_dir_all(&target);
+            } else if !keep_copy && let Err(err) = std::fs::remove_dir_all(&level_path) {
+                // The world is in place already, so the new instance is kept
+                log::warn!("Unable to remove the moved world {:?}: {}", level_path, err);
+            }
+            result
+        }).await.map_err(anyhow::Error::from).and_then(|result| result.map_err(anyhow::Error::from));
+
+        // An empty instance without the world isn't what was asked for
+        if result.is_err() {
+            _ = tokio::task::spawn_blocking(move || std::fs::remove_dir_all(&instance_dir)).await;
+        }
+        result
+    }
+
     pub async fn get_login_info(&self, modal_action: &ModalAction) -> Option<MinecraftLoginInfo> {
         let selected_account = {
             let mut account_info = self.account_info.write();
@@ -1036,7 +1721,8 @@ impl BackendState {
                         return Some(MinecraftLoginInfo {
                             uuid,
                             username: account.username.clone(),
-                            access_token: None
+                            access_token: None,
+                            offline_fallback: false,
                         })
                     }
                 } else {
@@ -1047,15 +1733,27 @@ impl BackendState {
             selected_account
         };
 
-        let Some((profile, access_token)) = self.login_flow(modal_action, selected_account).await else {
-            return None;
-        };
-
-        Some(MinecraftLoginInfo {
-            uuid: profile.id,
-            username: profile.name.clone(),
-            access_token: Some(access_token),
-        })
+        match self.login_flow(modal_action, selected_account).await? {
+            LoginFlowResult::LoggedIn(profile, access_token) => Some(MinecraftLoginInfo {
+                uuid: profile.id,
+                username: profile.name.clone(),
+                access_token: Some(access_token),
+                offline_fallback: false,
+            }),
+            LoginFlowResult::Unreachable(access_token) => {
+                let uuid = selected_account?;
+                let username = self.account_info.write().get().accounts.get(&uuid)?.username.clone();
+
+                self.send.send_warning("Unable to reach the authentication servers, launching in offline mode");
+
+                Some(MinecraftLoginInfo {
+                    uuid,
+                    username,
+                    access_token,
+                    offline_fallback: true,
+                })
+            },
+        }
     }
 }
 
@@ -1085,11 +1783,31 @@ impl BackendStateFileWatching {
         }
     }
 
+    /// Stops watching everything inside the instance folder. The folder itself is still watched to notice it being renamed or removed
+    pub fn unwatch_instance_contents(&mut self, id: InstanceID) {
+        let paths: Vec<Arc<Path>> = self.watching.iter()
+            .filter(|(_, target)| match target {
+                WatchTarget::InstanceDotMinecraftDir { id: target_id }
+                | WatchTarget::InstanceWorldDir { id: target_id }
+                | WatchTarget::InstanceSavesDir { id: target_id }
+                | WatchTarget::ServersDat { id: target_id }
+                | WatchTarget::InstanceContentDir { id: target_id, .. } => *target_id == id,
+                WatchTarget::RootDir | WatchTarget::InstancesDir | WatchTarget::InvalidInstanceDir | WatchTarget::InstanceDir { .. } => false,
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in paths {
+            self.unwatch(&path);
+        }
+    }
+
     pub fn get_target(&self, path: &Path) -> Option<&WatchTarget> {
         self.watching.get(path)
     }
 
     pub fn remove(&mut self, path: &Path) -> Option<WatchTarget> {
+        self.event_counts.remove(path);
         if let Some(src) = self.symlink_link_to_src.remove(path) {
             if let Some(links) = self.symlink_src_to_links.get_mut(&src) {
                 links.shift_remove(path);
@@ -1101,6 +1819,73 @@ impl BackendStateFileWatching {
         self.watching.remove(path)
     }
 
+    /// Stops watching the path entirely, unlike `remove` which is used after the path has been deleted
+    pub fn unwatch(&mut self, path: &Path) -> Option<WatchTarget> {
+        if let Err(err) = self.watcher.unwatch(path) {
+            log::warn!("Unable to unwatch {:?}: {:?}", path, err);
+        }
+        self.remove(path)
+    }
+
+    pub fn rewatch(&mut self, path: &Path) {
+        let Some((path, _)) = self.watching.get_key_value(path) else {
+            return;
+        };
+        let path = path.clone();
+        if let Some(target) = self.unwatch(&path) {
+            self.watch_filesystem(path, target);
+        }
+    }
+
+    fn watched_path_for<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if self.watching.contains_key(path) {
+            Some(path)
+        } else {
+            path.parent().filter(|parent| self.watching.contains_key(*parent))
+        }
+    }
+
+    /// Counts an event for a path returned by `all_paths`
+    pub fn record_event(&mut self, path: &Path) {
+        if let Some(watched) = self.watched_path_for(path) {
+            self.event_counts.entry(watched.into()).or_default().0 += 1;
+        }
+    }
+
+    pub fn record_error(&mut self, paths: &[PathBuf]) {
+        let mut attributed = false;
+        for path in paths {
+            for path in self.all_paths(path.as_path().into()) {
+                if let Some(watched) = self.watched_path_for(&path) {
+                    self.event_counts.entry(watched.into()).or_default().1 += 1;
+                    attributed = true;
+                }
+            }
+        }
+        if !attributed {
+            self.unattributed_errors += 1;
+        }
+    }
+
+    pub fn debug_info(&self) -> FileWatchDebugInfo {
+        let mut entries: Vec<FileWatchEntry> = self.watching.iter().map(|(path, target)| {
+            let (events, errors) = self.event_counts.get(path).copied().unwrap_or_default();
+            FileWatchEntry {
+                path: path.clone(),
+                real_path: self.symlink_link_to_src.get(path).cloned(),
+                target: format!("{:?}", target).into(),
+                events,
+                errors,
+            }
+        }).collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        FileWatchDebugInfo {
+            entries,
+            unattributed_errors: self.unattributed_errors,
+        }
+    }
+
     pub fn all_paths(&self, path: Arc<Path>) -> Vec<Arc<Path>> {
         let mut paths = Vec::new();
 
@@ -1137,6 +1922,32 @@ impl BackendStateFileWatching {
     }
 }
 
+/// Symlinks (i.e. synced folders) are skipped since they are recreated when the instance is launched
+fn copy_instance_dir(from: &Path, to: &Path, is_root: bool) -> std::io::Result<()> {
+    std::fs::create_dir(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let file_name = entry.file_name();
+
+        // The launch history belongs to the original instance
+        if is_root && file_name == "launch_history.json" {
+            continue;
+        }
+
+        if file_type.is_symlink() {
+            continue;
+        } else if file_type.is_dir() {
+            copy_instance_dir(&entry.path(), &to.join(file_name), false)?;
+        } else {
+            std::fs::copy(entry.path(), to.join(file_name))?;
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum LoginError {
     #[error("Login stage error: Backwards")]
@@ -1152,3 +1963,13 @@ pub enum LoginError {
     #[error("Cancelled by user")]
     CancelledByUser,
 }
+
+impl LoginError {
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            Self::MsaAuthorizationError(error) => error.is_connection_error(),
+            Self::XboxAuthenticateError(error) => error.is_connection_error(),
+            _ => false,
+        }
+    }
+}
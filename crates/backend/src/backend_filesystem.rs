@@ -5,28 +5,22 @@ use notify::{
     EventKind,
     event::{CreateKind, DataChange, ModifyKind, RemoveKind, RenameMode},
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use strum::IntoEnumIterator;
 
 use crate::{BackendState, WatchTarget, instance::ContentFolder};
 
-#[derive(Debug)]
+/// Extensions of files that only exist while something else is being written, e.g. partial downloads
+/// and the temporary files editors and the launcher itself write before renaming them into place
+const TEMPORARY_EXTENSIONS: &[&str] = &["part", "crdownload", "tmp", "new", "swp", "swx", "lock"];
+
+#[derive(Debug, PartialEq)]
 enum FilesystemEvent {
     Change(Arc<Path>),
     Remove(Arc<Path>),
     Rename(Arc<Path>, Arc<Path>),
 }
 
-impl FilesystemEvent {
-    pub fn change_or_remove_path(&self) -> Option<&Arc<Path>> {
-        match self {
-            FilesystemEvent::Change(path) => Some(path),
-            FilesystemEvent::Remove(path) => Some(path),
-            FilesystemEvent::Rename(..) => None,
-        }
-    }
-}
-
 struct AfterDebounceEffects {
     reload_immediately: FxHashSet<(InstanceID, ContentFolder)>,
 }
@@ -39,32 +33,47 @@ impl BackendState {
                     reload_immediately: Default::default(),
                 };
 
-                let mut last_event: Option<FilesystemEvent> = None;
+                // Writing a large file produces many events for the same path, only handle it again when it changes
+                // between existing and removed, otherwise every modification would mark the same file dirty again
+                let mut last_state: FxHashMap<Arc<Path>, bool> = FxHashMap::default();
                 for event in events {
-                    let Some(next_event) = get_simple_event(event.event) else {
+                    let Some(next_event) = get_simple_event(event.event).and_then(ignore_temporary_files) else {
                         continue;
                     };
 
                     log::trace!("Filesystem event: {:?}", next_event);
 
-                    if let Some(last_event) = last_event.take() {
-                        let last_path = last_event.change_or_remove_path();
-                        let new_path = next_event.change_or_remove_path();
-                        if last_path.is_none() || last_path != new_path {
-                            self.handle_filesystem_event(last_event, &mut after_debounce_effects).await;
-                        }
+                    match &next_event {
+                        FilesystemEvent::Change(path) => {
+                            if last_state.insert(path.clone(), false) == Some(false) {
+                                continue;
+                            }
+                        },
+                        FilesystemEvent::Remove(path) => {
+                            if last_state.insert(path.clone(), true) == Some(true) {
+                                continue;
+                            }
+                        },
+                        FilesystemEvent::Rename(from, to) => {
+                            last_state.insert(from.clone(), true);
+                            last_state.insert(to.clone(), false);
+                        },
                     }
 
-                    last_event = Some(next_event);
-                }
-                if let Some(last_event) = last_event.take() {
-                    self.handle_filesystem_event(last_event, &mut after_debounce_effects).await;
+                    self.handle_filesystem_event(next_event, &mut after_debounce_effects).await;
                 }
                 for (instance_id, folder) in after_debounce_effects.reload_immediately {
                     tokio::task::spawn(self.clone().load_instance_content(instance_id, folder));
                 }
             },
-            Err(_) => {
+            Err(errors) => {
+                let mut file_watching = self.file_watching.write();
+                for error in &errors {
+                    log::warn!("Filesystem watch error: {:?}", error);
+                    file_watching.record_error(&error.paths);
+                }
+                drop(file_watching);
+
                 log::error!("An error occurred while watching the filesystem! The launcher might be out-of-sync with your files!");
                 self.send.send_error("An error occurred while watching the filesystem! The launcher might be out-of-sync with your files!");
             },
@@ -117,12 +126,14 @@ impl BackendState {
         match event {
             FilesystemEvent::Change(path) => {
                 let paths = self.file_watching.write().all_paths(path.clone());
+                self.record_events(&paths);
                 for path in paths {
                     self.handle_filesystem_change_event(path, after_debounce_effects).await;
                 }
             },
             FilesystemEvent::Remove(path) => {
                 let paths = self.file_watching.write().all_paths(path.clone());
+                self.record_events(&paths);
                 for path in paths {
                     let target = self.file_watching.write().remove(&path);
                     self.handle_filesystem_remove_event(path, target, after_debounce_effects).await;
@@ -131,6 +142,7 @@ impl BackendState {
             FilesystemEvent::Rename(from, to) => {
                 if let Some(from_parent) = from.parent() && to.parent() == Some(from_parent) && let Some(to_name) = to.file_name() {
                     let from_paths = self.file_watching.write().all_paths(from.clone());
+                    self.record_events(&from_paths);
                     for from in from_paths {
                         let to = from_parent.join(to_name).into();
 
@@ -145,12 +157,13 @@ impl BackendState {
                     }
                 } else {
                     let from_paths = self.file_watching.write().all_paths(from.clone());
+                    self.record_events(&from_paths);
                     for from in from_paths {
                         let target = self.file_watching.write().remove(&from);
                         self.handle_filesystem_remove_event(from, target, after_debounce_effects).await;
                     }
 
-                    let to_paths = self.file_watching.write().all_paths(from.clone());
+                    let to_paths = self.file_watching.write().all_paths(to.clone());
                     for to in to_paths {
                         self.handle_filesystem_change_event(to, after_debounce_effects).await;
                     }
@@ -160,6 +173,49 @@ impl BackendState {
         }
     }
 
+    /// The game keeps its own copy of the server list and writes all of it back when the multiplayer
+    /// screen is closed, which undoes any edits made from the launcher in the meantime
+    fn check_server_edits(&self, id: InstanceID) {
+        let server_dat_path = match self.instance_state.read().instances.get(id) {
+            Some(instance) if !instance.pending_server_edits.is_empty() => instance.server_dat_path.clone(),
+            _ => return,
+        };
+
+        // The file doesn't exist for a moment while the game replaces it, the next event will check again
+        let Ok(file) = std::fs::File::open(&server_dat_path) else {
+            return;
+        };
+        let servers = match nbt::decode::read_maybe_compressed(file) {
+            Ok(servers) => servers,
+            Err(err) => {
+                log::warn!("Unable to read servers.dat to check for overwritten edits: {err}");
+                return;
+            },
+        };
+
+        let mut instance_state = self.instance_state.write();
+        let Some(instance) = instance_state.instances.get_mut(id) else {
+            return;
+        };
+
+        let (kept, overwritten): (Vec<_>, Vec<_>) = std::mem::take(&mut instance.pending_server_edits)
+            .into_iter()
+            .partition(|edit| crate::servers_dat::is_applied(&servers, edit));
+        instance.pending_server_edits = kept;
+
+        if !overwritten.is_empty() {
+            log::info!("The game overwrote {} servers.dat edit(s)", overwritten.len());
+            self.send.send(MessageToFrontend::ServerEditsOverwritten { id, edits: overwritten.into() });
+        }
+    }
+
+    fn record_events(&self, paths: &[Arc<Path>]) {
+        let mut file_watching = self.file_watching.write();
+        for path in paths {
+            file_watching.record_event(path);
+        }
+    }
+
     async fn filesystem_handle_change(
         &mut self,
         target: WatchTarget,
@@ -171,6 +227,7 @@ impl BackendState {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.mark_servers_dirty();
                 }
+                self.check_server_edits(id);
                 true
             },
             _ => false,
@@ -228,6 +285,7 @@ impl BackendState {
                 // Minecraft moves the servers.dat to servers.dat_old and then back,
                 // so lets just re-listen immediately
                 self.file_watching.write().watch_filesystem(path.clone(), target);
+                self.check_server_edits(id);
                 true
             },
             WatchTarget::InstanceContentDir { id, folder } => {
@@ -238,11 +296,7 @@ impl BackendState {
             },
             WatchTarget::InstanceDotMinecraftDir { id } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    instance.mark_world_dirty(None);
-                    instance.mark_servers_dirty();
-                    for folder in ContentFolder::iter() {
-                        instance.content_state[folder].mark_dirty(None);
-                    }
+                    instance.mark_all_dirty();
                 }
                 true
             },
@@ -309,9 +363,8 @@ impl BackendState {
                 } else if file_name == "config.json" {
                     self.config.write().mark_changed(&path);
                 } else if file_name == "accounts.json" {
-                    let mut account_info = self.account_info.write();
-                    account_info.mark_changed(&path);
-                    self.send.send(account_info.get().create_update_message());
+                    self.account_info.write().mark_changed(&path);
+                    self.send_accounts_update();
                 }
             }
             WatchTarget::InstancesDir => {
@@ -503,3 +556,83 @@ fn get_simple_event(event: notify::Event) -> Option<FilesystemEvent> {
         EventKind::Other => None,
     }
 }
+
+fn is_temporary_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if file_name.ends_with('~') || file_name.starts_with(".#") {
+        return true;
+    }
+    path.extension().and_then(|extension| extension.to_str())
+        .is_some_and(|extension| TEMPORARY_EXTENSIONS.contains(&extension))
+}
+
+/// Drops events for temporary files. A temporary file being renamed over the real one is how
+/// downloads and most editors finish writing, so that's treated as a change to the real file
+fn ignore_temporary_files(event: FilesystemEvent) -> Option<FilesystemEvent> {
+    match event {
+        FilesystemEvent::Change(ref path) | FilesystemEvent::Remove(ref path) => {
+            if is_temporary_file(path) {
+                None
+            } else {
+                Some(event)
+            }
+        },
+        FilesystemEvent::Rename(from, to) => match (is_temporary_file(&from), is_temporary_file(&to)) {
+            (false, false) => Some(FilesystemEvent::Rename(from, to)),
+            (true, false) => Some(FilesystemEvent::Change(to)),
+            (false, true) => Some(FilesystemEvent::Remove(from)),
+            (true, true) => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Arc<Path> {
+        Path::new(path).into()
+    }
+
+    #[test]
+    fn detects_temporary_files() {
+        assert!(is_temporary_file(Path::new("mods/sodium.jar.part")));
+        assert!(is_temporary_file(Path::new("mods/sodium.jar.1234.new")));
+        assert!(is_temporary_file(Path::new("config/sodium.json~")));
+        assert!(is_temporary_file(Path::new("config/.#sodium.json")));
+        assert!(!is_temporary_file(Path::new("mods/sodium.jar")));
+        assert!(!is_temporary_file(Path::new("mods/sodium.jar.disabled")));
+    }
+
+    #[test]
+    fn ignores_temporary_file_events() {
+        assert_eq!(ignore_temporary_files(FilesystemEvent::Change(path("mods/sodium.jar.part"))), None);
+        assert_eq!(ignore_temporary_files(FilesystemEvent::Remove(path("mods/sodium.jar.part"))), None);
+        assert_eq!(
+            ignore_temporary_files(FilesystemEvent::Change(path("mods/sodium.jar"))),
+            Some(FilesystemEvent::Change(path("mods/sodium.jar")))
+        );
+    }
+
+    #[test]
+    fn rename_from_temporary_file_is_change() {
+        assert_eq!(
+            ignore_temporary_files(FilesystemEvent::Rename(path("mods/sodium.jar.part"), path("mods/sodium.jar"))),
+            Some(FilesystemEvent::Change(path("mods/sodium.jar")))
+        );
+        assert_eq!(
+            ignore_temporary_files(FilesystemEvent::Rename(path("mods/sodium.jar"), path("mods/sodium.jar~"))),
+            Some(FilesystemEvent::Remove(path("mods/sodium.jar")))
+        );
+    }
+
+    #[test]
+    fn toggling_content_is_rename() {
+        assert_eq!(
+            ignore_temporary_files(FilesystemEvent::Rename(path("mods/sodium.jar"), path("mods/sodium.jar.disabled"))),
+            Some(FilesystemEvent::Rename(path("mods/sodium.jar"), path("mods/sodium.jar.disabled")))
+        );
+    }
+}
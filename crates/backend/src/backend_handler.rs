@@ -1,23 +1,40 @@
-use std::{io::{BufRead, Read, Seek, SeekFrom, Write}, path::Path, sync::{atomic::Ordering, Arc}, time::{Duration, SystemTime}};
+use std::{io::{BufRead, Read, Seek, SeekFrom, Write}, path::Path, sync::{atomic::Ordering, Arc}, time::{Duration, Instant, SystemTime}};
 
-use auth::{credentials::AccountCredentials, models::{MinecraftAccessToken, MinecraftProfileResponse}, secret::PlatformSecretStorage};
+use auth::{credentials::AccountCredentials, models::MinecraftProfileResponse, secret::SecretStorage};
 use bridge::{
-    install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{InstanceStatus, ContentType, ContentSummary}, message::{LogFiles, MessageToBackend, MessageToFrontend}, meta::MetadataResult, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, serial::AtomicOptionSerial
+    account::AccountTokenState, install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{InstanceID, InstanceContentID, InstanceStatus, ContentType, ModpackUpdateDiff, ContentSummary, LoaderVersionRequirement, VersionCompatibility, WorldVersionMismatch, ServerEdit}, message::{CleanupResult, ConfigFileWriteResult, LogFiles, MessageToBackend, MessageToFrontend, QuickPlayLaunch, StorageCleanup, UnlockResult}, meta::{MetadataCacheKind, MetadataResult}, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, serial::AtomicOptionSerial
 };
 use futures::TryFutureExt;
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{content::ContentSource, modrinth::ModrinthLoader, version::{LaunchArgument, LaunchArgumentValue}};
+use schema::{backend_config::DefaultResourcePack, content::ContentSource, following::FollowedProject, launch_history::{LaunchHistoryEntry, LaunchHistoryMod, LaunchHistoryQuickPlay, LaunchOutcome}, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectType, ModrinthProjectVersionsRequest}, version::{LaunchArgument, LaunchArgumentValue, MinecraftVersion}};
 use serde::Deserialize;
+use sha1::{Digest, Sha1};
 use strum::IntoEnumIterator;
 use tokio::{io::AsyncBufReadExt, sync::Semaphore};
 use ustr::Ustr;
 
 use crate::{
-    BackendState, LoginError, account::{BackendAccount, MinecraftLoginInfo}, arcfactory::ArcStrFactory, instance::ContentFolder, launch::{ArgumentExpansionKey, LaunchError}, log_reader, metadata::{items::{AssetsIndexMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthSearchMetadataItem, ModrinthV3VersionUpdateMetadataItem, ModrinthVersionUpdateMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem, VersionUpdateParameters, VersionV3LoaderFields, VersionV3UpdateParameters}, manager::MetaLoadError}, mod_metadata::ModUpdateAction
+    BackendState, LoginError, account::{BackendAccount, LoginFlowResult, MinecraftLoginInfo}, arcfactory::ArcStrFactory, content_lock::ContentLock, graceful_stop, instance::{ContentFolder, RUN_LOCK_FILENAME}, launch::{ArgumentExpansionKey, LaunchError}, lockfile::Lockfile, log_reader, metadata::{items::{AssetsIndexMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, ModrinthCategoryTagsMetadataItem, ModrinthProjectMembersMetadataItem, ModrinthProjectMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthSearchMetadataItem, ModrinthV3VersionUpdateMetadataItem, ModrinthVersionUpdateMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem, VersionUpdateParameters, VersionV3LoaderFields, VersionV3UpdateParameters}, manager::MetaLoadError}, mod_metadata::ModUpdateAction
 };
 
+/// Repaired game files listed after verifying an instance, the rest are only counted
+const MAX_LISTED_REPAIRED_FILES: usize = 20;
+
 impl BackendState {
     pub async fn handle_message(&self, message: MessageToBackend) {
+        if self.launcher_locked.load(Ordering::SeqCst) && crate::launcher_lock::requires_unlocked(&message) {
+            match &message {
+                MessageToBackend::StartInstance { modal_action, .. }
+                | MessageToBackend::AddNewAccount { modal_action }
+                | MessageToBackend::ReauthenticateAccount { modal_action, .. } => {
+                    modal_action.set_error_message("Launcher is locked".into());
+                    modal_action.set_finished();
+                },
+                _ => self.send.send_warning("Launcher is locked"),
+            }
+            return;
+        }
+
         match message {
             MessageToBackend::RequestMetadata { request, force_reload } => {
                 let meta = self.meta.clone();
@@ -26,7 +43,11 @@ impl BackendState {
                     let (result, keep_alive_handle) = match request {
                         bridge::meta::MetadataRequest::MinecraftVersionManifest => {
                             let (result, handle) = meta.fetch_with_keepalive(&MinecraftVersionManifestMetadataItem, force_reload).await;
-                            (result.map(MetadataResult::MinecraftVersionManifest), handle)
+                            let result = match result {
+                                Ok(manifest) => Ok(MetadataResult::MinecraftVersionManifest(meta.with_local_versions(&manifest).await)),
+                                Err(error) => Err(error),
+                            };
+                            (result, handle)
                         },
                         bridge::meta::MetadataRequest::FabricLoaderManifest => {
                             let (result, handle) = meta.fetch_with_keepalive(&FabricLoaderManifestMetadataItem, force_reload).await;
@@ -48,6 +69,18 @@ impl BackendState {
                             let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectVersionsMetadataItem(project_versions), force_reload).await;
                             (result.map(MetadataResult::ModrinthProjectVersionsResult), handle)
                         },
+                        bridge::meta::MetadataRequest::ModrinthProject(ref project_id) => {
+                            let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectMetadataItem(project_id), force_reload).await;
+                            (result.map(MetadataResult::ModrinthProject), handle)
+                        },
+                        bridge::meta::MetadataRequest::ModrinthProjectMembers(ref project_id) => {
+                            let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectMembersMetadataItem(project_id), force_reload).await;
+                            (result.map(MetadataResult::ModrinthProjectMembers), handle)
+                        },
+                        bridge::meta::MetadataRequest::ModrinthCategoryTags => {
+                            let (result, handle) = meta.fetch_with_keepalive(&ModrinthCategoryTagsMetadataItem, force_reload).await;
+                            (result.map(MetadataResult::ModrinthCategoryTags), handle)
+                        },
                     };
                     let result = result.map_err(|err| format!("{}", err).into());
                     send.send(MessageToFrontend::MetadataResult {
@@ -63,32 +96,171 @@ impl BackendState {
             MessageToBackend::RequestLoadServers { id } => {
                 tokio::task::spawn(self.clone().load_instance_servers(id));
             },
+            MessageToBackend::WatchLanGames { keep_alive } => {
+                self.lan_watchers.lock().push(keep_alive);
+            },
             MessageToBackend::RequestLoadMods { id } => {
                 tokio::task::spawn(self.clone().load_instance_content(id, ContentFolder::Mods));
             },
             MessageToBackend::RequestLoadResourcePacks { id } => {
                 tokio::task::spawn(self.clone().load_instance_content(id, ContentFolder::ResourcePacks));
             },
+            MessageToBackend::CancelInstanceLoads { id } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.cancel_loads();
+                }
+            },
+            MessageToBackend::RefreshInstance { id } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.mark_all_dirty();
+                }
+                self.send.send(MessageToFrontend::Refresh);
+            },
             MessageToBackend::CreateInstance { name, version, loader } => {
                 self.create_instance(&name, &version, loader).await;
             },
-            MessageToBackend::DeleteInstance { id } => {
+            MessageToBackend::AddLocalVersion { paths, modal_action } => {
+                let meta = self.meta.clone();
+                let send = self.send.clone();
+                tokio::task::spawn(async move {
+                    let (official, keep_alive_handle) = meta.fetch_with_keepalive(&MinecraftVersionManifestMetadataItem, false).await;
+                    let official = official.ok();
+                    match meta.add_local_version(&paths, official.as_deref()) {
+                        Ok(_) => {
+                            // Send the version list again so the new version can be selected right away
+                            if let Some(official) = official {
+                                send.send(MessageToFrontend::MetadataResult {
+                                    request: bridge::meta::MetadataRequest::MinecraftVersionManifest,
+                                    result: Ok(MetadataResult::MinecraftVersionManifest(meta.with_local_versions(&official).await)),
+                                    keep_alive_handle,
+                                });
+                            }
+                        },
+                        Err(error) => {
+                            modal_action.set_error_message(format!("{}", error).into());
+                        },
+                    }
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::DeleteInstance { id, permanent } => {
+                let Some((root_path, name, running)) = self.instance_state.read().instances.get(id)
+                    .map(|instance| (instance.root_path.clone(), instance.name, instance.is_running())) else {
+                    return;
+                };
+
+                if running {
+                    self.send.send_warning("Can't delete instance while it's running");
+                    return;
+                }
+
+                // Scans would otherwise keep reading files while they're being deleted
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    let result = std::fs::remove_dir_all(&instance.root_path);
-                    if let Err(err) = result {
+                    instance.cancel_loads();
+                }
+
+                // A queued write would otherwise create the instance folder again
+                _ = tokio::task::spawn_blocking(crate::write_behind::flush_global).await;
+
+                if permanent {
+                    if let Err(err) = std::fs::remove_dir_all(&root_path) {
                         self.send.send_error(format!("Unable to delete instance folder: {}", err));
                     }
+                    return;
+                }
+
+                // Moving to the trash can take a while for large instances
+                let result = tokio::task::spawn_blocking(move || trash::delete(&root_path)).await;
+                match result {
+                    Ok(Ok(())) => {
+                        self.send.send_success(format!("Moved '{}' to the trash, restore it from there to get it back", name));
+                    },
+                    Ok(Err(err)) => {
+                        self.send.send_error(format!("Unable to move instance folder to the trash: {}. Try deleting it permanently instead", err));
+                    },
+                    Err(err) => {
+                        self.send.send_error(format!("Unable to move instance folder to the trash: {}", err));
+                    },
                 }
             },
-            MessageToBackend::RenameInstance { id, name } => {
-                self.rename_instance(id, &name).await;
+            MessageToBackend::DuplicateInstance { id } => {
+                // Copying a large instance can take a while, other messages shouldn't wait for it
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    state.duplicate_instance(id).await;
+                });
+            },
+            MessageToBackend::SetInstancePinned { id, pinned } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.pinned = pinned;
+                    });
+                }
             },
-            MessageToBackend::SetInstanceMinecraftVersion { id, version } => {
+            MessageToBackend::SetInstanceFileWatching { id, value } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.configuration.modify(|configuration| {
-                        configuration.minecraft_version = version;
+                        configuration.file_watching = Some(value);
                     });
+                    self.send.send(instance.create_modify_message());
+                }
+                self.update_instance_file_watching(id);
+            },
+            MessageToBackend::RenameInstance { id, name } => {
+                self.rename_instance(id, &name).await;
+            },
+            MessageToBackend::CheckVersionCompatibility { id, version, channel } => {
+                _ = channel.send(self.check_version_compatibility(id, version).await);
+            },
+            MessageToBackend::ChangeInstanceMinecraftVersion { id, version, backup, modal_action } => {
+                let Some((name, old_version, running)) = self.instance_state.write().instances.get_mut(id)
+                    .map(|instance| (instance.name, instance.configuration.get().minecraft_version, instance.is_running()))
+                else {
+                    modal_action.set_error_message("Can't change version, unknown instance".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                // The game writes to the folder being backed up, and the version would change under it
+                if running {
+                    modal_action.set_error_message("Can't change version while the instance is running".into());
+                    modal_action.set_finished();
+                    return;
                 }
+
+                // Backing up a large instance can take a while, other messages shouldn't wait for it
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    if backup {
+                        let tracker = ProgressTracker::new("Backing up instance".into(), state.send.clone());
+                        modal_action.trackers.push(tracker.clone());
+
+                        // The copy keeps the old version, so it can be played if the new version doesn't work out
+                        if let Err(error) = state.copy_instance(id, format!("{name} (Backup {old_version})")).await {
+                            tracker.set_finished(ProgressTrackerFinishType::Error);
+                            modal_action.set_error_message(format!("Unable to back up instance, the version wasn't changed: {error}").into());
+                            modal_action.set_finished();
+                            return;
+                        }
+
+                        tracker.set_finished(ProgressTrackerFinishType::Normal);
+                        tracker.notify();
+                    }
+
+                    // The game may have been started while the backup was being made
+                    if let Some(instance) = state.instance_state.write().instances.get_mut(id) {
+                        if instance.is_running() {
+                            modal_action.set_error_message("Can't change version while the instance is running".into());
+                            modal_action.set_finished();
+                            return;
+                        }
+                        instance.configuration.modify(|configuration| {
+                            configuration.minecraft_version = version;
+                        });
+                    }
+                    log::info!("Changed the version of {name} from {old_version} to {version}");
+                    modal_action.set_finished();
+                });
             },
             MessageToBackend::SetInstanceLoader { id, loader } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
@@ -105,6 +277,9 @@ impl BackendState {
                     });
                 }
             }
+            MessageToBackend::CheckLoaderVersionRequirement { id, channel } => {
+                _ = channel.send(self.check_loader_version_requirement(id).await);
+            },
             MessageToBackend::SetInstanceMemory { id, memory } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.configuration.modify(|configuration| {
@@ -126,14 +301,66 @@ impl BackendState {
                     });
                 }
             },
+            MessageToBackend::SetInstanceWindow { id, window } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.window = Some(window);
+                    });
+                }
+            },
+            MessageToBackend::SetInstanceDiscreteGpu { id, value } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.discrete_gpu = value;
+                    });
+                }
+            },
+            MessageToBackend::StopInstance { id } => {
+                let grace_period = Duration::from_secs(self.config.write().get().stop_grace_period_secs() as u64);
+                let pid = {
+                    let mut instance_state = self.instance_state.write();
+                    let Some(instance) = instance_state.instances.get_mut(id) else {
+                        self.send.send_error("Can't stop instance, unknown id");
+                        return;
+                    };
+                    let Some(pid) = instance.pid() else {
+                        self.send.send_error("Can't stop instance, instance wasn't running");
+                        return;
+                    };
+                    if instance.stop_deadline.is_some() {
+                        return;
+                    }
+
+                    // Set before asking so a second stop request doesn't ask again
+                    instance.stop_deadline = Some(Instant::now() + grace_period);
+                    self.send.send(instance.create_modify_message());
+                    pid
+                };
+
+                // taskkill is a separate process, so the request is made without holding the instance state
+                let result = tokio::task::spawn_blocking(move || graceful_stop::request_stop(pid)).await
+                    .unwrap_or_else(|error| Err(std::io::Error::other(error)));
+
+                // If the game can't be asked nicely it's killed on the next tick instead
+                if let Err(error) = result {
+                    log::warn!("Unable to ask instance to stop, killing it instead: {error}");
+                    if let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                        && instance.pid() == Some(pid)
+                        && instance.stop_deadline.is_some()
+                    {
+                        instance.stop_deadline = Some(Instant::now());
+                    }
+                }
+            },
             MessageToBackend::KillInstance { id } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    if let Some(mut child) = instance.child.take() {
-                        let result = child.kill();
+                    if instance.is_running() {
+                        let result = instance.kill(&mut self.process_monitor.lock());
                         if result.is_err() {
                             self.send.send_error("Failed to kill instance");
                             log::error!("Failed to kill instance: {:?}", result.unwrap_err());
                         }
+                        instance.finish_launch_history(LaunchOutcome::Killed, None);
 
                         self.send.send(instance.create_modify_message());
                     } else {
@@ -149,6 +376,28 @@ impl BackendState {
                 quick_play,
                 modal_action,
             } => {
+                let Some(root_path) = self.instance_state.read().instances.get(id).map(|instance| instance.root_path.clone()) else {
+                    self.send.send_error("Can't launch instance, unknown id");
+                    modal_action.set_error_message("Can't launch instance, unknown id".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                // Taken before prelaunch so two launchers can't update the same instance's modpacks at once either
+                let run_lock = match Lockfile::try_create(root_path.join(RUN_LOCK_FILENAME).into()) {
+                    Ok(Some(run_lock)) => Some(run_lock),
+                    Ok(None) => {
+                        self.send.send_warning("Can't launch instance, it's already being launched or running in another launcher");
+                        modal_action.set_error_message("Can't launch instance, it's already being launched or running in another launcher".into());
+                        modal_action.set_finished();
+                        return;
+                    },
+                    Err(err) => {
+                        log::warn!("Unable to create run lock for instance: {}", err);
+                        None
+                    },
+                };
+
                 let Some(login_info) = self.get_login_info(&modal_action).await else {
                     return;
                 };
@@ -167,20 +416,42 @@ impl BackendState {
                     return;
                 }
 
-                let (dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    if instance.child.is_some() {
+                let history_quick_play = quick_play.as_ref().map(|quick_play| match quick_play {
+                    QuickPlayLaunch::Singleplayer(target) => LaunchHistoryQuickPlay::Singleplayer(target.to_string_lossy().into()),
+                    QuickPlayLaunch::Multiplayer(target) => LaunchHistoryQuickPlay::Multiplayer(target.to_string_lossy().into()),
+                    QuickPlayLaunch::Realms(target) => LaunchHistoryQuickPlay::Realms(target.to_string_lossy().into()),
+                });
+
+                let (name, dot_minecraft, configuration, mods) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    if instance.is_running() {
                         self.send.send_warning("Can't launch instance, already running");
                         modal_action.set_error_message("Can't launch instance, already running".into());
                         modal_action.set_finished();
                         return;
                     }
 
+                    if let Some(history_quick_play) = &history_quick_play {
+                        instance.configuration.modify(|configuration| {
+                            configuration.last_quick_play = Some(history_quick_play.clone());
+                        });
+                    }
+
                     self.send.send(MessageToFrontend::MoveInstanceToTop {
                         id
                     });
                     self.send.send(instance.create_modify_message_with_status(InstanceStatus::Launching));
 
-                    (instance.dot_minecraft_path.clone(), instance.configuration.get().clone())
+                    let mods: Option<Arc<[LaunchHistoryMod]>> = instance.loaded_content(ContentFolder::Mods).map(|mods| {
+                        mods.iter().filter(|summary| summary.enabled).map(|summary| LaunchHistoryMod {
+                            filename: summary.filename.clone(),
+                            id: summary.content_summary.id.clone(),
+                            name: summary.content_summary.name.clone(),
+                            version: summary.content_summary.version_str.clone(),
+                            sha1: hex::encode(summary.content_summary.hash).into(),
+                        }).collect()
+                    });
+
+                    (instance.name, instance.dot_minecraft_path.clone(), instance.configuration.get().clone(), mods)
                 } else {
                     self.send.send_error("Can't launch instance, unknown id");
                     modal_action.set_error_message("Can't launch instance, unknown id".into());
@@ -188,10 +459,27 @@ impl BackendState {
                     return;
                 };
 
-                let launch_tracker = ProgressTracker::new(Arc::from("Launching"), self.send.clone());
+                let launch_title = if login_info.offline_fallback { "Launching (offline)" } else { "Launching" };
+                let launch_tracker = ProgressTracker::new(Arc::from(launch_title), self.send.clone());
                 modal_action.trackers.push(launch_tracker.clone());
 
-                let result = self.launcher.launch(&self.redirecting_http_client, dot_minecraft, configuration, quick_play, login_info, add_mods, &launch_tracker, &modal_action).await;
+                let mut history_entry = LaunchHistoryEntry {
+                    started_at: chrono::Utc::now().timestamp_millis(),
+                    account: Some(login_info.username.clone()),
+                    offline: login_info.access_token.is_none() || login_info.offline_fallback,
+                    minecraft_version: configuration.minecraft_version,
+                    loader: configuration.loader,
+                    loader_version: configuration.preferred_loader_version,
+                    quick_play: history_quick_play,
+                    outcome: LaunchOutcome::Running,
+                    duration_secs: None,
+                    exit_code: None,
+                    error: None,
+                    mods,
+                    suspected_mods: None,
+                };
+
+                let result = self.launcher.launch(&self.redirecting_http_client, name, dot_minecraft, configuration, quick_play, login_info, add_mods, &launch_tracker, &modal_action).await;
 
                 if matches!(result, Err(LaunchError::CancelledByUser)) {
                     self.send.send(MessageToFrontend::CloseModal);
@@ -210,11 +498,24 @@ impl BackendState {
                             }
                         }
                         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                            if let Some(record) = self.process_monitor.lock().record(child.id())
+                                && let Err(err) = record.write(&instance.root_path)
+                            {
+                                log::warn!("Unable to write process record for instance: {}", err);
+                            }
                             instance.child = Some(child);
+                            instance.run_lock = run_lock;
+                            instance.launch_history.modify(|history| history.push(history_entry));
                         }
                     },
                     Err(ref err) => {
                         modal_action.set_error_message(format!("{}", &err).into());
+
+                        history_entry.outcome = LaunchOutcome::FailedToLaunch;
+                        history_entry.error = Some(format!("{}", &err).into());
+                        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                            instance.launch_history.modify(|history| history.push(history_entry));
+                        }
                     },
                 }
 
@@ -240,7 +541,7 @@ impl BackendState {
                 for mod_id in mod_ids {
                     if let Some((instance_mod, folder)) = instance.try_get_content(mod_id) {
                         if instance_mod.enabled == enabled {
-                            return;
+                            continue;
                         }
 
                         let mut new_path = instance_mod.path.to_path_buf();
@@ -281,35 +582,200 @@ impl BackendState {
                 self.download_all_metadata().await;
             },
             MessageToBackend::InstallContent { content, modal_action } => {
-                self.install_content(content, modal_action.clone()).await;
-                modal_action.set_finished();
-                self.send.send(MessageToFrontend::Refresh);
+                // Installs wait for their turn in the install queue, which shouldn't hold up other messages
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    state.install_content(content, modal_action.clone()).await;
+                    modal_action.set_finished();
+                    state.send.send(MessageToFrontend::Refresh);
+                });
             },
-            MessageToBackend::DeleteContent { id, content_ids: mod_ids } => {
-                let mut instance_state = self.instance_state.write();
-                let Some(instance) = instance_state.instances.get_mut(id) else {
-                    self.send.send_error("Unable to find instance, unknown id");
+            MessageToBackend::ReadModpackFile { path, channel } => {
+                let result = tokio::task::spawn_blocking(move || crate::modpack::read_modpack_file(&path)).await;
+                _ = channel.send(match result {
+                    Ok(result) => result.map_err(|error| error.to_string().into()),
+                    Err(_) => Err("Unable to read modpack".into()),
+                });
+            },
+            MessageToBackend::RetryInstallFile { id, file, modal_action } => {
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    state.retry_install_file(id, file, modal_action.clone()).await;
+                    modal_action.set_finished();
+                    state.send.send(MessageToFrontend::Refresh);
+                });
+            },
+            MessageToBackend::PreviewLaunchCommand { id, modal_action, channel } => {
+                let Some((name, root_path, dot_minecraft, configuration, running)) = self.instance_state.write().instances.get_mut(id).map(|instance| {
+                    (instance.name, instance.root_path.clone(), instance.dot_minecraft_path.clone(), instance.configuration.get().clone(), instance.is_running())
+                }) else {
+                    _ = channel.send(Err("Can't preview launch, unknown id".into()));
+                    modal_action.set_finished();
                     return;
                 };
 
-                let mut reload = FxHashSet::default();
+                // Preparing the launch installs the files of modpacks, which mustn't happen while the game is using them
+                if running {
+                    _ = channel.send(Err("Can't preview launch while the instance is running".into()));
+                    modal_action.set_finished();
+                    return;
+                }
+                let _run_lock = match Lockfile::try_create(root_path.join(RUN_LOCK_FILENAME).into()) {
+                    Ok(Some(run_lock)) => Some(run_lock),
+                    Ok(None) => {
+                        _ = channel.send(Err("Can't preview launch, the instance is being launched or running in another launcher".into()));
+                        modal_action.set_finished();
+                        return;
+                    },
+                    Err(err) => {
+                        log::warn!("Unable to create run lock for instance: {}", err);
+                        None
+                    },
+                };
 
-                for mod_id in mod_ids {
-                    let Some((instance_mod, folder)) = instance.try_get_content(mod_id) else {
-                        self.send.send_error("Unable to delete mod, invalid id");
+                let add_mods = tokio::select! {
+                    add_mods = self.prelaunch_apply_modpacks(id, &modal_action) => add_mods,
+                    _ = modal_action.request_cancel.cancelled() => {
+                        self.send.send(MessageToFrontend::CloseModal);
+                        _ = channel.send(Err("Cancelled".into()));
+                        return;
+                    }
+                };
+
+                // The access token is redacted from the preview, so there's no need to log in
+                let login_info = {
+                    let mut account_info = self.account_info.write();
+                    let account_info = account_info.get();
+                    let selected = account_info.selected_account.and_then(|uuid| account_info.accounts.get(&uuid).map(|account| (uuid, account)));
+                    MinecraftLoginInfo {
+                        uuid: selected.map(|(uuid, _)| uuid).unwrap_or_default(),
+                        username: selected.map(|(_, account)| account.username.clone()).unwrap_or_else(|| "Player".into()),
+                        access_token: None,
+                        offline_fallback: false,
+                    }
+                };
+
+                let launch_tracker = ProgressTracker::new("Preparing launch".into(), self.send.clone());
+                modal_action.trackers.push(launch_tracker.clone());
+
+                let result = self.launcher.preview_launch_command(&self.redirecting_http_client, name, dot_minecraft,
+                    configuration, login_info, add_mods, &launch_tracker, &modal_action).await;
+
+                launch_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
+                launch_tracker.notify();
+                modal_action.set_finished();
+                _ = channel.send(result.map_err(|error| format!("{}", error).into()));
+            },
+            MessageToBackend::VerifyInstance { id, modal_action } => {
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    state.verify_instance(id, modal_action.clone()).await;
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::RepairInstanceContent { instance, modal_action } => {
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    state.repair_instance_content(instance, modal_action.clone()).await;
+                    modal_action.set_finished();
+                    state.send.send(MessageToFrontend::Refresh);
+                });
+            },
+            MessageToBackend::RemoveFromInstallQueue { id } => {
+                self.install_queue.remove(id);
+                self.install_queue.send_update(&self.send);
+            },
+            MessageToBackend::DeleteContent { id, content_ids: mod_ids } => {
+                let (root_path, unlocked) = {
+                    let mut instance_state = self.instance_state.write();
+                    let Some(instance) = instance_state.instances.get_mut(id) else {
+                        self.send.send_error("Unable to find instance, unknown id");
+                        return;
+                    };
+
+                    let mut reload = FxHashSet::default();
+                    let mut unlocked = Vec::new();
+
+                    for mod_id in mod_ids {
+                        let Some((instance_mod, folder)) = instance.try_get_content(mod_id) else {
+                            self.send.send_error("Unable to delete mod, invalid id");
+                            return;
+                        };
+
+                        let _ = std::fs::remove_file(&instance_mod.path);
+                        unlocked.extend(ContentLock::key(&instance.dot_minecraft_path, &instance_mod.path));
+                        reload.insert((id, folder));
+                    }
+
+                    let root_path = instance.root_path.to_path_buf();
+                    instance_state.reload_immediately.extend(reload);
+                    (root_path, unlocked)
+                };
+
+                if !unlocked.is_empty() {
+                    ContentLock::modify(root_path, move |lock| {
+                        for key in &unlocked {
+                            lock.files.remove(key);
+                        }
+                    }).await;
+                }
+            },
+            MessageToBackend::SetDefaultResourcePack { id, content_id, default } => {
+                let content = {
+                    let instance_state = self.instance_state.read();
+                    let Some(instance) = instance_state.instances.get(id) else {
+                        self.send.send_error("Unable to find instance, unknown id");
                         return;
                     };
+                    match instance.try_get_content(content_id) {
+                        Some((content, ContentFolder::ResourcePacks)) => content.clone(),
+                        _ => {
+                            self.send.send_error("Unable to find resource pack, invalid id");
+                            return;
+                        },
+                    }
+                };
 
-                    let _ = std::fs::remove_file(&instance_mod.path);
-                    reload.insert((id, folder));
+                let sha1 = hex::encode(content.content_summary.hash);
+                if !default {
+                    self.config.write().modify(|config| {
+                        config.default_resource_packs.retain(|pack| pack.sha1 != sha1);
+                    });
+                    return;
                 }
 
-                instance_state.reload_immediately.extend(reload);
+                if !content.path.is_file() {
+                    self.send.send_warning("Only resource packs that are zip files can be used for new instances");
+                    return;
+                }
+
+                // The instance's copy can be deleted, so new instances copy the one in the content library instead
+                let extension = content.path.extension().and_then(|extension| extension.to_str());
+                let library_path = crate::create_content_library_path(&self.directories.content_library_dir, content.content_summary.hash, extension);
+                let source = content.path.clone();
+                let hash = content.content_summary.hash;
+                let result = tokio::task::spawn_blocking(move || {
+                    if crate::check_sha1_hash(&library_path, hash).unwrap_or(false) {
+                        return Ok(());
+                    }
+                    let _ = std::fs::create_dir_all(library_path.parent().unwrap());
+                    std::fs::copy(&source, &library_path).map(|_| ())
+                }).await.unwrap();
+                if let Err(err) = result {
+                    self.send.send_error(format!("Unable to copy resource pack into the content library: {err}"));
+                    return;
+                }
+
+                let filename = content.filename.strip_suffix(".disabled").unwrap_or(&content.filename).to_string();
+                self.config.write().modify(|config| {
+                    config.default_resource_packs.retain(|pack| pack.sha1 != sha1 && pack.filename != filename);
+                    config.default_resource_packs.push(DefaultResourcePack { sha1, filename });
+                });
             },
             MessageToBackend::UpdateCheck { instance: id, modal_action } => {
-                let (loader, version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                let (loader, version, root_path) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     let configuration = instance.configuration.get();
-                    (configuration.loader, configuration.minecraft_version)
+                    (configuration.loader, configuration.minecraft_version, instance.root_path.clone())
                 } else {
                     self.send.send_error("Can't update instance, unknown id");
                     modal_action.set_error_message("Can't update instance, unknown id".into());
@@ -382,9 +848,14 @@ impl BackendState {
                 }
 
                 { // Scope is needed so await doesn't complain about the non-send RwLockReadGuard
+                    // The instance's lock still knows where content came from if the global sources were lost
+                    let content_lock = ContentLock::read(&root_path);
                     let sources = self.mod_metadata_manager.read_content_sources();
                     for summary in content.iter() {
-                        let source = sources.get(&summary.content_summary.hash).unwrap_or(ContentSource::Manual);
+                        let source = sources.get(&summary.content_summary.hash)
+                            .filter(|source| *source != ContentSource::Manual)
+                            .or_else(|| content_lock.find_by_hash(&hex::encode(summary.content_summary.hash)).map(|file| file.source.clone()))
+                            .unwrap_or(ContentSource::Manual);
                         let semaphore = &semaphore;
                         let meta = &meta;
                         let tracker = &tracker;
@@ -505,6 +976,9 @@ impl BackendState {
                 tracker.set_finished(ProgressTrackerFinishType::Normal);
                 modal_action.set_finished();
             },
+            MessageToBackend::GetModpackUpdateDiff { instance: id, content_id, channel } => {
+                _ = channel.send(self.get_modpack_update_diff(id, content_id).await);
+            },
             MessageToBackend::UpdateContent { instance: id, content_id: mod_id, modal_action } => {
                 let content_install = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     let configuration = instance.configuration.get();
@@ -783,34 +1257,314 @@ impl BackendState {
                     });
                 }
             },
-            MessageToBackend::GetBackendConfiguration { channel } => {
-                let configuration = self.config.write().get().clone();
-                _ = channel.send(configuration);
-            },
-            MessageToBackend::CleanupOldLogFiles { instance: id } => {
-                let mut deleted = 0;
+            MessageToBackend::GetWorldDetails { id, level_path, channel } => {
+                let contains_world = self.instance_state.read().instances.get(id).map(|instance| instance.contains_world(&level_path));
+                match contains_world {
+                    Some(true) => {},
+                    Some(false) => {
+                        _ = channel.send(Err("World isn't part of this instance".into()));
+                        return;
+                    },
+                    None => {
+                        _ = channel.send(Err("Unknown instance".into()));
+                        return;
+                    },
+                }
 
-                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    let logs = instance.dot_minecraft_path.join("logs");
+                let level_dat_modified = std::fs::metadata(level_path.join("level.dat")).and_then(|metadata| metadata.modified()).ok();
 
-                    if let Ok(read_dir) = std::fs::read_dir(logs) {
-                        for file in read_dir {
-                            let Ok(entry) = file else {
-                                continue;
-                            };
+                let cached_size = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    level_dat_modified.and_then(|modified| instance.cached_world_size(&level_path, modified))
+                } else {
+                    _ = channel.send(Err("Unknown instance".into()));
+                    return;
+                };
 
-                            let filename = entry.file_name();
-                            let Some(filename) = filename.to_str() else {
-                                continue;
-                            };
+                let details = {
+                    let level_path = level_path.clone();
+                    tokio::task::spawn_blocking(move || crate::instance::load_world_details(&level_path, cached_size)).await
+                };
 
-                            if filename.ends_with(".log.gz") {
-                                if std::fs::remove_file(entry.path()).is_ok() {
-                                    deleted += 1;
-                                }
-                            }
-                        }
-                    }
+                let details = match details {
+                    Ok(Ok(details)) => details,
+                    Ok(Err(err)) => {
+                        _ = channel.send(Err(format!("Unable to read world: {err}").into()));
+                        return;
+                    },
+                    Err(err) => {
+                        _ = channel.send(Err(format!("{err}").into()));
+                        return;
+                    },
+                };
+
+                if cached_size.is_none()
+                    && let Some(modified) = level_dat_modified
+                    && let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                {
+                    instance.cache_world_size(level_path, modified, details.size_bytes);
+                }
+
+                _ = channel.send(Ok(details));
+            },
+            MessageToBackend::EditServers { id, edit } => {
+                let (server_dat_path, backup_dir) = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    (instance.server_dat_path.clone(), instance.root_path.join(crate::servers_dat::BACKUP_DIRNAME))
+                } else {
+                    self.send.send_error("Can't edit servers, unknown instance");
+                    return;
+                };
+
+                if let Err(err) = crate::servers_dat::write(&server_dat_path, &backup_dir, &edit) {
+                    self.send.send_error(err.to_string());
+                    return;
+                }
+
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                    && instance.is_running()
+                {
+                    // A newer edit of the same kind to the same server replaces the older one,
+                    // adding or removing the server replaces every earlier edit of it
+                    let replaces_all = matches!(edit, ServerEdit::Add { .. } | ServerEdit::Remove { .. });
+                    instance.pending_server_edits.retain(|pending| {
+                        pending.name() != edit.name() || pending.ip() != edit.ip()
+                            || !(replaces_all || std::mem::discriminant(pending) == std::mem::discriminant(&edit))
+                    });
+                    instance.pending_server_edits.push(edit);
+                }
+            },
+            MessageToBackend::CheckWorldVersion { id, level_path, channel } => {
+                let instance_version = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    if !instance.contains_world(&level_path) {
+                        _ = channel.send(Err("World isn't part of this instance".into()));
+                        return;
+                    }
+                    instance.configuration.get().minecraft_version
+                } else {
+                    _ = channel.send(Err("Unknown instance".into()));
+                    return;
+                };
+
+                let world_version = tokio::task::spawn_blocking(move || crate::instance::load_world_version(&level_path)).await;
+                let (world_version, world_data_version) = match world_version {
+                    Ok(Ok(world_version)) => world_version,
+                    Ok(Err(err)) => {
+                        _ = channel.send(Err(format!("Unable to read world: {err}").into()));
+                        return;
+                    },
+                    Err(err) => {
+                        _ = channel.send(Err(format!("{err}").into()));
+                        return;
+                    },
+                };
+
+                // Worlds from before 1.9 don't store the version, those can always be upgraded
+                let Some(world_version) = world_version else {
+                    _ = channel.send(Ok(None));
+                    return;
+                };
+
+                let versions = match self.meta.fetch(&MinecraftVersionManifestMetadataItem).await {
+                    Ok(versions) => versions,
+                    Err(err) => {
+                        _ = channel.send(Err(format!("Unable to load version manifest: {err}").into()));
+                        return;
+                    },
+                };
+
+                // The manifest is ordered from newest to oldest, so it doubles as a mapping from each
+                // release or snapshot to its DataVersion ordering. Unknown versions (e.g. custom jars) are ignored
+                let world_index = versions.versions.iter().position(|link| link.id.as_str() == world_version);
+                let instance_index = versions.versions.iter().position(|link| link.id == instance_version);
+
+                let mismatch = match (world_index, instance_index) {
+                    (Some(world_index), Some(instance_index)) if world_index < instance_index => Some(WorldVersionMismatch {
+                        world_version: world_version.into(),
+                        world_data_version,
+                        instance_version: instance_version.as_str().into(),
+                    }),
+                    _ => None,
+                };
+
+                _ = channel.send(Ok(mismatch));
+            },
+            MessageToBackend::SplitWorldIntoInstance { id, level_path, name, keep_copy, modal_action } => {
+                // Copying a large world can take a while, other messages shouldn't wait for it
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    if let Err(error) = state.split_world_into_instance(id, level_path, &name, keep_copy).await {
+                        modal_action.set_error_message(format!("Unable to create instance from world: {error}").into());
+                    }
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::GetLaunchHistory { id, channel } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    let entries = instance.launch_history.get().entries.iter().rev().cloned().collect();
+                    _ = channel.send(entries);
+                }
+            },
+            MessageToBackend::ListConfigFiles { id, channel } => {
+                let config_dir = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    crate::config_files::config_dir(&instance.dot_minecraft_path)
+                } else {
+                    return;
+                };
+                if let Ok(files) = tokio::task::spawn_blocking(move || crate::config_files::list(&config_dir)).await {
+                    _ = channel.send(files);
+                }
+            },
+            MessageToBackend::ReadConfigFile { id, path, channel } => {
+                let config_dir = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    crate::config_files::config_dir(&instance.dot_minecraft_path)
+                } else {
+                    _ = channel.send(Err("Unknown instance".into()));
+                    return;
+                };
+                if let Ok(result) = tokio::task::spawn_blocking(move || crate::config_files::read(&config_dir, &path)).await {
+                    _ = channel.send(result);
+                }
+            },
+            MessageToBackend::WriteConfigFile { id, path, contents, expected_modified, channel } => {
+                let config_dir = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    crate::config_files::config_dir(&instance.dot_minecraft_path)
+                } else {
+                    _ = channel.send(ConfigFileWriteResult::Error("Unknown instance".into()));
+                    return;
+                };
+                if let Ok(result) = tokio::task::spawn_blocking(move || {
+                    crate::config_files::write(&config_dir, &path, &contents, expected_modified)
+                }).await {
+                    _ = channel.send(result);
+                }
+            },
+            MessageToBackend::GetGameOptions { id, channel } => {
+                let options_path = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    crate::game_options::options_path(&instance.dot_minecraft_path)
+                } else {
+                    _ = channel.send(Err("Unknown instance".into()));
+                    return;
+                };
+                if let Ok(result) = tokio::task::spawn_blocking(move || crate::game_options::read(&options_path)).await {
+                    _ = channel.send(result);
+                }
+            },
+            MessageToBackend::SetGameOptions { id, changes, channel } => {
+                let options_path = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    crate::game_options::options_path(&instance.dot_minecraft_path)
+                } else {
+                    _ = channel.send(Err("Unknown instance".into()));
+                    return;
+                };
+                if let Ok(result) = tokio::task::spawn_blocking(move || crate::game_options::write(&options_path, &changes)).await {
+                    _ = channel.send(result);
+                }
+            },
+            MessageToBackend::GetBackendConfiguration { channel } => {
+                let mut configuration = self.config.write().get().clone();
+                // The password stays hidden until the launcher is unlocked
+                if configuration.proxy.password_saved
+                    && !self.launcher_locked.load(Ordering::SeqCst)
+                    && let Ok(storage) = self.secret_storage().await
+                {
+                    match storage.read_secret(crate::proxy::PASSWORD_SECRET).await {
+                        Ok(password) => configuration.proxy.password = password,
+                        Err(error) => log::error!("Unable to read the proxy password: {error}"),
+                    }
+                }
+                _ = channel.send(configuration);
+            },
+            MessageToBackend::GetGpus { channel } => {
+                let gpus = tokio::task::spawn_blocking(|| crate::gpu::detected().clone()).await.unwrap();
+                _ = channel.send(gpus);
+            },
+            MessageToBackend::GetLatestCompatibleVersion { instance, project_id, project_type, channel } => {
+                let Some(configuration) = self.instance_state.write().instances.get_mut(instance).map(|instance| instance.configuration.get().clone()) else {
+                    _ = channel.send(Err("Unable to find instance, unknown id".into()));
+                    return;
+                };
+
+                let loader = if matches!(project_type, ModrinthProjectType::Mod | ModrinthProjectType::Modpack) {
+                    // Which loader's version to use is up to the user
+                    if configuration.loader == Loader::Vanilla {
+                        _ = channel.send(Ok(None));
+                        return;
+                    }
+                    Some(configuration.loader.as_modrinth_loader())
+                } else {
+                    None
+                };
+
+                let request = ModrinthProjectVersionsRequest {
+                    project_id,
+                    game_versions: None,
+                    loaders: None,
+                };
+                let result = match self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&request)).await {
+                    Ok(versions) => Ok(crate::install_content::latest_compatible_version(&versions.0, configuration.minecraft_version.as_str(), loader).cloned()),
+                    Err(error) => Err(format!("Error loading project versions from Modrinth:\n{error}").into()),
+                };
+                _ = channel.send(result);
+            },
+            MessageToBackend::FollowProject { project_id, title, project_type, icon_url } => {
+                let project = FollowedProject {
+                    project_id,
+                    title,
+                    project_type,
+                    icon_url,
+                    followed_at: chrono::Utc::now().timestamp_millis(),
+                    versions: Vec::new(),
+                };
+                self.followed_projects.write().modify(|followed| followed.follow(project));
+                self.send_followed_projects_update();
+                self.check_followed_projects();
+            },
+            MessageToBackend::UnfollowProject { project_id } => {
+                self.followed_projects.write().modify(|followed| followed.unfollow(&project_id));
+                self.send_followed_projects_update();
+            },
+            MessageToBackend::CheckFollowedProjects => {
+                self.check_followed_projects();
+            },
+            MessageToBackend::MarkFollowedFeedSeen => {
+                self.followed_projects.write().modify(|followed| followed.seen_until = chrono::Utc::now().timestamp_millis());
+                self.send_followed_projects_update();
+            },
+            MessageToBackend::GetFileWatches { channel } => {
+                let info = self.file_watching.read().debug_info();
+                _ = channel.send(info);
+            },
+            MessageToBackend::RewatchPath { path } => {
+                log::info!("Re-registering watch for {:?}", path);
+                self.file_watching.write().rewatch(&path);
+            },
+            MessageToBackend::UnwatchPath { path } => {
+                log::info!("Dropping watch for {:?}", path);
+                self.file_watching.write().unwatch(&path);
+            },
+            MessageToBackend::CleanupOldLogFiles { instance: id } => {
+                let mut deleted = 0;
+
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    let logs = instance.dot_minecraft_path.join("logs");
+
+                    if let Ok(read_dir) = std::fs::read_dir(logs) {
+                        for file in read_dir {
+                            let Ok(entry) = file else {
+                                continue;
+                            };
+
+                            let filename = entry.file_name();
+                            let Some(filename) = filename.to_str() else {
+                                continue;
+                            };
+
+                            if filename.ends_with(".log.gz") {
+                                if std::fs::remove_file(entry.path()).is_ok() {
+                                    deleted += 1;
+                                }
+                            }
+                        }
+                    }
                 }
 
                 self.send.send_success(format!("Deleted {} files", deleted));
@@ -961,7 +1715,8 @@ impl BackendState {
                     account_info.accounts.insert(uuid, BackendAccount {
                         username: name,
                         offline: true,
-                        head: None
+                        legacy_head: None,
+                        skin_url: None,
                     });
                     account_info.selected_account = Some(uuid);
                 });
@@ -979,39 +1734,396 @@ impl BackendState {
                 });
             },
             MessageToBackend::DeleteAccount { uuid } => {
-                let mut account_info = self.account_info.write();
-
-                account_info.modify(|account_info| {
-                    account_info.accounts.remove(&uuid);
+                let mut offline = false;
+                self.account_info.write().modify(|account_info| {
+                    if let Some(account) = account_info.accounts.remove(&uuid) {
+                        offline = account.offline;
+                    }
                     if account_info.selected_account == Some(uuid) {
                         account_info.selected_account = None;
                     }
                 });
+                self.account_heads.write().remove(uuid);
+
+                if !offline {
+                    match self.secret_storage().await {
+                        Ok(secret_storage) => {
+                            if let Err(error) = secret_storage.delete_credentials(uuid).await {
+                                log::warn!("Unable to delete credentials from keychain: {error}");
+                            }
+                        },
+                        Err(error) => log::warn!("Unable to delete credentials, secret storage is unavailable: {error}"),
+                    }
+                }
+            },
+            MessageToBackend::ReauthenticateAccount { uuid, modal_action } => {
+                let previous_selection = self.account_info.write().get().selected_account;
+
+                // Starting without credentials forces the full Microsoft login
+                let Some(LoginFlowResult::LoggedIn(profile, _)) = self.login_flow(&modal_action, None).await else {
+                    return;
+                };
+
+                if profile.id != uuid {
+                    self.send.send_warning(format!("Logged in as {} instead, which was added as a separate account", profile.name));
+                }
+
+                // Logging in selects the account, but re-authenticating shouldn't change the default
+                if previous_selection.is_some() && previous_selection != Some(profile.id) {
+                    self.account_info.write().modify(|info| info.selected_account = previous_selection);
+                }
+            },
+            MessageToBackend::GetAccountTokenStates { channel } => {
+                let accounts: Vec<(uuid::Uuid, bool)> = self.account_info.write().get().accounts.iter()
+                    .map(|(uuid, account)| (*uuid, account.offline))
+                    .collect();
+
+                let secret_storage = self.secret_storage().await;
+                let mut states = Vec::with_capacity(accounts.len());
+                for (uuid, offline) in accounts {
+                    let state = if offline {
+                        AccountTokenState::Offline
+                    } else {
+                        match secret_storage {
+                            Ok(secret_storage) => crate::account::token_state(secret_storage.read_credentials(uuid).await),
+                            Err(_) => AccountTokenState::Unknown,
+                        }
+                    };
+                    states.push((uuid, state));
+                }
+                _ = channel.send(states);
             },
             MessageToBackend::SetOpenGameOutputAfterLaunching { value } => {
                 self.config.write().modify(|config| {
                     config.open_game_output_when_launching = value;
                 });
             },
+            MessageToBackend::SetHideHeadOverlay { value } => {
+                let mut config = self.config.write();
+                if config.get().hide_head_overlay == value {
+                    return;
+                }
+                config.modify(|config| {
+                    config.hide_head_overlay = value;
+                });
+                drop(config);
+                self.regenerate_account_heads();
+            },
+            MessageToBackend::SetBackendSettings { settings } => {
+                *self.settings.write() = settings;
+                self.config.write().modify(|config| {
+                    config.settings = settings;
+                });
+                self.send.send(MessageToFrontend::BackendSettingsUpdated { settings });
+            },
+            MessageToBackend::SetLauncherUpdateCheck { value } => {
+                let mut config = self.config.write();
+                if config.get().disable_launcher_update_check == !value {
+                    return;
+                }
+                config.modify(|config| {
+                    config.disable_launcher_update_check = !value;
+                });
+                drop(config);
+                if value {
+                    self.check_launcher_update();
+                }
+            },
+            MessageToBackend::SetFileWatching { value } => {
+                let mut config = self.config.write();
+                if config.get().disable_file_watching == !value {
+                    return;
+                }
+                config.modify(|config| {
+                    config.disable_file_watching = !value;
+                });
+                drop(config);
+
+                let ids: Vec<_> = self.instance_state.read().instances.iter().map(|instance| instance.id).collect();
+                for id in ids {
+                    self.update_instance_file_watching(id);
+                }
+            },
+            MessageToBackend::DownloadLauncherUpdate { modal_action } => {
+                let tracker = ProgressTracker::new("Downloading launcher update".into(), self.send.clone());
+                modal_action.trackers.push(tracker.clone());
+                let http_client = self.http_client.clone();
+                tokio::task::spawn(async move {
+                    if let Err(error) = crate::launcher_update::download(&http_client, &tracker).await {
+                        tracker.set_finished(ProgressTrackerFinishType::Error);
+                        tracker.notify();
+                        log::error!("Unable to download launcher update: {}", error);
+                        modal_action.set_error_message(error.to_string().into());
+                    }
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::SetStopGracePeriod { secs } => {
+                self.config.write().modify(|config| {
+                    config.stop_grace_period_secs = Some(secs);
+                });
+            },
+            MessageToBackend::SetPortableCredentialsConsent { value } => {
+                self.config.write().modify(|config| {
+                    config.portable_credentials_consent = value;
+                });
+                if let Some(Ok(SecretStorage::File(storage))) = self.secret_storage.get()
+                    && let Err(error) = storage.set_save_to_file(value)
+                {
+                    log::error!("Unable to update saved credentials: {error}");
+                    self.send.send_error(format!("Unable to update saved credentials: {error}"));
+                }
+            },
+            MessageToBackend::SetProxy { mut proxy } => {
+                // Only a reference to the password is kept in the config
+                let password = proxy.password.take().filter(|password| !password.is_empty());
+                let result = match self.secret_storage().await {
+                    Ok(storage) => storage.write_secret(crate::proxy::PASSWORD_SECRET, password.as_deref()).await.map_err(|error| error.to_string()),
+                    Err(error) => Err(error.to_string()),
+                };
+                if let Err(error) = result {
+                    log::error!("Unable to save the proxy password: {error}");
+                    self.send.send_error(format!("Unable to save the proxy password: {error}"));
+                    return;
+                }
+                proxy.password_saved = password.is_some();
+                self.config.write().modify(|config| {
+                    config.proxy = proxy;
+                });
+            },
+            MessageToBackend::SetMirrors { mirrors } => {
+                *self.mirrors.write() = mirrors.clone();
+                self.config.write().modify(|config| {
+                    config.mirrors = mirrors;
+                });
+            },
+            MessageToBackend::SetMetadataCacheConfig { config } => {
+                self.meta.set_cache_config(config);
+                self.config.write().modify(|backend_config| {
+                    backend_config.metadata_cache = config;
+                });
+            },
+            MessageToBackend::GetMetadataCacheEntries { channel } => {
+                let meta = self.meta.clone();
+                let assets_index_dir = self.directories.assets_index_dir.clone();
+                let entries = tokio::task::spawn_blocking(move || meta.cache_entries(&assets_index_dir)).await;
+                _ = channel.send(entries.unwrap_or_default().into());
+            },
+            MessageToBackend::RefreshMetadataCacheEntry { kind, path, channel } => {
+                let result = match kind {
+                    MetadataCacheKind::MinecraftVersionManifest => self.meta.fetch_with_keepalive(&MinecraftVersionManifestMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::MojangJavaRuntimes => self.meta.fetch_with_keepalive(&MojangJavaRuntimesMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::FabricLoaderManifest => self.meta.fetch_with_keepalive(&FabricLoaderManifestMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::ForgeMavenManifest => self.meta.fetch_with_keepalive(&ForgeInstallerMavenMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::NeoforgeMavenManifest => self.meta.fetch_with_keepalive(&NeoforgeInstallerMavenMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::ModrinthCategoryTags => self.meta.fetch_with_keepalive(&ModrinthCategoryTagsMetadataItem, true).await.0.map(|_| ()),
+                    MetadataCacheKind::AssetsIndex => {
+                        // Assets indexes are only downloaded when launching, removing it makes the next launch download it again
+                        if path.parent() != Some(&*self.directories.assets_index_dir) {
+                            _ = channel.send(Err("Not an assets index".into()));
+                            return;
+                        }
+                        match tokio::fs::remove_file(&path).await {
+                            Ok(()) => Ok(()),
+                            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                            Err(error) => Err(MetaLoadError::Error(format!("Unable to remove {}: {}", path.display(), error).into())),
+                        }
+                    },
+                };
+                _ = channel.send(result.map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::GetDiskUsage { channel } => {
+                let directories = self.directories.clone();
+                tokio::task::spawn_blocking(move || {
+                    _ = channel.send(crate::storage::compute_disk_usage(&directories));
+                });
+            },
+            MessageToBackend::CleanupStorage { cleanup, preview, channel } => {
+                let result = self.cleanup_storage(cleanup, preview).await;
+                _ = channel.send(result.map_err(|error| format!("{error:#}").into()));
+            },
+            MessageToBackend::TestProxy { proxy, channel } => {
+                tokio::task::spawn(async move {
+                    let result = crate::proxy::test_connection(crate::backend::redirecting_http_client_builder(), &proxy).await;
+                    _ = channel.send(result.map_err(|error| format!("{error:#}").into()));
+                });
+            },
+            MessageToBackend::ExportInstanceMultiMC { id, path, modal_action } => {
+                let Some((name, dot_minecraft, configuration)) = self.instance_state.write().instances.get_mut(id).map(|instance| {
+                    (instance.name, instance.dot_minecraft_path.clone(), instance.configuration.get().clone())
+                }) else {
+                    modal_action.set_error_message("Can't export instance, unknown id".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                // Zipping the whole instance can take a while, other messages shouldn't wait for it
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    let loader_version = match configuration.preferred_loader_version {
+                        Some(loader_version) => Some(loader_version),
+                        None => state.latest_loader_version(&configuration).await,
+                    };
+
+                    let tracker = ProgressTracker::new(format!("Exporting {}", name).into(), state.send.clone());
+                    modal_action.trackers.push(tracker.clone());
+
+                    let result = tokio::task::spawn_blocking({
+                        let tracker = tracker.clone();
+                        move || crate::export::export_multimc(&path, &name, &configuration, loader_version, &dot_minecraft, &tracker)
+                    }).await.unwrap();
+
+                    match result {
+                        Ok(()) => {
+                            tracker.set_finished(ProgressTrackerFinishType::Normal);
+                            state.send.send_success(format!("Exported {}", name));
+                        },
+                        Err(err) => {
+                            tracker.set_finished(ProgressTrackerFinishType::Error);
+                            modal_action.set_error_message(format!("{}", err).into());
+                        },
+                    }
+                    tracker.notify();
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::ExportSharedProfile { id, path, modal_action } => {
+                let Some((name, dot_minecraft, configuration)) = self.instance_state.write().instances.get_mut(id).map(|instance| {
+                    (instance.name, instance.dot_minecraft_path.clone(), instance.configuration.get().clone())
+                }) else {
+                    modal_action.set_error_message("Can't export profile, unknown id".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                let result = tokio::task::spawn_blocking(move || {
+                    crate::export::export_shared_profile(&path, &name, &configuration, &dot_minecraft)
+                }).await.unwrap();
+
+                match result {
+                    Ok(skipped) if skipped.is_empty() => {
+                        self.send.send_success(format!("Exported the profile of {}", name));
+                    },
+                    Ok(skipped) => {
+                        self.send.send_warning(format!("Exported the profile of {}, files that weren't installed from Modrinth were left out: {}",
+                            name, skipped.join(", ")));
+                    },
+                    Err(err) => {
+                        modal_action.set_error_message(format!("{}", err).into());
+                    },
+                }
+                modal_action.set_finished();
+            },
+            MessageToBackend::ReadSharedProfile { path, channel } => {
+                let result = tokio::task::spawn_blocking(move || crate::export::read_shared_profile(&path)).await.unwrap();
+                _ = channel.send(result.map_err(|error| format!("{}", error).into()));
+            },
+            MessageToBackend::SetLauncherLock { secret, idle_lock_minutes } => {
+                let launcher_lock = secret.as_deref().map(|secret| crate::launcher_lock::create(secret, idle_lock_minutes));
+                self.send.send(crate::launcher_lock::create_update_message(launcher_lock.as_ref(), false));
+
+                self.config.write().modify(|config| {
+                    config.launcher_lock = launcher_lock;
+                });
+
+                // Saved portable credentials are encrypted with the lock secret
+                if self.directories.portable
+                    && let Ok(SecretStorage::File(storage)) = self.secret_storage().await
+                    && let Err(error) = storage.set_lock_secret(secret.as_deref().map(String::from)).await
+                {
+                    log::error!("Unable to update saved credentials: {error}");
+                    self.send.send_error(format!("Unable to update saved credentials: {error}"));
+                }
+            },
+            MessageToBackend::LockLauncher => {
+                let launcher_lock = self.config.write().get().launcher_lock.clone();
+                if launcher_lock.is_some() {
+                    self.launcher_locked.store(true, Ordering::SeqCst);
+                    self.send.send(crate::launcher_lock::create_update_message(launcher_lock.as_ref(), true));
+                }
+            },
+            MessageToBackend::UnlockLauncher { secret, channel } => {
+                let Some(launcher_lock) = self.config.write().get().launcher_lock.clone() else {
+                    self.launcher_locked.store(false, Ordering::SeqCst);
+                    _ = channel.send(UnlockResult::Unlocked);
+                    return;
+                };
+
+                let now = chrono::Utc::now().timestamp_millis();
+                if let Some(retry_after_secs) = crate::launcher_lock::retry_after_secs(&launcher_lock, now) {
+                    _ = channel.send(UnlockResult::Throttled { retry_after_secs });
+                    return;
+                }
+
+                let had_failed_attempts = launcher_lock.failed_attempts > 0;
+                let matches = tokio::task::spawn_blocking({
+                    let secret = secret.clone();
+                    move || crate::launcher_lock::verify(&launcher_lock, &secret)
+                }).await.unwrap_or(false);
+
+                let result = if matches {
+                    if had_failed_attempts {
+                        self.config.write().modify(|config| {
+                            if let Some(launcher_lock) = &mut config.launcher_lock {
+                                crate::launcher_lock::record_success(launcher_lock);
+                            }
+                        });
+                    }
+                    if self.directories.portable
+                        && let Ok(SecretStorage::File(storage)) = self.secret_storage().await
+                    {
+                        match storage.unlock(secret.to_string()).await {
+                            // The proxy password may have been saved with the credentials
+                            Ok(()) => self.load_proxy_password(),
+                            Err(error) => log::error!("Unable to read saved credentials: {error}"),
+                        }
+                    }
+                    self.launcher_locked.store(false, Ordering::SeqCst);
+                    let launcher_lock = self.config.write().get().launcher_lock.clone();
+                    self.send.send(crate::launcher_lock::create_update_message(launcher_lock.as_ref(), false));
+                    UnlockResult::Unlocked
+                } else {
+                    log::warn!("Failed attempt to unlock the launcher");
+                    // Saved with the lock so restarting the launcher doesn't reset the wait
+                    let now = chrono::Utc::now().timestamp_millis();
+                    let mut retry_after_secs = None;
+                    self.config.write().modify(|config| {
+                        if let Some(launcher_lock) = &mut config.launcher_lock {
+                            crate::launcher_lock::record_failure(launcher_lock, now);
+                            retry_after_secs = crate::launcher_lock::retry_after_secs(launcher_lock, now);
+                        }
+                    });
+                    match retry_after_secs {
+                        Some(retry_after_secs) => UnlockResult::Throttled { retry_after_secs },
+                        None => UnlockResult::Incorrect,
+                    }
+                };
+
+                _ = channel.send(result);
+            },
             MessageToBackend::CreateInstanceShortcut { id, path } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     let Ok(current_exe) = std::env::current_exe() else {
                         return;
                     };
 
-                    let args = &[
+                    let mut args = vec![
                         "--run-instance",
                         instance.name.as_str()
                     ];
-                    crate::shortcut::create_shortcut(path, &format!("Launch {}", instance.name), &current_exe, args);
+                    // The shortcut needs to find the same data if portable mode was enabled with the flag
+                    if self.directories.portable {
+                        args.push("--portable");
+                    }
+                    crate::shortcut::create_shortcut(path, &format!("Launch {}", instance.name), &current_exe, &args);
                 }
             },
         }
     }
 
-    pub async fn login_flow(&self, modal_action: &ModalAction, selected_account: Option<uuid::Uuid>) -> Option<(MinecraftProfileResponse, MinecraftAccessToken)> {
+    pub async fn login_flow(&self, modal_action: &ModalAction, selected_account: Option<uuid::Uuid>) -> Option<LoginFlowResult> {
         let mut credentials = if let Some(selected_account) = selected_account {
-            let secret_storage = match self.secret_storage.get_or_init(PlatformSecretStorage::new).await {
+            let secret_storage = match self.secret_storage().await {
                 Ok(secret_storage) => secret_storage,
                 Err(error) => {
                     modal_action.set_error_message(format!("Error initializing secret storage: {error}").into());
@@ -1037,6 +2149,9 @@ impl BackendState {
         let login_tracker = ProgressTracker::new(Arc::from("Logging in"), self.send.clone());
         modal_action.trackers.push(login_tracker.clone());
 
+        // Login may try to refresh the token, so keep the cached one around in case the servers can't be reached
+        let cached_access_token = credentials.fresh_access_token();
+
         let login_result = self.login(&mut credentials, &login_tracker, &modal_action).await;
 
         if matches!(login_result, Err(LoginError::CancelledByUser)) {
@@ -1044,7 +2159,7 @@ impl BackendState {
             return None;
         }
 
-        let secret_storage = match self.secret_storage.get_or_init(PlatformSecretStorage::new).await {
+        let secret_storage = match self.secret_storage().await {
             Ok(secret_storage) => secret_storage,
             Err(error) => {
                 modal_action.set_error_message(format!("Error initializing secret storage: {error}").into());
@@ -1060,6 +2175,21 @@ impl BackendState {
                 login_result
             },
             Err(ref err) => {
+                if selected_account.is_some() && err.is_connection_error() {
+                    log::warn!("Unable to reach authentication servers, falling back to offline mode: {}", err);
+                    login_tracker.set_title("Logging in (offline)".into());
+                    login_tracker.set_finished(ProgressTrackerFinishType::Normal);
+                    login_tracker.notify();
+
+                    // Tokens that were refreshed before the failure replace the old ones, which may no longer be valid
+                    if let Some(selected_account) = selected_account
+                        && let Err(error) = secret_storage.write_credentials(selected_account, &credentials).await
+                    {
+                        log::warn!("Unable to write credentials to keychain: {error}");
+                    }
+                    return Some(LoginFlowResult::Unreachable(cached_access_token));
+                }
+
                 if let Some(selected_account) = selected_account {
                     let _ = secret_storage.delete_credentials(selected_account).await;
                 }
@@ -1085,7 +2215,231 @@ impl BackendState {
             self.send.send_warning("Unable to write credentials to keychain. You might need to fully log in again next time");
         }
 
-        Some((profile, access_token))
+        Some(LoginFlowResult::LoggedIn(profile, access_token))
+    }
+
+    async fn verify_instance(&self, id: InstanceID, modal_action: ModalAction) {
+        let Some((dot_minecraft, configuration)) = self.instance_state.write().instances.get_mut(id)
+            .map(|instance| (instance.dot_minecraft_path.clone(), instance.configuration.get().clone())) else {
+            modal_action.set_error_message("Can't verify instance, unknown id".into());
+            return;
+        };
+
+        let verify_tracker = ProgressTracker::new("Verifying game files".into(), self.send.clone());
+        modal_action.trackers.push(verify_tracker.clone());
+
+        let result = self.launcher.verify_game_files(&self.redirecting_http_client, dot_minecraft, &configuration, &verify_tracker, &modal_action).await;
+
+        match &result {
+            Ok(repaired) if repaired.is_empty() => verify_tracker.set_title("All game files are intact".into()),
+            Ok(repaired) => {
+                verify_tracker.set_title(format!("Repaired {} game file(s)", repaired.len()).into());
+                for file in repaired.iter().take(MAX_LISTED_REPAIRED_FILES) {
+                    let tracker = ProgressTracker::new(file.clone(), self.send.clone());
+                    tracker.set_finished(ProgressTrackerFinishType::Normal);
+                    modal_action.trackers.push(tracker);
+                }
+                if repaired.len() > MAX_LISTED_REPAIRED_FILES {
+                    let more = format!("and {} more", repaired.len() - MAX_LISTED_REPAIRED_FILES);
+                    let tracker = ProgressTracker::new(more.into(), self.send.clone());
+                    tracker.set_finished(ProgressTrackerFinishType::Normal);
+                    modal_action.trackers.push(tracker);
+                }
+            },
+            Err(LaunchError::CancelledByUser) => {
+                self.send.send(MessageToFrontend::CloseModal);
+                return;
+            },
+            Err(err) => modal_action.set_error_message(format!("{}", err).into()),
+        }
+
+        verify_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
+        verify_tracker.notify();
+    }
+
+    async fn get_modpack_update_diff(&self, id: InstanceID, content_id: InstanceContentID) -> Result<ModpackUpdateDiff, Arc<str>> {
+        let (old_summary, root_path, dot_minecraft_path) = {
+            let mut instance_state = self.instance_state.write();
+            let Some(instance) = instance_state.instances.get_mut(id) else {
+                return Err("Unknown instance".into());
+            };
+            let Some((summary, _)) = instance.try_get_content(content_id) else {
+                return Err("Unknown modpack".into());
+            };
+            (summary.content_summary.clone(), instance.root_path.clone(), instance.dot_minecraft_path.clone())
+        };
+
+        let Some(ModUpdateAction::Modrinth { file, .. }) = self.mod_metadata_manager.updates.read().get(&old_summary.hash).cloned() else {
+            return Err("No update is available".into());
+        };
+
+        let bytes = self.http_client.get(&*file.url).send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| format!("Unable to download update: {error}"))?
+            .bytes().await
+            .map_err(|error| format!("Unable to download update: {error}"))?;
+
+        let mut hasher = Sha1::new();
+        hasher.update(&bytes);
+        if hex::encode(hasher.finalize()) != *file.hashes.sha1 {
+            return Err("The downloaded update doesn't match its hash".into());
+        }
+
+        let mod_metadata_manager = self.mod_metadata_manager.clone();
+        tokio::task::spawn_blocking(move || {
+            let Some(new_summary) = mod_metadata_manager.get_bytes(&bytes) else {
+                return Err("Unable to read the updated modpack".into());
+            };
+            let old_files = crate::modpack::pack_files(&old_summary.extra);
+            let new_files = crate::modpack::pack_files(&new_summary.extra);
+            let state = crate::modpack::ModpackState::read(&root_path);
+            Ok(crate::modpack::diff(&old_files, &new_files, &state, &dot_minecraft_path))
+        }).await.unwrap()
+    }
+
+    /// Finds the enabled mods that declare a minimum loader version above the one the instance is pinned to
+    async fn check_loader_version_requirement(&self, id: InstanceID) -> Option<LoaderVersionRequirement> {
+        let configuration = self.instance_state.write().instances.get_mut(id).map(|instance| instance.configuration.get().clone())?;
+        // Without a pinned version the latest one is used, which is assumed to satisfy every mod
+        let current = configuration.preferred_loader_version?;
+        let mods = self.clone().load_instance_content(id, ContentFolder::Mods).await?;
+
+        // The requirements were read along with the rest of the mod summary, so the jars aren't opened again
+        let loader = configuration.loader;
+        let minimums: Vec<(Arc<str>, Arc<str>)> = mods.iter()
+            .filter(|summary| summary.enabled)
+            .filter_map(|summary| {
+                let minimum = crate::loader_requirements::minimum_loader_version(&summary.content_summary.loader_requirements, loader)?;
+                Some((summary.content_summary.name.clone().unwrap_or_else(|| summary.filename.clone()), minimum.clone()))
+            })
+            .collect();
+
+        let minecraft_version = configuration.minecraft_version.as_str();
+        let unsatisfied: Vec<(Arc<str>, Arc<str>)> = minimums.into_iter()
+            .filter(|(_, minimum)| !crate::loader_requirements::satisfies(&current, minecraft_version, minimum))
+            .collect();
+        let minimum = unsatisfied.iter()
+            .map(|(_, minimum)| minimum)
+            .max_by(|a, b| crate::loader_requirements::compare_versions(a, b))?
+            .clone();
+
+        let suggested = self.oldest_loader_version_satisfying(&configuration, &minimum).await;
+
+        Some(LoaderVersionRequirement {
+            minimum: minimum.into(),
+            current,
+            suggested,
+            mods: unsatisfied.into_iter().map(|(name, _)| name).collect(),
+        })
+    }
+
+    /// Finds the enabled mods without a Modrinth version for the Minecraft version and loader of the instance
+    async fn check_version_compatibility(&self, id: InstanceID, version: Ustr) -> Result<VersionCompatibility, Arc<str>> {
+        let Some(loader) = self.instance_state.write().instances.get_mut(id).map(|instance| instance.configuration.get().loader) else {
+            return Err("Unknown instance".into());
+        };
+        let Some(mods) = self.clone().load_instance_content(id, ContentFolder::Mods).await else {
+            return Err("Unable to load mods".into());
+        };
+
+        let modrinth_loader = loader.as_modrinth_loader();
+        let params = &VersionUpdateParameters {
+            loaders: [modrinth_loader].into(),
+            game_versions: [version].into(),
+        };
+        let semaphore = &Semaphore::new(8);
+
+        let mut compatibility = VersionCompatibility::default();
+        let mut futures = Vec::new();
+        for summary in mods.iter().filter(|summary| summary.enabled) {
+            let name = summary.content_summary.name.clone().unwrap_or_else(|| summary.filename.clone());
+            if summary.content_source == ContentSource::Manual || modrinth_loader == ModrinthLoader::Unknown {
+                compatibility.unchecked.push(name);
+                continue;
+            }
+
+            let meta = &self.meta;
+            futures.push(async move {
+                let _permit = semaphore.acquire().await.unwrap();
+                let result = meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                    sha1: hex::encode(summary.content_summary.hash).into(),
+                    params: params.clone(),
+                }).await;
+                (name, result)
+            });
+        }
+
+        for (name, result) in futures::future::join_all(futures).await {
+            match result {
+                Ok(_) => {},
+                Err(MetaLoadError::NonOK(404)) => compatibility.incompatible.push(name),
+                Err(error) => {
+                    log::warn!("Unable to check if {name} supports {version}: {error}");
+                    compatibility.unchecked.push(name);
+                },
+            }
+        }
+
+        Ok(compatibility)
+    }
+
+    async fn cleanup_storage(&self, cleanup: StorageCleanup, preview: bool) -> anyhow::Result<CleanupResult> {
+        // A running game may be reading any of these files, or be in the middle of downloading them
+        if self.instance_state.read().instances.iter().any(|instance| instance.is_running()) {
+            anyhow::bail!("Close all running instances before cleaning up storage");
+        }
+
+        let directories = self.directories.clone();
+        let result = match cleanup {
+            StorageCleanup::UnusedAssets => {
+                let (_, versions) = self.instance_versions().await?;
+                let used_indexes: Vec<Ustr> = versions.iter().map(|version| version.assets).collect();
+                tokio::task::spawn_blocking(move || crate::storage::remove_unused_assets(&directories, &used_indexes, preview)).await??
+            },
+            StorageCleanup::OrphanedLibraries => {
+                let (instances, versions) = self.instance_versions().await?;
+                tokio::task::spawn_blocking(move || {
+                    let versions: Vec<_> = versions.iter().map(|version| &**version).collect();
+                    crate::storage::remove_orphaned_libraries(&directories, &instances, &versions, preview)
+                }).await?
+            },
+            StorageCleanup::ContentLibrary => {
+                tokio::task::spawn_blocking(move || crate::storage::clear_content_library(&directories, preview)).await?
+            },
+            StorageCleanup::ForgeProcessorCache => {
+                tokio::task::spawn_blocking(move || crate::storage::clear_forge_processor_cache(&directories, preview)).await?
+            },
+            StorageCleanup::OldLogs => {
+                tokio::task::spawn_blocking(move || crate::storage::remove_old_logs(&directories, preview)).await?
+            },
+        };
+
+        if !preview {
+            log::info!("Storage cleanup {:?} removed {} files ({} bytes)", cleanup, result.removed_files, result.freed_bytes);
+        }
+        Ok(result)
+    }
+
+    /// The Minecraft version and loader of every instance, along with the metadata of those versions
+    async fn instance_versions(&self) -> anyhow::Result<(Vec<(Ustr, Loader)>, Vec<Arc<MinecraftVersion>>)> {
+        let instances: Vec<(Ustr, Loader)> = self.instance_state.write().instances.iter_mut().map(|instance| {
+            let configuration = instance.configuration.get();
+            (configuration.minecraft_version, configuration.loader)
+        }).collect();
+
+        // Every version has to be known, otherwise files that are still needed would be removed
+        let manifest = self.meta.fetch(&MinecraftVersionManifestMetadataItem).await?;
+        let mut versions = Vec::new();
+        for minecraft_version in instances.iter().map(|(version, _)| *version).collect::<FxHashSet<_>>() {
+            if let Some((version, _)) = self.meta.fetch_local_version(minecraft_version).await? {
+                versions.push(version);
+                continue;
+            }
+            let link = crate::storage::manifest_version(&manifest, minecraft_version)?;
+            versions.push(self.meta.fetch(&MinecraftVersionMetadataItem(link)).await?);
+        }
+
+        Ok((instances, versions))
     }
 
     pub fn update_account_info_with_profile(&self, profile: &MinecraftProfileResponse) {
@@ -1202,7 +2556,7 @@ impl BackendState {
             }
         }
 
-        println!("Done downloading all metadata");
+        log::info!("Done downloading all metadata");
     }
 }
 
@@ -0,0 +1,103 @@
+use std::{path::{Path, PathBuf}, sync::Arc, time::SystemTime};
+
+use bridge::{message::{ConfigFileContents, ConfigFileEntry, ConfigFileWriteResult}, safe_path::SafePath};
+
+/// Files larger than this are listed but can't be opened in the editor
+const MAX_EDITABLE_SIZE: u64 = 4 * 1024 * 1024;
+const MAX_LISTED_FILES: usize = 10000;
+
+pub fn config_dir(dot_minecraft: &Path) -> PathBuf {
+    dot_minecraft.join("config")
+}
+
+pub fn list(config_dir: &Path) -> Arc<[ConfigFileEntry]> {
+    let mut entries = Vec::new();
+    list_recursive(config_dir, "", &mut entries, 0);
+    entries.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.path, &b.path));
+    entries.into()
+}
+
+fn list_recursive(directory: &Path, prefix: &str, entries: &mut Vec<ConfigFileEntry>, depth: usize) {
+    // Don't follow symlink loops forever
+    if depth > 16 {
+        return;
+    }
+
+    let Ok(read_dir) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in read_dir {
+        if entries.len() >= MAX_LISTED_FILES {
+            return;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        let Ok(metadata) = std::fs::metadata(entry.path()) else {
+            continue;
+        };
+
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+
+        if metadata.is_dir() {
+            list_recursive(&entry.path(), &path, entries, depth + 1);
+        } else if metadata.is_file() {
+            entries.push(ConfigFileEntry {
+                path: path.into(),
+                size: metadata.len(),
+            });
+        }
+    }
+}
+
+fn resolve(config_dir: &Path, path: &str) -> Result<PathBuf, Arc<str>> {
+    let Some(safe_path) = SafePath::new(path) else {
+        return Err("Invalid config file path".into());
+    };
+    Ok(safe_path.to_path(config_dir))
+}
+
+pub fn read(config_dir: &Path, path: &str) -> Result<ConfigFileContents, Arc<str>> {
+    let path = resolve(config_dir, path)?;
+
+    let metadata = std::fs::metadata(&path).map_err(|err| format!("Unable to read file: {err}"))?;
+    if metadata.len() > MAX_EDITABLE_SIZE {
+        return Err("File is too large to edit".into());
+    }
+
+    let bytes = std::fs::read(&path).map_err(|err| format!("Unable to read file: {err}"))?;
+    let contents = String::from_utf8(bytes).map_err(|_| "File isn't valid UTF-8 text")?;
+
+    Ok(ConfigFileContents {
+        contents: contents.into(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+pub fn write(config_dir: &Path, path: &str, contents: &str, expected_modified: Option<SystemTime>) -> ConfigFileWriteResult {
+    let path = match resolve(config_dir, path) {
+        Ok(path) => path,
+        Err(error) => return ConfigFileWriteResult::Error(error),
+    };
+
+    let current_modified = std::fs::metadata(&path).ok().and_then(|metadata| metadata.modified().ok());
+    if current_modified != expected_modified {
+        return ConfigFileWriteResult::Conflict { modified: current_modified };
+    }
+
+    if let Err(err) = crate::write_safe(&path, contents.as_bytes()) {
+        return ConfigFileWriteResult::Error(format!("Unable to save file: {err}").into());
+    }
+
+    ConfigFileWriteResult::Saved {
+        modified: std::fs::metadata(&path).ok().and_then(|metadata| metadata.modified().ok()),
+    }
+}
@@ -0,0 +1,306 @@
+use std::{path::{Path, PathBuf}, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::SystemTime};
+
+use base64::Engine;
+use bridge::instance::{AtomicContentUpdateStatus, ContentSummary, ContentType, ContentUpdateStatus};
+use parking_lot::RwLock;
+use schema::loader::Loader;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Bump when the way summaries are read from jars changes, so stale summaries are read again
+const CACHE_VERSION: u32 = 2;
+
+/// Remembers the hash and summary of content files across restarts, so that loading a large
+/// mods folder doesn't need to hash and open every archive again.
+///
+/// Files are looked up by path, size and modification time to find their hash, any change to
+/// the file invalidates the entry. Summaries are stored by hash, so they stay valid forever
+pub struct ContentCache {
+    file_hashes_path: Arc<Path>,
+    summaries_dir: PathBuf,
+    file_hashes: RwLock<FxHashMap<Arc<Path>, FileHashEntry>>,
+    file_hashes_dirty: AtomicBool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileHashes {
+    version: u32,
+    files: FxHashMap<Arc<Path>, FileHashEntry>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileHashEntry {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+    #[serde(with = "hex::serde")]
+    sha1: [u8; 20],
+}
+
+impl FileHashEntry {
+    fn matches(&self, metadata: &std::fs::Metadata) -> bool {
+        let Some((modified_secs, modified_nanos)) = modified_time(metadata) else {
+            return false;
+        };
+        self.size == metadata.len() && self.modified_secs == modified_secs && self.modified_nanos == modified_nanos
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSummaryFile {
+    version: u32,
+    summary: Option<CachedSummary>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSummary {
+    id: Option<Arc<str>>,
+    name: Option<Arc<str>>,
+    version_str: Arc<str>,
+    authors: Arc<str>,
+    description: Option<Arc<str>>,
+    homepage: Option<Arc<str>>,
+    png_icon: Option<String>,
+    #[serde(default)]
+    loader_requirements: Vec<(Loader, Arc<str>)>,
+    extra: CachedContentType,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum CachedContentType {
+    Fabric,
+    Forge,
+    NeoForge,
+    JavaModule,
+    ResourcePack,
+}
+
+fn modified_time(metadata: &std::fs::Metadata) -> Option<(u64, u32)> {
+    let modified = metadata.modified().ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    Some((modified.as_secs(), modified.subsec_nanos()))
+}
+
+/// Toggling content only appends or removes `.disabled`, which keeps the size and modification time,
+/// so both names share an entry
+fn file_key(path: &Path) -> Arc<Path> {
+    match path.to_str().and_then(|path| path.strip_suffix(".disabled")) {
+        Some(stripped) => Path::new(stripped).into(),
+        None => path.into(),
+    }
+}
+
+impl ContentCache {
+    pub fn load(content_meta_dir: &Path) -> Self {
+        let file_hashes_path: Arc<Path> = content_meta_dir.join("file_hashes.json").into();
+
+        let mut file_hashes = match crate::read_json::<FileHashes>(&file_hashes_path) {
+            Ok(file_hashes) if file_hashes.version == CACHE_VERSION => file_hashes.files,
+            Ok(_) => FxHashMap::default(),
+            Err(crate::IoOrSerializationError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => FxHashMap::default(),
+            Err(err) => {
+                log::warn!("Unable to read content file hashes, they will be computed again: {err}");
+                FxHashMap::default()
+            },
+        };
+
+        // Forget files that have been deleted since the last run
+        let previous_len = file_hashes.len();
+        file_hashes.retain(|path, _| path.exists() || path.with_added_extension("disabled").exists());
+        let removed_deleted = file_hashes.len() != previous_len;
+
+        Self {
+            file_hashes_path,
+            summaries_dir: content_meta_dir.join("summaries"),
+            file_hashes: RwLock::new(file_hashes),
+            file_hashes_dirty: AtomicBool::new(removed_deleted),
+        }
+    }
+
+    /// Returns the hash of the file if it hasn't changed since it was last hashed
+    pub fn get_file_hash(&self, path: &Path, metadata: &std::fs::Metadata) -> Option<[u8; 20]> {
+        let entry = *self.file_hashes.read().get(&file_key(path))?;
+        entry.matches(metadata).then_some(entry.sha1)
+    }
+
+    pub fn set_file_hash(&self, path: &Path, metadata: &std::fs::Metadata, sha1: [u8; 20]) {
+        let Some((modified_secs, modified_nanos)) = modified_time(metadata) else {
+            return;
+        };
+        let entry = FileHashEntry {
+            size: metadata.len(),
+            modified_secs,
+            modified_nanos,
+            sha1,
+        };
+        if self.file_hashes.write().insert(file_key(path), entry) != Some(entry) {
+            self.file_hashes_dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Queues the file hashes to be written if any have changed, called once a folder has finished loading
+    pub fn save_file_hashes(&self) {
+        if !self.file_hashes_dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        let file_hashes = self.file_hashes.read();
+        let data = serde_json::to_vec(&FileHashes {
+            version: CACHE_VERSION,
+            files: file_hashes.clone(),
+        });
+        drop(file_hashes);
+
+        match data {
+            Ok(data) => crate::write_behind::global().enqueue(self.file_hashes_path.clone(), data),
+            Err(err) => log::error!("Unable to serialize content file hashes: {err}"),
+        }
+    }
+
+    fn summary_path(&self, hash: &[u8; 20]) -> PathBuf {
+        let hash = hex::encode(hash);
+        self.summaries_dir.join(&hash[..2]).join(hash)
+    }
+
+    /// Returns the cached summary for the hash. The outer option is none if the file hasn't been
+    /// read before, the inner option is none if the file wasn't recognized as content
+    pub fn get_summary(&self, hash: [u8; 20]) -> Option<Option<Arc<ContentSummary>>> {
+        let file: CachedSummaryFile = crate::read_json(&self.summary_path(&hash)).ok()?;
+        if file.version != CACHE_VERSION {
+            return None;
+        }
+
+        let Some(summary) = file.summary else {
+            return Some(None);
+        };
+
+        let png_icon = summary.png_icon
+            .and_then(|icon| base64::engine::general_purpose::STANDARD.decode(icon).ok())
+            .map(Arc::from);
+        let extra = match summary.extra {
+            CachedContentType::Fabric => ContentType::Fabric,
+            CachedContentType::Forge => ContentType::Forge,
+            CachedContentType::NeoForge => ContentType::NeoForge,
+            CachedContentType::JavaModule => ContentType::JavaModule,
+            CachedContentType::ResourcePack => ContentType::ResourcePack,
+        };
+
+        Some(Some(Arc::new(ContentSummary {
+            id: summary.id,
+            hash,
+            name: summary.name,
+            version_str: summary.version_str,
+            authors: summary.authors,
+            description: summary.description,
+            homepage: summary.homepage,
+            png_icon,
+            loader_requirements: summary.loader_requirements.into(),
+            update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
+            extra,
+        })))
+    }
+
+    pub fn set_summary(&self, hash: [u8; 20], summary: Option<&ContentSummary>) {
+        let summary = match summary {
+            Some(summary) => {
+                let extra = match summary.extra {
+                    ContentType::Fabric => CachedContentType::Fabric,
+                    ContentType::Forge => CachedContentType::Forge,
+                    ContentType::NeoForge => CachedContentType::NeoForge,
+                    ContentType::JavaModule => CachedContentType::JavaModule,
+                    ContentType::ResourcePack => CachedContentType::ResourcePack,
+                    // Modpack summaries reference the summaries of their files, which may not be downloaded yet
+                    ContentType::ModrinthModpack { .. } => return,
+                };
+                Some(CachedSummary {
+                    id: summary.id.clone(),
+                    name: summary.name.clone(),
+                    version_str: summary.version_str.clone(),
+                    authors: summary.authors.clone(),
+                    description: summary.description.clone(),
+                    homepage: summary.homepage.clone(),
+                    png_icon: summary.png_icon.as_ref().map(|icon| base64::engine::general_purpose::STANDARD.encode(icon)),
+                    loader_requirements: summary.loader_requirements.to_vec(),
+                    extra,
+                })
+            },
+            None => None,
+        };
+
+        let file = CachedSummaryFile {
+            version: CACHE_VERSION,
+            summary,
+        };
+        // Summaries are set while loading content on the async runtime, so they're written behind like the file hashes
+        match serde_json::to_vec(&file) {
+            Ok(data) => crate::write_behind::global().enqueue(self.summary_path(&hash).into(), data),
+            Err(err) => log::error!("Unable to serialize content summary: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn summary(hash: [u8; 20]) -> ContentSummary {
+        ContentSummary {
+            id: Some("sodium".into()),
+            hash,
+            name: Some("Sodium".into()),
+            version_str: "0.6.0".into(),
+            authors: "jellysquid3".into(),
+            description: None,
+            homepage: Some("https://modrinth.com/mod/sodium".into()),
+            png_icon: Some(Arc::from(&[0x89, b'P', b'N', b'G'][..])),
+            loader_requirements: vec![(Loader::Fabric, Arc::from(">=0.16"))].into(),
+            update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
+            extra: ContentType::Fabric,
+        }
+    }
+
+    #[test]
+    fn summaries_round_trip() {
+        let dir = TempDir::new("content-cache");
+        let cache = ContentCache::load(&dir);
+
+        let hash = [1; 20];
+        let unrecognized = [2; 20];
+        assert!(cache.get_summary(hash).is_none());
+
+        cache.set_summary(hash, Some(&summary(hash)));
+        cache.set_summary(unrecognized, None);
+        crate::write_behind::global().flush();
+
+        let read = cache.get_summary(hash).unwrap().unwrap();
+        let expected = summary(hash);
+        assert_eq!(read.id, expected.id);
+        assert_eq!(read.name, expected.name);
+        assert_eq!(read.version_str, expected.version_str);
+        assert_eq!(read.homepage, expected.homepage);
+        assert_eq!(read.png_icon, expected.png_icon);
+        assert_eq!(read.loader_requirements, expected.loader_requirements);
+        assert!(matches!(read.extra, ContentType::Fabric));
+        assert!(cache.get_summary(unrecognized).unwrap().is_none());
+    }
+
+    #[test]
+    fn file_hashes_round_trip() {
+        let dir = TempDir::new("content-cache-hashes");
+        let file = dir.join("sodium.jar");
+        std::fs::write(&file, b"jar").unwrap();
+        let metadata = std::fs::metadata(&file).unwrap();
+
+        let cache = ContentCache::load(&dir);
+        cache.set_file_hash(&file, &metadata, [3; 20]);
+        cache.save_file_hashes();
+        crate::write_behind::global().flush();
+
+        let cache = ContentCache::load(&dir);
+        assert_eq!(cache.get_file_hash(&file, &metadata), Some([3; 20]));
+        // Disabling the file keeps its hash
+        assert_eq!(cache.get_file_hash(&dir.join("sodium.jar.disabled"), &metadata), Some([3; 20]));
+    }
+}
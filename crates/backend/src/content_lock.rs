@@ -0,0 +1,124 @@
+use std::{collections::BTreeMap, path::{Path, PathBuf}, sync::Arc};
+
+use schema::content::ContentSource;
+use serde::{Deserialize, Serialize};
+
+/// Records where the content installed through the launcher came from, so it can be downloaded again
+pub const CONTENT_LOCK_FILENAME: &str = "pandora.lock.json";
+
+/// Serializes read-modify-write cycles, installs into the same instance can finish at the same time
+static MODIFY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContentLock {
+    /// Files by their path in .minecraft, disabled files are tracked under their enabled name
+    #[serde(default)]
+    pub files: BTreeMap<String, LockedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    /// Hex-encoded SHA-1
+    pub sha1: Arc<str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<Arc<str>>,
+    #[serde(default)]
+    pub size: usize,
+    #[serde(default)]
+    pub source: ContentSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modrinth_version: Option<Arc<str>>,
+}
+
+impl ContentLock {
+    pub fn read(instance_dir: &Path) -> Self {
+        let Ok(bytes) = std::fs::read(instance_dir.join(CONTENT_LOCK_FILENAME)) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            log::warn!("Ignoring invalid {} in {:?}: {}", CONTENT_LOCK_FILENAME, instance_dir, error);
+            Self::default()
+        })
+    }
+
+    pub fn write(&self, instance_dir: &Path) -> std::io::Result<()> {
+        crate::write_safe(&instance_dir.join(CONTENT_LOCK_FILENAME), &serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Reads, modifies and writes the lock file on the blocking pool
+    pub async fn modify(instance_dir: PathBuf, modify: impl FnOnce(&mut Self) + Send + 'static) {
+        let _guard = MODIFY_LOCK.lock().await;
+        tokio::task::spawn_blocking(move || {
+            let mut lock = Self::read(&instance_dir);
+            modify(&mut lock);
+            if let Err(error) = lock.write(&instance_dir) {
+                log::error!("Unable to write {} in {:?}: {}", CONTENT_LOCK_FILENAME, instance_dir, error);
+            }
+        }).await.unwrap();
+    }
+
+    /// The key of a file in .minecraft, none if the file is outside of it
+    pub fn key(dot_minecraft: &Path, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(dot_minecraft).ok()?;
+        let mut key = relative.components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?
+            .join("/");
+        if let Some(enabled) = key.strip_suffix(".disabled") {
+            key.truncate(enabled.len());
+        }
+        (!key.is_empty()).then_some(key)
+    }
+
+    pub fn find_by_hash(&self, sha1: &str) -> Option<&LockedFile> {
+        self.files.values().find(|file| &*file.sha1 == sha1)
+    }
+}
+
+/// Where a locked file currently is, it may have been disabled since it was installed
+pub fn locate(dot_minecraft: &Path, key: &str) -> Option<PathBuf> {
+    let path = bridge::safe_path::SafePath::new(key)?.to_path(dot_minecraft);
+    if path.exists() {
+        return Some(path);
+    }
+    let mut disabled = path;
+    disabled.add_extension("disabled");
+    disabled.exists().then_some(disabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn keys_ignore_disabled_extension() {
+        let dot_minecraft = Path::new("/instances/a/.minecraft");
+        assert_eq!(ContentLock::key(dot_minecraft, &dot_minecraft.join("mods").join("sodium.jar")).as_deref(), Some("mods/sodium.jar"));
+        assert_eq!(ContentLock::key(dot_minecraft, &dot_minecraft.join("mods").join("sodium.jar.disabled")).as_deref(), Some("mods/sodium.jar"));
+        assert_eq!(ContentLock::key(dot_minecraft, Path::new("/instances/b/.minecraft/mods/sodium.jar")), None);
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let dir = TempDir::new("content-lock");
+
+        let file = LockedFile {
+            sha1: "da39a3ee5e6b4b0d3255bfef95601890afd80709".into(),
+            url: Some("https://cdn.modrinth.com/data/AANobbMI/versions/abc/sodium.jar".into()),
+            size: 1024,
+            source: ContentSource::ModrinthProject { project: "AANobbMI".into() },
+            modrinth_version: Some("abc".into()),
+        };
+        ContentLock::modify(dir.to_path_buf(), {
+            let file = file.clone();
+            move |lock| {
+                lock.files.insert("mods/sodium.jar".into(), file);
+            }
+        }).await;
+
+        let lock = ContentLock::read(&dir);
+        assert_eq!(lock.files.get("mods/sodium.jar"), Some(&file));
+        assert_eq!(lock.find_by_hash("da39a3ee5e6b4b0d3255bfef95601890afd80709"), Some(&file));
+    }
+}
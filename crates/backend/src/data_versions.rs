@@ -0,0 +1,94 @@
+/// The DataVersion of every release since 1.9, the first version to store it in level.dat, from oldest to newest
+const RELEASE_DATA_VERSIONS: &[(&str, i32)] = &[
+    ("1.9", 169),
+    ("1.9.1", 175),
+    ("1.9.2", 176),
+    ("1.9.3", 183),
+    ("1.9.4", 184),
+    ("1.10", 510),
+    ("1.10.1", 511),
+    ("1.10.2", 512),
+    ("1.11", 819),
+    ("1.11.1", 921),
+    ("1.11.2", 922),
+    ("1.12", 1139),
+    ("1.12.1", 1241),
+    ("1.12.2", 1343),
+    ("1.13", 1519),
+    ("1.13.1", 1628),
+    ("1.13.2", 1631),
+    ("1.14", 1952),
+    ("1.14.1", 1957),
+    ("1.14.2", 1963),
+    ("1.14.3", 1968),
+    ("1.14.4", 1976),
+    ("1.15", 2225),
+    ("1.15.1", 2227),
+    ("1.15.2", 2230),
+    ("1.16", 2566),
+    ("1.16.1", 2567),
+    ("1.16.2", 2578),
+    ("1.16.3", 2580),
+    ("1.16.4", 2584),
+    ("1.16.5", 2586),
+    ("1.17", 2724),
+    ("1.17.1", 2730),
+    ("1.18", 2860),
+    ("1.18.1", 2865),
+    ("1.18.2", 2975),
+    ("1.19", 3105),
+    ("1.19.1", 3117),
+    ("1.19.2", 3120),
+    ("1.19.3", 3218),
+    ("1.19.4", 3337),
+    ("1.20", 3463),
+    ("1.20.1", 3465),
+    ("1.20.2", 3578),
+    ("1.20.3", 3698),
+    ("1.20.4", 3700),
+    ("1.20.5", 3837),
+    ("1.20.6", 3839),
+    ("1.21", 3953),
+    ("1.21.1", 3955),
+    ("1.21.2", 4080),
+    ("1.21.3", 4082),
+    ("1.21.4", 4189),
+    ("1.21.5", 4325),
+    ("1.21.6", 4435),
+    ("1.21.7", 4438),
+    ("1.21.8", 4440),
+    ("1.21.9", 4554),
+    ("1.21.10", 4556),
+];
+
+/// The oldest release that can open a world with the DataVersion without upgrading it past what it was
+/// played in. Worlds from snapshots map to the release that followed them. None if the DataVersion is
+/// newer than every known release
+pub fn release_for_data_version(data_version: i32) -> Option<&'static str> {
+    RELEASE_DATA_VERSIONS.iter()
+        .find(|(_, release_data_version)| *release_data_version >= data_version)
+        .map(|(release, _)| *release)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn releases_match_exactly() {
+        assert_eq!(release_for_data_version(3465), Some("1.20.1"));
+        assert_eq!(release_for_data_version(169), Some("1.9"));
+    }
+
+    #[test]
+    fn snapshots_match_the_following_release() {
+        // 23w31a, a snapshot for 1.20.2
+        assert_eq!(release_for_data_version(3567), Some("1.20.2"));
+        assert_eq!(release_for_data_version(i32::MAX), None);
+    }
+
+    #[test]
+    fn table_is_ordered() {
+        assert!(RELEASE_DATA_VERSIONS.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    }
+}
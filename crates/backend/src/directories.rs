@@ -1,11 +1,16 @@
 use std::{path::{Path, PathBuf}, sync::Arc};
 
 pub struct LauncherDirectories {
+    /// All data is kept next to the executable, nothing should be stored elsewhere on the machine
+    pub portable: bool,
+
     pub instances_dir: Arc<Path>,
 
     pub synced_dir: Arc<Path>,
 
     pub metadata_dir: Arc<Path>,
+    /// Versions added by the user, in the same layout as the official launcher
+    pub local_versions_dir: Arc<Path>,
 
     pub assets_root_dir: Arc<Path>,
     pub assets_index_dir: Arc<Path>,
@@ -13,6 +18,7 @@ pub struct LauncherDirectories {
     pub virtual_legacy_assets_dir: Arc<Path>,
 
     pub libraries_dir: Arc<Path>,
+    pub forge_processor_cache_dir: Arc<Path>,
     pub log_configs_dir: Arc<Path>,
     pub runtime_base_dir: Arc<Path>,
 
@@ -25,15 +31,19 @@ pub struct LauncherDirectories {
     pub root_launcher_dir: Arc<Path>,
     pub config_json: Arc<Path>,
     pub accounts_json: Arc<Path>,
+    pub followed_projects_json: Arc<Path>,
+    pub account_heads_dir: Arc<Path>,
+    pub portable_credentials: Arc<Path>,
 }
 
 impl LauncherDirectories {
-    pub fn new(launcher_dir: PathBuf) -> Self {
+    pub fn new(launcher_dir: PathBuf, portable: bool) -> Self {
         let instances_dir = launcher_dir.join("instances");
 
         let synced_dir = launcher_dir.join("synced");
 
         let metadata_dir = launcher_dir.join("metadata");
+        let local_versions_dir = launcher_dir.join("versions");
 
         let assets_root_dir = launcher_dir.join("assets");
         let assets_index_dir = assets_root_dir.join("indexes");
@@ -41,6 +51,7 @@ impl LauncherDirectories {
         let virtual_legacy_assets_dir = assets_index_dir.join("virtual").join("legacy");
 
         let libraries_dir = launcher_dir.join("libraries");
+        let forge_processor_cache_dir = launcher_dir.join("forgeprocessors");
 
         let log_configs_dir = launcher_dir.join("logconfigs");
 
@@ -54,13 +65,19 @@ impl LauncherDirectories {
 
         let config_json = launcher_dir.join("config.json");
         let accounts_json = launcher_dir.join("accounts.json");
+        let followed_projects_json = launcher_dir.join("followed_projects.json");
+        let account_heads_dir = launcher_dir.join("heads");
+        let portable_credentials = launcher_dir.join("credentials.bin");
 
         Self {
+            portable,
+
             instances_dir: instances_dir.into(),
 
             synced_dir: synced_dir.into(),
 
             metadata_dir: metadata_dir.into(),
+            local_versions_dir: local_versions_dir.into(),
 
             assets_root_dir: assets_root_dir.into(),
             assets_index_dir: assets_index_dir.into(),
@@ -68,6 +85,7 @@ impl LauncherDirectories {
             virtual_legacy_assets_dir: virtual_legacy_assets_dir.into(),
 
             libraries_dir: libraries_dir.into(),
+            forge_processor_cache_dir: forge_processor_cache_dir.into(),
             log_configs_dir: log_configs_dir.into(),
             runtime_base_dir: runtime_base_dir.into(),
 
@@ -80,6 +98,9 @@ impl LauncherDirectories {
             root_launcher_dir: launcher_dir.into(),
             config_json: config_json.into(),
             accounts_json: accounts_json.into(),
+            followed_projects_json: followed_projects_json.into(),
+            account_heads_dir: account_heads_dir.into(),
+            portable_credentials: portable_credentials.into(),
         }
     }
 }
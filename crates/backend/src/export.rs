@@ -0,0 +1,271 @@
+use std::{io::{BufWriter, Write}, path::{Path, PathBuf}};
+
+use bridge::modal_action::ProgressTracker;
+use rustc_hash::FxHashSet;
+use schema::{content::ContentSource, instance::InstanceConfiguration, loader::Loader, shared_profile::{SharedProfile, SharedProfileContent}};
+use ustr::Ustr;
+
+use crate::{content_lock::{ContentLock, CONTENT_LOCK_FILENAME}, zip_writer::{ZipWriteError, ZipWriter}};
+
+/// Folders inside .minecraft that are never useful on another machine
+const SKIPPED_FOLDERS: &[&str] = &["logs", "crash-reports"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to perform I/O operation:\n{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to write zip:\n{0}")]
+    ZipWriteError(#[from] ZipWriteError),
+    #[error("Unable to determine {0} version for this instance")]
+    UnknownLoaderVersion(&'static str),
+    #[error("Failed to serialize profile:\n{0}")]
+    SerializeError(#[from] serde_json::Error),
+}
+
+/// Writes a MultiMC/Prism Launcher compatible zip containing instance.cfg, mmc-pack.json, the content lock and the .minecraft folder
+pub fn export_multimc(
+    output: &Path,
+    name: &str,
+    configuration: &InstanceConfiguration,
+    loader_version: Option<Ustr>,
+    dot_minecraft: &Path,
+    tracker: &ProgressTracker,
+) -> Result<(), ExportError> {
+    let mmc_pack = create_mmc_pack(configuration, loader_version)?;
+    let instance_cfg = create_instance_cfg(name, configuration);
+
+    let mut files = Vec::new();
+    let mut visited = FxHashSet::default();
+    collect_files(dot_minecraft, dot_minecraft, &mut files, &mut visited);
+
+    tracker.set_total(files.len() + 1);
+    tracker.notify();
+
+    let mut temp = output.to_path_buf();
+    temp.add_extension("tmp");
+
+    let result = (|| {
+        let file = std::fs::File::create(&temp)?;
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+
+        zip.add_bytes("instance.cfg", instance_cfg.as_bytes())?;
+        zip.add_bytes("mmc-pack.json", mmc_pack.as_bytes())?;
+        // Other launchers ignore it, but it lets the content be downloaded again after importing
+        if let Some(instance_dir) = dot_minecraft.parent()
+            && let Ok(content_lock) = std::fs::read(instance_dir.join(CONTENT_LOCK_FILENAME))
+        {
+            zip.add_bytes(CONTENT_LOCK_FILENAME, &content_lock)?;
+        }
+        tracker.add_count(1);
+        tracker.notify();
+
+        for (path, relative) in files {
+            match std::fs::File::open(&path) {
+                Ok(mut file) => zip.add_reader(&format!(".minecraft/{}", relative), &mut file)?,
+                Err(err) => log::warn!("Skipping {:?} while exporting: {}", path, err),
+            }
+            tracker.add_count(1);
+            tracker.notify();
+        }
+
+        zip.finish()?.flush()?;
+        std::fs::rename(&temp, output)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        _ = std::fs::remove_file(&temp);
+    }
+
+    result
+}
+
+/// Writes the instance's settings and the Modrinth versions of its enabled content to a small JSON file. Returns
+/// the names of the enabled files that weren't installed from Modrinth, these are left out
+pub fn export_shared_profile(output: &Path, name: &str, configuration: &InstanceConfiguration, dot_minecraft: &Path) -> Result<Vec<String>, ExportError> {
+    let (profile, skipped) = create_shared_profile(name, configuration, dot_minecraft);
+    crate::write_safe(output, &serde_json::to_vec_pretty(&profile)?)?;
+    Ok(skipped)
+}
+
+fn create_shared_profile(name: &str, configuration: &InstanceConfiguration, dot_minecraft: &Path) -> (SharedProfile, Vec<String>) {
+    let content_lock = dot_minecraft.parent().map(ContentLock::read).unwrap_or_default();
+
+    let mut content = Vec::new();
+    let mut included = FxHashSet::default();
+    for (key, file) in &content_lock.files {
+        let ContentSource::ModrinthProject { project } = &file.source else {
+            continue;
+        };
+        let Some(version_id) = &file.modrinth_version else {
+            continue;
+        };
+        if !SharedProfileContent::is_content_path(key) {
+            continue;
+        }
+        // Disabled files aren't shared
+        let Some(path) = bridge::safe_path::SafePath::new(key).map(|path| path.to_path(dot_minecraft)) else {
+            continue;
+        };
+        if !path.is_file() {
+            continue;
+        }
+
+        included.insert(key.as_str());
+        content.push(SharedProfileContent {
+            path: key.as_str().into(),
+            project_id: project.clone(),
+            version_id: version_id.clone(),
+        });
+    }
+
+    let mut skipped = Vec::new();
+    for folder in SharedProfileContent::FOLDERS {
+        let Ok(read_dir) = std::fs::read_dir(dot_minecraft.join(folder)) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name();
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_file()) || file_name.to_string_lossy().ends_with(".disabled") {
+                continue;
+            }
+            let Some(key) = ContentLock::key(dot_minecraft, &entry.path()) else {
+                continue;
+            };
+            if !included.contains(key.as_str()) {
+                skipped.push(file_name.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    let profile = SharedProfile {
+        format_version: SharedProfile::FORMAT_VERSION,
+        name: name.into(),
+        minecraft_version: configuration.minecraft_version,
+        loader: configuration.loader,
+        loader_version: configuration.preferred_loader_version,
+        memory: configuration.memory.filter(|memory| memory.enabled),
+        jvm_flags: configuration.jvm_flags.clone().filter(|jvm_flags| jvm_flags.enabled),
+        content,
+    };
+
+    (profile, skipped)
+}
+
+/// Reads a profile exported with `export_shared_profile`, possibly on another machine
+pub fn read_shared_profile(path: &Path) -> anyhow::Result<SharedProfile> {
+    let bytes = std::fs::read(path)?;
+    let profile: SharedProfile = serde_json::from_slice(&bytes)?;
+    if profile.format_version > SharedProfile::FORMAT_VERSION {
+        anyhow::bail!("The profile was exported by a newer version of the launcher");
+    }
+    Ok(profile)
+}
+
+fn collect_files(root: &Path, directory: &Path, files: &mut Vec<(PathBuf, String)>, visited: &mut FxHashSet<PathBuf>) {
+    // Synced folders are symlinks, canonicalize to avoid exporting the same folder through a loop
+    let Ok(canonical) = directory.canonicalize() else {
+        return;
+    };
+    if !visited.insert(canonical) {
+        return;
+    }
+
+    let Ok(read_dir) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in read_dir {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        if metadata.is_dir() {
+            if directory == root && SKIPPED_FOLDERS.contains(&relative.as_str()) {
+                continue;
+            }
+            collect_files(root, &path, files, visited);
+        } else if metadata.is_file() {
+            files.push((path, relative));
+        }
+    }
+}
+
+fn create_instance_cfg(name: &str, configuration: &InstanceConfiguration) -> String {
+    let mut cfg = String::new();
+    cfg.push_str("[General]\n");
+    cfg.push_str("ConfigVersion=1.2\n");
+    cfg.push_str("InstanceType=OneSix\n");
+    cfg.push_str(&format!("name={}\n", name.replace('\n', " ")));
+
+    if let Some(memory) = &configuration.memory && memory.enabled {
+        cfg.push_str("OverrideMemory=true\n");
+        cfg.push_str(&format!("MinMemAlloc={}\n", memory.min));
+        cfg.push_str(&format!("MaxMemAlloc={}\n", memory.max.max(memory.min)));
+    }
+    if let Some(jvm_flags) = &configuration.jvm_flags && jvm_flags.enabled {
+        cfg.push_str("OverrideJavaArgs=true\n");
+        cfg.push_str(&format!("JvmArgs={}\n", jvm_flags.flags.replace('\n', " ")));
+    }
+    // The Java path is specific to this machine, the launcher importing the instance picks its own Java
+
+    cfg
+}
+
+fn create_mmc_pack(configuration: &InstanceConfiguration, loader_version: Option<Ustr>) -> Result<String, ExportError> {
+    let minecraft_version = configuration.minecraft_version.as_str();
+
+    let mut components = vec![serde_json::json!({
+        "uid": "net.minecraft",
+        "version": minecraft_version,
+        "important": true,
+    })];
+
+    match configuration.loader {
+        Loader::Vanilla | Loader::Unknown => {},
+        Loader::Fabric => {
+            let loader_version = loader_version.ok_or(ExportError::UnknownLoaderVersion("Fabric"))?;
+            components.push(serde_json::json!({
+                "uid": "net.fabricmc.intermediary",
+                "version": minecraft_version,
+                "dependencyOnly": true,
+            }));
+            components.push(serde_json::json!({
+                "uid": "net.fabricmc.fabric-loader",
+                "version": loader_version.as_str(),
+            }));
+        },
+        Loader::Forge => {
+            let loader_version = loader_version.ok_or(ExportError::UnknownLoaderVersion("Forge"))?;
+            // Forge maven versions are prefixed with the Minecraft version, MultiMC expects just the Forge version
+            let forge_version = loader_version.as_str().strip_prefix(&format!("{}-", minecraft_version))
+                .unwrap_or(loader_version.as_str());
+            components.push(serde_json::json!({
+                "uid": "net.minecraftforge",
+                "version": forge_version,
+            }));
+        },
+        Loader::NeoForge => {
+            let loader_version = loader_version.ok_or(ExportError::UnknownLoaderVersion("NeoForge"))?;
+            components.push(serde_json::json!({
+                "uid": "net.neoforged",
+                "version": loader_version.as_str(),
+            }));
+        },
+    }
+
+    let pack = serde_json::json!({
+        "components": components,
+        "formatVersion": 1,
+    });
+
+    Ok(serde_json::to_string_pretty(&pack).unwrap())
+}
@@ -0,0 +1,108 @@
+use std::{ffi::OsString, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+/// Files a Forge/NeoForge installer processor that ran successfully was given, both what it read and what
+/// it wrote. Installing the same loader version again skips the processor while every file still has the
+/// recorded hash
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CachedFiles {
+    files: Vec<CachedFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    path: PathBuf,
+    sha1: String,
+}
+
+/// Identifies a processor run by the installer it comes from and the arguments it's given.
+/// Everything a processor reads is either in the installer or passed as an argument
+pub fn key(installer_hash: &[u8; 20], jar: &str, args: &[OsString]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(installer_hash);
+    hasher.update(jar.as_bytes());
+    for arg in args {
+        hasher.update([0]);
+        hasher.update(arg.as_encoded_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+pub fn is_cached(cache_dir: &Path, key: &str) -> bool {
+    let Ok(bytes) = std::fs::read(cache_dir.join(format!("{}.json", key))) else {
+        return false;
+    };
+    let Ok(cached) = serde_json::from_slice::<CachedFiles>(&bytes) else {
+        return false;
+    };
+
+    // A processor that wasn't given any files can't be checked, so it always runs
+    !cached.files.is_empty() && cached.files.iter().all(|file| {
+        let mut expected_hash = [0u8; 20];
+        hex::decode_to_slice(&file.sha1, &mut expected_hash).is_ok()
+            && crate::check_sha1_hash(&file.path, expected_hash).unwrap_or(false)
+    })
+}
+
+/// Records the hashes of the files among the arguments after the processor has run
+pub fn store(cache_dir: &Path, key: &str, args: &[OsString]) {
+    let mut cached = CachedFiles::default();
+    for arg in args {
+        let path = Path::new(arg);
+        if !path.is_file() {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(path) else {
+            continue;
+        };
+        cached.files.push(CachedFile {
+            path: path.to_path_buf(),
+            sha1: hex::encode(Sha1::digest(&bytes)),
+        });
+    }
+
+    let result = serde_json::to_vec(&cached).map_err(std::io::Error::from)
+        .and_then(|bytes| crate::write_safe(&cache_dir.join(format!("{}.json", key)), &bytes));
+    if let Err(error) = result {
+        log::warn!("Unable to cache forge processor run: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn changed_files_invalidate_cache() {
+        let dir = TempDir::new("forge-processors");
+        let cache_dir = dir.join("cache");
+
+        let input = dir.join("input.jar");
+        std::fs::write(&input, b"input").unwrap();
+        let output = dir.join("output.jar");
+        std::fs::write(&output, b"output").unwrap();
+
+        let args = [OsString::from("--input"), input.clone().into_os_string(), OsString::from("--output"), output.clone().into_os_string()];
+        let key = key(&[1; 20], "net.minecraftforge:binarypatcher:1.0", &args);
+        assert!(!is_cached(&cache_dir, &key));
+
+        store(&cache_dir, &key, &args);
+        assert!(is_cached(&cache_dir, &key));
+
+        std::fs::write(&output, b"changed").unwrap();
+        assert!(!is_cached(&cache_dir, &key));
+
+        store(&cache_dir, &key, &args);
+        std::fs::write(&input, b"changed").unwrap();
+        assert!(!is_cached(&cache_dir, &key));
+    }
+
+    #[test]
+    fn key_depends_on_installer() {
+        let args = [OsString::from("--task"), OsString::from("MCP_DATA")];
+        assert_ne!(key(&[1; 20], "installertools", &args), key(&[2; 20], "installertools", &args));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::message::GameOptions;
+
+const RENDER_DISTANCE: &str = "renderDistance";
+const FULLSCREEN: &str = "fullscreen";
+const GUI_SCALE: &str = "guiScale";
+const LANGUAGE: &str = "lang";
+const RESOURCE_PACKS: &str = "resourcePacks";
+
+pub fn options_path(dot_minecraft: &Path) -> PathBuf {
+    dot_minecraft.join("options.txt")
+}
+
+pub fn parse(contents: &str) -> GameOptions {
+    let mut options = GameOptions::default();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            RENDER_DISTANCE => options.render_distance = value.parse().ok(),
+            FULLSCREEN => options.fullscreen = value.parse().ok(),
+            GUI_SCALE => options.gui_scale = value.parse().ok(),
+            LANGUAGE => options.language = Some(value.into()),
+            _ => {},
+        }
+    }
+
+    options
+}
+
+/// Replaces the values of the changed keys, keeping every other line (including unknown keys) as is.
+/// Keys that don't exist yet are appended
+pub fn apply(contents: &str, changes: &GameOptions) -> String {
+    let mut remaining: Vec<(&str, String)> = Vec::new();
+    if let Some(render_distance) = changes.render_distance {
+        remaining.push((RENDER_DISTANCE, render_distance.to_string()));
+    }
+    if let Some(fullscreen) = changes.fullscreen {
+        remaining.push((FULLSCREEN, fullscreen.to_string()));
+    }
+    if let Some(gui_scale) = changes.gui_scale {
+        remaining.push((GUI_SCALE, gui_scale.to_string()));
+    }
+    if let Some(language) = &changes.language {
+        remaining.push((LANGUAGE, language.to_string()));
+    }
+    apply_values(contents, remaining)
+}
+
+/// Enables the resource packs from the resourcepacks folder on top of the ones that are already enabled
+pub fn add_resource_packs(contents: &str, filenames: &[String]) -> String {
+    let mut enabled: Vec<String> = contents.lines()
+        .find_map(|line| line.strip_prefix(RESOURCE_PACKS).and_then(|rest| rest.strip_prefix(':')))
+        .and_then(|value| serde_json::from_str(value.trim()).ok())
+        .unwrap_or_else(|| vec!["vanilla".to_string()]);
+
+    for filename in filenames {
+        let pack = format!("file/{filename}");
+        if !enabled.contains(&pack) {
+            enabled.push(pack);
+        }
+    }
+
+    apply_values(contents, vec![(RESOURCE_PACKS, serde_json::to_string(&enabled).unwrap())])
+}
+
+fn apply_values(contents: &str, mut remaining: Vec<(&str, String)>) -> String {
+    let newline = if contents.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut output = String::with_capacity(contents.len() + 64);
+    for line in contents.split_inclusive('\n') {
+        let (text, ending) = match line.strip_suffix("\r\n") {
+            Some(text) => (text, "\r\n"),
+            None => match line.strip_suffix('\n') {
+                Some(text) => (text, "\n"),
+                None => (line, ""),
+            },
+        };
+
+        let changed = text.split_once(':').and_then(|(key, _)| {
+            remaining.iter().position(|(changed_key, _)| *changed_key == key)
+        });
+
+        if let Some(index) = changed {
+            let (key, value) = remaining.remove(index);
+            output.push_str(key);
+            output.push(':');
+            output.push_str(&value);
+        } else {
+            output.push_str(text);
+        }
+        output.push_str(ending);
+    }
+
+    if !remaining.is_empty() && !output.is_empty() && !output.ends_with('\n') {
+        output.push_str(newline);
+    }
+    for (key, value) in remaining {
+        output.push_str(key);
+        output.push(':');
+        output.push_str(&value);
+        output.push_str(newline);
+    }
+
+    output
+}
+
+pub fn read(path: &Path) -> Result<GameOptions, Arc<str>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(parse(&contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(GameOptions::default()),
+        Err(err) => Err(format!("Unable to read options.txt: {err}").into()),
+    }
+}
+
+pub fn write(path: &Path, changes: &GameOptions) -> Result<GameOptions, Arc<str>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("Unable to read options.txt: {err}").into()),
+    };
+
+    let new_contents = apply(&contents, changes);
+    if new_contents != contents {
+        crate::write_safe(path, new_contents.as_bytes()).map_err(|err| format!("Unable to write options.txt: {err}"))?;
+    }
+
+    Ok(parse(&new_contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: &str = "version:3955\nrenderDistance:12\nlang:en_us\nkey_key.jump:key.keyboard.space\nguiScale:0\n";
+
+    #[test]
+    fn parses_known_keys() {
+        let options = parse(OPTIONS);
+        assert_eq!(options.render_distance, Some(12));
+        assert_eq!(options.fullscreen, None);
+        assert_eq!(options.gui_scale, Some(0));
+        assert_eq!(options.language.as_deref(), Some("en_us"));
+    }
+
+    #[test]
+    fn only_changed_keys_are_written() {
+        let changes = GameOptions {
+            render_distance: Some(8),
+            fullscreen: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            apply(OPTIONS, &changes),
+            "version:3955\nrenderDistance:8\nlang:en_us\nkey_key.jump:key.keyboard.space\nguiScale:0\nfullscreen:true\n"
+        );
+        assert_eq!(apply(OPTIONS, &GameOptions::default()), OPTIONS);
+    }
+
+    #[test]
+    fn keeps_line_endings() {
+        let changes = GameOptions {
+            gui_scale: Some(2),
+            language: Some("de_de".into()),
+            ..Default::default()
+        };
+        assert_eq!(apply("lang:en_us\r\nversion:3955", &changes), "lang:de_de\r\nversion:3955\r\nguiScale:2\r\n");
+        assert_eq!(apply("", &changes), "guiScale:2\nlang:de_de\n");
+    }
+
+    #[test]
+    fn resource_packs_are_added_on_top() {
+        let packs = ["Faithful.zip".to_string(), "old.zip".to_string()];
+        assert_eq!(
+            add_resource_packs("", &packs),
+            "resourcePacks:[\"vanilla\",\"file/Faithful.zip\",\"file/old.zip\"]\n"
+        );
+        assert_eq!(
+            add_resource_packs("lang:en_us\nresourcePacks:[\"vanilla\",\"file/old.zip\"]\n", &packs),
+            "lang:en_us\nresourcePacks:[\"vanilla\",\"file/old.zip\",\"file/Faithful.zip\"]\n"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::OnceLock;
+
+use bridge::message::{DetectedGpus, GpuInfo, GpuVendor};
+
+/// GPUs don't come and go while the launcher is running, so they're only detected once
+pub fn detected() -> &'static DetectedGpus {
+    static DETECTED: OnceLock<DetectedGpus> = OnceLock::new();
+    DETECTED.get_or_init(detect)
+}
+
+/// Lists the GPUs known to the kernel. Only implemented on Linux, the only platform where the launcher picks the GPU
+#[cfg(target_os = "linux")]
+fn detect() -> DetectedGpus {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return DetectedGpus::default();
+    };
+
+    let mut gpus = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        // Connectors like card0-HDMI-A-1 belong to the card they're named after
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+        if let Some(gpu) = read_gpu(&entry.path().join("device")) {
+            gpus.push(gpu);
+        }
+    }
+
+    gpus.sort_by(|a, b| a.pci_slot.cmp(&b.pci_slot));
+    gpus.dedup_by(|a, b| a.pci_slot == b.pci_slot);
+
+    let offload = offload_target(&gpus);
+    DetectedGpus { gpus, offload }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect() -> DetectedGpus {
+    DetectedGpus::default()
+}
+
+/// Environment variables that make the game render on the discrete GPU, empty if there's no other GPU to offload to
+pub fn discrete_gpu_env() -> Vec<(&'static str, String)> {
+    let Some(gpu) = detected().offload_gpu() else {
+        log::warn!("Launching on the discrete GPU is enabled, but no second GPU was found");
+        return Vec::new();
+    };
+
+    log::info!("Offloading rendering to {} ({})", gpu.name, gpu.pci_slot);
+    offload_env(gpu)
+}
+
+#[cfg(target_os = "linux")]
+fn read_gpu(device: &std::path::Path) -> Option<GpuInfo> {
+    let read = |file: &str| std::fs::read_to_string(device.join(file)).ok().map(|contents| contents.trim().to_string());
+
+    let uevent = read("uevent")?;
+    let mut driver = None;
+    let mut pci_slot = None;
+    for line in uevent.lines() {
+        if let Some(value) = line.strip_prefix("DRIVER=") {
+            driver = Some(value);
+        } else if let Some(value) = line.strip_prefix("PCI_SLOT_NAME=") {
+            pci_slot = Some(value);
+        }
+    }
+    // Devices that aren't on the PCI bus, e.g. simpledrm, can't be offloaded to
+    let pci_slot = pci_slot?;
+
+    let vendor = match read("vendor").as_deref() {
+        Some("0x10de") => GpuVendor::Nvidia,
+        Some("0x1002") => GpuVendor::Amd,
+        Some("0x8086") => GpuVendor::Intel,
+        _ => GpuVendor::Other,
+    };
+
+    let name = nvidia_model(pci_slot)
+        .or_else(|| read("product_name").filter(|name| !name.is_empty()))
+        .unwrap_or_else(|| format!("{} GPU", vendor_name(vendor)));
+
+    Some(GpuInfo {
+        name: name.into(),
+        vendor,
+        driver: driver.unwrap_or("unknown").into(),
+        pci_slot: pci_slot.into(),
+        boot_vga: read("boot_vga").as_deref() == Some("1"),
+    })
+}
+
+/// The proprietary NVIDIA driver is the only one that reports the model without a PCI ID database
+#[cfg(target_os = "linux")]
+fn nvidia_model(pci_slot: &str) -> Option<String> {
+    let information = std::fs::read_to_string(std::path::Path::new("/proc/driver/nvidia/gpus").join(pci_slot).join("information")).ok()?;
+    information.lines()
+        .find_map(|line| line.strip_prefix("Model:"))
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn vendor_name(vendor: GpuVendor) -> &'static str {
+    match vendor {
+        GpuVendor::Nvidia => "NVIDIA",
+        GpuVendor::Amd => "AMD",
+        GpuVendor::Intel => "Intel",
+        GpuVendor::Other => "Unknown",
+    }
+}
+
+/// Prefers a GPU from a vendor that only makes discrete GPUs over one that isn't used by default
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn offload_target(gpus: &[GpuInfo]) -> Option<usize> {
+    if gpus.len() < 2 {
+        return None;
+    }
+
+    gpus.iter().position(|gpu| !gpu.boot_vga && gpu.vendor != GpuVendor::Intel)
+        .or_else(|| gpus.iter().position(|gpu| !gpu.boot_vga))
+}
+
+fn offload_env(gpu: &GpuInfo) -> Vec<(&'static str, String)> {
+    if &*gpu.driver == "nvidia" {
+        // The proprietary driver isn't part of Mesa, so it's selected as the GLX and Vulkan vendor instead
+        vec![
+            ("__NV_PRIME_RENDER_OFFLOAD", "1".to_string()),
+            ("__GLX_VENDOR_LIBRARY_NAME", "nvidia".to_string()),
+            ("__VK_LAYER_NV_optimus", "NVIDIA_only".to_string()),
+        ]
+    } else {
+        // Mesa takes the PCI address with every separator replaced by an underscore
+        vec![("DRI_PRIME", format!("pci-{}", gpu.pci_slot.replace([':', '.'], "_")))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(vendor: GpuVendor, driver: &str, pci_slot: &str, boot_vga: bool) -> GpuInfo {
+        GpuInfo {
+            name: "GPU".into(),
+            vendor,
+            driver: driver.into(),
+            pci_slot: pci_slot.into(),
+            boot_vga,
+        }
+    }
+
+    #[test]
+    fn single_gpu_is_not_offloaded() {
+        let gpus = [gpu(GpuVendor::Amd, "amdgpu", "0000:03:00.0", true)];
+        assert_eq!(offload_target(&gpus), None);
+    }
+
+    #[test]
+    fn discrete_gpu_is_preferred() {
+        let gpus = [
+            gpu(GpuVendor::Intel, "i915", "0000:00:02.0", true),
+            gpu(GpuVendor::Intel, "i915", "0000:00:03.0", false),
+            gpu(GpuVendor::Nvidia, "nvidia", "0000:01:00.0", false),
+        ];
+        assert_eq!(offload_target(&gpus), Some(2));
+    }
+
+    #[test]
+    fn falls_back_to_gpu_that_isnt_used_by_default() {
+        let gpus = [
+            gpu(GpuVendor::Intel, "i915", "0000:00:02.0", true),
+            gpu(GpuVendor::Intel, "xe", "0000:03:00.0", false),
+        ];
+        assert_eq!(offload_target(&gpus), Some(1));
+    }
+
+    #[test]
+    fn mesa_offload_selects_pci_address() {
+        let env = offload_env(&gpu(GpuVendor::Amd, "amdgpu", "0000:03:00.0", false));
+        assert_eq!(env, vec![("DRI_PRIME", "pci-0000_03_00_0".to_string())]);
+    }
+
+    #[test]
+    fn nvidia_offload_uses_glvnd() {
+        let env = offload_env(&gpu(GpuVendor::Nvidia, "nvidia", "0000:01:00.0", false));
+        assert!(env.contains(&("__NV_PRIME_RENDER_OFFLOAD", "1".to_string())));
+        assert!(env.contains(&("__GLX_VENDOR_LIBRARY_NAME", "nvidia".to_string())));
+    }
+}
@@ -0,0 +1,31 @@
+/// Asks the game to close on its own, the process is force killed later if it doesn't.
+/// SIGTERM lets the JVM run its shutdown hooks instead of dying immediately
+#[cfg(unix)]
+pub fn request_stop(pid: u32) -> std::io::Result<()> {
+    let pid = libc::pid_t::try_from(pid).map_err(std::io::Error::other)?;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Asks the game to close on its own, the process is force killed later if it doesn't.
+/// Without /F, taskkill sends WM_CLOSE to the game window, the same as clicking its close button
+#[cfg(windows)]
+pub fn request_stop(pid: u32) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let status = std::process::Command::new("taskkill")
+        .arg("/PID")
+        .arg(pid.to_string())
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("taskkill exited with {}", status)))
+    }
+}
@@ -1,14 +1,14 @@
-use std::{ffi::{OsStr, OsString}, io::Write, path::{Path, PathBuf}, sync::Arc};
+use std::{ffi::{OsStr, OsString}, io::Write, path::{Component, Path, PathBuf}, sync::Arc};
 
 use bridge::{
-    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{ContentType, ContentSummary}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallFileState, InstallQueueID, InstallTarget}, instance::{ContentType, ContentSummary, InstanceID}, modal_action::{BackgroundTaskKind, ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
 };
 use reqwest::StatusCode;
-use schema::{content::ContentSource, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectVersionsRequest}};
+use schema::{content::ContentSource, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthVersionStatus, ModrinthVersionType}};
 use sha1::{Digest, Sha1};
 use tokio::io::AsyncWriteExt;
 
-use crate::{lockfile::Lockfile, metadata::{items::{MinecraftVersionManifestMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthVersionMetadataItem}, manager::MetaLoadError}, BackendState};
+use crate::{content_lock::{ContentLock, LockedFile}, install_queue::QueuedFile, instance::ContentFolder, lockfile::Lockfile, metadata::{items::{MinecraftVersionManifestMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthVersionMetadataItem}, manager::MetaLoadError}, BackendState};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ContentInstallError {
@@ -32,17 +32,27 @@ pub enum ContentInstallError {
     IoError(#[from] std::io::Error),
     #[error("Failed to load metadata:\n{0}")]
     MetaLoadError(#[from] MetaLoadError),
+    #[error("Unable to install into world {0}, the world doesn't exist")]
+    UnknownWorld(Arc<str>),
+    #[error("Unable to install to {0}, content can only be installed into mods, resourcepacks, shaderpacks or a world's resources.zip")]
+    DisallowedPath(Arc<str>),
     #[error("Mismatched project id for version {0}, expected {1} got {2}")]
     MismatchedProjectIdForVersion(Arc<str>, Arc<str>, Arc<str>),
+    #[error("Cancelled by user")]
+    CancelledByUser,
 }
 
-struct InstallFromContentLibrary {
+pub struct InstallFromContentLibrary {
     from: PathBuf,
     replace: Option<Arc<Path>>,
     hash: [u8; 20],
     install_path: Arc<Path>,
     content_file: ContentInstallFile,
     mod_summary: Option<Arc<ContentSummary>>,
+    /// Where the file can be downloaded again, recorded in the instance's content lock
+    url: Option<Arc<str>>,
+    size: usize,
+    modrinth_version: Option<Arc<str>>,
 }
 
 #[derive(Clone)]
@@ -70,275 +80,591 @@ impl From<&Path> for FilenameAndExtension {
 }
 
 impl BackendState {
+    /// Adds the install to the install queue and runs it once the installs queued before it have finished
     pub async fn install_content(&self, content: ContentInstall, modal_action: ModalAction) {
-        let semaphore = tokio::sync::Semaphore::new(8);
-
-        let mut tasks = Vec::new();
-
-        for content_file in content.files.iter() {
-            tasks.push(async {
-                match content_file.download {
-                    bridge::install::ContentDownload::Modrinth { ref project_id, ref version_id } => {
-                        let version = if let Some(version_id) = version_id {
-                            let version = self.meta.fetch(&ModrinthVersionMetadataItem(version_id.clone())).await?;
-                            Some(version)
-                        } else {
-                            let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
-                                project_id: project_id.clone(),
-                                game_versions: content.version_hint.clone().map(|v| [v].into()),
-                                loaders: None,
-                            })).await?;
-
-                            let modrinth_loader = content.loader_hint.as_modrinth_loader();
-                            let version = if modrinth_loader != ModrinthLoader::Unknown {
-                                versions.0.iter()
-                                    .find(|version| if let Some(loaders) = &version.loaders {
-                                        loaders.contains(&modrinth_loader)
-                                    } else {
-                                        false
-                                    })
-                                    .or(versions.0.first())
-                            } else {
-                                versions.0.first()
-                            };
+        let names = content.files.iter().map(|file| (initial_file_name(file), initial_file_sha1(file)));
+        let id = self.install_queue.push(self.install_title(&content.target), content.clone(), names);
+        self.install_queue.send_update(&self.send);
 
-                            version.map(|v| Arc::new(v.clone()))
-                        };
+        let _running = self.install_queue.run_lock.lock().await;
+        let files: Vec<usize> = (0..content.files.len()).collect();
+        self.install_queued_files(id, &content, &files, &modal_action).await;
+    }
 
-                        if let Some(version) = version {
-                            if &version.project_id != project_id {
-                                return Err(ContentInstallError::MismatchedProjectIdForVersion(
-                                    version.id.clone(),
-                                    project_id.clone(),
-                                    version.project_id.clone()
-                                ));
-                            }
-
-                            let install_file = version
-                                .files
-                                .iter()
-                                .find(|file| file.primary)
-                                .unwrap_or(version.files.first().unwrap());
-
-                            let url = &install_file.url;
-                            let sha1 = &install_file.hashes.sha1;
-                            let size = install_file.size;
-
-                            let Some(safe_filename) = SafePath::new(&install_file.filename) else {
-                                return Err(ContentInstallError::InvalidFilename(install_file.filename.clone()));
-                            };
+    /// Downloads a single failed file of a queued install again
+    pub async fn retry_install_file(&self, id: InstallQueueID, file: usize, modal_action: ModalAction) {
+        let Some(content) = self.install_queue.content(id) else {
+            modal_action.set_error_message("The install is no longer queued".into());
+            return;
+        };
+        if file >= content.files.len() {
+            modal_action.set_error_message("Unknown file in queued install".into());
+            return;
+        }
 
-                            let (path, hash, mod_summary) = self.download_file_into_library(&modal_action,
-                                (&safe_filename).into(), url, sha1, size, &semaphore).await?;
-
-                            let install_path = match &content_file.path {
-                                ContentInstallPath::Raw(path) => path.clone(),
-                                ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
-                                ContentInstallPath::Automatic => {
-                                    let base = if let Some(mod_summary) = &mod_summary {
-                                        match mod_summary.extra {
-                                            ContentType::Fabric | ContentType::Forge | ContentType::NeoForge | ContentType::JavaModule | ContentType::ModrinthModpack { .. } => {
-                                                Path::new("mods")
-                                            },
-                                            ContentType::ResourcePack => {
-                                                Path::new("resourcepacks")
-                                            }
-                                        }
-                                    } else if let Some(loaders) = &version.loaders {
-                                        let mut base = None;
-                                        for loader in loaders.iter() {
-                                            base = loader.install_directory();
-                                            if base.is_some() {
-                                                break;
-                                            }
-                                        }
-                                        if let Some(base) = base {
-                                            Path::new(base)
-                                        } else {
-                                            return Err(ContentInstallError::UnableToDetermineContentType(install_file.filename.clone()))
-                                        }
-                                    } else {
-                                        return Err(ContentInstallError::UnableToDetermineContentType(install_file.filename.clone()))
-                                    };
-
-                                    safe_filename.to_path(base).into()
-                                },
-                            };
+        let _running = self.install_queue.run_lock.lock().await;
+        self.install_queued_files(id, &content, &[file], &modal_action).await;
+    }
 
-                            Ok(InstallFromContentLibrary {
-                                from: path,
-                                replace: content_file.replace_old.clone(),
-                                hash,
-                                install_path,
-                                content_file: content_file.clone(),
-                                mod_summary
-                            })
-                        } else {
-                            Err(ContentInstallError::UnableToFindDependencyVersion)
-                        }
-                    },
-                    bridge::install::ContentDownload::Url { ref url, ref sha1, size } => {
-                        let name = match &content_file.path {
-                            ContentInstallPath::Raw(path) => (&**path).into(),
-                            ContentInstallPath::Safe(safe_path) => safe_path.into(),
-                            ContentInstallPath::Automatic => unimplemented!(),
-                        };
+    /// Downloads the files in the instance's content lock again that are missing or don't match their hash
+    pub async fn repair_instance_content(&self, id: InstanceID, modal_action: ModalAction) {
+        let Some((root_path, dot_minecraft)) = self.instance_state.read().instances.get(id)
+            .map(|instance| (instance.root_path.clone(), instance.dot_minecraft_path.clone())) else {
+            modal_action.set_error_message("Can't repair instance, unknown id".into());
+            return;
+        };
 
-                        let (path, hash, mod_summary) = self.download_file_into_library(&modal_action,
-                            name, url, sha1, size, &semaphore).await?;
+        let tracker = ProgressTracker::new("Verifying content".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
 
-                        let install_path = match &content_file.path {
-                            ContentInstallPath::Raw(path) => path.clone(),
-                            ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
-                            ContentInstallPath::Automatic => unimplemented!(),
-                        };
+        let broken = tokio::task::spawn_blocking({
+            let tracker = tracker.clone();
+            move || {
+                let content_lock = ContentLock::read(&root_path);
+                tracker.set_total(content_lock.files.len());
+                tracker.notify();
+
+                let mut broken = Vec::new();
+                for (key, file) in content_lock.files {
+                    let path = crate::content_lock::locate(&dot_minecraft, &key);
+                    let expected_hash = hex::decode(&*file.sha1).ok().and_then(|hash| <[u8; 20]>::try_from(hash).ok());
+                    let intact = match (&path, expected_hash) {
+                        (Some(path), Some(expected_hash)) => crate::check_sha1_hash(path, expected_hash).unwrap_or(false),
+                        _ => false,
+                    };
+                    if !intact {
+                        broken.push((key, path, file));
+                    }
+                    tracker.add_count(1);
+                    tracker.notify();
+                }
+                broken
+            }
+        }).await.unwrap();
 
-                        return Ok(InstallFromContentLibrary {
-                            from: path,
-                            replace: content_file.replace_old.clone(),
-                            hash,
-                            install_path,
-                            content_file: content_file.clone(),
-                            mod_summary
-                        });
-                    },
-                    bridge::install::ContentDownload::File { path: ref copy_path } => {
-                        let title = format!("Copying {}", copy_path.file_name().unwrap().to_string_lossy());
-                        let tracker = ProgressTracker::new(title.into(), self.send.clone());
-                        modal_action.trackers.push(tracker.clone());
+        if broken.is_empty() {
+            tracker.set_title("All content is intact".into());
+            tracker.set_finished(ProgressTrackerFinishType::Normal);
+            tracker.notify();
+            return;
+        }
 
-                        tracker.set_total(3);
-                        tracker.notify();
+        tracker.set_title(format!("Repairing {} file(s)", broken.len()).into());
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
 
-                        let data = tokio::fs::read(copy_path).await?;
+        let mut unrepairable = 0;
+        let mut files = Vec::new();
+        for (key, path, file) in broken {
+            // Content from Modrinth is downloaded through Modrinth again so the version is checked against the
+            // project, urls are only used for content that came from elsewhere
+            let download = match (&file.source, file.modrinth_version, file.url) {
+                (ContentSource::ModrinthProject { project }, Some(version), _) => ContentDownload::Modrinth {
+                    project_id: project.clone(),
+                    version_id: Some(version),
+                },
+                (_, _, Some(url)) => ContentDownload::Url { url, sha1: file.sha1, size: file.size },
+                _ => {
+                    unrepairable += 1;
+                    continue;
+                },
+            };
+            let Some(safe_path) = SafePath::new(&key) else {
+                unrepairable += 1;
+                continue;
+            };
+            // Corrupted files are replaced where they are, so disabled files stay disabled
+            let (replace_old, path) = match path {
+                Some(path) => {
+                    let path: Arc<Path> = path.into();
+                    (Some(path.clone()), ContentInstallPath::Raw(path))
+                },
+                None => (None, ContentInstallPath::Safe(safe_path)),
+            };
+            files.push(ContentInstallFile {
+                replace_old,
+                path,
+                download,
+                content_source: file.source,
+            });
+        }
 
-                        tracker.set_count(1);
-                        tracker.notify();
+        if !files.is_empty() {
+            self.install_content(ContentInstall {
+                target: InstallTarget::Instance(id),
+                loader_hint: Loader::Unknown,
+                version_hint: None,
+                files: files.into(),
+            }, modal_action.clone()).await;
+        }
 
-                        let mut hasher = Sha1::new();
-                        hasher.update(&data);
-                        let hash: [u8; 20] = hasher.finalize().into();
+        if unrepairable > 0 {
+            modal_action.set_error_message(format!(
+                "{} file(s) were added from a local file and can't be downloaded again",
+                unrepairable
+            ).into());
+        }
+    }
 
-                        let hash_as_str = hex::encode(hash);
+    /// Paths chosen by the frontend must be one of the [`InstallLocation`]s. World resource packs go
+    /// straight into the world's folder, installing one into a world that doesn't exist would create an
+    /// empty world
+    fn validate_install_path(&self, content: &ContentInstall, path: &SafePath) -> Result<(), ContentInstallError> {
+        if let InstallTarget::Library = content.target {
+            return Ok(());
+        }
 
-                        let hash_folder = self.directories.content_library_dir.join(&hash_as_str[..2]);
-                        let _ = tokio::fs::create_dir_all(&hash_folder).await;
-                        let mut path = hash_folder.join(hash_as_str);
+        let relative = path.to_path(Path::new(""));
+        match install_location(&relative) {
+            None => Err(ContentInstallError::DisallowedPath(relative.to_string_lossy().into())),
+            Some(InstallLocation::Folder) => Ok(()),
+            Some(InstallLocation::WorldResources(world)) => {
+                let dot_minecraft = match content.target {
+                    InstallTarget::Instance(id) => self.instance_state.read().instances.get(id).map(|instance| instance.dot_minecraft_path.clone()),
+                    _ => None,
+                };
+                if dot_minecraft.is_some_and(|dot_minecraft| dot_minecraft.join("saves").join(world).join("level.dat").is_file()) {
+                    Ok(())
+                } else {
+                    Err(ContentInstallError::UnknownWorld(world.to_string_lossy().into()))
+                }
+            },
+        }
+    }
 
-                        let extension = match &content_file.path {
-                            ContentInstallPath::Raw(path) => path.extension(),
-                            ContentInstallPath::Safe(safe_path) => safe_path.extension().map(OsStr::new),
-                            ContentInstallPath::Automatic => unimplemented!(),
-                        };
+    fn install_title(&self, target: &InstallTarget) -> Arc<str> {
+        match target {
+            InstallTarget::Instance(id) => {
+                let name = self.instance_state.read().instances.get(*id).map(|instance| instance.name.clone());
+                match name {
+                    Some(name) => format!("Installing into {}", name).into(),
+                    None => "Installing into instance".into(),
+                }
+            },
+            InstallTarget::Library => "Downloading into library".into(),
+            InstallTarget::NewInstance { name, .. } => format!("Creating {}", name).into(),
+        }
+    }
 
-                        if let Some(extension) = extension {
-                            path.set_extension(extension);
-                        }
+    async fn install_queued_files(&self, id: InstallQueueID, content: &ContentInstall, files: &[usize], modal_action: &ModalAction) {
+        let semaphore = tokio::sync::Semaphore::new(self.settings.read().concurrent_downloads());
+
+        let tasks = files.iter().map(|&index| {
+            let semaphore = &semaphore;
+            async move {
+                let queued = QueuedFile::new(&self.install_queue, &self.send, id, index);
+                queued.set_state(InstallFileState::Downloading);
+                let result = self.install_content_file(content, &content.files[index], modal_action, semaphore, &queued).await;
+                if let Err(error) = &result {
+                    queued.set_failed(error.to_string().into());
+                }
+                (index, result)
+            }
+        });
+
+        // Unlike try_join_all, every file gets a chance to finish so only the failed ones need to be retried
+        let results = futures::future::join_all(tasks).await;
+
+        let mut failed = 0;
+        let mut first_error = None;
+        for (index, result) in results {
+            match result {
+                Ok(install) => self.install_queue.set_result(id, index, install),
+                Err(error) => {
+                    failed += 1;
+                    first_error.get_or_insert(error);
+                },
+            }
+        }
 
-                        let mod_summary = {
-                            let path = path.clone();
-                            let mod_metadata_manager = self.mod_metadata_manager.clone();
-                            let tracker = tracker.clone();
-                            tokio::task::spawn_blocking(move || {
-                                let valid_hash_on_disk = crate::check_sha1_hash(&path, hash).unwrap_or(false);
+        if let Some(error) = &first_error && let InstallTarget::NewInstance { name, .. } = &content.target {
+            // The instance is created with the files that did install, e.g. without a companion mod that couldn't be
+            // downloaded. It's taken out of the queue since retrying it would create a second instance
+            let installs = self.install_queue.remove_with_results(id);
+            self.finish_install(content, installs).await;
+            modal_action.set_error_message(format!(
+                "{} was created, but {} file(s) couldn't be installed and can be added to it later\n{}",
+                name, failed, error
+            ).into());
+        } else if let Some(error) = first_error {
+            let message = if failed > 1 {
+                format!("{} files failed to install, they can be retried from the install queue\n{}", failed, error)
+            } else {
+                format!("{}", error)
+            };
+            modal_action.set_error_message(message.into());
+        } else if let Some(mut installs) = self.install_queue.take_results(id) {
+            for install in &mut installs {
+                self.replace_conflicting_mod(content, install, modal_action).await;
+            }
+            self.finish_install(content, installs).await;
+        }
 
-                                tracker.set_count(2);
-                                tracker.notify();
+        self.install_queue.send_update(&self.send);
+    }
 
-                                if !valid_hash_on_disk {
-                                    std::fs::write(&path, &data)?;
-                                }
+    /// Asks whether an installed mod with the same id as the new file should be replaced by it,
+    /// instead of leaving two versions of the mod in the instance
+    async fn replace_conflicting_mod(&self, content: &ContentInstall, install: &mut InstallFromContentLibrary, modal_action: &ModalAction) {
+        let InstallTarget::Instance(id) = content.target else {
+            return;
+        };
+        if install.replace.is_some() || install.install_path.parent() != Some(Path::new("mods")) {
+            return;
+        }
+        let Some(summary) = &install.mod_summary else {
+            return;
+        };
+        let Some(mod_id) = &summary.id else {
+            return;
+        };
 
-                                std::io::Result::Ok(mod_metadata_manager.get_bytes(&data))
-                            }).await.unwrap()?
-                        };
+        let Some(mods) = self.clone().load_instance_content(id, ContentFolder::Mods).await else {
+            return;
+        };
+        let Some(existing) = mods.iter().find(|installed| {
+            installed.content_summary.id.as_ref() == Some(mod_id) && installed.content_summary.hash != install.hash
+        }) else {
+            return;
+        };
 
-                        tracker.set_count(3);
-                        tracker.notify();
+        let name = summary.name.as_deref().unwrap_or(mod_id);
+        let message = format!(
+            "{} {} is already installed as {}. Replace it with {}?",
+            name, existing.content_summary.version_str, existing.filename, summary.version_str
+        );
+        // Keeping both never loses a file, so it's the answer when nobody is there to choose
+        let replace = modal_action.ask(message.into(), "Replace".into(), "Keep both".into(), false, &self.send).await;
+        if replace {
+            install.replace = Some(existing.path.clone());
+        }
+    }
 
-                        let install_path = match &content_file.path {
-                            ContentInstallPath::Raw(path) => path.clone(),
-                            ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
-                            ContentInstallPath::Automatic => unimplemented!(),
-                        };
+    async fn finish_install(&self, content: &ContentInstall, files: Vec<InstallFromContentLibrary>) {
+        let mut instance_dir = None;
 
-                        return Ok(InstallFromContentLibrary {
-                            from: path,
-                            replace: content_file.replace_old.clone(),
-                            hash: hash.into(),
-                            install_path,
-                            content_file: content_file.clone(),
-                            mod_summary,
+        match &content.target {
+            bridge::install::InstallTarget::Instance(instance_id) => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(*instance_id) {
+                    if instance.configuration.get().loader == Loader::Vanilla && content.loader_hint != Loader::Unknown {
+                        instance.configuration.modify(|config| {
+                            config.loader = content.loader_hint;
                         });
-                    },
+                    }
+
+                    instance_dir = Some(instance.dot_minecraft_path.clone());
                 }
-            });
+            },
+            bridge::install::InstallTarget::Library => {},
+            bridge::install::InstallTarget::NewInstance { name, configuration: Some(configuration) } => {
+                instance_dir = self.create_instance_sanitized_from_configuration(name, configuration.clone()).await
+                    .map(|v| v.join(".minecraft").into());
+            },
+            bridge::install::InstallTarget::NewInstance { name, configuration: None } => {
+                let mut minecraft_version = content.version_hint.clone();
+                if minecraft_version.is_none() {
+                    if let Ok(meta) = self.meta.fetch(&MinecraftVersionManifestMetadataItem).await {
+                        minecraft_version = Some(meta.latest.release.into());
+                    }
+                }
+
+                if let Some(minecraft_version) = minecraft_version {
+                    instance_dir = self.create_instance_sanitized(name, &minecraft_version, content.loader_hint).await
+                        .map(|v| v.join(".minecraft").into());
+                }
+            },
         }
 
-        let result: Result<Vec<InstallFromContentLibrary>, ContentInstallError> = futures::future::try_join_all(tasks).await;
-        match result {
-            Ok(files) => {
-                let mut instance_dir = None;
-
-                match content.target {
-                    bridge::install::InstallTarget::Instance(instance_id) => {
-                        if let Some(instance) = self.instance_state.write().instances.get_mut(instance_id) {
-                            if instance.configuration.get().loader == Loader::Vanilla && content.loader_hint != Loader::Unknown {
-                                instance.configuration.modify(|config| {
-                                    config.loader = content.loader_hint;
-                                });
-                            }
-
-                            instance_dir = Some(instance.dot_minecraft_path.clone());
-                        }
-                    },
-                    bridge::install::InstallTarget::Library => {},
-                    bridge::install::InstallTarget::NewInstance { name } => {
-                        let mut minecraft_version = content.version_hint;
-                        if minecraft_version.is_none() {
-                            if let Ok(meta) = self.meta.fetch(&MinecraftVersionManifestMetadataItem).await {
-                                minecraft_version = Some(meta.latest.release.into());
-                            }
-                        }
+        let sources = files.iter()
+            .filter_map(|install| {
+                if install.content_file.content_source != ContentSource::Manual {
+                    Some((install.hash.clone(), install.content_file.content_source.clone()))
+                } else {
+                    None
+                }
+            });
+        self.mod_metadata_manager.set_content_sources(sources);
 
-                        if let Some(minecraft_version) = minecraft_version {
-                            instance_dir = self.create_instance_sanitized(&name, &minecraft_version, content.loader_hint).await
-                                .map(|v| v.join(".minecraft").into());
-                        }
-                    },
+        if let Some(instance_dir) = instance_dir {
+            let mut locked = Vec::new();
+
+            for install in files {
+                let target_path = instance_dir.join(&install.install_path);
+
+                let _ = std::fs::create_dir_all(target_path.parent().unwrap());
+
+                if let Some(InstallLocation::WorldResources(_)) = install_location(&install.install_path) && target_path.exists() {
+                    // A world only has a single resource pack, keep the previous one around in case it was
+                    // made for the world and isn't available anywhere else
+                    if let Err(error) = std::fs::rename(&target_path, target_path.with_file_name(WORLD_RESOURCES_BACKUP)) {
+                        log::error!("Unable to back up world resource pack {:?}: {error}", target_path);
+                        continue;
+                    }
+                }
+                // Only files the install could have put there itself are replaced
+                let replace = install.replace.as_ref().filter(|replace| {
+                    replace.strip_prefix(&instance_dir).ok().and_then(install_location).is_some()
+                });
+                if let Some(replace) = replace {
+                    let _ = std::fs::remove_file(replace);
+                    if let Some(key) = ContentLock::key(&instance_dir, replace) {
+                        locked.push((key, None));
+                    }
+                }
+                if std::fs::hard_link(&install.from, &target_path).is_err() {
+                    continue;
                 }
 
-                let sources = files.iter()
-                    .filter_map(|install| {
-                        if install.content_file.content_source != ContentSource::Manual {
-                            Some((install.hash.clone(), install.content_file.content_source.clone()))
-                        } else {
-                            None
-                        }
-                    });
-                self.mod_metadata_manager.set_content_sources(sources);
+                if let Some(key) = ContentLock::key(&instance_dir, &target_path) {
+                    locked.push((key, Some(LockedFile {
+                        sha1: hex::encode(install.hash).into(),
+                        url: install.url,
+                        size: install.size,
+                        source: install.content_file.content_source,
+                        modrinth_version: install.modrinth_version,
+                    })));
+                }
+            }
 
-                if let Some(instance_dir) = instance_dir {
-                    for install in files {
-                        let target_path = instance_dir.join(&install.install_path);
+            if !locked.is_empty() && let Some(lock_dir) = instance_dir.parent() {
+                ContentLock::modify(lock_dir.to_path_buf(), |lock| {
+                    for (key, file) in locked {
+                        match file {
+                            Some(file) => lock.files.insert(key, file),
+                            None => lock.files.remove(&key),
+                        };
+                    }
+                }).await;
+            }
+        }
+    }
 
-                        let _ = std::fs::create_dir_all(target_path.parent().unwrap());
+    async fn install_content_file(
+        &self,
+        content: &ContentInstall,
+        content_file: &ContentInstallFile,
+        modal_action: &ModalAction,
+        semaphore: &tokio::sync::Semaphore,
+        queued: &QueuedFile<'_>,
+    ) -> Result<InstallFromContentLibrary, ContentInstallError> {
+        if let ContentInstallPath::Safe(path) = &content_file.path {
+            self.validate_install_path(content, path)?;
+        }
 
-                        if let Some(replace) = install.replace {
-                            let _ = std::fs::remove_file(replace);
-                        }
-                        let _ = std::fs::hard_link(install.from, target_path);
+        match content_file.download {
+            bridge::install::ContentDownload::Modrinth { ref project_id, ref version_id } => {
+                let version = if let Some(version_id) = version_id {
+                    let version = self.meta.fetch(&ModrinthVersionMetadataItem(version_id.clone())).await?;
+                    Some(version)
+                } else {
+                    let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
+                        project_id: project_id.clone(),
+                        game_versions: content.version_hint.clone().map(|v| [v].into()),
+                        loaders: None,
+                    })).await?;
+
+                    let modrinth_loader = content.loader_hint.as_modrinth_loader();
+                    let version = if modrinth_loader != ModrinthLoader::Unknown {
+                        versions.0.iter()
+                            .find(|version| if let Some(loaders) = &version.loaders {
+                                loaders.contains(&modrinth_loader)
+                            } else {
+                                false
+                            })
+                            .or(versions.0.first())
+                    } else {
+                        versions.0.first()
+                    };
+
+                    version.map(|v| Arc::new(v.clone()))
+                };
+
+                if let Some(version) = version {
+                    if &version.project_id != project_id {
+                        return Err(ContentInstallError::MismatchedProjectIdForVersion(
+                            version.id.clone(),
+                            project_id.clone(),
+                            version.project_id.clone()
+                        ));
                     }
+
+                    let install_file = version
+                        .files
+                        .iter()
+                        .find(|file| file.primary)
+                        .unwrap_or(version.files.first().unwrap());
+
+                    let url = &install_file.url;
+                    let sha1 = &install_file.hashes.sha1;
+                    let size = install_file.size;
+
+                    let Some(safe_filename) = SafePath::new(&install_file.filename) else {
+                        return Err(ContentInstallError::InvalidFilename(install_file.filename.clone()));
+                    };
+
+                    queued.set_download(install_file.filename.clone(), sha1.clone());
+
+                    let (path, hash, mod_summary) = self.download_file_into_library(modal_action,
+                        (&safe_filename).into(), url, sha1, size, semaphore, Some(queued)).await?;
+
+                    let install_path = match &content_file.path {
+                        ContentInstallPath::Raw(path) => path.clone(),
+                        ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
+                        ContentInstallPath::Automatic => {
+                            let base = if let Some(mod_summary) = &mod_summary {
+                                match mod_summary.extra {
+                                    ContentType::Fabric | ContentType::Forge | ContentType::NeoForge | ContentType::JavaModule | ContentType::ModrinthModpack { .. } => {
+                                        Path::new("mods")
+                                    },
+                                    ContentType::ResourcePack => {
+                                        Path::new("resourcepacks")
+                                    }
+                                }
+                            } else if let Some(loaders) = &version.loaders {
+                                let mut base = None;
+                                for loader in loaders.iter() {
+                                    base = loader.install_directory();
+                                    if base.is_some() {
+                                        break;
+                                    }
+                                }
+                                if let Some(base) = base {
+                                    Path::new(base)
+                                } else {
+                                    return Err(ContentInstallError::UnableToDetermineContentType(install_file.filename.clone()))
+                                }
+                            } else {
+                                return Err(ContentInstallError::UnableToDetermineContentType(install_file.filename.clone()))
+                            };
+
+                            safe_filename.to_path(base).into()
+                        },
+                    };
+
+                    Ok(InstallFromContentLibrary {
+                        from: path,
+                        replace: content_file.replace_old.clone(),
+                        hash,
+                        install_path,
+                        content_file: content_file.clone(),
+                        mod_summary,
+                        url: Some(url.clone()),
+                        size,
+                        modrinth_version: Some(version.id.clone()),
+                    })
+                } else {
+                    Err(ContentInstallError::UnableToFindDependencyVersion)
                 }
             },
-            Err(error) => {
-                modal_action.set_error_message(Arc::from(format!("{}", error).as_str()));
+            bridge::install::ContentDownload::Url { ref url, ref sha1, size } => {
+                let name = match &content_file.path {
+                    ContentInstallPath::Raw(path) => (&**path).into(),
+                    ContentInstallPath::Safe(safe_path) => safe_path.into(),
+                    ContentInstallPath::Automatic => unimplemented!(),
+                };
+
+                let (path, hash, mod_summary) = self.download_file_into_library(modal_action,
+                    name, url, sha1, size, semaphore, Some(queued)).await?;
+
+                let install_path = match &content_file.path {
+                    ContentInstallPath::Raw(path) => path.clone(),
+                    ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
+                    ContentInstallPath::Automatic => unimplemented!(),
+                };
+
+                Ok(InstallFromContentLibrary {
+                    from: path,
+                    replace: content_file.replace_old.clone(),
+                    hash,
+                    install_path,
+                    content_file: content_file.clone(),
+                    mod_summary,
+                    url: Some(url.clone()),
+                    size,
+                    modrinth_version: None,
+                })
+            },
+            bridge::install::ContentDownload::File { path: ref copy_path } => {
+                let title = format!("Copying {}", copy_path.file_name().unwrap().to_string_lossy());
+                let tracker = ProgressTracker::new(title.into(), self.send.clone());
+                modal_action.trackers.push(tracker.clone());
+
+                tracker.set_total(3);
+                tracker.notify();
+
+                let data = tokio::fs::read(copy_path).await?;
+                let size = data.len();
+
+                tracker.set_count(1);
+                tracker.notify();
+
+                let mut hasher = Sha1::new();
+                hasher.update(&data);
+                let hash: [u8; 20] = hasher.finalize().into();
+
+                let hash_as_str = hex::encode(hash);
+                queued.set_state(InstallFileState::Verifying);
+
+                let hash_folder = self.directories.content_library_dir.join(&hash_as_str[..2]);
+                let _ = tokio::fs::create_dir_all(&hash_folder).await;
+                let mut path = hash_folder.join(hash_as_str);
+
+                let extension = match &content_file.path {
+                    ContentInstallPath::Raw(path) => path.extension(),
+                    ContentInstallPath::Safe(safe_path) => safe_path.extension().map(OsStr::new),
+                    ContentInstallPath::Automatic => unimplemented!(),
+                };
+
+                if let Some(extension) = extension {
+                    path.set_extension(extension);
+                }
+
+                let mod_summary = {
+                    let path = path.clone();
+                    let mod_metadata_manager = self.mod_metadata_manager.clone();
+                    let tracker = tracker.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let valid_hash_on_disk = crate::check_sha1_hash(&path, hash).unwrap_or(false);
+
+                        tracker.set_count(2);
+                        tracker.notify();
+
+                        if !valid_hash_on_disk {
+                            std::fs::write(&path, &data)?;
+                        }
+
+                        std::io::Result::Ok(mod_metadata_manager.get_bytes(&data))
+                    }).await.unwrap()?
+                };
+
+                tracker.set_count(3);
+                tracker.notify();
+                queued.set_state(InstallFileState::Verified);
+
+                let install_path = match &content_file.path {
+                    ContentInstallPath::Raw(path) => path.clone(),
+                    ContentInstallPath::Safe(safe_path) => safe_path.to_path(Path::new("")).into(),
+                    ContentInstallPath::Automatic => unimplemented!(),
+                };
+
+                Ok(InstallFromContentLibrary {
+                    from: path,
+                    replace: content_file.replace_old.clone(),
+                    hash: hash.into(),
+                    install_path,
+                    content_file: content_file.clone(),
+                    mod_summary,
+                    url: None,
+                    size,
+                    modrinth_version: None,
+                })
             },
         }
     }
 
-    async fn download_file_into_library(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
-        let mut result = self.download_file_into_library_inner(modal_action, name, url, sha1, size, semaphore).await?;
+    #[allow(clippy::too_many_arguments)]
+    async fn download_file_into_library(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore, queued: Option<&QueuedFile<'_>>) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
+        let mut result = self.download_file_into_library_inner(modal_action, name, url, sha1, size, semaphore, queued).await?;
 
         if let Some(summary) = &result.2 {
             if let ContentType::ModrinthModpack { downloads, .. } = &summary.extra {
@@ -355,7 +681,7 @@ impl BackendState {
                     };
 
                     tasks.push(self.download_file_into_library_inner(modal_action, name,
-                        &download.downloads[0], &download.hashes.sha1, download.file_size, semaphore));
+                        &download.downloads[0], &download.hashes.sha1, download.file_size, semaphore, None));
                 }
 
                 _ = futures::future::try_join_all(tasks).await;
@@ -366,7 +692,8 @@ impl BackendState {
         Ok(result)
     }
 
-    async fn download_file_into_library_inner(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
+    #[allow(clippy::too_many_arguments)]
+    async fn download_file_into_library_inner(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore, queued: Option<&QueuedFile<'_>>) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
         let mut expected_hash = [0u8; 20];
         let Ok(_) = hex::decode_to_slice(&**sha1, &mut expected_hash) else {
             log::warn!("Content install has invalid sha1: {}", sha1);
@@ -408,10 +735,15 @@ impl BackendState {
             tracker.set_count(size);
             tracker.set_finished(ProgressTrackerFinishType::Fast);
             tracker.notify();
+            if let Some(queued) = queued {
+                queued.set_state(InstallFileState::Cached);
+            }
             let summary = self.mod_metadata_manager.get_path(&path);
             return Ok((path, expected_hash, summary));
         }
 
+        let _background_task = tracker.register_background(BackgroundTaskKind::Install, Some(modal_action.request_cancel.clone()));
+
         let response = self.redirecting_http_client.get(&**url).send().await?;
 
         if response.status() != StatusCode::OK {
@@ -431,6 +763,15 @@ impl BackendState {
 
         let mut hasher = Sha1::new();
         while let Some(item) = stream.next().await {
+            if modal_action.has_requested_cancel() {
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                tracker.notify();
+                let _ = file.set_len(0).await;
+                drop(file);
+                let _ = tokio::fs::remove_file(&path).await;
+                return Err(ContentInstallError::CancelledByUser);
+            }
+
             let item = item?;
 
             total_bytes += item.len();
@@ -442,6 +783,9 @@ impl BackendState {
         }
 
         tracker.set_finished(ProgressTrackerFinishType::Fast);
+        if let Some(queued) = queued {
+            queued.set_state(InstallFileState::Verifying);
+        }
 
         let actual_hash = hasher.finalize();
 
@@ -463,8 +807,171 @@ impl BackendState {
         }
 
         drop(lockfile);
+        if let Some(queued) = queued {
+            queued.set_state(InstallFileState::Verified);
+        }
 
         let summary = self.mod_metadata_manager.get_path(&path);
         Ok((path, expected_hash, summary))
     }
 }
+
+/// What a file is called in the install queue before anything has been downloaded
+/// Folders inside `.minecraft` that content can be installed into directly
+const INSTALL_FOLDERS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+/// The previous `resources.zip` of a world is moved here when another one is installed
+const WORLD_RESOURCES_BACKUP: &str = "resources.zip.bak";
+
+/// Where inside `.minecraft` content is allowed to be installed
+#[derive(Debug, PartialEq)]
+enum InstallLocation<'a> {
+    /// Directly inside one of the [`INSTALL_FOLDERS`]
+    Folder,
+    /// The `resources.zip` of the world, which is only used while playing that world
+    WorldResources(&'a OsStr),
+}
+
+fn install_location(relative: &Path) -> Option<InstallLocation<'_>> {
+    let components: Vec<Component> = relative.components().collect();
+    match components[..] {
+        [Component::Normal(folder), Component::Normal(_)] if INSTALL_FOLDERS.iter().any(|allowed| folder == *allowed) => {
+            Some(InstallLocation::Folder)
+        },
+        [Component::Normal(saves), Component::Normal(world), Component::Normal(file)] if saves == "saves" && file == "resources.zip" => {
+            Some(InstallLocation::WorldResources(world))
+        },
+        _ => None,
+    }
+}
+
+fn initial_file_name(file: &ContentInstallFile) -> Arc<str> {
+    let name = match &file.path {
+        ContentInstallPath::Raw(path) => path.file_name().map(|name| name.to_string_lossy().into()),
+        ContentInstallPath::Safe(safe_path) => safe_path.file_name().map(Arc::from),
+        ContentInstallPath::Automatic => None,
+    };
+    if let Some(name) = name {
+        return name;
+    }
+    match &file.download {
+        ContentDownload::Modrinth { project_id, .. } => format!("Modrinth project {}", project_id).into(),
+        ContentDownload::Url { url, .. } => url.rsplit('/').next().unwrap_or(url).into(),
+        ContentDownload::File { path } => path.file_name().map(|name| name.to_string_lossy().into()).unwrap_or_else(|| "Unknown file".into()),
+    }
+}
+
+fn initial_file_sha1(file: &ContentInstallFile) -> Option<Arc<str>> {
+    match &file.download {
+        ContentDownload::Url { sha1, .. } => Some(sha1.clone()),
+        ContentDownload::Modrinth { .. } | ContentDownload::File { .. } => None,
+    }
+}
+
+/// The newest release for the game version and loader, or the newest beta or alpha if there's no release.
+/// Without a loader every version for the game version is considered, e.g. for resource packs
+pub fn latest_compatible_version<'a>(versions: &'a [ModrinthProjectVersion], minecraft_version: &str, loader: Option<ModrinthLoader>) -> Option<&'a ModrinthProjectVersion> {
+    let compatible = versions.iter().filter(|version| {
+        !version.files.is_empty()
+            && version.status.is_none_or(|status| matches!(status, ModrinthVersionStatus::Listed | ModrinthVersionStatus::Archived))
+            && version.game_versions.as_ref().is_some_and(|game_versions| game_versions.iter().any(|game_version| game_version.as_str() == minecraft_version))
+            && loader.is_none_or(|loader| version.loaders.as_ref().is_some_and(|loaders| loaders.contains(&loader)))
+    });
+
+    // Versions are listed newest first
+    let mut beta = None;
+    let mut alpha = None;
+    for version in compatible {
+        match version.version_type {
+            Some(ModrinthVersionType::Release) => return Some(version),
+            Some(ModrinthVersionType::Beta) => {
+                beta.get_or_insert(version);
+            },
+            Some(ModrinthVersionType::Alpha) => {
+                alpha.get_or_insert(version);
+            },
+            _ => {},
+        }
+    }
+    beta.or(alpha)
+}
+
+#[cfg(test)]
+impl InstallFromContentLibrary {
+    /// A downloaded file that is only told apart by its hash
+    pub(crate) fn for_test(hash: [u8; 20]) -> Self {
+        let path: Arc<Path> = Path::new("mods/test.jar").into();
+        Self {
+            from: PathBuf::from("test.jar"),
+            replace: None,
+            hash,
+            install_path: path.clone(),
+            content_file: ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Raw(path),
+                download: ContentDownload::File { path: PathBuf::from("test.jar") },
+                content_source: ContentSource::Manual,
+            },
+            mod_summary: None,
+            url: None,
+            size: 0,
+            modrinth_version: None,
+        }
+    }
+
+    pub(crate) fn hash(&self) -> [u8; 20] {
+        self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, version_type: &str, game_version: &str, loader: &str) -> ModrinthProjectVersion {
+        serde_json::from_value(serde_json::json!({
+            "game_versions": [game_version],
+            "loaders": [loader],
+            "id": id,
+            "project_id": "project",
+            "version_type": version_type,
+            "files": [{
+                "hashes": { "sha1": "0000000000000000000000000000000000000000" },
+                "url": "https://cdn.modrinth.com/file.jar",
+                "filename": "file.jar",
+                "primary": true,
+                "size": 1,
+            }],
+        })).unwrap()
+    }
+
+    #[test]
+    fn only_content_folders_and_world_resources_are_install_locations() {
+        assert_eq!(install_location(Path::new("mods/sodium.jar")), Some(InstallLocation::Folder));
+        assert_eq!(install_location(Path::new("shaderpacks/pack.zip")), Some(InstallLocation::Folder));
+        assert_eq!(install_location(Path::new("saves/New World/resources.zip")), Some(InstallLocation::WorldResources(OsStr::new("New World"))));
+
+        assert_eq!(install_location(Path::new("config/sodium.json")), None);
+        assert_eq!(install_location(Path::new("mods/nested/sodium.jar")), None);
+        assert_eq!(install_location(Path::new("mods")), None);
+        assert_eq!(install_location(Path::new("saves/New World/level.dat")), None);
+        assert_eq!(install_location(Path::new("saves/../resources.zip")), None);
+        assert_eq!(install_location(Path::new("/mods/sodium.jar")), None);
+    }
+
+    #[test]
+    fn prefers_release_for_game_version_and_loader() {
+        let versions = [
+            version("beta", "beta", "1.21.1", "fabric"),
+            version("other-game-version", "release", "1.20.1", "fabric"),
+            version("other-loader", "release", "1.21.1", "neoforge"),
+            version("release", "release", "1.21.1", "fabric"),
+        ];
+        let latest = latest_compatible_version(&versions, "1.21.1", Some(ModrinthLoader::Fabric));
+        assert_eq!(latest.map(|version| &*version.id), Some("release"));
+
+        let latest = latest_compatible_version(&versions[..3], "1.21.1", Some(ModrinthLoader::Fabric));
+        assert_eq!(latest.map(|version| &*version.id), Some("beta"));
+
+        assert!(latest_compatible_version(&versions, "1.19.2", Some(ModrinthLoader::Fabric)).is_none());
+    }
+}
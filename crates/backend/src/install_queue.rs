@@ -0,0 +1,247 @@
+use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
+
+use bridge::{
+    handle::FrontendHandle, install::{ContentInstall, InstallFileState, InstallQueueEntry, InstallQueueFile, InstallQueueID}, message::MessageToFrontend
+};
+use parking_lot::Mutex;
+
+use crate::install_content::InstallFromContentLibrary;
+
+/// Content installs that are waiting to run, running, or waiting for failed files to be retried
+#[derive(Default)]
+pub struct InstallQueue {
+    next_id: AtomicU64,
+    entries: Mutex<Vec<QueuedInstall>>,
+    /// Installs run one at a time. Tokio's mutex is fair, so they run in the order they were queued
+    pub run_lock: tokio::sync::Mutex<()>,
+}
+
+struct QueuedInstall {
+    id: InstallQueueID,
+    title: Arc<str>,
+    content: ContentInstall,
+    files: Vec<InstallQueueFile>,
+    /// Files that were downloaded, kept until every file is so that failed files can be retried on their own
+    results: Vec<Option<InstallFromContentLibrary>>,
+    finished: bool,
+}
+
+impl InstallQueue {
+    pub fn push(&self, title: Arc<str>, content: ContentInstall, names: impl Iterator<Item = (Arc<str>, Option<Arc<str>>)>) -> InstallQueueID {
+        let id = InstallQueueID(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let files: Vec<InstallQueueFile> = names.map(|(name, sha1)| InstallQueueFile {
+            name,
+            state: InstallFileState::Pending,
+            sha1,
+            error: None,
+        }).collect();
+        let results = files.iter().map(|_| None).collect();
+
+        self.entries.lock().push(QueuedInstall {
+            id,
+            title,
+            content,
+            files,
+            results,
+            finished: false,
+        });
+        id
+    }
+
+    pub fn content(&self, id: InstallQueueID) -> Option<ContentInstall> {
+        self.entries.lock().iter().find(|entry| entry.id == id).map(|entry| entry.content.clone())
+    }
+
+    pub fn remove(&self, id: InstallQueueID) {
+        self.entries.lock().retain(|entry| entry.id != id);
+    }
+
+    /// Removes the install from the queue, returning the files that were downloaded before it stopped
+    pub fn remove_with_results(&self, id: InstallQueueID) -> Vec<InstallFromContentLibrary> {
+        let mut entries = self.entries.lock();
+        let Some(index) = entries.iter().position(|entry| entry.id == id) else {
+            return Vec::new();
+        };
+        entries.remove(index).results.into_iter().flatten().collect()
+    }
+
+    pub fn set_result(&self, id: InstallQueueID, index: usize, result: InstallFromContentLibrary) {
+        self.update(id, |entry| {
+            if let Some(slot) = entry.results.get_mut(index) {
+                *slot = Some(result);
+            }
+        });
+    }
+
+    /// Takes the downloaded files once every file of the install has been downloaded, marking it as finished
+    pub fn take_results(&self, id: InstallQueueID) -> Option<Vec<InstallFromContentLibrary>> {
+        let mut entries = self.entries.lock();
+        let entry = entries.iter_mut().find(|entry| entry.id == id)?;
+        if entry.finished || entry.results.iter().any(Option::is_none) {
+            return None;
+        }
+        entry.finished = true;
+        Some(entry.results.iter_mut().filter_map(Option::take).collect())
+    }
+
+    pub fn send_update(&self, send: &FrontendHandle) {
+        let entries = self.entries.lock().iter().map(|entry| InstallQueueEntry {
+            id: entry.id,
+            title: entry.title.clone(),
+            files: entry.files.clone().into(),
+            finished: entry.finished,
+        }).collect();
+        send.send(MessageToFrontend::InstallQueueUpdated { entries });
+    }
+
+    fn update(&self, id: InstallQueueID, f: impl FnOnce(&mut QueuedInstall)) {
+        if let Some(entry) = self.entries.lock().iter_mut().find(|entry| entry.id == id) {
+            f(entry);
+        }
+    }
+}
+
+/// A single file of a queued install, changes to it are sent to the frontend straight away
+pub struct QueuedFile<'a> {
+    queue: &'a InstallQueue,
+    send: &'a FrontendHandle,
+    id: InstallQueueID,
+    index: usize,
+}
+
+impl<'a> QueuedFile<'a> {
+    pub fn new(queue: &'a InstallQueue, send: &'a FrontendHandle, id: InstallQueueID, index: usize) -> Self {
+        Self { queue, send, id, index }
+    }
+
+    pub fn set_state(&self, state: InstallFileState) {
+        self.modify(|file| {
+            file.state = state;
+            if state != InstallFileState::Failed {
+                file.error = None;
+            }
+        });
+    }
+
+    /// The name and hash of files downloaded from Modrinth aren't known until the version has been picked
+    pub fn set_download(&self, name: Arc<str>, sha1: Arc<str>) {
+        self.modify(|file| {
+            file.name = name;
+            file.sha1 = Some(sha1);
+        });
+    }
+
+    pub fn set_failed(&self, error: Arc<str>) {
+        self.modify(|file| {
+            file.state = InstallFileState::Failed;
+            file.error = Some(error);
+        });
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut InstallQueueFile)) {
+        self.queue.update(self.id, |entry| {
+            if let Some(file) = entry.files.get_mut(self.index) {
+                f(file);
+            }
+        });
+        self.queue.send_update(self.send);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bridge::install::{ContentInstall, InstallTarget};
+    use schema::loader::Loader;
+
+    use super::*;
+
+    fn push(queue: &InstallQueue, files: usize) -> InstallQueueID {
+        let content = ContentInstall {
+            target: InstallTarget::Library,
+            loader_hint: Loader::Fabric,
+            version_hint: None,
+            files: Arc::from([]),
+        };
+        let names = (0..files).map(|index| (Arc::from(format!("file{index}.jar")), None));
+        queue.push("Test".into(), content, names)
+    }
+
+    fn hashes(results: &[InstallFromContentLibrary]) -> Vec<u8> {
+        results.iter().map(|result| result.hash()[0]).collect()
+    }
+
+    fn queued_files(receiver: &mut bridge::handle::FrontendReceiver) -> Arc<[InstallQueueFile]> {
+        let mut last = None;
+        while let Some(message) = receiver.try_recv() {
+            if let MessageToFrontend::InstallQueueUpdated { entries } = message {
+                last = Some(entries);
+            }
+        }
+        let entries = last.expect("queue update was sent");
+        entries[0].files.clone()
+    }
+
+    #[test]
+    fn ids_are_unique_and_removable() {
+        let queue = InstallQueue::default();
+        let first = push(&queue, 1);
+        let second = push(&queue, 1);
+        assert_ne!(first, second);
+
+        queue.remove(first);
+        assert!(queue.content(first).is_none());
+        assert!(queue.content(second).is_some());
+    }
+
+    #[test]
+    fn results_are_taken_once_every_file_is_downloaded() {
+        let queue = InstallQueue::default();
+        let id = push(&queue, 2);
+
+        queue.set_result(id, 1, InstallFromContentLibrary::for_test([1; 20]));
+        assert!(queue.take_results(id).is_none());
+
+        queue.set_result(id, 0, InstallFromContentLibrary::for_test([0; 20]));
+        let results = queue.take_results(id).expect("every file was downloaded");
+        assert_eq!(hashes(&results), [0, 1]);
+
+        // Finished installs stay in the queue so the frontend can show them, but don't run again
+        assert!(queue.take_results(id).is_none());
+        assert!(queue.content(id).is_some());
+    }
+
+    #[test]
+    fn removing_keeps_downloaded_results() {
+        let queue = InstallQueue::default();
+        let id = push(&queue, 3);
+        queue.set_result(id, 2, InstallFromContentLibrary::for_test([2; 20]));
+        queue.set_result(id, 5, InstallFromContentLibrary::for_test([5; 20]));
+
+        assert_eq!(hashes(&queue.remove_with_results(id)), [2]);
+        assert!(queue.remove_with_results(id).is_empty());
+    }
+
+    #[test]
+    fn file_changes_are_sent() {
+        let (_, _, mut receiver, send) = bridge::handle::create_pair();
+        let queue = InstallQueue::default();
+        let id = push(&queue, 2);
+
+        let file = QueuedFile::new(&queue, &send, id, 1);
+        file.set_download("sodium.jar".into(), "abcd".into());
+        file.set_failed("Connection reset".into());
+
+        let files = queued_files(&mut receiver);
+        assert_eq!(files[0].state, InstallFileState::Pending);
+        assert_eq!(&*files[1].name, "sodium.jar");
+        assert_eq!(files[1].sha1.as_deref(), Some("abcd"));
+        assert_eq!(files[1].state, InstallFileState::Failed);
+        assert_eq!(files[1].error.as_deref(), Some("Connection reset"));
+
+        // Retrying clears the error of the previous attempt
+        file.set_state(InstallFileState::Downloading);
+        let files = queued_files(&mut receiver);
+        assert_eq!(files[1].state, InstallFileState::Downloading);
+        assert!(files[1].error.is_none());
+    }
+}
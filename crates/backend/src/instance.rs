@@ -1,25 +1,33 @@
 use std::{
-    collections::HashSet, ffi::OsStr, hash::{DefaultHasher, Hash, Hasher}, io::Read, path::Path, process::Child, sync::{
+    collections::{HashMap, HashSet}, ffi::OsStr, hash::{DefaultHasher, Hash, Hasher}, io::Read, path::{Component, Path}, process::Child, sync::{
         atomic::Ordering, Arc
-    }
+    }, time::{Duration, Instant, SystemTime}
 };
 
 use anyhow::Context;
 use base64::Engine;
 use bridge::{
     instance::{
-        InstanceID, InstanceContentID, InstanceContentSummary, InstanceServerSummary, InstanceStatus, InstanceWorldSummary,
+        InstanceID, InstanceContentID, InstanceContentSummary, InstanceServerSummary, InstanceStatus, InstanceWorldDetails, InstanceWorldSummary,
+        ServerEdit, WorldDifficulty, WorldGameMode,
     }, message::{AtomicBridgeDataLoadState, BridgeDataLoadState, MessageToFrontend}, notify_signal::{KeepAliveNotifySignal, KeepAliveNotifySignalHandle}
 };
 use parking_lot::RwLock;
 use relative_path::RelativePath;
-use schema::instance::InstanceConfiguration;
+use schema::{crash_report::SuspectedMod, instance::InstanceConfiguration, launch_history::{LaunchHistory, LaunchOutcome}};
 use strum::IntoEnumIterator;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 
 use ustr::Ustr;
 
-use crate::{id_slab::{GetId, Id}, mod_metadata::ModMetadataManager, persistent::Persistent, BackendStateInstances, IoOrSerializationError};
+use crate::{id_slab::{GetId, Id}, lockfile::Lockfile, mod_metadata::ModMetadataManager, persistent::Persistent, process_monitor::ProcessMonitor, process_record::ProcessRecord, BackendStateInstances, IoOrSerializationError};
+
+/// Number of worlds read between updates sent to the frontend while loading a saves folder
+const WORLD_LOAD_BATCH_SIZE: usize = 32;
+
+/// Locked in the instance folder while the game runs, shared between launcher processes
+pub const RUN_LOCK_FILENAME: &str = ".pandora_running.lock";
 
 #[derive(Debug)]
 pub struct Instance {
@@ -30,23 +38,44 @@ pub struct Instance {
     pub saves_path: Arc<Path>,
     pub name: Ustr,
     pub configuration: Persistent<InstanceConfiguration>,
+    pub launch_history: Persistent<LaunchHistory>,
 
     pub child: Option<Child>,
+    /// Game process left running by a launcher that crashed, adopted when the instance was loaded
+    pub reattached: Option<ProcessRecord>,
+    /// Held while the game is running so other launcher processes can't start the same instance
+    pub run_lock: Option<Lockfile>,
+    /// Set once the game has been asked to stop, it's force killed if still running at this time
+    pub stop_deadline: Option<Instant>,
 
     pub watching_dot_minecraft: bool,
     pub watching_server_dat: bool,
     pub watching_saves_dir: bool,
 
+    /// Whether the instance folder is on a network filesystem, where watching it is unreliable
+    pub network_drive: bool,
+    /// Whether the folders of the instance are watched for changes, otherwise they're polled
+    pub file_watching: bool,
+    /// Fingerprints of the polled paths as of the last poll, see `poll_fingerprint`
+    polled_fingerprints: HashMap<Arc<Path>, Option<u64>>,
+
+    /// Parent of the tokens of in-flight scans, replaced after being cancelled
+    load_cancel: CancellationToken,
+
     pub worlds_state: Arc<AtomicBridgeDataLoadState>,
     dirty_worlds: HashSet<Arc<Path>>,
     all_worlds_dirty: bool,
-    pending_worlds_load: Option<KeepAliveNotifySignalHandle>,
+    pending_worlds_load: Option<PendingLoad>,
     worlds: Option<Arc<[InstanceWorldSummary]>>,
+    /// Sizes of world folders, keyed by the modification time of level.dat since the game rewrites it on every save
+    world_sizes: HashMap<Arc<Path>, (SystemTime, u64)>,
 
     pub servers_state: Arc<AtomicBridgeDataLoadState>,
     dirty_servers: bool,
-    pending_servers_load: Option<KeepAliveNotifySignalHandle>,
+    pending_servers_load: Option<PendingLoad>,
     servers: Option<Arc<[InstanceServerSummary]>>,
+    /// Edits made to servers.dat while the game is running, the game may overwrite them with its own copy
+    pub pending_server_edits: Vec<ServerEdit>,
 
     content_generation: usize,
 
@@ -61,10 +90,17 @@ pub struct ContentFolderState {
     dirty_paths: HashSet<Arc<Path>>,
     all_dirty: bool,
     generation: usize,
-    pending_load: Option<KeepAliveNotifySignalHandle>,
+    pending_load: Option<PendingLoad>,
     summaries: Option<Arc<[InstanceContentSummary]>>,
 }
 
+/// A scan that is running, later scans of the same data wait for it or cancel it if its result is already outdated
+#[derive(Debug)]
+struct PendingLoad {
+    done: KeepAliveNotifySignalHandle,
+    cancel: CancellationToken,
+}
+
 #[derive(enum_map::Enum, Debug, strum::EnumIter, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ContentFolder {
     Mods,
@@ -171,7 +207,10 @@ impl Instance {
 
         self.name = path.file_name().unwrap().to_string_lossy().into_owned().into();
         self.root_path = path.into();
-        self.configuration = Persistent::load_or(path.join("info_v1.json").into(), self.configuration.get().clone());
+        self.configuration = Persistent::load_or(path.join("info_v1.json").into(), self.configuration.get().clone())
+            .with_write_behind(crate::write_behind::global().clone());
+        self.launch_history = Persistent::load(path.join("launch_history.json").into())
+            .with_write_behind(crate::write_behind::global().clone());
 
         let mut dot_minecraft_path = path.to_owned();
         dot_minecraft_path.push(".minecraft");
@@ -196,9 +235,16 @@ impl Instance {
         None
     }
 
+    pub fn loaded_content(&self, folder: ContentFolder) -> Option<&Arc<[InstanceContentSummary]>> {
+        self.content_state[folder].summaries.as_ref()
+    }
+
+    /// Loads the worlds of the instance. When every world needs to be read, `on_progress` is called with
+    /// the worlds loaded so far and the total number of worlds, so large saves folders show up incrementally
     pub async fn load_worlds(
         instances: Arc<RwLock<BackendStateInstances>>,
         id: InstanceID,
+        on_progress: impl Fn(Arc<[InstanceWorldSummary]>, usize) + Send + 'static,
     ) -> Option<(Arc<[InstanceWorldSummary]>, bool)> {
         let mut await_pending: Option<KeepAliveNotifySignalHandle> = None;
 
@@ -210,34 +256,43 @@ impl Instance {
             let mut guard = instances.write();
             let this = guard.instances.get_mut(id)?;
 
-            if let Some(pending) = &this.pending_worlds_load && !pending.is_notified() {
-                await_pending = Some(pending.clone());
+            if let Some(pending) = &this.pending_worlds_load && !pending.done.is_notified() {
+                // Every world has to be read again anyway, so the result of the pending scan would be thrown away
+                if this.all_worlds_dirty {
+                    pending.cancel.cancel();
+                }
+                await_pending = Some(pending.done.clone());
                 continue;
             }
 
-            if cfg!(debug_assertions) && (!this.watching_dot_minecraft || !this.watching_saves_dir) {
+            if cfg!(debug_assertions) && this.file_watching && (!this.watching_dot_minecraft || !this.watching_saves_dir) {
                 panic!("Must be watching .minecraft and .minecraft/saves");
             }
 
+            let cancel = this.load_cancel.child_token();
             let future = if let Some(last) = &this.worlds && !this.all_worlds_dirty {
                 if !this.dirty_worlds.is_empty() {
                     let dirty_worlds = std::mem::take(&mut this.dirty_worlds);
                     let last = last.clone();
                     tokio::task::spawn_blocking(move || {
-                        Self::load_worlds_dirty(dirty_worlds, last)
+                        Some(Self::load_worlds_dirty(dirty_worlds, last))
                     })
                 } else {
                     return Some((last.clone(), false));
                 }
             } else {
                 let saves_path = this.saves_path.clone();
+                let cancel = cancel.clone();
                 tokio::task::spawn_blocking(move || {
-                    Self::load_worlds_all(&saves_path)
+                    Self::load_worlds_all(&saves_path, &cancel, on_progress)
                 })
             };
 
             let keep_alive = KeepAliveNotifySignal::new();
-            this.pending_worlds_load = Some(keep_alive.create_handle());
+            this.pending_worlds_load = Some(PendingLoad {
+                done: keep_alive.create_handle(),
+                cancel,
+            });
 
             this.worlds_state.store(BridgeDataLoadState::Loading, Ordering::Release);
             this.all_worlds_dirty = false;
@@ -251,6 +306,13 @@ impl Instance {
         let mut guard = instances.write();
         let this = guard.instances.get_mut(id)?;
 
+        let Some(result) = result else {
+            this.all_worlds_dirty = true;
+            set_cancelled_state(&this.worlds_state, this.worlds.is_some());
+            keep_alive.notify();
+            return None;
+        };
+
         cas_update(&this.worlds_state, |old_state| match old_state {
             BridgeDataLoadState::LoadingDirty => BridgeDataLoadState::LoadedDirty,
             BridgeDataLoadState::Loading => BridgeDataLoadState::Loaded,
@@ -262,33 +324,39 @@ impl Instance {
         Some((result, true))
     }
 
-    fn load_worlds_all(saves_path: &Path) -> Arc<[InstanceWorldSummary]> {
+    fn load_worlds_all(
+        saves_path: &Path,
+        cancel: &CancellationToken,
+        on_progress: impl Fn(Arc<[InstanceWorldSummary]>, usize),
+    ) -> Option<Arc<[InstanceWorldSummary]>> {
         log::info!("Loading all worlds in {:?}", saves_path);
 
         let Ok(directory) = std::fs::read_dir(&saves_path) else {
-            return [].into();
+            return Some([].into());
         };
 
-        let mut count = 0;
-        let mut summaries = Vec::with_capacity(64);
-
+        let mut world_paths = Vec::new();
         for entry in directory {
-            if count >= 64 {
-                break;
-            }
-
             let Ok(entry) = entry else {
                 log::error!("Error reading directory in saves folder: {:?}", entry.unwrap_err());
                 continue;
             };
             let path = entry.path();
-            if !path.is_dir() {
-                continue;
+            if path.is_dir() {
+                world_paths.push(path);
             }
+        }
+
+        let total = world_paths.len();
+        let mut summaries = Vec::with_capacity(total);
 
-            count += 1;
+        for (index, path) in world_paths.iter().enumerate() {
+            if cancel.is_cancelled() {
+                log::info!("Cancelled loading worlds in {:?}", saves_path);
+                return None;
+            }
 
-            match load_world_summary(&path) {
+            match load_world_summary(path) {
                 Ok(summary) => {
                     summaries.push(summary);
                 },
@@ -296,32 +364,31 @@ impl Instance {
                     log::error!("Error loading world summary: {:?}", err);
                 },
             }
+
+            let loaded = index + 1;
+            if loaded % WORLD_LOAD_BATCH_SIZE == 0 && loaded < total {
+                let mut partial = summaries.clone();
+                partial.sort_by_key(|s| -s.last_played);
+                on_progress(partial.into(), total);
+            }
         }
 
         summaries.sort_by_key(|s| -s.last_played);
 
-        summaries.into()
+        Some(summaries.into())
     }
 
     fn load_worlds_dirty(dirty: HashSet<Arc<Path>>, last: Arc<[InstanceWorldSummary]>) -> Arc<[InstanceWorldSummary]> {
         log::debug!("Loading changed worlds");
         log::trace!("Changed worlds: {:?}", dirty);
 
-        let mut summaries = Vec::with_capacity(64);
-
-        let mut count = 0;
+        let mut summaries = Vec::with_capacity(last.len() + dirty.len());
 
         for path in dirty.iter() {
-            if count >= 64 {
-                break;
-            }
-
             if !path.is_dir() {
                 continue;
             }
 
-            count += 1;
-
             match load_world_summary(path) {
                 Ok(summary) => {
                     summaries.push(summary);
@@ -340,10 +407,6 @@ impl Instance {
 
         summaries.sort_by_key(|s| -s.last_played);
 
-        if summaries.len() > 64 {
-            summaries.truncate(64);
-        }
-
         summaries.into()
     }
 
@@ -361,26 +424,34 @@ impl Instance {
             let mut guard = instances.write();
             let this = guard.instances.get_mut(id)?;
 
-            if let Some(pending) = &this.pending_servers_load && !pending.is_notified() {
-                await_pending = Some(pending.clone());
+            if let Some(pending) = &this.pending_servers_load && !pending.done.is_notified() {
+                if this.dirty_servers {
+                    pending.cancel.cancel();
+                }
+                await_pending = Some(pending.done.clone());
                 continue;
             }
 
-            if cfg!(debug_assertions) && (!this.watching_dot_minecraft || !this.watching_server_dat) {
+            if cfg!(debug_assertions) && this.file_watching && (!this.watching_dot_minecraft || !this.watching_server_dat) {
                 panic!("Must be watching .minecraft and .minecraft/servers.dat");
             }
 
+            let cancel = this.load_cancel.child_token();
             let future = if let Some(last) = &this.servers && !this.dirty_servers {
                 return Some((last.clone(), false));
             } else {
                 let server_dat_path = this.server_dat_path.clone();
+                let cancel = cancel.clone();
                 tokio::task::spawn_blocking(move || {
-                    Self::load_servers_all(&server_dat_path)
+                    Self::load_servers_all(&server_dat_path, &cancel)
                 })
             };
 
             let keep_alive = KeepAliveNotifySignal::new();
-            this.pending_servers_load = Some(keep_alive.create_handle());
+            this.pending_servers_load = Some(PendingLoad {
+                done: keep_alive.create_handle(),
+                cancel,
+            });
 
             this.servers_state.store(BridgeDataLoadState::Loading, Ordering::Release);
             this.dirty_servers = false;
@@ -393,6 +464,13 @@ impl Instance {
         let mut guard = instances.write();
         let this = guard.instances.get_mut(id)?;
 
+        let Some(result) = result else {
+            this.dirty_servers = true;
+            set_cancelled_state(&this.servers_state, this.servers.is_some());
+            keep_alive.notify();
+            return None;
+        };
+
         cas_update(&this.servers_state, |old_state| match old_state {
             BridgeDataLoadState::LoadingDirty => BridgeDataLoadState::LoadedDirty,
             BridgeDataLoadState::Loading => BridgeDataLoadState::Loaded,
@@ -404,11 +482,15 @@ impl Instance {
         Some((result, true))
     }
 
-    fn load_servers_all(server_dat_path: &Path) -> Arc<[InstanceServerSummary]> {
+    fn load_servers_all(server_dat_path: &Path, cancel: &CancellationToken) -> Option<Arc<[InstanceServerSummary]>> {
+        if cancel.is_cancelled() {
+            return None;
+        }
+
         log::info!("Loading servers from {:?}", server_dat_path);
 
         if !server_dat_path.is_file() {
-            return Arc::from([]);
+            return Some(Arc::from([]));
         }
 
         let result = match load_servers_summary(&server_dat_path) {
@@ -419,7 +501,7 @@ impl Instance {
             },
         };
 
-        result
+        Some(result)
     }
 
     pub async fn load_content(
@@ -439,18 +521,22 @@ impl Instance {
             let this = guard.instances.get_mut(id)?;
             let state = &mut this.content_state[content_folder];
 
-            if let Some(pending) = &state.pending_load && !pending.is_notified() {
-                await_pending = Some(pending.clone());
+            if let Some(pending) = &state.pending_load && !pending.done.is_notified() {
+                if state.all_dirty {
+                    pending.cancel.cancel();
+                }
+                await_pending = Some(pending.done.clone());
                 continue;
             }
 
+            let cancel = this.load_cancel.child_token();
             let future = if let Some(last) = &state.summaries && !state.all_dirty {
                 if !state.dirty_paths.is_empty() {
                     let dirty_paths = std::mem::take(&mut state.dirty_paths);
                     let mod_metadata_manager = mod_metadata_manager.clone();
                     let last = last.clone();
                     tokio::task::spawn_blocking(move || {
-                        Self::load_content_dirty(dirty_paths, mod_metadata_manager, last)
+                        Some(Self::load_content_dirty(dirty_paths, mod_metadata_manager, last))
                     })
                 } else {
                     return Some((last.clone(), false));
@@ -458,13 +544,17 @@ impl Instance {
             } else {
                 let path = state.path.clone();
                 let mod_metadata_manager = mod_metadata_manager.clone();
+                let cancel = cancel.clone();
                 tokio::task::spawn_blocking(move || {
-                    Self::load_content_all(&path, mod_metadata_manager)
+                    Self::load_content_all(&path, &cancel, mod_metadata_manager)
                 })
             };
 
             let keep_alive = KeepAliveNotifySignal::new();
-            state.pending_load = Some(keep_alive.create_handle());
+            state.pending_load = Some(PendingLoad {
+                done: keep_alive.create_handle(),
+                cancel,
+            });
 
             state.load_state.store(BridgeDataLoadState::Loading, Ordering::Release);
             state.all_dirty = false;
@@ -473,12 +563,20 @@ impl Instance {
             break (future, keep_alive);
         };
 
-        let mut result = future.await.unwrap();
+        let result = future.await.unwrap();
 
         let mut guard = instances.write();
         let this = guard.instances.get_mut(id)?;
         let state = &mut this.content_state[content_folder];
 
+        let Some(mut result) = result else {
+            state.all_dirty = true;
+            state.pending_load = None;
+            set_cancelled_state(&state.load_state, state.summaries.is_some());
+            keep_alive.notify();
+            return None;
+        };
+
         cas_update(&state.load_state, |old_state| match old_state {
             BridgeDataLoadState::LoadingDirty => BridgeDataLoadState::LoadedDirty,
             BridgeDataLoadState::Loading => BridgeDataLoadState::Loaded,
@@ -501,11 +599,11 @@ impl Instance {
         Some((result, true))
     }
 
-    fn load_content_all(path: &Path, mod_metadata_manager: Arc<ModMetadataManager>) -> Vec<InstanceContentSummary> {
+    fn load_content_all(path: &Path, cancel: &CancellationToken, mod_metadata_manager: Arc<ModMetadataManager>) -> Option<Vec<InstanceContentSummary>> {
         log::info!("Loading all content from {:?}", path);
 
         let Ok(directory) = std::fs::read_dir(&path) else {
-            return Vec::new();
+            return Some(Vec::new());
         };
 
         let mut summaries = Vec::with_capacity(32);
@@ -513,6 +611,13 @@ impl Instance {
         // todo: multithread?
 
         for entry in directory {
+            if cancel.is_cancelled() {
+                log::info!("Cancelled loading content from {:?}", path);
+                // The hashes read so far are still correct
+                mod_metadata_manager.save_file_hashes();
+                return None;
+            }
+
             let Ok(entry) = entry else {
                 log::error!("Error reading file in content folder: {:?}", entry.unwrap_err());
                 continue;
@@ -523,12 +628,14 @@ impl Instance {
             }
         }
 
+        mod_metadata_manager.save_file_hashes();
+
         summaries.sort_by(|a, b| {
             a.content_summary.id.cmp(&b.content_summary.id)
                 .then_with(|| lexical_sort::natural_lexical_cmp(&a.filename, &b.filename).reverse())
         });
 
-        summaries
+        Some(summaries)
     }
 
     fn load_content_dirty(
@@ -615,6 +722,8 @@ impl Instance {
             }
         }
 
+        mod_metadata_manager.save_file_hashes();
+
         summaries.sort_by(|a, b| {
             a.content_summary.id.cmp(&b.content_summary.id)
                 .then_with(|| a.filename.cmp(&b.filename).reverse())
@@ -633,7 +742,10 @@ impl Instance {
 
         let info_path: Arc<Path> = path.join("info_v1.json").into();
 
-        let instance_info: Persistent<InstanceConfiguration> = Persistent::try_load(info_path.clone())?;
+        let instance_info: Persistent<InstanceConfiguration> = Persistent::try_load(info_path.clone())?
+            .with_write_behind(crate::write_behind::global().clone());
+        let launch_history = Persistent::load(path.join("launch_history.json").into())
+            .with_write_behind(crate::write_behind::global().clone());
 
         let mut dot_minecraft_path = path.to_owned();
         dot_minecraft_path.push(".minecraft");
@@ -653,23 +765,35 @@ impl Instance {
             saves_path: saves_path.into(),
             name: path.file_name().unwrap().to_string_lossy().into_owned().into(),
             configuration: instance_info,
+            launch_history,
 
             child: None,
+            reattached: None,
+            run_lock: None,
+            stop_deadline: None,
 
             watching_dot_minecraft: false,
             watching_server_dat: false,
             watching_saves_dir: false,
 
+            network_drive: crate::network_fs::is_network_path(path),
+            file_watching: false,
+            polled_fingerprints: HashMap::new(),
+
+            load_cancel: CancellationToken::new(),
+
             worlds_state: Arc::new(AtomicBridgeDataLoadState::new(BridgeDataLoadState::Unloaded)),
             dirty_worlds: HashSet::new(),
             all_worlds_dirty: true,
             pending_worlds_load: None,
             worlds: None,
+            world_sizes: HashMap::new(),
 
             servers_state: Arc::new(AtomicBridgeDataLoadState::new(BridgeDataLoadState::Unloaded)),
             dirty_servers: true,
             pending_servers_load: None,
             servers: None,
+            pending_server_edits: Vec::new(),
 
             content_generation: 0,
 
@@ -697,6 +821,98 @@ impl Instance {
         });
     }
 
+    /// Whether the path is a world folder directly inside this instance's saves. The check is on whole
+    /// components, so paths like `saves/..` or `saves/a/../../b` aren't treated as worlds
+    pub fn contains_world(&self, level_path: &Path) -> bool {
+        is_world_in(&self.saves_path, level_path)
+    }
+
+    pub fn cached_world_size(&self, level_path: &Path, level_dat_modified: SystemTime) -> Option<u64> {
+        match self.world_sizes.get(level_path) {
+            Some((modified, size)) if *modified == level_dat_modified => Some(*size),
+            _ => None,
+        }
+    }
+
+    pub fn cache_world_size(&mut self, level_path: Arc<Path>, level_dat_modified: SystemTime, size: u64) {
+        self.world_sizes.insert(level_path, (level_dat_modified, size));
+    }
+
+    /// Stops the in-flight scans of worlds, servers and content, e.g. because nothing is showing them anymore
+    pub fn cancel_loads(&mut self) {
+        self.load_cancel.cancel();
+        self.load_cancel = CancellationToken::new();
+    }
+
+    pub fn mark_all_dirty(&mut self) {
+        self.mark_world_dirty(None);
+        self.mark_servers_dirty();
+        for folder in ContentFolder::iter() {
+            self.content_state[folder].mark_dirty(None);
+        }
+    }
+
+    /// Whether the folders of the instance should be watched, the setting of the instance wins over the
+    /// network drive detection but watching can also be turned off for every instance
+    pub fn should_watch_files(&mut self, disabled_globally: bool) -> bool {
+        !disabled_globally && self.configuration.get().file_watching.unwrap_or(!self.network_drive)
+    }
+
+    /// Paths whose fingerprint is compared by `update_polled` while the instance isn't watched
+    pub fn polled_paths(&self) -> Vec<Arc<Path>> {
+        let mut paths = vec![self.saves_path.clone(), self.server_dat_path.clone()];
+        for folder in ContentFolder::iter() {
+            paths.push(self.content_state[folder].path.clone());
+        }
+        paths
+    }
+
+    /// Summarizes the modification time of a polled path along with the name, size and modification time of
+    /// its direct entries. Replacing a file in place doesn't change the modification time of its folder, and
+    /// worlds are changed inside their own folders. None if the path doesn't exist
+    pub fn poll_fingerprint(path: &Path) -> Option<u64> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let mut hasher = DefaultHasher::new();
+        metadata.modified().ok().hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+
+        if metadata.is_dir() && let Ok(read_dir) = std::fs::read_dir(path) {
+            // Network filesystems don't always list entries in the same order
+            let mut entries: Vec<_> = read_dir.flatten().map(|entry| {
+                let metadata = entry.metadata().ok();
+                let modified = metadata.as_ref().and_then(|metadata| metadata.modified().ok());
+                (entry.file_name(), metadata.map(|metadata| metadata.len()), modified)
+            }).collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+            entries.hash(&mut hasher);
+        }
+
+        Some(hasher.finish())
+    }
+
+    /// Marks the data of a polled path as dirty if its fingerprint changed since the last poll,
+    /// the first poll of a path only records its fingerprint
+    pub fn update_polled(&mut self, path: Arc<Path>, fingerprint: Option<u64>) -> bool {
+        let previous = self.polled_fingerprints.insert(path.clone(), fingerprint);
+        if previous.is_none_or(|previous| previous == fingerprint) {
+            return false;
+        }
+
+        if path == self.saves_path {
+            self.mark_world_dirty(None);
+        } else if path == self.server_dat_path {
+            self.mark_servers_dirty();
+        } else if let Some(folder) = ContentFolder::iter().find(|folder| self.content_state[*folder].path == path) {
+            self.content_state[folder].mark_dirty(None);
+        }
+        true
+    }
+
+    /// Forgets the fingerprints of the last poll, e.g. after watching was turned back on
+    pub fn clear_polled(&mut self) {
+        self.polled_fingerprints.clear();
+    }
+
     pub fn mark_servers_dirty(&mut self) {
         if self.dirty_servers {
             return;
@@ -716,11 +932,126 @@ impl Instance {
         self.root_path = new.root_path;
         self.name = new.name;
         self.configuration = new.configuration;
+        self.launch_history = new.launch_history;
+    }
+
+    pub fn finish_launch_history(&mut self, outcome: LaunchOutcome, exit_code: Option<i32>) {
+        // The game can't overwrite servers.dat anymore
+        self.pending_server_edits.clear();
+
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let started_at = self.launch_history.get().entries.iter().rev()
+            .find(|entry| entry.outcome == LaunchOutcome::Running)
+            .map(|entry| entry.started_at);
+        let suspected_mods = match started_at {
+            Some(started_at) if outcome == LaunchOutcome::Crashed => self.read_suspected_mods(started_at),
+            _ => None,
+        };
+
+        self.launch_history.modify(|history| {
+            if let Some(entry) = history.last_running_mut() {
+                entry.outcome = outcome;
+                entry.exit_code = exit_code;
+                entry.duration_secs = Some((now - entry.started_at).max(0) as u64 / 1000);
+                entry.suspected_mods = suspected_mods;
+            }
+        });
+    }
+
+    /// Parses the mods blamed by the newest crash report that was written since the launch started
+    fn read_suspected_mods(&self, started_at_millis: i64) -> Option<Arc<[SuspectedMod]>> {
+        let started_at = SystemTime::UNIX_EPOCH + Duration::from_millis(started_at_millis.max(0) as u64);
+
+        let read_dir = std::fs::read_dir(self.dot_minecraft_path.join("crash-reports")).ok()?;
+        let (_, newest) = read_dir.filter_map(Result::ok).filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            (modified >= started_at).then(|| (modified, entry.path()))
+        }).max_by_key(|(modified, _)| *modified)?;
+
+        let report = std::fs::read_to_string(&newest).ok()?;
+        let suspected_mods = schema::crash_report::parse_suspected_mods(&report);
+        if suspected_mods.is_empty() {
+            None
+        } else {
+            log::info!("Crash report {:?} suspects {} mod(s)", newest, suspected_mods.len());
+            Some(suspected_mods.into())
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.child.is_some() || self.reattached.is_some()
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        match &self.child {
+            Some(child) => Some(child.id()),
+            None => self.reattached.map(|record| record.pid),
+        }
+    }
+
+    /// Forgets the game process once it has exited or been killed
+    pub fn clear_process(&mut self) {
+        let had_process = self.child.take().is_some() | self.reattached.take().is_some();
+        if had_process {
+            ProcessRecord::remove(&self.root_path);
+        }
+        self.run_lock = None;
+        self.stop_deadline = None;
+    }
+
+    pub fn kill(&mut self, monitor: &mut ProcessMonitor) -> std::io::Result<()> {
+        let result = match (&mut self.child, self.reattached) {
+            (Some(child), _) => child.kill(),
+            (None, Some(record)) => monitor.kill(record),
+            (None, None) => Ok(()),
+        };
+        self.clear_process();
+        result
+    }
+
+    /// Adopts the game process if a previous launcher left it running, removing the record if it has exited since
+    pub fn reattach(&mut self, monitor: &mut ProcessMonitor) {
+        let Some(record) = ProcessRecord::read(&self.root_path) else {
+            self.clear_stale_launch_history(false);
+            return;
+        };
+        if !monitor.is_running(record) {
+            log::debug!("Removing stale process record for {}", self.name);
+            ProcessRecord::remove(&self.root_path);
+            self.clear_stale_launch_history(false);
+            return;
+        }
+
+        match Lockfile::try_create(self.root_path.join(RUN_LOCK_FILENAME).into()) {
+            Ok(Some(run_lock)) => self.run_lock = Some(run_lock),
+            // Another launcher that's still open started the game, it keeps track of it
+            Ok(None) => return,
+            Err(err) => log::warn!("Unable to create run lock for instance: {}", err),
+        }
+
+        log::info!("Reattached to game process {} of {}", record.pid, self.name);
+        self.reattached = Some(record);
+        self.clear_stale_launch_history(true);
+    }
+
+    /// Launches that are still marked as running but whose game exited while no launcher was watching it
+    /// would otherwise stay running forever
+    fn clear_stale_launch_history(&mut self, keep_newest: bool) {
+        let running = self.launch_history.get().running_count();
+        if running > usize::from(keep_newest) {
+            log::info!("Clearing {} stale running launch(es) of {}", running - usize::from(keep_newest), self.name);
+            self.launch_history.modify(|history| history.clear_stale_running(keep_newest));
+        }
     }
 
     pub fn status(&self) -> InstanceStatus {
-        if self.child.is_some() {
-            InstanceStatus::Running
+        if self.is_running() {
+            if self.stop_deadline.is_some() {
+                InstanceStatus::Stopping
+            } else {
+                InstanceStatus::Running
+            }
         } else {
             InstanceStatus::NotRunning
         }
@@ -739,6 +1070,14 @@ impl Instance {
             status,
         }
     }
+
+    pub fn create_file_watching_message(&self) -> MessageToFrontend {
+        MessageToFrontend::InstanceFileWatchingUpdated {
+            id: self.id,
+            watching: self.file_watching,
+            network_drive: self.network_drive,
+        }
+    }
 }
 
 fn create_instance_content_summary(path: &Path, mod_metadata_manager: &Arc<ModMetadataManager>) -> Option<InstanceContentSummary> {
@@ -759,11 +1098,7 @@ fn create_instance_content_summary(path: &Path, mod_metadata_manager: &Arc<ModMe
         log::trace!("Skipping content file {}, unknown extension", filename);
         return None;
     };
-    let Ok(mut file) = std::fs::File::open(&path) else {
-        return None;
-    };
-
-    let Some(summary) = mod_metadata_manager.get_file(&mut file) else {
+    let Some(summary) = mod_metadata_manager.get_path(path) else {
         return None;
     };
 
@@ -819,26 +1154,29 @@ fn read_disabled_children_for(path: &Path) -> Option<HashSet<String>> {
     Some(string.split_terminator('\n').map(str::to_string).collect())
 }
 
+#[derive(serde::Deserialize)]
+struct LevelDat {
+    #[serde(rename = "Data")]
+    data: LevelData,
+}
+
+#[derive(serde::Deserialize)]
+struct LevelData {
+    #[serde(rename = "LastPlayed")]
+    last_played: i64,
+    #[serde(rename = "LevelName", default)]
+    level_name: String,
+}
+
 fn load_world_summary(path: &Path) -> anyhow::Result<InstanceWorldSummary> {
     let level_dat_path = path.join("level.dat");
     if !level_dat_path.is_file() {
         anyhow::bail!("level.dat doesn't exist");
     }
 
-    let compressed = std::fs::read(&level_dat_path)?;
-
-    let mut decoder = flate2::bufread::GzDecoder::new(compressed.as_slice());
-
-    let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed)?;
+    let result = nbt::decode::read_maybe_compressed(std::fs::File::open(&level_dat_path)?)?;
 
-    let mut nbt_data = decompressed.as_slice();
-    let result = nbt::decode::read_named(&mut nbt_data)?;
-
-    let root = result.as_compound().context("Unable to get root compound")?;
-    let data = root.find_compound("Data").context("Unable to get Data")?;
-    let last_played: i64 = data.find_numeric("LastPlayed").context("Unable to get LastPlayed")?;
-    let level_name = data.find_string("LevelName").cloned().unwrap_or_default();
+    let LevelDat { data: LevelData { last_played, level_name } } = nbt::serialize::from_nbt(&result)?;
 
     let folder = path.file_name().context("Unable to get filename")?.to_string_lossy();
 
@@ -871,49 +1209,138 @@ fn load_world_summary(path: &Path) -> anyhow::Result<InstanceWorldSummary> {
     })
 }
 
-fn load_servers_summary(server_dat_path: &Path) -> anyhow::Result<Vec<InstanceServerSummary>> {
-    let raw = std::fs::read(server_dat_path)?;
+#[derive(serde::Deserialize)]
+struct LevelDetailsDat {
+    #[serde(rename = "Data")]
+    data: LevelDetailsData,
+}
 
-    let mut nbt_data = raw.as_slice();
-    let result = nbt::decode::read_named(&mut nbt_data)?;
+#[derive(serde::Deserialize)]
+struct LevelDetailsData {
+    #[serde(rename = "GameType", default)]
+    game_type: i32,
+    #[serde(default)]
+    hardcore: bool,
+    #[serde(rename = "Difficulty")]
+    difficulty: Option<i8>,
+    #[serde(rename = "allowCommands", default)]
+    allow_commands: bool,
+    /// Before 1.16 the seed is stored directly in Data
+    #[serde(rename = "RandomSeed")]
+    random_seed: Option<i64>,
+    #[serde(rename = "WorldGenSettings")]
+    world_gen_settings: Option<WorldGenSettings>,
+    #[serde(rename = "Version")]
+    version: Option<LevelVersion>,
+}
 
-    let root = result.as_compound().context("Unable to get root compound")?;
-    let servers = root.find_list("servers", nbt::TAG_COMPOUND_ID).context("Unable to get servers")?;
+#[derive(serde::Deserialize)]
+struct WorldGenSettings {
+    seed: i64,
+}
 
-    let mut summaries = Vec::with_capacity(servers.len());
+#[derive(serde::Deserialize)]
+struct LevelVersion {
+    #[serde(rename = "Name")]
+    name: String,
+}
 
-    for server in servers.iter() {
-        let server = server.as_compound().unwrap();
+/// Reads the details of the world at `path`. Walking the world folder can be slow for large worlds,
+/// so the size is only computed if `cached_size` is None
+pub fn load_world_details(path: &Path, cached_size: Option<u64>) -> anyhow::Result<InstanceWorldDetails> {
+    let level_dat_path = path.join("level.dat");
+    let result = nbt::decode::read_maybe_compressed(std::fs::File::open(&level_dat_path)?)?;
+    let LevelDetailsDat { data } = nbt::serialize::from_nbt(&result)?;
+
+    let seed = data.world_gen_settings.map(|settings| settings.seed).or(data.random_seed);
+
+    Ok(InstanceWorldDetails {
+        game_mode: WorldGameMode::from_id(data.game_type),
+        hardcore: data.hardcore,
+        difficulty: data.difficulty.and_then(WorldDifficulty::from_id),
+        seed,
+        version_name: data.version.map(|version| version.name.into()),
+        allow_commands: data.allow_commands,
+        size_bytes: cached_size.unwrap_or_else(|| crate::storage::directory_size(path)),
+    })
+}
 
-        if let Some(hidden) = server.find_byte("hidden")
-            && *hidden != 0
-        {
-            continue;
-        }
+#[derive(serde::Deserialize)]
+struct LevelVersionDat {
+    #[serde(rename = "Data")]
+    data: LevelVersionData,
+}
 
-        let Some(ip) = server.find_string("ip") else {
-            continue;
-        };
+fn is_world_in(saves: &Path, level_path: &Path) -> bool {
+    let mut components = level_path.components();
+    matches!(components.next_back(), Some(Component::Normal(_))) && components.as_path() == saves
+}
+
+#[derive(serde::Deserialize)]
+struct LevelVersionData {
+    #[serde(rename = "DataVersion")]
+    data_version: Option<i32>,
+    #[serde(rename = "Version")]
+    version: Option<LevelVersion>,
+}
+
+/// Reads the name and DataVersion of the version the world was last played in
+pub fn load_world_version(path: &Path) -> anyhow::Result<(Option<String>, Option<i32>)> {
+    let result = nbt::decode::read_maybe_compressed(std::fs::File::open(path.join("level.dat"))?)?;
+    let LevelVersionDat { data } = nbt::serialize::from_nbt(&result)?;
+    Ok((data.version.map(|version| version.name), data.data_version))
+}
 
-        let name: Arc<str> = server
-            .find_string("name")
-            .map(|v| Arc::from(v.as_str()))
-            .unwrap_or_else(|| Arc::from("<unnamed>"));
+#[derive(serde::Deserialize)]
+struct ServersDat {
+    servers: Vec<ServerEntry>,
+}
+
+#[derive(serde::Deserialize)]
+struct ServerEntry {
+    #[serde(default)]
+    hidden: bool,
+    ip: Option<String>,
+    name: Option<String>,
+    icon: Option<String>,
+}
 
+fn load_servers_summary(server_dat_path: &Path) -> anyhow::Result<Vec<InstanceServerSummary>> {
+    let result = nbt::decode::read_maybe_compressed(std::fs::File::open(server_dat_path)?)?;
+
+    let servers: ServersDat = nbt::serialize::from_nbt(&result)?;
+
+    let summaries = servers.servers.into_iter().filter_map(|server| {
+        if server.hidden {
+            return None;
+        }
+
+        let ip = server.ip?;
+        let name: Arc<str> = server.name.map(Arc::from).unwrap_or_else(|| Arc::from("<unnamed>"));
         let icon = server
-            .find_string("icon")
+            .icon
             .and_then(|v| base64::engine::general_purpose::STANDARD.decode(v).map(Arc::from).ok());
 
-        summaries.push(InstanceServerSummary {
+        Some(InstanceServerSummary {
             name,
-            ip: Arc::from(ip.as_str()),
+            ip: Arc::from(ip),
             png_icon: icon,
-        });
-    }
+        })
+    }).collect();
 
     Ok(summaries)
 }
 
+/// Cancelled scans leave the data dirty, so it's scanned again the next time it's requested
+fn set_cancelled_state(state: &Arc<AtomicBridgeDataLoadState>, has_previous: bool) {
+    let new_state = if has_previous {
+        BridgeDataLoadState::LoadedDirty
+    } else {
+        BridgeDataLoadState::Unloaded
+    };
+    state.store(new_state, Ordering::Release);
+}
+
 fn cas_update(state: &Arc<AtomicBridgeDataLoadState>, func: impl Fn(BridgeDataLoadState) -> BridgeDataLoadState) {
     let mut old_state = state.load(Ordering::Acquire);
     loop {
@@ -929,3 +1356,73 @@ fn cas_update(state: &Arc<AtomicBridgeDataLoadState>, func: impl Fn(BridgeDataLo
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use schema::loader::Loader;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn load_instance(dir: &Path) -> Instance {
+        let configuration = InstanceConfiguration::new(Ustr::from("1.21.1"), Loader::Vanilla);
+        std::fs::write(dir.join("info_v1.json"), serde_json::to_vec(&configuration).unwrap()).unwrap();
+        Instance::load_from_folder(dir).unwrap()
+    }
+
+    fn poll(instance: &mut Instance) -> bool {
+        let mut changed = false;
+        for path in instance.polled_paths() {
+            let fingerprint = Instance::poll_fingerprint(&path);
+            changed |= instance.update_polled(path, fingerprint);
+        }
+        changed
+    }
+
+    #[test]
+    fn worlds_must_be_inside_saves() {
+        let saves = Path::new("/instances/a/.minecraft/saves");
+        assert!(is_world_in(saves, &saves.join("New World")));
+        assert!(!is_world_in(saves, saves));
+        assert!(!is_world_in(saves, &saves.join("..")));
+        assert!(!is_world_in(saves, &saves.join("New World").join("..").join("..").join("mods")));
+        assert!(!is_world_in(saves, &saves.join("New World").join("region")));
+        assert!(!is_world_in(saves, Path::new("/instances/b/.minecraft/saves/New World")));
+    }
+
+    #[test]
+    fn first_poll_only_records() {
+        let dir = TempDir::new("polled-first");
+        let mut instance = load_instance(&dir);
+
+        assert!(!poll(&mut instance));
+        assert!(!poll(&mut instance));
+    }
+
+    #[test]
+    fn replaced_file_is_noticed() {
+        let dir = TempDir::new("polled-replaced");
+        let mods = dir.join(".minecraft").join("mods");
+        std::fs::create_dir_all(&mods).unwrap();
+        std::fs::write(mods.join("example.jar"), b"old").unwrap();
+        let mut instance = load_instance(&dir);
+        assert!(!poll(&mut instance));
+
+        // Overwriting a file in place keeps the modification time of the folder
+        std::fs::write(mods.join("example.jar"), b"newer").unwrap();
+
+        assert!(poll(&mut instance));
+        assert!(!poll(&mut instance));
+    }
+
+    #[test]
+    fn created_folder_is_noticed() {
+        let dir = TempDir::new("polled-created");
+        let mut instance = load_instance(&dir);
+        assert!(!poll(&mut instance));
+
+        std::fs::create_dir_all(dir.join(".minecraft").join("saves").join("New World")).unwrap();
+
+        assert!(poll(&mut instance));
+    }
+}
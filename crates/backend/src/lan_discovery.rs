@@ -0,0 +1,101 @@
+use std::{net::{Ipv4Addr, SocketAddr}, sync::Arc, time::{Duration, Instant}};
+
+use bridge::{handle::FrontendHandle, message::{LanGame, MessageToFrontend}};
+use rustc_hash::FxHashMap;
+
+const MULTICAST_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 2, 60);
+const PORT: u16 = 4445;
+/// Games announce themselves every 1.5 seconds, so one that has missed a few announcements has been closed
+const EXPIRE_AFTER: Duration = Duration::from_secs(5);
+
+/// Reports the worlds opened to LAN on the local network until the task is aborted
+pub async fn listen(send: FrontendHandle) {
+    let socket = match bind() {
+        Ok(socket) => socket,
+        Err(error) => {
+            log::warn!("Unable to listen for LAN games: {}", error);
+            return;
+        },
+    };
+
+    let mut games: FxHashMap<Arc<str>, (LanGame, Instant)> = FxHashMap::default();
+    let mut buf = [0_u8; 1024];
+    let mut expire_interval = tokio::time::interval(Duration::from_secs(1));
+    expire_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        let changed = tokio::select! {
+            received = socket.recv_from(&mut buf) => {
+                let (len, from) = match received {
+                    Ok(received) => received,
+                    Err(error) => {
+                        log::debug!("Error receiving LAN announcement: {}", error);
+                        continue;
+                    },
+                };
+                let text = String::from_utf8_lossy(&buf[..len]);
+                let Some((motd, port)) = parse_announcement(&text) else {
+                    continue;
+                };
+
+                let address: Arc<str> = SocketAddr::new(from.ip(), port).to_string().into();
+                let game = LanGame { motd: motd.into(), address: address.clone() };
+                let previous = games.insert(address, (game.clone(), Instant::now()));
+                previous.is_none_or(|(previous, _)| previous != game)
+            },
+            _ = expire_interval.tick() => {
+                let count = games.len();
+                games.retain(|_, (_, last_seen)| last_seen.elapsed() < EXPIRE_AFTER);
+                games.len() != count
+            },
+        };
+
+        if changed {
+            let mut list: Vec<LanGame> = games.values().map(|(game, _)| game.clone()).collect();
+            list.sort_by(|a, b| a.motd.cmp(&b.motd).then_with(|| a.address.cmp(&b.address)));
+            send.send(MessageToFrontend::LanGamesUpdated { games: list.into() });
+        }
+    }
+}
+
+fn bind() -> std::io::Result<tokio::net::UdpSocket> {
+    let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::DGRAM, Some(socket2::Protocol::UDP))?;
+    // The game listens on the same port while its multiplayer screen is open
+    socket.set_reuse_address(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, PORT)).into())?;
+    socket.join_multicast_v4(&MULTICAST_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_nonblocking(true)?;
+    tokio::net::UdpSocket::from_std(socket.into())
+}
+
+/// Announcements look like `[MOTD]Player - World[/MOTD][AD]51234[/AD]`, the host is whoever sent them
+fn parse_announcement(text: &str) -> Option<(&str, u16)> {
+    let port = between(text, "[AD]", "[/AD]")?.trim().parse().ok()?;
+    let motd = between(text, "[MOTD]", "[/MOTD]").unwrap_or("LAN World");
+    Some((motd, port))
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = text.split_once(start)?;
+    let (value, _) = rest.split_once(end)?;
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_announcements() {
+        assert_eq!(parse_announcement("[MOTD]Steve - New World[/MOTD][AD]51234[/AD]"), Some(("Steve - New World", 51234)));
+        assert_eq!(parse_announcement("[AD]25565[/AD]"), Some(("LAN World", 25565)));
+    }
+
+    #[test]
+    fn rejects_invalid_announcements() {
+        assert_eq!(parse_announcement("[MOTD]Steve - New World[/MOTD]"), None);
+        assert_eq!(parse_announcement("[MOTD]Steve - New World[/MOTD][AD]not a port[/AD]"), None);
+        assert_eq!(parse_announcement("[MOTD]Steve - New World[/MOTD][AD]70000[/AD]"), None);
+        assert_eq!(parse_announcement("[AD]25565"), None);
+    }
+}
@@ -1,19 +1,19 @@
 use std::{
-    borrow::Cow, cmp::Ordering, collections::{BTreeSet, HashMap, HashSet}, ffi::{OsStr, OsString}, fs::File, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, process::{Child, Stdio}, sync::{Arc, OnceLock, atomic::AtomicBool}
+    borrow::Cow, cmp::Ordering, collections::{BTreeSet, HashMap, HashSet}, ffi::{OsStr, OsString}, fs::File, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, process::{Child, Stdio}, sync::{Arc, LazyLock, OnceLock, atomic::{AtomicBool, AtomicUsize}}
 };
 
 use bridge::{
-    handle::FrontendHandle, message::{MessageToFrontend, QuickPlayLaunch}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType, ProgressTrackers}, safe_path::SafePath
+    handle::FrontendHandle, message::{LaunchPreview, MessageToFrontend, QuickPlayLaunch}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType, ProgressTrackerUnit, ProgressTrackers}, safe_path::SafePath
 };
 use futures::{FutureExt, TryFutureExt};
+use parking_lot::{Mutex, RwLock};
 use rand::seq::SliceRandom;
 use rc_zip_sync::{ArchiveHandle, ReadZip};
-use regex::Regex;
 use rustc_hash::FxHashMap;
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, forge::{ForgeInstallProfile, ForgeInstallProfileLegacy, ForgeSide, VersionFragment}, instance::InstanceConfiguration, java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest}, loader::Loader, maven::{MavenCoordinate, MavenMetadataXml}, version::{
-        GameLibrary, GameLibraryArtifact, GameLibraryDownloads, GameLibraryExtractOptions, GameLogging, LaunchArgument, LaunchArgumentValue, MinecraftVersion, OsArch, OsName, PartialMinecraftVersion, Rule, RuleAction
-    }, version_manifest::MinecraftVersionManifest
+    assets_index::AssetsIndex, backend_config::{BackendSettings, MirrorConfig}, fabric_launch::FabricLaunch, forge::{ForgeInstallProfile, ForgeInstallProfileLegacy, ForgeSide, VersionFragment}, instance::InstanceConfiguration, java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest}, loader::Loader, maven::{MavenCoordinate, MavenMetadataXml}, version::{
+        GameLibrary, GameLibraryArtifact, GameLibraryDownloads, GameLibraryExtractOptions, GameLogging, LaunchArgument, LaunchArgumentValue, MinecraftVersion, PartialMinecraftVersion, Rule
+    }, version_manifest::MinecraftVersionManifest, rules::{RuleContext, RuleFeatureSet, RuleOsEnvironment}
 };
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
@@ -31,6 +31,8 @@ pub struct Launcher {
     directories: Arc<LauncherDirectories>,
     launch_wrapper: Arc<Path>,
     sender: FrontendHandle,
+    mirrors: Arc<RwLock<MirrorConfig>>,
+    settings: Arc<RwLock<BackendSettings>>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -61,28 +63,44 @@ pub enum LaunchError {
     CancelledByUser,
     #[error("Loader supports the wrong version of Minecraft: {0}")]
     MismatchedLoaderVersions(Arc<str>),
+    #[error("Custom versions can't be launched with a mod loader: {0}")]
+    LoaderOnCustomVersion(&'static str),
 }
 
-#[derive(PartialEq, Eq)]
 pub enum AddVanillaJar {
     Yes,
     No,
+    /// A patched client jar of a custom version, replacing the vanilla one
+    Custom(GameLibraryArtifact),
+}
+
+impl AddVanillaJar {
+    fn artifact(self, version_info: &MinecraftVersion, instance_info: &InstanceConfiguration) -> Option<GameLibraryArtifact> {
+        match self {
+            AddVanillaJar::Yes => Some(vanilla_jar_artifact(version_info, instance_info)),
+            AddVanillaJar::No => None,
+            AddVanillaJar::Custom(artifact) => Some(artifact),
+        }
+    }
 }
 
 impl Launcher {
-    pub fn new(meta: Arc<MetadataManager>, directories: Arc<LauncherDirectories>, sender: FrontendHandle) -> Self {
+    pub fn new(meta: Arc<MetadataManager>, directories: Arc<LauncherDirectories>, sender: FrontendHandle, mirrors: Arc<RwLock<MirrorConfig>>, settings: Arc<RwLock<BackendSettings>>) -> Self {
         let launch_wrapper = launch_wrapper::create_wrapper(&directories.temp_dir).into();
         Self {
             meta,
             directories,
             launch_wrapper,
             sender,
+            mirrors,
+            settings,
         }
     }
 
     pub async fn launch(
         &self,
         http_client: &reqwest::Client,
+        instance_name: Ustr,
         dot_minecraft_path: Arc<Path>,
         instance_info: InstanceConfiguration,
         quick_play: Option<QuickPlayLaunch>,
@@ -93,6 +111,58 @@ impl Launcher {
     ) -> Result<Child, LaunchError> {
         log::info!("Launching {:?}", dot_minecraft_path);
 
+        let (launch_context, version_info) = self.prepare_launch(http_client, instance_name, dot_minecraft_path, instance_info,
+            quick_play, login_info, add_mods, launch_tracker, modal_action).await?;
+
+        if modal_action.has_requested_cancel() {
+            self.sender.send(MessageToFrontend::CloseModal);
+            return Err(LaunchError::CancelledByUser);
+        }
+
+        log::info!("Launching game process");
+        let child = launch_context.launch(&version_info)?;
+
+        launch_tracker.add_count(1);
+
+        Ok(child)
+    }
+
+    /// Does everything a launch does except starting the game, and returns the command it would have run
+    #[allow(clippy::too_many_arguments)]
+    pub async fn preview_launch_command(
+        &self,
+        http_client: &reqwest::Client,
+        instance_name: Ustr,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        login_info: MinecraftLoginInfo,
+        add_mods: Vec<PathBuf>,
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<LaunchPreview, LaunchError> {
+        log::info!("Previewing launch command of {:?}", dot_minecraft_path);
+
+        let (launch_context, version_info) = self.prepare_launch(http_client, instance_name, dot_minecraft_path, instance_info,
+            None, login_info, add_mods, launch_tracker, modal_action).await?;
+
+        launch_tracker.add_count(1);
+
+        Ok(launch_context.preview_command(&version_info))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_launch(
+        &self,
+        http_client: &reqwest::Client,
+        instance_name: Ustr,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        quick_play: Option<QuickPlayLaunch>,
+        login_info: MinecraftLoginInfo,
+        add_mods: Vec<PathBuf>,
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<(LaunchContext, Arc<MinecraftVersion>), LaunchError> {
         launch_tracker.set_total(6);
 
         log::debug!("Creating launch version");
@@ -110,11 +180,7 @@ impl Launcher {
 
         let _ = std::fs::create_dir_all(&dot_minecraft_path);
 
-        let launch_rule_context = LaunchRuleContext {
-            is_demo_user: false,
-            custom_resolution: None,
-            quick_play,
-        };
+        let launch_rule_context = LaunchRuleContext::new(false, None, quick_play);
 
         let mut artifacts = Vec::new();
         let mut natives_to_extract = HashMap::new();
@@ -124,15 +190,7 @@ impl Launcher {
         let natives_dir = self.directories.temp_natives_base_dir.join(calculate_natives_dirname(&artifacts));
         let _ = std::fs::create_dir_all(&natives_dir);
 
-        if add_vanilla_jar == AddVanillaJar::Yes {
-            let client_download = &version_info.downloads.client;
-            artifacts.push(GameLibraryArtifact {
-                path: format!("net/minecraft/{0}/minecraft-client-{0}.jar", instance_info.minecraft_version).into(),
-                sha1: Some(client_download.sha1),
-                size: Some(client_download.size),
-                url: client_download.url,
-            });
-        }
+        artifacts.extend(add_vanilla_jar.artifact(&version_info, &instance_info));
 
         let mojang_java_binary_future = self.load_mojang_java_binary(
             &self.meta,
@@ -142,10 +200,11 @@ impl Launcher {
             &modal_action.trackers,
             launch_tracker,
         );
+        let repaired = Mutex::new(Vec::new());
         let load_assets_future =
-            self.load_assets(&self.meta, http_client, &dot_minecraft_path, &version_info, &modal_action.trackers, launch_tracker);
+            self.load_assets(&self.meta, http_client, &dot_minecraft_path, &version_info, &modal_action.trackers, launch_tracker, &repaired);
         let load_libraries_future =
-            self.load_libraries(http_client, &artifacts, &modal_action.trackers, launch_tracker);
+            self.load_libraries(http_client, &artifacts, &modal_action.trackers, launch_tracker, &repaired);
         let load_log_configuration = self.load_log_configuration(http_client, version_info.logging.as_ref());
 
         log::debug!("Loading java, assets, libraries and log configuration");
@@ -168,6 +227,11 @@ impl Launcher {
         launch_tracker.add_count(1);
         launch_tracker.notify();
 
+        let repaired = repaired.into_inner();
+        if !repaired.is_empty() {
+            log::info!("Downloaded {} missing or corrupt game files: {:?}", repaired.len(), repaired);
+        }
+
         let mut classpath = Vec::new();
         for (raw_path, library_path) in library_paths {
             if let Some(extract_options) = natives_to_extract.get(&raw_path) {
@@ -214,6 +278,24 @@ impl Launcher {
             }
         }
 
+        let env = if cfg!(target_os = "linux") && instance_info.discrete_gpu {
+            crate::gpu::discrete_gpu_env()
+        } else {
+            Vec::new()
+        };
+
+        let window = instance_info.window.as_ref().filter(|window| window.enabled).map(|window| {
+            let title = if window.title.trim_ascii().is_empty() {
+                instance_name.as_str().into()
+            } else {
+                window.title.clone()
+            };
+            GameWindowOptions {
+                title,
+                icon: window.icon.clone(),
+            }
+        });
+
         let launch_context = LaunchContext {
             launch_wrapper_path: self.launch_wrapper.clone(),
             java_path,
@@ -228,20 +310,63 @@ impl Launcher {
             log_configuration,
             rule_context: launch_rule_context,
             login_info,
-            add_mods
+            add_mods,
+            window,
+            env,
         };
 
-        if modal_action.has_requested_cancel() {
-            self.sender.send(MessageToFrontend::CloseModal);
-            return Err(LaunchError::CancelledByUser);
-        }
+        Ok((launch_context, version_info))
+    }
 
-        log::info!("Launching game process");
-        let child = launch_context.launch(&version_info)?;
+    /// Checks the client jar, libraries and assets of the instance's version, downloading anything missing
+    /// or corrupt. Returns the files that were downloaded, libraries by their path in the libraries folder
+    /// and assets by their name
+    pub async fn verify_game_files(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: &InstanceConfiguration,
+        verify_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<Vec<Arc<str>>, LaunchError> {
+        log::info!("Verifying game files of {:?}", dot_minecraft_path);
 
-        launch_tracker.add_count(1);
+        verify_tracker.set_total(3);
 
-        Ok(child)
+        let (version_info, add_vanilla_jar) = tokio::select! {
+            result = self.create_launch_version(http_client, &modal_action.trackers, verify_tracker, instance_info) => result?,
+            _ = modal_action.request_cancel.cancelled() => {
+                return Err(LaunchError::CancelledByUser);
+            }
+        };
+
+        verify_tracker.add_count(1);
+        verify_tracker.notify();
+
+        let launch_rule_context = LaunchRuleContext::new(false, None, None);
+
+        let mut artifacts = Vec::new();
+        let mut natives_to_extract = HashMap::new();
+        launch_rule_context.collect_libraries(&version_info.libraries, &mut artifacts, &mut natives_to_extract);
+
+        artifacts.extend(add_vanilla_jar.artifact(&version_info, instance_info));
+
+        let repaired = Mutex::new(Vec::new());
+        let joined = futures::future::try_join(
+            self.load_assets(&self.meta, http_client, &dot_minecraft_path, &version_info, &modal_action.trackers, verify_tracker, &repaired)
+                .map_err(LaunchError::from),
+            self.load_libraries(http_client, &artifacts, &modal_action.trackers, verify_tracker, &repaired)
+                .map_err(LaunchError::from),
+        );
+
+        tokio::select! {
+            result = joined => result?,
+            _ = modal_action.request_cancel.cancelled() => {
+                return Err(LaunchError::CancelledByUser);
+            }
+        };
+
+        Ok(repaired.into_inner())
     }
 
     async fn create_launch_version(
@@ -251,11 +376,26 @@ impl Launcher {
         launch_tracker: &ProgressTracker,
         instance_info: &InstanceConfiguration,
     ) -> Result<(Arc<MinecraftVersion>, AddVanillaJar), LaunchError> {
+        if instance_info.loader != Loader::Vanilla && self.meta.has_local_version(&instance_info.minecraft_version) {
+            return Err(LaunchError::LoaderOnCustomVersion(instance_info.minecraft_version.as_str()));
+        }
+
         match instance_info.loader {
             Loader::Vanilla => {
                 launch_tracker.add_total(1);
                 launch_tracker.notify();
 
+                if let Some((version, client_jar)) = self.meta.fetch_local_version(instance_info.minecraft_version).await? {
+                    launch_tracker.add_count(1);
+                    launch_tracker.notify();
+
+                    let add_vanilla_jar = match client_jar {
+                        Some(client_jar) => AddVanillaJar::Custom(self.custom_client_jar_artifact(instance_info.minecraft_version, &client_jar)?),
+                        None => AddVanillaJar::Yes,
+                    };
+                    return Ok((version, add_vanilla_jar));
+                }
+
                 let versions = self.meta.fetch(&MinecraftVersionManifestMetadataItem).await?;
 
                 launch_tracker.add_count(1);
@@ -462,37 +602,7 @@ impl Launcher {
         let loader_version = if let Some(preferred_loader_version) = instance_info.preferred_loader_version {
             preferred_loader_version
         } else {
-            let mut minecraft_version_parts = VersionFragment::string_to_parts(instance_info.minecraft_version.as_str());
-            if neoforge_versioning {
-                // 1.21.5 -> 21.5
-                // 25w14craftmine -> 0.25w14craftmine
-                // 1.21 -> 21.0
-                // 26.1 -> 26.1.0
-                if minecraft_version_parts[0] == VersionFragment::String("25w14craftmine".into()) {
-                    minecraft_version_parts.insert(0, VersionFragment::Number(0))
-                } else {
-                    if minecraft_version_parts.len() < 3 {
-                        minecraft_version_parts.push(VersionFragment::Number(0))
-                    }
-                    if minecraft_version_parts[0] == VersionFragment::Number(1) {
-                        minecraft_version_parts.remove(0);
-                    }
-                }
-            }
-
-            let mut latest_loader_version = None;
-            let mut latest_loader_version_parts = Vec::new();
-            for version in loader_versions.iter() {
-                let parts = VersionFragment::string_to_parts(version);
-
-                if parts.starts_with(&minecraft_version_parts) {
-                    if parts > latest_loader_version_parts {
-                        latest_loader_version_parts = parts;
-                        latest_loader_version = Some(version.clone());
-                    }
-                }
-            }
-            let Some(latest_loader_version) = latest_loader_version else {
+            let Some(latest_loader_version) = latest_forgelike_loader_version(instance_info.minecraft_version, loader_versions, neoforge_versioning) else {
                 return Err(LaunchError::CantFindVersion(instance_info.minecraft_version.as_str()));
             };
 
@@ -534,7 +644,8 @@ impl Launcher {
             progress_trackers,
             launch_tracker,
         );
-        let load_installer_library_future = self.load_libraries(http_client, artifacts, progress_trackers, launch_tracker);
+        let installer_repaired = Mutex::new(Vec::new());
+        let load_installer_library_future = self.load_libraries(http_client, artifacts, progress_trackers, launch_tracker, &installer_repaired);
 
         let (artifact_load_result, java_load_result) = futures::future::try_join(
             load_installer_library_future.map_err(LaunchError::from),
@@ -619,7 +730,7 @@ impl Launcher {
             Some(artifact)
         }).collect::<Vec<_>>();
 
-        self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker).await?;
+        self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker, &Mutex::new(Vec::new())).await?;
 
         let forge_temp = self.directories.temp_dir.join("forge_installer");
 
@@ -675,6 +786,9 @@ impl Launcher {
         processor_tracker.set_total(install_profile.processors.len());
         processor_tracker.notify();
 
+        // Processors are cached per installer, a different build of the same version has different outputs
+        let installer_hash: [u8; 20] = Sha1::digest(std::fs::read(installer_path)?).into();
+
         for processor in install_profile.processors.iter() {
             if let Some(sides) = &processor.sides {
                 if !sides.iter().any(|side| *side == ForgeSide::Client) {
@@ -687,8 +801,30 @@ impl Launcher {
 
             let jar = MavenCoordinate::create(&processor.jar);
 
-            // Check if the output already exists and the step can be skipped
-            let skip = self.can_skip_forge_processor(&jar, processor, &data);
+            let mut args = Vec::with_capacity(processor.args.len());
+            for arg in processor.args.iter() {
+                let expanded = if arg.starts_with('[') && arg.ends_with(']') {
+                    let artifact = MavenCoordinate::create(&arg[1..arg.len()-1]);
+                    let artifact_path = artifact.artifact_path();
+                    if let Some(target) = SafePath::new(&artifact_path) {
+                        let target = target.to_path(&self.directories.libraries_dir);
+                        target.into_os_string()
+                    } else {
+                        log::error!("Artifact generated invalid path: {}", artifact_path);
+                        continue;
+                    }
+                } else if &**arg == "{ROOT}/libraries/" {
+                    self.directories.libraries_dir.as_os_str().to_os_string()
+                } else {
+                    expand_forge_argument(&arg, &data).into_owned()
+                };
+                args.push(expanded);
+            }
+
+            // Check if the processor already ran for this installer, or the output already exists and the step can be skipped
+            let cache_key = crate::forge_processor_cache::key(&installer_hash, &processor.jar, &args);
+            let skip = crate::forge_processor_cache::is_cached(&self.directories.forge_processor_cache_dir, &cache_key)
+                || self.can_skip_forge_processor(&jar, processor, &data);
             if skip {
                 processor_tracker.add_count(1);
                 processor_tracker.notify();
@@ -747,25 +883,7 @@ impl Launcher {
             }).chain(std::iter::once(jar_path.into_os_string()))).unwrap());
 
             command.arg(main_class);
-
-            for arg in processor.args.iter() {
-                let expanded = if arg.starts_with('[') && arg.ends_with(']') {
-                    let artifact = MavenCoordinate::create(&arg[1..arg.len()-1]);
-                    let artifact_path = artifact.artifact_path();
-                    if let Some(target) = SafePath::new(&artifact_path) {
-                        let target = target.to_path(&self.directories.libraries_dir);
-                        Cow::Owned(target.into_os_string())
-                    } else {
-                        log::error!("Artifact generated invalid path: {}", artifact_path);
-                        continue;
-                    }
-                } else if &**arg == "{ROOT}/libraries/" {
-                    Cow::Borrowed(self.directories.libraries_dir.as_os_str())
-                } else {
-                    expand_forge_argument(&arg, &data)
-                };
-                command.arg(expanded);
-            }
+            command.args(&args);
 
             let mut child = command.spawn()?;
             let exit_code = child.wait()?;
@@ -774,6 +892,8 @@ impl Launcher {
                 return Err(LaunchError::ForgePostProcessorError);
             }
 
+            crate::forge_processor_cache::store(&self.directories.forge_processor_cache_dir, &cache_key, &args);
+
             processor_tracker.add_count(1);
             processor_tracker.notify();
         }
@@ -837,12 +957,31 @@ impl Launcher {
                 Some(artifact)
             }).collect::<Vec<_>>();
 
-            self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker).await?;
+            self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker, &Mutex::new(Vec::new())).await?;
         }
 
         Ok((Arc::new(version.apply_to(&base_version)), AddVanillaJar::Yes))
     }
 
+    /// Copies the patched client jar of a custom version into the libraries, so it's checked and loaded like the vanilla jar
+    fn custom_client_jar_artifact(&self, id: Ustr, client_jar: &Path) -> Result<GameLibraryArtifact, LaunchError> {
+        let bytes = std::fs::read(client_jar)?;
+        let sha1: [u8; 20] = Sha1::digest(&bytes).into();
+
+        let path = format!("net/minecraft/{0}/minecraft-client-{0}-custom.jar", id);
+        let artifact_path = self.directories.libraries_dir.join(&path);
+        if !crate::check_sha1_hash(&artifact_path, sha1).unwrap_or(false) {
+            crate::write_safe(&artifact_path, &bytes)?;
+        }
+
+        Ok(GameLibraryArtifact {
+            path: path.into(),
+            sha1: Some(hex::encode(sha1).as_str().into()),
+            size: Some(bytes.len() as u32),
+            url: Ustr::default(),
+        })
+    }
+
     async fn download_sha1(http_client: &reqwest::Client, url: &str) -> Option<Ustr> {
         let response = http_client
             .get(url)
@@ -888,8 +1027,15 @@ impl Launcher {
         if let Some(jvm_binary) = &configuration.jvm_binary {
             if jvm_binary.enabled && let Some(path) = &jvm_binary.path {
                 if let Some(binary) = Self::search_for_java_binary(&path) {
+                    // The rest of the Java installation next to the binary wasn't shared with the sandbox
+                    if bridge::sandbox::is_flatpak() && path.is_file() && bridge::sandbox::is_document_portal_path(path) {
+                        return Err(LoadJavaRuntimeError::SandboxedJvmFile(path.clone()));
+                    }
                     return Ok(binary);
                 }
+                if bridge::sandbox::is_flatpak() {
+                    return Err(LoadJavaRuntimeError::SandboxedJvmMissing(path.clone()));
+                }
             }
         }
 
@@ -920,7 +1066,12 @@ impl Launcher {
                 }
             }
 
-            return Err(LoadJavaRuntimeError::UnableToFindExternalBinary(needed_version, found_versions.into_iter().collect()));
+            // Mojang's runtimes work inside the sandbox, unlike most Java installations of the host
+            if bridge::sandbox::is_flatpak() {
+                log::warn!("No external Java {} found, using the bundled runtime instead (found {:?})", needed_version, found_versions);
+            } else {
+                return Err(LoadJavaRuntimeError::UnableToFindExternalBinary(needed_version, found_versions.into_iter().collect()));
+            }
         }
 
         let mut platform: Ustr = match (std::env::consts::OS, std::env::consts::ARCH) {
@@ -990,7 +1141,9 @@ impl Launcher {
         progress_trackers.push(java_runtime_tracker.clone());
         java_runtime_tracker.notify();
 
-        let result = do_java_runtime_load(http_client, runtime_component_dir, fresh_install, runtime, &java_runtime_tracker).await;
+        let mirrors = self.mirrors.read().clone();
+        let concurrent_downloads = self.settings.read().concurrent_downloads();
+        let result = do_java_runtime_load(http_client, &mirrors, concurrent_downloads, runtime_component_dir, fresh_install, runtime, &java_runtime_tracker).await;
 
         java_runtime_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         java_runtime_tracker.notify();
@@ -1009,6 +1162,7 @@ impl Launcher {
         version_info: &MinecraftVersion,
         progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
+        repaired: &Mutex<Vec<Arc<str>>>,
     ) -> Result<String, LoadAssetObjectsError> {
         let asset_index = format!("{}", version_info.assets);
 
@@ -1020,6 +1174,7 @@ impl Launcher {
 
         let initial_title = Arc::from("Verifying integrity of game assets");
         let assets_tracker = ProgressTracker::new(initial_title, self.sender.clone());
+        assets_tracker.set_unit(ProgressTrackerUnit::Bytes);
         progress_trackers.push(assets_tracker.clone());
         assets_tracker.notify();
 
@@ -1031,7 +1186,9 @@ impl Launcher {
             self.directories.assets_objects_dir.clone()
         };
 
-        let result = do_asset_objects_load(http_client, assets_index, assets_dir, &assets_tracker).await;
+        let mirrors = self.mirrors.read().clone();
+        let concurrent_downloads = self.settings.read().concurrent_downloads();
+        let result = do_asset_objects_load(http_client, &mirrors, concurrent_downloads, assets_index, assets_dir, &assets_tracker, repaired).await;
 
         assets_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         assets_tracker.notify();
@@ -1050,14 +1207,18 @@ impl Launcher {
         artifacts: &[GameLibraryArtifact],
         progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
+        repaired: &Mutex<Vec<Arc<str>>>,
     ) -> Result<Vec<(Ustr, PathBuf)>, LoadLibrariesError> {
         let initial_title = Arc::from("Verifying integrity of game libraries");
         let libraries_tracker = ProgressTracker::new(initial_title, self.sender.clone());
+        libraries_tracker.set_unit(ProgressTrackerUnit::Bytes);
         progress_trackers.push(libraries_tracker.clone());
         libraries_tracker.notify();
 
+        let mirrors = self.mirrors.read().clone();
+        let concurrent_downloads = self.settings.read().concurrent_downloads();
         let result =
-            do_libraries_load(http_client, artifacts, self.directories.libraries_dir.clone(), &libraries_tracker).await;
+            do_libraries_load(http_client, &mirrors, concurrent_downloads, artifacts, self.directories.libraries_dir.clone(), &libraries_tracker, repaired).await;
 
         libraries_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         libraries_tracker.notify();
@@ -1105,7 +1266,8 @@ impl Launcher {
             return Some(expand_logging_argument(client.argument.as_str(), &path));
         }
 
-        let Ok(response) = http_client.get(client.file.url.as_str()).send().await else {
+        let url = self.mirrors.read().rewrite(client.file.url.as_str()).into_owned();
+        let Ok(response) = http_client.get(url).send().await else {
             log::error!("Failed to make request to download log configuration");
             return None;
         };
@@ -1350,6 +1512,63 @@ fn expand_logging_argument(argument: &str, path: &Path) -> OsString {
     builder
 }
 
+/// Quotes an argument when needed, so a previewed command can be pasted into a terminal of the current platform
+fn quote_argument(argument: &str) -> Cow<'_, str> {
+    if cfg!(windows) {
+        quote_argument_windows(argument)
+    } else {
+        quote_argument_posix(argument)
+    }
+}
+
+fn quote_argument_posix(argument: &str) -> Cow<'_, str> {
+    let plain = !argument.is_empty() && argument.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+    if plain {
+        Cow::Borrowed(argument)
+    } else {
+        Cow::Owned(format!("'{}'", argument.replace('\'', "'\\''")))
+    }
+}
+
+/// Quotes like the command line parsing of the C runtime expects, backslashes are only special in front of a quote
+fn quote_argument_windows(argument: &str) -> Cow<'_, str> {
+    let plain = !argument.is_empty() && !argument.contains([' ', '\t', '\n', '"', '&', '|', '<', '>', '^', '(', ')']);
+    if plain {
+        return Cow::Borrowed(argument);
+    }
+
+    let mut quoted = String::with_capacity(argument.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in argument.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        if c == '"' {
+            quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+        } else {
+            quoted.extend(std::iter::repeat_n('\\', backslashes));
+        }
+        quoted.push(c);
+        backslashes = 0;
+    }
+    // The closing quote would be escaped by trailing backslashes
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+
+fn vanilla_jar_artifact(version_info: &MinecraftVersion, instance_info: &InstanceConfiguration) -> GameLibraryArtifact {
+    let client_download = &version_info.downloads.client;
+    GameLibraryArtifact {
+        path: format!("net/minecraft/{0}/minecraft-client-{0}.jar", instance_info.minecraft_version).into(),
+        sha1: Some(client_download.sha1),
+        size: Some(client_download.size),
+        url: client_download.url,
+    }
+}
+
 fn calculate_natives_dirname(artifacts: &[GameLibraryArtifact]) -> String {
     let mut hashes = HashSet::new();
 
@@ -1400,10 +1619,16 @@ pub enum LoadJavaRuntimeError {
     UnableToFindBinary,
     #[error("Unable to find external binary, needed Java {0}, got Java {1:?}")]
     UnableToFindExternalBinary(u32, Vec<u32>),
+    #[error("The JVM binary {0:?} was picked as a single file, so the rest of its Java installation isn't accessible from the Flatpak sandbox. Select the folder of the Java installation instead, or turn off the JVM binary override to use the bundled runtime")]
+    SandboxedJvmFile(Arc<Path>),
+    #[error("The JVM binary {0:?} isn't accessible from the Flatpak sandbox, which can't see most host folders like /usr/lib/jvm. Select the folder of the Java installation through the file chooser, or turn off the JVM binary override to use the bundled runtime")]
+    SandboxedJvmMissing(Arc<Path>),
 }
 
 async fn do_java_runtime_load(
     http_client: &reqwest::Client,
+    mirrors: &MirrorConfig,
+    concurrent_downloads: usize,
     runtime_component_dir: PathBuf,
     fresh_install: bool,
     runtime: Arc<JavaRuntimeComponentManifest>,
@@ -1411,8 +1636,8 @@ async fn do_java_runtime_load(
 ) -> Result<PathBuf, LoadJavaRuntimeError> {
     let mut links = HashMap::new();
 
-    // Limit max concurrent connections to 8 to avoid ratelimiting issues
-    let download_semaphore = tokio::sync::Semaphore::new(8);
+    // Limit concurrent connections to avoid ratelimiting issues
+    let download_semaphore = tokio::sync::Semaphore::new(concurrent_downloads);
     let disk_semaphore = tokio::sync::Semaphore::new(32);
     let started_downloading = AtomicBool::new(fresh_install);
 
@@ -1472,7 +1697,7 @@ async fn do_java_runtime_load(
                     };
 
                     let permit = download_semaphore.acquire().await.unwrap();
-                    let response = http_client.get(download.url.as_str()).send().await?;
+                    let response = http_client.get(&*mirrors.rewrite(download.url.as_str())).send().await?;
                     let bytes = response.bytes().await?;
                     drop(permit);
 
@@ -1611,14 +1836,54 @@ pub enum LoadAssetObjectsError {
     MetaLoadError(#[from] MetaLoadError),
 }
 
+static DOWNLOAD_LOCKS: LazyLock<DownloadLocks> = LazyLock::new(DownloadLocks::default);
+
+/// Makes launches that need the same file wait for each other instead of downloading it twice
+#[derive(Default)]
+struct DownloadLocks {
+    locks: parking_lot::Mutex<FxHashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+struct DownloadLockGuard<'a> {
+    locks: &'a DownloadLocks,
+    path: PathBuf,
+    guard: Option<tokio::sync::OwnedMutexGuard<()>>,
+}
+
+impl DownloadLocks {
+    async fn lock(&self, path: PathBuf) -> DownloadLockGuard<'_> {
+        let lock = self.locks.lock().entry(path.clone()).or_default().clone();
+        let guard = lock.lock_owned().await;
+        DownloadLockGuard {
+            locks: self,
+            path,
+            guard: Some(guard),
+        }
+    }
+}
+
+impl Drop for DownloadLockGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut locks = self.locks.locks.lock();
+        // Only the map itself still references the lock when nobody else is waiting for it
+        if locks.get(&self.path).is_some_and(|lock| Arc::strong_count(lock) == 1) {
+            locks.remove(&self.path);
+        }
+    }
+}
+
 async fn do_asset_objects_load(
     http_client: &reqwest::Client,
+    mirrors: &MirrorConfig,
+    concurrent_downloads: usize,
     assets_index: Arc<AssetsIndex>,
     assets_objects_dir: Arc<Path>,
     assets_tracker: &ProgressTracker,
+    repaired: &Mutex<Vec<Arc<str>>>,
 ) -> Result<(), LoadAssetObjectsError> {
-    // Limit max concurrent connections to 8 to avoid ratelimiting issues
-    let download_semaphore = tokio::sync::Semaphore::new(8);
+    // Limit concurrent connections to avoid ratelimiting issues
+    let download_semaphore = tokio::sync::Semaphore::new(concurrent_downloads);
     let disk_semaphore = tokio::sync::Semaphore::new(32);
     let started_downloading = AtomicBool::new(false);
 
@@ -1628,7 +1893,7 @@ async fn do_asset_objects_load(
 
     let _ = std::fs::create_dir_all(&assets_objects_dir);
 
-    for (_, asset) in &assets_index.objects {
+    for (name, asset) in &assets_index.objects {
         let mut expected_hash = [0u8; 20];
         let Ok(_) = hex::decode_to_slice(asset.hash.as_str(), &mut expected_hash) else {
             return Err(LoadAssetObjectsError::InvalidHash(asset.hash));
@@ -1644,7 +1909,7 @@ async fn do_asset_objects_load(
         let download_semaphore = &download_semaphore;
         let disk_semaphore = &disk_semaphore;
 
-        let url = format!("https://resources.download.minecraft.net/{}/{}", &asset.hash[..2], &asset.hash);
+        let url = mirrors.rewrite(&format!("https://resources.download.minecraft.net/{}/{}", &asset.hash[..2], &asset.hash)).into_owned();
 
         let task = async move {
             let valid_hash_on_disk = {
@@ -1663,6 +1928,21 @@ async fn do_asset_objects_load(
                 return Ok(());
             }
 
+            let _download_lock = DOWNLOAD_LOCKS.lock(path.clone()).await;
+
+            // Another instance launching at the same time may have downloaded it while waiting
+            let downloaded_elsewhere = {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || {
+                    crate::check_sha1_hash(&path, expected_hash).unwrap_or(false)
+                }).await.unwrap()
+            };
+            if downloaded_elsewhere {
+                assets_tracker.add_count(asset.size as usize);
+                assets_tracker.notify();
+                return Ok(());
+            }
+
             let was_downloading = started_downloading.swap(true, std::sync::atomic::Ordering::Relaxed);
             if !was_downloading {
                 assets_tracker.set_title(Arc::from("Downloading game assets"));
@@ -1694,6 +1974,7 @@ async fn do_asset_objects_load(
             }
 
             tokio::fs::write(path.clone(), &*bytes).await?;
+            repaired.lock().push(Arc::from(name.as_str()));
             assets_tracker.add_count(asset.size as usize);
             assets_tracker.notify();
             Ok(())
@@ -1727,16 +2008,34 @@ pub enum LoadLibrariesError {
 
 async fn do_libraries_load(
     http_client: &reqwest::Client,
+    mirrors: &MirrorConfig,
+    concurrent_downloads: usize,
     artifacts: &[GameLibraryArtifact],
     libraries_dir: Arc<Path>,
     libraries_tracker: &ProgressTracker,
+    repaired: &Mutex<Vec<Arc<str>>>,
 ) -> Result<Vec<(Ustr, PathBuf)>, LoadLibrariesError> {
-    // Limit max concurrent connections to 8 to avoid ratelimiting issues
-    let download_semaphore = tokio::sync::Semaphore::new(8);
+    // Limit concurrent connections to avoid ratelimiting issues
+    let download_semaphore = tokio::sync::Semaphore::new(concurrent_downloads);
     let disk_semaphore = tokio::sync::Semaphore::new(32);
     let started_downloading = AtomicBool::new(false);
 
+    // Libraries without a size in the version json are added to the total once their size is known
     let mut total_size = 0;
+    let unknown_sizes = AtomicUsize::new(artifacts.iter().filter(|artifact| artifact.size.is_none()).count());
+    if unknown_sizes.load(std::sync::atomic::Ordering::Relaxed) > 0 {
+        libraries_tracker.set_unit(ProgressTrackerUnit::BytesUnknownTotal);
+    }
+    let add_library_size = |known_size: Option<u32>, size: u64| {
+        if known_size.is_none() {
+            libraries_tracker.add_total(size as usize);
+            if unknown_sizes.fetch_sub(1, std::sync::atomic::Ordering::Relaxed) == 1 {
+                libraries_tracker.set_unit(ProgressTrackerUnit::Bytes);
+            }
+        }
+        libraries_tracker.add_count(size as usize);
+        libraries_tracker.notify();
+    };
 
     let mut tasks = Vec::new();
 
@@ -1763,10 +2062,10 @@ async fn do_libraries_load(
         };
         let _ = std::fs::create_dir_all(artifact_path_parent);
 
-        let tracker_size = artifact.size.unwrap_or(1000000);
-        total_size += tracker_size;
+        total_size += artifact.size.unwrap_or(0);
 
         let started_downloading = &started_downloading;
+        let add_library_size = &add_library_size;
         let download_semaphore = &download_semaphore;
         let disk_semaphore = &disk_semaphore;
 
@@ -1784,8 +2083,26 @@ async fn do_libraries_load(
             };
 
             if valid_hash_on_disk {
-                libraries_tracker.add_count(tracker_size as usize);
-                libraries_tracker.notify();
+                add_library_size(artifact.size, on_disk_size(artifact.size, &artifact_path).await);
+                return Ok((artifact.path, artifact_path));
+            }
+
+            let _download_lock = DOWNLOAD_LOCKS.lock(artifact_path.clone()).await;
+
+            // Another instance launching at the same time may have downloaded it while waiting
+            if let Some(expected_hash) = expected_hash {
+                let downloaded_elsewhere = {
+                    let artifact_path = artifact_path.clone();
+                    tokio::task::spawn_blocking(move || {
+                        crate::check_sha1_hash(&artifact_path, expected_hash).unwrap_or(false)
+                    }).await.unwrap()
+                };
+                if downloaded_elsewhere {
+                    add_library_size(artifact.size, on_disk_size(artifact.size, &artifact_path).await);
+                    return Ok((artifact.path, artifact_path));
+                }
+            } else if artifact_path.exists() {
+                add_library_size(artifact.size, on_disk_size(artifact.size, &artifact_path).await);
                 return Ok((artifact.path, artifact_path));
             }
 
@@ -1795,7 +2112,7 @@ async fn do_libraries_load(
             }
 
             let permit = download_semaphore.acquire().await.unwrap();
-            let response = http_client.get(artifact.url.as_str()).send().await?;
+            let response = http_client.get(&*mirrors.rewrite(artifact.url.as_str())).send().await?;
             let bytes = Arc::new(response.bytes().await?);
             drop(permit);
 
@@ -1824,8 +2141,8 @@ async fn do_libraries_load(
             }
 
             tokio::fs::write(artifact_path.clone(), &*bytes).await?;
-            libraries_tracker.add_count(tracker_size as usize);
-            libraries_tracker.notify();
+            repaired.lock().push(Arc::from(artifact.path.as_str()));
+            add_library_size(artifact.size, bytes.len() as u64);
             Ok((artifact.path, artifact_path))
         };
         tasks.push(task);
@@ -1837,6 +2154,14 @@ async fn do_libraries_load(
     futures::future::try_join_all(tasks).await
 }
 
+/// Size of a library that is already on disk, read from the file if the version json doesn't list it
+async fn on_disk_size(known_size: Option<u32>, path: &Path) -> u64 {
+    match known_size {
+        Some(size) => size as u64,
+        None => tokio::fs::metadata(path).await.map(|metadata| metadata.len()).unwrap_or(0),
+    }
+}
+
 pub enum ArgumentExpansionKey {
     NativesDirectory,
     LibrariesDirectory,
@@ -1897,29 +2222,47 @@ impl ArgumentExpansionKey {
 }
 
 pub struct LaunchRuleContext {
-    pub is_demo_user: bool,
     pub custom_resolution: Option<(u32, u32)>,
     pub quick_play: Option<QuickPlayLaunch>,
+    rules: RuleContext,
 }
 
 impl LaunchRuleContext {
+    pub fn new(is_demo_user: bool, custom_resolution: Option<(u32, u32)>, quick_play: Option<QuickPlayLaunch>) -> Self {
+        static OS_VERSION: OnceLock<String> = OnceLock::new();
+        let os_version = OS_VERSION.get_or_init(|| format!("{}", os_info::get().version()));
+
+        let features = RuleFeatureSet {
+            is_demo_user,
+            has_custom_resolution: custom_resolution.is_some(),
+            // We use quick play, but we don't need the quick play file to
+            // be generated by the client so we set this to false
+            has_quick_plays_support: false,
+            is_quick_play_singleplayer: matches!(quick_play, Some(QuickPlayLaunch::Singleplayer(_))),
+            is_quick_play_multiplayer: matches!(quick_play, Some(QuickPlayLaunch::Multiplayer(_))),
+            is_quick_play_realms: matches!(quick_play, Some(QuickPlayLaunch::Realms(_))),
+        };
+
+        Self {
+            custom_resolution,
+            quick_play,
+            rules: RuleContext {
+                os: RuleOsEnvironment::current().with_version(os_version.as_str()),
+                features,
+            },
+        }
+    }
+
     pub fn collect_libraries(
         &self,
         libraries: &[GameLibrary],
         artifacts: &mut Vec<GameLibraryArtifact>,
         natives_to_extract: &mut HashMap<Ustr, GameLibraryExtractOptions>,
     ) {
-        let os_name = match std::env::consts::OS {
-            "linux" => Some(OsName::Linux),
-            "macos" => Some(OsName::Osx),
-            "windows" => Some(OsName::Windows),
-            _ => None,
-        };
-
         // Remove duplicate libraries
         let mut deduplicated_libraries: HashMap<String, (GameLibrary, Vec<isize>)> = HashMap::new();
         for library in libraries {
-            if let Some(rules) = &library.rules && !self.check_rules(rules) {
+            if !self.rules.is_allowed_optional(library.rules.as_deref()) {
                 continue;
             }
 
@@ -1966,9 +2309,9 @@ impl LaunchRuleContext {
 
             if let Some(platform_natives) = &library.natives
                 && let Some(classifiers) = &library.downloads.classifiers
-                && let Some(os_name) = os_name
+                && let Some(os_name) = self.rules.os.name
                 && let Some(natives_id) = platform_natives.get(&os_name)
-                && let Some(natives) = classifiers.get(natives_id)
+                && let Some(natives) = classifiers.get(&Ustr::from(&self.rules.os.expand_natives_classifier(natives_id)))
             {
                 artifacts.push(natives.clone());
                 if let Some(extract) = &library.extract {
@@ -1979,77 +2322,7 @@ impl LaunchRuleContext {
     }
 
     pub fn check_rules(&self, rules: &[Rule]) -> bool {
-        let mut allowed = false;
-        for rule in rules {
-            if self.check_rule(rule) {
-                allowed = match rule.action {
-                    RuleAction::Allow => true,
-                    RuleAction::Disallow => false,
-                };
-            }
-        }
-        allowed
-    }
-
-    pub fn check_rule(&self, rule: &Rule) -> bool {
-        if let Some(features) = &rule.features {
-            if features.is_demo_user && !self.is_demo_user {
-                return false;
-            }
-            if features.has_custom_resolution && self.custom_resolution.is_none() {
-                return false;
-            }
-            if features.has_quick_plays_support {
-                // We use quick play, but we don't need the quick play file to
-                // be generated by the client so we set this to false
-                return false;
-            }
-            if features.is_quick_play_singleplayer && !matches!(self.quick_play, Some(QuickPlayLaunch::Singleplayer(_))) {
-                return false;
-            }
-            if features.is_quick_play_multiplayer && !matches!(self.quick_play, Some(QuickPlayLaunch::Multiplayer(_))) {
-                return false;
-            }
-            if features.is_quick_play_realms && !matches!(self.quick_play, Some(QuickPlayLaunch::Realms(_))) {
-                return false;
-            }
-        }
-
-        if let Some(os) = &rule.os {
-            if let Some(name) = &os.name {
-                let matches = match name {
-                    OsName::Linux => std::env::consts::OS == "linux",
-                    OsName::Osx => std::env::consts::OS == "macos",
-                    OsName::Windows => std::env::consts::OS == "windows",
-                };
-                if !matches {
-                    return false;
-                }
-            }
-            if let Some(arch) = &os.arch {
-                match arch {
-                    OsArch::Arm64 => {
-                        if std::env::consts::ARCH != "aarch64" {
-                            return false;
-                        }
-                    },
-                    OsArch::X86 => {
-                        if std::env::consts::ARCH != "x86" {
-                            return false;
-                        }
-                    },
-                }
-            }
-            if let Some(version) = &os.version && let Ok(regex) = Regex::new(version.as_str()) {
-                static OS_VERSION: OnceLock<String> = OnceLock::new();
-                let os_version = OS_VERSION.get_or_init(|| format!("{}", os_info::get().version()));
-                if !regex.is_match(os_version) {
-                    return false;
-                }
-            }
-        }
-
-        true
+        self.rules.is_allowed(rules)
     }
 }
 
@@ -2068,6 +2341,16 @@ pub struct LaunchContext {
     pub rule_context: LaunchRuleContext,
     pub login_info: MinecraftLoginInfo,
     pub add_mods: Vec<PathBuf>,
+    pub window: Option<GameWindowOptions>,
+    /// Extra environment variables of the game process, e.g. to render on the discrete GPU
+    pub env: Vec<(&'static str, String)>,
+}
+
+/// Window customization applied by the launch wrapper. Only versions using LWJGL 2
+/// (1.12.2 and older) expose the window globally, newer versions ignore these options
+pub struct GameWindowOptions {
+    pub title: Arc<str>,
+    pub icon: Option<Arc<Path>>,
 }
 
 impl LaunchContext {
@@ -2078,38 +2361,11 @@ impl LaunchContext {
         command.stdin(Stdio::piped());
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
+        command.envs(self.env.iter().map(|(key, value)| (*key, value)));
 
-        self.classpath.push(self.launch_wrapper_path.as_os_str().to_os_string());
-
-        if let Some(arguments) = &version_info.arguments {
-            self.process_arguments(&arguments.jvm, &mut |arg| {
-                command.arg(arg);
-            });
-        } else {
-            let mut java_library_path = OsString::new();
-            java_library_path.push("-Djava.library.path=");
-            java_library_path.push(self.natives_dir.as_os_str());
-
-            command.arg(java_library_path);
-            command.arg("-cp");
-            command.arg(std::env::join_paths(&self.classpath).unwrap());
-        }
-
-        if let Some(log_configuration) = &self.log_configuration {
-            command.arg(log_configuration);
-        }
-
-        if let Some(memory) = &self.configuration.memory && memory.enabled {
-            command.arg(format!("-Xms{}m", memory.min));
-            command.arg(format!("-Xmx{}m", memory.max.max(memory.min).max(128)));
-        }
-        if let Some(jvm_flags) = &self.configuration.jvm_flags && jvm_flags.enabled {
-            if let Ok(split) = shell_words::split(&jvm_flags.flags) {
-                command.args(split);
-            } else {
-                command.args(jvm_flags.flags.split_whitespace());
-            }
-        }
+        let jvm_arguments = self.jvm_arguments(version_info);
+        let (jvm_arguments, secret_properties) = self.split_secret_properties(jvm_arguments);
+        command.args(jvm_arguments);
 
         command.arg("com.moulberry.pandora.LaunchWrapper");
 
@@ -2119,21 +2375,19 @@ impl LaunchContext {
 
         let mut stdin_arguments = String::new();
 
-        if let Some(arguments) = &version_info.arguments {
-            self.process_arguments(&arguments.game, &mut |arg| {
-                stdin_arguments.push_str("arg\n");
-                stdin_arguments.push_str(arg.to_string_lossy().as_ref());
-                stdin_arguments.push('\n');
-            });
-        }
-        if let Some(legacy_arguments) = &version_info.minecraft_arguments {
-            for argument in legacy_arguments.split_ascii_whitespace() {
-                stdin_arguments.push_str("arg\n");
-                stdin_arguments.push_str(self.expand_argument(argument).to_string_lossy().as_ref());
-                stdin_arguments.push('\n');
-            }
+        for (key, value) in secret_properties {
+            stdin_arguments.push_str("property\n");
+            stdin_arguments.push_str(&key);
+            stdin_arguments.push('\n');
+            stdin_arguments.push_str(&value);
+            stdin_arguments.push('\n');
         }
 
+        for argument in self.game_arguments(version_info) {
+            stdin_arguments.push_str("arg\n");
+            stdin_arguments.push_str(argument.to_string_lossy().as_ref());
+            stdin_arguments.push('\n');
+        }
 
         if !self.add_mods.is_empty() {
             match self.configuration.loader {
@@ -2154,6 +2408,18 @@ impl LaunchContext {
             }
         }
 
+        if let Some(window) = &self.window {
+            stdin_arguments.push_str("windowtitle\n");
+            stdin_arguments.push_str(&window.title.replace(['\r', '\n'], " "));
+            stdin_arguments.push('\n');
+
+            if let Some(icon) = &window.icon {
+                stdin_arguments.push_str("windowicon\n");
+                stdin_arguments.push_str(&icon.to_string_lossy());
+                stdin_arguments.push('\n');
+            }
+        }
+
         stdin_arguments.push_str("launch\n");
         stdin_arguments.push_str(version_info.main_class.as_str());
         stdin_arguments.push('\n');
@@ -2164,6 +2430,116 @@ impl LaunchContext {
         Ok(child)
     }
 
+    /// The command that starts the game directly instead of through the launch wrapper, with the access
+    /// token redacted so it can be shared
+    pub fn preview_command(mut self, version_info: &MinecraftVersion) -> LaunchPreview {
+        let jvm_arguments = self.jvm_arguments(version_info);
+        let mut command = vec![self.java_path.clone().into_os_string()];
+        command.extend(jvm_arguments.iter().cloned());
+        command.push(version_info.main_class.as_str().into());
+        command.extend(self.game_arguments(version_info));
+
+        // cmd has no syntax for setting a variable for a single command
+        let env = self.env.iter().map(|(key, value)| if cfg!(windows) {
+            format!("set \"{}={}\" &&", key, value)
+        } else {
+            format!("{}={}", key, quote_argument(value))
+        });
+
+        // Older versions pass the token inside other arguments, e.g. `token:<access token>:<uuid>`
+        let access_token = self.access_token();
+        let redact = |argument: &OsString| -> String {
+            let argument = argument.to_string_lossy();
+            match access_token {
+                Some(access_token) if argument.contains(access_token) => argument.replace(access_token, "<redacted>"),
+                _ => argument.into_owned(),
+            }
+        };
+
+        let command = env.chain(command.iter().map(|argument| quote_argument(&redact(argument)).into_owned()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        LaunchPreview {
+            command: command.into(),
+            jvm_arguments: jvm_arguments.iter().map(|argument| Arc::from(redact(argument))).collect(),
+        }
+    }
+
+    fn access_token(&self) -> Option<&str> {
+        self.login_info.access_token.as_ref().map(|access_token| access_token.secret()).filter(|secret| !secret.is_empty())
+    }
+
+    /// The arguments of the java process can be read by every other process, so system properties that contain
+    /// the access token are set by the launch wrapper instead
+    fn split_secret_properties(&self, jvm_arguments: Vec<OsString>) -> (Vec<OsString>, Vec<(String, String)>) {
+        let Some(access_token) = self.access_token() else {
+            return (jvm_arguments, Vec::new());
+        };
+
+        let mut public = Vec::with_capacity(jvm_arguments.len());
+        let mut secret_properties = Vec::new();
+        for argument in jvm_arguments {
+            let Some(string) = argument.to_str().filter(|string| string.contains(access_token)) else {
+                public.push(argument);
+                continue;
+            };
+            if let Some((key, value)) = string.strip_prefix("-D").and_then(|property| property.split_once('='))
+                && !key.contains(['\r', '\n']) && !value.contains(['\r', '\n'])
+            {
+                secret_properties.push((key.to_string(), value.to_string()));
+            } else {
+                log::warn!("A JVM argument of this version contains the access token, other processes will be able to read it");
+                public.push(argument);
+            }
+        }
+        (public, secret_properties)
+    }
+
+    fn jvm_arguments(&mut self, version_info: &MinecraftVersion) -> Vec<OsString> {
+        let mut jvm_arguments = Vec::new();
+
+        self.classpath.push(self.launch_wrapper_path.as_os_str().to_os_string());
+
+        if let Some(arguments) = &version_info.arguments {
+            self.process_arguments(&arguments.jvm, &mut |arg| {
+                jvm_arguments.push(arg.to_os_string());
+            });
+        } else {
+            let mut java_library_path = OsString::new();
+            java_library_path.push("-Djava.library.path=");
+            java_library_path.push(self.natives_dir.as_os_str());
+
+            jvm_arguments.push(java_library_path);
+            jvm_arguments.push("-cp".into());
+            jvm_arguments.push(std::env::join_paths(&self.classpath).unwrap());
+        }
+
+        if let Some(log_configuration) = &self.log_configuration {
+            jvm_arguments.push(log_configuration.clone());
+        }
+
+        jvm_arguments.extend(self.configuration.runtime_jvm_arguments().into_iter().map(OsString::from));
+
+        jvm_arguments
+    }
+
+    fn game_arguments(&self, version_info: &MinecraftVersion) -> Vec<OsString> {
+        let mut game_arguments = Vec::new();
+
+        if let Some(arguments) = &version_info.arguments {
+            self.process_arguments(&arguments.game, &mut |arg| {
+                game_arguments.push(arg.to_os_string());
+            });
+        }
+        if let Some(legacy_arguments) = &version_info.minecraft_arguments {
+            for argument in legacy_arguments.split_ascii_whitespace() {
+                game_arguments.push(self.expand_argument(argument).into_owned());
+            }
+        }
+
+        game_arguments
+    }
+
     fn process_arguments(&self, arguments: &[LaunchArgument], handler: &mut impl FnMut(&OsStr)) {
         for argument in arguments {
             match argument {
@@ -2281,6 +2657,49 @@ impl LaunchContext {
     }
 }
 
+/// Picks the newest Forge/NeoForge version matching the given Minecraft version
+pub(crate) fn latest_forgelike_loader_version(minecraft_version: Ustr, loader_versions: &[Ustr], neoforge_versioning: bool) -> Option<Ustr> {
+    let minecraft_version_parts = forgelike_version_prefix(minecraft_version, neoforge_versioning);
+
+    let mut latest_loader_version = None;
+    let mut latest_loader_version_parts = Vec::new();
+    for version in loader_versions.iter() {
+        let parts = VersionFragment::string_to_parts(version);
+
+        if parts.starts_with(&minecraft_version_parts) {
+            if parts > latest_loader_version_parts {
+                latest_loader_version_parts = parts;
+                latest_loader_version = Some(version.clone());
+            }
+        }
+    }
+
+    latest_loader_version
+}
+
+/// The leading parts shared by every Forge or NeoForge version for a Minecraft version
+pub(crate) fn forgelike_version_prefix(minecraft_version: Ustr, neoforge_versioning: bool) -> Vec<VersionFragment> {
+    let mut minecraft_version_parts = VersionFragment::string_to_parts(minecraft_version.as_str());
+    if neoforge_versioning {
+        // 1.21.5 -> 21.5
+        // 25w14craftmine -> 0.25w14craftmine
+        // 1.21 -> 21.0
+        // 26.1 -> 26.1.0
+        if minecraft_version_parts[0] == VersionFragment::String("25w14craftmine".into()) {
+            minecraft_version_parts.insert(0, VersionFragment::Number(0))
+        } else {
+            if minecraft_version_parts.len() < 3 {
+                minecraft_version_parts.push(VersionFragment::Number(0))
+            }
+            if minecraft_version_parts[0] == VersionFragment::Number(1) {
+                minecraft_version_parts.remove(0);
+            }
+        }
+    }
+
+    minecraft_version_parts
+}
+
 fn path_is_normal(path: impl AsRef<Path>) -> bool {
     let components = path.as_ref().components();
 
@@ -2321,3 +2740,29 @@ fn expand_forge_argument<'a>(argument: &'a str, map: &FxHashMap<String, OsString
     }
     Cow::Borrowed(OsStr::new(argument))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posix_quoting() {
+        assert_eq!(quote_argument_posix("-Xmx2G"), "-Xmx2G");
+        assert_eq!(quote_argument_posix(""), "''");
+        assert_eq!(quote_argument_posix("/home/a b/.minecraft"), "'/home/a b/.minecraft'");
+        assert_eq!(quote_argument_posix("it's"), "'it'\\''s'");
+        assert_eq!(quote_argument_posix("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn windows_quoting() {
+        assert_eq!(quote_argument_windows(r"C:\Users\a\.minecraft"), r"C:\Users\a\.minecraft");
+        assert_eq!(quote_argument_windows(""), r#""""#);
+        assert_eq!(quote_argument_windows(r"C:\Program Files\Java\bin"), r#""C:\Program Files\Java\bin""#);
+        assert_eq!(quote_argument_windows(r#"say "hi""#), r#""say \"hi\"""#);
+        // Backslashes are doubled in front of a quote, including the closing one
+        assert_eq!(quote_argument_windows(r#"a\"b"#), r#""a\\\"b""#);
+        assert_eq!(quote_argument_windows(r"C:\a b\"), r#""C:\a b\\""#);
+        assert_eq!(quote_argument_windows("a&b"), r#""a&b""#);
+    }
+}
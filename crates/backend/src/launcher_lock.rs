@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use bridge::message::{MessageToBackend, MessageToFrontend};
+use rand::RngCore;
+use schema::backend_config::LauncherLock;
+use sha2::{Digest, Sha256};
+
+const HASH_ITERATIONS: u32 = 100_000;
+
+/// Incorrect attempts that can be made in a row before further ones have to wait
+const FREE_ATTEMPTS: u32 = 3;
+const BASE_THROTTLE_DELAY: Duration = Duration::from_secs(5);
+const MAX_THROTTLE_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Seconds left, rounded up, before another attempt is checked. `now` is in unix milliseconds. The wait
+/// is capped, so setting the clock back can't make it longer
+pub fn retry_after_secs(lock: &LauncherLock, now: i64) -> Option<u64> {
+    let remaining = lock.retry_at?.checked_sub(now)?;
+    if remaining <= 0 {
+        return None;
+    }
+    let remaining = (remaining as u64).min(MAX_THROTTLE_DELAY.as_millis() as u64);
+    Some(remaining.div_ceil(1000))
+}
+
+/// Slows down guessing the secret, once the free attempts are used up the wait doubles with every incorrect attempt
+pub fn record_failure(lock: &mut LauncherLock, now: i64) {
+    lock.failed_attempts = lock.failed_attempts.saturating_add(1);
+    if lock.failed_attempts >= FREE_ATTEMPTS {
+        let doublings = (lock.failed_attempts - FREE_ATTEMPTS).min(16);
+        let delay = BASE_THROTTLE_DELAY.saturating_mul(1 << doublings).min(MAX_THROTTLE_DELAY);
+        lock.retry_at = Some(now.saturating_add(delay.as_millis() as i64));
+    }
+}
+
+pub fn record_success(lock: &mut LauncherLock) {
+    lock.failed_attempts = 0;
+    lock.retry_at = None;
+}
+
+pub fn create(secret: &str, idle_lock_minutes: Option<u32>) -> LauncherLock {
+    let mut salt = [0_u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    LauncherLock {
+        salt: hex::encode(salt),
+        hash: hex::encode(hash_secret(&salt, secret)),
+        idle_lock_minutes,
+        failed_attempts: 0,
+        retry_at: None,
+    }
+}
+
+pub fn verify(lock: &LauncherLock, secret: &str) -> bool {
+    let Ok(salt) = hex::decode(&lock.salt) else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(&lock.hash) else {
+        return false;
+    };
+
+    let actual = hash_secret(&salt, secret);
+    if actual.len() != expected.len() {
+        return false;
+    }
+
+    // Compare without short-circuiting to avoid leaking how much of the hash matched
+    actual.iter().zip(expected.iter()).fold(0_u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn hash_secret(salt: &[u8], secret: &str) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::new().chain_update(salt).chain_update(secret.as_bytes()).finalize().into();
+    for _ in 1..HASH_ITERATIONS {
+        hash = Sha256::new().chain_update(salt).chain_update(hash).finalize().into();
+    }
+    hash
+}
+
+pub fn create_update_message(lock: Option<&LauncherLock>, locked: bool) -> MessageToFrontend {
+    MessageToFrontend::LauncherLockUpdated {
+        enabled: lock.is_some(),
+        locked,
+        idle_lock_minutes: lock.and_then(|lock| lock.idle_lock_minutes),
+    }
+}
+
+/// Messages that use accounts, launch the game, change the lock itself or change where the launcher
+/// connects to are refused while locked
+pub fn requires_unlocked(message: &MessageToBackend) -> bool {
+    matches!(message,
+        MessageToBackend::StartInstance { .. }
+        | MessageToBackend::AddNewAccount { .. }
+        | MessageToBackend::AddOfflineAccount { .. }
+        | MessageToBackend::SelectAccount { .. }
+        | MessageToBackend::DeleteAccount { .. }
+        | MessageToBackend::ReauthenticateAccount { .. }
+        | MessageToBackend::SetLauncherLock { .. }
+        | MessageToBackend::SetProxy { .. }
+        | MessageToBackend::TestProxy { .. }
+        | MessageToBackend::SetMirrors { .. }
+        | MessageToBackend::SetPortableCredentialsConsent { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_secret() {
+        let lock = create("1234", None);
+        assert!(verify(&lock, "1234"));
+        assert!(!verify(&lock, "4321"));
+        assert!(!verify(&lock, ""));
+    }
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    #[test]
+    fn throttle_after_free_attempts() {
+        let mut lock = create("1234", None);
+
+        for _ in 1..FREE_ATTEMPTS {
+            record_failure(&mut lock, NOW);
+            assert_eq!(retry_after_secs(&lock, NOW), None);
+        }
+
+        record_failure(&mut lock, NOW);
+        assert_eq!(retry_after_secs(&lock, NOW), Some(5));
+        assert_eq!(retry_after_secs(&lock, NOW + 2500), Some(3));
+        assert_eq!(retry_after_secs(&lock, NOW + BASE_THROTTLE_DELAY.as_millis() as i64), None);
+
+        record_failure(&mut lock, NOW);
+        assert_eq!(retry_after_secs(&lock, NOW), Some(10));
+    }
+
+    #[test]
+    fn throttle_is_capped_and_reset() {
+        let mut lock = create("1234", None);
+
+        for _ in 0..100 {
+            record_failure(&mut lock, NOW);
+        }
+        assert_eq!(retry_after_secs(&lock, NOW), Some(MAX_THROTTLE_DELAY.as_secs()));
+        // A clock that was set back doesn't extend the wait
+        assert_eq!(retry_after_secs(&lock, NOW - 24 * 60 * 60 * 1000), Some(MAX_THROTTLE_DELAY.as_secs()));
+
+        record_success(&mut lock);
+        record_failure(&mut lock, NOW);
+        assert_eq!(retry_after_secs(&lock, NOW), None);
+    }
+
+    #[test]
+    fn throttle_is_saved_with_the_lock() {
+        let mut lock = create("1234", None);
+        for _ in 0..FREE_ATTEMPTS {
+            record_failure(&mut lock, NOW);
+        }
+
+        let saved: LauncherLock = serde_json::from_str(&serde_json::to_string(&lock).unwrap()).unwrap();
+        assert_eq!(retry_after_secs(&saved, NOW), Some(5));
+    }
+
+    #[test]
+    fn network_settings_require_unlocked() {
+        let (channel, _) = tokio::sync::oneshot::channel();
+        assert!(requires_unlocked(&MessageToBackend::SetProxy { proxy: Default::default() }));
+        assert!(requires_unlocked(&MessageToBackend::TestProxy { proxy: Default::default(), channel }));
+        assert!(requires_unlocked(&MessageToBackend::SetMirrors { mirrors: Default::default() }));
+        assert!(!requires_unlocked(&MessageToBackend::LockLauncher));
+    }
+}
@@ -0,0 +1,379 @@
+use std::{cmp::Ordering, path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{message::LauncherUpdate, modal_action::{ProgressTracker, ProgressTrackerFinishType, ProgressTrackerUnit}};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Moulberry/PandoraLauncher/releases/latest";
+
+/// Set by the release build scripts, development builds never look for updates
+const RELEASE_VERSION: Option<&str> = option_env!("PANDORA_RELEASE_VERSION");
+
+#[derive(Deserialize, Debug)]
+struct GithubRelease {
+    tag_name: Arc<str>,
+    #[serde(default)]
+    body: Option<Arc<str>>,
+    html_url: Arc<str>,
+    #[serde(default)]
+    assets: Vec<GithubReleaseAsset>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// e.g. "sha256:<hex>", missing for releases uploaded before GitHub added digests
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+impl GithubReleaseAsset {
+    fn sha256(&self) -> Option<Vec<u8>> {
+        let digest = self.digest.as_deref()?.strip_prefix("sha256:")?;
+        hex::decode(digest).ok().filter(|digest| digest.len() == 32)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LauncherUpdateError {
+    #[error("This build of the launcher can't update itself, download the update from the release page instead")]
+    Unsupported,
+    #[error("Request failed:\n{0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("GitHub responded with {0}")]
+    NotOK(StatusCode),
+    #[error("The latest release is not newer than the running launcher")]
+    NotNewer,
+    #[error("The latest release has no download for this platform")]
+    MissingAsset,
+    #[error("The download doesn't match the checksum of the release")]
+    WrongHash,
+    #[error("The release doesn't publish a checksum for its download, it can't be verified")]
+    MissingDigest,
+    #[error("Failed to write the update:\n{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Returns the latest release if it's newer than the running launcher
+pub async fn check(http_client: &reqwest::Client) -> Result<Option<LauncherUpdate>, LauncherUpdateError> {
+    let Some(current) = RELEASE_VERSION else {
+        log::debug!("Not checking for launcher updates in a development build");
+        return Ok(None);
+    };
+
+    let release = fetch_latest_release(http_client).await?;
+    let version = release.tag_name.trim_start_matches('v');
+    if !is_newer(version, current) {
+        log::debug!("Launcher is up to date ({})", current);
+        return Ok(None);
+    }
+
+    log::info!("Launcher update available: {} -> {}", current, version);
+    let can_install = installable_executable().is_some()
+        && find_asset(&release, version).is_some_and(|asset| asset.sha256().is_some());
+    Ok(Some(LauncherUpdate {
+        version: version.into(),
+        changelog: release.body.clone().unwrap_or_default(),
+        release_url: release.html_url.clone(),
+        can_install,
+    }))
+}
+
+/// Downloads the latest release next to the executable, it replaces the executable the next time the launcher starts
+pub async fn download(http_client: &reqwest::Client, tracker: &ProgressTracker) -> Result<(), LauncherUpdateError> {
+    let Some(executable) = installable_executable() else {
+        return Err(LauncherUpdateError::Unsupported);
+    };
+    let current = RELEASE_VERSION.ok_or(LauncherUpdateError::Unsupported)?;
+
+    let release = fetch_latest_release(http_client).await?;
+    let version = release.tag_name.trim_start_matches('v');
+    if !is_newer(version, current) {
+        return Err(LauncherUpdateError::NotNewer);
+    }
+    let asset = find_asset(&release, version).ok_or(LauncherUpdateError::MissingAsset)?;
+    let expected_sha256 = asset.sha256().ok_or(LauncherUpdateError::MissingDigest)?;
+
+    tracker.set_title(format!("Downloading Pandora {}", version).into());
+    tracker.set_unit(ProgressTrackerUnit::Bytes);
+    tracker.set_total(asset.size as usize);
+    tracker.notify();
+
+    let response = http_client.get(&asset.browser_download_url).send().await?;
+    if response.status() != StatusCode::OK {
+        return Err(LauncherUpdateError::NotOK(response.status()));
+    }
+
+    // Only a complete and verified download may be renamed to the staged path, anything found there on the next
+    // start replaces the executable
+    let staged = staged_update_path(&executable);
+    let partial = partial_update_path(&executable);
+    if let Err(error) = download_verified(response, &partial, &expected_sha256, tracker).await {
+        _ = tokio::fs::remove_file(&partial).await;
+        return Err(error);
+    }
+    if let Err(error) = tokio::fs::rename(&partial, &staged).await {
+        _ = tokio::fs::remove_file(&partial).await;
+        return Err(error.into());
+    }
+
+    tracker.set_finished(ProgressTrackerFinishType::Normal);
+    tracker.notify();
+    log::info!("Downloaded launcher update {} to {:?}", version, staged);
+    Ok(())
+}
+
+async fn download_verified(response: reqwest::Response, path: &Path, expected_sha256: &[u8], tracker: &ProgressTracker) -> Result<(), LauncherUpdateError> {
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+
+    use futures::StreamExt;
+    let mut stream = response.bytes_stream();
+    while let Some(item) = stream.next().await {
+        let item = item?;
+        tracker.add_count(item.len());
+        tracker.notify();
+
+        hasher.update(&item);
+        file.write_all(&item).await?;
+    }
+    file.flush().await?;
+    file.sync_all().await?;
+    drop(file);
+
+    if hasher.finalize().as_slice() != expected_sha256 {
+        return Err(LauncherUpdateError::WrongHash);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the executable with a downloaded update and starts the updated launcher with the same arguments.
+/// Returns true if this process should exit because the updated launcher was started
+pub fn apply_staged_update() -> bool {
+    let Ok(executable) = std::env::current_exe() else {
+        return false;
+    };
+
+    // Left behind by the previous update, only removable once that launcher has exited
+    _ = std::fs::remove_file(old_executable_path(&executable));
+    // Left behind by a download that was interrupted by the launcher exiting
+    _ = std::fs::remove_file(partial_update_path(&executable));
+
+    let staged = staged_update_path(&executable);
+    if !staged.is_file() {
+        return false;
+    }
+
+    let old = old_executable_path(&executable);
+    if let Err(error) = std::fs::rename(&executable, &old) {
+        log::error!("Unable to apply launcher update, couldn't move the current executable: {}", error);
+        _ = std::fs::remove_file(&staged);
+        return false;
+    }
+    if let Err(error) = std::fs::rename(&staged, &executable) {
+        log::error!("Unable to apply launcher update: {}", error);
+        _ = std::fs::rename(&old, &executable);
+        return false;
+    }
+
+    log::info!("Applied launcher update, starting the new version");
+    match std::process::Command::new(&executable).args(std::env::args_os().skip(1)).spawn() {
+        Ok(_) => true,
+        Err(error) => {
+            log::error!("Unable to start the updated launcher: {}", error);
+            false
+        },
+    }
+}
+
+async fn fetch_latest_release(http_client: &reqwest::Client) -> Result<GithubRelease, LauncherUpdateError> {
+    let response = http_client.get(LATEST_RELEASE_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?;
+    if response.status() != StatusCode::OK {
+        return Err(LauncherUpdateError::NotOK(response.status()));
+    }
+    Ok(response.json().await?)
+}
+
+/// The release builds that are only a single executable can replace themselves. Installed builds (deb, AppImage,
+/// flatpak, the Windows installer) are updated through their package and the macOS app bundle is signed as a whole
+fn installable_executable() -> Option<PathBuf> {
+    RELEASE_VERSION?;
+    asset_name("")?;
+    if std::env::var_os("APPIMAGE").is_some() || bridge::sandbox::is_flatpak() {
+        return None;
+    }
+    let executable = std::env::current_exe().ok()?;
+    let file_name = executable.file_name()?.to_str()?;
+    file_name.contains("Portable").then_some(executable)
+}
+
+fn find_asset<'a>(release: &'a GithubRelease, version: &str) -> Option<&'a GithubReleaseAsset> {
+    let name = asset_name(version)?;
+    release.assets.iter().find(|asset| asset.name == name)
+}
+
+/// Name of the portable executable for this platform, as uploaded by the release build scripts
+fn asset_name(version: &str) -> Option<String> {
+    if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some(format!("PandoraLauncher-Linux-{}-x86_64-Portable", version))
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        Some(format!("PandoraLauncher-Windows-{}-x86_64-Portable.exe", version))
+    } else {
+        None
+    }
+}
+
+fn staged_update_path(executable: &Path) -> PathBuf {
+    let mut path = executable.as_os_str().to_owned();
+    path.push(".update");
+    path.into()
+}
+
+fn partial_update_path(executable: &Path) -> PathBuf {
+    let mut path = executable.as_os_str().to_owned();
+    path.push(".update.part");
+    path.into()
+}
+
+fn old_executable_path(executable: &Path) -> PathBuf {
+    let mut path = executable.as_os_str().to_owned();
+    path.push(".old");
+    path.into()
+}
+
+/// Compares versions by semver precedence, a prerelease (e.g. "1.0.0-beta.1") is older than its release
+/// and build metadata after a '+' is ignored
+fn is_newer(version: &str, current: &str) -> bool {
+    compare_versions(version, current) == Ordering::Greater
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        let version = version.split_once('+').map_or(version, |(version, _)| version);
+        match version.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease)),
+            None => (version, None),
+        }
+    }
+
+    let (a_core, a_prerelease) = split(a);
+    let (b_core, b_prerelease) = split(b);
+
+    let a_core: Vec<u64> = a_core.split('.').map(|part| part.parse().unwrap_or(0)).collect();
+    let b_core: Vec<u64> = b_core.split('.').map(|part| part.parse().unwrap_or(0)).collect();
+    for index in 0..a_core.len().max(b_core.len()) {
+        let a = a_core.get(index).copied().unwrap_or(0);
+        let b = b_core.get(index).copied().unwrap_or(0);
+        if a != b {
+            return a.cmp(&b);
+        }
+    }
+
+    match (a_prerelease, b_prerelease) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut a = a.split('.');
+            let mut b = b.split('.');
+            loop {
+                let ordering = match (a.next(), b.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    // Numeric identifiers are compared numerically and have lower precedence than alphanumeric ones
+                    (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                        (Ok(a), Ok(b)) => a.cmp(&b),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => a.cmp(b),
+                    },
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_comparison() {
+        assert!(is_newer("1.2.0", "1.1.9"));
+        assert!(is_newer("1.10.0", "1.9.0"));
+        assert!(is_newer("2", "1.9.9"));
+        assert!(is_newer("1.0.1", "1.0"));
+        assert!(!is_newer("1.0.0", "1.0"));
+        assert!(!is_newer("1.0.0-beta.2", "1.0.0"));
+        assert!(!is_newer("0.9.0", "1.0.0"));
+    }
+
+    #[test]
+    fn prerelease_precedence() {
+        assert!(is_newer("1.0.0", "1.0.0-beta.2"));
+        assert!(is_newer("1.0.0-beta.2", "1.0.0-beta.1"));
+        assert!(is_newer("1.0.0-beta.11", "1.0.0-beta.2"));
+        assert!(is_newer("1.0.0-beta", "1.0.0-alpha.1"));
+        assert!(is_newer("1.0.0-alpha.1", "1.0.0-alpha"));
+        assert!(is_newer("1.0.0-alpha.beta", "1.0.0-alpha.1"));
+        assert!(is_newer("1.0.1-alpha", "1.0.0"));
+        assert!(!is_newer("1.0.0-beta.1", "1.0.0-beta.1"));
+        assert!(!is_newer("1.0.0-rc.1", "1.0.0"));
+        assert!(!is_newer("1.0.0+build.5", "1.0.0"));
+    }
+
+    #[test]
+    fn release_assets_are_found() {
+        let release: GithubRelease = serde_json::from_str(r#"{
+            "tag_name": "v1.2.0",
+            "html_url": "https://github.com/Moulberry/PandoraLauncher/releases/tag/v1.2.0",
+            "body": "- Fixed things",
+            "assets": [
+                { "name": "PandoraLauncher-Linux-1.2.0-x86_64-Portable", "browser_download_url": "https://example.com/linux", "size": 10, "digest": "sha256:00" },
+                { "name": "PandoraLauncher-Windows-1.2.0-x86_64-Portable.exe", "browser_download_url": "https://example.com/windows", "size": 10, "digest": null }
+            ]
+        }"#).unwrap();
+
+        let asset = find_asset(&release, "1.2.0");
+        if asset_name("").is_some() {
+            assert!(asset.is_some());
+        } else {
+            assert!(asset.is_none());
+        }
+        assert!(find_asset(&release, "1.3.0").is_none());
+    }
+
+    #[test]
+    fn assets_without_sha256_digest_are_rejected() {
+        let asset = |digest: Option<&str>| GithubReleaseAsset {
+            name: String::new(),
+            browser_download_url: String::new(),
+            size: 0,
+            digest: digest.map(str::to_string),
+        };
+
+        let valid = format!("sha256:{}", "ab".repeat(32));
+        assert_eq!(asset(Some(&valid)).sha256(), Some(vec![0xab; 32]));
+        assert!(asset(None).sha256().is_none());
+        assert!(asset(Some("sha256:00")).sha256().is_none());
+        assert!(asset(Some(&valid.replace("sha256", "sha512"))).sha256().is_none());
+    }
+}
@@ -1,10 +1,11 @@
 #![deny(unused_must_use)]
 
 mod backend;
-use std::{ffi::OsString, io::Write, path::{Path, PathBuf}};
+use std::{ffi::OsString, path::{Path, PathBuf}};
 
 pub use backend::*;
-use rand::RngCore;
+pub use write_behind::flush_global as flush_pending_writes;
+pub use launcher_update::apply_staged_update;
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
 
@@ -13,20 +14,45 @@ mod backend_handler;
 
 mod account;
 mod arcfactory;
+mod config_files;
+mod content_cache;
+mod content_lock;
+mod data_versions;
 mod directories;
+mod export;
+mod forge_processor_cache;
+mod game_options;
+mod gpu;
+mod graceful_stop;
 mod install_content;
+mod install_queue;
 mod instance;
 mod java_manifest;
+mod lan_discovery;
 mod launch;
 mod launch_wrapper;
+mod launcher_lock;
+mod launcher_update;
+mod loader_requirements;
 mod lockfile;
 mod log_reader;
 mod metadata;
 mod mod_metadata;
+mod modpack;
+mod network_fs;
 mod id_slab;
 mod persistent;
+mod process_monitor;
+mod process_record;
+mod proxy;
+mod servers_dat;
 mod shortcut;
+mod storage;
 mod syncing;
+#[cfg(test)]
+use auth::test_util;
+mod write_behind;
+pub mod zip_writer;
 
 pub(crate) fn is_single_component_path(path: &str) -> bool {
     let path = std::path::Path::new(path);
@@ -57,36 +83,13 @@ pub enum IoOrSerializationError {
     Serialization(#[from] serde_json::Error),
 }
 
+pub(crate) use auth::atomic_file::write_safe;
+
 pub(crate) fn read_json<T: for <'de> Deserialize<'de>>(path: &Path) -> Result<T, IoOrSerializationError> {
     let data = std::fs::read(path)?;
     Ok(serde_json::from_slice(&data)?)
 }
 
-pub(crate) fn write_safe(path: &Path, content: &[u8]) -> std::io::Result<()> {
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
-
-    let mut temp = path.to_path_buf();
-    temp.add_extension(format!("{}", rand::thread_rng().next_u32()));
-    temp.add_extension("new");
-
-    let mut temp_file = std::fs::File::create(&temp)?;
-
-    temp_file.write_all(content)?;
-    temp_file.flush()?;
-    temp_file.sync_all()?;
-
-    drop(temp_file);
-
-    if let Err(err) = std::fs::rename(&temp, path) {
-        _ = std::fs::remove_file(&temp);
-        return Err(err);
-    }
-
-    Ok(())
-}
-
 pub(crate) fn child_state_path(path: &Path) -> Option<PathBuf> {
     let mut new_path = path.to_path_buf();
 
@@ -0,0 +1,159 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use schema::{fabric_mod::FabricModJson, forge::VersionFragment, forge_mod::ModsToml, loader::Loader};
+use ustr::Ustr;
+
+/// The lowest Fabric Loader version the mod accepts, if its fabric.mod.json declares one
+pub fn fabric_requirements(fabric_mod_json: &FabricModJson) -> Arc<[(Loader, Arc<str>)]> {
+    fabric_mod_json.depends.get("fabricloader")
+        .and_then(|dependency| fabric_minimum(dependency.predicates()))
+        .map(|minimum| (Loader::Fabric, minimum.into()))
+        .into_iter()
+        .collect()
+}
+
+/// The lowest Forge and NeoForge versions the mod accepts. NeoForge for 1.20.1 still used mods.toml, so both
+/// are looked for in either file
+pub fn mods_toml_requirements(mods_toml: &ModsToml) -> Arc<[(Loader, Arc<str>)]> {
+    [(Loader::Forge, "forge"), (Loader::NeoForge, "neoforge")].into_iter()
+        .filter_map(|(loader, loader_mod_id)| {
+            let minimum = mods_toml.dependencies.values()
+                .flatten()
+                .filter(|dependency| dependency.mod_id.as_deref() == Some(loader_mod_id) && dependency.is_required())
+                .filter_map(|dependency| maven_range_minimum(dependency.version_range.as_deref()?))
+                .max_by(|a, b| compare_versions(a, b))?;
+            Some((loader, minimum.into()))
+        })
+        .collect()
+}
+
+/// The lowest version of the loader the mod accepts, from the requirements read with its summary
+pub fn minimum_loader_version(loader_requirements: &[(Loader, Arc<str>)], loader: Loader) -> Option<&Arc<str>> {
+    loader_requirements.iter()
+        .find(|(required_loader, _)| *required_loader == loader)
+        .map(|(_, minimum)| minimum)
+}
+
+/// Fabric dependencies are satisfied by any one of the predicates, and each predicate can combine
+/// several space separated ones that all have to match
+fn fabric_minimum(predicates: &[Arc<str>]) -> Option<String> {
+    let mut lowest: Option<&str> = None;
+    for predicate in predicates {
+        // An alternative without a lower bound accepts any version
+        let minimum = predicate.split_whitespace()
+            .filter_map(predicate_minimum)
+            .max_by(|a, b| compare_versions(a, b))?;
+        if lowest.is_none_or(|lowest| compare_versions(minimum, lowest) == Ordering::Less) {
+            lowest = Some(minimum);
+        }
+    }
+    lowest.map(str::to_string)
+}
+
+fn predicate_minimum(predicate: &str) -> Option<&str> {
+    if predicate.starts_with('<') {
+        return None;
+    }
+
+    let version = predicate.trim_start_matches(['>', '=', '^', '~']);
+    // Build metadata doesn't affect ordering, and wildcards like 0.15.x only fix the parts before them
+    let version = version.split('+').next().unwrap_or(version);
+    let version = version.split(['x', 'X', '*']).next().unwrap_or(version).trim_end_matches('.');
+
+    (!version.is_empty()).then_some(version)
+}
+
+/// The lower bound of a Maven version range such as `[47.1,)`. A version outside of brackets is only a
+/// recommendation, so it isn't a requirement
+fn maven_range_minimum(range: &str) -> Option<&str> {
+    let inner = range.trim().strip_prefix(['[', '('])?;
+    let lower = inner.split([',', ']', ')']).next()?.trim();
+    (!lower.is_empty()).then_some(lower)
+}
+
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    VersionFragment::string_to_parts(a).cmp(&VersionFragment::string_to_parts(b))
+}
+
+/// Forge versions are prefixed with the Minecraft version, while mods only name the Forge version they need
+pub fn loader_version_number<'a>(version: &'a str, minecraft_version: &str) -> &'a str {
+    version.strip_prefix(minecraft_version)
+        .and_then(|version| version.strip_prefix('-'))
+        .unwrap_or(version)
+}
+
+pub fn satisfies(version: &str, minecraft_version: &str, minimum: &str) -> bool {
+    compare_versions(loader_version_number(version, minecraft_version), minimum) != Ordering::Less
+}
+
+/// The oldest of the available loader versions that's at least `minimum`, to change as little as possible
+pub fn oldest_satisfying(available: impl IntoIterator<Item = Ustr>, minecraft_version: &str, minimum: &str) -> Option<Ustr> {
+    available.into_iter()
+        .filter(|version| satisfies(version, minecraft_version, minimum))
+        .min_by(|a, b| compare_versions(loader_version_number(a, minecraft_version), loader_version_number(b, minecraft_version)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn predicates(predicates: &[&str]) -> Vec<Arc<str>> {
+        predicates.iter().map(|predicate| Arc::from(*predicate)).collect()
+    }
+
+    #[test]
+    fn fabric_predicates() {
+        assert_eq!(fabric_minimum(&predicates(&[">=0.15.0"])).as_deref(), Some("0.15.0"));
+        assert_eq!(fabric_minimum(&predicates(&["^0.14.21+build.1"])).as_deref(), Some("0.14.21"));
+        assert_eq!(fabric_minimum(&predicates(&["0.16.x"])).as_deref(), Some("0.16"));
+        assert_eq!(fabric_minimum(&predicates(&[">=0.14.0 <0.16.0"])).as_deref(), Some("0.14.0"));
+        assert_eq!(fabric_minimum(&predicates(&[">=0.15.3", ">=0.14.22"])).as_deref(), Some("0.14.22"));
+        assert_eq!(fabric_minimum(&predicates(&["*"])), None);
+        assert_eq!(fabric_minimum(&predicates(&["<0.16.0"])), None);
+        assert_eq!(fabric_minimum(&predicates(&[">=0.15.0", "*"])), None);
+    }
+
+    #[test]
+    fn mods_toml_requirements_by_loader() {
+        let mods_toml: ModsToml = toml::from_str(r#"
+            [[mods]]
+            modId = "example"
+
+            [[dependencies.example]]
+            modId = "neoforge"
+            type = "required"
+            versionRange = "[21.1.77,)"
+
+            [[dependencies.example]]
+            modId = "minecraft"
+            versionRange = "[1.21.1]"
+        "#).unwrap();
+
+        let requirements = mods_toml_requirements(&mods_toml);
+        assert_eq!(minimum_loader_version(&requirements, Loader::NeoForge).map(|minimum| &**minimum), Some("21.1.77"));
+        assert_eq!(minimum_loader_version(&requirements, Loader::Forge), None);
+    }
+
+    #[test]
+    fn maven_ranges() {
+        assert_eq!(maven_range_minimum("[47.1,)"), Some("47.1"));
+        assert_eq!(maven_range_minimum("[21.1.77, 22)"), Some("21.1.77"));
+        assert_eq!(maven_range_minimum("(,48)"), None);
+        assert_eq!(maven_range_minimum("47.1"), None);
+    }
+
+    #[test]
+    fn forge_versions_are_compared_without_minecraft_version() {
+        assert!(satisfies("1.20.1-47.2.0", "1.20.1", "47.1"));
+        assert!(!satisfies("1.20.1-47.0.35", "1.20.1", "47.1"));
+        assert!(satisfies("21.1.77", "1.21.1", "21.1.77"));
+        assert!(!satisfies("0.14.25", "1.20.1", "0.15.0"));
+    }
+
+    #[test]
+    fn picks_oldest_satisfying_version() {
+        let available = ["1.20.1-47.0.35", "1.20.1-47.3.0", "1.20.1-47.1.3", "1.20.1-47.2.0"].map(Ustr::from);
+        assert_eq!(oldest_satisfying(available, "1.20.1", "47.1").as_deref(), Some("1.20.1-47.1.3"));
+        assert_eq!(oldest_satisfying(available, "1.20.1", "48"), None);
+    }
+}
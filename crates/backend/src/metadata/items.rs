@@ -4,7 +4,7 @@ use std::{
 
 use reqwest::RequestBuilder;
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::{FABRIC_LOADER_MANIFEST_URL, FabricLoaderManifest}, forge::{ForgeMavenManifest, NeoforgeMavenManifest, VersionFragment}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::{JAVA_RUNTIMES_URL, JavaRuntimes}, maven::MavenMetadataXml, modrinth::{MODRINTH_SEARCH_URL, ModrinthLoader, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::{MOJANG_VERSION_MANIFEST_URL, MinecraftVersionLink, MinecraftVersionManifest}
+    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::{FABRIC_LOADER_MANIFEST_URL, FabricLoaderManifest}, forge::{ForgeMavenManifest, NeoforgeMavenManifest, VersionFragment}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::{JAVA_RUNTIMES_URL, JavaRuntimes}, maven::MavenMetadataXml, modrinth::{MODRINTH_CATEGORY_TAGS_URL, MODRINTH_SEARCH_URL, ModrinthCategoryTags, ModrinthLoader, ModrinthProject, ModrinthProjectMembers, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthProjectsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthTeamsResult, ModrinthVersionFileUpdateResult, ModrinthVersionFilesRequest, ModrinthVersionFilesResult}, version::MinecraftVersion, version_manifest::{MOJANG_VERSION_MANIFEST_URL, MinecraftVersionLink, MinecraftVersionManifest}
 };
 use serde::Serialize;
 use ustr::Ustr;
@@ -273,6 +273,33 @@ impl<'a> MetadataItem for ModrinthSearchMetadataItem<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct ModrinthCategoryTagsMetadataItem;
+
+impl MetadataItem for ModrinthCategoryTagsMetadataItem {
+    type T = ModrinthCategoryTags;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        client.get(MODRINTH_CATEGORY_TAGS_URL)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        Some(Arc::clone(&metadata_manager.modrinth_category_tags_cache))
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_category_tags.clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
 #[derive(Debug)]
 pub struct ModrinthProjectVersionsMetadataItem<'a>(pub &'a ModrinthProjectVersionsRequest);
 
@@ -304,6 +331,54 @@ impl<'a> MetadataItem for ModrinthProjectVersionsMetadataItem<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct ModrinthProjectMetadataItem<'a>(pub &'a Arc<str>);
+
+impl<'a> MetadataItem for ModrinthProjectMetadataItem<'a> {
+    type T = ModrinthProject;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let url = format!("https://api.modrinth.com/v2/project/{}", self.0);
+        client.get(url)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_project.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModrinthProjectMembersMetadataItem<'a>(pub &'a Arc<str>);
+
+impl<'a> MetadataItem for ModrinthProjectMembersMetadataItem<'a> {
+    type T = ModrinthProjectMembers;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let url = format!("https://api.modrinth.com/v2/project/{}/members", self.0);
+        client.get(url)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_project_members.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
 #[derive(Debug)]
 pub struct ModrinthVersionMetadataItem(pub Arc<str>);
 
@@ -328,6 +403,77 @@ impl MetadataItem for ModrinthVersionMetadataItem {
     }
 }
 
+#[derive(Debug)]
+pub struct ModrinthVersionFilesMetadataItem<'a>(pub &'a ModrinthVersionFilesRequest);
+
+impl<'a> MetadataItem for ModrinthVersionFilesMetadataItem<'a> {
+    type T = ModrinthVersionFilesResult;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        client.post("https://api.modrinth.com/v2/version_files").json(self.0)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_version_files.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModrinthProjectsMetadataItem(pub Arc<[Arc<str>]>);
+
+impl MetadataItem for ModrinthProjectsMetadataItem {
+    type T = ModrinthProjectsResult;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let ids = serde_json::to_string(&self.0).unwrap();
+        client.get("https://api.modrinth.com/v2/projects").query(&[("ids", ids)])
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_projects.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModrinthTeamsMetadataItem(pub Arc<[Arc<str>]>);
+
+impl MetadataItem for ModrinthTeamsMetadataItem {
+    type T = ModrinthTeamsResult;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let ids = serde_json::to_string(&self.0).unwrap();
+        client.get("https://api.modrinth.com/v2/teams").query(&[("ids", ids)])
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_teams.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct VersionUpdateParameters {
     pub loaders: Arc<[ModrinthLoader]>,
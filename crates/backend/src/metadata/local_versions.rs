@@ -0,0 +1,264 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::Arc, time::SystemTime};
+
+use chrono::{DateTime, Utc};
+use schema::{maven::MavenCoordinate, version_manifest::{MinecraftVersionLink, MinecraftVersionManifest, MinecraftVersionType}};
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use ustr::Ustr;
+
+/// Versions added by the user are laid out like the official launcher's versions folder,
+/// `<id>/<id>.json` with an optional patched client at `<id>/<id>.jar`
+pub struct LocalVersionFiles {
+    pub json: serde_json::Value,
+    pub inherits_from: Option<Ustr>,
+    pub client_jar: Option<PathBuf>,
+}
+
+/// Hashes of the version jsons, reused while a json's modification time is unchanged
+#[derive(Default)]
+pub struct LocalVersionHashes(HashMap<PathBuf, (SystemTime, Ustr)>);
+
+/// The fields needed to list a version before it's merged with the version it inherits from
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalVersionHeader {
+    id: Option<Ustr>,
+    inherits_from: Option<Ustr>,
+    r#type: Option<MinecraftVersionType>,
+    time: Option<DateTime<Utc>>,
+    release_time: Option<DateTime<Utc>>,
+    compliance_level: Option<u32>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AddLocalVersionError {
+    #[error("No version json was selected")]
+    MissingJson,
+    #[error("Only one version json and one client jar can be added at a time")]
+    TooManyFiles,
+    #[error("Invalid version id: {0}")]
+    InvalidId(Ustr),
+    #[error("{0} is an official version, change the id in the version json")]
+    OfficialVersion(Ustr),
+    #[error("Invalid version json:\n{0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("Failed to copy version files:\n{0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub fn read(dir: &Path, id: &str) -> Result<Option<LocalVersionFiles>, serde_json::Error> {
+    if !crate::is_single_component_path(id) {
+        return Ok(None);
+    }
+    let version_dir = dir.join(id);
+    let Ok(bytes) = std::fs::read(version_dir.join(format!("{}.json", id))) else {
+        return Ok(None);
+    };
+
+    let mut json: serde_json::Value = serde_json::from_slice(&bytes)?;
+    let header: LocalVersionHeader = serde_json::from_value(json.clone())?;
+    normalize_libraries(&mut json);
+
+    let client_jar = version_dir.join(format!("{}.jar", id));
+    Ok(Some(LocalVersionFiles {
+        json,
+        inherits_from: header.inherits_from,
+        client_jar: client_jar.is_file().then_some(client_jar),
+    }))
+}
+
+/// Lists the local versions like the versions of Mojang's manifest, newest first
+pub fn links(dir: &Path, hashes: &mut LocalVersionHashes) -> Vec<MinecraftVersionLink> {
+    let mut links = Vec::new();
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return links;
+    };
+
+    for entry in read_dir.flatten() {
+        let Some(id) = entry.file_name().to_str().map(Ustr::from) else {
+            continue;
+        };
+        let json_path = entry.path().join(format!("{}.json", id));
+        let Ok(bytes) = std::fs::read(&json_path) else {
+            continue;
+        };
+        let header = match serde_json::from_slice::<LocalVersionHeader>(&bytes) {
+            Ok(header) => header,
+            Err(error) => {
+                log::warn!("Ignoring invalid local version {}: {}", id, error);
+                continue;
+            },
+        };
+
+        let modified = std::fs::metadata(&json_path).and_then(|metadata| metadata.modified()).ok();
+        let sha1 = match hashes.0.get(&json_path) {
+            Some((hashed_at, sha1)) if Some(*hashed_at) == modified => *sha1,
+            _ => {
+                let sha1 = Ustr::from(hex::encode(Sha1::digest(&bytes)).as_str());
+                if let Some(modified) = modified {
+                    hashes.0.insert(json_path.clone(), (modified, sha1));
+                }
+                sha1
+            },
+        };
+
+        let release_time = header.release_time.or(header.time)
+            .unwrap_or_else(|| modified.map(DateTime::<Utc>::from).unwrap_or_default());
+        links.push(MinecraftVersionLink {
+            id,
+            r#type: header.r#type.unwrap_or(MinecraftVersionType::Release),
+            url: Ustr::from(json_path.to_string_lossy().as_ref()),
+            time: header.time.unwrap_or(release_time),
+            release_time,
+            sha1,
+            compliance_level: header.compliance_level.unwrap_or(0),
+        });
+    }
+
+    links.sort_by(|a, b| b.release_time.cmp(&a.release_time));
+    links
+}
+
+/// Adds the local versions to the top of Mojang's manifest, replacing official versions with the same id
+pub fn merge_into(manifest: &Arc<MinecraftVersionManifest>, local: Vec<MinecraftVersionLink>) -> Arc<MinecraftVersionManifest> {
+    if local.is_empty() {
+        return Arc::clone(manifest);
+    }
+
+    let mut merged = MinecraftVersionManifest::clone(manifest);
+    merged.versions.retain(|link| !local.iter().any(|local| local.id == link.id));
+    merged.versions.splice(0..0, local);
+    Arc::new(merged)
+}
+
+/// Copies a version json and optionally its client jar into the local versions directory, returning the id.
+/// Adding a version with the same id again replaces it
+pub fn add(dir: &Path, paths: &[PathBuf], official: Option<&MinecraftVersionManifest>) -> Result<Ustr, AddLocalVersionError> {
+    let has_extension = |path: &PathBuf, extension: &str| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+    let jsons: Vec<&PathBuf> = paths.iter().filter(|path| has_extension(path, "json")).collect();
+    let jars: Vec<&PathBuf> = paths.iter().filter(|path| has_extension(path, "jar")).collect();
+    if jsons.len() > 1 || jars.len() > 1 {
+        return Err(AddLocalVersionError::TooManyFiles);
+    }
+    let Some(json_path) = jsons.first() else {
+        return Err(AddLocalVersionError::MissingJson);
+    };
+
+    let bytes = std::fs::read(json_path)?;
+    let header: LocalVersionHeader = serde_json::from_slice(&bytes)?;
+    let id = match header.id {
+        Some(id) => id,
+        None => Ustr::from(json_path.file_stem().unwrap_or_default().to_string_lossy().as_ref()),
+    };
+    if !crate::is_single_component_path(&id) {
+        return Err(AddLocalVersionError::InvalidId(id));
+    }
+    if let Some(official) = official && official.versions.iter().any(|link| link.id == id) {
+        return Err(AddLocalVersionError::OfficialVersion(id));
+    }
+
+    let version_dir = dir.join(id.as_str());
+    std::fs::create_dir_all(&version_dir)?;
+    crate::write_safe(&version_dir.join(format!("{}.json", id)), &bytes)?;
+    let jar_path = version_dir.join(format!("{}.jar", id));
+    if let Some(jar) = jars.first() {
+        std::fs::copy(jar, jar_path)?;
+    } else if jar_path.exists() {
+        std::fs::remove_file(jar_path)?;
+    }
+
+    log::info!("Added local version {}", id);
+    Ok(id)
+}
+
+/// Versions made for other launchers often list libraries by name only, those are
+/// downloaded from the repository given with them or Mojang's library server
+fn normalize_libraries(json: &mut serde_json::Value) {
+    let Some(libraries) = json.get_mut("libraries").and_then(|libraries| libraries.as_array_mut()) else {
+        return;
+    };
+
+    for library in libraries {
+        let Some(library) = library.as_object_mut() else {
+            continue;
+        };
+        let base_url = library.remove("url");
+        if library.contains_key("downloads") {
+            continue;
+        }
+        let Some(name) = library.get("name").and_then(|name| name.as_str()) else {
+            continue;
+        };
+
+        let artifact_path = MavenCoordinate::create(name).artifact_path();
+        let base_url = base_url.as_ref().and_then(|url| url.as_str()).unwrap_or("https://libraries.minecraft.net/");
+        let mut url = base_url.to_string();
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+        url.push_str(&artifact_path);
+
+        library.insert("downloads".into(), serde_json::json!({
+            "artifact": {
+                "path": artifact_path,
+                "url": url,
+                "sha1": null,
+                "size": null,
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::version::PartialMinecraftVersion;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn name_only_libraries_get_downloads() {
+        let mut json = serde_json::json!({
+            "inheritsFrom": "1.20.1",
+            "libraries": [
+                { "name": "optifine:OptiFine:1.20.1_HD_U_I6", "url": "https://optifine.net/libraries" },
+                { "name": "net.minecraft:launchwrapper:1.12" },
+            ]
+        });
+        normalize_libraries(&mut json);
+
+        let version: PartialMinecraftVersion = serde_json::from_value(json).unwrap();
+        let libraries = version.libraries.unwrap();
+        let artifact = libraries[0].downloads.artifact.as_ref().unwrap();
+        assert_eq!(artifact.path.as_str(), "optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar");
+        assert_eq!(artifact.url.as_str(), "https://optifine.net/libraries/optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar");
+        let artifact = libraries[1].downloads.artifact.as_ref().unwrap();
+        assert_eq!(artifact.url.as_str(), "https://libraries.minecraft.net/net/minecraft/launchwrapper/1.12/launchwrapper-1.12.jar");
+    }
+
+    #[test]
+    fn added_versions_are_listed() {
+        let dir = TempDir::new("local-versions");
+
+        let json = dir.join("patched.json");
+        std::fs::write(&json, r#"{"id": "1.20.1-patched", "inheritsFrom": "1.20.1", "releaseTime": "2023-06-12T13:25:51+00:00"}"#).unwrap();
+        let jar = dir.join("patched.jar");
+        std::fs::write(&jar, b"jar").unwrap();
+
+        let versions_dir = dir.join("versions");
+        let id = add(&versions_dir, &[json, jar], None).unwrap();
+        assert_eq!(id.as_str(), "1.20.1-patched");
+
+        let mut hashes = LocalVersionHashes::default();
+        let links = links(&versions_dir, &mut hashes);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].id.as_str(), "1.20.1-patched");
+        assert_eq!(hashes.0.len(), 1);
+
+        let files = read(&versions_dir, "1.20.1-patched").unwrap().unwrap();
+        assert_eq!(files.inherits_from.as_deref(), Some("1.20.1"));
+        assert!(files.client_jar.is_some());
+
+        assert!(read(&versions_dir, "../versions").unwrap().is_none());
+    }
+}
@@ -1,22 +1,40 @@
 use std::{
-    collections::{HashMap, VecDeque}, fmt::Display, path::Path, sync::Arc, time::{Duration, Instant}
+    borrow::Cow, collections::{HashMap, VecDeque}, fmt::Display, path::{Path, PathBuf}, sync::Arc, time::{Duration, Instant, SystemTime}
 };
 
-use bridge::keep_alive::{KeepAlive, KeepAliveHandle};
-use reqwest::StatusCode;
+use bridge::{handle::FrontendHandle, keep_alive::{KeepAlive, KeepAliveHandle}, meta::{MetadataCacheEntry, MetadataCacheKind}, modal_action::{BackgroundTaskKind, ProgressTracker}};
+use parking_lot::{Mutex, RwLock};
+use reqwest::{header::{HeaderValue, ETAG, IF_NONE_MATCH}, StatusCode};
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::JavaRuntimes, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::MinecraftVersionManifest
+    assets_index::AssetsIndex, backend_config::{MetadataCacheConfig, MirrorConfig}, fabric_launch::FabricLaunch, fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::JavaRuntimes, maven::MavenMetadataXml, modrinth::{ModrinthCategoryTags, ModrinthProject, ModrinthProjectMembers, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthProjectsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthTeamsResult, ModrinthVersionFileUpdateResult, ModrinthVersionFilesRequest, ModrinthVersionFilesResult}, version::{MinecraftVersion, PartialMinecraftVersion}, version_manifest::MinecraftVersionManifest
 };
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
 use tokio::task::JoinHandle;
 use ustr::Ustr;
 
-use crate::metadata::items::MetadataItem;
+use crate::metadata::{items::{MetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem}, local_versions, rate_limit::{self, RateLimits}};
 
-const DATA_TTL: Duration = Duration::from_secs(5 * 60);
+/// Local versions can inherit from other local versions, this stops cycles
+const MAX_INHERITANCE_DEPTH: usize = 8;
 
-pub(super) type MetaLoadStateWrapper<T> = Arc<tokio::sync::Mutex<(Option<KeepAliveHandle>, MetaLoadState<T>)>>;
+pub(super) type MetaLoadStateWrapper<T> = Arc<tokio::sync::Mutex<(Option<KeepAliveHandle>, MetaLoadState<T>, Option<Validated<T>>)>>;
+
+/// The last downloaded value and its ETag. When the value is reloaded the ETag is sent as If-None-Match,
+/// and the value is reused if the server says it hasn't changed
+pub(super) struct Validated<T> {
+    etag: Arc<str>,
+    value: Arc<T>,
+}
+
+impl<T> Clone for Validated<T> {
+    fn clone(&self) -> Self {
+        Self {
+            etag: self.etag.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct MetadataManagerStates {
@@ -33,6 +51,12 @@ pub struct MetadataManagerStates {
     pub(super) modrinth_project_versions: HashMap<ModrinthProjectVersionsRequest, MetaLoadStateWrapper<ModrinthProjectVersionsResult>>,
     pub(super) modrinth_versions: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProjectVersion>>,
     pub(super) modrinth_version_updates: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthVersionFileUpdateResult>>,
+    pub(super) modrinth_version_files: HashMap<ModrinthVersionFilesRequest, MetaLoadStateWrapper<ModrinthVersionFilesResult>>,
+    pub(super) modrinth_projects: HashMap<Arc<[Arc<str>]>, MetaLoadStateWrapper<ModrinthProjectsResult>>,
+    pub(super) modrinth_project: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProject>>,
+    pub(super) modrinth_project_members: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProjectMembers>>,
+    pub(super) modrinth_teams: HashMap<Arc<[Arc<str>]>, MetaLoadStateWrapper<ModrinthTeamsResult>>,
+    pub(super) modrinth_category_tags: MetaLoadStateWrapper<ModrinthCategoryTags>,
 }
 
 pub struct MetadataManager {
@@ -44,10 +68,18 @@ pub struct MetadataManager {
     pub(super) fabric_loader_manifest_cache: Arc<Path>,
     pub(super) neoforge_installer_maven_cache: Arc<Path>,
     pub(super) forge_installer_maven_cache: Arc<Path>,
+    pub(super) modrinth_category_tags_cache: Arc<Path>,
+
+    local_versions_dir: Arc<Path>,
+    local_version_hashes: Arc<Mutex<local_versions::LocalVersionHashes>>,
 
     expiring: tokio::sync::Mutex<VecDeque<(Instant, KeepAlive)>>,
 
     http_client: reqwest::Client,
+    mirrors: Arc<RwLock<MirrorConfig>>,
+    cache_config: RwLock<MetadataCacheConfig>,
+    rate_limits: Arc<RateLimits>,
+    send: FrontendHandle,
 }
 
 #[derive(thiserror::Error, Clone, Debug)]
@@ -60,6 +92,8 @@ pub enum MetaLoadError {
     Error(Arc<str>),
     ErrorWithDescription(Arc<str>, Arc<str>),
     NonOK(u16),
+    /// Seconds until the server accepts requests again
+    RateLimited(u64),
 }
 
 impl Display for MetaLoadError {
@@ -106,6 +140,9 @@ impl Display for MetaLoadError {
             Self::NonOK(status_code) => {
                 f.write_fmt(format_args!("Non-OK response: {}", *status_code))
             }
+            Self::RateLimited(secs) => {
+                f.write_fmt(format_args!("Too many requests, try again in {}s", *secs))
+            }
             Self::TokioJoin(error) => f.debug_tuple("TokioJoin").field(error).finish(),
         }
     }
@@ -139,13 +176,20 @@ impl From<tokio::task::JoinError> for MetaLoadError {
 pub enum MetaLoadState<T> {
     #[default]
     Unloaded,
-    Pending(JoinHandle<Result<Arc<T>, MetaLoadError>>),
+    Pending(JoinHandle<Result<(Arc<T>, Option<Arc<str>>), MetaLoadError>>),
     Loaded(Arc<T>),
     Error(MetaLoadError),
 }
 
 impl MetadataManager {
-    pub fn new(http_client: reqwest::Client, directory: Arc<Path>) -> Self {
+    pub fn new(
+        http_client: reqwest::Client,
+        mirrors: Arc<RwLock<MirrorConfig>>,
+        cache_config: MetadataCacheConfig,
+        directory: Arc<Path>,
+        local_versions_dir: Arc<Path>,
+        send: FrontendHandle,
+    ) -> Self {
         Self {
             states: tokio::sync::Mutex::new(MetadataManagerStates::default()),
 
@@ -154,14 +198,139 @@ impl MetadataManager {
             fabric_loader_manifest_cache: directory.join("fabric_loader_manifest.json").into(),
             neoforge_installer_maven_cache: directory.join("neoforge_installer_maven.xml").into(),
             forge_installer_maven_cache: directory.join("forge_installer_maven.xml").into(),
+            modrinth_category_tags_cache: directory.join("modrinth_category_tags.json").into(),
             metadata_cache: directory,
 
+            local_versions_dir,
+            local_version_hashes: Default::default(),
+
             expiring: Default::default(),
 
             http_client,
+            mirrors,
+            cache_config: RwLock::new(cache_config),
+            rate_limits: Arc::new(RateLimits::new(send.clone())),
+            send,
         }
     }
 
+    pub fn set_cache_config(&self, cache_config: MetadataCacheConfig) {
+        *self.cache_config.write() = cache_config;
+    }
+
+    /// Lists the manifests cached on disk, along with the assets indexes in `assets_index_dir`
+    pub fn cache_entries(&self, assets_index_dir: &Path) -> Vec<MetadataCacheEntry> {
+        let manifests = [
+            (MetadataCacheKind::MinecraftVersionManifest, "Minecraft version manifest", &self.version_manifest_cache),
+            (MetadataCacheKind::MojangJavaRuntimes, "Java runtimes", &self.mojang_java_runtimes_cache),
+            (MetadataCacheKind::FabricLoaderManifest, "Fabric loader manifest", &self.fabric_loader_manifest_cache),
+            (MetadataCacheKind::ForgeMavenManifest, "Forge installer manifest", &self.forge_installer_maven_cache),
+            (MetadataCacheKind::NeoforgeMavenManifest, "NeoForge installer manifest", &self.neoforge_installer_maven_cache),
+            (MetadataCacheKind::ModrinthCategoryTags, "Modrinth categories", &self.modrinth_category_tags_cache),
+        ];
+
+        let mut entries = Vec::new();
+        for (kind, name, path) in manifests {
+            let metadata = std::fs::metadata(path).ok();
+            entries.push(MetadataCacheEntry {
+                kind,
+                name: name.into(),
+                path: path.clone(),
+                size: metadata.as_ref().map(|metadata| metadata.len()).unwrap_or(0),
+                modified: metadata.and_then(|metadata| metadata.modified().ok()),
+            });
+        }
+
+        let mut assets_indexes = Vec::new();
+        if let Ok(read_dir) = std::fs::read_dir(assets_index_dir) {
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if !metadata.is_file() || path.extension().is_none_or(|extension| extension != "json") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                assets_indexes.push(MetadataCacheEntry {
+                    kind: MetadataCacheKind::AssetsIndex,
+                    name: format!("Assets index {}", name).into(),
+                    path: path.as_path().into(),
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                });
+            }
+        }
+        assets_indexes.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.name, &b.name));
+        entries.extend(assets_indexes);
+
+        entries
+    }
+
+    /// Mojang's version manifest with the versions added by the user on top
+    pub async fn with_local_versions(&self, manifest: &Arc<MinecraftVersionManifest>) -> Arc<MinecraftVersionManifest> {
+        let dir = self.local_versions_dir.clone();
+        let hashes = self.local_version_hashes.clone();
+        let local = tokio::task::spawn_blocking(move || local_versions::links(&dir, &mut hashes.lock())).await;
+        match local {
+            Ok(local) => local_versions::merge_into(manifest, local),
+            Err(error) => {
+                log::error!("Failed to list local versions: {}", error);
+                Arc::clone(manifest)
+            },
+        }
+    }
+
+    pub fn add_local_version(&self, paths: &[PathBuf], official: Option<&MinecraftVersionManifest>) -> Result<Ustr, local_versions::AddLocalVersionError> {
+        local_versions::add(&self.local_versions_dir, paths, official)
+    }
+
+    pub fn has_local_version(&self, id: &str) -> bool {
+        matches!(local_versions::read(&self.local_versions_dir, id), Ok(Some(_)))
+    }
+
+    /// Loads a version added by the user, merged with the versions it inherits from. Also returns the
+    /// patched client jar of the version or the closest version it inherits from that has one
+    pub async fn fetch_local_version(&self, id: Ustr) -> Result<Option<(Arc<MinecraftVersion>, Option<PathBuf>)>, MetaLoadError> {
+        self.fetch_local_version_inner(id, 0).await
+    }
+
+    fn fetch_local_version_inner(&self, id: Ustr, depth: usize)
+        -> futures::future::BoxFuture<'_, Result<Option<(Arc<MinecraftVersion>, Option<PathBuf>)>, MetaLoadError>>
+    {
+        Box::pin(async move {
+            let Some(files) = local_versions::read(&self.local_versions_dir, &id)? else {
+                return Ok(None);
+            };
+
+            let Some(parent_id) = files.inherits_from else {
+                let version: MinecraftVersion = serde_json::from_value(files.json)?;
+                return Ok(Some((Arc::new(version), files.client_jar)));
+            };
+
+            if depth >= MAX_INHERITANCE_DEPTH {
+                return Err(MetaLoadError::Error(format!("Version {} inherits from too many versions", id).into()));
+            }
+
+            let (parent, parent_jar) = match self.fetch_local_version_inner(parent_id, depth + 1).await? {
+                Some(parent) => parent,
+                None => {
+                    let manifest = self.fetch(&MinecraftVersionManifestMetadataItem).await?;
+                    let Some(link) = manifest.versions.iter().find(|link| link.id == parent_id) else {
+                        return Err(MetaLoadError::Error(format!("Version {} inherits from unknown version {}", id, parent_id).into()));
+                    };
+                    (self.fetch(&MinecraftVersionMetadataItem(link)).await?, None)
+                },
+            };
+
+            let mut version: PartialMinecraftVersion = serde_json::from_value(files.json)?;
+            version.id.get_or_insert(id);
+            Ok(Some((Arc::new(version.apply_to(&parent)), files.client_jar.or(parent_jar))))
+        })
+    }
+
     pub async fn expire(&self) {
         let now = Instant::now();
 
@@ -181,20 +350,7 @@ impl MetadataManager {
 
         let is_valid = wrapper.0.as_ref().map(|h| h.is_alive()).unwrap_or(true);
         if !is_valid || matches!(wrapper.1, MetaLoadState::Unloaded) {
-            if item.expires() {
-                let keep_alive = KeepAlive::new();
-                let handle = keep_alive.create_handle();
-                wrapper.0 = Some(handle);
-                self.expiring.lock().await.push_back((Instant::now() + DATA_TTL, keep_alive));
-            }
-
-            let cache_file = item.cache_file(self);
-            Self::inner_start_loading(
-                &mut wrapper.1,
-                item,
-                cache_file,
-                &self.http_client,
-            );
+            self.start_loading(&mut wrapper, item, false).await;
         }
     }
 
@@ -208,20 +364,7 @@ impl MetadataManager {
 
         let is_valid = wrapper.0.as_ref().map(|h| h.is_alive()).unwrap_or(true);
         if force_reload || !is_valid || matches!(wrapper.1, MetaLoadState::Unloaded) {
-            if item.expires() {
-                let keep_alive = KeepAlive::new();
-                let handle = keep_alive.create_handle();
-                wrapper.0 = Some(handle);
-                self.expiring.lock().await.push_back((Instant::now() + DATA_TTL, keep_alive));
-            }
-
-            let cache_file = item.cache_file(self);
-            Self::inner_start_loading(
-                &mut wrapper.1,
-                item,
-                cache_file,
-                &self.http_client,
-            );
+            self.start_loading(&mut wrapper, item, force_reload).await;
         }
 
         let valid = wrapper.0.clone();
@@ -231,8 +374,9 @@ impl MetadataManager {
             MetaLoadState::Pending(join_handle) => {
                 let result = join_handle.await.map_err(MetaLoadError::from).flatten();
                 match result {
-                    Ok(value) => {
+                    Ok((value, etag)) => {
                         wrapper.1 = MetaLoadState::Loaded(Arc::clone(&value));
+                        wrapper.2 = etag.map(|etag| Validated { etag, value: Arc::clone(&value) });
                         (Ok(value), valid)
                     },
                     Err(error) => {
@@ -250,15 +394,68 @@ impl MetadataManager {
         }
     }
 
+    async fn start_loading<I: MetadataItem>(
+        &self,
+        wrapper: &mut (Option<KeepAliveHandle>, MetaLoadState<I::T>, Option<Validated<I::T>>),
+        item: &I,
+        force_reload: bool,
+    ) {
+        let cache_config = *self.cache_config.read();
+
+        if item.expires() {
+            let keep_alive = KeepAlive::new();
+            let handle = keep_alive.create_handle();
+            wrapper.0 = Some(handle);
+            let expires_at = Instant::now() + Duration::from_secs(cache_config.memory_ttl_secs);
+            self.expiring.lock().await.push_back((expires_at, keep_alive));
+        }
+
+        // Reloads are requested explicitly, so they should always hit the network
+        let disk_ttl = if force_reload {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(cache_config.disk_ttl_secs)
+        };
+
+        let cache_file = item.cache_file(self);
+        let validated = wrapper.2.clone();
+        Self::inner_start_loading(
+            &mut wrapper.1,
+            item,
+            cache_file,
+            validated,
+            disk_ttl,
+            &self.http_client,
+            &self.mirrors.read(),
+            self.rate_limits.clone(),
+            self.send.clone(),
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn inner_start_loading<I: MetadataItem>(
         state: &mut MetaLoadState<I::T>,
         item: &I,
         cache_file: Option<impl AsRef<Path> + Send + Sync + 'static>,
+        validated: Option<Validated<I::T>>,
+        disk_ttl: Duration,
         http_client: &reqwest::Client,
+        mirrors: &MirrorConfig,
+        rate_limits: Arc<RateLimits>,
+        send: FrontendHandle,
     ) {
         log::debug!("Loading metadata {:?}", item);
 
-        let request = item.request(http_client);
+        let request = item.request(http_client).build().map(|mut request| {
+            if let Cow::Owned(url) = mirrors.rewrite(request.url().as_str()) {
+                match reqwest::Url::parse(&url) {
+                    Ok(url) => *request.url_mut() = url,
+                    Err(error) => log::warn!("Ignoring invalid mirror url {}: {}", url, error),
+                }
+            }
+            request
+        });
+        let http_client = http_client.clone();
         let expected_hash = item.data_hash().and_then(|sha1| {
             let mut expected_hash = [0u8; 20];
             hex::decode_to_slice(sha1.as_str(), &mut expected_hash).ok()?;
@@ -266,14 +463,22 @@ impl MetadataManager {
         });
         let join_handle = tokio::task::spawn(async move {
             let mut file_fallback = None;
+            let mut validated = validated;
 
             if let Some(cache_file) = &cache_file {
+                let etag_file = etag_file(cache_file.as_ref());
                 let cache_file = cache_file.as_ref().to_owned();
                 let meta = tokio::task::spawn_blocking(move || {
                     let Ok(file) = std::fs::read(&cache_file) else {
                         return None;
                     };
 
+                    let fresh = std::fs::metadata(&cache_file)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                        .is_some_and(|age| age < disk_ttl);
+
                     let correct_hash = if let Some(expected_hash) = &expected_hash {
                         let mut hasher = Sha1::new();
                         hasher.update(&file);
@@ -292,7 +497,7 @@ impl MetadataManager {
                     let result = I::deserialize(&file);
                     match result {
                         Ok(meta) => {
-                            Some(meta)
+                            Some((meta, fresh))
                         },
                         Err(error) => {
                             log::warn!("Error parsing cached metadata file for {:?}, downloading file again... {}", cache_file, error);
@@ -300,19 +505,70 @@ impl MetadataManager {
                         },
                     }
                 }).await.unwrap();
-                if let Some(meta) = meta {
-                    if expected_hash.is_some() {
-                        return Ok(Arc::new(meta));
-                    } else {
-                        file_fallback = Some(Arc::new(meta));
+                if let Some((meta, fresh)) = meta {
+                    if expected_hash.is_some() || fresh {
+                        return Ok((Arc::new(meta), None));
                     }
+
+                    let meta = Arc::new(meta);
+                    if let Ok(etag) = tokio::fs::read_to_string(etag_file).await
+                        && !etag.trim().is_empty()
+                    {
+                        validated = Some(Validated { etag: etag.trim().into(), value: Arc::clone(&meta) });
+                    }
+                    file_fallback = Some(meta);
                 }
             }
 
-            let mut result: Result<Arc<I::T>, MetaLoadError> = async move {
-                let response = request.send().await?;
+            let mut result: Result<(Arc<I::T>, Option<Arc<str>>), MetaLoadError> = async move {
+                let request = request?;
+                let host: Option<Arc<str>> = request.url().host_str().map(Arc::from);
+
+                let title = format!("Fetching metadata from {}", host.as_deref().unwrap_or("???"));
+                let tracker = ProgressTracker::new(title.into(), send);
+                let _background_task = tracker.register_background(BackgroundTaskKind::Metadata, None);
+
+                let mut retries = 0;
+                let response = loop {
+                    let Some(mut attempt) = request.try_clone() else {
+                        return Err(MetaLoadError::Error("Request body can't be sent again".into()));
+                    };
+                    if let Some(validated) = &validated && let Ok(etag) = HeaderValue::from_str(&validated.etag) {
+                        attempt.headers_mut().insert(IF_NONE_MATCH, etag);
+                    }
+
+                    if let Some(host) = &host {
+                        rate_limits.wait(host).await;
+                    }
+                    let response = http_client.execute(attempt).await?;
+                    if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                        break response;
+                    }
+
+                    let wait = rate_limit::retry_after(response.headers());
+                    if retries >= rate_limit::MAX_RETRIES || wait > rate_limit::MAX_RETRY_WAIT {
+                        return Err(MetaLoadError::RateLimited(wait.as_secs()));
+                    }
+                    retries += 1;
+                    match &host {
+                        Some(host) => rate_limits.limit(host, wait),
+                        None => tokio::time::sleep(wait).await,
+                    }
+                };
 
                 let status = response.status();
+                if status == StatusCode::NOT_MODIFIED && let Some(validated) = validated {
+                    if let Some(cache_file) = &cache_file {
+                        // Bump the modified time so the cache counts as fresh again
+                        let cache_file = cache_file.as_ref().to_owned();
+                        let _ = tokio::task::spawn_blocking(move || {
+                            std::fs::File::options().write(true).open(cache_file)
+                                .and_then(|file| file.set_modified(SystemTime::now()))
+                        }).await;
+                    }
+                    return Ok((validated.value, Some(validated.etag)));
+                }
+
                 if status != StatusCode::OK {
                     if status == StatusCode::BAD_REQUEST {
                         if let Ok(bytes) = response.bytes().await {
@@ -334,6 +590,10 @@ impl MetadataManager {
                     return Err(MetaLoadError::NonOK(status.as_u16()));
                 }
 
+                let etag: Option<Arc<str>> = response.headers().get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(Arc::from);
+
                 let bytes = response.bytes().await?;
                 let bytes = I::post_process_download(&bytes)?;
 
@@ -356,13 +616,25 @@ impl MetadataManager {
                 }
 
                 if let Some(cache_file) = &cache_file {
-                    if let Some(parent) = cache_file.as_ref().parent() {
-                        let _ = tokio::fs::create_dir_all(parent).await;
-                    }
-                    let _ = tokio::fs::write(cache_file, bytes).await;
+                    let cache_file = cache_file.as_ref().to_owned();
+                    let etag = etag.clone();
+                    let bytes = bytes.into_owned();
+                    let _ = tokio::task::spawn_blocking(move || {
+                        // The old ETag is removed first and the new one only written once the body is stored, so an
+                        // ETag never ends up next to a body it doesn't belong to
+                        let etag_file = etag_file(&cache_file);
+                        let _ = std::fs::remove_file(&etag_file);
+                        if let Err(error) = crate::write_safe(&cache_file, &bytes) {
+                            log::warn!("Unable to cache metadata in {:?}: {}", cache_file, error);
+                            return;
+                        }
+                        if let Some(etag) = etag {
+                            let _ = std::fs::write(etag_file, etag.as_bytes());
+                        }
+                    }).await;
                 }
 
-                Ok(Arc::new(meta))
+                Ok((Arc::new(meta), etag))
             }
             .await;
 
@@ -372,7 +644,7 @@ impl MetadataManager {
                         "Error while fetching metadata {:?}, using file fallback: {error:?}",
                         std::any::type_name::<I::T>()
                     );
-                    result = Ok(file_fallback);
+                    result = Ok((file_fallback, None));
                 } else {
                     log::error!("Error while fetching metadata {:?}: {error:?}", std::any::type_name::<I::T>());
                 }
@@ -384,3 +656,10 @@ impl MetadataManager {
         *state = MetaLoadState::Pending(join_handle);
     }
 }
+
+/// The ETag of a cached file is stored next to it
+fn etag_file(cache_file: &Path) -> PathBuf {
+    let mut path = cache_file.as_os_str().to_owned();
+    path.push(".etag");
+    path.into()
+}
@@ -1,2 +1,4 @@
 pub mod manager;
 pub mod items;
+pub mod rate_limit;
+pub mod local_versions;
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant, SystemTime}};
+
+use bridge::{handle::FrontendHandle, message::MessageToFrontend};
+use parking_lot::Mutex;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+/// Rate limits longer than this are returned as an error instead of waiting them out
+pub const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+pub const MAX_RETRIES: usize = 3;
+
+/// Used when a 429 response doesn't say how long to wait
+const DEFAULT_RETRY_WAIT: Duration = Duration::from_secs(10);
+
+/// Hosts that responded with 429 Too Many Requests. Other requests to the same host wait
+/// until the limit has passed instead of making it worse
+pub struct RateLimits {
+    until: Mutex<HashMap<Arc<str>, Instant>>,
+    send: FrontendHandle,
+}
+
+impl RateLimits {
+    pub fn new(send: FrontendHandle) -> Self {
+        Self {
+            until: Default::default(),
+            send,
+        }
+    }
+
+    /// How long until requests to the host can be sent again, if it's currently rate limited
+    pub fn remaining(&self, host: &str) -> Option<Duration> {
+        let mut until = self.until.lock();
+        let remaining = until.get(host)?.checked_duration_since(Instant::now());
+        if remaining.is_none() {
+            until.remove(host);
+        }
+        remaining
+    }
+
+    pub async fn wait(&self, host: &str) {
+        if let Some(remaining) = self.remaining(host) {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// Records that the host is rate limited and lets the frontend know that requests will be retried
+    pub fn limit(&self, host: &Arc<str>, wait: Duration) {
+        log::warn!("Rate limited by {}, retrying in {}s", host, wait.as_secs());
+        self.until.lock().insert(host.clone(), Instant::now() + wait);
+        self.send.send(MessageToFrontend::MetadataRateLimited {
+            host: host.clone(),
+            retry_in_secs: wait.as_secs(),
+        });
+    }
+}
+
+/// How long a 429 response asks us to wait. Uses Retry-After, falling back to the
+/// X-Ratelimit-Reset header that Modrinth sends
+pub fn retry_after(headers: &HeaderMap) -> Duration {
+    let header = headers.get(RETRY_AFTER).or_else(|| headers.get("x-ratelimit-reset"));
+    header
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, SystemTime::now()))
+        .unwrap_or(DEFAULT_RETRY_WAIT)
+}
+
+/// Retry-After is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(SystemTime::from(date).duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seconds() {
+        assert_eq!(parse_retry_after("42", SystemTime::now()), Some(Duration::from_secs(42)));
+        assert_eq!(parse_retry_after(" 7 ", SystemTime::now()), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn parses_http_date() {
+        let now = SystemTime::from(chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap());
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(parse_retry_after("soon", SystemTime::now()), None);
+        assert_eq!(parse_retry_after("-5", SystemTime::now()), None);
+    }
+}
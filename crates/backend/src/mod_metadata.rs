@@ -2,18 +2,23 @@ use std::{
     io::{BufRead, Cursor, Read, Write}, path::{Path, PathBuf}, sync::Arc
 };
 
-use bridge::{instance::{AtomicContentUpdateStatus, ContentUpdateStatus, ContentType, ContentSummary}, safe_path::SafePath};
+use bridge::{instance::{AtomicContentUpdateStatus, ContentModrinthProject, ContentUpdateStatus, ContentType, ContentSummary}, safe_path::SafePath};
 use image::imageops::FilterType;
 use indexmap::IndexMap;
 use parking_lot::{RwLock, RwLockReadGuard};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use rc_zip_sync::EntryHandle;
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{content::ContentSource, fabric_mod::{FabricModJson, Icon, Person}, forge_mod::{JarJarMetadata, ModsToml}, modrinth::{ModrinthFile, ModrinthSideRequirement}, mrpack::ModrinthIndexJson, resourcepack::PackMcmeta};
+use schema::{content::ContentSource, fabric_mod::{FabricModJson, Icon, Person}, forge_mod::{JarJarMetadata, McModInfo, ModsToml}, modrinth::{ModrinthFile, ModrinthProject, ModrinthSideRequirement, ModrinthVersionFilesRequest}, mrpack::ModrinthIndexJson, quilt_mod::QuiltModJson, resourcepack::PackMcmeta};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeAs};
 use sha1::{Digest, Sha1};
 
+use crate::{content_cache::ContentCache, metadata::{items::{ModrinthProjectsMetadataItem, ModrinthTeamsMetadataItem, ModrinthVersionFilesMetadataItem}, manager::{MetaLoadError, MetadataManager}}};
+
+/// Maximum number of hashes sent to Modrinth in a single lookup request
+const MODRINTH_LOOKUP_BATCH_SIZE: usize = 100;
+
 #[derive(Clone)]
 pub enum ModUpdateAction {
     ErrorNotFound,
@@ -38,12 +43,17 @@ impl ModUpdateAction {
     }
 }
 
+/// Jars bundled inside jars are only looked into this many levels deep, so a jar nesting itself can't recurse forever
+const MAX_NESTED_JAR_DEPTH: usize = 4;
+
 pub struct ModMetadataManager {
     content_library_dir: Arc<Path>,
     sources_dir: PathBuf,
+    cache: ContentCache,
     by_hash: RwLock<FxHashMap<[u8; 20], Option<Arc<ContentSummary>>>>,
     content_sources: RwLock<ContentSources>,
     parents_by_missing_child: RwLock<FxHashMap<[u8; 20], Vec<[u8; 20]>>>,
+    modrinth_projects: RwLock<FxHashMap<[u8; 20], Option<Arc<ContentModrinthProject>>>>,
     pub updates: RwLock<FxHashMap<[u8; 20], ModUpdateAction>>,
 }
 
@@ -69,12 +79,16 @@ impl ModMetadataManager {
             Default::default()
         };
 
+        let cache = ContentCache::load(&content_meta_dir);
+
         Self {
             content_library_dir,
             sources_dir,
+            cache,
             by_hash: Default::default(),
             content_sources: RwLock::new(content_sources),
             parents_by_missing_child: Default::default(),
+            modrinth_projects: Default::default(),
             updates: Default::default(),
         }
     }
@@ -98,43 +112,160 @@ impl ModMetadataManager {
         }
     }
 
-    pub fn get_path(self: &Arc<Self>, path: &Path) -> Option<Arc<ContentSummary>> {
-        let mut file = std::fs::File::open(path).ok()?;
-        self.get_file(&mut file)
+    /// Matches content against Modrinth by hash, filling in `ContentSummary::modrinth_project`.
+    /// Each hash is only looked up once, returns true if any summaries were updated
+    pub async fn lookup_modrinth_projects(&self, meta: &MetadataManager, summaries: impl Iterator<Item = Arc<ContentSummary>>) -> bool {
+        let mut changed = false;
+        let mut pending = Vec::new();
+
+        {
+            let mut modrinth_projects = self.modrinth_projects.write();
+            for summary in summaries {
+                if summary.modrinth_project.get().is_some() {
+                    continue;
+                }
+                match modrinth_projects.get(&summary.hash) {
+                    Some(Some(project)) => {
+                        _ = summary.modrinth_project.set(project.clone());
+                        changed = true;
+                    },
+                    Some(None) => {},
+                    None => {
+                        // Inserting None marks the hash as looked up, so it won't be requested again while pending
+                        modrinth_projects.insert(summary.hash, None);
+                        pending.push(summary);
+                    },
+                }
+            }
+        }
+
+        for batch in pending.chunks(MODRINTH_LOOKUP_BATCH_SIZE) {
+            match Self::lookup_modrinth_batch(meta, batch).await {
+                Ok(found) => {
+                    let mut modrinth_projects = self.modrinth_projects.write();
+                    for (summary, project) in batch.iter().zip(found) {
+                        if let Some(project) = project {
+                            modrinth_projects.insert(summary.hash, Some(project.clone()));
+                            _ = summary.modrinth_project.set(project);
+                            changed = true;
+                        }
+                    }
+                },
+                Err(error) => {
+                    log::warn!("Unable to look up content on Modrinth: {error}");
+
+                    // Allow the lookup to be retried next time the content is loaded
+                    let mut modrinth_projects = self.modrinth_projects.write();
+                    for summary in batch {
+                        modrinth_projects.remove(&summary.hash);
+                    }
+                },
+            }
+        }
+
+        changed
     }
 
-    pub fn get_file(self: &Arc<Self>, file: &mut std::fs::File) -> Option<Arc<ContentSummary>> {
-        let mut hasher = Sha1::new();
-        let _ = std::io::copy(file, &mut hasher).ok()?;
-        let actual_hash: [u8; 20] = hasher.finalize().into();
+    async fn lookup_modrinth_batch(meta: &MetadataManager, batch: &[Arc<ContentSummary>]) -> Result<Vec<Option<Arc<ContentModrinthProject>>>, MetaLoadError> {
+        let hashes: Arc<[Arc<str>]> = batch.iter().map(|summary| Arc::from(hex::encode(summary.hash))).collect();
 
-        if let Some(summary) = self.by_hash.read().get(&actual_hash) {
-            return summary.clone();
+        let versions = meta.fetch(&ModrinthVersionFilesMetadataItem(&ModrinthVersionFilesRequest {
+            hashes: hashes.clone(),
+            algorithm: "sha1".into(),
+        })).await?;
+
+        if versions.0.is_empty() {
+            return Ok(vec![None; batch.len()]);
         }
 
-        let summary = self.load_mod_summary(actual_hash, file, true);
+        let mut project_ids: Vec<Arc<str>> = versions.0.values().map(|version| version.project_id.clone()).collect();
+        project_ids.sort();
+        project_ids.dedup();
+
+        let projects = meta.fetch(&ModrinthProjectsMetadataItem(project_ids.into())).await?;
+        let projects_by_id: FxHashMap<&str, &ModrinthProject> = projects.0.iter()
+            .map(|project| (&*project.id, project))
+            .collect();
+
+        let mut team_ids: Vec<Arc<str>> = projects.0.iter().map(|project| project.team.clone()).collect();
+        team_ids.sort();
+        team_ids.dedup();
+
+        let teams = meta.fetch(&ModrinthTeamsMetadataItem(team_ids.into())).await?;
+        let authors_by_team: FxHashMap<&str, Arc<str>> = teams.0.iter().filter_map(|members| {
+            let team_id = &*members.first()?.team_id;
+            let names: Vec<&str> = members.iter().map(|member| &*member.user.username).collect();
+            Some((team_id, Arc::from(format!("By {}", names.join(", ")))))
+        }).collect();
+
+        Ok(hashes.iter().map(|hash| {
+            let version = versions.0.get(hash)?;
+            let project = projects_by_id.get(&*version.project_id)?;
+
+            Some(Arc::new(ContentModrinthProject {
+                id: project.id.clone(),
+                slug: project.slug.clone(),
+                title: project.title.clone(),
+                description: project.description.clone(),
+                authors: authors_by_team.get(&*project.team).cloned().unwrap_or_default(),
+            }))
+        }).collect())
+    }
 
-        self.put(actual_hash, summary.clone());
+    pub fn get_path(self: &Arc<Self>, path: &Path) -> Option<Arc<ContentSummary>> {
+        let mut file = std::fs::File::open(path).ok()?;
+        let metadata = file.metadata().ok()?;
+
+        let hash = match self.cache.get_file_hash(path, &metadata) {
+            Some(hash) => hash,
+            None => {
+                let mut hasher = Sha1::new();
+                let _ = std::io::copy(&mut file, &mut hasher).ok()?;
+                let hash: [u8; 20] = hasher.finalize().into();
+                self.cache.set_file_hash(path, &metadata, hash);
+                hash
+            },
+        };
 
-        summary
+        self.get_or_load(hash, &file, 0)
     }
 
     pub fn get_bytes(self: &Arc<Self>, bytes: &[u8]) -> Option<Arc<ContentSummary>> {
+        self.get_nested_bytes(bytes, 0)
+    }
+
+    /// `depth` is the number of jars the bytes are nested in
+    fn get_nested_bytes(self: &Arc<Self>, bytes: &[u8], depth: usize) -> Option<Arc<ContentSummary>> {
         let mut hasher = Sha1::new();
         hasher.write_all(bytes).ok()?;
         let actual_hash: [u8; 20] = hasher.finalize().into();
 
-        if let Some(summary) = self.by_hash.read().get(&actual_hash) {
+        self.get_or_load(actual_hash, &bytes, depth)
+    }
+
+    fn get_or_load<R: rc_zip_sync::ReadZip>(self: &Arc<Self>, hash: [u8; 20], file: &R, depth: usize) -> Option<Arc<ContentSummary>> {
+        if let Some(summary) = self.by_hash.read().get(&hash) {
             return summary.clone();
         }
 
-        let summary = self.load_mod_summary(actual_hash, &bytes, true);
+        if let Some(summary) = self.cache.get_summary(hash) {
+            self.put(hash, summary.clone());
+            return summary;
+        }
+
+        let summary = self.load_mod_summary(hash, file, true, depth);
 
-        self.put(actual_hash, summary.clone());
+        self.cache.set_summary(hash, summary.as_deref());
+        self.put(hash, summary.clone());
 
         summary
     }
 
+    /// Writes file hashes learned while loading content, so they don't need to be computed after a restart
+    pub fn save_file_hashes(&self) {
+        self.cache.save_file_hashes();
+    }
+
     fn put(self: &Arc<Self>, hash: [u8; 20], summary: Option<Arc<ContentSummary>>) {
         self.by_hash.write().insert(hash, summary.clone());
 
@@ -147,26 +278,41 @@ impl ModMetadataManager {
         }
     }
 
-    fn load_mod_summary<R: rc_zip_sync::ReadZip>(self: &Arc<Self>, hash: [u8; 20], file: &R, allow_children: bool) -> Option<Arc<ContentSummary>> {
+    fn load_mod_summary<R: rc_zip_sync::ReadZip>(self: &Arc<Self>, hash: [u8; 20], file: &R, allow_children: bool, depth: usize) -> Option<Arc<ContentSummary>> {
         let archive = file.read_zip().ok()?;
 
-        if let Some(file) = archive.by_name("fabric.mod.json") {
-            self.load_fabric_mod(hash, &archive, file)
-        } else if let Some(file) = archive.by_name("META-INF/mods.toml") {
-            self.load_forge_mod(hash, &archive, file, ContentType::Forge)
-        } else if let Some(file) = archive.by_name("META-INF/neoforge.mods.toml") {
-            self.load_forge_mod(hash, &archive, file, ContentType::NeoForge)
-        } else if let Some(file) = archive.by_name("META-INF/jarjar/metadata.json") {
-            self.load_jarjar(hash, &archive, file)
-        } else if let Some(file) = archive.by_name("META-INF/MANIFEST.MF") {
-            self.load_from_java_manifest(hash, &archive, file)
-        } else if let Some(file) = archive.by_name("pack.mcmeta") {
-            self.load_from_pack_mcmeta(hash, &archive, file)
-        } else if allow_children && let Some(file) = archive.by_name("modrinth.index.json") {
-            self.load_modrinth_modpack(hash, &archive, file)
-        } else {
-            None
+        // Multi-loader jars can contain metadata for several loaders, if one fails to parse try the next
+        if let Some(file) = archive.by_name("fabric.mod.json") && let Some(summary) = self.load_fabric_mod(hash, &archive, file) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("quilt.mod.json") && let Some(summary) = self.load_quilt_mod(hash, &archive, file) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("META-INF/mods.toml") && let Some(summary) = self.load_forge_mod(hash, &archive, file, ContentType::Forge) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("META-INF/neoforge.mods.toml") && let Some(summary) = self.load_forge_mod(hash, &archive, file, ContentType::NeoForge) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("mcmod.info") && let Some(summary) = self.load_legacy_forge_mod(hash, &archive, file) {
+            return Some(summary);
+        }
+        if depth < MAX_NESTED_JAR_DEPTH && let Some(file) = archive.by_name("META-INF/jarjar/metadata.json") && let Some(summary) = self.load_jarjar(hash, &archive, file, depth) {
+            return Some(summary);
+        }
+        if depth < MAX_NESTED_JAR_DEPTH && let Some(summary) = self.load_nested_jars(&archive, depth) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("META-INF/MANIFEST.MF") && let Some(summary) = self.load_from_java_manifest(hash, &archive, file) {
+            return Some(summary);
+        }
+        if let Some(file) = archive.by_name("pack.mcmeta") {
+            return self.load_from_pack_mcmeta(hash, &archive, file);
         }
+        if allow_children && let Some(file) = archive.by_name("modrinth.index.json") {
+            return self.load_modrinth_modpack(hash, &archive, file);
+        }
+        None
     }
 
     fn load_fabric_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
@@ -185,18 +331,10 @@ impl ModMetadataManager {
 
         drop(file);
 
+        let loader_requirements = crate::loader_requirements::fabric_requirements(&fabric_mod_json);
         let name = fabric_mod_json.name.unwrap_or_else(|| Arc::clone(&fabric_mod_json.id));
 
-        let icon = match fabric_mod_json.icon {
-            Some(icon) => match icon {
-                Icon::Single(icon) => Some(icon),
-                Icon::Sizes(hash_map) => {
-                    const DESIRED_SIZE: usize = 64;
-                    hash_map.iter().min_by_key(|size| size.0.abs_diff(DESIRED_SIZE)).map(|e| Arc::clone(e.1))
-                },
-            },
-            None => None,
-        };
+        let icon = fabric_mod_json.icon.as_ref().and_then(select_icon);
 
         let mut png_icon: Option<Arc<[u8]>> = None;
         if let Some(icon) = icon && let Some(icon_file) = archive.by_name(&icon) {
@@ -215,12 +353,120 @@ impl ModMetadataManager {
             name: Some(name),
             authors,
             version_str: format!("v{}", fabric_mod_json.version).into(),
+            description: fabric_mod_json.description.filter(|description| !description.trim_ascii().is_empty()),
+            homepage: fabric_mod_json.contact.and_then(|contact| contact.homepage.or(contact.sources)),
             png_icon,
+            loader_requirements,
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra: ContentType::Fabric
         }))
     }
 
+    fn load_quilt_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
+        let bytes = file.bytes().ok()?;
+
+        let quilt_mod_json: QuiltModJson = serde_json::from_slice(&bytes).inspect_err(|e| {
+            log::error!("Error parsing quilt.mod.json: {e}");
+        }).ok()?;
+
+        drop(file);
+
+        let loader = quilt_mod_json.quilt_loader;
+        let metadata = loader.metadata;
+
+        let name = metadata.name.unwrap_or_else(|| Arc::clone(&loader.id));
+
+        let mut png_icon: Option<Arc<[u8]>> = None;
+        if let Some(icon) = metadata.icon.as_ref().and_then(select_icon) && let Some(icon_file) = archive.by_name(&icon) {
+            png_icon = load_icon(icon_file);
+        }
+
+        let authors = if metadata.contributors.is_empty() {
+            "".into()
+        } else {
+            let names: Vec<&str> = metadata.contributors.keys().map(|name| &**name).collect();
+            format!("By {}", names.join(", ")).into()
+        };
+
+        Some(Arc::new(ContentSummary {
+            id: Some(loader.id),
+            hash,
+            name: Some(name),
+            authors,
+            version_str: format!("v{}", loader.version).into(),
+            description: metadata.description.filter(|description| !description.trim_ascii().is_empty()),
+            homepage: metadata.contact.get("homepage").or_else(|| metadata.contact.get("sources")).cloned(),
+            png_icon,
+            loader_requirements: Default::default(),
+            update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
+            // Quilt isn't a supported loader, but Quilt mods are closest to Fabric mods
+            extra: ContentType::Fabric,
+        }))
+    }
+
+    fn load_legacy_forge_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
+        let mut bytes = file.bytes().ok()?;
+
+        // Raw newlines inside strings are very common in mcmod.info files
+        for byte in bytes.iter_mut() {
+            if *byte == '\n' as u8 || *byte == '\r' as u8 {
+                *byte = ' ' as u8;
+            }
+        }
+
+        let mcmod_info: McModInfo = serde_json::from_slice(&bytes).inspect_err(|e| {
+            log::error!("Error parsing mcmod.info: {e}");
+        }).ok()?;
+
+        drop(file);
+
+        let first = mcmod_info.mods().first()?;
+
+        let name = first.name.clone().filter(|name| !name.is_empty()).unwrap_or_else(|| Arc::clone(&first.modid));
+
+        let mut png_icon: Option<Arc<[u8]>> = None;
+        if let Some(icon) = &first.logo_file && let Some(icon_file) = archive.by_name(icon.trim_start_matches('/')) {
+            png_icon = load_icon(icon_file);
+        }
+
+        let authors = if first.author_list.is_empty() {
+            "".into()
+        } else {
+            format!("By {}", first.author_list.join(", ")).into()
+        };
+
+        // Versions are often left as a build script placeholder such as ${version}
+        let version = first.version.as_deref().filter(|version| !version.is_empty() && !version.contains("${"));
+        let version: Arc<str> = if let Some(version) = version {
+            format!("v{version}").into()
+        } else if let Some(manifest) = archive.by_name("META-INF/MANIFEST.MF")
+            && let Ok(manifest_bytes) = manifest.bytes()
+            && let Ok(manifest_str) = str::from_utf8(&manifest_bytes)
+            && let Some(impl_version) = crate::java_manifest::parse_java_manifest(manifest_str).get("Implementation-Version")
+        {
+            format!("v{impl_version}").into()
+        } else {
+            "".into()
+        };
+
+        Some(Arc::new(ContentSummary {
+            id: Some(first.modid.clone()),
+            hash,
+            name: Some(name),
+            authors,
+            version_str: version,
+            description: first.description.as_deref().map(str::trim_ascii).filter(|description| !description.is_empty()).map(Arc::from),
+            homepage: first.url.clone().filter(|url| !url.is_empty()),
+            png_icon,
+            loader_requirements: Default::default(),
+            update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
+            extra: ContentType::Forge,
+        }))
+    }
+
     fn load_forge_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>, extra: ContentType) -> Option<Arc<ContentSummary>> {
         let bytes = file.bytes().ok()?;
 
@@ -231,6 +477,7 @@ impl ModMetadataManager {
         let Some(first) = mods_toml.mods.first() else {
             return None;
         };
+        let loader_requirements = crate::loader_requirements::mods_toml_requirements(&mods_toml);
 
         drop(file);
 
@@ -267,8 +514,12 @@ impl ModMetadataManager {
             name: Some(name),
             authors,
             version_str: version.into(),
+            description: first.description.as_deref().map(str::trim_ascii).filter(|description| !description.is_empty()).map(Arc::from),
+            homepage: first.display_url.clone(),
             png_icon,
+            loader_requirements,
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra,
         }))
     }
@@ -335,7 +586,7 @@ impl ModMetadataManager {
             }
 
             if let Ok(mut file) = std::fs::File::open(file) {
-                let summary = self.load_mod_summary(file_hash, &mut file, false);
+                let summary = self.load_mod_summary(file_hash, &mut file, false, 0);
                 self.put(file_hash, summary.clone());
                 return summary;
             }
@@ -365,8 +616,12 @@ impl ModMetadataManager {
             name: Some(modrinth_index_json.name),
             authors,
             version_str: format!("v{}", modrinth_index_json.version_id).into(),
+            description: modrinth_index_json.summary,
+            homepage: None,
             png_icon,
+            loader_requirements: Default::default(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra: ContentType::ModrinthModpack {
                 downloads: modrinth_index_json.files,
                 summaries: summaries.into(),
@@ -375,7 +630,7 @@ impl ModMetadataManager {
         }))
     }
 
-    fn load_jarjar<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
+    fn load_jarjar<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>, depth: usize) -> Option<Arc<ContentSummary>> {
         let bytes = file.bytes().ok()?;
 
         let metadata_json: JarJarMetadata = serde_json::from_slice(&bytes).inspect_err(|e| {
@@ -391,7 +646,29 @@ impl ModMetadataManager {
             let Ok(child_bytes) = child.bytes() else {
                 continue;
             };
-            if let Some(child) = self.get_bytes(&child_bytes) {
+            if let Some(child) = self.get_nested_bytes(&child_bytes, depth + 1) {
+                return Some(child);
+            }
+        }
+
+        None
+    }
+
+    /// Jars that only bundle other jars (e.g. Fabric's META-INF/jars) use the metadata of the first nested mod
+    fn load_nested_jars<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, archive: &rc_zip_sync::ArchiveHandle<R>, depth: usize) -> Option<Arc<ContentSummary>> {
+        for entry in archive.entries() {
+            if entry.kind() != rc_zip_sync::rc_zip::EntryKind::File {
+                continue;
+            }
+            let is_nested_jar = (entry.name.starts_with("META-INF/jars/") || entry.name.starts_with("META-INF/jarjar/"))
+                && entry.name.ends_with(".jar");
+            if !is_nested_jar {
+                continue;
+            }
+            let Ok(child_bytes) = entry.bytes() else {
+                continue;
+            };
+            if let Some(child) = self.get_nested_bytes(&child_bytes, depth + 1) {
                 return Some(child);
             }
         }
@@ -438,8 +715,12 @@ impl ModMetadataManager {
             name: Some(name.clone()),
             authors: author.unwrap_or_default(),
             version_str: version.unwrap_or_default(),
+            description: None,
+            homepage: None,
             png_icon: None,
+            loader_requirements: Default::default(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra: ContentType::JavaModule
         }))
     }
@@ -464,13 +745,27 @@ impl ModMetadataManager {
             name: None,
             authors: "".into(),
             version_str: pack_mcmeta.pack.description,
+            description: None,
+            homepage: None,
             png_icon,
+            loader_requirements: Default::default(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra: ContentType::ResourcePack
         }))
     }
 }
 
+fn select_icon(icon: &Icon) -> Option<Arc<str>> {
+    match icon {
+        Icon::Single(icon) => Some(Arc::clone(icon)),
+        Icon::Sizes(hash_map) => {
+            const DESIRED_SIZE: usize = 64;
+            hash_map.iter().min_by_key(|size| size.0.abs_diff(DESIRED_SIZE)).map(|e| Arc::clone(e.1))
+        },
+    }
+}
+
 fn load_icon<R: rc_zip_sync::HasCursor>(icon_file: rc_zip_sync::EntryHandle<R>) -> Option<Arc<[u8]>> {
     let Ok(mut icon_bytes) = icon_file.bytes() else {
         return None;
@@ -0,0 +1,428 @@
+use std::{collections::{BTreeMap, BTreeSet}, path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{install::ModpackFileInfo, instance::{ContentType, ModpackUpdateDiff}, safe_path::SafePath};
+use rc_zip_sync::ReadZip;
+use schema::{loader::Loader, modrinth::ModrinthSideRequirement, mrpack::ModrinthIndexJson};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+/// Remembers the files modpacks copied into .minecraft, so files the user changed since can be left alone
+pub const MODPACK_STATE_FILENAME: &str = ".pandora_modpack.json";
+
+/// Files that are about to be replaced, kept until all modpack files were copied
+const BACKUP_DIRNAME: &str = ".pandora_modpack_backup";
+/// The backup once all modpack files were copied, it's only there to be deleted and is never restored
+const DISCARDED_BACKUP_DIRNAME: &str = ".pandora_modpack_backup.old";
+
+/// A modpack file that gets copied into .minecraft
+pub struct ModpackFile {
+    pub path: SafePath,
+    /// The copy of the file in the content library
+    pub source: PathBuf,
+    pub sha1: [u8; 20],
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModpackState {
+    /// Hex-encoded SHA-1 of the files as they were written, by their path in .minecraft
+    #[serde(default)]
+    pub written: BTreeMap<String, String>,
+}
+
+impl ModpackState {
+    pub fn read(instance_dir: &Path) -> Self {
+        let Ok(bytes) = std::fs::read(instance_dir.join(MODPACK_STATE_FILENAME)) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+            log::warn!("Ignoring invalid modpack state in {:?}: {}", instance_dir, error);
+            Self::default()
+        })
+    }
+
+    pub fn write(&self, instance_dir: &Path) -> std::io::Result<()> {
+        crate::write_safe(&instance_dir.join(MODPACK_STATE_FILENAME), &serde_json::to_vec(self)?)
+    }
+
+    /// Files that were never written by a modpack count as unmodified, modpacks used to overwrite them on every launch
+    fn is_user_modified(&self, path: &str, current: Option<[u8; 20]>) -> bool {
+        match (self.written.get(path), current) {
+            (Some(written), Some(current)) => *written != hex::encode(current),
+            _ => false,
+        }
+    }
+}
+
+fn hash_file(path: &Path) -> Option<[u8; 20]> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(Sha1::digest(&bytes).into())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReadModpackFileError {
+    #[error("Unable to read file:\n{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a zip file:\n{0}")]
+    Zip(#[from] rc_zip_sync::rc_zip::Error),
+    #[error("Not a Modrinth modpack, modrinth.index.json is missing")]
+    MissingIndex,
+    #[error("Invalid modrinth.index.json:\n{0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("Modpacks for {0} aren't supported")]
+    UnsupportedLoader(Arc<str>),
+}
+
+/// Reads what a .mrpack file opened from outside the launcher will be installed as
+pub fn read_modpack_file(path: &Path) -> Result<ModpackFileInfo, ReadModpackFileError> {
+    let file = std::fs::File::open(path)?;
+    let archive = file.read_zip()?;
+    let Some(index) = archive.by_name("modrinth.index.json") else {
+        return Err(ReadModpackFileError::MissingIndex);
+    };
+    let index: ModrinthIndexJson = serde_json::from_slice(&index.bytes()?)?;
+    file_info(index)
+}
+
+fn file_info(index: ModrinthIndexJson) -> Result<ModpackFileInfo, ReadModpackFileError> {
+    let mut loader = Loader::Vanilla;
+    for dependency in index.dependencies.keys() {
+        loader = match &**dependency {
+            "minecraft" => continue,
+            "fabric-loader" => Loader::Fabric,
+            "forge" => Loader::Forge,
+            "neoforge" => Loader::NeoForge,
+            _ => return Err(ReadModpackFileError::UnsupportedLoader(dependency.clone())),
+        };
+    }
+
+    Ok(ModpackFileInfo {
+        name: index.name,
+        version: index.version_id,
+        minecraft_version: index.dependencies.get("minecraft").cloned(),
+        loader,
+    })
+}
+
+/// The files of a modpack that end up in .minecraft, with the hex-encoded SHA-1 of their contents
+pub fn pack_files(extra: &ContentType) -> BTreeMap<Arc<str>, Arc<str>> {
+    let mut files = BTreeMap::new();
+    let ContentType::ModrinthModpack { downloads, overrides, .. } = extra else {
+        return files;
+    };
+
+    for download in downloads.iter() {
+        if download.env.is_some_and(|env| env.client == ModrinthSideRequirement::Unsupported) {
+            continue;
+        }
+        files.insert(download.path.clone(), download.hashes.sha1.clone());
+    }
+    for (path, bytes) in overrides.iter() {
+        files.insert(Arc::from(path.as_str()), Arc::from(hex::encode(Sha1::digest(bytes))));
+    }
+
+    files
+}
+
+/// Compares the files of two versions of a modpack. Changed files that the user edited are listed as preserved
+pub fn diff(
+    old: &BTreeMap<Arc<str>, Arc<str>>,
+    new: &BTreeMap<Arc<str>, Arc<str>>,
+    state: &ModpackState,
+    dot_minecraft: &Path,
+) -> ModpackUpdateDiff {
+    let mut diff = ModpackUpdateDiff::default();
+
+    for (path, hash) in new {
+        match old.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(old_hash) if old_hash != hash => diff.updated.push(path.clone()),
+            Some(_) => {},
+        }
+    }
+    diff.removed.extend(old.keys().filter(|path| !new.contains_key(*path)).cloned());
+
+    for path in diff.updated.iter().chain(diff.removed.iter()) {
+        let Some(safe_path) = SafePath::new(path) else {
+            continue;
+        };
+        if state.is_user_modified(path, hash_file(&safe_path.to_path(dot_minecraft))) {
+            diff.preserved.push(path.clone());
+        }
+    }
+
+    diff
+}
+
+/// Copies the modpack files into .minecraft and removes files of previous versions that are no
+/// longer part of any modpack. Files the user changed since they were copied are left alone.
+/// Either all changes are made or none are, the previous files are put back if anything fails
+pub fn apply_files(instance_dir: &Path, dot_minecraft: &Path, files: &[ModpackFile]) -> std::io::Result<()> {
+    let backup_dir = instance_dir.join(BACKUP_DIRNAME);
+    let discarded_backup_dir = instance_dir.join(DISCARDED_BACKUP_DIRNAME);
+    if backup_dir.exists() {
+        log::warn!("Restoring modpack files from an interrupted update in {:?}", instance_dir);
+        restore_backup(&backup_dir, dot_minecraft);
+    }
+    if discarded_backup_dir.exists() {
+        _ = std::fs::remove_dir_all(&discarded_backup_dir);
+    }
+
+    let mut state = ModpackState::read(instance_dir);
+    let mut written = BTreeMap::new();
+    let mut copies = Vec::new();
+
+    for file in files {
+        let key = file.path.as_str().to_string();
+        let dest = file.path.to_path(dot_minecraft);
+        let current = hash_file(&dest);
+
+        if current == Some(file.sha1) {
+            written.insert(key, hex::encode(file.sha1));
+        } else if state.is_user_modified(&key, current) {
+            log::info!("Keeping {} because it was changed since the modpack copied it", key);
+            written.insert(key.clone(), state.written[&key].clone());
+        } else {
+            written.insert(key, hex::encode(file.sha1));
+            copies.push((file.source.as_path(), dest));
+        }
+    }
+
+    let wanted: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    let mut removals = Vec::new();
+    for key in state.written.keys().filter(|key| !wanted.contains(key.as_str())) {
+        let Some(path) = SafePath::new(key) else {
+            continue;
+        };
+        let dest = path.to_path(dot_minecraft);
+        let current = hash_file(&dest);
+        if current.is_some() && !state.is_user_modified(key, current) {
+            removals.push(dest);
+        }
+    }
+
+    if copies.is_empty() && removals.is_empty() {
+        if written != state.written {
+            state.written = written;
+            state.write(instance_dir)?;
+        }
+        return Ok(());
+    }
+
+    let mut created = Vec::new();
+    let result = (|| {
+        for (source, dest) in &copies {
+            if !backup(&backup_dir, dot_minecraft, dest)? {
+                created.push(dest.clone());
+            }
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(source, dest)?;
+        }
+        for dest in &removals {
+            backup(&backup_dir, dot_minecraft, dest)?;
+            std::fs::remove_file(dest)?;
+        }
+        // The backup has to be gone before the new state is written, otherwise an update interrupted in
+        // between would restore the previous files while the state already describes the new ones
+        if backup_dir.exists() {
+            std::fs::rename(&backup_dir, &discarded_backup_dir)?;
+        }
+        Ok(())
+    })();
+
+    if let Err(error) = result {
+        log::error!("Unable to apply modpack files, rolling back: {}", error);
+        for path in created {
+            _ = std::fs::remove_file(path);
+        }
+        restore_backup(&backup_dir, dot_minecraft);
+        return Err(error);
+    }
+    _ = std::fs::remove_dir_all(&discarded_backup_dir);
+
+    state.written = written;
+    state.write(instance_dir)
+}
+
+/// Returns false if there was no file to back up
+fn backup(backup_dir: &Path, dot_minecraft: &Path, dest: &Path) -> std::io::Result<bool> {
+    if !dest.exists() {
+        return Ok(false);
+    }
+    let Ok(relative) = dest.strip_prefix(dot_minecraft) else {
+        return Ok(false);
+    };
+    let backup_path = backup_dir.join(relative);
+    if let Some(parent) = backup_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(dest, backup_path)?;
+    Ok(true)
+}
+
+fn restore_backup(backup_dir: &Path, dot_minecraft: &Path) {
+    restore_backup_dir(backup_dir, backup_dir, dot_minecraft);
+    _ = std::fs::remove_dir_all(backup_dir);
+}
+
+fn restore_backup_dir(dir: &Path, backup_dir: &Path, dot_minecraft: &Path) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            restore_backup_dir(&path, backup_dir, dot_minecraft);
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(backup_dir) else {
+            continue;
+        };
+        let dest = dot_minecraft.join(relative);
+        if let Some(parent) = dest.parent() {
+            _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(error) = std::fs::copy(&path, &dest) {
+            log::error!("Unable to restore {:?} from modpack backup: {}", dest, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn library_file(dir: &Path, path: &str, contents: &str) -> ModpackFile {
+        let source = dir.join(format!("library-{}", rand::thread_rng().next_u32()));
+        std::fs::write(&source, contents).unwrap();
+        ModpackFile {
+            path: SafePath::new(path).unwrap(),
+            source,
+            sha1: Sha1::digest(contents.as_bytes()).into(),
+        }
+    }
+
+    #[test]
+    fn modpack_file_info() {
+        let index: ModrinthIndexJson = serde_json::from_str(r#"{
+            "formatVersion": 1,
+            "game": "minecraft",
+            "versionId": "1.2.0",
+            "name": "Example Pack",
+            "files": [],
+            "dependencies": { "minecraft": "1.20.1", "fabric-loader": "0.16.10" }
+        }"#).unwrap();
+        let info = file_info(index).unwrap();
+        assert_eq!(&*info.name, "Example Pack");
+        assert_eq!(info.minecraft_version.as_deref(), Some("1.20.1"));
+        assert_eq!(info.loader, Loader::Fabric);
+
+        let index: ModrinthIndexJson = serde_json::from_str(r#"{
+            "versionId": "1",
+            "name": "Quilt Pack",
+            "files": [],
+            "dependencies": { "minecraft": "1.20.1", "quilt-loader": "0.26.0" }
+        }"#).unwrap();
+        assert!(matches!(file_info(index), Err(ReadModpackFileError::UnsupportedLoader(_))));
+    }
+
+    #[test]
+    fn user_modified_files_are_kept() {
+        let dir = TempDir::new("modpack");
+        std::fs::create_dir_all(dir.join(".minecraft")).unwrap();
+        let dot_minecraft = dir.join(".minecraft");
+
+        let v1 = [library_file(&dir, "config/a.toml", "a = 1"), library_file(&dir, "config/b.toml", "b = 1")];
+        apply_files(&dir, &dot_minecraft, &v1).unwrap();
+        assert_eq!(std::fs::read_to_string(dot_minecraft.join("config/a.toml")).unwrap(), "a = 1");
+
+        std::fs::write(dot_minecraft.join("config/a.toml"), "a = 5").unwrap();
+
+        let v2 = [library_file(&dir, "config/a.toml", "a = 2"), library_file(&dir, "config/b.toml", "b = 2")];
+        apply_files(&dir, &dot_minecraft, &v2).unwrap();
+        assert_eq!(std::fs::read_to_string(dot_minecraft.join("config/a.toml")).unwrap(), "a = 5");
+        assert_eq!(std::fs::read_to_string(dot_minecraft.join("config/b.toml")).unwrap(), "b = 2");
+    }
+
+    #[test]
+    fn removed_files_are_deleted() {
+        let dir = TempDir::new("modpack");
+        std::fs::create_dir_all(dir.join(".minecraft")).unwrap();
+        let dot_minecraft = dir.join(".minecraft");
+
+        let v1 = [library_file(&dir, "config/a.toml", "a = 1"), library_file(&dir, "config/b.toml", "b = 1")];
+        apply_files(&dir, &dot_minecraft, &v1).unwrap();
+
+        let v2 = [library_file(&dir, "config/a.toml", "a = 1")];
+        apply_files(&dir, &dot_minecraft, &v2).unwrap();
+        assert!(!dot_minecraft.join("config/b.toml").exists());
+    }
+
+    #[test]
+    fn failed_apply_is_rolled_back() {
+        let dir = TempDir::new("modpack");
+        std::fs::create_dir_all(dir.join(".minecraft")).unwrap();
+        let dot_minecraft = dir.join(".minecraft");
+
+        let v1 = [library_file(&dir, "config/a.toml", "a = 1")];
+        apply_files(&dir, &dot_minecraft, &v1).unwrap();
+
+        let mut missing = library_file(&dir, "config/c.toml", "c = 2");
+        missing.source = dir.join("does-not-exist");
+        let v2 = [library_file(&dir, "config/a.toml", "a = 2"), library_file(&dir, "config/new.toml", "new"), missing];
+        assert!(apply_files(&dir, &dot_minecraft, &v2).is_err());
+
+        assert_eq!(std::fs::read_to_string(dot_minecraft.join("config/a.toml")).unwrap(), "a = 1");
+        assert!(!dot_minecraft.join("config/new.toml").exists());
+        assert!(!dir.join(BACKUP_DIRNAME).exists());
+    }
+
+    #[test]
+    fn discarded_backup_is_not_restored() {
+        let dir = TempDir::new("modpack");
+        std::fs::create_dir_all(dir.join(".minecraft")).unwrap();
+        let dot_minecraft = dir.join(".minecraft");
+
+        let v1 = [library_file(&dir, "config/a.toml", "a = 1")];
+        apply_files(&dir, &dot_minecraft, &v1).unwrap();
+        let v2 = [library_file(&dir, "config/a.toml", "a = 2")];
+        apply_files(&dir, &dot_minecraft, &v2).unwrap();
+        assert!(!dir.join(BACKUP_DIRNAME).exists());
+        assert!(!dir.join(DISCARDED_BACKUP_DIRNAME).exists());
+
+        // Left behind by an update that was interrupted after the files were copied
+        std::fs::create_dir_all(dir.join(DISCARDED_BACKUP_DIRNAME).join("config")).unwrap();
+        std::fs::write(dir.join(DISCARDED_BACKUP_DIRNAME).join("config/a.toml"), "a = 1").unwrap();
+        apply_files(&dir, &dot_minecraft, &v2).unwrap();
+        assert_eq!(std::fs::read_to_string(dot_minecraft.join("config/a.toml")).unwrap(), "a = 2");
+        assert!(!dir.join(DISCARDED_BACKUP_DIRNAME).exists());
+    }
+
+    #[test]
+    fn diff_lists_changes() {
+        let dir = TempDir::new("modpack");
+        std::fs::create_dir_all(dir.join(".minecraft")).unwrap();
+        let dot_minecraft = dir.join(".minecraft");
+        std::fs::create_dir_all(dot_minecraft.join("config")).unwrap();
+        std::fs::write(dot_minecraft.join("config/a.toml"), "edited").unwrap();
+
+        let mut state = ModpackState::default();
+        state.written.insert("config/a.toml".into(), hex::encode(Sha1::digest(b"a = 1")));
+
+        let files = |entries: &[(&str, &str)]| -> BTreeMap<Arc<str>, Arc<str>> {
+            entries.iter().map(|(path, hash)| (Arc::from(*path), Arc::from(*hash))).collect()
+        };
+        let old = files(&[("config/a.toml", "1"), ("mods/old.jar", "2"), ("mods/same.jar", "3")]);
+        let new = files(&[("config/a.toml", "4"), ("mods/new.jar", "5"), ("mods/same.jar", "3")]);
+
+        let diff = diff(&old, &new, &state, &dot_minecraft);
+        assert_eq!(&*diff.added, &[Arc::from("mods/new.jar")]);
+        assert_eq!(&*diff.removed, &[Arc::from("mods/old.jar")]);
+        assert_eq!(&*diff.updated, &[Arc::from("config/a.toml")]);
+        assert_eq!(&*diff.preserved, &[Arc::from("config/a.toml")]);
+    }
+}
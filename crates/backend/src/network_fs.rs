@@ -0,0 +1,103 @@
+use std::{path::Path, time::Duration};
+
+/// How long the filesystem may take to answer, a hung network mount can block `statfs` indefinitely
+const DETECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether the path is on a network filesystem, where watching for changes is unreliable.
+/// A filesystem that doesn't answer in time is assumed to be a network filesystem
+pub fn is_network_path(path: &Path) -> bool {
+    let (send, recv) = std::sync::mpsc::channel();
+    let path = path.to_path_buf();
+    let spawned = std::thread::Builder::new()
+        .name("network-fs-detection".into())
+        .spawn(move || _ = send.send(detect_network_path(&path)));
+    if spawned.is_err() {
+        return false;
+    }
+
+    match recv.recv_timeout(DETECTION_TIMEOUT) {
+        Ok(network) => network,
+        Err(_) => {
+            log::warn!("Filesystem didn't respond within {:?}, treating it as a network filesystem", DETECTION_TIMEOUT);
+            true
+        },
+    }
+}
+
+/// Whether the path is on a network filesystem, where watching for changes is unreliable
+#[cfg(target_os = "linux")]
+fn detect_network_path(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    const NFS: u32 = 0x6969;
+    const SMB: u32 = 0x517b;
+    const CIFS: u32 = 0xff534d42;
+    const SMB2: u32 = 0xfe534d42;
+    const AFS: u32 = 0x5346414f;
+    const CODA: u32 = 0x73757245;
+    const CEPH: u32 = 0x00c36400;
+    // Also used for WSL's mounts of Windows drives
+    const V9FS: u32 = 0x01021997;
+
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+
+    // The width of f_type differs between architectures, every magic number fits in 32 bits
+    matches!(stat.f_type as u32, NFS | SMB | CIFS | SMB2 | AFS | CODA | CEPH | V9FS)
+}
+
+/// Whether the path is on a network filesystem, where watching for changes is unreliable
+#[cfg(target_os = "macos")]
+fn detect_network_path(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    matches!(name.to_bytes(), b"nfs" | b"smbfs" | b"afpfs" | b"webdav" | b"cifs")
+}
+
+/// Whether the path is on a network filesystem, where watching for changes is unreliable.
+/// Only UNC paths are detected, drive letters mapped to a network share aren't
+#[cfg(windows)]
+fn detect_network_path(path: &Path) -> bool {
+    use std::path::{Component, Prefix};
+
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    matches!(path.components().next(), Some(Component::Prefix(prefix))
+        if matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn detect_network_path(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_directory_is_not_network() {
+        let dir = std::env::temp_dir();
+        assert!(!is_network_path(&dir));
+    }
+
+    #[test]
+    fn missing_path_is_not_network() {
+        assert!(!is_network_path(Path::new("/this/path/does/not/exist")));
+    }
+}
@@ -2,22 +2,24 @@ use std::{path::Path, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
-use crate::IoOrSerializationError;
+use crate::{write_behind::WriteBehindQueue, IoOrSerializationError};
 
 #[derive(Debug)]
 pub struct Persistent<T: Serialize + for <'de> Deserialize<'de>> {
     path: Arc<Path>,
     dirty: bool,
-    data: T
+    data: T,
+    write_behind: Option<Arc<WriteBehindQueue>>,
 }
 
 impl<T: Serialize + for <'de> Deserialize<'de> + Default> Persistent<T> {
     pub fn load(path: Arc<Path>) -> Self {
-        let data = crate::read_json(&path).unwrap_or_default();
+        let data = read_json_or_backup(&path).unwrap_or_default();
         Self {
             path,
             dirty: false,
             data,
+            write_behind: None,
         }
     }
 }
@@ -29,18 +31,27 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
             path,
             dirty: false,
             data,
+            write_behind: None,
         })
     }
 
     pub fn load_or(path: Arc<Path>, default_value: T) -> Self {
-        let data = crate::read_json(&path).unwrap_or(default_value);
+        let data = read_json_or_backup(&path).unwrap_or(default_value);
         Self {
             path,
             dirty: false,
             data,
+            write_behind: None,
         }
     }
 
+    /// Writes modifications through the queue instead of synchronously. The in-memory data is
+    /// authoritative until the queued write has finished
+    pub fn with_write_behind(mut self, queue: Arc<WriteBehindQueue>) -> Self {
+        self.write_behind = Some(queue);
+        self
+    }
+
     pub fn modify(&mut self, func: impl FnOnce(&mut T)) {
         if self.dirty {
             self.load_from_disk();
@@ -48,10 +59,14 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
 
         (func)(&mut self.data);
 
-        if let Ok(bytes) = serde_json::to_vec(&self.data) {
-            if crate::write_safe(&self.path, &bytes).is_ok() {
-                self.dirty = true;
-            }
+        let Ok(bytes) = serde_json::to_vec(&self.data) else {
+            return;
+        };
+
+        if let Some(write_behind) = &self.write_behind {
+            write_behind.enqueue(self.path.clone(), bytes);
+        } else if crate::write_safe(&self.path, &bytes).is_ok() {
+            self.dirty = true;
         }
     }
 
@@ -71,6 +86,12 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
     #[inline(always)]
     pub fn mark_changed(&mut self, path: &Path) {
         self.sanity_check_path_eq(path);
+
+        // The change is from one of our own writes, reloading now could read an older version
+        if let Some(write_behind) = &self.write_behind && write_behind.is_pending(path) {
+            return;
+        }
+
         self.dirty = true;
     }
 
@@ -84,3 +105,127 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
         self.data = data;
     }
 }
+
+/// Reads the json file, moving it to `<name>.corrupt` if it can't be parsed so that the
+/// contents aren't lost once the default value gets written over it
+fn read_json_or_backup<T: for <'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    match crate::read_json(path) {
+        Ok(data) => Some(data),
+        Err(IoOrSerializationError::Io(_)) => None,
+        Err(IoOrSerializationError::Serialization(err)) => {
+            let mut backup = path.to_path_buf();
+            backup.add_extension("corrupt");
+            log::error!("Unable to parse {:?}, moving it to {:?}: {}", path, backup, err);
+            if let Err(err) = std::fs::rename(path, &backup) {
+                log::error!("Unable to back up {:?}: {}", path, err);
+            }
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rustc_hash::FxHashMap;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct TestData {
+        values: FxHashMap<String, u32>,
+    }
+
+    fn backup_path(path: &Path) -> PathBuf {
+        let mut backup = path.to_path_buf();
+        backup.add_extension("corrupt");
+        backup
+    }
+
+    #[test]
+    fn missing_file_uses_default() {
+        let dir = TempDir::new("persistent");
+        let path: Arc<Path> = dir.join("accounts.json").into();
+
+        let mut persistent: Persistent<TestData> = Persistent::load(path.clone());
+        assert_eq!(persistent.get(), &TestData::default());
+        assert!(!backup_path(&path).exists());
+    }
+
+    #[test]
+    fn corrupt_file_is_backed_up() {
+        let dir = TempDir::new("persistent");
+        let path: Arc<Path> = dir.join("accounts.json").into();
+        std::fs::write(&path, b"{\"values\": {\"a\": ").unwrap();
+
+        let mut persistent: Persistent<TestData> = Persistent::load(path.clone());
+        assert_eq!(persistent.get(), &TestData::default());
+        assert!(!path.exists());
+        assert_eq!(std::fs::read(backup_path(&path)).unwrap(), b"{\"values\": {\"a\": ");
+
+        // Writing the new data mustn't touch the backup
+        persistent.modify(|data| {
+            data.values.insert("b".into(), 2);
+        });
+        assert!(path.exists());
+        assert_eq!(std::fs::read(backup_path(&path)).unwrap(), b"{\"values\": {\"a\": ");
+    }
+
+    #[test]
+    fn wrong_type_is_backed_up() {
+        let dir = TempDir::new("persistent");
+        let path: Arc<Path> = dir.join("config.json").into();
+        std::fs::write(&path, b"[1, 2, 3]").unwrap();
+
+        let persistent: Persistent<TestData> = Persistent::load_or(path.clone(), TestData {
+            values: FxHashMap::from_iter([("default".into(), 1)]),
+        });
+        assert_eq!(persistent.data.values.get("default"), Some(&1));
+        assert!(backup_path(&path).exists());
+    }
+
+    #[test]
+    fn corrupt_file_on_reload_keeps_data() {
+        let dir = TempDir::new("persistent");
+        let path: Arc<Path> = dir.join("accounts.json").into();
+
+        let mut persistent: Persistent<TestData> = Persistent::load(path.clone());
+        persistent.modify(|data| {
+            data.values.insert("a".into(), 1);
+        });
+
+        std::fs::write(&path, b"\0\0\0\0").unwrap();
+        persistent.mark_changed(&path);
+        assert_eq!(persistent.get().values.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn write_behind_round_trip() {
+        let dir = TempDir::new("persistent");
+        let path: Arc<Path> = dir.join("accounts.json").into();
+        let queue = WriteBehindQueue::new();
+
+        let mut persistent: Persistent<TestData> = Persistent::load(path.clone()).with_write_behind(queue.clone());
+        for i in 0..10 {
+            persistent.modify(|data| {
+                data.values.insert(format!("{i}"), i);
+            });
+        }
+
+        // Our own pending write shouldn't cause a reload of the older contents on disk
+        persistent.mark_changed(&path);
+        assert_eq!(persistent.get().values.len(), 10);
+
+        queue.flush();
+
+        let mut reloaded: Persistent<TestData> = Persistent::load(path.clone());
+        assert_eq!(reloaded.get(), persistent.get());
+
+        // Once written, external changes are picked up again
+        std::fs::write(&path, b"{\"values\": {}}").unwrap();
+        persistent.mark_changed(&path);
+        assert!(persistent.get().values.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use bridge::instance::InstanceResourceUsage;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::process_record::ProcessRecord;
+
+/// Samples the CPU and memory use of running game processes. CPU use is measured between
+/// two samples, so the first sample of a process always reports 0
+pub struct ProcessMonitor {
+    system: System,
+    cpu_count: f32,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        let cpu_count = std::thread::available_parallelism().map(|count| count.get()).unwrap_or(1);
+        Self {
+            system: System::new(),
+            cpu_count: cpu_count as f32,
+        }
+    }
+}
+
+impl ProcessMonitor {
+    /// Refreshes the processes and returns their usage in the same order, none if a process has exited
+    pub fn sample(&mut self, pids: &[u32]) -> Vec<Option<InstanceResourceUsage>> {
+        if pids.is_empty() {
+            return Vec::new();
+        }
+
+        let pids: Vec<Pid> = pids.iter().map(|pid| Pid::from_u32(*pid)).collect();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+
+        pids.iter().map(|pid| {
+            let process = self.system.process(*pid)?;
+            Some(InstanceResourceUsage {
+                cpu_percent: (process.cpu_usage() / self.cpu_count).clamp(0.0, 100.0),
+                memory_bytes: process.memory(),
+            })
+        }).collect()
+    }
+
+    /// Creates a record for the process so it can be recognised after the launcher restarts
+    pub fn record(&mut self, pid: u32) -> Option<ProcessRecord> {
+        let start_time = self.refresh(pid)?.start_time();
+        Some(ProcessRecord { pid, start_time })
+    }
+
+    pub fn is_running(&mut self, record: ProcessRecord) -> bool {
+        self.refresh(record.pid).is_some_and(|process| process.start_time() == record.start_time)
+    }
+
+    /// Kills a process that isn't a child of this launcher
+    pub fn kill(&mut self, record: ProcessRecord) -> std::io::Result<()> {
+        match self.refresh(record.pid) {
+            Some(process) if process.start_time() == record.start_time => {
+                if process.kill() {
+                    Ok(())
+                } else {
+                    Err(std::io::Error::other("the process couldn't be killed"))
+                }
+            },
+            // Already exited
+            _ => Ok(()),
+        }
+    }
+
+    fn refresh(&mut self, pid: u32) -> Option<&Process> {
+        let pid = Pid::from_u32(pid);
+        self.system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), ProcessRefreshKind::new());
+        self.system.process(pid)
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Written to the instance folder while the game runs, so a launcher started after a crash
+/// can find the game again
+pub const PROCESS_RECORD_FILENAME: &str = ".pandora_process.json";
+
+/// Identifies a running game process. Pids are reused once a process exits, so the start
+/// time is recorded as well to tell the game apart from an unrelated process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessRecord {
+    pub pid: u32,
+    /// Seconds since the epoch
+    pub start_time: u64,
+}
+
+impl ProcessRecord {
+    pub fn read(instance_dir: &Path) -> Option<Self> {
+        let bytes = std::fs::read(instance_dir.join(PROCESS_RECORD_FILENAME)).ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(record) => Some(record),
+            Err(error) => {
+                log::warn!("Ignoring invalid process record in {:?}: {}", instance_dir, error);
+                None
+            },
+        }
+    }
+
+    pub fn write(&self, instance_dir: &Path) -> std::io::Result<()> {
+        std::fs::write(instance_dir.join(PROCESS_RECORD_FILENAME), serde_json::to_vec(self)?)
+    }
+
+    pub fn remove(instance_dir: &Path) {
+        let path = instance_dir.join(PROCESS_RECORD_FILENAME);
+        if let Err(error) = std::fs::remove_file(&path) && error.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Unable to remove process record {:?}: {}", path, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn round_trip() {
+        let dir = TempDir::new("process-record");
+        let record = ProcessRecord { pid: 1234, start_time: 1_700_000_000 };
+
+        record.write(&dir).unwrap();
+        assert_eq!(ProcessRecord::read(&dir), Some(record));
+
+        ProcessRecord::remove(&dir);
+        assert_eq!(ProcessRecord::read(&dir), None);
+    }
+
+    #[test]
+    fn invalid_record_is_ignored() {
+        let dir = TempDir::new("process-record");
+        std::fs::write(dir.join(PROCESS_RECORD_FILENAME), "not json").unwrap();
+
+        assert_eq!(ProcessRecord::read(&dir), None);
+    }
+}
@@ -0,0 +1,77 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use auth::secret::SecretStorage;
+use parking_lot::RwLock;
+use reqwest::ClientBuilder;
+use schema::{backend_config::{ProxyConfig, ProxyMode}, version_manifest::MOJANG_VERSION_MANIFEST_URL};
+
+/// Name of the proxy password in the secret storage
+pub const PASSWORD_SECRET: &str = "proxy_password";
+
+/// The password of the proxy the clients were created with, looked up for every request since it's only
+/// read from the secret storage after startup
+pub type ProxyPassword = Arc<RwLock<Option<String>>>;
+
+pub fn apply(builder: ClientBuilder, proxy: &ProxyConfig) -> anyhow::Result<ClientBuilder> {
+    if proxy.mode == ProxyMode::Direct {
+        return Ok(builder.no_proxy());
+    }
+
+    // System proxies are detected by reqwest itself
+    let Some(url) = proxy.url()? else {
+        return Ok(builder);
+    };
+
+    Ok(builder.proxy(reqwest::Proxy::all(url)?))
+}
+
+/// Like `apply`, but adds the password to the proxy once it's known
+fn apply_with_password(builder: ClientBuilder, proxy: &ProxyConfig, password: &ProxyPassword) -> anyhow::Result<ClientBuilder> {
+    if proxy.mode == ProxyMode::Direct {
+        return Ok(builder.no_proxy());
+    }
+
+    let Some(url) = proxy.url()? else {
+        return Ok(builder);
+    };
+
+    let password = password.clone();
+    Ok(builder.proxy(reqwest::Proxy::custom(move |_| {
+        let mut url = url.clone();
+        if !url.username().is_empty() && let Some(password) = password.read().as_deref() {
+            _ = url.set_password(Some(password));
+        }
+        Some(url)
+    })))
+}
+
+/// Builds a client, falling back to connecting without the configured proxy if it's invalid
+/// so a broken configuration can still be fixed from the settings
+pub fn build_client(builder: impl Fn() -> ClientBuilder, proxy: &ProxyConfig, password: &ProxyPassword) -> reqwest::Client {
+    match apply_with_password(builder(), proxy, password).and_then(|builder| Ok(builder.build()?)) {
+        Ok(client) => client,
+        Err(error) => {
+            log::error!("Invalid proxy configuration, ignoring it: {}", error);
+            builder().build().unwrap()
+        },
+    }
+}
+
+/// Requests the version manifest through the proxy, returning how long the request took
+pub async fn test_connection(builder: ClientBuilder, proxy: &ProxyConfig) -> anyhow::Result<Duration> {
+    let client = apply(builder.timeout(Duration::from_secs(15)), proxy)?.build()?;
+
+    let start = Instant::now();
+    client.head(MOJANG_VERSION_MANIFEST_URL).send().await?.error_for_status()?;
+    Ok(start.elapsed())
+}
+
+/// Reads the proxy password from the secret storage into the clients created at startup. This happens after the
+/// launcher has opened, since the secret storage may be slow to answer or ask the user for access first
+pub async fn load_password(password: &ProxyPassword, storage: &SecretStorage) {
+    match storage.read_secret(PASSWORD_SECRET).await {
+        Ok(Some(value)) => *password.write() = Some(value),
+        Ok(None) => {},
+        Err(error) => log::error!("Unable to read the proxy password: {error}"),
+    }
+}
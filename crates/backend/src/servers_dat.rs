@@ -0,0 +1,271 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::instance::ServerEdit;
+use nbt::{CompoundRef, ListRef, NBT, TAG_COMPOUND_ID};
+
+const SERVERS: &str = "servers";
+
+/// Copies of servers.dat from before each edit, kept in the instance folder
+pub const BACKUP_DIRNAME: &str = "servers_backups";
+const MAX_BACKUPS: usize = 10;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriteServersError {
+    #[error("{name} ({ip}) is already in the server list")]
+    AlreadyAdded { name: Arc<str>, ip: Arc<str> },
+    #[error("Unable to read servers.dat: {0}")]
+    Read(anyhow::Error),
+    #[error("Unable to save servers.dat: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub fn read(path: &Path) -> anyhow::Result<NBT> {
+    match std::fs::File::open(path) {
+        Ok(file) => nbt::decode::read_maybe_compressed(file),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(NBT::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn is_server(server: CompoundRef, name: &str, ip: &str) -> bool {
+    server.find_string("name").is_some_and(|server_name| server_name == name)
+        && server.find_string("ip").is_some_and(|server_ip| server_ip == ip)
+}
+
+fn position(servers: ListRef, name: &str, ip: &str) -> Option<usize> {
+    servers.iter().position(|server| server.as_compound().is_some_and(|server| is_server(server, name, ip)))
+}
+
+/// The number of servers and the index of the server, if it's in the list
+fn find_server(nbt: &NBT, name: &str, ip: &str) -> (usize, Option<usize>) {
+    let Some(root) = nbt.as_compound() else {
+        return (0, None);
+    };
+    let Some(servers) = root.find_list(SERVERS, TAG_COMPOUND_ID) else {
+        return (0, None);
+    };
+    (servers.len(), position(servers, name, ip))
+}
+
+fn contains_server(nbt: &NBT, name: &str, ip: &str) -> bool {
+    find_server(nbt, name, ip).1.is_some()
+}
+
+/// Whether the effect of the edit can be seen in the servers.dat, used to detect when the game has overwritten an edit
+pub fn is_applied(nbt: &NBT, edit: &ServerEdit) -> bool {
+    match edit {
+        ServerEdit::Add { name, ip } => contains_server(nbt, name, ip),
+        ServerEdit::Remove { name, ip } => !contains_server(nbt, name, ip),
+        ServerEdit::Edit { name, ip, new_name, new_ip } => {
+            contains_server(nbt, new_name, new_ip) && ((name, ip) == (new_name, new_ip) || !contains_server(nbt, name, ip))
+        },
+        // A server that's gone can't be moved back into place, so it doesn't count as overwritten
+        ServerEdit::Move { name, ip, index } => match find_server(nbt, name, ip) {
+            (len, Some(position)) => position == (*index).min(len - 1),
+            (_, None) => true,
+        },
+    }
+}
+
+/// Applies the edit, leaving every other server (and any unknown fields) untouched
+pub fn apply(nbt: &mut NBT, edit: &ServerEdit) {
+    let Some(mut root) = nbt.as_compound_mut() else {
+        return;
+    };
+
+    // An empty list is stored as a list of TAG_END
+    if root.find_list(SERVERS, TAG_COMPOUND_ID).is_none() {
+        root.remove(SERVERS);
+        root.create_list(SERVERS, TAG_COMPOUND_ID);
+    }
+    let Some(mut servers) = root.find_list_mut(SERVERS, TAG_COMPOUND_ID) else {
+        return;
+    };
+
+    match edit {
+        ServerEdit::Add { name, ip } => {
+            let mut server = servers.create_compound();
+            server.insert_string("name", name.to_string());
+            server.insert_string("ip", ip.to_string());
+        },
+        ServerEdit::Remove { name, ip } => {
+            let index = (0..servers.len()).find(|index| {
+                servers.get(*index).and_then(|server| server.as_compound()).is_some_and(|server| is_server(server, name, ip))
+            });
+            if let Some(index) = index {
+                servers.remove(index);
+            }
+        },
+        ServerEdit::Edit { name, ip, new_name, new_ip } => {
+            let index = (0..servers.len()).find(|index| {
+                servers.get(*index).and_then(|server| server.as_compound()).is_some_and(|server| is_server(server, name, ip))
+            });
+            let Some(mut server) = index.and_then(|index| servers.get_mut(index)).and_then(|server| server.as_compound()) else {
+                return;
+            };
+            for (key, value) in [("name", new_name), ("ip", new_ip)] {
+                match server.find_string_mut(key) {
+                    Some(existing) => *existing = value.to_string(),
+                    None => server.insert_string(key, value.to_string()),
+                }
+            }
+        },
+        ServerEdit::Move { name, ip, index } => {
+            let from = (0..servers.len()).find(|index| {
+                servers.get(*index).and_then(|server| server.as_compound()).is_some_and(|server| is_server(server, name, ip))
+            });
+            if let Some(from) = from {
+                let to = (*index).min(servers.len() - 1);
+                servers.move_element(from, to);
+            }
+        },
+    }
+}
+
+/// Applies the edit to the current contents of the file, after copying the previous file into `backup_dir`.
+/// Writing is atomic, so the game never reads a partial file
+/// Adding a server that's already in the list is an error, every other edit that's already applied does nothing
+pub fn write(path: &Path, backup_dir: &Path, edit: &ServerEdit) -> Result<(), WriteServersError> {
+    let mut nbt = read(path).map_err(WriteServersError::Read)?;
+    if let ServerEdit::Add { name, ip } = edit
+        && contains_server(&nbt, name, ip)
+    {
+        return Err(WriteServersError::AlreadyAdded { name: name.clone(), ip: ip.clone() });
+    }
+    if is_applied(&nbt, edit) {
+        return Ok(());
+    }
+
+    if path.exists() {
+        backup(path, backup_dir)?;
+    }
+    apply(&mut nbt, edit);
+    crate::write_safe(path, &nbt::encode::write_named(&nbt))?;
+    Ok(())
+}
+
+/// Backups are named by time so they sort oldest first, only the newest are kept
+fn backup(path: &Path, backup_dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(backup_dir)?;
+    let name = format!("servers-{}.dat", chrono::Local::now().format("%Y-%m-%d_%H.%M.%S%.3f"));
+    std::fs::copy(path, backup_dir.join(name))?;
+
+    let mut backups: Vec<_> = std::fs::read_dir(backup_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("servers-") && name.ends_with(".dat")))
+        .collect();
+    if backups.len() > MAX_BACKUPS {
+        backups.sort();
+        for old in &backups[..backups.len() - MAX_BACKUPS] {
+            if let Err(err) = std::fs::remove_file(old) {
+                log::warn!("Unable to remove old servers.dat backup {:?}: {err}", old);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn add(name: &str, ip: &str) -> ServerEdit {
+        ServerEdit::Add { name: name.into(), ip: ip.into() }
+    }
+
+    fn remove(name: &str, ip: &str) -> ServerEdit {
+        ServerEdit::Remove { name: name.into(), ip: ip.into() }
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut nbt = NBT::from_snbt(r#"{servers: [{name: "A", ip: "a.com", acceptTextures: 1b}, {name: "B", ip: "b.com"}]}"#).unwrap();
+
+        assert!(!is_applied(&nbt, &add("C", "c.com")));
+        apply(&mut nbt, &add("C", "c.com"));
+        assert!(is_applied(&nbt, &add("C", "c.com")));
+
+        apply(&mut nbt, &remove("B", "b.com"));
+        assert!(is_applied(&nbt, &remove("B", "b.com")));
+
+        let expected = NBT::from_snbt(r#"{servers: [{name: "A", ip: "a.com", acceptTextures: 1b}, {name: "C", ip: "c.com"}]}"#).unwrap();
+        assert_eq!(nbt, expected);
+    }
+
+    #[test]
+    fn add_to_empty() {
+        let mut nbt = NBT::from_snbt("{servers: []}").unwrap();
+        apply(&mut nbt, &add("A", "a.com"));
+        assert!(is_applied(&nbt, &add("A", "a.com")));
+
+        let mut nbt = NBT::new();
+        apply(&mut nbt, &add("A", "a.com"));
+        assert!(is_applied(&nbt, &add("A", "a.com")));
+    }
+
+    #[test]
+    fn edit_and_move() {
+        let mut nbt = NBT::from_snbt(r#"{servers: [{name: "A", ip: "a.com", acceptTextures: 1b}, {name: "B", ip: "b.com"}, {name: "C", ip: "c.com"}]}"#).unwrap();
+
+        let edit = ServerEdit::Edit { name: "A".into(), ip: "a.com".into(), new_name: "A2".into(), new_ip: "a2.com".into() };
+        assert!(!is_applied(&nbt, &edit));
+        apply(&mut nbt, &edit);
+        assert!(is_applied(&nbt, &edit));
+
+        let move_edit = ServerEdit::Move { name: "C".into(), ip: "c.com".into(), index: 0 };
+        assert!(!is_applied(&nbt, &move_edit));
+        apply(&mut nbt, &move_edit);
+        assert!(is_applied(&nbt, &move_edit));
+
+        let expected = NBT::from_snbt(r#"{servers: [{name: "C", ip: "c.com"}, {name: "A2", ip: "a2.com", acceptTextures: 1b}, {name: "B", ip: "b.com"}]}"#).unwrap();
+        assert_eq!(nbt, expected);
+    }
+
+    #[test]
+    fn previous_file_is_backed_up() {
+        let dir = TempDir::new("servers");
+        let path = dir.join("servers.dat");
+        let backup_dir = dir.join(BACKUP_DIRNAME);
+
+        write(&path, &backup_dir, &add("A", "a.com")).unwrap();
+        assert!(!backup_dir.exists());
+
+        let previous = std::fs::read(&path).unwrap();
+        write(&path, &backup_dir, &add("B", "b.com")).unwrap();
+        let backups: Vec<_> = std::fs::read_dir(&backup_dir).unwrap().flatten().collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(std::fs::read(backups[0].path()).unwrap(), previous);
+    }
+
+    #[test]
+    fn adding_a_duplicate_is_an_error() {
+        let dir = TempDir::new("servers_duplicate");
+        let path = dir.join("servers.dat");
+        let backup_dir = dir.join(BACKUP_DIRNAME);
+
+        write(&path, &backup_dir, &add("A", "a.com")).unwrap();
+        let previous = std::fs::read(&path).unwrap();
+
+        assert!(matches!(write(&path, &backup_dir, &add("A", "a.com")), Err(WriteServersError::AlreadyAdded { .. })));
+        assert_eq!(std::fs::read(&path).unwrap(), previous);
+        assert!(!backup_dir.exists());
+
+        // Only the same name and address together count as a duplicate
+        write(&path, &backup_dir, &add("A", "b.com")).unwrap();
+        write(&path, &backup_dir, &remove("A", "a.com")).unwrap();
+        write(&path, &backup_dir, &remove("A", "a.com")).unwrap();
+    }
+
+    #[test]
+    fn overwritten_edit_is_detected() {
+        let mut nbt = NBT::from_snbt(r#"{servers: [{name: "A", ip: "a.com"}]}"#).unwrap();
+        apply(&mut nbt, &add("B", "b.com"));
+
+        // The game writes back the list it had loaded before the edit
+        let overwritten = NBT::from_snbt(r#"{servers: [{name: "A", ip: "a.com"}]}"#).unwrap();
+        assert!(is_applied(&nbt, &add("B", "b.com")));
+        assert!(!is_applied(&overwritten, &add("B", "b.com")));
+    }
+}
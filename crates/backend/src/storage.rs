@@ -0,0 +1,447 @@
+use std::{collections::HashSet, path::{Path, PathBuf}};
+
+use bridge::message::{CleanupResult, DiskUsage};
+use schema::{assets_index::AssetsIndex, fabric_launch::FabricLaunch, loader::Loader, maven::MavenCoordinate, version::MinecraftVersion, version_manifest::{MinecraftVersionLink, MinecraftVersionManifest}};
+use ustr::Ustr;
+
+use crate::directories::LauncherDirectories;
+
+/// Library groups written by the Forge and NeoForge installers. Removing them would make the
+/// installer processors run again on the next launch, which is much slower than a download, so
+/// they're kept even when no instance uses Forge or NeoForge right now
+const FORGELIKE_LIBRARY_PREFIXES: &[&str] = &[
+    "net/minecraftforge/",
+    "net/neoforged/",
+    "net/minecraft/",
+    "cpw/mods/",
+    "de/oceanlabs/",
+];
+
+/// Total size of the files in a directory. Symlinks aren't followed
+pub fn directory_size(path: &Path) -> u64 {
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    read_dir.flatten().map(|entry| match entry.file_type() {
+        Ok(file_type) if file_type.is_dir() => directory_size(&entry.path()),
+        Ok(file_type) if file_type.is_file() => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        _ => 0,
+    }).sum()
+}
+
+/// Logs of previous launches, rotated to launcher.log.1 to launcher.log.N. launcher.log.old is from
+/// before logs were rotated
+fn old_log_files(launcher_dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = std::fs::read_dir(launcher_dir) else {
+        return Vec::new();
+    };
+
+    read_dir.flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .filter(|entry| {
+            let file_name = entry.file_name();
+            let Some(suffix) = file_name.to_str().and_then(|name| name.strip_prefix("launcher.log.")) else {
+                return false;
+            };
+            suffix == "old" || (!suffix.is_empty() && suffix.bytes().all(|byte| byte.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+pub fn compute_disk_usage(directories: &LauncherDirectories) -> DiskUsage {
+    let current_log = directories.root_launcher_dir.join("launcher.log");
+    let logs = std::iter::once(current_log)
+        .chain(old_log_files(&directories.root_launcher_dir))
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+
+    DiskUsage {
+        instances: directory_size(&directories.instances_dir),
+        assets: directory_size(&directories.assets_root_dir),
+        libraries: directory_size(&directories.libraries_dir),
+        java_runtimes: directory_size(&directories.runtime_base_dir),
+        content_library: directory_size(&directories.content_library_dir),
+        metadata: directory_size(&directories.metadata_dir),
+        logs,
+    }
+}
+
+/// Collects the files a cleanup removes. When previewing, nothing is removed and the result is what
+/// would have been freed
+struct Removal {
+    preview: bool,
+    result: CleanupResult,
+}
+
+impl Removal {
+    fn new(preview: bool) -> Self {
+        Self {
+            preview,
+            result: CleanupResult::default(),
+        }
+    }
+
+    fn remove_file(&mut self, path: &Path) {
+        let size = std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+        if self.preview {
+            self.result.removed_files += 1;
+            self.result.freed_bytes += size;
+            return;
+        }
+        match std::fs::remove_file(path) {
+            Ok(()) => {
+                self.result.removed_files += 1;
+                self.result.freed_bytes += size;
+            },
+            Err(error) => log::warn!("Unable to remove {:?}: {}", path, error),
+        }
+    }
+
+    fn remove_dir_if_empty(&self, path: &Path) {
+        if !self.preview {
+            _ = std::fs::remove_dir(path);
+        }
+    }
+}
+
+/// The manifest entry of an instance version that isn't cached locally. Every version has to be known,
+/// otherwise the files of the instances on it would look unused and be removed
+pub fn manifest_version(manifest: &MinecraftVersionManifest, minecraft_version: Ustr) -> anyhow::Result<&MinecraftVersionLink> {
+    let Some(link) = manifest.versions.iter().find(|link| link.id == minecraft_version) else {
+        anyhow::bail!("Unknown version {minecraft_version} of an instance");
+    };
+    Ok(link)
+}
+
+/// Removes asset objects that aren't listed in the assets index of any instance's Minecraft version,
+/// along with the indexes no instance uses anymore
+pub fn remove_unused_assets(directories: &LauncherDirectories, used_indexes: &[Ustr], preview: bool) -> anyhow::Result<CleanupResult> {
+    let mut removal = Removal::new(preview);
+
+    let mut used_hashes = HashSet::new();
+    for entry in std::fs::read_dir(&directories.assets_index_dir)?.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+
+        let used = path.file_stem().and_then(|stem| stem.to_str()).is_some_and(|stem| used_indexes.iter().any(|index| index == stem));
+        if !used {
+            removal.remove_file(&path);
+            continue;
+        }
+
+        // Bail out instead of skipping the index, otherwise every object it references would be removed
+        let bytes = std::fs::read(&path)?;
+        let index: AssetsIndex = serde_json::from_slice(&bytes)
+            .map_err(|error| anyhow::anyhow!("Unable to read assets index {:?}: {}", path, error))?;
+        used_hashes.extend(index.objects.into_values().map(|object| object.hash));
+    }
+
+    let Ok(read_dir) = std::fs::read_dir(&directories.assets_objects_dir) else {
+        return Ok(removal.result);
+    };
+    for folder in read_dir.flatten() {
+        let Ok(objects) = std::fs::read_dir(folder.path()) else {
+            continue;
+        };
+        for object in objects.flatten() {
+            let Some(hash) = object.file_name().to_str().map(Ustr::from) else {
+                continue;
+            };
+            if !used_hashes.contains(&hash) {
+                removal.remove_file(&object.path());
+            }
+        }
+    }
+
+    Ok(removal.result)
+}
+
+/// Removes libraries that aren't needed by the Minecraft and Fabric versions of any instance.
+/// Anything removed by mistake is downloaded again on the next launch
+pub fn remove_orphaned_libraries(
+    directories: &LauncherDirectories,
+    instances: &[(Ustr, Loader)],
+    versions: &[&MinecraftVersion],
+    preview: bool,
+) -> CleanupResult {
+    let mut used = HashSet::new();
+
+    for version in versions {
+        for library in &version.libraries {
+            used.extend(library.downloads.artifact.iter().map(|artifact| PathBuf::from(artifact.path.as_str())));
+            for classifier in library.downloads.classifiers.iter().flat_map(|classifiers| classifiers.values()) {
+                used.insert(PathBuf::from(classifier.path.as_str()));
+            }
+        }
+    }
+
+    let mut protected_prefixes = FORGELIKE_LIBRARY_PREFIXES.to_vec();
+    for (minecraft_version, loader) in instances {
+        used.insert(PathBuf::from(format!("net/minecraft/{0}/minecraft-client-{0}.jar", minecraft_version)));
+
+        if *loader == Loader::Fabric {
+            protected_prefixes.push("net/fabricmc/");
+            used.extend(cached_fabric_libraries(directories, *minecraft_version));
+        }
+    }
+
+    let mut removal = Removal::new(preview);
+    remove_orphaned_libraries_in(&directories.libraries_dir, &directories.libraries_dir, &used, &protected_prefixes, &mut removal);
+    removal.result
+}
+
+fn cached_fabric_libraries(directories: &LauncherDirectories, minecraft_version: Ustr) -> Vec<PathBuf> {
+    let mut libraries = Vec::new();
+
+    let Ok(read_dir) = std::fs::read_dir(directories.metadata_dir.join("fabric_launch").join(minecraft_version.as_str())) else {
+        return libraries;
+    };
+    for entry in read_dir.flatten() {
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        let Ok(fabric_launch) = serde_json::from_slice::<FabricLaunch>(&bytes) else {
+            continue;
+        };
+
+        let meta = &fabric_launch.launcher_meta.libraries;
+        let names = fabric_launch.loader.iter().map(|loader| loader.maven)
+            .chain(fabric_launch.intermediary.iter().map(|intermediary| intermediary.maven))
+            .chain(meta.common.iter().chain(meta.client.iter()).map(|library| library.name));
+        for name in names {
+            libraries.push(PathBuf::from(MavenCoordinate::create(&name).artifact_path()));
+        }
+    }
+
+    libraries
+}
+
+fn remove_orphaned_libraries_in(root: &Path, dir: &Path, used: &HashSet<PathBuf>, protected_prefixes: &[&str], removal: &mut Removal) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+
+        // Compare with forward slashes so the same prefixes work on Windows
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        if protected_prefixes.iter().any(|prefix| relative_str.starts_with(prefix)) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            remove_orphaned_libraries_in(root, &path, used, protected_prefixes, removal);
+            removal.remove_dir_if_empty(&path);
+        } else if file_type.is_file() && !used.contains(Path::new(&relative_str)) {
+            removal.remove_file(&path);
+        }
+    }
+}
+
+/// Removes the downloaded content library, files are downloaded again when an instance or modpack needs them
+pub fn clear_content_library(directories: &LauncherDirectories, preview: bool) -> CleanupResult {
+    let mut removal = Removal::new(preview);
+    remove_all_files(&directories.content_library_dir, &mut removal);
+    removal.result
+}
+
+/// Forgets which Forge and NeoForge installer processors already ran. Their outputs are kept, but the next
+/// launch runs any processor that can't tell from its outputs alone that it's done
+pub fn clear_forge_processor_cache(directories: &LauncherDirectories, preview: bool) -> CleanupResult {
+    let mut removal = Removal::new(preview);
+    remove_all_files(&directories.forge_processor_cache_dir, &mut removal);
+    removal.result
+}
+
+/// Removes the logs of previous launches, the log of the current launch is kept
+pub fn remove_old_logs(directories: &LauncherDirectories, preview: bool) -> CleanupResult {
+    let mut removal = Removal::new(preview);
+    for path in old_log_files(&directories.root_launcher_dir) {
+        removal.remove_file(&path);
+    }
+    removal.result
+}
+
+fn remove_all_files(dir: &Path, removal: &mut Removal) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            remove_all_files(&path, removal);
+            removal.remove_dir_if_empty(&path);
+        } else {
+            removal.remove_file(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn create(root: &Path, relative: &str) -> PathBuf {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"library").unwrap();
+        path
+    }
+
+    #[test]
+    fn orphaned_libraries_are_removed() {
+        let dir = TempDir::new("storage-libraries");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        let client = create(&directories.libraries_dir, "net/minecraft/1.21.1/minecraft-client-1.21.1.jar");
+        let old_client = create(&directories.libraries_dir, "net/minecraft/1.20.1/minecraft-client-1.20.1.jar");
+        let orphan = create(&directories.libraries_dir, "org/example/orphan/1.0/orphan-1.0.jar");
+
+        let instances = [(Ustr::from("1.21.1"), Loader::Vanilla)];
+        let result = remove_orphaned_libraries(&directories, &instances, &[], false);
+
+        assert_eq!(result.removed_files, 1);
+        assert!(client.exists());
+        // Kept because net/minecraft/ also holds the outputs of the Forge installer
+        assert!(old_client.exists());
+        assert!(!orphan.exists());
+        assert!(!directories.libraries_dir.join("org").exists());
+    }
+
+    #[test]
+    fn forgelike_libraries_are_kept_without_forge_instances() {
+        let dir = TempDir::new("storage-forge");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        let forge = create(&directories.libraries_dir, "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-client.jar");
+        let neoforge = create(&directories.libraries_dir, "net/neoforged/neoforge/21.1.1/neoforge-21.1.1-client.jar");
+        let fabric = create(&directories.libraries_dir, "net/fabricmc/fabric-loader/0.16.0/fabric-loader-0.16.0.jar");
+
+        let instances = [(Ustr::from("1.21.1"), Loader::Vanilla)];
+        remove_orphaned_libraries(&directories, &instances, &[], false);
+
+        assert!(forge.exists());
+        assert!(neoforge.exists());
+        assert!(!fabric.exists());
+    }
+
+    fn write_index(directories: &LauncherDirectories, name: &str, hashes: &[&str]) {
+        let objects: serde_json::Map<String, serde_json::Value> = hashes.iter().enumerate()
+            .map(|(index, hash)| (format!("minecraft/file{}", index), serde_json::json!({ "hash": hash, "size": 1 })))
+            .collect();
+        let index = serde_json::json!({ "objects": objects });
+        std::fs::create_dir_all(&directories.assets_index_dir).unwrap();
+        std::fs::write(directories.assets_index_dir.join(format!("{}.json", name)), index.to_string()).unwrap();
+    }
+
+    fn create_object(directories: &LauncherDirectories, hash: &str) -> PathBuf {
+        create(&directories.assets_objects_dir, &format!("{}/{}", &hash[..2], hash))
+    }
+
+    #[test]
+    fn unused_assets_are_removed() {
+        let dir = TempDir::new("storage-assets");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        let shared = "aa00000000000000000000000000000000000000";
+        let used = "bb00000000000000000000000000000000000000";
+        let unused = "cc00000000000000000000000000000000000000";
+        write_index(&directories, "17", &[shared, used]);
+        write_index(&directories, "5", &[shared, unused]);
+        let shared_object = create_object(&directories, shared);
+        let used_object = create_object(&directories, used);
+        let unused_object = create_object(&directories, unused);
+
+        let result = remove_unused_assets(&directories, &[Ustr::from("17")], false).unwrap();
+
+        assert_eq!(result.removed_files, 2);
+        assert!(shared_object.exists());
+        assert!(used_object.exists());
+        assert!(!unused_object.exists());
+        assert!(directories.assets_index_dir.join("17.json").exists());
+        assert!(!directories.assets_index_dir.join("5.json").exists());
+    }
+
+    #[test]
+    fn unreadable_assets_index_removes_nothing() {
+        let dir = TempDir::new("storage-assets-corrupt");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        create(&directories.assets_index_dir, "17.json");
+        let object = create_object(&directories, "aa00000000000000000000000000000000000000");
+
+        assert!(remove_unused_assets(&directories, &[Ustr::from("17")], false).is_err());
+        assert!(object.exists());
+    }
+
+    #[test]
+    fn unknown_instance_version_stops_cleanup() {
+        let manifest: MinecraftVersionManifest = serde_json::from_value(serde_json::json!({
+            "latest": { "release": "1.21.1", "snapshot": "1.21.1" },
+            "versions": [{
+                "id": "1.21.1",
+                "type": "release",
+                "url": "https://piston-meta.mojang.com/v1/packages/0000/1.21.1.json",
+                "time": "2024-08-08T12:24:45+00:00",
+                "releaseTime": "2024-08-08T12:24:45+00:00",
+                "sha1": "0000000000000000000000000000000000000000",
+                "complianceLevel": 1
+            }]
+        })).unwrap();
+
+        assert_eq!(manifest_version(&manifest, Ustr::from("1.21.1")).unwrap().id, Ustr::from("1.21.1"));
+        assert!(manifest_version(&manifest, Ustr::from("1.21.1-patched")).is_err());
+    }
+
+    #[test]
+    fn old_logs_are_counted_and_removed() {
+        let dir = TempDir::new("storage-logs");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        let current = create(&directories.root_launcher_dir, "launcher.log");
+        let rotated: Vec<PathBuf> = ["launcher.log.1", "launcher.log.5", "launcher.log.old"].iter()
+            .map(|name| create(&directories.root_launcher_dir, name))
+            .collect();
+        let unrelated = create(&directories.root_launcher_dir, "launcher.log.txt");
+
+        assert_eq!(compute_disk_usage(&directories).logs, 4 * b"library".len() as u64);
+
+        let result = remove_old_logs(&directories, false);
+
+        assert_eq!(result.removed_files, 3);
+        assert!(current.exists());
+        assert!(unrelated.exists());
+        assert!(rotated.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn previews_keep_files() {
+        let dir = TempDir::new("storage-preview");
+        let directories = LauncherDirectories::new(dir.to_path_buf(), false);
+
+        let orphan = create(&directories.libraries_dir, "org/example/orphan/1.0/orphan-1.0.jar");
+
+        let result = remove_orphaned_libraries(&directories, &[], &[], true);
+
+        assert_eq!(result.removed_files, 1);
+        assert_eq!(result.freed_bytes, b"library".len() as u64);
+        assert!(orphan.exists());
+    }
+}
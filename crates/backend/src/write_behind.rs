@@ -0,0 +1,150 @@
+use std::{path::Path, sync::{Arc, OnceLock}, time::{Duration, Instant}};
+
+use parking_lot::{Condvar, Mutex};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// How long a write is held back, modifications to the same file within this window are coalesced
+const WRITE_DELAY: Duration = Duration::from_millis(250);
+
+static GLOBAL: OnceLock<Arc<WriteBehindQueue>> = OnceLock::new();
+
+/// Queue used by hot files in the launcher directory (e.g. accounts.json)
+pub fn global() -> &'static Arc<WriteBehindQueue> {
+    GLOBAL.get_or_init(WriteBehindQueue::new)
+}
+
+/// Writes all queued files to disk, should be called before the process exits
+pub fn flush_global() {
+    if let Some(queue) = GLOBAL.get() {
+        queue.flush();
+    }
+}
+
+/// Debounces file writes onto a dedicated thread so that callers (i.e. the backend loop) never
+/// block on disk I/O. Files are replaced atomically, so a crash can't leave a partially written file
+#[derive(Debug)]
+pub struct WriteBehindQueue {
+    state: Mutex<QueueState>,
+    condvar: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    pending: FxHashMap<Arc<Path>, Vec<u8>>,
+    writing: FxHashSet<Arc<Path>>,
+    flush_requested: bool,
+}
+
+impl WriteBehindQueue {
+    pub fn new() -> Arc<Self> {
+        let queue = Arc::new(Self {
+            state: Mutex::new(QueueState::default()),
+            condvar: Condvar::new(),
+        });
+
+        let thread_queue = queue.clone();
+        std::thread::Builder::new()
+            .name("write-behind".into())
+            .spawn(move || thread_queue.run())
+            .expect("failed to spawn write-behind thread");
+
+        queue
+    }
+
+    /// Queues the contents to be written to the path, replacing any previously queued contents
+    pub fn enqueue(&self, path: Arc<Path>, contents: Vec<u8>) {
+        let mut state = self.state.lock();
+        state.pending.insert(path, contents);
+        self.condvar.notify_all();
+    }
+
+    /// Drops a queued write that hasn't started yet, used before the file is deleted
+    pub fn cancel(&self, path: &Path) {
+        self.state.lock().pending.remove(path);
+    }
+
+    /// Whether a write to the path hasn't finished yet
+    pub fn is_pending(&self, path: &Path) -> bool {
+        let state = self.state.lock();
+        state.pending.contains_key(path) || state.writing.contains(path)
+    }
+
+    /// Blocks until every queued write has been written
+    pub fn flush(&self) {
+        let mut state = self.state.lock();
+        while !state.pending.is_empty() || !state.writing.is_empty() {
+            state.flush_requested = true;
+            self.condvar.notify_all();
+            self.condvar.wait(&mut state);
+        }
+    }
+
+    fn run(&self) {
+        loop {
+            let mut state = self.state.lock();
+            while state.pending.is_empty() {
+                self.condvar.wait(&mut state);
+            }
+
+            let deadline = Instant::now() + WRITE_DELAY;
+            while !state.flush_requested && Instant::now() < deadline {
+                self.condvar.wait_until(&mut state, deadline);
+            }
+            state.flush_requested = false;
+
+            let writes = std::mem::take(&mut state.pending);
+            state.writing.extend(writes.keys().cloned());
+            drop(state);
+
+            for (path, contents) in writes {
+                if let Err(err) = crate::write_safe(&path, &contents) {
+                    log::error!("Unable to write {:?}: {}", path, err);
+                }
+                self.state.lock().writing.remove(&path);
+            }
+
+            self.condvar.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn coalesces_writes() {
+        let dir = TempDir::new("write-behind");
+        let path: Arc<Path> = dir.join("file.json").into();
+
+        let queue = WriteBehindQueue::new();
+        for i in 0..100 {
+            queue.enqueue(path.clone(), format!("{i}").into_bytes());
+        }
+        assert!(queue.is_pending(&path));
+
+        queue.flush();
+        assert!(!queue.is_pending(&path));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "99");
+
+        // Only the target file should remain, no temporary files
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn writes_without_flush() {
+        let dir = TempDir::new("write-behind");
+        let path: Arc<Path> = dir.join("file.json").into();
+
+        let queue = WriteBehindQueue::new();
+        queue.enqueue(path.clone(), b"hello".to_vec());
+
+        let start = Instant::now();
+        while queue.is_pending(&path) {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+    }
+}
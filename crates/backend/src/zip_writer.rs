@@ -0,0 +1,341 @@
+use std::io::{Read, Write};
+
+use flate2::{write::DeflateEncoder, Compression, Crc};
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x04034b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x08074b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06064b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE: u32 = 0x07064b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+const FLAG_UTF8: u16 = 1 << 11;
+const METHOD_DEFLATE: u16 = 8;
+const VERSION_ZIP64: u16 = 45;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ZipWriteError {
+    #[error("I/O error:\n{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("File name is longer than 65535 bytes: {0}")]
+    NameTooLong(String),
+}
+
+fn checked_name_len(name: &str) -> Result<u16, ZipWriteError> {
+    u16::try_from(name.len()).map_err(|_| ZipWriteError::NameTooLong(name.to_string()))
+}
+
+/// Values that don't fit are stored as 0xFFFFFFFF and written to the ZIP64 extra field instead
+fn needs_zip64(value: u64) -> bool {
+    value >= u32::MAX as u64
+}
+
+struct CentralDirectoryEntry {
+    name: String,
+    crc: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    offset: u64,
+}
+
+impl CentralDirectoryEntry {
+    /// The ZIP64 extra field always contains both sizes, to match the local header, and the offset if it didn't fit
+    fn zip64_values(&self) -> Vec<u64> {
+        let mut values = vec![self.uncompressed_size, self.compressed_size];
+        if needs_zip64(self.offset) {
+            values.push(self.offset);
+        }
+        values
+    }
+}
+
+/// Minimal streaming zip writer, every entry is deflated and sizes are written
+/// in a data descriptor so files don't need to be buffered in memory. Sizes aren't known
+/// when the local header is written, so every entry uses ZIP64 sizes (a ZIP64 extra field
+/// and an 8 byte data descriptor). The ZIP64 end of central directory is only written for
+/// archives that need it (over 4GiB or 65534 entries)
+pub struct ZipWriter<W: Write> {
+    inner: CountingWriter<W>,
+    entries: Vec<CentralDirectoryEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: CountingWriter { inner, count: 0 },
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), ZipWriteError> {
+        self.add_reader(name, &mut &*bytes)
+    }
+
+    pub fn add_reader(&mut self, name: &str, reader: &mut impl Read) -> Result<(), ZipWriteError> {
+        let name_len = checked_name_len(name)?;
+        let offset = self.inner.count;
+
+        self.write_u32(LOCAL_FILE_HEADER_SIGNATURE)?;
+        self.write_u16(VERSION_ZIP64)?;
+        self.write_u16(FLAG_DATA_DESCRIPTOR | FLAG_UTF8)?;
+        self.write_u16(METHOD_DEFLATE)?;
+        self.write_u16(0)?; // modification time
+        self.write_u16(0x21)?; // modification date, 1980-01-01
+        self.write_u32(0)?; // crc, in data descriptor
+        self.write_u32(u32::MAX)?; // compressed size, in ZIP64 extra field
+        self.write_u32(u32::MAX)?; // uncompressed size, in ZIP64 extra field
+        self.write_u16(name_len)?;
+        self.write_u16(20)?; // extra field length
+        self.inner.write_all(name.as_bytes())?;
+        // The ZIP64 extra field tells readers the data descriptor has 8 byte sizes, the sizes themselves are in it
+        self.write_u16(ZIP64_EXTRA_FIELD_ID)?;
+        self.write_u16(16)?;
+        self.write_u64(0)?; // uncompressed size
+        self.write_u64(0)?; // compressed size
+
+        let data_start = self.inner.count;
+        let mut crc = Crc::new();
+        let mut encoder = DeflateEncoder::new(&mut self.inner, Compression::default());
+        let mut buffer = [0_u8; 64*1024];
+        let mut uncompressed_size = 0_u64;
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            uncompressed_size += read as u64;
+            crc.update(&buffer[..read]);
+            encoder.write_all(&buffer[..read])?;
+        }
+        encoder.finish()?;
+
+        let compressed_size = self.inner.count - data_start;
+
+        self.write_u32(DATA_DESCRIPTOR_SIGNATURE)?;
+        self.write_u32(crc.sum())?;
+        self.write_u64(compressed_size)?;
+        self.write_u64(uncompressed_size)?;
+
+        self.entries.push(CentralDirectoryEntry {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed_size,
+            uncompressed_size,
+            offset,
+        });
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, ZipWriteError> {
+        let central_directory_offset = self.inner.count;
+
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            let zip64_values = entry.zip64_values();
+            let extra_len = 4 + 8 * zip64_values.len() as u16;
+
+            self.write_u32(CENTRAL_DIRECTORY_SIGNATURE)?;
+            self.write_u16(VERSION_ZIP64)?; // version made by
+            self.write_u16(VERSION_ZIP64)?; // version needed
+            self.write_u16(FLAG_DATA_DESCRIPTOR | FLAG_UTF8)?;
+            self.write_u16(METHOD_DEFLATE)?;
+            self.write_u16(0)?;
+            self.write_u16(0x21)?;
+            self.write_u32(entry.crc)?;
+            self.write_u32(u32::MAX)?; // compressed size, in ZIP64 extra field
+            self.write_u32(u32::MAX)?; // uncompressed size, in ZIP64 extra field
+            self.write_u16(checked_name_len(&entry.name)?)?;
+            self.write_u16(extra_len)?;
+            self.write_u16(0)?; // comment length
+            self.write_u16(0)?; // disk number
+            self.write_u16(0)?; // internal attributes
+            self.write_u32(0)?; // external attributes
+            self.write_u32(entry.offset.min(u32::MAX as u64) as u32)?;
+            self.inner.write_all(entry.name.as_bytes())?;
+            self.write_u16(ZIP64_EXTRA_FIELD_ID)?;
+            self.write_u16(extra_len - 4)?;
+            for value in zip64_values {
+                self.write_u64(value)?;
+            }
+        }
+
+        let entry_count = entries.len() as u64;
+        let central_directory_end = self.inner.count;
+        let central_directory_size = central_directory_end - central_directory_offset;
+
+        if entry_count >= u16::MAX as u64 || needs_zip64(central_directory_size) || needs_zip64(central_directory_offset) {
+            self.write_u32(ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE)?;
+            self.write_u64(44)?; // size of the rest of the record
+            self.write_u16(VERSION_ZIP64)?; // version made by
+            self.write_u16(VERSION_ZIP64)?; // version needed
+            self.write_u32(0)?; // disk number
+            self.write_u32(0)?; // disk with central directory
+            self.write_u64(entry_count)?;
+            self.write_u64(entry_count)?;
+            self.write_u64(central_directory_size)?;
+            self.write_u64(central_directory_offset)?;
+
+            self.write_u32(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE)?;
+            self.write_u32(0)?; // disk with the zip64 end of central directory
+            self.write_u64(central_directory_end)?;
+            self.write_u32(1)?; // number of disks
+        }
+
+        // Values that don't fit are 0xFFFF or 0xFFFFFFFF, telling readers to use the ZIP64 record
+        let entry_count = entry_count.min(u16::MAX as u64) as u16;
+        self.write_u32(END_OF_CENTRAL_DIRECTORY_SIGNATURE)?;
+        self.write_u16(0)?; // disk number
+        self.write_u16(0)?; // disk with central directory
+        self.write_u16(entry_count)?;
+        self.write_u16(entry_count)?;
+        self.write_u32(central_directory_size.min(u32::MAX as u64) as u32)?;
+        self.write_u32(central_directory_offset.min(u32::MAX as u64) as u32)?;
+        self.write_u16(0)?; // comment length
+
+        self.inner.flush()?;
+        Ok(self.inner.inner)
+    }
+
+    fn write_u16(&mut self, value: u16) -> std::io::Result<()> {
+        self.inner.write_all(&value.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, value: u32) -> std::io::Result<()> {
+        self.inner.write_all(&value.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, value: u64) -> std::io::Result<()> {
+        self.inner.write_all(&value.to_le_bytes())
+    }
+}
+
+struct CountingWriter<W: Write> {
+    inner: W,
+    count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rc_zip_sync::ReadZip;
+
+    use super::*;
+
+    fn read_u16(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+    }
+
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn read_u64(bytes: &[u8], at: usize) -> u64 {
+        u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+    }
+
+    fn find_signature(bytes: &[u8], signature: u32) -> usize {
+        bytes.windows(4).position(|window| window == signature.to_le_bytes().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn entries_can_be_read_back() {
+        let large: Vec<u8> = (0..200_000_u32).flat_map(|value| (value % 251).to_le_bytes()).collect();
+
+        let mut writer = ZipWriter::new(Vec::new());
+        writer.add_bytes("empty.txt", b"").unwrap();
+        writer.add_bytes("config/ünïcödé.json", b"{\"key\": true}").unwrap();
+        writer.add_reader("large.bin", &mut large.as_slice()).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let archive = bytes.read_zip().unwrap();
+        assert_eq!(archive.entries().count(), 3);
+        assert_eq!(archive.by_name("empty.txt").unwrap().bytes().unwrap(), b"");
+        assert_eq!(archive.by_name("config/ünïcödé.json").unwrap().bytes().unwrap(), b"{\"key\": true}");
+        assert_eq!(archive.by_name("large.bin").unwrap().bytes().unwrap(), large);
+    }
+
+    #[test]
+    fn long_names_are_refused() {
+        let mut writer = ZipWriter::new(Vec::new());
+        let name = "a".repeat(u16::MAX as usize + 1);
+        assert!(matches!(writer.add_bytes(&name, b""), Err(ZipWriteError::NameTooLong(_))));
+
+        let name = "a".repeat(u16::MAX as usize);
+        writer.add_bytes(&name, b"").unwrap();
+        assert!(writer.finish().unwrap().read_zip().unwrap().by_name(&name).is_some());
+    }
+
+    #[test]
+    fn many_entries_use_zip64() {
+        let mut writer = ZipWriter::new(Vec::new());
+        for index in 0..70_000 {
+            writer.add_bytes(&index.to_string(), b"").unwrap();
+        }
+        writer.add_bytes("last.txt", b"last").unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let archive = bytes.read_zip().unwrap();
+        assert_eq!(archive.entries().count(), 70_001);
+        assert_eq!(archive.by_name("last.txt").unwrap().bytes().unwrap(), b"last");
+    }
+
+    #[test]
+    fn local_headers_announce_zip64_data_descriptors() {
+        let mut writer = ZipWriter::new(Vec::new());
+        writer.add_bytes("a.txt", b"first").unwrap();
+        let bytes = writer.finish().unwrap();
+
+        assert_eq!(read_u32(&bytes, 0), LOCAL_FILE_HEADER_SIGNATURE);
+        assert_eq!(read_u16(&bytes, 4), VERSION_ZIP64);
+        assert_eq!(read_u16(&bytes, 28), 20);
+        assert_eq!(read_u16(&bytes, 30 + "a.txt".len()), ZIP64_EXTRA_FIELD_ID);
+
+        // Signature, crc and two 8 byte sizes
+        let descriptor = find_signature(&bytes, DATA_DESCRIPTOR_SIGNATURE);
+        assert_eq!(find_signature(&bytes, CENTRAL_DIRECTORY_SIGNATURE), descriptor + 24);
+        assert_eq!(read_u64(&bytes, descriptor + 16), 5);
+    }
+
+    #[test]
+    fn offsets_past_4gib_use_zip64() {
+        // Pretend the archive starts after 4GiB of other data, only the offsets written into it are checked
+        let start = u32::MAX as u64 + 1024;
+        let mut writer = ZipWriter::new(Vec::new());
+        writer.inner.count = start;
+        writer.add_bytes("a.txt", b"first").unwrap();
+        writer.add_bytes("b.txt", b"second").unwrap();
+        let bytes = writer.finish().unwrap();
+
+        // The entry offset is in the ZIP64 extra field, after both sizes
+        let central_directory = find_signature(&bytes, CENTRAL_DIRECTORY_SIGNATURE);
+        assert_eq!(read_u32(&bytes, central_directory + 42), u32::MAX);
+        let extra = central_directory + 46 + "a.txt".len();
+        assert_eq!(read_u16(&bytes, extra), ZIP64_EXTRA_FIELD_ID);
+        assert_eq!(read_u16(&bytes, extra + 2), 24);
+        assert_eq!(read_u64(&bytes, extra + 20), start);
+
+        let zip64_end = find_signature(&bytes, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        assert_eq!(read_u64(&bytes, zip64_end + 24), 2);
+        assert_eq!(read_u64(&bytes, zip64_end + 48), start + central_directory as u64);
+        let locator = find_signature(&bytes, ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE);
+        assert_eq!(read_u64(&bytes, locator + 8), start + zip64_end as u64);
+
+        let end = bytes.len() - 22;
+        assert_eq!(read_u32(&bytes, end), END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        assert_eq!(read_u32(&bytes, end + 16), u32::MAX);
+    }
+}
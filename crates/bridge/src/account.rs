@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::SystemTime};
 
 use uuid::Uuid;
 
@@ -7,4 +7,22 @@ pub struct Account {
     pub uuid: Uuid,
     pub username: Arc<str>,
     pub head: Option<Arc<[u8]>>,
+    pub offline: bool,
+}
+
+/// Whether the stored credentials of an account can still be used to launch the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountTokenState {
+    /// Offline accounts don't have credentials
+    Offline,
+    /// The Minecraft access token can be used until it expires
+    Valid {
+        expires_at: SystemTime,
+    },
+    /// The access token expired, but a new one can be requested without logging in again
+    Refreshable,
+    /// There are no usable credentials, the account needs to log in again
+    LoginRequired,
+    /// The credentials couldn't be read
+    Unknown,
 }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameOutputLogLevel {
     Fatal,
@@ -8,3 +10,11 @@ pub enum GameOutputLogLevel {
     Trace,
     Other,
 }
+
+#[derive(Clone, Debug)]
+pub struct GameOutputLine {
+    pub time: i64,
+    pub level: GameOutputLogLevel,
+    pub thread: Option<Arc<str>>,
+    pub text: Arc<[Arc<str>]>,
+}
@@ -2,10 +2,9 @@ use std::sync::Arc;
 
 #[cfg(debug_assertions)]
 use tokio::sync::mpsc::{Receiver, Sender};
-#[cfg(not(debug_assertions))]
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
-use crate::{message::{BridgeNotificationType, MessageToBackend, MessageToFrontend}, serial::{AtomicOptionSerial, AtomicSerialProvider, AtomicSetSerial, Serial}};
+use crate::{message::{BridgeNotificationType, MessageToBackend, MessageToFrontend}, modal_action::BackgroundTasks, serial::{AtomicOptionSerial, AtomicSerialProvider, AtomicSetSerial, Serial}};
 
 pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, FrontendHandle) {
     #[cfg(debug_assertions)]
@@ -18,8 +17,12 @@ pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, Front
     #[cfg(not(debug_assertions))]
     let (backend_send, backend_recv) = tokio::sync::mpsc::unbounded_channel();
 
+    // Bulk traffic is never bounded, a burst of game output shouldn't be able to fill the control channel
+    let (frontend_bulk_send, frontend_bulk_recv) = tokio::sync::mpsc::unbounded_channel();
+
     let backend_serial = AtomicSetSerial::default();
     let frontend_serial = AtomicSetSerial::default();
+    let background_tasks = BackgroundTasks::default();
 
     (
         BackendReceiver {
@@ -33,12 +36,16 @@ pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, Front
         },
         FrontendReceiver {
             receiver: frontend_recv,
+            bulk_receiver: frontend_bulk_recv,
             processed_serial: frontend_serial.clone(),
+            background_tasks: background_tasks.clone(),
         },
         FrontendHandle {
             sender: frontend_send,
+            bulk_sender: frontend_bulk_send,
             processed_serial: frontend_serial.clone(),
             next_serial: Default::default(),
+            background_tasks,
         }
     )
 }
@@ -68,24 +75,48 @@ pub struct FrontendReceiver {
     receiver: Receiver<(MessageToFrontend, Option<Serial>)>,
     #[cfg(not(debug_assertions))]
     receiver: UnboundedReceiver<(MessageToFrontend, Option<Serial>)>,
+    bulk_receiver: UnboundedReceiver<MessageToFrontend>,
     processed_serial: AtomicSetSerial,
+    background_tasks: BackgroundTasks,
 }
 
 impl FrontendReceiver {
+    pub fn background_tasks(&self) -> &BackgroundTasks {
+        &self.background_tasks
+    }
+
+    /// Receives the next message, control messages are always received before bulk messages
     pub async fn recv(&mut self) -> Option<MessageToFrontend> {
-        let (message, serial) = self.receiver.recv().await?;
-        if let Some(serial) = serial {
-            self.processed_serial.set(serial);
+        if let Some(message) = self.try_recv() {
+            return Some(message);
+        }
+
+        tokio::select! {
+            biased;
+            message = self.receiver.recv() => {
+                let (message, serial) = message?;
+                if let Some(serial) = serial {
+                    self.processed_serial.set(serial);
+                }
+                Some(message)
+            },
+            Some(message) = self.bulk_receiver.recv() => Some(message),
         }
-        Some(message)
     }
 
     pub fn try_recv(&mut self) -> Option<MessageToFrontend> {
-        let (message, serial) = self.receiver.try_recv().ok()?;
-        if let Some(serial) = serial {
-            self.processed_serial.set(serial);
+        if let Ok((message, serial)) = self.receiver.try_recv() {
+            if let Some(serial) = serial {
+                self.processed_serial.set(serial);
+            }
+            return Some(message);
         }
-        Some(message)
+        self.try_recv_bulk()
+    }
+
+    /// Receives a queued bulk message without waiting, so bursts can be handled together
+    pub fn try_recv_bulk(&mut self) -> Option<MessageToFrontend> {
+        self.bulk_receiver.try_recv().ok()
     }
 }
 
@@ -135,15 +166,26 @@ pub struct FrontendHandle {
     sender: Sender<(MessageToFrontend, Option<Serial>)>,
     #[cfg(not(debug_assertions))]
     sender: UnboundedSender<(MessageToFrontend, Option<Serial>)>,
+    bulk_sender: UnboundedSender<MessageToFrontend>,
     processed_serial: AtomicSetSerial,
     next_serial: AtomicSerialProvider,
+    background_tasks: BackgroundTasks,
 }
 
 unsafe impl Send for FrontendHandle {}
 unsafe impl Sync for FrontendHandle {}
 
 impl FrontendHandle {
+    pub fn background_tasks(&self) -> &BackgroundTasks {
+        &self.background_tasks
+    }
+
     pub fn send(&self, message: MessageToFrontend) {
+        if message.is_bulk() {
+            let _ = self.bulk_sender.send(message);
+            return;
+        }
+
         #[cfg(debug_assertions)]
         if let Err(tokio::sync::mpsc::error::TrySendError::Full(v)) = self.sender.try_send((message, None)) {
             panic!("Sender is full, unable to send message: {v:?}");
@@ -1,6 +1,6 @@
 use std::{path::{Path, PathBuf}, sync::Arc};
 
-use schema::{content::ContentSource, loader::Loader};
+use schema::{content::ContentSource, instance::InstanceConfiguration, loader::Loader};
 
 use crate::{instance::InstanceID, safe_path::SafePath};
 
@@ -10,6 +10,8 @@ pub enum InstallTarget {
     Library,
     NewInstance {
         name: Arc<str>,
+        /// Settings of the new instance, otherwise it's created from the loader and version hints
+        configuration: Option<InstanceConfiguration>,
     },
 }
 
@@ -51,3 +53,45 @@ pub enum ContentDownload {
         path: PathBuf,
     }
 }
+
+/// A modpack file from outside the launcher, read before it's installed as a new instance
+#[derive(Debug, Clone)]
+pub struct ModpackFileInfo {
+    pub name: Arc<str>,
+    pub version: Arc<str>,
+    pub minecraft_version: Option<Arc<str>>,
+    pub loader: Loader,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstallQueueID(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallFileState {
+    Pending,
+    Downloading,
+    Verifying,
+    /// Downloaded and the SHA1 matched
+    Verified,
+    /// Already in the content library with the right SHA1, nothing had to be downloaded
+    Cached,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstallQueueFile {
+    pub name: Arc<str>,
+    pub state: InstallFileState,
+    /// Expected SHA1, once it's known
+    pub sha1: Option<Arc<str>>,
+    pub error: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstallQueueEntry {
+    pub id: InstallQueueID,
+    pub title: Arc<str>,
+    pub files: Arc<[InstallQueueFile]>,
+    /// All files were installed, entries with failed files stay unfinished until they're retried
+    pub finished: bool,
+}
@@ -1,6 +1,7 @@
-use std::{collections::HashSet, path::Path, sync::Arc};
+use std::{collections::HashSet, path::Path, sync::{Arc, OnceLock}};
 
-use schema::{content::ContentSource, modification::ModrinthModpackFileDownload};
+use schema::{content::ContentSource, loader::Loader, modification::ModrinthModpackFileDownload};
+use ustr::Ustr;
 
 use crate::safe_path::SafePath;
 
@@ -34,11 +35,21 @@ impl InstanceContentID {
     }
 }
 
+/// CPU and memory use of a running instance's game process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstanceResourceUsage {
+    /// Share of all CPU cores, from 0 to 100
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InstanceStatus {
     NotRunning,
     Launching,
     Running,
+    /// The game has been asked to close and will be force killed if it doesn't
+    Stopping,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +61,108 @@ pub struct InstanceWorldSummary {
     pub png_icon: Option<Arc<[u8]>>,
 }
 
+/// Extra information about a world, only loaded when its details are opened
+#[derive(Debug, Clone)]
+pub struct InstanceWorldDetails {
+    pub game_mode: WorldGameMode,
+    pub hardcore: bool,
+    pub difficulty: Option<WorldDifficulty>,
+    pub seed: Option<i64>,
+    /// Name of the version the world was last opened with, missing before 1.9
+    pub version_name: Option<Arc<str>>,
+    pub allow_commands: bool,
+    pub size_bytes: u64,
+}
+
+/// A world that was last played in a newer version than the instance it's being opened with
+#[derive(Debug, Clone)]
+pub struct WorldVersionMismatch {
+    pub world_version: Arc<str>,
+    pub world_data_version: Option<i32>,
+    pub instance_version: Arc<str>,
+}
+
+/// Mods of an instance that may not work after changing its Minecraft version
+#[derive(Debug, Clone, Default)]
+pub struct VersionCompatibility {
+    /// Mods without a Modrinth version for the new Minecraft version
+    pub incompatible: Vec<Arc<str>>,
+    /// Mods that weren't installed from Modrinth or couldn't be checked
+    pub unchecked: Vec<Arc<str>>,
+}
+
+/// Enabled mods that need a newer loader version than the one the instance is pinned to
+#[derive(Debug, Clone)]
+pub struct LoaderVersionRequirement {
+    /// The lowest loader version every enabled mod accepts
+    pub minimum: Arc<str>,
+    pub current: Ustr,
+    /// The oldest available loader version satisfying `minimum`, if one was found
+    pub suggested: Option<Ustr>,
+    /// Mods that don't accept the current version
+    pub mods: Vec<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldGameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+    Unknown(i32),
+}
+
+impl WorldGameMode {
+    pub fn from_id(id: i32) -> Self {
+        match id {
+            0 => Self::Survival,
+            1 => Self::Creative,
+            2 => Self::Adventure,
+            3 => Self::Spectator,
+            id => Self::Unknown(id),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Survival => "Survival",
+            Self::Creative => "Creative",
+            Self::Adventure => "Adventure",
+            Self::Spectator => "Spectator",
+            Self::Unknown(_) => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldDifficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl WorldDifficulty {
+    pub fn from_id(id: i8) -> Option<Self> {
+        match id {
+            0 => Some(Self::Peaceful),
+            1 => Some(Self::Easy),
+            2 => Some(Self::Normal),
+            3 => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Peaceful => "Peaceful",
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InstanceServerSummary {
     pub name: Arc<str>,
@@ -57,6 +170,33 @@ pub struct InstanceServerSummary {
     pub png_icon: Option<Arc<[u8]>>,
 }
 
+/// A change to servers.dat made from the launcher. Servers are matched by name and address
+/// rather than by index, since the game may have reordered the list in the meantime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerEdit {
+    Add { name: Arc<str>, ip: Arc<str> },
+    Remove { name: Arc<str>, ip: Arc<str> },
+    /// Renames a server or changes its address, keeping its other settings
+    Edit { name: Arc<str>, ip: Arc<str>, new_name: Arc<str>, new_ip: Arc<str> },
+    /// Moves a server to `index` in the list
+    Move { name: Arc<str>, ip: Arc<str>, index: usize },
+}
+
+impl ServerEdit {
+    /// The name of the server the edit applies to, before the edit
+    pub fn name(&self) -> &Arc<str> {
+        match self {
+            ServerEdit::Add { name, .. } | ServerEdit::Remove { name, .. } | ServerEdit::Edit { name, .. } | ServerEdit::Move { name, .. } => name,
+        }
+    }
+
+    pub fn ip(&self) -> &Arc<str> {
+        match self {
+            ServerEdit::Add { ip, .. } | ServerEdit::Remove { ip, .. } | ServerEdit::Edit { ip, .. } | ServerEdit::Move { ip, .. } => ip,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InstanceContentSummary {
     pub content_summary: Arc<ContentSummary>,
@@ -77,11 +217,32 @@ pub struct ContentSummary {
     pub name: Option<Arc<str>>,
     pub version_str: Arc<str>,
     pub authors: Arc<str>,
+    pub description: Option<Arc<str>>,
+    pub homepage: Option<Arc<str>>,
     pub png_icon: Option<Arc<[u8]>>,
+    /// The lowest version of each loader the mod declares it needs
+    pub loader_requirements: Arc<[(Loader, Arc<str>)]>,
     pub update_status: Arc<AtomicContentUpdateStatus>,
+    /// Filled in asynchronously once the file's hash has been matched against Modrinth
+    pub modrinth_project: Arc<OnceLock<Arc<ContentModrinthProject>>>,
     pub extra: ContentType,
 }
 
+#[derive(Debug)]
+pub struct ContentModrinthProject {
+    pub id: Arc<str>,
+    pub slug: Option<Arc<str>>,
+    pub title: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    pub authors: Arc<str>,
+}
+
+impl ContentModrinthProject {
+    pub fn url(&self) -> String {
+        format!("https://modrinth.com/project/{}", self.slug.as_deref().unwrap_or(&self.id))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ContentType {
     Fabric,
@@ -96,6 +257,15 @@ pub enum ContentType {
     ResourcePack,
 }
 
+/// Files that change when a modpack is updated, by their path in .minecraft
+#[derive(Debug, Default, Clone)]
+pub struct ModpackUpdateDiff {
+    pub added: Vec<Arc<str>>,
+    pub removed: Vec<Arc<str>>,
+    pub updated: Vec<Arc<str>>,
+    /// Updated or removed files that the user changed, these are kept as they are
+    pub preserved: Vec<Arc<str>>,
+}
 
 #[atomic_enum::atomic_enum]
 #[derive(PartialEq, Eq)]
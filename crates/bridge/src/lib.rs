@@ -8,5 +8,6 @@ pub mod message;
 pub mod meta;
 pub mod modal_action;
 pub mod safe_path;
+pub mod sandbox;
 pub mod serial;
 pub mod notify_signal;
@@ -1,14 +1,14 @@
-use std::{ffi::OsString, path::{Path, PathBuf}, sync::Arc};
+use std::{ffi::OsString, path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime}};
 
 use enumset::{EnumSet, EnumSetType};
-use schema::{backend_config::{BackendConfig, SyncTarget}, instance::{InstanceConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration}, loader::Loader};
+use schema::{backend_config::{BackendConfig, BackendSettings, MetadataCacheConfig, MirrorConfig, ProxyConfig, SyncTarget}, instance::{InstanceConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration, InstanceWindowConfiguration}, following::FollowedProjects, launch_history::LaunchHistoryEntry, loader::Loader, modrinth::{ModrinthProjectType, ModrinthProjectVersion}, shared_profile::SharedProfile};
 use ustr::Ustr;
 use uuid::Uuid;
 
 use crate::{
-    account::Account, game_output::GameOutputLogLevel, install::ContentInstall, instance::{
-        InstanceID, InstanceContentID, InstanceContentSummary, InstanceServerSummary, InstanceStatus, InstanceWorldSummary,
-    }, keep_alive::{KeepAlive, KeepAliveHandle}, meta::{MetadataRequest, MetadataResult}, modal_action::ModalAction
+    account::{Account, AccountTokenState}, game_output::GameOutputLine, install::{ContentInstall, InstallQueueEntry, InstallQueueID, ModpackFileInfo}, instance::{
+        InstanceID, InstanceContentID, InstanceContentSummary, InstanceResourceUsage, InstanceServerSummary, InstanceStatus, InstanceWorldDetails, InstanceWorldSummary, LoaderVersionRequirement, ModpackUpdateDiff, ServerEdit, VersionCompatibility, WorldVersionMismatch,
+    }, keep_alive::{KeepAlive, KeepAliveHandle}, meta::{MetadataCacheEntry, MetadataCacheKind, MetadataRequest, MetadataResult}, modal_action::ModalAction
 };
 
 #[derive(Debug)]
@@ -22,16 +22,43 @@ pub enum MessageToBackend {
         version: Ustr,
         loader: Loader,
     },
+    /// Adds a custom version from a version json and optionally its client jar
+    AddLocalVersion {
+        paths: Arc<[PathBuf]>,
+        modal_action: ModalAction,
+    },
     DeleteInstance {
         id: InstanceID,
+        /// Remove the folder instead of moving it to the trash
+        permanent: bool,
+    },
+    DuplicateInstance {
+        id: InstanceID,
+    },
+    SetInstancePinned {
+        id: InstanceID,
+        pinned: bool,
+    },
+    /// Whether the folders of the instance are watched for changes instead of being polled
+    SetInstanceFileWatching {
+        id: InstanceID,
+        value: bool,
     },
     RenameInstance {
         id: InstanceID,
         name: Ustr,
     },
-    SetInstanceMinecraftVersion {
+    CheckVersionCompatibility {
+        id: InstanceID,
+        version: Ustr,
+        channel: tokio::sync::oneshot::Sender<Result<VersionCompatibility, Arc<str>>>,
+    },
+    /// Changes the Minecraft version, copying the instance to a backup instance first if requested
+    ChangeInstanceMinecraftVersion {
         id: InstanceID,
-        version: Ustr
+        version: Ustr,
+        backup: bool,
+        modal_action: ModalAction,
     },
     SetInstanceLoader {
         id: InstanceID,
@@ -41,6 +68,11 @@ pub enum MessageToBackend {
         id: InstanceID,
         loader_version: Option<&'static str>
     },
+    /// Checks the loader versions the enabled mods require against the pinned loader version
+    CheckLoaderVersionRequirement {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Option<LoaderVersionRequirement>>,
+    },
     SetInstanceMemory {
         id: InstanceID,
         memory: InstanceMemoryConfiguration,
@@ -53,6 +85,19 @@ pub enum MessageToBackend {
         id: InstanceID,
         jvm_binary: InstanceJvmBinaryConfiguration,
     },
+    SetInstanceWindow {
+        id: InstanceID,
+        window: InstanceWindowConfiguration,
+    },
+    /// Whether the game is offloaded to the discrete GPU on Linux systems with more than one GPU
+    SetInstanceDiscreteGpu {
+        id: InstanceID,
+        value: bool,
+    },
+    /// Asks the game to close, force killing it if it's still running after the grace period
+    StopInstance {
+        id: InstanceID,
+    },
     KillInstance {
         id: InstanceID,
     },
@@ -61,18 +106,41 @@ pub enum MessageToBackend {
         quick_play: Option<QuickPlayLaunch>,
         modal_action: ModalAction,
     },
+    /// Prepares a launch without starting the game and returns the command that would have been run
+    PreviewLaunchCommand {
+        id: InstanceID,
+        modal_action: ModalAction,
+        channel: tokio::sync::oneshot::Sender<Result<LaunchPreview, Arc<str>>>,
+    },
+    /// Checks the game files the instance needs to launch, downloading anything missing or corrupt
+    VerifyInstance {
+        id: InstanceID,
+        modal_action: ModalAction,
+    },
     RequestLoadWorlds {
         id: InstanceID,
     },
     RequestLoadServers {
         id: InstanceID,
     },
+    /// Listens for LAN games until the handle is dropped, in addition to while an instance is running
+    WatchLanGames {
+        keep_alive: KeepAliveHandle,
+    },
     RequestLoadMods {
         id: InstanceID,
     },
     RequestLoadResourcePacks {
         id: InstanceID,
     },
+    /// Stops in-flight scans of the instance, sent when its page is closed
+    CancelInstanceLoads {
+        id: InstanceID,
+    },
+    /// Reads the worlds, servers and content of the instance again, for instances whose folders aren't watched
+    RefreshInstance {
+        id: InstanceID,
+    },
     SetContentEnabled {
         id: InstanceID,
         content_ids: Vec<InstanceContentID>,
@@ -88,20 +156,73 @@ pub enum MessageToBackend {
         id: InstanceID,
         content_ids: Vec<InstanceContentID>,
     },
+    /// Adds the resource pack to or removes it from the packs that new instances start with
+    SetDefaultResourcePack {
+        id: InstanceID,
+        content_id: InstanceContentID,
+        default: bool,
+    },
+    /// Resolves the newest version of the project for the instance's game version and loader, `None` if there isn't one
+    GetLatestCompatibleVersion {
+        instance: InstanceID,
+        project_id: Arc<str>,
+        project_type: ModrinthProjectType,
+        channel: tokio::sync::oneshot::Sender<Result<Option<ModrinthProjectVersion>, Arc<str>>>,
+    },
+    /// Answered with `MessageToFrontend::FollowedProjectsUpdated`
+    FollowProject {
+        project_id: Arc<str>,
+        title: Arc<str>,
+        project_type: ModrinthProjectType,
+        icon_url: Option<Arc<str>>,
+    },
+    /// Answered with `MessageToFrontend::FollowedProjectsUpdated`
+    UnfollowProject {
+        project_id: Arc<str>,
+    },
+    /// Checks followed projects for new versions without waiting for the periodic check
+    CheckFollowedProjects,
+    /// Marks every version currently in the followed projects feed as seen
+    MarkFollowedFeedSeen,
     InstallContent {
         content: ContentInstall,
         modal_action: ModalAction,
     },
+    /// Reads the name, Minecraft version and loader of a modpack file that's about to be installed
+    ReadModpackFile {
+        path: PathBuf,
+        channel: tokio::sync::oneshot::Sender<Result<ModpackFileInfo, Arc<str>>>,
+    },
+    /// Downloads a file that failed again, finishing the install if it was the last one missing
+    RetryInstallFile {
+        id: InstallQueueID,
+        file: usize,
+        modal_action: ModalAction,
+    },
+    RemoveFromInstallQueue {
+        id: InstallQueueID,
+    },
     DownloadAllMetadata,
     UpdateCheck {
         instance: InstanceID,
         modal_action: ModalAction
     },
+    /// Downloads content from the instance's content lock again if it's missing or corrupted
+    RepairInstanceContent {
+        instance: InstanceID,
+        modal_action: ModalAction,
+    },
     UpdateContent {
         instance: InstanceID,
         content_id: InstanceContentID,
         modal_action: ModalAction,
     },
+    /// Downloads the available update of a modpack and compares it to the installed version
+    GetModpackUpdateDiff {
+        instance: InstanceID,
+        content_id: InstanceContentID,
+        channel: tokio::sync::oneshot::Sender<Result<ModpackUpdateDiff, Arc<str>>>,
+    },
     Sleep5s,
     ReadLog {
         path: Arc<Path>,
@@ -111,12 +232,78 @@ pub enum MessageToBackend {
         instance: InstanceID,
         channel: tokio::sync::oneshot::Sender<LogFiles>,
     },
+    GetWorldDetails {
+        id: InstanceID,
+        level_path: Arc<Path>,
+        channel: tokio::sync::oneshot::Sender<Result<InstanceWorldDetails, Arc<str>>>,
+    },
+    EditServers {
+        id: InstanceID,
+        edit: ServerEdit,
+    },
+    CheckWorldVersion {
+        id: InstanceID,
+        level_path: Arc<Path>,
+        channel: tokio::sync::oneshot::Sender<Result<Option<WorldVersionMismatch>, Arc<str>>>,
+    },
+    /// Creates a vanilla instance on the version the world was last played in and moves the world into it
+    SplitWorldIntoInstance {
+        id: InstanceID,
+        level_path: Arc<Path>,
+        name: Ustr,
+        /// Copy the world instead, leaving it in the original instance too
+        keep_copy: bool,
+        modal_action: ModalAction,
+    },
+    GetLaunchHistory {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[LaunchHistoryEntry]>>,
+    },
+    ListConfigFiles {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[ConfigFileEntry]>>,
+    },
+    ReadConfigFile {
+        id: InstanceID,
+        path: Arc<str>,
+        channel: tokio::sync::oneshot::Sender<Result<ConfigFileContents, Arc<str>>>,
+    },
+    WriteConfigFile {
+        id: InstanceID,
+        path: Arc<str>,
+        contents: Arc<str>,
+        /// Modification time when the file was read, the write is refused if the file changed since
+        expected_modified: Option<SystemTime>,
+        channel: tokio::sync::oneshot::Sender<ConfigFileWriteResult>,
+    },
+    GetGameOptions {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Result<GameOptions, Arc<str>>>,
+    },
+    /// Only the values that are set are written, the rest of options.txt is left untouched
+    SetGameOptions {
+        id: InstanceID,
+        changes: GameOptions,
+        channel: tokio::sync::oneshot::Sender<Result<GameOptions, Arc<str>>>,
+    },
     GetSyncState {
         channel: tokio::sync::oneshot::Sender<SyncState>,
     },
     GetBackendConfiguration {
         channel: tokio::sync::oneshot::Sender<BackendConfig>,
     },
+    GetFileWatches {
+        channel: tokio::sync::oneshot::Sender<FileWatchDebugInfo>,
+    },
+    GetGpus {
+        channel: tokio::sync::oneshot::Sender<DetectedGpus>,
+    },
+    RewatchPath {
+        path: Arc<Path>,
+    },
+    UnwatchPath {
+        path: Arc<Path>,
+    },
     SetSyncing {
         target: SyncTarget,
         value: bool,
@@ -138,16 +325,107 @@ pub enum MessageToBackend {
     SelectAccount {
         uuid: Uuid,
     },
+    /// Removes the account along with its stored credentials
     DeleteAccount {
         uuid: Uuid,
     },
+    /// Logs in to the account again from scratch, e.g. after its refresh token was revoked
+    ReauthenticateAccount {
+        uuid: Uuid,
+        modal_action: ModalAction,
+    },
+    GetAccountTokenStates {
+        channel: tokio::sync::oneshot::Sender<Vec<(Uuid, AccountTokenState)>>,
+    },
     SetOpenGameOutputAfterLaunching {
         value: bool,
     },
+    SetStopGracePeriod {
+        secs: u32,
+    },
+    /// Whether account heads are rendered without the hat layer of the skin
+    SetHideHeadOverlay {
+        value: bool,
+    },
+    /// Answered with `MessageToFrontend::BackendSettingsUpdated`
+    SetBackendSettings {
+        settings: BackendSettings,
+    },
+    /// Whether GitHub is checked for new launcher versions at startup
+    SetLauncherUpdateCheck {
+        value: bool,
+    },
+    /// Whether instance folders are watched for changes, turning it off overrides the setting of every instance
+    SetFileWatching {
+        value: bool,
+    },
+    /// Downloads the latest launcher release, which replaces the executable on the next start
+    DownloadLauncherUpdate {
+        modal_action: ModalAction,
+    },
+    /// Whether a portable install may save account credentials next to the launcher
+    SetPortableCredentialsConsent {
+        value: bool,
+    },
+    SetProxy {
+        proxy: ProxyConfig,
+    },
+    SetMirrors {
+        mirrors: MirrorConfig,
+    },
+    SetMetadataCacheConfig {
+        config: MetadataCacheConfig,
+    },
+    GetMetadataCacheEntries {
+        channel: tokio::sync::oneshot::Sender<Arc<[MetadataCacheEntry]>>,
+    },
+    /// Downloads the manifest again, or removes the assets index so it's downloaded again on the next launch
+    RefreshMetadataCacheEntry {
+        kind: MetadataCacheKind,
+        path: Arc<Path>,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    GetDiskUsage {
+        channel: tokio::sync::oneshot::Sender<DiskUsage>,
+    },
+    CleanupStorage {
+        cleanup: StorageCleanup,
+        /// Only computes what would be removed, so it can be confirmed first
+        preview: bool,
+        channel: tokio::sync::oneshot::Sender<Result<CleanupResult, Arc<str>>>,
+    },
+    TestProxy {
+        proxy: ProxyConfig,
+        channel: tokio::sync::oneshot::Sender<Result<Duration, Arc<str>>>,
+    },
+    SetLauncherLock {
+        secret: Option<Arc<str>>,
+        idle_lock_minutes: Option<u32>,
+    },
+    LockLauncher,
+    UnlockLauncher {
+        secret: Arc<str>,
+        channel: tokio::sync::oneshot::Sender<UnlockResult>,
+    },
     CreateInstanceShortcut {
         id: InstanceID,
         path: PathBuf
     },
+    ExportInstanceMultiMC {
+        id: InstanceID,
+        path: PathBuf,
+        modal_action: ModalAction,
+    },
+    /// Writes the settings and Modrinth content of the instance to a small JSON file, without any jars
+    ExportSharedProfile {
+        id: InstanceID,
+        path: PathBuf,
+        modal_action: ModalAction,
+    },
+    ReadSharedProfile {
+        path: PathBuf,
+        channel: tokio::sync::oneshot::Sender<Result<SharedProfile, Arc<str>>>,
+    },
 }
 
 #[derive(Debug)]
@@ -176,14 +454,37 @@ pub enum MessageToFrontend {
         id: InstanceID,
         worlds: Arc<[InstanceWorldSummary]>,
     },
+    /// Sent while a saves folder with many worlds is loading, followed by `InstanceWorldsUpdated` once finished
+    InstanceWorldsUpdatedPartial {
+        id: InstanceID,
+        worlds: Arc<[InstanceWorldSummary]>,
+        total: usize,
+    },
     InstanceServersUpdated {
         id: InstanceID,
         servers: Arc<[InstanceServerSummary]>,
     },
+    /// The game overwrote servers.dat while running, undoing edits made from the launcher
+    ServerEditsOverwritten {
+        id: InstanceID,
+        edits: Arc<[ServerEdit]>,
+    },
     InstanceModsUpdated {
         id: InstanceID,
         mods: Arc<[InstanceContentSummary]>,
     },
+    /// Sent after `InstanceAdded` and whenever watching the folders of the instance is turned on or off
+    InstanceFileWatchingUpdated {
+        id: InstanceID,
+        watching: bool,
+        /// The instance folder is on a network filesystem, so it's only watched if enabled for the instance
+        network_drive: bool,
+    },
+    /// Sampled every second while the instance's game is running
+    InstanceResourceUsageUpdated {
+        id: InstanceID,
+        usage: InstanceResourceUsage,
+    },
     InstanceResourcePacksUpdated {
         id: InstanceID,
         resource_packs: Arc<[InstanceContentSummary]>,
@@ -194,9 +495,7 @@ pub enum MessageToFrontend {
     },
     AddGameOutput {
         id: usize,
-        time: i64,
-        level: GameOutputLogLevel,
-        text: Arc<[Arc<str>]>,
+        lines: Vec<GameOutputLine>,
     },
     AddNotification {
         notification_type: BridgeNotificationType,
@@ -206,6 +505,11 @@ pub enum MessageToFrontend {
         accounts: Arc<[Account]>,
         selected_account: Option<Uuid>,
     },
+    LauncherLockUpdated {
+        enabled: bool,
+        locked: bool,
+        idle_lock_minutes: Option<u32>,
+    },
     Refresh,
     CloseModal,
     MoveInstanceToTop {
@@ -216,6 +520,62 @@ pub enum MessageToFrontend {
         result: Result<MetadataResult, Arc<str>>,
         keep_alive_handle: Option<KeepAliveHandle>,
     },
+    InstallQueueUpdated {
+        entries: Arc<[InstallQueueEntry]>,
+    },
+    /// Metadata requests to the host will be retried once the rate limit has passed
+    MetadataRateLimited {
+        host: Arc<str>,
+        retry_in_secs: u64,
+    },
+    /// Files the launcher was asked to open, on the command line or by starting the launcher again while
+    /// it was running. The main window is brought to the front even if there are none
+    OpenFiles {
+        paths: Arc<[PathBuf]>,
+    },
+    LauncherUpdateAvailable {
+        update: LauncherUpdate,
+    },
+    /// Sent on startup and whenever the settings change
+    BackendSettingsUpdated {
+        settings: BackendSettings,
+    },
+    /// Sent on startup, whenever a project is (un)followed and after checking for new versions
+    FollowedProjectsUpdated {
+        followed: FollowedProjects,
+    },
+    /// Sent whenever a LAN game appears or disappears, they're only listened for while an instance is running or
+    /// something is watching them
+    LanGamesUpdated {
+        games: Arc<[LanGame]>,
+    },
+}
+
+impl MessageToFrontend {
+    /// Bulk messages are sent on a separate lane so they can't delay messages the UI is waiting on
+    pub fn is_bulk(&self) -> bool {
+        matches!(self, MessageToFrontend::AddGameOutput { .. })
+    }
+}
+
+/// A launcher release newer than the running version
+#[derive(Debug, Clone)]
+pub struct LauncherUpdate {
+    pub version: Arc<str>,
+    /// Release notes, in markdown
+    pub changelog: Arc<str>,
+    pub release_url: Arc<str>,
+    /// This build can download the update and replace itself, otherwise the release page has to be visited
+    pub can_install: bool,
+}
+
+/// What a launch of an instance would run, with the access token redacted
+#[derive(Debug, Clone)]
+pub struct LaunchPreview {
+    /// The whole command, quoted so it can be pasted into a terminal
+    pub command: Arc<str>,
+    /// The arguments passed to java before the main class
+    pub jvm_arguments: Arc<[Arc<str>]>,
 }
 
 #[derive(Debug, Default)]
@@ -224,6 +584,41 @@ pub struct LogFiles {
     pub total_gzipped_size: usize,
 }
 
+/// A file inside the instance's config folder, the path is relative and uses '/' as the separator
+#[derive(Debug, Clone)]
+pub struct ConfigFileEntry {
+    pub path: Arc<str>,
+    pub size: u64,
+}
+
+#[derive(Debug)]
+pub struct ConfigFileContents {
+    pub contents: Arc<str>,
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug)]
+pub enum ConfigFileWriteResult {
+    Saved {
+        modified: Option<SystemTime>,
+    },
+    /// The file was modified by something else, contains the new modification time
+    Conflict {
+        modified: Option<SystemTime>,
+    },
+    Error(Arc<str>),
+}
+
+/// Common vanilla settings from options.txt, a value is None if its key is missing
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GameOptions {
+    pub render_distance: Option<u32>,
+    pub fullscreen: Option<bool>,
+    /// 0 means auto
+    pub gui_scale: Option<u32>,
+    pub language: Option<Arc<str>>,
+}
+
 #[derive(Debug, Default)]
 pub struct SyncState {
     pub sync_folder: Option<Arc<Path>>,
@@ -233,6 +628,92 @@ pub struct SyncState {
     pub cannot_sync: enum_map::EnumMap<SyncTarget, usize>,
 }
 
+#[derive(Debug, Clone)]
+pub struct FileWatchEntry {
+    pub path: Arc<Path>,
+    /// Set when the watched path is a symlink
+    pub real_path: Option<Arc<Path>>,
+    pub target: Arc<str>,
+    pub events: usize,
+    pub errors: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct FileWatchDebugInfo {
+    pub entries: Vec<FileWatchEntry>,
+    /// Errors that couldn't be matched to a watched path
+    pub unattributed_errors: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuInfo {
+    pub name: Arc<str>,
+    pub vendor: GpuVendor,
+    /// Kernel driver, e.g. `nvidia`, `amdgpu` or `i915`
+    pub driver: Arc<str>,
+    /// PCI address, e.g. `0000:01:00.0`
+    pub pci_slot: Arc<str>,
+    /// Used by default for rendering, usually the integrated GPU on laptops
+    pub boot_vga: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DetectedGpus {
+    pub gpus: Vec<GpuInfo>,
+    /// Index into `gpus` of the GPU the game is offloaded to when launching on the discrete GPU
+    pub offload: Option<usize>,
+}
+
+impl DetectedGpus {
+    pub fn offload_gpu(&self) -> Option<&GpuInfo> {
+        self.offload.and_then(|index| self.gpus.get(index))
+    }
+
+    pub fn default_gpu(&self) -> Option<&GpuInfo> {
+        self.gpus.iter().find(|gpu| gpu.boot_vga).or(self.gpus.first())
+    }
+}
+
+/// Sizes in bytes of the launcher's data folders
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DiskUsage {
+    pub instances: u64,
+    pub assets: u64,
+    pub libraries: u64,
+    pub java_runtimes: u64,
+    pub content_library: u64,
+    pub metadata: u64,
+    pub logs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageCleanup {
+    /// Asset objects and indexes not used by the Minecraft version of any instance
+    UnusedAssets,
+    /// Libraries not needed by the Minecraft or loader versions of any instance
+    OrphanedLibraries,
+    /// Every file in the content library, they're downloaded again when needed
+    ContentLibrary,
+    /// Which Forge and NeoForge installer processors already ran, so they run again on the next launch
+    ForgeProcessorCache,
+    /// Logs of previous launches, the log of the current launch is kept
+    OldLogs,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CleanupResult {
+    pub removed_files: usize,
+    pub freed_bytes: u64,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BridgeNotificationType {
     Success,
@@ -263,9 +744,24 @@ impl BridgeDataLoadState {
     }
 }
 
+/// A world opened to LAN, found from the announcements the game sends to the local network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanGame {
+    pub motd: Arc<str>,
+    pub address: Arc<str>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QuickPlayLaunch {
     Singleplayer(OsString),
     Multiplayer(OsString),
     Realms(OsString),
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockResult {
+    Unlocked,
+    Incorrect,
+    /// Too many incorrect attempts were made, the next one is only checked after this many seconds
+    Throttled { retry_after_secs: u64 },
+}
@@ -1,6 +1,6 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc, time::SystemTime};
 
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthCategoryTags, ModrinthProject, ModrinthProjectMembers, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MetadataRequest {
@@ -10,6 +10,10 @@ pub enum MetadataRequest {
     NeoforgeMavenManifest,
     ModrinthSearch(ModrinthSearchRequest),
     ModrinthProjectVersions(ModrinthProjectVersionsRequest),
+    /// Project id or slug
+    ModrinthProject(Arc<str>),
+    ModrinthProjectMembers(Arc<str>),
+    ModrinthCategoryTags,
 }
 
 #[derive(Debug)]
@@ -20,4 +24,27 @@ pub enum MetadataResult {
     NeoforgeMavenManifest(Arc<NeoforgeMavenManifest>),
     ModrinthSearchResult(Arc<ModrinthSearchResult>),
     ModrinthProjectVersionsResult(Arc<ModrinthProjectVersionsResult>),
+    ModrinthProject(Arc<ModrinthProject>),
+    ModrinthProjectMembers(Arc<ModrinthProjectMembers>),
+    ModrinthCategoryTags(Arc<ModrinthCategoryTags>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataCacheKind {
+    MinecraftVersionManifest,
+    MojangJavaRuntimes,
+    FabricLoaderManifest,
+    ForgeMavenManifest,
+    NeoforgeMavenManifest,
+    ModrinthCategoryTags,
+    AssetsIndex,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetadataCacheEntry {
+    pub kind: MetadataCacheKind,
+    pub name: Arc<str>,
+    pub path: Arc<Path>,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
 }
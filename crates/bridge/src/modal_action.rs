@@ -1,10 +1,10 @@
 use std::{
     ops::Deref,
     sync::{
-        Arc, RwLock,
-        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use atomic_time::AtomicOptionInstant;
@@ -38,11 +38,42 @@ pub struct ModalActionVisitUrl {
     pub prevent_auto_finish: bool,
 }
 
+/// Questions nobody has shown by then are answered with the default, e.g. for queued installs without a visible modal
+const QUESTION_UNSEEN_TIMEOUT: Duration = Duration::from_secs(10);
+/// Shown questions are answered with the default after this long, so a dismissed notification can't block the action
+const QUESTION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Asked by the backend in the middle of an action, the action waits until it's answered
+#[derive(Debug)]
+pub struct ModalActionQuestion {
+    pub message: Arc<str>,
+    pub confirm: Arc<str>,
+    pub deny: Arc<str>,
+    seen: AtomicBool,
+    answer: Mutex<Option<tokio::sync::oneshot::Sender<bool>>>,
+}
+
+impl ModalActionQuestion {
+    /// Called by the frontend when rendering the question, unseen questions aren't waited on for long
+    pub fn mark_seen(&self) {
+        self.seen.store(true, Ordering::Relaxed);
+    }
+
+    pub fn answer(&self, confirmed: bool) {
+        if let Some(answer) = self.answer.lock().unwrap().take() {
+            let _ = answer.send(confirmed);
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct ModalActionInner {
     pub finished_at: AtomicOptionInstant,
     pub error: RwLock<Option<Arc<str>>>,
     pub visit_url: RwLock<Option<ModalActionVisitUrl>>,
+    pub question: RwLock<Option<Arc<ModalActionQuestion>>>,
+    /// Held while a question is shown, so questions from concurrent tasks are asked one after another
+    question_lock: tokio::sync::Mutex<()>,
     pub trackers: ProgressTrackers,
     pub request_cancel: CancellationToken,
 }
@@ -68,6 +99,40 @@ impl ModalActionInner {
         *self.visit_url.write().unwrap() = None;
     }
 
+    /// Shows the question and waits for it to be answered, cancelling the action counts as denying. If the question
+    /// isn't shown anywhere or isn't answered in time, `default` is used
+    pub async fn ask(&self, message: Arc<str>, confirm: Arc<str>, deny: Arc<str>, default: bool, sender: &FrontendHandle) -> bool {
+        let _asking = self.question_lock.lock().await;
+
+        let (answer, receive_answer) = tokio::sync::oneshot::channel();
+        let question = Arc::new(ModalActionQuestion {
+            message,
+            confirm,
+            deny,
+            seen: AtomicBool::new(false),
+            answer: Mutex::new(Some(answer)),
+        });
+        *self.question.write().unwrap() = Some(question.clone());
+        sender.send(MessageToFrontend::Refresh);
+
+        let timed_out = async {
+            tokio::time::sleep(QUESTION_UNSEEN_TIMEOUT).await;
+            if question.seen.load(Ordering::Relaxed) {
+                tokio::time::sleep(QUESTION_TIMEOUT.saturating_sub(QUESTION_UNSEEN_TIMEOUT)).await;
+            }
+        };
+
+        let confirmed = tokio::select! {
+            answer = receive_answer => answer.unwrap_or(default),
+            _ = self.request_cancel.cancelled() => false,
+            _ = timed_out => default,
+        };
+
+        *self.question.write().unwrap() = None;
+        sender.send(MessageToFrontend::Refresh);
+        confirmed
+    }
+
     pub fn request_cancel(&self) {
         self.request_cancel.cancel();
     }
@@ -83,6 +148,7 @@ impl std::fmt::Debug for ModalActionInner {
             .field("finished_at", &self.finished_at.load(Ordering::Relaxed))
             .field("error", &self.error)
             .field("visit_url", &self.visit_url)
+            .field("question", &self.question)
             .field("trackers", &self.trackers)
             .field("request_cancel", &self.request_cancel)
             .finish()
@@ -116,6 +182,7 @@ struct ProgressTrackerInner {
     total: AtomicUsize,
     finished_at: AtomicOptionInstant,
     finish_type: AtomicProgressTrackerFinishType,
+    unit: AtomicProgressTrackerUnit,
     title: RwLock<Arc<str>>,
 }
 
@@ -127,6 +194,18 @@ pub enum ProgressTrackerFinishType {
     Fast,
 }
 
+/// What the count and total of a tracker measure
+#[atomic_enum::atomic_enum]
+#[derive(PartialEq, Eq)]
+pub enum ProgressTrackerUnit {
+    /// Only shown as a progress bar
+    Steps,
+    /// Also shown as the amount downloaded out of the total size
+    Bytes,
+    /// Also shown as the amount downloaded, the total is still growing as sizes that weren't known up front are found out
+    BytesUnknownTotal,
+}
+
 impl ProgressTrackerFinishType {
     pub fn from_err(error: bool) -> Self {
         if error {
@@ -155,6 +234,7 @@ impl ProgressTracker {
                 total: AtomicUsize::new(0),
                 finished_at: AtomicOptionInstant::none(),
                 finish_type: AtomicProgressTrackerFinishType::new(ProgressTrackerFinishType::Normal),
+                unit: AtomicProgressTrackerUnit::new(ProgressTrackerUnit::Steps),
                 title: RwLock::new(title),
             }),
             sender,
@@ -203,6 +283,14 @@ impl ProgressTracker {
         self.inner.finish_type.load(Ordering::SeqCst)
     }
 
+    pub fn unit(&self) -> ProgressTrackerUnit {
+        self.inner.unit.load(Ordering::SeqCst)
+    }
+
+    pub fn set_unit(&self, unit: ProgressTrackerUnit) {
+        self.inner.unit.store(unit, Ordering::SeqCst);
+    }
+
     pub fn add_count(&self, count: usize) {
         self.inner.count.fetch_add(count, Ordering::SeqCst);
     }
@@ -222,4 +310,60 @@ impl ProgressTracker {
     pub fn notify(&self) {
         self.sender.send_with_serial(MessageToFrontend::Refresh, &self.notify_serial);
     }
+
+    /// Lists the tracker in the status bar of the frontend until the returned guard is dropped
+    pub fn register_background(&self, kind: BackgroundTaskKind, cancel: Option<CancellationToken>) -> BackgroundTaskGuard {
+        let tasks = self.sender.background_tasks().clone();
+        tasks.tasks.write().unwrap().push(BackgroundTask {
+            kind,
+            tracker: self.clone(),
+            cancel,
+        });
+        self.notify();
+        BackgroundTaskGuard {
+            tasks,
+            tracker: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackgroundTaskKind {
+    Metadata,
+    Install,
+    WorldLoad,
+}
+
+/// Work that isn't shown in a modal, e.g. because it wasn't started by the user
+#[derive(Clone, Debug)]
+pub struct BackgroundTask {
+    pub kind: BackgroundTaskKind,
+    pub tracker: ProgressTracker,
+    /// Stops the task when cancelled, none if the task can't be stopped
+    pub cancel: Option<CancellationToken>,
+}
+
+/// Shared between the backend and frontend, the frontend reads it whenever a tracker notifies
+#[derive(Default, Clone, Debug)]
+pub struct BackgroundTasks {
+    tasks: Arc<RwLock<Vec<BackgroundTask>>>,
+}
+
+impl BackgroundTasks {
+    pub fn get(&self) -> Vec<BackgroundTask> {
+        self.tasks.read().unwrap().clone()
+    }
+}
+
+pub struct BackgroundTaskGuard {
+    tasks: BackgroundTasks,
+    tracker: ProgressTracker,
+}
+
+impl Drop for BackgroundTaskGuard {
+    fn drop(&mut self) {
+        let id = self.tracker.id();
+        self.tasks.tasks.write().unwrap().retain(|task| task.tracker.id() != id);
+        self.tracker.notify();
+    }
 }
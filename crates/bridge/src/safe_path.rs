@@ -31,6 +31,10 @@ impl SafePath {
         Self::from_relative_path(RelativePath::new(path))
     }
 
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
     pub fn to_path(&self, base: &Path) -> PathBuf {
         self.0.to_path(base)
     }
@@ -0,0 +1,21 @@
+use std::{path::{Component, Path}, sync::OnceLock};
+
+/// Whether the launcher runs inside the Flatpak sandbox, where most host folders and programs aren't visible
+pub fn is_flatpak() -> bool {
+    static FLATPAK: OnceLock<bool> = OnceLock::new();
+    *FLATPAK.get_or_init(|| {
+        std::env::var_os("FLATPAK_ID").is_some() || Path::new("/.flatpak-info").exists()
+    })
+}
+
+/// Paths exported by the document portal, which is how the file chooser shares files the sandbox can't otherwise see.
+/// Picking a file only exports that file, picking a folder exports everything inside it
+pub fn is_document_portal_path(path: &Path) -> bool {
+    if path.starts_with("/run/flatpak/doc") {
+        return true;
+    }
+
+    // /run/user/<uid>/doc/...
+    path.starts_with("/run/user") && path.components().nth(4) == Some(Component::Normal("doc".as_ref()))
+}
+
@@ -7,12 +7,12 @@ use bridge::{
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, h_flex, list::{ListDelegate, ListItem, ListState}, switch::Switch, v_flex, ActiveTheme as _, Icon, IconName, IndexPath, Sizable
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputState}, list::{ListDelegate, ListItem, ListState}, menu::{ContextMenuExt, PopupMenuItem}, switch::Switch, v_flex, ActiveTheme as _, Icon, IconName, IndexPath, Sizable
 };
 use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
 
-use crate::{interface_config::InterfaceConfig, png_render_cache};
+use crate::{interface_config::InterfaceConfig, png_render_cache, ts};
 
 #[derive(Clone)]
 struct ContentEntryChild {
@@ -33,8 +33,11 @@ pub struct ContentListDelegate {
     id: InstanceID,
     backend_handle: BackendHandle,
     content: Vec<InstanceContentSummary>,
+    /// Indices into `content`, enabled content is listed in the first section and disabled in the second
+    sections: [Vec<usize>; 2],
     searched: Option<Vec<SummaryOrChild>>,
     children: Vec<Vec<ContentEntryChild>>,
+    /// Index into `content` plus one of the entry whose children are shown, zero if none are
     expanded: Arc<AtomicUsize>,
     confirming_delete: Arc<Mutex<FxHashSet<u64>>>,
     updating: Arc<Mutex<FxHashSet<u64>>>,
@@ -42,14 +45,30 @@ pub struct ContentListDelegate {
     selected: FxHashSet<u64>,
     selected_range: FxHashSet<u64>,
     last_clicked_non_range: Option<u64>,
+    /// Ids of mods that the last crash report blamed, along with the instance name used when relaunching
+    suspected: FxHashSet<Arc<str>>,
+    relaunch_name: SharedString,
+    /// Only list the suspected mods, applied together with the search query
+    only_suspected: bool,
+    /// Hashes of the resource packs that new instances start with, `None` if the list isn't for resource packs
+    default_resource_packs: Option<Arc<Mutex<FxHashSet<[u8; 20]>>>>,
+    /// Section of the topmost visible row, its header is kept pinned above the list
+    top_section: usize,
+    /// Last row passed to `render_item`. Visible rows are rendered top to bottom, so a row that isn't
+    /// below it means a new frame started
+    last_rendered: Option<(usize, usize)>,
 }
 
+/// Icons are shown at 64x64, decoding them at that size keeps large mod icons from filling the cache
+const ICON_TRANSFORM: png_render_cache::ImageTransformation = png_render_cache::ImageTransformation::Resize { width: 64, height: 64 };
+
 impl ContentListDelegate {
     pub fn new(id: InstanceID, backend_handle: BackendHandle) -> Self {
         Self {
             id,
             backend_handle,
             content: Vec::new(),
+            sections: [Vec::new(), Vec::new()],
             searched: None,
             children: Vec::new(),
             expanded: Arc::new(AtomicUsize::new(0)),
@@ -59,21 +78,67 @@ impl ContentListDelegate {
             selected: FxHashSet::default(),
             selected_range: FxHashSet::default(),
             last_clicked_non_range: None,
+            suspected: FxHashSet::default(),
+            relaunch_name: SharedString::new_static(""),
+            only_suspected: false,
+            default_resource_packs: None,
+            top_section: 0,
+            last_rendered: None,
+        }
+    }
+
+    pub fn set_default_resource_packs(&mut self, default_resource_packs: Arc<Mutex<FxHashSet<[u8; 20]>>>) {
+        self.default_resource_packs = Some(default_resource_packs);
+    }
+
+    pub fn set_suspected(&mut self, suspected: FxHashSet<Arc<str>>, instance_name: SharedString) {
+        self.suspected = suspected;
+        self.relaunch_name = instance_name;
+        if self.suspected.is_empty() {
+            self.only_suspected = false;
         }
+        self.actual_perform_search(&self.last_query.clone());
+    }
+
+    pub fn suspected_count(&self) -> usize {
+        self.suspected.len()
+    }
+
+    pub fn only_suspected(&self) -> bool {
+        self.only_suspected
+    }
+
+    pub fn set_only_suspected(&mut self, only_suspected: bool) {
+        self.only_suspected = only_suspected;
+        self.actual_perform_search(&self.last_query.clone());
+    }
+
+    fn is_suspected(&self, summary: &ContentSummary) -> bool {
+        summary.id.as_ref().is_some_and(|mod_id| self.suspected.contains(mod_id))
     }
 
-    pub fn render_summary(&self, summary: &InstanceContentSummary, selected: bool, expanded: bool, can_expand: bool, ix: usize, cx: &mut Context<ListState<Self>>) -> ListItem {
+    pub fn render_summary(&self, summary: &InstanceContentSummary, selected: bool, expanded: bool, can_expand: bool, content_index: usize, cx: &mut Context<ListState<Self>>) -> ListItem {
         let icon = if let Some(png_icon) = summary.content_summary.png_icon.as_ref() {
-            png_render_cache::render(Arc::clone(png_icon), cx)
+            png_render_cache::render_with_transform(Arc::clone(png_icon), ICON_TRANSFORM, cx)
         } else {
             gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
         };
 
         const GRAY: Hsla = Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0};
 
-        let (desc1, desc2) = create_descriptions(summary.content_summary.name.clone(),
-            summary.content_summary.version_str.clone(), summary.content_summary.authors.clone(),
-            summary.filename.clone());
+        let content_summary = &summary.content_summary;
+        let modrinth_project = content_summary.modrinth_project.get();
+
+        let name = content_summary.name.clone().or_else(|| modrinth_project.and_then(|project| project.title.clone()));
+        let authors = match modrinth_project {
+            Some(project) if content_summary.authors.is_empty() => project.authors.clone(),
+            _ => content_summary.authors.clone(),
+        };
+        let description = content_summary.description.clone()
+            .or_else(|| modrinth_project.and_then(|project| project.description.clone()));
+
+        let (desc1, desc2) = create_descriptions(name, content_summary.version_str.clone(), authors,
+            description, summary.filename.clone());
 
         let id = self.id;
         let content_id = summary.id;
@@ -133,25 +198,27 @@ impl ContentListDelegate {
             bridge::instance::ContentUpdateStatus::Unknown => None,
             bridge::instance::ContentUpdateStatus::ManualInstall => Some(
                 Button::new(("update", element_id)).warning().icon(Icon::default().path("icons/file-question-mark.svg"))
-                    .tooltip("Installed manually - cannot automatically update")
+                    .tooltip(ts!("modpack_update.manual_install"))
             ),
             bridge::instance::ContentUpdateStatus::ErrorNotFound => Some(
                 Button::new(("update", element_id)).danger().icon(Icon::default().path("icons/triangle-alert.svg"))
-                    .tooltip("Error while checking updates - 404 not found")
+                    .tooltip(ts!("modpack_update.not_found"))
             ),
             bridge::instance::ContentUpdateStatus::ErrorInvalidHash => Some(
                 Button::new(("update", element_id)).danger().icon(Icon::default().path("icons/triangle-alert.svg"))
-                    .tooltip("Error while checking updates - returned invalid hash")
+                    .tooltip(ts!("modpack_update.invalid_hash"))
             ),
             bridge::instance::ContentUpdateStatus::AlreadyUpToDate => Some(
                 Button::new(("update", element_id)).icon(Icon::default().path("icons/check.svg"))
-                    .tooltip("Up-to-date as of last check")
+                    .tooltip(ts!("modpack_update.up_to_date"))
             ),
             bridge::instance::ContentUpdateStatus::Modrinth => {
                 let loading = self.updating.lock().contains(&element_id);
+                let is_modpack = matches!(content_summary.extra, ContentType::ModrinthModpack { .. });
+                let name = content_summary.name.clone().unwrap_or_else(|| summary.filename.clone());
                 Some(
                     Button::new(("update", element_id)).success().loading(loading).icon(Icon::default().path("icons/download.svg"))
-                        .tooltip("Download update from Modrinth").on_click({
+                        .tooltip(if is_modpack { ts!("modpack_update.review") } else { ts!("modpack_update.download_from_modrinth") }).on_click({
                             let backend_handle = self.backend_handle.clone();
                             let updating = self.updating.clone();
                             cx.listener(move |this, _, window, cx| {
@@ -159,7 +226,10 @@ impl ContentListDelegate {
 
                                 let mut updating = updating.lock();
                                 let delegate = this.delegate_mut();
-                                if delegate.is_selected(element_id) {
+                                if is_modpack && !delegate.is_selected(element_id) {
+                                    // Modpacks can change many files, so the changes are shown first
+                                    crate::modals::modpack_update::open_modpack_update(id, content_id, SharedString::new(name.clone()), backend_handle.clone(), window, cx);
+                                } else if delegate.is_selected(element_id) {
                                     for summary in &delegate.content {
                                         if delegate.is_selected(summary.filename_hash) && summary.content_summary.update_status.load(Ordering::Relaxed).can_update() {
                                             updating.insert(summary.filename_hash);
@@ -179,6 +249,48 @@ impl ContentListDelegate {
             },
         };
 
+        let page_button = if let Some(project) = modrinth_project {
+            Some((project.url(), "Open Modrinth page"))
+        } else if let Some(homepage) = &content_summary.homepage
+            && (homepage.starts_with("https://") || homepage.starts_with("http://"))
+        {
+            Some((homepage.to_string(), "Open homepage"))
+        } else {
+            None
+        };
+        let page_button = page_button.map(|(url, tooltip)| {
+            Button::new(("page", element_id)).info().icon(IconName::Globe).tooltip(tooltip).on_click(move |_, _, cx| {
+                cx.stop_propagation();
+                cx.open_url(&url);
+            })
+        });
+
+        let suspected = self.is_suspected(content_summary);
+        let suspected_badge = suspected.then(|| {
+            div()
+                .px_1p5()
+                .rounded(cx.theme().radius)
+                .bg(cx.theme().danger)
+                .text_color(cx.theme().danger_foreground)
+                .text_sm()
+                .child(ts!("suspected.badge"))
+        });
+        let relaunch_button = (suspected && summary.enabled).then(|| {
+            let backend_handle = self.backend_handle.clone();
+            let instance_name = self.relaunch_name.clone();
+            Button::new(("relaunch", element_id)).danger().label(ts!("suspected.disable_and_relaunch"))
+                .tooltip(ts!("suspected.disable_and_relaunch_tooltip"))
+                .on_click(move |_, window, cx| {
+                    cx.stop_propagation();
+                    backend_handle.send(MessageToBackend::SetContentEnabled {
+                        id,
+                        content_ids: vec![content_id],
+                        enabled: false,
+                    });
+                    crate::root::start_instance(id, instance_name.clone(), None, &backend_handle, window, cx);
+                })
+        });
+
         let backend_handle = self.backend_handle.clone();
 
         let toggle_control = Switch::new(("toggle", element_id))
@@ -209,6 +321,23 @@ impl ContentListDelegate {
             }))
             .px_2();
 
+        let select_control = Checkbox::new(("select", element_id))
+            .checked(selected)
+            .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                cx.stop_propagation();
+                let delegate = this.delegate_mut();
+                delegate.confirming_delete.lock().clear();
+                delegate.selected.extend(&delegate.selected_range);
+                delegate.selected_range.clear();
+                if *checked {
+                    delegate.selected.insert(element_id);
+                } else {
+                    delegate.selected.remove(&element_id);
+                }
+                delegate.last_clicked_non_range = Some(element_id);
+                cx.notify();
+            }));
+
         let controls = if !can_expand {
             toggle_control.into_any_element()
         } else {
@@ -220,7 +349,7 @@ impl ContentListDelegate {
 
             let expand_control = Button::new(("expand", element_id)).icon(expand_icon).compact().small().info().on_click({
                 let expanded = self.expanded.clone();
-                let index = ix+1;
+                let index = content_index+1;
                 move |_, _, _| {
                     let value = expanded.load(Ordering::Relaxed);
                     if value == index {
@@ -238,21 +367,47 @@ impl ContentListDelegate {
                 .child(expand_control).into_any_element()
         };
 
-        let mut item_content = h_flex()
+        let item_content = h_flex()
             .gap_1()
+            .child(div().pl_2().child(select_control))
             .child(controls)
             .child(icon.size_16().min_w_16().min_h_16().grayscale(!summary.enabled))
             .when(!summary.enabled, |this| this.line_through())
             .child(desc1)
             .when_some(desc2, |div, desc2| div.child(desc2))
             .border_1()
-            .when(selected, |content| content.border_color(cx.theme().selection).bg(cx.theme().selection.alpha(0.2)));
-
-        if let Some(update_button) = update_button {
-            item_content = item_content.child(h_flex().absolute().right_4().gap_2().child(update_button).child(delete_button))
+            .when(selected, |content| content.border_color(cx.theme().selection).bg(cx.theme().selection.alpha(0.2)))
+            .when(suspected && !selected, |content| content.border_color(cx.theme().danger))
+            .child(h_flex()
+                .absolute()
+                .right_4()
+                .gap_2()
+                .children(suspected_badge)
+                .children(relaunch_button)
+                .children(page_button)
+                .children(update_button)
+                .child(delete_button));
+
+        let item_content = if let Some(default_resource_packs) = self.default_resource_packs.clone() {
+            let hash = content_summary.hash;
+            let backend_handle = self.backend_handle.clone();
+            item_content.id(("content", element_id)).context_menu(move |menu, _, _| {
+                let is_default = default_resource_packs.lock().contains(&hash);
+                let default_resource_packs = default_resource_packs.clone();
+                let backend_handle = backend_handle.clone();
+                menu.item(PopupMenuItem::new(ts!("instance.default_resource_pack")).checked(is_default).on_click(move |_, _, _| {
+                    let mut default_resource_packs = default_resource_packs.lock();
+                    if is_default {
+                        default_resource_packs.remove(&hash);
+                    } else {
+                        default_resource_packs.insert(hash);
+                    }
+                    backend_handle.send(MessageToBackend::SetDefaultResourcePack { id, content_id, default: !is_default });
+                }))
+            }).into_any_element()
         } else {
-            item_content = item_content.child(delete_button.absolute().right_4())
-        }
+            item_content.into_any_element()
+        };
 
         ListItem::new(("item", element_id)).p_1().child(item_content).on_click(cx.listener(move |this, click: &ClickEvent, _, cx| {
             cx.stop_propagation();
@@ -293,13 +448,16 @@ impl ContentListDelegate {
                             }
                         }
                     } else {
-                        let from_index = delegate.content.iter().position(|element| element.filename_hash == from);
+                        // Select in the order shown, which groups enabled and disabled content
+                        let ordered: Vec<u64> = delegate.ordered_content().map(|summary| summary.filename_hash).collect();
+
+                        let from_index = ordered.iter().position(|filename_hash| *filename_hash == from);
 
                         let Some(from_index) = from_index else {
                             return;
                         };
 
-                        let to_index = delegate.content.iter().position(|element| element.filename_hash == element_id);
+                        let to_index = ordered.iter().position(|filename_hash| *filename_hash == element_id);
 
                         let Some(to_index) = to_index else {
                             return;
@@ -308,9 +466,7 @@ impl ContentListDelegate {
                         let min_index = from_index.min(to_index);
                         let max_index = from_index.max(to_index);
 
-                        for add in delegate.content[min_index..=max_index].iter() {
-                            delegate.selected_range.insert(add.filename_hash);
-                        }
+                        delegate.selected_range.extend(&ordered[min_index..=max_index]);
                     }
                 } else if click.modifiers().secondary() || click.modifiers().shift {
                     // Cmd+Click (macos), Ctrl+Click (win/linux)
@@ -339,14 +495,14 @@ impl ContentListDelegate {
     fn render_child_entry(&self, child: &ContentEntryChild, cx: &mut App) -> ListItem {
         let summary = &child.summary;
         let icon = if let Some(png_icon) = summary.png_icon.as_ref() {
-            png_render_cache::render(Arc::clone(png_icon), cx)
+            png_render_cache::render_with_transform(Arc::clone(png_icon), ICON_TRANSFORM, cx)
         } else {
             gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
         };
 
         let (desc1, desc2) = create_descriptions(summary.name.clone(),
             summary.version_str.clone(), summary.authors.clone(),
-            child.path.clone());
+            summary.description.clone(), child.path.clone());
 
         let mut hasher = DefaultHasher::new();
         child.parent.hash(&mut hasher);
@@ -398,8 +554,12 @@ impl ContentListDelegate {
             name: None,
             version_str: "unknown".into(),
             authors: "".into(),
+            description: None,
+            homepage: None,
             png_icon: None,
+            loader_requirements: Default::default(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(bridge::instance::ContentUpdateStatus::Unknown)),
+            modrinth_project: Default::default(),
             extra: ContentType::Fabric,
         });
 
@@ -449,7 +609,13 @@ impl ContentListDelegate {
         }
         drop(updating);
 
+        let mut sections = [Vec::new(), Vec::new()];
+        for (index, summary) in mods.iter().enumerate() {
+            sections[Self::section_for(summary)].push(index);
+        }
+
         self.content = mods.clone();
+        self.sections = sections;
         self.children = children;
         self.searched = None;
         self.confirming_delete.lock().clear();
@@ -459,33 +625,35 @@ impl ContentListDelegate {
         let _ = self.actual_perform_search(&self.last_query.clone());
     }
 
-    fn actual_perform_search(&mut self, query: &str) {
+    pub fn actual_perform_search(&mut self, query: &str) {
         let query = query.trim_ascii();
 
         self.last_clicked_non_range = None;
 
-        if query.is_empty() {
-            self.last_query = SharedString::new_static("");
+        self.last_query = SharedString::new(query);
+
+        if query.is_empty() && !self.only_suspected {
             self.searched = None;
             return;
         }
 
-        self.last_query = SharedString::new(query);
-
         let query = query.to_lowercase();
+        let matches = |summary: &ContentSummary, lowercase_search_keys: &[Arc<str>]| {
+            (!self.only_suspected || self.is_suspected(summary)) && lowercase_search_keys.iter().any(|f| f.contains(&query))
+        };
 
         let mut searched = Vec::new();
 
         for (m, children) in self.content.iter().zip(self.children.iter()) {
             let mut parent_added = false;
 
-            if m.lowercase_search_keys.iter().any(|f| f.contains(&query)) {
+            if matches(&m.content_summary, &m.lowercase_search_keys) {
                 parent_added = true;
                 searched.push(SummaryOrChild::Summary(m.clone()));
             }
 
             for child in children {
-                if child.lowercase_search_keys.iter().any(|f| f.contains(&query)) {
+                if matches(&child.summary, &child.lowercase_search_keys) {
                     if !parent_added {
                         parent_added = true;
                         searched.push(SummaryOrChild::Summary(m.clone()));
@@ -499,10 +667,60 @@ impl ContentListDelegate {
         self.searched = Some(searched);
     }
 
+    fn section_header(&self, section: usize, cx: &App) -> Option<Div> {
+        if self.searched.is_some() || self.sections[section].is_empty() {
+            return None;
+        }
+
+        let label = if section == 0 { "Enabled" } else { "Disabled" };
+        Some(h_flex()
+            .px_3()
+            .py_1()
+            .gap_1()
+            .text_sm()
+            .text_color(cx.theme().muted_foreground)
+            .child(label)
+            .child(format!("({})", self.sections[section].len())))
+    }
+
+    /// Header of the section at the top of the list, drawn over the list so it stays visible while scrolling
+    pub fn render_sticky_header(&self, cx: &App) -> Option<Div> {
+        self.section_header(self.top_section, cx).map(|header| header.bg(cx.theme().background))
+    }
+
+    fn section_for(summary: &InstanceContentSummary) -> usize {
+        if summary.enabled { 0 } else { 1 }
+    }
+
+    fn ordered_content(&self) -> impl Iterator<Item = &InstanceContentSummary> {
+        self.sections.iter().flatten().map(|index| &self.content[*index])
+    }
+
+    /// Position within its section and number of children of the expanded entry, if it's in the section
+    fn expanded_in_section(&self, section: usize) -> Option<(usize, usize)> {
+        let expanded = self.expanded.load(Ordering::Relaxed).checked_sub(1)?;
+        let summary = self.content.get(expanded)?;
+        if Self::section_for(summary) != section {
+            return None;
+        }
+        let position = self.sections[section].binary_search(&expanded).ok()?;
+        Some((position, self.children[expanded].len()))
+    }
+
     fn is_selected(&self, element_id: u64) -> bool {
         self.selected.contains(&element_id) || self.selected_range.contains(&element_id)
     }
 
+    pub fn selected_count(&self) -> usize {
+        self.content.iter().filter(|summary| self.is_selected(summary.filename_hash)).count()
+    }
+
+    pub fn selected_content_ids(&self) -> Vec<InstanceContentID> {
+        self.content.iter().filter_map(|summary| {
+            self.is_selected(summary.filename_hash).then(|| summary.id)
+        }).collect()
+    }
+
     pub fn clear_selection(&mut self) {
         self.selected.clear();
         self.selected_range.clear();
@@ -530,28 +748,34 @@ impl ContentListDelegate {
 impl ListDelegate for ContentListDelegate {
     type Item = ListItem;
 
-    fn items_count(&self, _section: usize, _cx: &App) -> usize {
+    fn sections_count(&self, _cx: &App) -> usize {
+        if self.searched.is_some() {
+            1
+        } else {
+            self.sections.len()
+        }
+    }
+
+    fn items_count(&self, section: usize, _cx: &App) -> usize {
         if let Some(searched) = &self.searched {
             return searched.len();
         }
 
-        let expanded = self.expanded.load(Ordering::Relaxed);
-        if expanded > 0 {
-            self.content.len() + self.children[expanded - 1].len()
-        } else {
-            self.content.len()
-        }
+        let children = self.expanded_in_section(section).map(|(_, children)| children).unwrap_or(0);
+        self.sections[section].len() + children
     }
 
-    fn render_item(&mut self, ix: IndexPath, _window: &mut Window, cx: &mut Context<ListState<Self>>) -> Option<Self::Item> {
-        let mut index = ix.row;
+    fn render_section_header(&mut self, section: usize, _window: &mut Window, cx: &mut Context<ListState<Self>>) -> Option<impl IntoElement> {
+        self.section_header(section, cx)
+    }
 
+    fn render_item(&mut self, ix: IndexPath, _window: &mut Window, cx: &mut Context<ListState<Self>>) -> Option<Self::Item> {
         if let Some(searched) = &self.searched {
-            let item = searched.get(index)?;
+            let item = searched.get(ix.row)?;
             match item {
                 SummaryOrChild::Summary(instance_mod_summary) => {
                     let selected = self.is_selected(instance_mod_summary.filename_hash);
-                    return Some(self.render_summary(instance_mod_summary, selected, false, false, ix.row, cx));
+                    return Some(self.render_summary(instance_mod_summary, selected, false, false, 0, cx));
                 },
                 SummaryOrChild::Child(mod_entry_child) => {
                     return Some(self.render_child_entry(mod_entry_child, cx));
@@ -559,19 +783,30 @@ impl ListDelegate for ContentListDelegate {
             }
         }
 
-        let expanded = self.expanded.load(Ordering::Relaxed);
+        let rendered = (ix.section, ix.row);
+        if self.last_rendered.is_none_or(|last| rendered <= last) && self.top_section != ix.section {
+            self.top_section = ix.section;
+            // The page draws the sticky header, it observes the list
+            cx.notify();
+        }
+        self.last_rendered = Some(rendered);
+
+        let mut row = ix.row;
 
-        if expanded > 0 && index >= expanded {
-            if let Some(child) = self.children[expanded - 1].get(index-expanded) {
+        if let Some((position, children)) = self.expanded_in_section(ix.section) && row > position {
+            if row <= position + children {
+                let expanded = self.sections[ix.section][position];
+                let child = self.children[expanded].get(row - position - 1)?;
                 return Some(self.render_child_entry(child, cx));
             }
-            index -= self.children[expanded - 1].len();
+            row -= children;
         }
 
+        let index = *self.sections[ix.section].get(row)?;
         let summary = self.content.get(index)?;
         let selected = self.is_selected(summary.filename_hash);
-        Some(self.render_summary(summary, selected, index+1 == expanded, !self.children[index].is_empty(), ix.row, cx))
-
+        let expanded = self.expanded.load(Ordering::Relaxed) == index + 1;
+        Some(self.render_summary(summary, selected, expanded, !self.children[index].is_empty(), index, cx))
     }
 
     fn set_selected_index(&mut self, _ix: Option<IndexPath>, _window: &mut Window, _cx: &mut Context<ListState<Self>>) {
@@ -583,7 +818,26 @@ impl ListDelegate for ContentListDelegate {
     }
 }
 
-fn create_descriptions(name: Option<Arc<str>>, version: Arc<str>, authors: Arc<str>, filename: Arc<str>) -> (Div, Option<Div>) {
+/// The search input above the list, with the header of the topmost section pinned over the list
+pub fn render_with_search(list: &Entity<ListState<ContentListDelegate>>, search_input: &Entity<InputState>, cx: &App) -> impl IntoElement {
+    let sticky_header = list.read(cx).delegate().render_sticky_header(cx);
+
+    v_flex()
+        .size_full()
+        .child(div()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(Input::new(search_input).appearance(false).prefix(Icon::new(IconName::Search).small())))
+        .child(div()
+            .relative()
+            .size_full()
+            .child(list.clone())
+            .children(sticky_header.map(|header| header.absolute().top_0().left_0().right_0())))
+}
+
+fn create_descriptions(name: Option<Arc<str>>, version: Arc<str>, authors: Arc<str>, description: Option<Arc<str>>, filename: Arc<str>) -> (Div, Option<Div>) {
     if name.is_none() && authors.is_empty() {
         let description1 = v_flex()
             .w_2_5()
@@ -601,7 +855,12 @@ fn create_descriptions(name: Option<Arc<str>>, version: Arc<str>, authors: Arc<s
 
     const GRAY: Hsla = Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0};
     let mut description2 = v_flex()
+        .min_w_0()
+        .max_w_2_5()
         .text_color(GRAY)
+        .when_some(description, |this, description| {
+            this.child(div().truncate().child(SharedString::from(description)))
+        })
         .child(SharedString::from(authors));
 
     if name.is_some() {
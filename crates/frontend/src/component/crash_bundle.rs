@@ -0,0 +1,253 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, scroll::ScrollableElement, v_flex, ActiveTheme as _, Disableable, IconName, Selectable, Sizable, StyledExt
+};
+
+use crate::{entity::instance::InstanceEntries, ts};
+
+/// Writes the named files into a zip at the path. Provided by the launcher since the zip writer lives in the backend
+pub type WriteCrashBundle = fn(&Path, &[(String, String)]) -> std::io::Result<()>;
+
+/// Only the end of the launcher log is included, that's where the crash is
+const MAX_LOG_BYTES: u64 = 512 * 1024;
+
+struct BundleFile {
+    name: &'static str,
+    description: SharedString,
+    contents: SharedString,
+    included: bool,
+}
+
+/// Shows everything that would go into a crash report bundle so the user can check it for private
+/// information and leave files out before it's written
+pub struct CrashBundleReview {
+    files: Vec<BundleFile>,
+    previewed: usize,
+    write: WriteCrashBundle,
+    saved: Option<Result<PathBuf, SharedString>>,
+    _save_task: Task<()>,
+}
+
+impl EventEmitter<DismissEvent> for CrashBundleReview {}
+
+impl CrashBundleReview {
+    pub fn new(
+        title: &str,
+        details: Option<&str>,
+        instances: &Entity<InstanceEntries>,
+        launcher_log: &Path,
+        write: WriteCrashBundle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let crash = match details {
+            Some(details) => format!("{}\n\n{}", title, details),
+            None => title.to_string(),
+        };
+
+        let mut system = super::crash_screen::system_info();
+        if let Some(gpu) = window.gpu_specs() {
+            system.push_str(&format!("\nGPU: {}\nDriver: {} {}", gpu.device_name, gpu.driver_name, gpu.driver_info));
+            if gpu.is_software_emulated {
+                system.push_str("\nGPU is software emulated");
+            }
+        }
+
+        let log = match read_log_tail(launcher_log) {
+            Ok(log) => log,
+            Err(error) => format!("Unable to read launcher log: {error}"),
+        };
+
+        let mut instance_info = String::new();
+        for instance in instances.read(cx).entries.values() {
+            let instance = instance.read(cx);
+            let configuration = &instance.configuration;
+            instance_info.push_str(&format!("{}\n  Minecraft {}, {:?}", instance.name, configuration.minecraft_version, configuration.loader));
+            if let Some(loader_version) = configuration.preferred_loader_version {
+                instance_info.push_str(&format!(" {}", loader_version));
+            }
+            if let Some((min, max)) = configuration.effective_memory() {
+                instance_info.push_str(&format!(", {}-{} MiB", min, max));
+            }
+            instance_info.push_str(&format!(", {:?}\n", instance.status));
+        }
+
+        let files = vec![
+            BundleFile {
+                name: "crash.txt",
+                description: ts!("crash_bundle.crash_description"),
+                contents: redact(&crash).into(),
+                included: true,
+            },
+            BundleFile {
+                name: "system.txt",
+                description: ts!("crash_bundle.system_description"),
+                contents: system.into(),
+                included: true,
+            },
+            BundleFile {
+                name: "launcher.log",
+                description: ts!("crash_bundle.log_description"),
+                contents: redact(&log).into(),
+                included: true,
+            },
+            BundleFile {
+                name: "instances.txt",
+                description: ts!("crash_bundle.instances_description"),
+                contents: instance_info.into(),
+                included: true,
+            },
+        ];
+
+        Self {
+            files,
+            previewed: 0,
+            write,
+            saved: None,
+            _save_task: Task::ready(()),
+        }
+    }
+
+    fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let user_dirs = directories::UserDirs::new();
+        let directory = user_dirs.as_ref()
+            .and_then(directories::UserDirs::download_dir).unwrap_or(Path::new("."));
+        let file_name = format!("pandora-crash-{}.zip", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"));
+        let receiver = cx.prompt_for_new_path(directory, Some(&file_name));
+
+        self._save_task = cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
+                return;
+            };
+            _ = this.update(cx, |this, cx| {
+                let files: Vec<(String, String)> = this.files.iter()
+                    .filter(|file| file.included)
+                    .map(|file| (file.name.to_string(), file.contents.to_string()))
+                    .collect();
+                this.saved = Some(match (this.write)(&path, &files) {
+                    Ok(()) => Ok(path),
+                    Err(error) => Err(SharedString::new(rust_i18n::t!("crash_bundle.save_failed", error = error))),
+                });
+                cx.notify();
+            });
+        });
+    }
+}
+
+/// Reads the end of the log, starting at a line boundary
+fn read_log_tail(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(MAX_LOG_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let log = String::from_utf8_lossy(&bytes);
+
+    if start > 0 && let Some(newline) = log.find('\n') {
+        Ok(format!("... (earlier lines omitted)\n{}", &log[newline + 1..]))
+    } else {
+        Ok(log.into_owned())
+    }
+}
+
+/// Hides access tokens and session ids the same way as log uploads do, and the home folder, which usually contains
+/// the name of the user's computer account
+fn redact(text: &str) -> String {
+    let text = schema::redaction::redact(text);
+    let Some(base_dirs) = directories::BaseDirs::new() else {
+        return text.into_owned();
+    };
+    let home = base_dirs.home_dir().to_string_lossy();
+    if home.len() <= 1 {
+        return text.into_owned();
+    }
+    text.replace(&*home, "~")
+}
+
+impl Render for CrashBundleReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let files = self.files.iter().enumerate().map(|(index, file)| {
+            h_flex()
+                .gap_2()
+                .child(Checkbox::new(("include", index))
+                    .checked(file.included)
+                    .on_click(cx.listener(move |this, value: &bool, _, cx| {
+                        this.files[index].included = *value;
+                        cx.notify();
+                    })))
+                .child(v_flex()
+                    .flex_1()
+                    .child(div().font_medium().child(file.name))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(file.description.clone())))
+                .child(Button::new(("preview", index))
+                    .label(ts!("crash_bundle.preview"))
+                    .small()
+                    .outline()
+                    .selected(self.previewed == index)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.previewed = index;
+                        cx.notify();
+                    })))
+        });
+
+        let save = Button::new("save-bundle")
+            .success()
+            .icon(IconName::FolderOpen)
+            .label(ts!("crash_bundle.save_bundle"))
+            .disabled(!self.files.iter().any(|file| file.included))
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.save(window, cx);
+            }));
+        let back = Button::new("back").label(ts!("crash_bundle.back")).on_click(cx.listener(|_, _, _, cx| {
+            cx.emit(DismissEvent);
+        }));
+
+        let saved = self.saved.as_ref().map(|saved| match saved {
+            Ok(path) => h_flex()
+                .gap_2()
+                .text_color(theme.success)
+                .child(SharedString::new(rust_i18n::t!("crash_bundle.saved", path = path.display())))
+                .child(Button::new("show-bundle").label(ts!("crash_bundle.open_folder")).small().on_click({
+                    let folder = path.parent().map(Path::to_path_buf);
+                    move |_, window, cx| {
+                        if let Some(folder) = &folder {
+                            crate::open_folder(folder, window, cx);
+                        }
+                    }
+                })),
+            Err(error) => h_flex().text_color(theme.danger).child(error.clone()),
+        });
+
+        let preview = self.files.get(self.previewed).map(|file| file.contents.clone()).unwrap_or_default();
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(div().text_xl().font_semibold().child(ts!("crash_bundle.title")))
+            .child(div().text_color(theme.muted_foreground)
+                .child(ts!("crash_bundle.description")))
+            .child(v_flex().gap_2().children(files))
+            .child(h_flex().gap_2().child(save).child(back))
+            .children(saved)
+            .child(div()
+                .flex_1()
+                .min_h_0()
+                .p_2()
+                .border_1()
+                .border_color(theme.border)
+                .rounded(theme.radius)
+                .bg(theme.secondary)
+                .child(div().font_family("Roboto Mono").text_xs().child(preview))
+                .overflow_y_scrollbar())
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, v_flex, ActiveTheme as _, Icon, IconName, Sizable, StyledExt
+};
+
+use crate::ts;
+
+const ISSUES_URL: &str = "https://github.com/Moulberry/PandoraLauncher/issues/new";
+
+/// GitHub rejects overly long urls, the full report is copied to the clipboard instead
+const MAX_ISSUE_DETAILS_LEN: usize = 4000;
+
+/// Replaces the whole launcher when the backend panics, deadlocks or shuts down, since nothing else can work anymore
+#[derive(IntoElement)]
+pub struct CrashScreen {
+    title: SharedString,
+    details: Option<SharedString>,
+    restart_requested: Arc<AtomicBool>,
+    on_save_bundle: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+}
+
+impl CrashScreen {
+    pub fn new(title: impl Into<SharedString>, restart_requested: Arc<AtomicBool>) -> Self {
+        Self {
+            title: title.into(),
+            details: None,
+            restart_requested,
+            on_save_bundle: None,
+        }
+    }
+
+    pub fn details(mut self, details: impl Into<SharedString>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Shows a button for saving the report together with logs and system information
+    pub fn on_save_bundle(mut self, handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static) -> Self {
+        self.on_save_bundle = Some(Box::new(handler));
+        self
+    }
+}
+
+pub(crate) fn system_info() -> String {
+    format!(
+        "Launcher version: {}\nOS: {} ({})\nArch: {}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::FAMILY,
+        std::env::consts::ARCH
+    )
+}
+
+fn create_report(title: &str, details: Option<&str>) -> String {
+    match details {
+        Some(details) => format!("{}\n\n{}\n\n```\n{}\n```", title, system_info(), details),
+        None => format!("{}\n\n{}", title, system_info()),
+    }
+}
+
+fn create_issue_url(title: &str, details: Option<&str>) -> Option<String> {
+    let details = details.map(|details| match details.char_indices().nth(MAX_ISSUE_DETAILS_LEN) {
+        Some((end, _)) => format!("{}\n... (truncated, full report was copied to the clipboard)", &details[..end]),
+        None => details.to_string(),
+    });
+    let body = create_report(title, details.as_deref());
+
+    let url = url::Url::parse_with_params(ISSUES_URL, &[("title", title), ("body", &body)]).ok()?;
+    Some(url.into())
+}
+
+impl RenderOnce for CrashScreen {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let report = SharedString::new(create_report(&self.title, self.details.as_deref()));
+        let issue_url = create_issue_url(&self.title, self.details.as_deref());
+
+        let copy_report = Button::new("copy-report").icon(IconName::Copy).label(ts!("crash_screen.copy_report")).on_click({
+            let report = report.clone();
+            move |_, _, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(report.to_string()));
+            }
+        });
+        let open_issue = issue_url.map(|issue_url| {
+            Button::new("open-issue").icon(IconName::ExternalLink).label(ts!("crash_screen.open_issue")).on_click({
+                let report = report.clone();
+                move |_, _, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(report.to_string()));
+                    cx.open_url(&issue_url);
+                }
+            })
+        });
+        let save_bundle = self.on_save_bundle.map(|handler| {
+            Button::new("save-bundle").icon(IconName::File).label(ts!("crash_screen.save_bundle")).on_click(handler)
+        });
+        let restart = Button::new("restart").success().icon(IconName::Redo).label(ts!("crash_screen.restart")).on_click({
+            let restart_requested = self.restart_requested.clone();
+            move |_, _, cx| {
+                // The new process is started after quitting, once pending writes have been flushed
+                restart_requested.store(true, Ordering::SeqCst);
+                cx.quit();
+            }
+        });
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(h_flex()
+                .gap_2()
+                .text_color(theme.danger)
+                .child(Icon::new(IconName::CircleX).large())
+                .child(div().text_xl().font_semibold().child(self.title.clone())))
+            .child(div().text_color(theme.muted_foreground)
+                .child(ts!("crash_screen.description")))
+            .child(h_flex().gap_2().child(restart).child(copy_report).children(save_bundle).children(open_issue))
+            .when_some(self.details, |this, details| {
+                this.child(div()
+                    .flex_1()
+                    .min_h_0()
+                    .p_2()
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded(theme.radius)
+                    .bg(theme.secondary)
+                    .child(div().font_family("Roboto Mono").text_xs().child(details))
+                    .overflow_y_scrollbar())
+            })
+    }
+}
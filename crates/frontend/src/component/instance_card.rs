@@ -0,0 +1,362 @@
+use std::{ffi::OsString, path::Path, rc::Rc, sync::Arc};
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceStatus}, message::{MessageToBackend, QuickPlayLaunch}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, menu::{ContextMenuExt, PopupMenu, PopupMenuItem}, v_flex, ActiveTheme as _, Icon, IconName, Sizable, StyledExt
+};
+
+use schema::launch_history::LaunchHistoryQuickPlay;
+
+use crate::{
+    entity::instance::InstanceEntry, interface_config::{InstanceActivateAction, InterfaceConfig},
+    pages::instance::{instance_page::InstanceSubpageType, quickplay_subpage}, root, ts, ui
+};
+
+fn instance_icon() -> Img {
+    gpui::img(ImageSource::Resource(Resource::Embedded("images/default_world.png".into())))
+}
+
+fn instance_subtitle(instance: &InstanceEntry) -> SharedString {
+    format!("{} {}", instance.configuration.loader.name(), instance.configuration.minecraft_version).into()
+}
+
+fn start_button(id: impl Into<ElementId>, instance: &InstanceEntry, backend_handle: &BackendHandle) -> Button {
+    let instance_id = instance.id;
+    match instance.status {
+        InstanceStatus::NotRunning => {
+            let name = instance.name.clone();
+            let backend_handle = backend_handle.clone();
+            Button::new(id).success().icon(Icon::empty().path("icons/play.svg")).on_click(move |_, window, cx| {
+                root::start_instance(instance_id, name.clone(), None, &backend_handle, window, cx);
+            })
+        },
+        InstanceStatus::Launching => Button::new(id).warning().icon(IconName::Loader),
+        InstanceStatus::Running => {
+            let backend_handle = backend_handle.clone();
+            Button::new(id).danger().icon(IconName::Close).tooltip(ts!("instance.stop_short")).on_click(move |_, _, _| {
+                backend_handle.send(MessageToBackend::StopInstance { id: instance_id });
+            })
+        },
+        InstanceStatus::Stopping => {
+            let backend_handle = backend_handle.clone();
+            Button::new(id).danger().icon(IconName::Loader).tooltip(ts!("instance.force_kill")).on_click(move |_, _, _| {
+                backend_handle.send(MessageToBackend::KillInstance { id: instance_id });
+            })
+        },
+    }
+}
+
+fn resume_label(quick_play: &LaunchHistoryQuickPlay) -> SharedString {
+    match quick_play {
+        LaunchHistoryQuickPlay::Singleplayer(world) => format!("Resume {}", world).into(),
+        LaunchHistoryQuickPlay::Multiplayer(server) => format!("Resume {}", server).into(),
+        LaunchHistoryQuickPlay::Realms(realm) => format!("Resume realm {}", realm).into(),
+    }
+}
+
+/// Launches the instance straight into the world or server it was last quick-played into
+fn resume_instance(instance: &InstanceEntry, quick_play: &LaunchHistoryQuickPlay, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let name = instance.name.clone();
+    match quick_play {
+        LaunchHistoryQuickPlay::Singleplayer(world) => {
+            let level_path: Arc<Path> = instance.dot_minecraft_folder.join("saves").join(&**world).into();
+            quickplay_subpage::start_world(instance.id, name, level_path, OsString::from(&**world), backend_handle, window, cx);
+        },
+        LaunchHistoryQuickPlay::Multiplayer(server) => {
+            let quick_play = QuickPlayLaunch::Multiplayer(OsString::from(&**server));
+            root::start_instance(instance.id, name, Some(quick_play), backend_handle, window, cx);
+        },
+        LaunchHistoryQuickPlay::Realms(realm) => {
+            let quick_play = QuickPlayLaunch::Realms(OsString::from(&**realm));
+            root::start_instance(instance.id, name, Some(quick_play), backend_handle, window, cx);
+        },
+    }
+}
+
+/// Second half of the start split-button, only present while the instance isn't running and has been quick-played before
+fn resume_button(id: impl Into<ElementId>, instance: &InstanceEntry, backend_handle: &BackendHandle) -> Option<Button> {
+    if instance.status != InstanceStatus::NotRunning {
+        return None;
+    }
+    let quick_play = instance.configuration.last_quick_play.clone()?;
+
+    let instance = instance.clone();
+    let backend_handle = backend_handle.clone();
+    Some(Button::new(id).success().icon(IconName::ArrowRight).tooltip(resume_label(&quick_play)).on_click(move |_, window, cx| {
+        resume_instance(&instance, &quick_play, &backend_handle, window, cx);
+    }))
+}
+
+fn view_instance(id: InstanceID, window: &mut Window, cx: &mut App) {
+    root::switch_page(ui::PageType::InstancePage(id, InstanceSubpageType::Quickplay), &[ui::PageType::Instances], window, cx);
+}
+
+/// Performs the configured action for double-clicking or pressing Enter on an instance
+pub fn activate_instance(instance: &InstanceEntry, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    match InterfaceConfig::get(cx).instance_activate_action {
+        InstanceActivateAction::OpenPage => view_instance(instance.id, window, cx),
+        InstanceActivateAction::Launch => match instance.status {
+            InstanceStatus::NotRunning => {
+                root::start_instance(instance.id, instance.name.clone(), None, backend_handle, window, cx);
+            },
+            // Launching twice isn't possible, show the instance instead
+            InstanceStatus::Launching | InstanceStatus::Running | InstanceStatus::Stopping => view_instance(instance.id, window, cx),
+        },
+        InstanceActivateAction::OpenFolder => crate::open_folder(&instance.dot_minecraft_folder, window, cx),
+    }
+}
+
+type SelectHandler = Rc<dyn Fn(&InstanceID, &mut Window, &mut App)>;
+
+/// Single clicks select the instance, double clicks activate it
+fn handle_click(
+    instance: &InstanceEntry,
+    backend_handle: &BackendHandle,
+    on_select: Option<&SelectHandler>,
+) -> impl Fn(&ClickEvent, &mut Window, &mut App) + 'static {
+    let instance = instance.clone();
+    let backend_handle = backend_handle.clone();
+    let on_select = on_select.cloned();
+    move |click, window, cx| {
+        if click.click_count() >= 2 {
+            activate_instance(&instance, &backend_handle, window, cx);
+        } else if let Some(on_select) = &on_select {
+            on_select(&instance.id, window, cx);
+        }
+    }
+}
+
+/// Quick actions shown when right-clicking an instance
+pub fn instance_context_menu(menu: PopupMenu, instance: &InstanceEntry, backend_handle: &BackendHandle) -> PopupMenu {
+    let id = instance.id;
+    let name = instance.name.clone();
+
+    let launch_item = match instance.status {
+        InstanceStatus::NotRunning => PopupMenuItem::new(ts!("instances.launch")).icon(Icon::empty().path("icons/play.svg")).on_click({
+            let name = name.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                root::start_instance(id, name.clone(), None, &backend_handle, window, cx);
+            }
+        }),
+        InstanceStatus::Launching => PopupMenuItem::new(ts!("instance.launching")).icon(IconName::Loader).disabled(true),
+        InstanceStatus::Running => PopupMenuItem::new(ts!("instance.stop_short")).icon(IconName::Close).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, _, _| {
+                backend_handle.send(MessageToBackend::StopInstance { id });
+            }
+        }),
+        InstanceStatus::Stopping => PopupMenuItem::new(ts!("instance.stopping")).icon(IconName::Loader).disabled(true),
+    };
+
+    let force_kill_item = matches!(instance.status, InstanceStatus::Running | InstanceStatus::Stopping).then(|| {
+        PopupMenuItem::new(ts!("instance.force_kill")).icon(IconName::Close).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, _, _| {
+                backend_handle.send(MessageToBackend::KillInstance { id });
+            }
+        })
+    });
+
+    let resume_item = match &instance.configuration.last_quick_play {
+        Some(quick_play) if instance.status == InstanceStatus::NotRunning => {
+            Some(PopupMenuItem::new(resume_label(quick_play)).icon(IconName::ArrowRight).on_click({
+                let instance = instance.clone();
+                let quick_play = quick_play.clone();
+                let backend_handle = backend_handle.clone();
+                move |_, window, cx| {
+                    resume_instance(&instance, &quick_play, &backend_handle, window, cx);
+                }
+            }))
+        },
+        _ => None,
+    };
+
+    let pinned = instance.configuration.pinned;
+
+    let mut menu = menu.item(launch_item);
+    if let Some(force_kill_item) = force_kill_item {
+        menu = menu.item(force_kill_item);
+    }
+    if let Some(resume_item) = resume_item {
+        menu = menu.item(resume_item);
+    }
+
+    menu.separator()
+        .item(PopupMenuItem::new(ts!("instances.open_folder")).icon(IconName::FolderOpen).on_click({
+            let dot_minecraft = instance.dot_minecraft_folder.clone();
+            move |_, window, cx| {
+                crate::open_folder(&dot_minecraft, window, cx);
+            }
+        }))
+        .item(PopupMenuItem::new(ts!("instances.duplicate")).icon(IconName::Copy).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, _, _| {
+                backend_handle.send(MessageToBackend::DuplicateInstance { id });
+            }
+        }))
+        .item(PopupMenuItem::new(ts!("instance_settings.export_multimc")).icon(IconName::ExternalLink).on_click({
+            let name = name.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                let user_dirs = directories::UserDirs::new();
+                let directory = user_dirs.as_ref()
+                    .and_then(directories::UserDirs::download_dir).unwrap_or(Path::new("."));
+                let receiver = cx.prompt_for_new_path(directory, Some(&format!("{name}.zip")));
+                let backend_handle = backend_handle.clone();
+                window.spawn(cx, async move |cx| {
+                    let Ok(Ok(Some(path))) = receiver.await else {
+                        return;
+                    };
+                    _ = cx.update(|window, cx| {
+                        root::start_export_multimc(id, path, &backend_handle, window, cx);
+                    });
+                }).detach();
+            }
+        }))
+        .item(PopupMenuItem::new(if pinned { ts!("instances.unpin") } else { ts!("instances.pin") }).icon(IconName::Star).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, _, _| {
+                backend_handle.send(MessageToBackend::SetInstancePinned { id, pinned: !pinned });
+            }
+        }))
+        .separator()
+        .item(PopupMenuItem::new(ts!("instances.delete")).icon(IconName::Delete).disabled(instance.status != InstanceStatus::NotRunning).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                crate::modals::delete_instance::open_delete_instance(id, name.clone(), backend_handle.clone(), window, cx);
+            }
+        }))
+}
+
+/// Large card used by the grid view of the instances page
+#[derive(IntoElement)]
+pub struct InstanceCard {
+    instance: InstanceEntry,
+    backend_handle: BackendHandle,
+    selected: bool,
+    on_select: Option<SelectHandler>,
+}
+
+impl InstanceCard {
+    pub fn new(instance: InstanceEntry, backend_handle: BackendHandle) -> Self {
+        Self {
+            instance,
+            backend_handle,
+            selected: false,
+            on_select: None,
+        }
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn on_select(mut self, handler: impl Fn(&InstanceID, &mut Window, &mut App) + 'static) -> Self {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for InstanceCard {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+        let id = self.instance.id;
+
+        v_flex()
+            .id(("instance_card", id.index))
+            .w_48()
+            .p_3()
+            .gap_2()
+            .border_1()
+            .border_color(theme.border)
+            .rounded(theme.radius)
+            .bg(theme.secondary)
+            .hover(|this| this.border_color(theme.primary))
+            .when(self.selected, |this| this.border_color(theme.primary).bg(theme.secondary_hover))
+            .cursor_pointer()
+            .on_click(handle_click(&self.instance, &self.backend_handle, self.on_select.as_ref()))
+            .child(instance_icon().size_full().rounded(theme.radius))
+            .child(h_flex()
+                .w_full()
+                .gap_1()
+                .when(self.instance.configuration.pinned, |this| this.child(Icon::new(IconName::Star).small().text_color(theme.muted_foreground)))
+                .child(div().flex_1().truncate().font_semibold().child(self.instance.name.clone())))
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(instance_subtitle(&self.instance)))
+                    .child(h_flex()
+                        .gap_px()
+                        .child(start_button("start", &self.instance, &self.backend_handle).small())
+                        .when_some(resume_button("resume", &self.instance, &self.backend_handle), |this, resume| {
+                            this.child(resume.small())
+                        })),
+            )
+            .context_menu(move |menu, _, _| instance_context_menu(menu, &self.instance, &self.backend_handle))
+    }
+}
+
+/// Single line row used by the compact view of the instances page
+#[derive(IntoElement)]
+pub struct InstanceRow {
+    instance: InstanceEntry,
+    backend_handle: BackendHandle,
+    selected: bool,
+    on_select: Option<SelectHandler>,
+}
+
+impl InstanceRow {
+    pub fn new(instance: InstanceEntry, backend_handle: BackendHandle) -> Self {
+        Self {
+            instance,
+            backend_handle,
+            selected: false,
+            on_select: None,
+        }
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn on_select(mut self, handler: impl Fn(&InstanceID, &mut Window, &mut App) + 'static) -> Self {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for InstanceRow {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+        let id = self.instance.id;
+
+        h_flex()
+            .id(("instance_row", id.index))
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_3()
+            .border_b_1()
+            .border_color(theme.border)
+            .hover(|this| this.bg(theme.secondary))
+            .when(self.selected, |this| this.bg(theme.secondary_active))
+            .cursor_pointer()
+            .on_click(handle_click(&self.instance, &self.backend_handle, self.on_select.as_ref()))
+            .child(h_flex()
+                .gap_px()
+                .child(start_button("start", &self.instance, &self.backend_handle).xsmall())
+                .when_some(resume_button("resume", &self.instance, &self.backend_handle), |this, resume| {
+                    this.child(resume.xsmall())
+                }))
+            .child(instance_icon().size_6().min_w_6().min_h_6())
+            .when(self.instance.configuration.pinned, |this| this.child(Icon::new(IconName::Star).small().text_color(theme.muted_foreground)))
+            .child(div().flex_1().truncate().child(self.instance.name.clone()))
+            .child(div().text_sm().text_color(theme.muted_foreground).child(instance_subtitle(&self.instance)))
+            .context_menu(move |menu, _, _| instance_context_menu(menu, &self.instance, &self.backend_handle))
+    }
+}
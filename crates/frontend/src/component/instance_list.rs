@@ -1,16 +1,17 @@
 use bridge::handle::BackendHandle;
 use gpui::{prelude::*, *};
 use gpui_component::{
-    Sizable,
+    ActiveTheme as _, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
     h_flex,
+    menu::PopupMenu,
     table::{Column, ColumnSort, TableDelegate, TableState},
 };
 
 use crate::{
-    entity::{
+    component::instance_card::instance_context_menu, entity::{
         instance::{InstanceAddedEvent, InstanceEntry, InstanceModifiedEvent, InstanceRemovedEvent}, DataEntities
-    }, pages::instance::instance_page::InstanceSubpageType, root, ui
+    }, pages::instance::instance_page::InstanceSubpageType, root, ts, ui
 };
 
 pub struct InstanceList {
@@ -28,7 +29,9 @@ impl InstanceList {
         let items = instances.read(cx).entries.values().map(|i| i.read(cx).clone()).collect();
         cx.new(|cx| {
             let _instance_added_subscription = cx.subscribe::<_, InstanceAddedEvent>(&instances, |table: &mut TableState<InstanceList>, _, event, cx| {
-                table.delegate_mut().items.insert(0, event.instance.clone());
+                let delegate = table.delegate_mut();
+                delegate.items.insert(0, event.instance.clone());
+                delegate.sort_pinned_first();
                 cx.notify();
             });
             let _instance_removed_subscription = cx.subscribe::<_, InstanceRemovedEvent>(&instances, |table, _, event, cx| {
@@ -38,29 +41,31 @@ impl InstanceList {
                 cx.notify();
             });
             let _instance_modified_subscription = cx.subscribe::<_, InstanceModifiedEvent>(&instances, |table, _, event, cx| {
-                if let Some(entry) = table.delegate_mut().items.iter_mut().find(|entry| entry.id == event.instance.id) {
+                let delegate = table.delegate_mut();
+                if let Some(entry) = delegate.items.iter_mut().find(|entry| entry.id == event.instance.id) {
                     *entry = event.instance.clone();
+                    delegate.sort_pinned_first();
                     cx.notify();
                 }
             });
-            let instance_list = Self {
+            let mut instance_list = Self {
                 columns: vec![
                     Column::new("controls", "")
                         .width(150.)
                         .fixed_left()
                         .movable(false)
                         .resizable(false),
-                    Column::new("name", "Name")
+                    Column::new("name", ts!("common.name"))
                         .width(150.)
                         .fixed_left()
                         .sortable()
                         .resizable(true),
-                    Column::new("version", "Version")
+                    Column::new("version", ts!("instances.version"))
                         .width(150.)
                         .fixed_left()
                         .sortable()
                         .resizable(true),
-                    Column::new("loader", "Loader")
+                    Column::new("loader", ts!("instances.loader"))
                         .width(150.)
                         .fixed_left()
                         .resizable(true),
@@ -71,9 +76,19 @@ impl InstanceList {
                 _instance_removed_subscription,
                 _instance_modified_subscription,
             };
+            instance_list.sort_pinned_first();
             TableState::new(instance_list, window, cx)
         })
     }
+
+    pub fn instance(&self, row_ix: usize) -> Option<&InstanceEntry> {
+        self.items.get(row_ix)
+    }
+
+    /// Pinned instances stay on top, the stable sort keeps the current order otherwise
+    fn sort_pinned_first(&mut self) {
+        self.items.sort_by_key(|instance| !instance.configuration.pinned);
+    }
 }
 
 impl TableDelegate for InstanceList {
@@ -109,13 +124,25 @@ impl TableDelegate for InstanceList {
                 _ => {},
             }
         }
+        self.sort_pinned_first();
+    }
+
+    fn context_menu(&mut self, row_ix: usize, menu: PopupMenu, _window: &mut Window, _cx: &mut Context<TableState<Self>>) -> PopupMenu {
+        match self.items.get(row_ix) {
+            Some(item) => instance_context_menu(menu, item, &self.backend_handle),
+            None => menu,
+        }
     }
 
-    fn render_td(&mut self, row_ix: usize, col_ix: usize, _window: &mut Window, _cx: &mut Context<TableState<Self>>) -> impl IntoElement {
+    fn render_td(&mut self, row_ix: usize, col_ix: usize, _window: &mut Window, cx: &mut Context<TableState<Self>>) -> impl IntoElement {
         let item = &self.items[row_ix];
         if let Some(col) = self.columns.get(col_ix) {
             match col.key.as_ref() {
-                "name" => item.name.clone().into_any_element(),
+                "name" => h_flex()
+                    .gap_1()
+                    .child(item.name.clone())
+                    .when(item.configuration.pinned, |this| this.child(Icon::new(IconName::Star).small().text_color(cx.theme().muted_foreground)))
+                    .into_any_element(),
                 "version" => item.configuration.minecraft_version.as_str().into_any_element(),
                 "controls" => {
                     let backend_handle = self.backend_handle.clone();
@@ -123,14 +150,14 @@ impl TableDelegate for InstanceList {
                         .size_full()
                         .gap_2()
                         .border_r_4()
-                        .child(Button::new("start").w(relative(0.5)).small().success().label("Start").on_click({
+                        .child(Button::new("start").w(relative(0.5)).small().success().label(ts!("instances.start")).on_click({
                             let name = item.name.clone();
                             let id = item.id;
                             move |_, window, cx| {
                                 root::start_instance(id, name.clone(), None, &backend_handle, window, cx);
                             }
                         }))
-                        .child(Button::new("view").w(relative(0.5)).small().info().label("View").on_click({
+                        .child(Button::new("view").w(relative(0.5)).small().info().label(ts!("instances.view")).on_click({
                             let id = item.id;
                             move |_, window, cx| {
                                 root::switch_page(ui::PageType::InstancePage(id, InstanceSubpageType::Quickplay),
@@ -140,10 +167,10 @@ impl TableDelegate for InstanceList {
                         .into_any_element()
                 },
                 "loader" => item.configuration.loader.name().into_any_element(),
-                _ => "Unknown".into_any_element(),
+                _ => ts!("instances.unknown").into_any_element(),
             }
         } else {
-            "Unknown".into_any_element()
+            ts!("instances.unknown").into_any_element()
         }
     }
 }
@@ -0,0 +1,258 @@
+use std::{hash::Hasher, ops::Range, rc::Rc};
+
+use chrono::Timelike;
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::Button, h_flex, input::{Input, InputEvent, InputState}, ActiveTheme as _, Icon, IconName, Sizable
+};
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{interface_config::InterfaceConfig, ts};
+
+pub const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// Logs whose bookmarks are remembered, the ones changed longest ago are forgotten first
+const MAX_SAVED_LOGS: usize = 32;
+/// Lines hashed to tell apart logs saved under the same path, like the `latest.log` of each launch
+const IDENTITY_LINES: usize = 16;
+
+/// Bookmarked lines of a saved log, kept in the interface config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedLogBookmarks {
+    pub path: SharedString,
+    /// Hash of the first lines of the log
+    pub identity: u64,
+    pub lines: Vec<usize>,
+}
+
+/// A log file as it was when it was read, see [`LogIdentityHasher`]
+#[derive(Clone)]
+pub struct LogIdentity {
+    path: SharedString,
+    hash: u64,
+}
+
+/// Hashes the first lines of a log, the path alone would mix up the bookmarks of every launch
+/// writing to `latest.log`
+pub struct LogIdentityHasher {
+    path: SharedString,
+    hasher: FxHasher,
+    lines: usize,
+}
+
+impl LogIdentityHasher {
+    pub fn new(path: SharedString) -> Self {
+        Self {
+            path,
+            hasher: FxHasher::default(),
+            lines: 0,
+        }
+    }
+
+    /// Returns true once enough lines were hashed to call [`Self::finish`]
+    pub fn add_line(&mut self, line: &str) -> bool {
+        self.hasher.write(line.as_bytes());
+        self.hasher.write_u8(b'\n');
+        self.lines += 1;
+        self.lines >= IDENTITY_LINES
+    }
+
+    pub fn finish(self) -> LogIdentity {
+        LogIdentity {
+            path: self.path,
+            hash: self.hasher.finish(),
+        }
+    }
+}
+
+impl LogIdentity {
+    /// The lines bookmarked in this log previously. Bookmarks of an older log with the same path are
+    /// forgotten, that log has been replaced
+    pub fn load_bookmarks(&self, cx: &mut App) -> Vec<usize> {
+        let saved = &InterfaceConfig::get(cx).log_bookmarks;
+        if saved.iter().any(|saved| saved.path == self.path && saved.identity != self.hash) {
+            InterfaceConfig::get_mut(cx).log_bookmarks.retain(|saved| saved.path != self.path || saved.identity == self.hash);
+        }
+
+        InterfaceConfig::get(cx).log_bookmarks.iter()
+            .find(|saved| saved.path == self.path)
+            .map(|saved| saved.lines.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn save_bookmarks(&self, lines: Vec<usize>, cx: &mut App) {
+        let saved = &mut InterfaceConfig::get_mut(cx).log_bookmarks;
+        saved.retain(|saved| saved.path != self.path);
+        if lines.is_empty() {
+            return;
+        }
+
+        saved.push(SavedLogBookmarks {
+            path: self.path.clone(),
+            identity: self.hash,
+            lines,
+        });
+        if saved.len() > MAX_SAVED_LOGS {
+            saved.drain(..saved.len() - MAX_SAVED_LOGS);
+        }
+    }
+}
+
+struct PaintedItem {
+    index: usize,
+    top: Pixels,
+    bottom: Pixels,
+}
+
+/// Where the items of a log were painted in the last frame, used to find the item whose gutter was clicked
+#[derive(Default)]
+pub struct PaintedItems {
+    items: Vec<PaintedItem>,
+    gutter: Range<Pixels>,
+}
+
+impl PaintedItems {
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Clicking inside the horizontal range bookmarks an item
+    pub fn set_gutter(&mut self, gutter: Range<Pixels>) {
+        self.gutter = gutter;
+    }
+
+    /// Remembers where an item was painted, marking it at the start of the gutter if it's bookmarked
+    pub fn paint(&mut self, index: usize, origin: Point<Pixels>, height: Pixels, bookmarked: bool, window: &mut Window, cx: &App) {
+        if bookmarked {
+            let marker = Bounds::new(origin, size(px(4.0), height));
+            window.paint_quad(fill(marker, cx.theme().primary));
+        }
+        self.items.push(PaintedItem {
+            index,
+            top: origin.y,
+            bottom: origin.y + height,
+        });
+    }
+
+    /// The item whose gutter is at the position
+    pub fn item_at(&self, position: Point<Pixels>) -> Option<usize> {
+        if !self.gutter.contains(&position.x) {
+            return None;
+        }
+        self.items.iter()
+            .find(|painted| painted.top <= position.y && position.y < painted.bottom)
+            .map(|painted| painted.index)
+    }
+}
+
+/// The closest bookmarked item after (or before) the current one
+pub fn find_bookmark(current: usize, len: usize, forwards: bool, is_bookmark: impl Fn(usize) -> bool) -> Option<usize> {
+    if forwards {
+        (current + 1..len).find(|index| is_bookmark(*index))
+    } else {
+        (0..current.min(len)).rev().find(|index| is_bookmark(*index))
+    }
+}
+
+pub fn create_jump_input(window: &mut Window, cx: &mut App) -> Entity<InputState> {
+    cx.new(|cx| InputState::new(window, cx).placeholder(ts!("logs.jump_to_time")).clean_on_escape())
+}
+
+/// The time that was entered when Enter is pressed in the input created by [`create_jump_input`]
+pub fn jump_target(state: &Entity<InputState>, event: &InputEvent, cx: &App) -> Option<chrono::NaiveTime> {
+    let InputEvent::PressEnter { secondary: false } = event else {
+        return None;
+    };
+    parse_time_of_day(&state.read(cx).value())
+}
+
+/// The "jump to time" input along with the buttons going to the previous and next bookmark. The
+/// listener is called with true to go to the next bookmark
+pub fn controls(jump_state: &Entity<InputState>, on_bookmark: impl Fn(&bool, &mut Window, &mut App) + 'static) -> impl IntoElement {
+    let on_bookmark = Rc::new(on_bookmark);
+    let on_next = on_bookmark.clone();
+    h_flex()
+        .flex_none()
+        .gap_4()
+        .child(div().w_32().child(Input::new(jump_state).prefix(Icon::new(IconName::Calendar).small())))
+        .child(Button::new("previous-bookmark").icon(IconName::ChevronUp).tooltip(ts!("logs.previous_bookmark")).on_click(move |_, window, cx| {
+            on_bookmark(&false, window, cx);
+        }))
+        .child(Button::new("next-bookmark").icon(IconName::ChevronDown).tooltip(ts!("logs.next_bookmark")).on_click(move |_, window, cx| {
+            on_next(&true, window, cx);
+        }))
+}
+
+/// Parses a time typed into a "jump to time" input, seconds are optional
+fn parse_time_of_day(text: &str) -> Option<chrono::NaiveTime> {
+    let text = text.trim();
+    chrono::NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
+
+/// Reads the time of day from the start of a line like `[12:34:56] [main/INFO]: ...`, in seconds since
+/// midnight. Some loaders put the date or milliseconds into the brackets as well, like `[05Jan2025 12:34:56.789]`
+pub fn parse_log_time(line: &str) -> Option<u32> {
+    let rest = line.strip_prefix('[')?;
+    let end = rest.get(..32).unwrap_or(rest).find(']')?;
+    let time = rest[..end].rsplit(' ').next()?;
+    let time = chrono::NaiveTime::parse_from_str(time, "%H:%M:%S%.f").ok()?;
+    Some(time.num_seconds_from_midnight())
+}
+
+/// Turns the time of day of a line into seconds since midnight of the day the log started, given the
+/// time of the line before it
+pub fn continue_log_time(time_of_day: u32, previous: u32) -> u32 {
+    let time = time_of_day + (previous / SECONDS_PER_DAY) * SECONDS_PER_DAY;
+    // The time jumped back by more than half a day, the log continued past midnight
+    if time + SECONDS_PER_DAY / 2 < previous {
+        time + SECONDS_PER_DAY
+    } else {
+        time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_times() {
+        assert_eq!(parse_log_time("[12:34:56] [main/INFO]: Setting user: Player"), Some(12 * 3600 + 34 * 60 + 56));
+        assert_eq!(parse_log_time("[05Jan2025 01:02:03.456] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: Loading"), Some(3723));
+        assert_eq!(parse_log_time("\tat net.minecraft.client.main.Main.main(Main.java:218)"), None);
+        assert_eq!(parse_log_time("[main/INFO]: no time"), None);
+        assert_eq!(parse_log_time("[25:00:00] invalid"), None);
+        // Cut off in the middle of a character when looking for the closing bracket
+        assert_eq!(parse_log_time("[aéééééééééééééééééééé] text"), None);
+    }
+
+    #[test]
+    fn log_time_continues_past_midnight() {
+        let before_midnight = continue_log_time(23 * 3600 + 59 * 60, 0);
+        let after_midnight = continue_log_time(60, before_midnight);
+        assert_eq!(after_midnight, SECONDS_PER_DAY + 60);
+        assert_eq!(continue_log_time(120, after_midnight), SECONDS_PER_DAY + 120);
+        // Lines logged slightly out of order stay on the same day
+        assert_eq!(continue_log_time(50, after_midnight), SECONDS_PER_DAY + 50);
+    }
+
+    #[test]
+    fn times_of_day() {
+        assert_eq!(parse_time_of_day(" 12:30 "), chrono::NaiveTime::from_hms_opt(12, 30, 0));
+        assert_eq!(parse_time_of_day("01:02:03"), chrono::NaiveTime::from_hms_opt(1, 2, 3));
+        assert_eq!(parse_time_of_day("noon"), None);
+    }
+
+    #[test]
+    fn bookmarks_are_found_in_both_directions() {
+        let bookmarks = [2, 5, 9];
+        let is_bookmark = |index| bookmarks.contains(&index);
+        assert_eq!(find_bookmark(5, 10, true, is_bookmark), Some(9));
+        assert_eq!(find_bookmark(5, 10, false, is_bookmark), Some(2));
+        assert_eq!(find_bookmark(9, 10, true, is_bookmark), None);
+        assert_eq!(find_bookmark(20, 10, false, is_bookmark), Some(9));
+    }
+}
@@ -0,0 +1,335 @@
+use gpui::{prelude::*, *};
+use gpui_component::{ActiveTheme as _, StyledExt, h_flex, v_flex};
+
+/// The subset of markdown that most Modrinth descriptions use. Headings, lists, quotes, code blocks,
+/// rules and images get their own blocks, inline formatting is dropped and links keep only their text.
+/// HTML tags are removed except for images
+pub struct Markdown {
+    blocks: Vec<Block>,
+}
+
+enum Block {
+    Heading(u8, SharedString),
+    Paragraph(SharedString),
+    ListItem(usize, SharedString),
+    Quote(SharedString),
+    Code(SharedString),
+    Image(SharedUri),
+    Rule,
+}
+
+impl Markdown {
+    pub fn parse(markdown: &str) -> Self {
+        let mut blocks = Vec::new();
+        let mut paragraph = String::new();
+        let mut code: Option<String> = None;
+
+        for line in markdown.lines() {
+            if let Some(code_block) = &mut code {
+                if line.trim_start().starts_with("```") {
+                    blocks.push(Block::Code(code_block.trim_end().to_string().into()));
+                    code = None;
+                } else {
+                    code_block.push_str(line);
+                    code_block.push('\n');
+                }
+                continue;
+            }
+
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                code = Some(String::new());
+                continue;
+            }
+
+            let mut images = Vec::new();
+            let without_images = take_images(trimmed, &mut images);
+            let without_images = without_images.trim();
+
+            if !images.is_empty() {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.extend(images.into_iter().map(|url| Block::Image(url.into())));
+            }
+
+            if is_rule(without_images) {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.push(Block::Rule);
+                continue;
+            }
+
+            if let Some((level, heading)) = parse_heading(without_images) {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.push(Block::Heading(level, strip_inline(heading).trim().to_string().into()));
+                continue;
+            }
+            if let Some(item) = parse_list_item(without_images) {
+                let indent = (line.len() - line.trim_start().len()) / 2;
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.push(Block::ListItem(indent, strip_inline(item).trim().to_string().into()));
+                continue;
+            }
+
+            let quote = without_images.strip_prefix('>');
+            let text = strip_inline(quote.unwrap_or(without_images));
+            let text = text.trim();
+            if text.is_empty() {
+                // Lines that only contained html tags or images don't end paragraphs
+                if trimmed.is_empty() {
+                    flush_paragraph(&mut paragraph, &mut blocks);
+                }
+                continue;
+            }
+
+            if quote.is_some() {
+                flush_paragraph(&mut paragraph, &mut blocks);
+                blocks.push(Block::Quote(text.to_string().into()));
+            } else {
+                if !paragraph.is_empty() {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(text);
+            }
+        }
+
+        if let Some(code_block) = code {
+            blocks.push(Block::Code(code_block.trim_end().to_string().into()));
+        }
+        flush_paragraph(&mut paragraph, &mut blocks);
+
+        Self { blocks }
+    }
+
+    pub fn render(&self, cx: &App) -> Div {
+        let theme = cx.theme();
+        v_flex().gap_2().children(self.blocks.iter().map(|block| match block {
+            Block::Heading(level, text) => {
+                let heading = div().font_bold().pt_2().child(text.clone());
+                match level {
+                    1 => heading.text_2xl(),
+                    2 => heading.text_xl(),
+                    _ => heading.text_lg(),
+                }
+            },
+            Block::Paragraph(text) => div().child(text.clone()),
+            Block::ListItem(indent, text) => div().child(h_flex()
+                .items_start()
+                .gap_2()
+                .pl(px(16.0 * *indent as f32))
+                .child("•")
+                .child(div().flex_1().child(text.clone()))),
+            Block::Quote(text) => div()
+                .pl_3()
+                .border_l_2()
+                .border_color(theme.border)
+                .text_color(theme.muted_foreground)
+                .child(text.clone()),
+            Block::Code(text) => div()
+                .p_2()
+                .rounded_md()
+                .bg(theme.secondary)
+                .font_family("Roboto Mono")
+                .text_sm()
+                .child(text.clone()),
+            Block::Image(url) => div().child(img(url.clone()).max_w_full()),
+            Block::Rule => div().h_px().w_full().bg(theme.border),
+        }))
+    }
+}
+
+fn flush_paragraph(paragraph: &mut String, blocks: &mut Vec<Block>) {
+    if !paragraph.is_empty() {
+        blocks.push(Block::Paragraph(std::mem::take(paragraph).into()));
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3 && ['-', '*', '_'].into_iter().any(|rule| compact.chars().all(|c| c == rule))
+}
+
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let heading = line[level..].strip_prefix(' ')?;
+    Some((level as u8, heading.trim_end_matches('#').trim()))
+}
+
+fn parse_list_item(line: &str) -> Option<&str> {
+    if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).or_else(|| line.strip_prefix("+ ")) {
+        return Some(item);
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 {
+        return line[digits..].strip_prefix(". ").or_else(|| line[digits..].strip_prefix(") "));
+    }
+    None
+}
+
+/// Removes markdown and html images from the line, adding their urls to `images`
+fn take_images(line: &str, images: &mut Vec<String>) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        let markdown_image = rest.find("![");
+        let html_image = rest.find("<img");
+        let start = match (markdown_image, html_image) {
+            (Some(markdown), Some(html)) => markdown.min(html),
+            (Some(start), None) | (None, Some(start)) => start,
+            (None, None) => break,
+        };
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("![") {
+            let Some(url_start) = rest.find("](") else {
+                result.push_str(rest);
+                return result;
+            };
+            let Some(url_len) = rest[url_start+2..].find(')') else {
+                result.push_str(rest);
+                return result;
+            };
+            let url = rest[url_start+2..url_start+2+url_len].split_whitespace().next().unwrap_or_default();
+            if !url.is_empty() {
+                images.push(url.to_string());
+            }
+            rest = &rest[url_start+3+url_len..];
+        } else {
+            let end = rest.find('>').map(|end| end + 1).unwrap_or(rest.len());
+            let tag = &rest[..end];
+            if let Some(src) = html_attribute(tag, "src") {
+                images.push(src.to_string());
+            }
+            rest = &rest[end..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn html_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{name}="))? + name.len() + 1;
+    let value = &tag[start..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(&value[..value.find(quote)?])
+}
+
+/// Drops emphasis, inline code markers and html tags, and replaces links with their text
+fn strip_inline(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '<' => {
+                // Skip html tags, but keep a lone '<'
+                if let Some(end) = text[index..].find('>') && text[index+1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+                    while chars.peek().is_some_and(|(next, _)| *next <= index + end) {
+                        chars.next();
+                    }
+                } else {
+                    result.push(c);
+                }
+            },
+            '[' => {
+                // Links become their text, the url is dropped
+                let rest = &text[index+1..];
+                if let Some(text_end) = rest.find("](") && let Some(url_len) = rest[text_end+2..].find(')') {
+                    result.push_str(&strip_inline(&rest[..text_end]));
+                    let skip_to = index + 1 + text_end + 2 + url_len;
+                    while chars.peek().is_some_and(|(next, _)| *next <= skip_to) {
+                        chars.next();
+                    }
+                } else {
+                    result.push(c);
+                }
+            },
+            '*' | '`' | '~' => {},
+            '_' if chars.peek().is_some_and(|(_, next)| *next == '_') => {
+                chars.next();
+            },
+            '&' => {
+                let rest = &text[index..];
+                let entity = [("&amp;", '&'), ("&lt;", '<'), ("&gt;", '>'), ("&quot;", '"'), ("&#39;", '\''), ("&nbsp;", ' ')]
+                    .into_iter()
+                    .find(|(entity, _)| rest.starts_with(entity));
+                if let Some((entity, replacement)) = entity {
+                    result.push(replacement);
+                    for _ in 1..entity.len() {
+                        chars.next();
+                    }
+                } else {
+                    result.push(c);
+                }
+            },
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    result.push(escaped);
+                }
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(markdown: &str) -> Vec<String> {
+        Markdown::parse(markdown).blocks.iter().map(|block| match block {
+            Block::Heading(level, text) => format!("h{level} {text}"),
+            Block::Paragraph(text) => format!("p {text}"),
+            Block::ListItem(indent, text) => format!("li{indent} {text}"),
+            Block::Quote(text) => format!("> {text}"),
+            Block::Code(text) => format!("code {text}"),
+            Block::Image(_) => "image".to_string(),
+            Block::Rule => "rule".to_string(),
+        }).collect()
+    }
+
+    #[test]
+    fn blocks() {
+        let markdown = "# Title\n\nSome **bold** text\nacross lines\n\n- one\n  - nested\n1. first\n> quoted\n\n* * *\n```rust\nlet x = 1;\n```";
+        assert_eq!(summary(markdown), [
+            "h1 Title",
+            "p Some bold text across lines",
+            "li0 one",
+            "li1 nested",
+            "li0 first",
+            "> quoted",
+            "rule",
+            "code let x = 1;",
+        ]);
+    }
+
+    #[test]
+    fn headings() {
+        assert_eq!(summary("## Features ##\n\n#hashtag\n\n####### seven"), ["h2 Features", "p #hashtag", "p ####### seven"]);
+    }
+
+    #[test]
+    fn inline_formatting_is_dropped() {
+        assert_eq!(summary("See [the *wiki*](https://example.com) &amp; <b>more</b>"), ["p See the wiki & more"]);
+        assert_eq!(summary("a < b, `code` and \\*escaped\\*"), ["p a < b, code and *escaped*"]);
+    }
+
+    #[test]
+    fn images_get_their_own_blocks() {
+        assert_eq!(summary("![logo](https://example.com/logo.png \"Logo\") Intro"), ["image", "p Intro"]);
+        assert_eq!(summary("before\n<img src=\"https://example.com/a.png\">\nafter"), ["p before", "image", "p after"]);
+
+        let mut images = Vec::new();
+        assert_eq!(take_images("a ![x](one.png) b <img src='two.png'/> c", &mut images), "a  b  c");
+        assert_eq!(images, ["one.png", "two.png"]);
+    }
+
+    #[test]
+    fn unterminated_code_block() {
+        assert_eq!(summary("text\n```\ncode"), ["p text", "code code"]);
+    }
+}
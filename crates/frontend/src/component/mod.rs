@@ -1,10 +1,17 @@
 pub mod content_list;
+pub mod crash_bundle;
+pub mod crash_screen;
 pub mod error_alert;
+pub mod instance_card;
 pub mod instance_dropdown;
 pub mod instance_list;
+pub mod log_navigation;
+pub mod markdown;
 pub mod menu;
 pub mod named_dropdown;
 pub mod page_path;
 pub mod progress_bar;
 pub mod readonly_text_field;
+pub mod resource_usage_graph;
 pub mod search_helper;
+pub mod title_bar;
@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
-use gpui::SharedString;
 use gpui_component::breadcrumb::{Breadcrumb, BreadcrumbItem};
 use gpui::*;
 
-use crate::{entity::{DataEntities, instance::InstanceEntries}, ui::PageType};
+use crate::{entity::DataEntities, pages::instance::instance_page::InstanceSubpageType, ui::PageType};
 
 pub struct PagePath {
     pages: Arc<[PageType]>,
@@ -15,42 +14,52 @@ impl PagePath {
         Self { pages }
     }
 
+    /// Replaces the last page of the path, e.g. when the subpage of an instance page changes
+    pub fn set_current(&mut self, page: PageType) {
+        let mut pages = self.pages.to_vec();
+        if let Some(last) = pages.last_mut() {
+            *last = page;
+        }
+        self.pages = pages.into();
+    }
+
     pub fn create_breadcrumb(&self, data: &DataEntities, cx: &App) -> Breadcrumb {
         let mut breadcrumb = Breadcrumb::new().text_xl();
 
         let pages = self.pages.clone();
 
         for i in 0..pages.len() {
-            let title = match pages[i] {
-                PageType::Instances => "Instances".into(),
-                PageType::Syncing => "Syncing".into(),
-                PageType::Modrinth { installing_for, .. } => {
-                    if installing_for.is_some() {
-                        "Add from Modrinth".into()
-                    } else {
-                        "Modrinth".into()
-                    }
-                },
-                PageType::InstancePage(instance_id, _) => {
-                    InstanceEntries::find_title_by_id(&data.instances, instance_id, cx)
-                        .unwrap_or("<instance name>".into())
-                },
+            let is_current = i == pages.len()-1;
+
+            // Instance pages get a separate item for their subpage, e.g. "Instances > Survival > Mods"
+            let subpage = match pages[i] {
+                PageType::InstancePage(id, subpage) if subpage != InstanceSubpageType::Quickplay => Some((id, subpage)),
+                _ => None,
             };
 
-            let mut item = BreadcrumbItem::new(title);
+            let page = match pages[i] {
+                PageType::InstancePage(id, _) if subpage.is_some() => PageType::InstancePage(id, InstanceSubpageType::Quickplay),
+                page => page,
+            };
+            breadcrumb = breadcrumb.child(Self::item(page.title(data, cx), page, &pages[0..i], is_current && subpage.is_none()));
 
-            if i < pages.len()-1 {
-                let pages = pages.clone();
-                item = item.on_click(move |_, window, cx| {
-                    let page = pages[i];
-                    let rest = &pages[0..i];
-                    crate::root::switch_page(page, rest.into(), window, cx);
-                });
+            if let Some((id, subpage)) = subpage {
+                breadcrumb = breadcrumb.child(Self::item(subpage.name(), PageType::InstancePage(id, subpage), &pages[0..i], is_current));
             }
-
-            breadcrumb = breadcrumb.child(item);
         }
 
         breadcrumb
     }
+
+    fn item(title: SharedString, page: PageType, breadcrumbs: &[PageType], is_current: bool) -> BreadcrumbItem {
+        let item = BreadcrumbItem::new(title);
+        if is_current {
+            return item;
+        }
+
+        let breadcrumbs: Arc<[PageType]> = breadcrumbs.into();
+        item.on_click(move |_, window, cx| {
+            crate::root::switch_page(page, &breadcrumbs, window, cx);
+        })
+    }
 }
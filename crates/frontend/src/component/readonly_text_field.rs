@@ -1,13 +1,16 @@
-use std::{cell::RefCell, num::NonZeroUsize, ops::Range, rc::Rc, sync::Arc};
+use std::{cell::RefCell, collections::BTreeSet, num::NonZeroUsize, ops::Range, rc::Rc, sync::Arc};
 
 use ftree::FenwickTree;
 use gpui::{prelude::*, *};
 use gpui_component::{
     button::Button, h_flex, input::{Input, InputEvent, InputState}, scroll::{Scrollbar, ScrollbarHandle}, v_flex, ActiveTheme as _, Icon, IconName, Sizable
 };
+use chrono::Timelike;
 use lru::LruCache;
 use rustc_hash::FxBuildHasher;
 
+use crate::{component::log_navigation::{self, LogIdentity, PaintedItems, SECONDS_PER_DAY}, ts};
+
 struct CachedShapedLines {
     item_lines: LruCache<usize, WrappedLines, FxBuildHasher>,
 }
@@ -19,6 +22,8 @@ struct ReadonlyTextFieldItemState {
     total_line_count: usize,
     cached_shaped_lines: CachedShapedLines,
     search_query: SharedString,
+    /// Time of the last line with a timestamp, in seconds since midnight of the day the log started
+    last_time: u32,
 }
 
 pub struct ReadonlyTextField {
@@ -26,6 +31,10 @@ pub struct ReadonlyTextField {
     scroll_state: Rc<RefCell<GameOutputScrollState>>,
     pending: Vec<Arc<str>>,
     item_state: Option<ReadonlyTextFieldItemState>,
+    bookmarks: BTreeSet<usize>,
+    /// The log the bookmarks are saved for, they aren't saved if this is none
+    bookmarked_log: Option<LogIdentity>,
+    painted_items: PaintedItems,
 }
 
 impl Default for ReadonlyTextField {
@@ -49,12 +58,26 @@ impl Default for ReadonlyTextField {
                     item_lines: LruCache::with_hasher(NonZeroUsize::new(256).unwrap(), FxBuildHasher),
                 },
                 search_query: SharedString::new_static(""),
+                last_time: 0,
             }),
+            bookmarks: BTreeSet::new(),
+            bookmarked_log: None,
+            painted_items: PaintedItems::default(),
         }
     }
 }
 
 impl ReadonlyTextField {
+    /// Restores the bookmarks saved for the log and saves them from now on, bookmarks added before are kept
+    pub fn set_bookmarked_log(&mut self, log: LogIdentity, cx: &mut App) {
+        let added = !self.bookmarks.is_empty();
+        self.bookmarks.extend(log.load_bookmarks(cx));
+        if added {
+            log.save_bookmarks(self.bookmarks.iter().copied().collect(), cx);
+        }
+        self.bookmarked_log = Some(log);
+    }
+
     pub fn add(&mut self, line: Arc<str>) {
         self.pending.push(line);
     }
@@ -71,6 +94,15 @@ impl ReadonlyTextField {
             return;
         };
         for line in self.pending.drain(..) {
+            // Lines without a timestamp, like stack traces, use the time of the line before them
+            let time = match log_navigation::parse_log_time(&line) {
+                Some(time) => {
+                    item_state.last_time = log_navigation::continue_log_time(time, item_state.last_time);
+                    item_state.last_time
+                },
+                None => item_state.last_time,
+            };
+
             let mut highlighted_text = None;
 
             if !item_state.search_query.is_empty() {
@@ -82,6 +114,7 @@ impl ReadonlyTextField {
                     item_state.item_sizes.push(0);
                     item_state.items.push(TextFieldLine {
                         line: line.clone(),
+                        time,
                         index: item_state.items.len(),
                         backup_total_lines_while_skipped: 1,
                         total_lines: 0,
@@ -96,6 +129,7 @@ impl ReadonlyTextField {
             item_state.total_line_count += 1;
             item_state.items.push(TextFieldLine {
                 line: line.clone(),
+                time,
                 index: item_state.items.len(),
                 backup_total_lines_while_skipped: 1,
                 total_lines: 1,
@@ -104,6 +138,63 @@ impl ReadonlyTextField {
             });
         }
     }
+
+    /// Toggles the bookmark of the line at the position if it's inside the gutter
+    fn toggle_bookmark_at(&mut self, position: Point<Pixels>, cx: &mut App) -> bool {
+        let Some(index) = self.painted_items.item_at(position) else {
+            return false;
+        };
+        if !self.bookmarks.remove(&index) {
+            self.bookmarks.insert(index);
+        }
+
+        if let Some(log) = &self.bookmarked_log {
+            log.save_bookmarks(self.bookmarks.iter().copied().collect(), cx);
+        }
+        true
+    }
+
+    /// Scrolls to the closest bookmarked line after (or before) the line at the top of the view
+    fn scroll_to_bookmark(&mut self, forwards: bool) -> bool {
+        let Some(item_state) = &self.item_state else {
+            return false;
+        };
+        let is_bookmark = |index| self.bookmarks.contains(&index) && !item_state.items[index].skip;
+        let Some(index) = log_navigation::find_bookmark(item_state.last_scrolled_item, item_state.items.len(), forwards, is_bookmark) else {
+            return false;
+        };
+        self.scroll_to_item(index);
+        true
+    }
+
+    /// Scrolls to the first line logged at or after the time of day, starting from the day the log started
+    fn jump_to_time(&mut self, time: chrono::NaiveTime) -> bool {
+        let Some(item_state) = &self.item_state else {
+            return false;
+        };
+        let Some(first) = item_state.items.first() else {
+            return false;
+        };
+
+        let mut target = time.num_seconds_from_midnight();
+        if target < first.time {
+            target += SECONDS_PER_DAY;
+        }
+
+        let index = item_state.items.partition_point(|item| item.time < target);
+        self.scroll_to_item(index.min(item_state.items.len() - 1));
+        true
+    }
+
+    fn scroll_to_item(&self, index: usize) {
+        let Some(item_state) = &self.item_state else {
+            return;
+        };
+        let lines_above = item_state.item_sizes.prefix_sum(index, 0);
+        let mut scroll_state = self.scroll_state.borrow_mut();
+        let offset = -(lines_above * scroll_state.line_height);
+        scroll_state.set_offset(offset);
+    }
 }
 
 pub struct ReadonlyTextFieldComponent {
@@ -113,6 +204,8 @@ pub struct ReadonlyTextFieldComponent {
 
 struct TextFieldLine {
     line: Arc<str>,
+    /// Seconds since midnight of the day the log started
+    time: u32,
     index: usize,
     backup_total_lines_while_skipped: usize,
     total_lines: usize,
@@ -319,6 +412,11 @@ impl Element for ReadonlyTextFieldComponent {
                         let font_size = text_style.font_size.to_pixels(window.rem_size());
                         let line_height = font_size * 1.25;
 
+                        // Clicking left of the text bookmarks the line
+                        bounds.origin.x += font_size;
+                        bounds.size.width -= font_size;
+                        inner.painted_items.set_gutter(visible_bounds.origin.x..bounds.origin.x);
+
                         let text_width = bounds.size.width;
                         let wrap_width = text_width.max(font_size * 30);
 
@@ -327,6 +425,7 @@ impl Element for ReadonlyTextFieldComponent {
                         let scroll_render_info = inner.update_scrolling(line_height, wrap_width,
                             font_size, &text_style, &mut line_wrapper, window.text_system());
 
+                        inner.painted_items.clear();
                         if let Some(item_state) = inner.item_state.as_mut() && !item_state.items.is_empty() {
                             if scroll_render_info.reverse {
                                 paint_lines::<true>(
@@ -343,6 +442,8 @@ impl Element for ReadonlyTextFieldComponent {
                                     &mut item_state.total_line_count,
                                     &mut line_wrapper,
                                     &mut item_state.cached_shaped_lines,
+                                    &inner.bookmarks,
+                                    &mut inner.painted_items,
                                     window,
                                     cx,
                                 );
@@ -361,6 +462,8 @@ impl Element for ReadonlyTextFieldComponent {
                                     &mut item_state.total_line_count,
                                     &mut line_wrapper,
                                     &mut item_state.cached_shaped_lines,
+                                    &inner.bookmarks,
+                                    &mut inner.painted_items,
                                     window,
                                     cx,
                                 );
@@ -596,6 +699,8 @@ fn paint_lines<'a, const REVERSE: bool>(
     total_line_count: &mut usize,
     line_wrapper: &mut LineWrapperHandle,
     cache: &mut CachedShapedLines,
+    bookmarks: &BTreeSet<usize>,
+    painted_items: &mut PaintedItems,
     window: &mut Window,
     cx: &mut App,
 ) {
@@ -624,6 +729,14 @@ fn paint_lines<'a, const REVERSE: bool>(
 
         let line_count = lines.len().max(1);
 
+        let item_top = if REVERSE {
+            text_origin.y - (line_count - 1) * line_height
+        } else {
+            text_origin.y
+        };
+        painted_items.paint(item.index, point(visible_bounds.origin.x, item_top), line_count * line_height,
+            bookmarks.contains(&item.index), window, cx);
+
         let mut line_origin = text_origin;
         if REVERSE {
             for shaped in lines.iter().rev() {
@@ -676,9 +789,11 @@ pub struct ReadonlyTextFieldWithControls {
     scroll_handler: ScrollHandler,
     text_field: Entity<ReadonlyTextField>,
     search_state: Entity<InputState>,
+    jump_state: Entity<InputState>,
     extra: Box<dyn Fn(Div) -> Div>,
     _search_task: Task<()>,
     _search_input_subscription: Subscription,
+    _jump_input_subscription: Subscription,
 }
 
 #[derive(Clone)]
@@ -791,17 +906,37 @@ impl ReadonlyTextFieldWithControls {
     ) -> Self {
         let scroll_state = Rc::clone(&text_field.read(cx).scroll_state);
 
-        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder("Search").clean_on_escape());
+        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.search")).clean_on_escape());
 
         let _search_input_subscription = cx.subscribe_in(&search_state, window, Self::on_search_input_event);
 
+        let jump_state = log_navigation::create_jump_input(window, cx);
+        let _jump_input_subscription = cx.subscribe_in(&jump_state, window, Self::on_jump_input_event);
+
         Self {
             scroll_handler: ScrollHandler { state: scroll_state },
             text_field,
             search_state,
+            jump_state,
             extra,
             _search_task: Task::ready(()),
             _search_input_subscription,
+            _jump_input_subscription,
+        }
+    }
+
+    fn on_jump_input_event(
+        &mut self,
+        state: &Entity<InputState>,
+        event: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(time) = log_navigation::jump_target(state, event, cx) else {
+            return;
+        };
+        if self.text_field.update(cx, |text_field, _| text_field.jump_to_time(time)) {
+            cx.notify();
         }
     }
 
@@ -902,12 +1037,17 @@ impl Render for ReadonlyTextFieldWithControls {
             .flex_1()
             .gap_4()
             .child(search)
-            .child(Button::new("top").label("Go to Top").on_click(cx.listener(|root, _, _, cx| {
+            .child(log_navigation::controls(&self.jump_state, cx.listener(|root, forwards: &bool, _, cx| {
+                if root.text_field.update(cx, |text_field, _| text_field.scroll_to_bookmark(*forwards)) {
+                    cx.notify();
+                }
+            })))
+            .child(Button::new("top").label(ts!("logs.go_to_top")).on_click(cx.listener(|root, _, _, cx| {
                 let mut state = root.scroll_handler.state.borrow_mut();
                 state.scrolling = GameOutputScrolling::Top { offset: Pixels::ZERO };
                 cx.notify();
             })))
-            .child(Button::new("bottom").label("Go to Bottom").on_click(cx.listener(|root, _, _, cx| {
+            .child(Button::new("bottom").label(ts!("logs.go_to_bottom")).on_click(cx.listener(|root, _, _, cx| {
                 let mut state = root.scroll_handler.state.borrow_mut();
                 state.scrolling = GameOutputScrolling::Bottom;
                 cx.notify();
@@ -929,7 +1069,11 @@ impl Render for ReadonlyTextFieldWithControls {
                     .child(ReadonlyTextFieldComponent {
                         interactivity: Interactivity::new(),
                         text_field: self.text_field.clone(),
-                    })
+                    }.on_mouse_down(MouseButton::Left, cx.listener(|root, event: &MouseDownEvent, _, cx| {
+                        if root.text_field.update(cx, |text_field, cx| text_field.toggle_bookmark_at(event.position, cx)) {
+                            cx.notify();
+                        }
+                    })))
                     .child(
                         div()
                             .w_3()
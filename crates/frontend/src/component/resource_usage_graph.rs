@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+
+use bridge::instance::InstanceResourceUsage;
+use gpui::{prelude::*, *};
+use gpui_component::{h_flex, v_flex, ActiveTheme};
+
+/// Number of samples shown, the backend samples once a second
+pub const RESOURCE_USAGE_HISTORY: usize = 60;
+
+/// Memory use above this share of the configured maximum is shown as a warning
+const HIGH_MEMORY: f32 = 0.9;
+
+/// Live memory graph with the current CPU and memory use of a running instance
+#[derive(IntoElement)]
+pub struct ResourceUsageGraph {
+    history: VecDeque<InstanceResourceUsage>,
+    /// The -Xmx of the instance, when its memory is configured
+    max_memory_bytes: Option<u64>,
+}
+
+impl ResourceUsageGraph {
+    pub fn new(history: VecDeque<InstanceResourceUsage>, max_memory_mib: Option<u32>) -> Self {
+        Self {
+            history,
+            max_memory_bytes: max_memory_mib.map(|mib| mib as u64 * 1024 * 1024),
+        }
+    }
+}
+
+fn format_gib(bytes: u64) -> String {
+    format!("{:.1}", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+impl RenderOnce for ResourceUsageGraph {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let Some(current) = self.history.back().copied() else {
+            return h_flex();
+        };
+
+        // Without a configured maximum, scale to the highest sample so the graph still shows the trend
+        let peak = self.history.iter().map(|usage| usage.memory_bytes).max().unwrap_or(0);
+        let scale = self.max_memory_bytes.unwrap_or(peak).max(peak).max(1);
+
+        let high = self.max_memory_bytes.is_some_and(|max| current.memory_bytes as f32 >= max as f32 * HIGH_MEMORY);
+        let bar_color = if high { theme.warning } else { theme.primary };
+
+        let padding = RESOURCE_USAGE_HISTORY.saturating_sub(self.history.len());
+        let bars = std::iter::repeat_n(0.0, padding)
+            .chain(self.history.iter().map(|usage| usage.memory_bytes as f32 / scale as f32))
+            .map(|amount| div().w(px(2.0)).h(relative(amount.clamp(0.0, 1.0))).bg(bar_color));
+
+        let memory = match self.max_memory_bytes {
+            Some(max) => SharedString::new(rust_i18n::t!("resource_usage.memory_of_max", current = format_gib(current.memory_bytes), max = format_gib(max))),
+            None => SharedString::new(rust_i18n::t!("resource_usage.memory", current = format_gib(current.memory_bytes))),
+        };
+
+        h_flex()
+            .gap_2()
+            .child(h_flex()
+                .items_end()
+                .h_8()
+                .px_0p5()
+                .border_1()
+                .border_color(theme.border)
+                .rounded(theme.radius)
+                .children(bars))
+            .child(v_flex()
+                .text_xs()
+                .text_color(if high { theme.warning } else { theme.muted_foreground })
+                .child(SharedString::new(rust_i18n::t!("resource_usage.cpu", percent = format!("{:.0}", current.cpu_percent))))
+                .child(memory))
+    }
+}
@@ -0,0 +1,113 @@
+use bridge::{handle::BackendHandle, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, menu::{DropdownMenu, PopupMenuItem}, Disableable, IconName, Sizable, StyledExt
+};
+
+use crate::{entity::{account::AccountEntries, launcher_lock::LauncherLockState, DataEntities}, png_render_cache, root, ts, ui::{LauncherUI, PageChangedEvent, PageType}};
+
+/// Titlebar shown instead of the one of the operating system, with back/forward navigation, the title of the
+/// current page and an account switcher
+pub struct LauncherTitleBar {
+    ui: Entity<LauncherUI>,
+    accounts: Entity<AccountEntries>,
+    launcher_lock: Entity<LauncherLockState>,
+    backend_handle: BackendHandle,
+    _page_changed_subscription: Subscription,
+    _accounts_subscription: Subscription,
+    _launcher_lock_subscription: Subscription,
+}
+
+impl LauncherTitleBar {
+    pub fn new(ui: Entity<LauncherUI>, data: &DataEntities, cx: &mut Context<Self>) -> Self {
+        let _page_changed_subscription = cx.subscribe(&ui, |_, _, _: &PageChangedEvent, cx| cx.notify());
+        let _accounts_subscription = cx.observe(&data.accounts, |_, _, cx| cx.notify());
+        let _launcher_lock_subscription = cx.observe(&data.launcher_lock, |_, _, cx| cx.notify());
+
+        Self {
+            ui,
+            accounts: data.accounts.clone(),
+            launcher_lock: data.launcher_lock.clone(),
+            backend_handle: data.backend_handle.clone(),
+            _page_changed_subscription,
+            _accounts_subscription,
+            _launcher_lock_subscription,
+        }
+    }
+}
+
+impl Render for LauncherTitleBar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.launcher_lock.read(cx).locked {
+            return gpui_component::TitleBar::new().child(ts!("common.app_name"));
+        }
+
+        let ui = self.ui.read(cx);
+        let navigation = h_flex()
+            .gap_1()
+            .child(Button::new("navigate-back")
+                .ghost()
+                .small()
+                .icon(IconName::ArrowLeft)
+                .disabled(!ui.can_navigate_back())
+                .on_click(cx.listener(|title_bar, _, window, cx| {
+                    title_bar.ui.update(cx, |ui, cx| ui.navigate_back(window, cx));
+                })))
+            .child(Button::new("navigate-forward")
+                .ghost()
+                .small()
+                .icon(IconName::ArrowRight)
+                .disabled(!ui.can_navigate_forward())
+                .on_click(cx.listener(|title_bar, _, window, cx| {
+                    title_bar.ui.update(cx, |ui, cx| ui.navigate_forward(window, cx));
+                })))
+            .child(div().pl_1().font_medium().child(ui.page_title(cx)));
+
+        let accounts = self.accounts.read(cx);
+        let all_accounts = accounts.accounts.clone();
+        let selected_uuid = accounts.selected_account_uuid;
+        let (head, account_name) = match &accounts.selected_account {
+            Some(account) => (account.head.clone(), SharedString::new(account.username.clone())),
+            None => (None, ts!("accounts.no_account")),
+        };
+        let account_head = if let Some(head) = head {
+            let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
+            png_render_cache::render_with_transform(head, resize, cx)
+        } else {
+            gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
+        };
+
+        let account_switcher = Button::new("account-switcher")
+            .ghost()
+            .small()
+            .child(h_flex().gap_2().child(account_head.size_5()).child(account_name))
+            .dropdown_menu({
+                let backend_handle = self.backend_handle.clone();
+                move |mut menu, _, _| {
+                    for account in all_accounts.iter() {
+                        let uuid = account.uuid;
+                        let backend_handle = backend_handle.clone();
+                        menu = menu.item(PopupMenuItem::new(SharedString::new(account.username.clone()))
+                            .checked(selected_uuid == Some(uuid))
+                            .on_click(move |_, _, _| {
+                                backend_handle.send(MessageToBackend::SelectAccount { uuid });
+                            }));
+                    }
+                    if !all_accounts.is_empty() {
+                        menu = menu.separator();
+                    }
+                    menu.item(PopupMenuItem::new(ts!("accounts.manage")).on_click(|_, window, cx| {
+                        root::switch_page(PageType::Accounts, &[], window, cx);
+                    }))
+                }
+            });
+
+        gpui_component::TitleBar::new()
+            .child(h_flex()
+                .w_full()
+                .pr_2()
+                .justify_between()
+                .child(navigation)
+                .child(account_switcher))
+    }
+}
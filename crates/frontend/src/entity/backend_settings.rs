@@ -0,0 +1,16 @@
+use gpui::{App, Entity};
+use schema::backend_config::BackendSettings;
+
+#[derive(Default)]
+pub struct BackendSettingsState {
+    pub settings: BackendSettings,
+}
+
+impl BackendSettingsState {
+    pub fn set(entity: &Entity<Self>, settings: BackendSettings, cx: &mut App) {
+        entity.update(cx, |state, cx| {
+            state.settings = settings;
+            cx.notify();
+        });
+    }
+}
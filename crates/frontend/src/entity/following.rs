@@ -0,0 +1,16 @@
+use gpui::{App, Entity};
+use schema::following::FollowedProjects;
+
+#[derive(Default)]
+pub struct FollowedProjectsState {
+    pub followed: FollowedProjects,
+}
+
+impl FollowedProjectsState {
+    pub fn set(entity: &Entity<Self>, followed: FollowedProjects, cx: &mut App) {
+        entity.update(cx, |state, cx| {
+            state.followed = followed;
+            cx.notify();
+        });
+    }
+}
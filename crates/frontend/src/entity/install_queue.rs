@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use bridge::install::InstallQueueEntry;
+use gpui::{App, Entity};
+
+#[derive(Default)]
+pub struct InstallQueueState {
+    pub entries: Arc<[InstallQueueEntry]>,
+}
+
+impl InstallQueueState {
+    pub fn set(entity: &Entity<Self>, entries: Arc<[InstallQueueEntry]>, cx: &mut App) {
+        entity.update(cx, |state, cx| {
+            state.entries = entries;
+            cx.notify();
+        });
+    }
+
+    /// Installs that still have files waiting to be downloaded or retried
+    pub fn unfinished_count(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.finished).count()
+    }
+}
@@ -1,13 +1,15 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::VecDeque, path::Path, sync::Arc};
 
 use bridge::{
-    instance::{InstanceID, InstanceContentSummary, InstanceServerSummary, InstanceStatus, InstanceWorldSummary},
-    message::AtomicBridgeDataLoadState,
+    instance::{InstanceID, InstanceContentSummary, InstanceResourceUsage, InstanceServerSummary, InstanceStatus, InstanceWorldSummary},
+    message::{AtomicBridgeDataLoadState, BridgeDataLoadState},
 };
 use gpui::{prelude::*, *};
 use gpui_component::select::SelectItem;
 use indexmap::IndexMap;
-use schema::{instance::InstanceConfiguration, loader::Loader};
+use schema::{content::ContentSource, instance::InstanceConfiguration, loader::{Loader, LoaderCompanion}};
+
+use crate::component::resource_usage_graph::RESOURCE_USAGE_HISTORY;
 
 pub struct InstanceEntries {
     pub entries: IndexMap<InstanceID, Entity<InstanceEntry>>,
@@ -36,12 +38,16 @@ impl InstanceEntries {
                 status: InstanceStatus::NotRunning,
                 worlds_state,
                 worlds: cx.new(|_| [].into()),
+                worlds_total: cx.new(|_| None),
                 servers_state,
                 servers: cx.new(|_| [].into()),
                 mods_state,
                 mods: cx.new(|_| [].into()),
                 resource_packs_state,
                 resource_packs: cx.new(|_| [].into()),
+                resource_usage: cx.new(|_| VecDeque::new()),
+                file_watching: true,
+                network_drive: false,
             };
             instance.title = instance.create_title().into();
 
@@ -96,6 +102,9 @@ impl InstanceEntries {
                     instance.name = name.clone();
                     instance.dot_minecraft_folder = dot_minecraft_folder.clone();
                     instance.configuration = configuration.clone();
+                    if instance.status == InstanceStatus::NotRunning && status != InstanceStatus::NotRunning {
+                        instance.resource_usage.update(cx, |history, _| history.clear());
+                    }
                     instance.status = status;
                     instance.title = instance.create_title().into();
                     cx.notify();
@@ -120,7 +129,35 @@ impl InstanceEntries {
                     instance.worlds.update(cx, |existing_worlds, cx| {
                         *existing_worlds = worlds;
                         cx.notify();
-                    })
+                    });
+                    instance.worlds_total.update(cx, |existing_total, cx| {
+                        if existing_total.take().is_some() {
+                            cx.notify();
+                        }
+                    });
+                });
+            }
+        });
+    }
+
+    pub fn set_worlds_partial(
+        entity: &Entity<Self>,
+        id: InstanceID,
+        worlds: Arc<[InstanceWorldSummary]>,
+        total: usize,
+        cx: &mut App,
+    ) {
+        entity.update(cx, |entries, cx| {
+            if let Some(instance) = entries.entries.get_mut(&id) {
+                instance.update(cx, |instance, cx| {
+                    instance.worlds.update(cx, |existing_worlds, cx| {
+                        *existing_worlds = worlds;
+                        cx.notify();
+                    });
+                    instance.worlds_total.update(cx, |existing_total, cx| {
+                        *existing_total = Some(total);
+                        cx.notify();
+                    });
                 });
             }
         });
@@ -170,6 +207,30 @@ impl InstanceEntries {
         });
     }
 
+    pub fn push_resource_usage(entity: &Entity<Self>, id: InstanceID, usage: InstanceResourceUsage, cx: &mut App) {
+        let Some(instance) = entity.read(cx).entries.get(&id) else {
+            return;
+        };
+        instance.read(cx).resource_usage.clone().update(cx, |history, cx| {
+            if history.len() >= RESOURCE_USAGE_HISTORY {
+                history.pop_front();
+            }
+            history.push_back(usage);
+            cx.notify();
+        });
+    }
+
+    pub fn set_file_watching(entity: &Entity<Self>, id: InstanceID, watching: bool, network_drive: bool, cx: &mut App) {
+        let Some(instance) = entity.read(cx).entries.get(&id) else {
+            return;
+        };
+        instance.clone().update(cx, |instance, cx| {
+            instance.file_watching = watching;
+            instance.network_drive = network_drive;
+            cx.notify();
+        });
+    }
+
     pub fn move_to_top(entity: &Entity<Self>, id: InstanceID, cx: &mut App) {
         entity.update(cx, |entries, cx| {
             if let Some(index) = entries.entries.get_index_of(&id) {
@@ -193,12 +254,19 @@ pub struct InstanceEntry {
     pub status: InstanceStatus,
     pub worlds_state: Arc<AtomicBridgeDataLoadState>,
     pub worlds: Entity<Arc<[InstanceWorldSummary]>>,
+    /// Number of worlds in the saves folder while `worlds` only contains some of them
+    pub worlds_total: Entity<Option<usize>>,
     pub servers_state: Arc<AtomicBridgeDataLoadState>,
     pub servers: Entity<Arc<[InstanceServerSummary]>>,
     pub mods_state: Arc<AtomicBridgeDataLoadState>,
     pub mods: Entity<Arc<[InstanceContentSummary]>>,
     pub resource_packs_state: Arc<AtomicBridgeDataLoadState>,
     pub resource_packs: Entity<Arc<[InstanceContentSummary]>>,
+    /// Samples from the running game process, oldest first. Cleared when the instance is launched again
+    pub resource_usage: Entity<VecDeque<InstanceResourceUsage>>,
+    /// Whether the backend watches the folders of the instance, otherwise changes are only noticed by polling
+    pub file_watching: bool,
+    pub network_drive: bool,
 }
 
 impl SelectItem for InstanceEntry {
@@ -224,6 +292,18 @@ impl InstanceEntry {
         self.title.clone()
     }
 
+    /// Whether the companion is in the mods folder, or none if the mods haven't been loaded yet
+    pub fn has_companion(&self, companion: LoaderCompanion, cx: &App) -> Option<bool> {
+        let state = self.mods_state.load(std::sync::atomic::Ordering::SeqCst);
+        if !matches!(state, BridgeDataLoadState::Loaded | BridgeDataLoadState::LoadedDirty) {
+            return None;
+        }
+        Some(self.mods.read(cx).iter().any(|summary| {
+            summary.content_summary.id.as_deref() == Some(companion.mod_id)
+                || matches!(&summary.content_source, ContentSource::ModrinthProject { project } if &**project == companion.modrinth_project_id)
+        }))
+    }
+
     fn create_title(&self) -> String {
         if self.name == &*self.configuration.minecraft_version {
             if self.configuration.loader == Loader::Vanilla {
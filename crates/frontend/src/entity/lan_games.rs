@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use bridge::message::LanGame;
+use gpui::{App, Entity};
+
+#[derive(Default)]
+pub struct LanGamesState {
+    pub games: Arc<[LanGame]>,
+}
+
+impl LanGamesState {
+    pub fn set(entity: &Entity<Self>, games: Arc<[LanGame]>, cx: &mut App) {
+        entity.update(cx, |state, cx| {
+            state.games = games;
+            cx.notify();
+        });
+    }
+}
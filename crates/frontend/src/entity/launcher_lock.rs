@@ -0,0 +1,25 @@
+use gpui::{App, Entity};
+
+#[derive(Default)]
+pub struct LauncherLockState {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_lock_minutes: Option<u32>,
+}
+
+impl LauncherLockState {
+    pub fn set(
+        entity: &Entity<Self>,
+        enabled: bool,
+        locked: bool,
+        idle_lock_minutes: Option<u32>,
+        cx: &mut App,
+    ) {
+        entity.update(cx, |state, cx| {
+            state.enabled = enabled;
+            state.locked = locked;
+            state.idle_lock_minutes = idle_lock_minutes;
+            cx.notify();
+        });
+    }
+}
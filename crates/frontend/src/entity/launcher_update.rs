@@ -0,0 +1,16 @@
+use bridge::message::LauncherUpdate;
+use gpui::{App, Entity};
+
+#[derive(Default)]
+pub struct LauncherUpdateState {
+    pub update: Option<LauncherUpdate>,
+}
+
+impl LauncherUpdateState {
+    pub fn set(entity: &Entity<Self>, update: LauncherUpdate, cx: &mut App) {
+        entity.update(cx, |state, cx| {
+            state.update = Some(update);
+            cx.notify();
+        });
+    }
+}
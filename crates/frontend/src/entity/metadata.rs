@@ -1,8 +1,10 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
 
 use bridge::{handle::BackendHandle, keep_alive::KeepAliveHandle, message::MessageToBackend, meta::{MetadataRequest, MetadataResult}};
 use gpui::{prelude::*, *};
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersionsResult, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthCategoryTags, ModrinthProject, ModrinthProjectMembers, ModrinthProjectVersionsResult, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
+
+use crate::ts;
 
 #[derive(Debug)]
 pub enum FrontendMetadataState {
@@ -38,6 +40,7 @@ pub enum TypelessFrontendMetadataResult {
 pub struct FrontendMetadata {
     pub data: HashMap<MetadataRequest, Entity<FrontendMetadataState>>,
     pub backend_handle: BackendHandle,
+    rate_limited_until: HashMap<Arc<str>, Instant>,
 }
 
 impl FrontendMetadata {
@@ -45,6 +48,7 @@ impl FrontendMetadata {
         Self {
             data: HashMap::new(),
             backend_handle,
+            rate_limited_until: HashMap::new(),
         }
     }
 
@@ -92,6 +96,29 @@ impl FrontendMetadata {
         })
     }
 
+    /// Forgets a request that's still loading so that a result arriving later is ignored. Only for requests that
+    /// aren't shared, anything else observing the state wouldn't get the result. The backend still finishes the
+    /// download and caches it
+    pub fn cancel(entity: &Entity<Self>, request: &MetadataRequest, cx: &mut App) {
+        entity.update(cx, |this, cx| {
+            if let Some(existing) = this.data.get(request) && matches!(existing.read(cx), FrontendMetadataState::Loading) {
+                this.data.remove(request);
+            }
+        });
+    }
+
+    pub fn set_rate_limited(entity: &Entity<Self>, host: Arc<str>, retry_in_secs: u64, cx: &mut App) {
+        entity.update(cx, |this, cx| {
+            this.rate_limited_until.insert(host, Instant::now() + Duration::from_secs(retry_in_secs));
+            cx.notify();
+        });
+    }
+
+    /// Time left until the backend retries requests to the host, if it's currently rate limited
+    pub fn rate_limit_remaining(&self, host: &str) -> Option<Duration> {
+        self.rate_limited_until.get(host)?.checked_duration_since(Instant::now())
+    }
+
     pub fn set(
         entity: &Entity<Self>,
         request: MetadataRequest,
@@ -100,7 +127,11 @@ impl FrontendMetadata {
         cx: &mut App,
     ) {
         entity.update(cx, |this, cx| {
-            this.data.get(&request).unwrap().update(cx, |value, cx| {
+            let Some(state) = this.data.get(&request) else {
+                // The request was cancelled
+                return;
+            };
+            state.update(cx, |value, cx| {
                 *value = FrontendMetadataState::Loaded { result, keep_alive };
                 cx.notify();
             });
@@ -121,7 +152,7 @@ macro_rules! define_as_metadata_result {
                     FrontendMetadataState::Loaded { result, .. } => {
                         match result {
                             Ok(MetadataResult::$t(result)) => FrontendMetadataResult::Loaded(&*result),
-                            Ok(_) => FrontendMetadataResult::Error(SharedString::new_static("Wrong metadata type! Pandora bug!")),
+                            Ok(_) => FrontendMetadataResult::Error(ts!("common.wrong_metadata_type")),
                             Err(error) => FrontendMetadataResult::Error(SharedString::new(error.clone())),
                         }
                     },
@@ -134,6 +165,9 @@ macro_rules! define_as_metadata_result {
 define_as_metadata_result!(MinecraftVersionManifest);
 define_as_metadata_result!(ModrinthSearchResult);
 define_as_metadata_result!(ModrinthProjectVersionsResult);
+define_as_metadata_result!(ModrinthProject);
+define_as_metadata_result!(ModrinthProjectMembers);
 define_as_metadata_result!(FabricLoaderManifest);
 define_as_metadata_result!(ForgeMavenManifest);
 define_as_metadata_result!(NeoforgeMavenManifest);
+define_as_metadata_result!(ModrinthCategoryTags);
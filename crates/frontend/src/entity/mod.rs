@@ -1,28 +1,51 @@
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::{atomic::AtomicBool, Arc}};
 
-use bridge::handle::BackendHandle;
+use bridge::{handle::BackendHandle, modal_action::BackgroundTasks};
 use gpui::Entity;
 use parking_lot::RwLock;
 
-use crate::entity::{
-    account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata
-};
+use crate::{component::crash_bundle::WriteCrashBundle, entity::{
+    account::AccountEntries, backend_settings::BackendSettingsState, following::FollowedProjectsState, install_queue::InstallQueueState, instance::InstanceEntries, lan_games::LanGamesState, launcher_lock::LauncherLockState, launcher_update::LauncherUpdateState, metadata::FrontendMetadata, notifications::NotificationCenter, search_index::SearchIndex
+}};
 
 pub mod account;
+pub mod backend_settings;
+pub mod following;
+pub mod install_queue;
 pub mod instance;
+pub mod lan_games;
+pub mod launcher_lock;
+pub mod launcher_update;
 pub mod metadata;
+pub mod notifications;
+pub mod search_index;
 
 #[derive(Clone)]
 pub struct DataEntities {
     pub instances: Entity<InstanceEntries>,
     pub metadata: Entity<FrontendMetadata>,
     pub accounts: Entity<AccountEntries>,
+    pub launcher_lock: Entity<LauncherLockState>,
+    pub launcher_update: Entity<LauncherUpdateState>,
+    pub backend_settings: Entity<BackendSettingsState>,
+    pub followed_projects: Entity<FollowedProjectsState>,
+    pub install_queue: Entity<InstallQueueState>,
+    pub lan_games: Entity<LanGamesState>,
+    pub notifications: Entity<NotificationCenter>,
+    pub search_index: Entity<SearchIndex>,
     pub backend_handle: BackendHandle,
+    /// Work the backend is doing outside of modals, read whenever a tracker asks for a refresh
+    pub background_tasks: BackgroundTasks,
     pub theme_folder: Arc<Path>,
+    /// Running as a portable install, with all data next to the executable
+    pub portable: bool,
     pub panic_messages: Arc<PanicMessages>,
 }
 
 pub struct PanicMessages {
     pub panic_message: Arc<RwLock<Option<String>>>,
     pub deadlock_message: Arc<RwLock<Option<String>>>,
+    pub restart_requested: Arc<AtomicBool>,
+    pub launcher_log: Arc<Path>,
+    pub write_crash_bundle: WriteCrashBundle,
 }
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use bridge::message::BridgeNotificationType;
+use chrono::{DateTime, Local};
+use gpui::{App, Entity, Global, SharedString, Window};
+use gpui_component::{notification::{Notification, NotificationType}, WindowExt};
+
+/// Notifications other than errors that are kept, the oldest are dropped first
+const MAX_RECENT: usize = 50;
+
+pub struct NotificationEntry {
+    pub id: u64,
+    pub notification_type: BridgeNotificationType,
+    pub message: SharedString,
+    pub time: DateTime<Local>,
+}
+
+/// Notifications from the backend and the frontend, kept after their popup has disappeared. Errors are only removed by
+/// dismissing them
+#[derive(Default)]
+pub struct NotificationCenter {
+    pub entries: VecDeque<NotificationEntry>,
+    /// Only entries of this type are listed, all are listed if none
+    pub filter: Option<BridgeNotificationType>,
+    pub unseen_errors: bool,
+    next_id: u64,
+}
+
+struct NotificationCenterGlobal(Entity<NotificationCenter>);
+
+impl Global for NotificationCenterGlobal {}
+
+impl NotificationCenter {
+    /// Lets notifications shown by the frontend be kept, see [`push_notification`]
+    pub fn set_global(entity: Entity<Self>, cx: &mut App) {
+        cx.set_global(NotificationCenterGlobal(entity));
+    }
+
+    /// Keeps a notification that was shown by the frontend
+    pub fn push_global(notification_type: NotificationType, message: SharedString, cx: &mut App) {
+        let Some(global) = cx.try_global::<NotificationCenterGlobal>() else {
+            return;
+        };
+        let entity = global.0.clone();
+        let notification_type = match notification_type {
+            NotificationType::Success => BridgeNotificationType::Success,
+            NotificationType::Info => BridgeNotificationType::Info,
+            NotificationType::Error => BridgeNotificationType::Error,
+            NotificationType::Warning => BridgeNotificationType::Warning,
+        };
+        Self::push(&entity, notification_type, message, cx);
+    }
+
+    pub fn push(entity: &Entity<Self>, notification_type: BridgeNotificationType, message: SharedString, cx: &mut App) {
+        entity.update(cx, |center, cx| {
+            center.entries.push_back(NotificationEntry {
+                id: center.next_id,
+                notification_type,
+                message,
+                time: Local::now(),
+            });
+            center.next_id += 1;
+
+            let recent = center.entries.iter().filter(|entry| entry.notification_type != BridgeNotificationType::Error).count();
+            if recent > MAX_RECENT
+                && let Some(oldest) = center.entries.iter().position(|entry| entry.notification_type != BridgeNotificationType::Error)
+            {
+                center.entries.remove(oldest);
+            }
+
+            if notification_type == BridgeNotificationType::Error {
+                center.unseen_errors = true;
+            }
+            cx.notify();
+        });
+    }
+
+    pub fn dismiss(entity: &Entity<Self>, id: u64, cx: &mut App) {
+        entity.update(cx, |center, cx| {
+            center.entries.retain(|entry| entry.id != id);
+            cx.notify();
+        });
+    }
+
+    pub fn clear(entity: &Entity<Self>, cx: &mut App) {
+        entity.update(cx, |center, cx| {
+            center.entries.clear();
+            center.unseen_errors = false;
+            cx.notify();
+        });
+    }
+
+    pub fn set_filter(entity: &Entity<Self>, filter: Option<BridgeNotificationType>, cx: &mut App) {
+        entity.update(cx, |center, cx| {
+            center.filter = filter;
+            cx.notify();
+        });
+    }
+
+    pub fn mark_seen(entity: &Entity<Self>, cx: &mut App) {
+        entity.update(cx, |center, cx| {
+            if center.unseen_errors {
+                center.unseen_errors = false;
+                cx.notify();
+            }
+        });
+    }
+}
+
+/// Shows a popup and keeps the message in the notification center, errors stay until they're dismissed like the ones
+/// from the backend
+pub fn push_notification(notification_type: NotificationType, message: impl Into<SharedString>, window: &mut Window, cx: &mut App) {
+    let message = message.into();
+    NotificationCenter::push_global(notification_type, message.clone(), cx);
+
+    let mut notification: Notification = (notification_type, message).into();
+    if let NotificationType::Error = notification_type {
+        notification = notification.autohide(false);
+    }
+    window.push_notification(notification, cx);
+}
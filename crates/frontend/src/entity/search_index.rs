@@ -0,0 +1,152 @@
+use bridge::instance::{InstanceContentSummary, InstanceID, InstanceServerSummary, InstanceWorldSummary};
+use gpui::{App, Entity, SharedString};
+use indexmap::IndexMap;
+
+/// Names of everything the command palette can find, kept up to date from the messages the backend sends.
+/// Mods, worlds and servers are only indexed once they've been loaded for an instance
+#[derive(Default)]
+pub struct SearchIndex {
+    instances: IndexMap<InstanceID, IndexedInstance>,
+}
+
+#[derive(Default)]
+struct IndexedInstance {
+    name: IndexedItem,
+    mods: Vec<IndexedItem>,
+    worlds: Vec<IndexedItem>,
+    servers: Vec<IndexedItem>,
+}
+
+#[derive(Default)]
+struct IndexedItem {
+    title: SharedString,
+    /// Lowercase text matched against the query
+    key: String,
+}
+
+impl IndexedItem {
+    fn new(title: SharedString, extra: &[&str]) -> Self {
+        let mut key = title.to_lowercase();
+        for extra in extra {
+            key.push('\n');
+            key.push_str(&extra.to_lowercase());
+        }
+        Self { title, key }
+    }
+
+    fn matches(&self, terms: &[String]) -> bool {
+        terms.iter().all(|term| self.key.contains(term.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchResultKind {
+    Instance,
+    Mod,
+    World,
+    Server,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub instance: InstanceID,
+    pub instance_name: SharedString,
+    pub kind: SearchResultKind,
+    pub title: SharedString,
+}
+
+impl SearchIndex {
+    pub fn set_instance(entity: &Entity<Self>, id: InstanceID, name: SharedString, cx: &mut App) {
+        entity.update(cx, |index, cx| {
+            let name = IndexedItem::new(name, &[]);
+            if let Some(instance) = index.instances.get_mut(&id) {
+                instance.name = name;
+            } else {
+                index.instances.insert_before(0, id, IndexedInstance { name, ..Default::default() });
+            }
+            cx.notify();
+        });
+    }
+
+    pub fn remove_instance(entity: &Entity<Self>, id: InstanceID, cx: &mut App) {
+        entity.update(cx, |index, cx| {
+            if index.instances.shift_remove(&id).is_some() {
+                cx.notify();
+            }
+        });
+    }
+
+    pub fn set_mods(entity: &Entity<Self>, id: InstanceID, mods: &[InstanceContentSummary], cx: &mut App) {
+        Self::update_instance(entity, id, cx, |instance| {
+            instance.mods = mods.iter().map(|summary| {
+                let title = summary.content_summary.name.as_deref().unwrap_or(&summary.filename);
+                IndexedItem::new(SharedString::new(title), &[&*summary.filename])
+            }).collect();
+        });
+    }
+
+    pub fn set_worlds(entity: &Entity<Self>, id: InstanceID, worlds: &[InstanceWorldSummary], cx: &mut App) {
+        Self::update_instance(entity, id, cx, |instance| {
+            instance.worlds = worlds.iter().map(|world| IndexedItem::new(SharedString::new(world.title.clone()), &[])).collect();
+        });
+    }
+
+    pub fn set_servers(entity: &Entity<Self>, id: InstanceID, servers: &[InstanceServerSummary], cx: &mut App) {
+        Self::update_instance(entity, id, cx, |instance| {
+            instance.servers = servers.iter().map(|server| IndexedItem::new(SharedString::new(server.name.clone()), &[&*server.ip])).collect();
+        });
+    }
+
+    fn update_instance(entity: &Entity<Self>, id: InstanceID, cx: &mut App, update: impl FnOnce(&mut IndexedInstance)) {
+        entity.update(cx, |index, cx| {
+            if let Some(instance) = index.instances.get_mut(&id) {
+                update(instance);
+                cx.notify();
+            }
+        });
+    }
+
+    /// Every word of the query has to appear in a result. Instances are listed first, then their content
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut results = Vec::new();
+        if terms.is_empty() {
+            return results;
+        }
+
+        for (id, instance) in &self.instances {
+            if instance.name.matches(&terms) {
+                results.push(SearchResult {
+                    instance: *id,
+                    instance_name: instance.name.title.clone(),
+                    kind: SearchResultKind::Instance,
+                    title: instance.name.title.clone(),
+                });
+            }
+        }
+
+        for kind in [SearchResultKind::Mod, SearchResultKind::World, SearchResultKind::Server] {
+            for (id, instance) in &self.instances {
+                let items = match kind {
+                    SearchResultKind::Mod => &instance.mods,
+                    SearchResultKind::World => &instance.worlds,
+                    _ => &instance.servers,
+                };
+                for item in items.iter().filter(|item| item.matches(&terms)) {
+                    if results.len() >= limit {
+                        return results;
+                    }
+                    results.push(SearchResult {
+                        instance: *id,
+                        instance_name: instance.name.title.clone(),
+                        kind,
+                        title: item.title.clone(),
+                    });
+                }
+            }
+        }
+
+        results.truncate(limit);
+        results
+    }
+}
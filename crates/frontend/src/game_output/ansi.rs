@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use gpui::{rgb, Hsla};
+
+/// Foreground colors for SGR codes 30-37 followed by the bright variants 90-97, using the xterm palette
+const BASIC_COLORS: [u32; 16] = [
+    0x000000, 0xcd0000, 0x00cd00, 0xcdcd00, 0x0000ee, 0xcd00cd, 0x00cdcd, 0xe5e5e5,
+    0x7f7f7f, 0xff0000, 0x00ff00, 0xffff00, 0x5c5cff, 0xff00ff, 0x00ffff, 0xffffff,
+];
+
+pub type ColorSpans = Vec<(Range<usize>, Hsla)>;
+
+/// Removes ANSI escape sequences from a line, returning the colors set by SGR sequences as
+/// byte ranges into the stripped line. The color is carried over in `current` so that it
+/// continues onto the next line of the same item
+pub fn strip_escapes(line: &str, colorize: bool, current: &mut Option<Hsla>) -> (String, ColorSpans) {
+    let mut stripped = String::with_capacity(line.len());
+    let mut spans: ColorSpans = Vec::new();
+
+    let mut rest = line;
+    while let Some(escape) = rest.find('\x1b') {
+        push_text(&mut stripped, &mut spans, &rest[..escape], *current);
+        rest = &rest[escape+1..];
+
+        // Control sequences are `ESC [`, parameters and then a final byte between '@' and '~'
+        let Some(sequence) = rest.strip_prefix('[') else {
+            // Lone escape or a sequence type that can't be displayed, skip the next character
+            let mut chars = rest.chars();
+            chars.next();
+            rest = chars.as_str();
+            continue;
+        };
+        let Some(end) = sequence.find(|c: char| ('@'..='~').contains(&c)) else {
+            rest = "";
+            break;
+        };
+
+        if sequence.as_bytes()[end] == b'm' && colorize {
+            apply_sgr(&sequence[..end], current);
+        }
+        rest = &sequence[end+1..];
+    }
+    push_text(&mut stripped, &mut spans, rest, *current);
+
+    if !colorize {
+        spans.clear();
+    }
+    (stripped, spans)
+}
+
+fn push_text(stripped: &mut String, spans: &mut ColorSpans, text: &str, color: Option<Hsla>) {
+    if text.is_empty() {
+        return;
+    }
+
+    let start = stripped.len();
+    stripped.push_str(text);
+
+    let Some(color) = color else {
+        return;
+    };
+    if let Some((last_range, last_color)) = spans.last_mut() && last_range.end == start && *last_color == color {
+        last_range.end = stripped.len();
+    } else {
+        spans.push((start..stripped.len(), color));
+    }
+}
+
+fn apply_sgr(parameters: &str, current: &mut Option<Hsla>) {
+    // An empty parameter list is the same as a reset
+    if parameters.is_empty() {
+        *current = None;
+        return;
+    }
+
+    let mut codes = parameters.split([';', ':']).map(|code| code.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 | 39 => *current = None,
+            30..=37 => *current = Some(rgb(BASIC_COLORS[(code - 30) as usize]).into()),
+            90..=97 => *current = Some(rgb(BASIC_COLORS[(code - 90 + 8) as usize]).into()),
+            38 => {
+                if let Some(color) = read_extended_color(&mut codes) {
+                    *current = Some(color);
+                }
+            },
+            48 => {
+                // Backgrounds aren't shown, but their parameters still need to be skipped
+                _ = read_extended_color(&mut codes);
+            },
+            _ => {},
+        }
+    }
+}
+
+fn read_extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Hsla> {
+    match codes.next()? {
+        5 => Some(palette_color(codes.next()?)),
+        2 => {
+            let r = codes.next()? as u32;
+            let g = codes.next()? as u32;
+            let b = codes.next()? as u32;
+            Some(rgb((r << 16) | (g << 8) | b).into())
+        },
+        _ => None,
+    }
+}
+
+/// Color from the 256 color palette: the basic colors, a 6x6x6 color cube and then a grayscale ramp
+fn palette_color(index: u8) -> Hsla {
+    let rgb_value = match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let index = index as u32 - 16;
+            let level = |value: u32| if value == 0 { 0 } else { 55 + value * 40 };
+            (level(index / 36) << 16) | (level((index / 6) % 6) << 8) | level(index % 6)
+        },
+        232..=255 => {
+            let gray = 8 + (index as u32 - 232) * 10;
+            (gray << 16) | (gray << 8) | gray
+        },
+    };
+    rgb(rgb_value).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(value: u32) -> Option<Hsla> {
+        Some(rgb(value).into())
+    }
+
+    #[test]
+    fn escapes_are_stripped_into_spans() {
+        let line = "\x1b[31mred\x1b[0m plain \x1b[1;92mgreen";
+        let mut current = None;
+        let (stripped, spans) = strip_escapes(line, true, &mut current);
+        assert_eq!(stripped, "red plain green");
+        assert_eq!(spans, [(0..3, color(0xcd0000).unwrap()), (10..15, color(0x00ff00).unwrap())]);
+        assert_eq!(current, color(0x00ff00));
+
+        let mut current = None;
+        let (stripped, spans) = strip_escapes(line, false, &mut current);
+        assert_eq!(stripped, "red plain green");
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn color_continues_onto_the_next_line() {
+        let mut current = None;
+        strip_escapes("\x1b[34mblue", true, &mut current);
+        let (stripped, spans) = strip_escapes("still blue\x1b[m", true, &mut current);
+        assert_eq!(stripped, "still blue");
+        assert_eq!(spans, [(0..10, color(0x0000ee).unwrap())]);
+        assert_eq!(current, None);
+    }
+
+    #[test]
+    fn extended_colors() {
+        let mut current = None;
+        let (stripped, spans) = strip_escapes("\x1b[38;5;196mA\x1b[38;2;1;2;3mB\x1b[48;5;1;33mC\x1b[38;5;232mD", true, &mut current);
+        assert_eq!(stripped, "ABCD");
+        assert_eq!(spans, [
+            (0..1, color(0xff0000).unwrap()),
+            (1..2, color(0x010203).unwrap()),
+            (2..3, color(0xcdcd00).unwrap()),
+            (3..4, color(0x080808).unwrap()),
+        ]);
+    }
+
+    #[test]
+    fn other_escapes_are_removed() {
+        let mut current = None;
+        let (stripped, spans) = strip_escapes("a\x1b7b\x1b[2Kc\x1b[31", true, &mut current);
+        assert_eq!(stripped, "abc");
+        assert!(spans.is_empty());
+    }
+}
@@ -1,18 +1,23 @@
-use std::{cell::RefCell, collections::HashMap, num::NonZeroUsize, ops::Range, rc::Rc, sync::Arc};
+use std::{cell::RefCell, collections::HashMap, num::NonZeroUsize, ops::Range, path::Path, rc::Rc, sync::Arc};
 
 use ftree::FenwickTree;
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::Button, h_flex, input::{Input, InputEvent, InputState}, scroll::{Scrollbar, ScrollbarHandle}, v_flex, ActiveTheme as _, Icon, IconName, Sizable
+    button::Button, h_flex, input::{Input, InputEvent, InputState}, scroll::{Scrollbar, ScrollbarHandle}, v_flex, ActiveTheme as _, Icon, IconName, Sizable, Theme
 };
 use lru::LruCache;
 use rustc_hash::FxBuildHasher;
 
-use bridge::{game_output::GameOutputLogLevel, keep_alive::KeepAlive};
+use bridge::{game_output::{GameOutputLine, GameOutputLogLevel}, keep_alive::KeepAlive};
 
-use crate::CloseWindow;
+use crate::{component::log_navigation::{self, PaintedItems}, game_output::{ansi::ColorSpans, spill::{SpillFile, SpilledItem}}, interface_config::InterfaceConfig, ts, CloseWindow, FocusSearch};
+
+mod ansi;
+mod spill;
 
 struct CachedShapedLogLevels {
+    /// Colors the levels were shaped with, they're shaped again when the theme changes
+    colors: [Hsla; 7],
     fatal: Arc<ShapedLine>,
     error: Arc<ShapedLine>,
     warn: Arc<ShapedLine>,
@@ -22,6 +27,32 @@ struct CachedShapedLogLevels {
     other: Arc<ShapedLine>,
 }
 
+impl CachedShapedLogLevels {
+    fn get(&self, level: GameOutputLogLevel) -> Arc<ShapedLine> {
+        match level {
+            GameOutputLogLevel::Fatal => self.fatal.clone(),
+            GameOutputLogLevel::Error => self.error.clone(),
+            GameOutputLogLevel::Warn => self.warn.clone(),
+            GameOutputLogLevel::Info => self.info.clone(),
+            GameOutputLogLevel::Debug => self.debug.clone(),
+            GameOutputLogLevel::Trace => self.trace.clone(),
+            GameOutputLogLevel::Other => self.other.clone(),
+        }
+    }
+}
+
+fn log_level_colors(theme: &Theme) -> [Hsla; 7] {
+    [
+        theme.danger_active,
+        theme.danger,
+        theme.warning,
+        theme.success,
+        theme.info,
+        theme.link,
+        theme.muted_foreground,
+    ]
+}
+
 struct CachedShapedLines {
     last_time: Option<Arc<ShapedLine>>,
     last_time_millis: i64,
@@ -34,6 +65,8 @@ pub struct GameOutputItemState {
     last_scrolled_item: usize,
     item_sizes: FenwickTree<usize>,
     total_line_count: usize,
+    /// Lines of text held by the items, unlike `total_line_count` this doesn't depend on wrapping or searching
+    stored_line_count: usize,
     cached_shaped_lines: CachedShapedLines,
     search_query: SharedString,
 }
@@ -41,15 +74,24 @@ pub struct GameOutputItemState {
 pub struct GameOutput {
     font: Font,
     scroll_state: Rc<RefCell<GameOutputScrollState>>,
-    pending: Vec<(i64, GameOutputLogLevel, Arc<[Arc<str>]>)>,
+    pending: Vec<GameOutputLine>,
     item_state: Option<GameOutputItemState>,
     time_column_width: Pixels,
     level_column_width: Pixels,
     shaped_log_levels: Option<CachedShapedLogLevels>,
+    /// Folder for the file evicted lines are written to, only created once lines are written
+    spill_dir: Arc<Path>,
+    spill: Option<SpillFile>,
+    /// Set by "Go to Top" until the most recently spilled lines have been read back in, nothing is evicted meanwhile
+    page_in_pending: bool,
+    /// Spilled lines that were read back, they're put in front of the items on the next paint
+    paged_in: Option<Vec<SpilledItem>>,
+    /// Where items were painted in the last frame, used to find the item that was clicked
+    painted_items: PaintedItems,
 }
 
-impl Default for GameOutput {
-    fn default() -> Self {
+impl GameOutput {
+    pub fn new(spill_dir: Arc<Path>) -> Self {
         Self {
             font: Font {
                 family: SharedString::new_static("Roboto Mono"),
@@ -65,6 +107,7 @@ impl Default for GameOutput {
                 last_scrolled_item: 0,
                 item_sizes: FenwickTree::new(),
                 total_line_count: 0,
+                stored_line_count: 0,
                 cached_shaped_lines: CachedShapedLines {
                     last_time: None,
                     last_time_millis: 0,
@@ -75,13 +118,22 @@ impl Default for GameOutput {
             time_column_width: Default::default(),
             level_column_width: Default::default(),
             shaped_log_levels: None,
+            spill_dir,
+            spill: None,
+            page_in_pending: false,
+            paged_in: None,
+            painted_items: PaintedItems::default(),
         }
     }
 }
 
 impl GameOutput {
-    pub fn add(&mut self, time: i64, level: GameOutputLogLevel, text: Arc<[Arc<str>]>) {
-        self.pending.push((time, level, text));
+    pub fn add(&mut self, lines: Vec<GameOutputLine>) {
+        if self.pending.is_empty() {
+            self.pending = lines;
+        } else {
+            self.pending.extend(lines);
+        }
     }
 
     fn shape_log_level(
@@ -103,82 +155,298 @@ impl GameOutput {
         Arc::new(text_system.shape_line(SharedString::new_static(level), font_size, &[level_run], None))
     }
 
-    pub fn apply_pending(&mut self, window: &mut Window, _cx: &mut App) {
-        if self.shaped_log_levels.is_none() {
+    pub fn apply_pending(&mut self, window: &mut Window, cx: &mut App) {
+        let colors = log_level_colors(cx.theme());
+        if self.shaped_log_levels.as_ref().is_none_or(|levels| levels.colors != colors) {
             let text_style = window.text_style();
             let font_size = text_style.font_size.to_pixels(window.rem_size());
             let text_system = window.text_system();
 
             let levels = CachedShapedLogLevels {
-                fatal: self.shape_log_level("FATAL", hsla(0.0, 0.737, 0.418, 1.0), text_system, &text_style, font_size), // red-700
-                error: self.shape_log_level("ERROR", hsla(0.0, 0.842, 0.602, 1.0), text_system, &text_style, font_size), // red-500
-                warn: self.shape_log_level("WARN", hsla(24.6/360.0, 0.95, 0.531, 1.0), text_system, &text_style, font_size), // orange-500
-                info: self.shape_log_level("INFO", hsla(83.7/360.0, 0.805, 0.443, 1.0), text_system, &text_style, font_size), // lime-500
-                debug: self.shape_log_level("DEBUG", hsla(258.3/360.0, 0.895, 0.663, 1.0), text_system, &text_style, font_size), // violet-500
-                trace: self.shape_log_level("TRACE", hsla(198.6/360.0, 0.887, 0.484, 1.0), text_system, &text_style, font_size), // sky-500
-                other: self.shape_log_level("OTHER", hsla(0.0, 0.5, 0.5, 1.0), text_system, &text_style, font_size),
+                colors,
+                fatal: self.shape_log_level("FATAL", colors[0], text_system, &text_style, font_size),
+                error: self.shape_log_level("ERROR", colors[1], text_system, &text_style, font_size),
+                warn: self.shape_log_level("WARN", colors[2], text_system, &text_style, font_size),
+                info: self.shape_log_level("INFO", colors[3], text_system, &text_style, font_size),
+                debug: self.shape_log_level("DEBUG", colors[4], text_system, &text_style, font_size),
+                trace: self.shape_log_level("TRACE", colors[5], text_system, &text_style, font_size),
+                other: self.shape_log_level("OTHER", colors[6], text_system, &text_style, font_size),
             };
 
             self.level_column_width = levels.fatal.width.max(levels.error.width).max(levels.warn.width)
                 .max(levels.info.width).max(levels.debug.width).max(levels.trace.width).max(levels.other.width) + font_size/2.0;
+
+            if let Some(item_state) = &mut self.item_state {
+                for item in &mut item_state.items {
+                    item.level = levels.get(item.log_level);
+                }
+            }
             self.shaped_log_levels = Some(levels);
         }
         let Some(item_state) = &mut self.item_state else {
             return;
         };
-        for (time, level, text) in self.pending.drain(..) {
-            let shaped_level = match level {
-                GameOutputLogLevel::Fatal => self.shaped_log_levels.as_ref().unwrap().fatal.clone(),
-                GameOutputLogLevel::Error => self.shaped_log_levels.as_ref().unwrap().error.clone(),
-                GameOutputLogLevel::Warn => self.shaped_log_levels.as_ref().unwrap().warn.clone(),
-                GameOutputLogLevel::Info => self.shaped_log_levels.as_ref().unwrap().info.clone(),
-                GameOutputLogLevel::Debug => self.shaped_log_levels.as_ref().unwrap().debug.clone(),
-                GameOutputLogLevel::Trace => self.shaped_log_levels.as_ref().unwrap().trace.clone(),
-                GameOutputLogLevel::Other => self.shaped_log_levels.as_ref().unwrap().other.clone(),
+        let interface_config = InterfaceConfig::get(cx);
+        let colorize = !interface_config.disable_game_output_colors;
+        let line_limit = interface_config.game_output_line_limit.lines();
+        let spill = interface_config.spill_game_output_to_disk;
+
+        for GameOutputLine { time, level, thread, text } in self.pending.drain(..) {
+            let text: Arc<[Arc<str>]> = if let Some(thread) = thread && let Some(first) = text.first() {
+                let mut lines = text.to_vec();
+                lines[0] = format!("[{thread}] {first}").into();
+                lines.into()
+            } else {
+                text
             };
+            let (text, colors) = strip_ansi_escapes(text, colorize);
 
-            let mut highlighted_text = None;
+            let shaped_level = self.shaped_log_levels.as_ref().unwrap().get(level);
+            let item = create_item(time, level, shaped_level, text, colors, item_state.items.len(), &item_state.search_query);
 
-            if !item_state.search_query.is_empty() {
-                for (line_index, line) in text.iter().enumerate() {
-                    if let Some(found) = line.find(item_state.search_query.as_str()) {
-                        highlighted_text = Some((line_index, found..found+item_state.search_query.as_str().len()));
-                        break;
-                    }
-                }
-                if highlighted_text.is_none() {
-                    // Item doesn't match search query, push skipped item
-                    let backup_total_lines_while_skipped = text.len();
-                    item_state.item_sizes.push(0);
-                    item_state.items.push(GameOutputItem {
-                        time: TimeShapedLine::Timestamp(time),
-                        level: shaped_level.clone(),
-                        text: text.clone(),
-                        index: item_state.items.len(),
-                        backup_total_lines_while_skipped,
-                        total_lines: 0,
-                        highlighted_text: None,
-                        skip: true,
-                    });
-                    continue;
-                }
+            item_state.item_sizes.push(item.total_lines);
+            item_state.total_line_count += item.total_lines;
+            item_state.stored_line_count += item.text.len();
+            item_state.items.push(item);
+        }
+
+        if let Some(spilled) = self.paged_in.take() {
+            self.page_in(spilled);
+            self.page_in_pending = false;
+        }
+        if let Some(line_limit) = line_limit && !self.page_in_pending {
+            self.evict_oldest(line_limit, spill);
+        }
+    }
+
+    /// Drops the oldest items once more lines than the limit are stored, optionally writing them to disk first.
+    /// Items are evicted in batches of an eighth of the limit since the fenwick tree has to be rebuilt afterwards
+    fn evict_oldest(&mut self, line_limit: usize, spill: bool) {
+        let Some(item_state) = &mut self.item_state else {
+            return;
+        };
+        let mut scroll_state = self.scroll_state.borrow_mut();
+        if scroll_state.active_drag.is_some() {
+            return;
+        }
+
+        // While scrolled up there's more leeway, so lines that were just read back in from disk aren't evicted straight away
+        let following = scroll_state.scrolling == GameOutputScrolling::Bottom;
+        let threshold = if following { line_limit } else { line_limit.saturating_mul(2) };
+        if item_state.stored_line_count <= threshold {
+            return;
+        }
+
+        let target = line_limit - line_limit / 8;
+        let mut evicted_lines = 0;
+        let mut evict_count = 0;
+        // Always keep the newest item, even if it's larger than the limit on its own
+        for item in &item_state.items[..item_state.items.len().saturating_sub(1)] {
+            if item_state.stored_line_count - evicted_lines <= target {
+                break;
             }
+            evicted_lines += item.text.len();
+            evict_count += 1;
+        }
+        if evict_count == 0 {
+            return;
+        }
 
-            let total_lines = text.len();
-            item_state.item_sizes.push(total_lines);
-            item_state.total_line_count += total_lines;
-            item_state.items.push(GameOutputItem {
+        let evicted: Vec<GameOutputItem> = item_state.items.drain(..evict_count).collect();
+
+        let evicted_display_lines: usize = evicted.iter().map(|item| item.total_lines).sum();
+        if spill {
+            let file = self.spill.get_or_insert_with(|| SpillFile::new(self.spill_dir.clone()));
+            file.write(evicted.into_iter().map(|item| SpilledItem {
+                time: item.timestamp,
+                level: item.log_level,
+                text: item.text,
+            }).collect());
+        }
+
+        item_state.stored_line_count -= evicted_lines;
+        item_state.total_line_count -= evicted_display_lines;
+        item_state.last_scrolled_item = item_state.last_scrolled_item.saturating_sub(evict_count);
+        item_state.reindex();
+
+        if let GameOutputScrolling::Top { offset } = &mut scroll_state.scrolling {
+            *offset = (*offset + evicted_display_lines * scroll_state.line_height).min(Pixels::ZERO);
+        }
+    }
+
+    /// Puts the spilled chunk that was read back in front of the items
+    fn page_in(&mut self, spilled: Vec<SpilledItem>) {
+        let Some(item_state) = &mut self.item_state else {
+            return;
+        };
+        let Some(shaped_log_levels) = &self.shaped_log_levels else {
+            return;
+        };
+
+        let mut items: Vec<GameOutputItem> = spilled.into_iter().map(|spilled| {
+            // Colors aren't written to disk, so lines that were read back in are uncolored
+            create_item(spilled.time, spilled.level, shaped_log_levels.get(spilled.level), spilled.text, None, 0, &item_state.search_query)
+        }).collect();
+
+        item_state.total_line_count += items.iter().map(|item| item.total_lines).sum::<usize>();
+        item_state.stored_line_count += items.iter().map(|item| item.text.len()).sum::<usize>();
+        item_state.last_scrolled_item += items.len();
+        items.append(&mut item_state.items);
+        item_state.items = items;
+        item_state.reindex();
+    }
+}
+
+impl GameOutput {
+    /// Toggles the bookmark of the item at the position if it's inside the time column
+    fn toggle_bookmark_at(&mut self, position: Point<Pixels>) -> bool {
+        let Some(item_state) = &mut self.item_state else {
+            return false;
+        };
+        let Some(index) = self.painted_items.item_at(position) else {
+            return false;
+        };
+        let Some(item) = item_state.items.get_mut(index) else {
+            return false;
+        };
+        item.bookmarked = !item.bookmarked;
+        true
+    }
+
+    /// Scrolls to the closest bookmarked item after (or before) the item at the top of the view
+    fn scroll_to_bookmark(&mut self, forwards: bool) -> bool {
+        let Some(item_state) = &self.item_state else {
+            return false;
+        };
+        let is_bookmark = |index: usize| item_state.items[index].bookmarked && !item_state.items[index].skip;
+        let Some(index) = log_navigation::find_bookmark(item_state.last_scrolled_item, item_state.items.len(), forwards, is_bookmark) else {
+            return false;
+        };
+        self.scroll_to_item(index);
+        true
+    }
+
+    /// Scrolls to the first item logged at or after the time of day, starting from the day of the first item
+    fn jump_to_time(&mut self, time: chrono::NaiveTime) -> bool {
+        let Some(item_state) = &self.item_state else {
+            return false;
+        };
+        let Some(first) = item_state.items.first() else {
+            return false;
+        };
+        let Some(first_time) = chrono::DateTime::from_timestamp_millis(first.timestamp) else {
+            return false;
+        };
+        let first_time = first_time.with_timezone(&chrono::Local);
+
+        let mut target = first_time.date_naive().and_time(time);
+        if target < first_time.naive_local() {
+            target += chrono::Duration::days(1);
+        }
+        let Some(target) = target.and_local_timezone(chrono::Local).earliest() else {
+            return false;
+        };
+        let target = target.timestamp_millis();
+
+        // Items arrive in order, so their timestamps are sorted
+        let index = item_state.items.partition_point(|item| item.timestamp < target);
+        self.scroll_to_item(index.min(item_state.items.len() - 1));
+        true
+    }
+
+    fn scroll_to_item(&self, index: usize) {
+        let Some(item_state) = &self.item_state else {
+            return;
+        };
+        let lines_above = item_state.item_sizes.prefix_sum(index, 0);
+        let mut scroll_state = self.scroll_state.borrow_mut();
+        let offset = -(lines_above * scroll_state.line_height);
+        scroll_state.set_offset(offset);
+    }
+}
+
+impl GameOutputItemState {
+    /// Rebuilds the indices and sizes after items were added or removed at the front
+    fn reindex(&mut self) {
+        for (index, item) in self.items.iter_mut().enumerate() {
+            item.index = index;
+        }
+        self.item_sizes = FenwickTree::from_iter(self.items.iter().map(|item| item.total_lines));
+        self.cached_shaped_lines.item_lines.clear();
+    }
+}
+
+fn create_item(
+    time: i64,
+    log_level: GameOutputLogLevel,
+    level: Arc<ShapedLine>,
+    text: Arc<[Arc<str>]>,
+    colors: Option<Arc<[ColorSpans]>>,
+    index: usize,
+    search_query: &SharedString,
+) -> GameOutputItem {
+    let mut highlighted_text = None;
+
+    if !search_query.is_empty() {
+        for (line_index, line) in text.iter().enumerate() {
+            if let Some(found) = line.find(search_query.as_str()) {
+                highlighted_text = Some((line_index, found..found+search_query.as_str().len()));
+                break;
+            }
+        }
+        if highlighted_text.is_none() {
+            // Item doesn't match search query, create skipped item
+            return GameOutputItem {
                 time: TimeShapedLine::Timestamp(time),
-                level: shaped_level.clone(),
-                text: text.clone(),
-                index: item_state.items.len(),
-                backup_total_lines_while_skipped: total_lines,
-                total_lines,
-                highlighted_text,
-                skip: false,
-            });
+                timestamp: time,
+                level,
+                log_level,
+                backup_total_lines_while_skipped: text.len(),
+                text,
+                colors,
+                index,
+                total_lines: 0,
+                highlighted_text: None,
+                skip: true,
+                bookmarked: false,
+            };
         }
     }
+
+    let total_lines = text.len();
+    GameOutputItem {
+        time: TimeShapedLine::Timestamp(time),
+        timestamp: time,
+        level,
+        log_level,
+        text,
+        colors,
+        index,
+        backup_total_lines_while_skipped: total_lines,
+        total_lines,
+        highlighted_text,
+        skip: false,
+        bookmarked: false,
+    }
+}
+
+/// Removes ANSI escape sequences that some mods print, keeping the colors they set unless colorization is disabled
+fn strip_ansi_escapes(text: Arc<[Arc<str>]>, colorize: bool) -> (Arc<[Arc<str>]>, Option<Arc<[ColorSpans]>>) {
+    if !text.iter().any(|line| line.contains('\x1b')) {
+        return (text, None);
+    }
+
+    let mut current_color = None;
+    let mut lines = Vec::with_capacity(text.len());
+    let mut colors = Vec::with_capacity(text.len());
+    for line in text.iter() {
+        let (stripped, spans) = ansi::strip_escapes(line, colorize, &mut current_color);
+        lines.push(Arc::from(stripped));
+        colors.push(spans);
+    }
+
+    let colors = colors.iter().any(|spans| !spans.is_empty()).then(|| colors.into());
+    (lines.into(), colors)
 }
 
 pub struct GameOutputList {
@@ -193,14 +461,18 @@ enum TimeShapedLine {
 
 struct GameOutputItem {
     time: TimeShapedLine,
+    timestamp: i64,
     level: Arc<ShapedLine>,
+    log_level: GameOutputLogLevel,
 
     text: Arc<[Arc<str>]>,
+    colors: Option<Arc<[ColorSpans]>>,
     index: usize,
     backup_total_lines_while_skipped: usize,
     total_lines: usize,
     highlighted_text: Option<(usize, Range<usize>)>,
     skip: bool,
+    bookmarked: bool,
 }
 
 impl GameOutputItem {
@@ -228,52 +500,13 @@ impl GameOutputItem {
                 let boundaries = line_wrapper.wrap_line(&fragments, wrap_width);
 
                 let mut handle_segment = |wrapped_line: SharedString, from, to| {
-                    let runs: &[TextRun] = if let Some((highlight_line, highlight_range)) = &self.highlighted_text
-                        && *highlight_line == original_line_index
-                        && highlight_range.start < to
-                        && highlight_range.end > from
-                    {
-                        let highlight_start = highlight_range.start.max(from);
-                        let highlight_end = highlight_range.end.min(to);
-
-                        &[
-                            TextRun {
-                                len: highlight_start - from,
-                                font: font.clone(),
-                                color: text_style.color,
-                                background_color: text_style.background_color,
-                                underline: text_style.underline,
-                                strikethrough: text_style.strikethrough,
-                            },
-                            TextRun {
-                                len: highlight_end - highlight_start,
-                                font: font.clone(),
-                                color: gpui::black(),
-                                background_color: Some(gpui::yellow()),
-                                underline: text_style.underline,
-                                strikethrough: text_style.strikethrough,
-                            },
-                            TextRun {
-                                len: to - highlight_end,
-                                font: font.clone(),
-                                color: text_style.color,
-                                background_color: text_style.background_color,
-                                underline: text_style.underline,
-                                strikethrough: text_style.strikethrough,
-                            },
-                        ]
-                    } else {
-                        &[TextRun {
-                            len: wrapped_line.len(),
-                            font: font.clone(),
-                            color: text_style.color,
-                            background_color: text_style.background_color,
-                            underline: text_style.underline,
-                            strikethrough: text_style.strikethrough,
-                        }]
-                    };
+                    let highlight = self.highlighted_text.as_ref()
+                        .filter(|(highlight_line, _)| *highlight_line == original_line_index)
+                        .map(|(_, highlight_range)| highlight_range);
+                    let colors = self.colors.as_ref().map(|colors| colors[original_line_index].as_slice()).unwrap_or_default();
+                    let runs = create_text_runs(from, to, colors, highlight, font, text_style);
 
-                    let shaped = text_system.shape_line(wrapped_line, font_size, runs, None);
+                    let shaped = text_system.shape_line(wrapped_line, font_size, &runs, None);
                     wrapped.push(shaped);
                 };
 
@@ -307,6 +540,62 @@ impl GameOutputItem {
     }
 }
 
+/// Splits the `from..to` part of a line into runs colored by its ANSI colors, with the search highlight on top
+fn create_text_runs(
+    from: usize,
+    to: usize,
+    colors: &[(Range<usize>, Hsla)],
+    highlight: Option<&Range<usize>>,
+    font: &Font,
+    text_style: &TextStyle,
+) -> Vec<TextRun> {
+    let mut boundaries = vec![from, to];
+    for range in colors.iter().map(|(range, _)| range).chain(highlight) {
+        boundaries.extend([range.start, range.end].into_iter().filter(|ix| *ix > from && *ix < to));
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut runs: Vec<TextRun> = Vec::with_capacity(boundaries.len());
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let (color, background_color) = if highlight.is_some_and(|highlight| highlight.contains(&start)) {
+            (gpui::black(), Some(gpui::yellow()))
+        } else {
+            let color = colors.iter()
+                .find(|(range, _)| range.contains(&start))
+                .map(|(_, color)| *color)
+                .unwrap_or(text_style.color);
+            (color, text_style.background_color)
+        };
+
+        if let Some(last) = runs.last_mut() && last.color == color && last.background_color == background_color {
+            last.len += end - start;
+        } else {
+            runs.push(TextRun {
+                len: end - start,
+                font: font.clone(),
+                color,
+                background_color,
+                underline: text_style.underline,
+                strikethrough: text_style.strikethrough,
+            });
+        }
+    }
+
+    if runs.is_empty() {
+        runs.push(TextRun {
+            len: 0,
+            font: font.clone(),
+            color: text_style.color,
+            background_color: text_style.background_color,
+            underline: text_style.underline,
+            strikethrough: text_style.strikethrough,
+        });
+    }
+    runs
+}
+
 struct WrappedLines {
     wrap_width: Pixels,
     lines: Vec<ShapedLine>,
@@ -414,6 +703,7 @@ impl Element for GameOutputList {
                         let scroll_render_info = game_output.update_scrolling(line_height, wrap_width,
                             font_size, &text_style, &mut line_wrapper, window.text_system());
 
+                        game_output.painted_items.clear();
                         if let Some(item_state) = game_output.item_state.as_mut() && !item_state.items.is_empty() {
                             if scroll_render_info.reverse {
                                 paint_lines::<true>(
@@ -432,6 +722,7 @@ impl Element for GameOutputList {
                                     &mut item_state.total_line_count,
                                     &mut line_wrapper,
                                     &mut item_state.cached_shaped_lines,
+                                    &mut game_output.painted_items,
                                     window,
                                     cx,
                                 );
@@ -452,12 +743,15 @@ impl Element for GameOutputList {
                                     &mut item_state.total_line_count,
                                     &mut line_wrapper,
                                     &mut item_state.cached_shaped_lines,
+                                    &mut game_output.painted_items,
                                     window,
                                     cx,
                                 );
                             }
                         }
 
+                        game_output.painted_items.set_gutter(visible_bounds.origin.x..bounds.origin.x + game_output.time_column_width);
+
                         let mut scroll_state = game_output.scroll_state.borrow_mut();
                         scroll_state.bounds_y = bounds.size.height;
                         scroll_state.line_height = line_height;
@@ -689,6 +983,7 @@ fn paint_lines<'a, const REVERSE: bool>(
     total_line_count: &mut usize,
     line_wrapper: &mut LineWrapperHandle,
     cache: &mut CachedShapedLines,
+    painted_items: &mut PaintedItems,
     window: &mut Window,
     cx: &mut App,
 ) {
@@ -791,6 +1086,9 @@ fn paint_lines<'a, const REVERSE: bool>(
         if REVERSE {
             time_origin.y -= (line_count - 1) * line_height;
         }
+        painted_items.paint(item.index, point(visible_bounds.origin.x, time_origin.y), line_count * line_height,
+            item.bookmarked, window, cx);
+
         if let TimeShapedLine::Shaped(shaped_time) = &item.time {
             _ = shaped_time.paint(time_origin, line_height, TextAlign::Left, None, window, cx);
         }
@@ -829,8 +1127,10 @@ pub struct GameOutputRoot {
     _keep_alive: KeepAlive,
     game_output: Entity<GameOutput>,
     search_state: Entity<InputState>,
+    jump_state: Entity<InputState>,
     _search_task: Task<()>,
     _search_input_subscription: Subscription,
+    _jump_input_subscription: Subscription,
     focus_handle: FocusHandle,
 }
 
@@ -944,10 +1244,13 @@ impl GameOutputRoot {
     ) -> Self {
         let scroll_state = Rc::clone(&game_output.read(cx).scroll_state);
 
-        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder("Search").clean_on_escape());
+        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.search")).clean_on_escape());
 
         let _search_input_subscription = cx.subscribe_in(&search_state, window, Self::on_search_input_event);
 
+        let jump_state = log_navigation::create_jump_input(window, cx);
+        let _jump_input_subscription = cx.subscribe_in(&jump_state, window, Self::on_jump_input_event);
+
         let focus_handle = cx.focus_handle();
         focus_handle.focus(window, cx);
 
@@ -956,12 +1259,66 @@ impl GameOutputRoot {
             _keep_alive: keep_alive,
             game_output,
             search_state,
+            jump_state,
             _search_task: Task::ready(()),
             _search_input_subscription,
+            _jump_input_subscription,
             focus_handle,
         }
     }
 
+    fn on_jump_input_event(
+        &mut self,
+        state: &Entity<InputState>,
+        event: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(time) = log_navigation::jump_target(state, event, cx) else {
+            return;
+        };
+        if self.game_output.update(cx, |game_output, _| game_output.jump_to_time(time)) {
+            cx.notify();
+        }
+    }
+
+    /// Reads the most recently spilled lines back in on the spill thread, they're shown on the next paint
+    fn page_in_spilled(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let receiver = self.game_output.update(cx, |game_output, _| {
+            if game_output.page_in_pending {
+                return None;
+            }
+            let receiver = game_output.spill.as_ref()?.read_last();
+            game_output.page_in_pending = true;
+            Some(receiver)
+        });
+        let Some(receiver) = receiver else {
+            return;
+        };
+
+        let game_output = self.game_output.clone();
+        cx.spawn_in(window, async move |_, window| {
+            let spilled = match receiver.await {
+                Ok(Ok(spilled)) => spilled,
+                Ok(Err(error)) => {
+                    log::error!("Unable to read evicted game output: {error}");
+                    None
+                },
+                Err(_) => None,
+            };
+            _ = window.update(|window, cx| {
+                game_output.update(cx, |game_output, _| {
+                    if spilled.is_some() {
+                        game_output.paged_in = spilled;
+                    } else {
+                        game_output.page_in_pending = false;
+                    }
+                });
+                window.refresh();
+            });
+        }).detach();
+    }
+
     fn on_search_input_event(
         &mut self,
         state: &Entity<InputState>,
@@ -1063,17 +1420,23 @@ impl Render for GameOutputRoot {
             .flex_1()
             .gap_4()
             .child(search)
-            .child(Button::new("top").label("Go to Top").on_click(cx.listener(|root, _, _, cx| {
+            .child(log_navigation::controls(&self.jump_state, cx.listener(|root, forwards: &bool, _, cx| {
+                if root.game_output.update(cx, |game_output, _| game_output.scroll_to_bookmark(*forwards)) {
+                    cx.notify();
+                }
+            })))
+            .child(Button::new("top").label(ts!("logs.go_to_top")).on_click(cx.listener(|root, _, window, cx| {
+                root.page_in_spilled(window, cx);
                 let mut state = root.scroll_handler.state.borrow_mut();
                 state.scrolling = GameOutputScrolling::Top { offset: Pixels::ZERO };
                 cx.notify();
             })))
-            .child(Button::new("bottom").label("Go to Bottom").on_click(cx.listener(|root, _, _, cx| {
+            .child(Button::new("bottom").label(ts!("logs.go_to_bottom")).on_click(cx.listener(|root, _, _, cx| {
                 let mut state = root.scroll_handler.state.borrow_mut();
                 state.scrolling = GameOutputScrolling::Bottom;
                 cx.notify();
             })))
-            .child(Button::new("upload").label("Upload"));
+            .child(Button::new("upload").label(ts!("logs.upload")));
 
         v_flex()
             .size_full()
@@ -1089,7 +1452,11 @@ impl Render for GameOutputRoot {
                     .child(GameOutputList {
                         interactivity: Interactivity::new(),
                         game_output: self.game_output.clone(),
-                    })
+                    }.on_mouse_down(MouseButton::Left, cx.listener(|root, event: &MouseDownEvent, _, cx| {
+                        if root.game_output.update(cx, |game_output, _| game_output.toggle_bookmark_at(event.position)) {
+                            cx.notify();
+                        }
+                    })))
                     .child(
                         div()
                             .w_3()
@@ -1115,5 +1482,8 @@ impl Render for GameOutputRoot {
             .on_action(|_: &CloseWindow, window, _| {
                 window.remove_window();
             })
+            .on_action(cx.listener(|root, _: &FocusSearch, window, cx| {
+                root.search_state.update(cx, |state, cx| state.focus(window, cx));
+            }))
     }
 }
@@ -0,0 +1,274 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+};
+
+use bridge::game_output::GameOutputLogLevel;
+
+/// Lines evicted from a game output window, written to a file in the temp folder of the launcher so
+/// that they can be read back in when scrolling to the top. Items are evicted while painting, so the
+/// file is only touched on its own thread
+pub struct SpillFile {
+    sender: mpsc::Sender<SpillRequest>,
+}
+
+enum SpillRequest {
+    Write(Vec<SpilledItem>),
+    ReadLast(tokio::sync::oneshot::Sender<std::io::Result<Option<Vec<SpilledItem>>>>),
+}
+
+pub struct SpilledItem {
+    pub time: i64,
+    pub level: GameOutputLogLevel,
+    pub text: Arc<[Arc<str>]>,
+}
+
+impl SpillFile {
+    /// The file is created inside the folder once the first items are written
+    pub fn new(dir: Arc<Path>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("game-output-spill".into())
+            .spawn(move || run(&dir, receiver));
+        if let Err(error) = spawned {
+            log::error!("Unable to start thread for evicted game output: {error}");
+        }
+        Self { sender }
+    }
+
+    pub fn write(&self, items: Vec<SpilledItem>) {
+        _ = self.sender.send(SpillRequest::Write(items));
+    }
+
+    /// Reads back the most recently written items, the space they took up in the file is reused
+    pub fn read_last(&self) -> tokio::sync::oneshot::Receiver<std::io::Result<Option<Vec<SpilledItem>>>> {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        _ = self.sender.send(SpillRequest::ReadLast(send));
+        recv
+    }
+}
+
+/// Handles requests until the [`SpillFile`] is dropped, which removes the file
+fn run(dir: &Path, receiver: mpsc::Receiver<SpillRequest>) {
+    let mut store: Option<std::io::Result<SpillStore>> = None;
+    for request in receiver {
+        match request {
+            SpillRequest::Write(items) => {
+                let store = store.get_or_insert_with(|| {
+                    SpillStore::create(dir).inspect_err(|error| log::error!("Unable to create file for evicted game output: {error}"))
+                });
+                if let Ok(store) = store && let Err(error) = store.write(&items) {
+                    log::error!("Unable to write evicted game output: {error}");
+                }
+            },
+            SpillRequest::ReadLast(send) => {
+                let result = match &mut store {
+                    Some(Ok(store)) => store.read_last(),
+                    _ => Ok(None),
+                };
+                _ = send.send(result);
+            },
+        }
+    }
+}
+
+/// The file behind a [`SpillFile`], only readable by the current user
+struct SpillStore {
+    path: PathBuf,
+    file: File,
+    /// Evicted chunks in the order they were written, the last one holds the newest lines
+    chunks: Vec<SpilledChunk>,
+    end: u64,
+}
+
+struct SpilledChunk {
+    offset: u64,
+    len: u64,
+}
+
+impl SpillStore {
+    fn create(dir: &Path) -> std::io::Result<Self> {
+        let mut dir_builder = std::fs::DirBuilder::new();
+        dir_builder.recursive(true);
+        let mut options = File::options();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+            dir_builder.mode(0o700);
+            options.mode(0o600);
+        }
+        dir_builder.create(dir)?;
+
+        let path = dir.join(format!("{:016x}", rand::random::<u64>()));
+        let file = options.open(&path)?;
+        Ok(Self {
+            path,
+            file,
+            chunks: Vec::new(),
+            end: 0,
+        })
+    }
+
+    fn write(&mut self, items: &[SpilledItem]) -> std::io::Result<()> {
+        self.file.seek(SeekFrom::Start(self.end))?;
+        let mut writer = BufWriter::new(&mut self.file);
+
+        let mut len = 0;
+        for item in items {
+            writer.write_all(&item.time.to_le_bytes())?;
+            writer.write_all(&[level_to_byte(item.level)])?;
+            writer.write_all(&(item.text.len() as u32).to_le_bytes())?;
+            len += 13;
+            for line in item.text.iter() {
+                writer.write_all(&(line.len() as u32).to_le_bytes())?;
+                writer.write_all(line.as_bytes())?;
+                len += 4 + line.len() as u64;
+            }
+        }
+        writer.flush()?;
+
+        self.chunks.push(SpilledChunk {
+            offset: self.end,
+            len,
+        });
+        self.end += len;
+        Ok(())
+    }
+
+    /// Reads back the most recently written chunk. The space it took up is reused by the next write
+    fn read_last(&mut self) -> std::io::Result<Option<Vec<SpilledItem>>> {
+        let Some(chunk) = self.chunks.pop() else {
+            return Ok(None);
+        };
+        self.end = chunk.offset;
+
+        let mut bytes = vec![0; chunk.len as usize];
+        self.file.seek(SeekFrom::Start(chunk.offset))?;
+        self.file.read_exact(&mut bytes)?;
+
+        let mut reader = bytes.as_slice();
+        let mut items = Vec::new();
+        while !reader.is_empty() {
+            let time = i64::from_le_bytes(read_array(&mut reader)?);
+            let [level] = read_array(&mut reader)?;
+            let count = u32::from_le_bytes(read_array(&mut reader)?);
+
+            let mut text = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let len = u32::from_le_bytes(read_array(&mut reader)?) as usize;
+                let Some((line, rest)) = reader.split_at_checked(len) else {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                };
+                text.push(Arc::from(String::from_utf8_lossy(line)));
+                reader = rest;
+            }
+
+            items.push(SpilledItem {
+                time,
+                level: byte_to_level(level),
+                text: text.into(),
+            });
+        }
+        Ok(Some(items))
+    }
+}
+
+impl Drop for SpillStore {
+    fn drop(&mut self) {
+        _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut &[u8]) -> std::io::Result<[u8; N]> {
+    let mut array = [0; N];
+    reader.read_exact(&mut array)?;
+    Ok(array)
+}
+
+fn level_to_byte(level: GameOutputLogLevel) -> u8 {
+    match level {
+        GameOutputLogLevel::Fatal => 0,
+        GameOutputLogLevel::Error => 1,
+        GameOutputLogLevel::Warn => 2,
+        GameOutputLogLevel::Info => 3,
+        GameOutputLogLevel::Debug => 4,
+        GameOutputLogLevel::Trace => 5,
+        GameOutputLogLevel::Other => 6,
+    }
+}
+
+fn byte_to_level(byte: u8) -> GameOutputLogLevel {
+    match byte {
+        0 => GameOutputLogLevel::Fatal,
+        1 => GameOutputLogLevel::Error,
+        2 => GameOutputLogLevel::Warn,
+        3 => GameOutputLogLevel::Info,
+        4 => GameOutputLogLevel::Debug,
+        5 => GameOutputLogLevel::Trace,
+        _ => GameOutputLogLevel::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(time: i64, level: GameOutputLogLevel, lines: &[&str]) -> SpilledItem {
+        SpilledItem {
+            time,
+            level,
+            text: lines.iter().map(|line| Arc::from(*line)).collect(),
+        }
+    }
+
+    fn summary(items: &[SpilledItem]) -> Vec<(i64, u8, Vec<String>)> {
+        items.iter().map(|item| (item.time, level_to_byte(item.level), item.text.iter().map(|line| line.to_string()).collect())).collect()
+    }
+
+    #[test]
+    fn chunks_are_read_back_newest_first() {
+        let dir = std::env::temp_dir().join(format!("pandora-spill-test-{:016x}", rand::random::<u64>()));
+        let first = [item(1, GameOutputLogLevel::Info, &["first", "second line"]), item(2, GameOutputLogLevel::Error, &[])];
+        let second = [item(3, GameOutputLogLevel::Other, &["ünïcödé"])];
+
+        let mut store = SpillStore::create(&dir).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&store.path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        store.write(&first).unwrap();
+        store.write(&second).unwrap();
+        assert_eq!(summary(&store.read_last().unwrap().unwrap()), summary(&second));
+
+        // The space of the chunk that was read back is reused
+        let third = [item(4, GameOutputLogLevel::Warn, &["third"])];
+        store.write(&third).unwrap();
+        assert_eq!(summary(&store.read_last().unwrap().unwrap()), summary(&third));
+        assert_eq!(summary(&store.read_last().unwrap().unwrap()), summary(&first));
+        assert!(store.read_last().unwrap().is_none());
+
+        let path = store.path.clone();
+        drop(store);
+        assert!(!path.exists());
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn requests_are_handled_in_order() {
+        let dir: Arc<Path> = std::env::temp_dir().join(format!("pandora-spill-test-{:016x}", rand::random::<u64>())).into();
+        let spill = SpillFile::new(dir.clone());
+        assert!(spill.read_last().blocking_recv().unwrap().unwrap().is_none());
+
+        spill.write(vec![item(1, GameOutputLogLevel::Debug, &["line"])]);
+        let read = spill.read_last().blocking_recv().unwrap().unwrap().unwrap();
+        assert_eq!(summary(&read), vec![(1, level_to_byte(GameOutputLogLevel::Debug), vec!["line".to_string()])]);
+
+        drop(spill);
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
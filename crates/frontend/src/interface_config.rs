@@ -1,11 +1,12 @@
 use std::{io::Write, path::Path, sync::Arc, time::Duration};
 
-use gpui::{App, SharedString, Task};
+use gpui::{point, px, size, App, Bounds, SharedString, Task, WindowBounds};
 use rand::RngCore;
-use schema::modrinth::ModrinthProjectType;
+use rustc_hash::FxHashMap;
+use schema::{modrinth::ModrinthProjectType, version_manifest::{MinecraftVersionLink, MinecraftVersionType}};
 use serde::{Deserialize, Serialize};
 
-use crate::ui::SerializedPageType;
+use crate::{component::log_navigation::SavedLogBookmarks, keymap::Keybind, pages::instance::instance_page::InstanceSubpageType, ui::SerializedPageType};
 
 struct InterfaceConfigHolder {
     config: InterfaceConfig,
@@ -19,6 +20,20 @@ impl gpui::Global for InterfaceConfigHolder {}
 pub struct InterfaceConfig {
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub active_theme: SharedString,
+    /// Locale of the interface, empty for the default language
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub language: SharedString,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub theme_mode: ThemeModeSetting,
+    /// RGB color replacing the primary color of the theme
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub accent_color: Option<u32>,
+    /// Replaces the titlebar of the operating system with one holding the navigation and account switcher
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub custom_titlebar: bool,
+    /// Blurs whatever is behind the window into its background, only on platforms that support it
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub window_vibrancy: bool,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub main_page: SerializedPageType,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
@@ -31,16 +46,179 @@ pub struct InterfaceConfig {
     pub modrinth_install_normally: bool,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub modrinth_page_project_type: ModrinthProjectType,
+    /// Replaced by `launch_window_behavior`, only read to migrate older configs
+    #[serde(default, deserialize_with = "schema::try_deserialize", skip_serializing)]
+    hide_main_window_on_launch: bool,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub launch_window_behavior: LaunchWindowBehavior,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub instances_view_mode: InstancesViewMode,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub instance_activate_action: InstanceActivateAction,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub version_filter: VersionFilter,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub disable_game_output_colors: bool,
+    /// Lines kept in memory by each game output window
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub game_output_line_limit: GameOutputLineLimit,
+    /// Lines over the limit are written to a temporary file instead of being dropped
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub spill_game_output_to_disk: bool,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub main_window_bounds: Option<SavedWindowBounds>,
+    /// Last subpage that was open for each instance, by instance name
     #[serde(default, deserialize_with = "schema::try_deserialize")]
-    pub hide_main_window_on_launch: bool,
+    pub instance_subpages: FxHashMap<SharedString, InstanceSubpageType>,
+    /// Bookmarked lines of saved logs, the most recently changed last
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub log_bookmarks: Vec<SavedLogBookmarks>,
+    /// Shortcuts that were changed from their defaults
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub keybindings: FxHashMap<Keybind, SharedString>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeModeSetting {
+    #[default]
+    Dark,
+    Light,
+    /// Follows the light or dark appearance of the operating system
+    System,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstancesViewMode {
+    #[default]
+    Table,
+    Grid,
+    Compact,
+}
+
+/// What happens when an instance is double-clicked, or Enter is pressed while it's selected
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceActivateAction {
+    #[default]
+    OpenPage,
+    Launch,
+    OpenFolder,
+}
+
+/// What happens to the main window when a game is launched
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchWindowBehavior {
+    #[default]
+    KeepOpen,
+    /// Minimized, and restored once no game is running anymore
+    Minimize,
+    /// Closed, and opened again once no game is running anymore
+    Close,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameOutputLineLimit {
+    Lines50k,
+    #[default]
+    Lines100k,
+    Lines250k,
+    Unlimited,
 }
 
+impl GameOutputLineLimit {
+    pub fn lines(self) -> Option<usize> {
+        match self {
+            GameOutputLineLimit::Lines50k => Some(50_000),
+            GameOutputLineLimit::Lines100k => Some(100_000),
+            GameOutputLineLimit::Lines250k => Some(250_000),
+            GameOutputLineLimit::Unlimited => None,
+        }
+    }
+}
 
+/// Kinds of Minecraft versions offered when creating an instance besides releases
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionFilter {
+    #[serde(default)]
+    pub show_snapshots: bool,
+    /// Betas and alphas from before the first release
+    #[serde(default)]
+    pub show_old_versions: bool,
+    #[serde(default)]
+    pub show_april_fools: bool,
+}
+
+impl VersionFilter {
+    pub fn allows(self, version: &MinecraftVersionLink) -> bool {
+        if version.is_april_fools() {
+            return self.show_april_fools;
+        }
+        match version.r#type {
+            MinecraftVersionType::Release => true,
+            MinecraftVersionType::Snapshot => self.show_snapshots,
+            MinecraftVersionType::OldBeta | MinecraftVersionType::OldAlpha => self.show_old_versions,
+        }
+    }
+}
+
+/// Position and size of the main window, restored on the next start
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindowBounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+impl SavedWindowBounds {
+    pub fn from_window_bounds(window_bounds: WindowBounds) -> Self {
+        let (bounds, maximized) = match window_bounds {
+            WindowBounds::Windowed(bounds) => (bounds, false),
+            WindowBounds::Maximized(bounds) => (bounds, true),
+            // Start windowed again instead of taking over the whole screen
+            WindowBounds::Fullscreen(bounds) => (bounds, false),
+        };
+
+        Self {
+            x: bounds.origin.x.into(),
+            y: bounds.origin.y.into(),
+            width: bounds.size.width.into(),
+            height: bounds.size.height.into(),
+            maximized,
+        }
+    }
+
+    /// Returns none if the window wouldn't be visible on any of the current displays,
+    /// eg. after a monitor was disconnected
+    pub fn to_window_bounds(self, cx: &App) -> Option<WindowBounds> {
+        let bounds = Bounds::new(point(px(self.x), px(self.y)), size(px(self.width), px(self.height)));
+        if !cx.displays().iter().any(|display| display.bounds().intersects(&bounds)) {
+            return None;
+        }
+
+        if self.maximized {
+            Some(WindowBounds::Maximized(bounds))
+        } else {
+            Some(WindowBounds::Windowed(bounds))
+        }
+    }
+}
 
 impl InterfaceConfig {
     pub fn init(cx: &mut App, path: Arc<Path>) {
+        let mut config: Self = try_read_json(&path);
+        if config.hide_main_window_on_launch {
+            config.hide_main_window_on_launch = false;
+            config.launch_window_behavior = LaunchWindowBehavior::Close;
+        }
+
         cx.set_global(InterfaceConfigHolder {
-            config: try_read_json(&path),
+            config,
             write_task: None,
             path,
         });
@@ -0,0 +1,158 @@
+use gpui::{App, KeyBinding, Keystroke, NoAction, SharedString};
+use serde::{Deserialize, Serialize};
+
+use crate::{interface_config::InterfaceConfig, CloseWindow, FocusSearch, NavigateBack, NavigateForward, NewInstance, OpenCommandPalette, OpenDebugConsole, OpenFileWatches, Quit, ts};
+
+/// Shortcuts that can be changed from the settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Keybind {
+    NewInstance,
+    FocusSearch,
+    CommandPalette,
+    NavigateBack,
+    NavigateForward,
+    CloseWindow,
+    Quit,
+}
+
+impl Keybind {
+    pub const ALL: [Keybind; 7] = [
+        Keybind::NewInstance,
+        Keybind::FocusSearch,
+        Keybind::CommandPalette,
+        Keybind::NavigateBack,
+        Keybind::NavigateForward,
+        Keybind::CloseWindow,
+        Keybind::Quit,
+    ];
+
+    pub fn name(self) -> SharedString {
+        match self {
+            Keybind::NewInstance => ts!("keybind.new_instance"),
+            Keybind::FocusSearch => ts!("keybind.focus_search"),
+            Keybind::CommandPalette => ts!("keybind.command_palette"),
+            Keybind::NavigateBack => ts!("keybind.navigate_back"),
+            Keybind::NavigateForward => ts!("keybind.navigate_forward"),
+            Keybind::CloseWindow => ts!("keybind.close_window"),
+            Keybind::Quit => ts!("keybind.quit"),
+        }
+    }
+
+    pub fn default_keystroke(self) -> &'static str {
+        match self {
+            Keybind::NewInstance => "secondary-n",
+            Keybind::FocusSearch => "secondary-f",
+            Keybind::CommandPalette => "secondary-k",
+            Keybind::NavigateBack => "alt-left",
+            Keybind::NavigateForward => "alt-right",
+            Keybind::CloseWindow => "secondary-w",
+            Keybind::Quit => "secondary-q",
+        }
+    }
+
+    /// The keystroke from the interface config, or the default if it hasn't been changed
+    pub fn keystroke(self, cx: &App) -> SharedString {
+        InterfaceConfig::get(cx).keybindings.get(&self).cloned()
+            .unwrap_or(SharedString::new_static(self.default_keystroke()))
+    }
+
+    fn binding(self, keystroke: &str) -> KeyBinding {
+        match self {
+            Keybind::NewInstance => KeyBinding::new(keystroke, NewInstance, None),
+            Keybind::FocusSearch => KeyBinding::new(keystroke, FocusSearch, None),
+            Keybind::CommandPalette => KeyBinding::new(keystroke, OpenCommandPalette, None),
+            Keybind::NavigateBack => KeyBinding::new(keystroke, NavigateBack, None),
+            Keybind::NavigateForward => KeyBinding::new(keystroke, NavigateForward, None),
+            Keybind::CloseWindow => KeyBinding::new(keystroke, CloseWindow, None),
+            Keybind::Quit => KeyBinding::new(keystroke, Quit, None),
+        }
+    }
+}
+
+/// Shortcuts of the developer tools, they can't be changed
+const FIXED_KEYSTROKES: [&str; 2] = ["secondary-alt-shift-w", "secondary-alt-shift-d"];
+
+pub fn init(cx: &mut App) {
+    let mut bindings = vec![
+        KeyBinding::new(FIXED_KEYSTROKES[0], OpenFileWatches, None),
+        KeyBinding::new(FIXED_KEYSTROKES[1], OpenDebugConsole, None),
+    ];
+    for keybind in Keybind::ALL {
+        let keystroke = keybind.keystroke(cx);
+        if Keystroke::parse(&keystroke).is_ok() {
+            bindings.push(keybind.binding(&keystroke));
+        } else {
+            log::warn!("Invalid keystroke {:?} for {:?}, using the default", keystroke, keybind);
+            bindings.push(keybind.binding(keybind.default_keystroke()));
+        }
+    }
+    cx.bind_keys(bindings);
+}
+
+/// The keystroke written the same way however it was typed, e.g. `secondary-n` becomes `ctrl-n` outside macOS
+fn normalize(keystroke: &str) -> Option<SharedString> {
+    Keystroke::parse(keystroke).ok().map(|parsed| parsed.unparse().into())
+}
+
+impl Keybind {
+    fn normalized_default(self) -> SharedString {
+        normalize(self.default_keystroke()).unwrap_or(SharedString::new_static(self.default_keystroke()))
+    }
+
+    /// The keystroke that was bound by [`init`] or [`rebind`]
+    fn bound_keystroke(self, cx: &App) -> SharedString {
+        normalize(&self.keystroke(cx)).unwrap_or_else(|| self.normalized_default())
+    }
+}
+
+/// Changes the keystrokes of shortcuts, an empty keystroke resets the shortcut to its default. Nothing is
+/// changed if any keystroke can't be parsed or would be used twice, so shortcuts can be swapped in one go
+pub fn rebind(changes: &[(Keybind, &str)], cx: &mut App) -> Result<(), SharedString> {
+    let mut keystrokes = Vec::with_capacity(Keybind::ALL.len());
+    for keybind in Keybind::ALL {
+        let old = keybind.bound_keystroke(cx);
+        let new = match changes.iter().find(|(changed, _)| *changed == keybind).map(|(_, keystroke)| keystroke.trim()) {
+            Some("") => keybind.normalized_default(),
+            Some(keystroke) => normalize(keystroke)
+                .ok_or_else(|| SharedString::new(rust_i18n::t!("keybind.invalid", keystroke = keystroke)))?,
+            None => old.clone(),
+        };
+        keystrokes.push((keybind, old, new));
+    }
+
+    for (index, (keybind, _, new)) in keystrokes.iter().enumerate() {
+        if FIXED_KEYSTROKES.iter().any(|fixed| normalize(fixed).as_ref() == Some(new)) {
+            return Err(SharedString::new(rust_i18n::t!("keybind.reserved", keystroke = new)));
+        }
+        if let Some((other, _, _)) = keystrokes[index + 1..].iter().find(|(_, _, other_new)| other_new == new) {
+            return Err(SharedString::new(rust_i18n::t!("keybind.conflict", keystroke = new, first = keybind.name(), second = other.name())));
+        }
+    }
+
+    // Bindings can't be removed individually and later bindings take precedence, so keystrokes that are no
+    // longer used are disabled before the changed shortcuts are bound again. Disabling a keystroke that
+    // another shortcut moves to would disable that shortcut as well
+    let mut bindings = Vec::new();
+    for (_, old, new) in &keystrokes {
+        if old != new && !keystrokes.iter().any(|(_, _, other_new)| other_new == old) {
+            bindings.push(KeyBinding::new(old, NoAction, None));
+        }
+    }
+    for (keybind, old, new) in &keystrokes {
+        if old != new {
+            bindings.push(keybind.binding(new));
+        }
+    }
+    cx.bind_keys(bindings);
+
+    let keybindings = &mut InterfaceConfig::get_mut(cx).keybindings;
+    for (keybind, _, new) in keystrokes {
+        if new == keybind.normalized_default() {
+            keybindings.remove(&keybind);
+        } else {
+            keybindings.insert(keybind, new);
+        }
+    }
+    Ok(())
+}
@@ -4,19 +4,20 @@ use std::{
     path::{Path, PathBuf}, sync::{Arc, atomic::AtomicBool}
 };
 
-use bridge::
-    handle::{BackendHandle, FrontendReceiver}
-;
+use bridge::{
+    handle::{BackendHandle, FrontendHandle, FrontendReceiver}, message::MessageToFrontend
+};
 use gpui::*;
 use gpui_component::{
-    notification::{Notification, NotificationType}, Root, StyledExt, WindowExt
+    notification::NotificationType, Root, StyledExt
 };
 use indexmap::IndexMap;
 use parking_lot::RwLock;
+use schema::backend_config::{ProxyConfig, ProxyMode};
 
 use crate::{
-    entity::{
-        DataEntities, PanicMessages, account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata
+    component::crash_bundle::WriteCrashBundle, entity::{
+        DataEntities, PanicMessages, account::AccountEntries, backend_settings::BackendSettingsState, following::FollowedProjectsState, install_queue::InstallQueueState, instance::InstanceEntries, lan_games::LanGamesState, launcher_lock::LauncherLockState, launcher_update::LauncherUpdateState, metadata::FrontendMetadata, notifications::{push_notification, NotificationCenter}, search_index::SearchIndex
     }, interface_config::InterfaceConfig, processor::Processor, root::{LauncherRoot, LauncherRootGlobal}
 };
 
@@ -24,14 +25,26 @@ pub mod component;
 pub mod entity;
 pub mod game_output;
 pub mod modals;
+pub mod navigation;
 pub mod pages;
 pub mod interface_config;
+pub mod keymap;
 pub mod png_render_cache;
 pub mod processor;
 pub mod root;
+pub mod theme;
 pub mod ui;
+pub mod window_manager;
+#[cfg(test)]
+mod translation_check;
+
+rust_i18n::i18n!("locales", fallback = "en");
 
-rust_i18n::i18n!("locales");
+/// Locale used when no language has been chosen in the settings
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Bulk messages handled in a single update, bounded so queued control messages aren't held back for long
+const MAX_BULK_MESSAGES_PER_UPDATE: usize = 256;
 
 macro_rules! ts {
     ($($all:tt)*) => {
@@ -40,6 +53,35 @@ macro_rules! ts {
 }
 pub(crate) use ts;
 
+/// Like `ts!`, but keys without a translation are shown title-cased instead of as the raw key,
+/// e.g. a new Modrinth category `world-generation` becomes "World Generation"
+pub(crate) fn ts_or_title_case(key: &str) -> SharedString {
+    let translated = rust_i18n::t!(key);
+    if translated != key {
+        return SharedString::new_static(ustr::ustr(&translated).as_str());
+    }
+
+    let title_case = key.split(['-', '_']).filter(|word| !word.is_empty()).map(|word| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+            None => String::new(),
+        }
+    }).collect::<Vec<_>>().join(" ");
+    SharedString::new_static(ustr::ustr(&title_case).as_str())
+}
+
+/// Sets the locale from the interface config. Translated text is looked up on every render,
+/// so refreshing the windows afterwards is enough to switch languages without restarting
+pub(crate) fn apply_language(cx: &App) {
+    let language = &InterfaceConfig::get(cx).language;
+    if language.is_empty() || !rust_i18n::available_locales!().contains(&language.as_ref()) {
+        rust_i18n::set_locale(DEFAULT_LANGUAGE);
+    } else {
+        rust_i18n::set_locale(language);
+    }
+}
+
 #[derive(rust_embed::RustEmbed)]
 #[folder = "../../assets"]
 #[include = "icons/**/*.svg"]
@@ -68,23 +110,39 @@ pub const MAIN_FONT: &'static str = "Inter 24pt 24pt";
 #[cfg(not(windows))]
 pub const MAIN_FONT: &'static str = "Inter 24pt";
 
-actions!([Quit, CloseWindow]);
+actions!([Quit, CloseWindow, OpenFileWatches, OpenDebugConsole, NewInstance, FocusSearch, OpenCommandPalette, NavigateBack, NavigateForward]);
 
+/// Runs the frontend until the app quits, returns whether the launcher should be restarted
 pub fn start(
     launcher_dir: PathBuf,
+    portable: bool,
     panic_message: Arc<RwLock<Option<String>>>,
     deadlock_message: Arc<RwLock<Option<String>>>,
+    write_crash_bundle: WriteCrashBundle,
     backend_handle: BackendHandle,
+    frontend_handle: FrontendHandle,
     recv: FrontendReceiver,
-) {
-    let http_client = std::sync::Arc::new(
-        reqwest_client::ReqwestClient::user_agent(
-            "PandoraLauncher/0.1.0 (https://github.com/Moulberry/PandoraLauncher)",
-        )
-        .unwrap(),
-    );
-
-    Application::new().with_http_client(http_client).with_assets(Assets).run(move |cx: &mut App| {
+    proxy: ProxyConfig,
+    proxy_password: Arc<RwLock<Option<String>>>,
+) -> bool {
+    let restart_requested = Arc::new(AtomicBool::new(false));
+    let should_restart = restart_requested.clone();
+
+    let http_client = std::sync::Arc::new(create_http_client(&proxy, proxy_password));
+
+    let application = Application::new().with_http_client(http_client).with_assets(Assets);
+
+    // macOS hands files that are opened with the launcher to the running app instead of passing them as arguments
+    application.on_open_urls(move |urls| {
+        let paths: Arc<[PathBuf]> = urls.iter()
+            .filter_map(|url| url::Url::parse(url).ok()?.to_file_path().ok())
+            .collect();
+        if !paths.is_empty() {
+            frontend_handle.send(MessageToFrontend::OpenFiles { paths });
+        }
+    });
+
+    application.run(move |cx: &mut App| {
         let _ = cx.text_system().add_fonts(vec![
             Assets.load("fonts/inter/Inter-Regular.ttf").unwrap().unwrap(),
             Assets.load("fonts/roboto-mono/RobotoMono-Regular.ttf").unwrap().unwrap(),
@@ -93,27 +151,16 @@ pub fn start(
         gpui_component::init(cx);
         InterfaceConfig::init(cx, launcher_dir.join("interface.json").into());
 
-        gpui_component::Theme::change(gpui_component::ThemeMode::Dark, None, cx);
+        apply_language(cx);
+        theme::apply(cx);
 
         let theme_folder = launcher_dir.join("themes");
 
+        // Apply the custom theme again once the theme folder has been read, and whenever it changes
         _ = gpui_component::ThemeRegistry::watch_dir(theme_folder.clone(), cx, move |cx| {
-            let theme_name = InterfaceConfig::get(cx).active_theme.clone();
-            if theme_name.is_empty() {
-                return;
-            }
-
-            let Some(theme) = gpui_component::ThemeRegistry::global(cx).themes().get(&SharedString::new(theme_name.trim_ascii())).cloned() else {
-                return;
-            };
-
-            gpui_component::Theme::global_mut(cx).apply_config(&theme);
+            theme::apply(cx);
         });
 
-        let theme = gpui_component::Theme::global_mut(cx);
-        theme.font_family = SharedString::new_static(MAIN_FONT);
-        theme.scrollbar_show = gpui_component::scroll::ScrollbarShow::Always;
-
         cx.on_app_quit(|cx| {
             InterfaceConfig::force_save(cx);
             async {}
@@ -130,10 +177,7 @@ pub fn start(
             }
         }).detach();
 
-        cx.bind_keys([
-            KeyBinding::new("secondary-q", Quit, None),
-            KeyBinding::new("secondary-w", CloseWindow, None),
-        ]);
+        keymap::init(cx);
 
         cx.on_action(|_: &Quit, cx| {
             cx.quit();
@@ -144,37 +188,84 @@ pub fn start(
         });
         let metadata = cx.new(|_| FrontendMetadata::new(backend_handle.clone()));
         let accounts = cx.new(|_| AccountEntries::default());
+        let launcher_lock = cx.new(|_| LauncherLockState::default());
+        let launcher_update = cx.new(|_| LauncherUpdateState::default());
+        let backend_settings = cx.new(|_| BackendSettingsState::default());
+        let followed_projects = cx.new(|_| FollowedProjectsState::default());
+        let install_queue = cx.new(|_| InstallQueueState::default());
+        let lan_games = cx.new(|_| LanGamesState::default());
+        let notifications = cx.new(|_| NotificationCenter::default());
+        NotificationCenter::set_global(notifications.clone(), cx);
+        let search_index = cx.new(|_| SearchIndex::default());
         let data = DataEntities {
             instances,
             metadata,
             backend_handle,
+            background_tasks: recv.background_tasks().clone(),
             accounts,
+            launcher_lock,
+            launcher_update,
+            backend_settings,
+            followed_projects,
+            install_queue,
+            lan_games,
+            notifications,
+            search_index,
             theme_folder: theme_folder.into(),
+            portable,
             panic_messages: Arc::new(PanicMessages {
                 panic_message,
                 deadlock_message,
+                restart_requested,
+                launcher_log: launcher_dir.join("launcher.log").into(),
+                write_crash_bundle,
             })
         };
 
-        open_main_window(&data, Some((recv, main_window_hidden)), cx);
+        let game_output_spill_dir = launcher_dir.join("temp").join("gameoutput");
+        open_main_window(&data, Some((recv, main_window_hidden, game_output_spill_dir.into())), cx);
     });
+
+    should_restart.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// The proxy password is read by the backend after startup, so it's looked up for every request
+fn create_http_client(proxy: &ProxyConfig, proxy_password: Arc<RwLock<Option<String>>>) -> reqwest_client::ReqwestClient {
+    const USER_AGENT: &str = "PandoraLauncher/0.1.0 (https://github.com/Moulberry/PandoraLauncher)";
+
+    let client = match proxy.mode {
+        ProxyMode::System => reqwest_client::ReqwestClient::user_agent(USER_AGENT),
+        ProxyMode::Direct => reqwest_client::ReqwestClient::no_proxy_and_user_agent(USER_AGENT),
+        ProxyMode::Http | ProxyMode::Socks5 => match proxy.url() {
+            Ok(Some(url)) => reqwest_client::ReqwestClient::proxy_with_password_and_user_agent(url, move || proxy_password.read().clone(), USER_AGENT),
+            Ok(None) => reqwest_client::ReqwestClient::user_agent(USER_AGENT),
+            Err(error) => {
+                log::error!("Invalid proxy configuration, ignoring it: {}", error);
+                reqwest_client::ReqwestClient::user_agent(USER_AGENT)
+            },
+        },
+    };
+    client.unwrap()
 }
 
-pub fn open_main_window(data: &DataEntities, start_processor: Option<(FrontendReceiver, Arc<AtomicBool>)>, cx: &mut App) -> AnyWindowHandle {
+pub fn open_main_window(data: &DataEntities, start_processor: Option<(FrontendReceiver, Arc<AtomicBool>, Arc<Path>)>, cx: &mut App) -> AnyWindowHandle {
+    let custom_titlebar = InterfaceConfig::get(cx).custom_titlebar;
     let handle = cx.open_window(
         WindowOptions {
             app_id: Some("PandoraLauncher".into()),
             window_min_size: Some(size(px(360.0), px(240.0))),
             titlebar: Some(TitlebarOptions {
                 title: Some(SharedString::new_static("Pandora")),
-                ..Default::default()
+                ..if custom_titlebar { gpui_component::TitleBar::title_bar_options() } else { Default::default() }
             }),
-            window_decorations: Some(WindowDecorations::Server),
+            window_decorations: Some(if custom_titlebar { WindowDecorations::Client } else { WindowDecorations::Server }),
+            window_background: theme::window_background(cx),
+            window_bounds: InterfaceConfig::get(cx).main_window_bounds.and_then(|bounds| bounds.to_window_bounds(cx)),
             ..Default::default()
         },
         |window, cx| {
-            if let Some((mut recv, main_window_hidden)) = start_processor {
-                let mut processor = Processor::new(data.clone(), window.window_handle(), main_window_hidden);
+            if let Some((mut recv, main_window_hidden, game_output_spill_dir)) = start_processor {
+                let mut processor = Processor::new(data.clone(), window.window_handle(), main_window_hidden, game_output_spill_dir);
 
                 while let Some(message) = recv.try_recv() {
                     processor.process(message, cx);
@@ -184,6 +275,14 @@ pub fn open_main_window(data: &DataEntities, start_processor: Option<(FrontendRe
                     while let Some(message) = recv.recv().await {
                         _ = cx.update(|cx| {
                             processor.process(message, cx);
+
+                            // Game output can arrive much faster than it can be shown, handle what's already queued at once
+                            for _ in 0..MAX_BULK_MESSAGES_PER_UPDATE {
+                                let Some(message) = recv.try_recv_bulk() else {
+                                    break;
+                                };
+                                processor.process(message, cx);
+                            }
                         });
                     }
                 }).detach();
@@ -194,6 +293,7 @@ pub fn open_main_window(data: &DataEntities, start_processor: Option<(FrontendRe
             let launcher_root = cx.new(|cx| LauncherRoot::new(&data, window, cx));
             cx.set_global(LauncherRootGlobal {
                 root: launcher_root.clone(),
+                custom_titlebar,
             });
             cx.new(|cx| Root::new(launcher_root, window, cx))
         },
@@ -223,18 +323,32 @@ pub(crate) fn is_single_component_path(path: &str) -> bool {
 }
 
 #[inline]
-pub(crate) fn labelled(label: &'static str, element: impl IntoElement) -> Div {
+pub(crate) fn labelled(label: impl Into<SharedString>, element: impl IntoElement) -> Div {
     gpui_component::v_flex().gap_0p5().child(div().text_sm().font_medium().child(label)).child(element)
 }
 
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
 pub(crate) fn open_folder(path: &Path, window: &mut Window, cx: &mut App) {
     if path.is_dir() {
         if let Err(err) = open::that_detached(path) {
-            let notification: Notification = (NotificationType::Error, SharedString::from(format!("Unable to open folder: {err}"))).into();
-            window.push_notification(notification.autohide(false), cx);
+            push_notification(NotificationType::Error, SharedString::new(rust_i18n::t!("common.open_folder_failed", error = err)), window, cx);
         }
     } else {
-        let notification: Notification = (NotificationType::Error, SharedString::from("Unable to open folder: not a directory")).into();
-        window.push_notification(notification.autohide(false), cx);
+        push_notification(NotificationType::Error, ts!("common.not_a_directory"), window, cx);
     }
 }
@@ -0,0 +1,67 @@
+use bridge::modal_action::{BackgroundTask, BackgroundTaskKind};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, sheet::Sheet, v_flex, ActiveTheme as _, Disableable, Sizable
+};
+
+use crate::{component::progress_bar::ProgressBar, entity::DataEntities, ts};
+
+/// Lists the work the backend is doing in the background, tasks that support it can be cancelled
+pub fn build_background_tasks_sheet(data: &DataEntities) -> impl Fn(Sheet, &mut Window, &mut App) -> Sheet + 'static {
+    let background_tasks = data.background_tasks.clone();
+    move |sheet, window, cx| {
+        let tasks = background_tasks.get();
+
+        let content = if tasks.is_empty() {
+            v_flex().child(div().text_color(cx.theme().muted_foreground).child(ts!("background_tasks.empty")))
+        } else {
+            v_flex().gap_2().children(tasks.iter().map(|task| render_task(task, cx)))
+        };
+
+        sheet
+            .title(ts!("background_tasks.title"))
+            .overlay_top(crate::root::sheet_margin_top(window, cx))
+            .child(content)
+    }
+}
+
+fn render_task(task: &BackgroundTask, cx: &App) -> Div {
+    let theme = cx.theme();
+
+    let mut progress_bar = ProgressBar::new();
+    if let Some(progress_amount) = task.tracker.get_float() {
+        progress_bar.amount = progress_amount;
+    }
+
+    let cancel = task.cancel.clone().map(|cancel| {
+        Button::new(("cancel", task.tracker.id()))
+            .label(ts!("common.cancel"))
+            .small()
+            .danger()
+            .disabled(cancel.is_cancelled())
+            .on_click(move |_, _, _| {
+                cancel.cancel();
+            })
+    });
+
+    v_flex()
+        .gap_1()
+        .p_2()
+        .rounded(theme.radius)
+        .border_1()
+        .border_color(theme.border)
+        .child(h_flex()
+            .gap_2()
+            .child(div().flex_1().min_w_0().text_sm().child(crate::modals::generic::tracker_label(&task.tracker)))
+            .child(div().text_xs().text_color(theme.muted_foreground).child(kind_name(task.kind)))
+            .children(cancel))
+        .child(progress_bar)
+}
+
+fn kind_name(kind: BackgroundTaskKind) -> SharedString {
+    match kind {
+        BackgroundTaskKind::Metadata => ts!("background_tasks.metadata"),
+        BackgroundTaskKind::Install => ts!("background_tasks.install"),
+        BackgroundTaskKind::WorldLoad => ts!("background_tasks.worlds"),
+    }
+}
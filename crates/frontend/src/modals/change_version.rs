@@ -0,0 +1,125 @@
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, VersionCompatibility}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, spinner::Spinner, v_flex, ActiveTheme as _, WindowExt
+};
+use parking_lot::RwLock;
+use ustr::Ustr;
+
+use crate::ts;
+
+/// Mods listed by name before the rest are summarized as a count
+const MAX_LISTED_MODS: usize = 8;
+
+/// Asks for confirmation before changing the Minecraft version of an instance, listing the mods that
+/// don't support the new version and offering to back up the instance first
+pub fn open_change_version(
+    instance: InstanceID,
+    old_version: Ustr,
+    new_version: Ustr,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let compatibility: Arc<RwLock<Option<Result<VersionCompatibility, Arc<str>>>>> = Arc::new(RwLock::new(None));
+    let backup = Arc::new(AtomicBool::new(true));
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::CheckVersionCompatibility {
+        id: instance,
+        version: new_version,
+        channel: send,
+    });
+
+    window.spawn(cx, {
+        let compatibility = compatibility.clone();
+        async move |cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            *compatibility.write() = Some(result);
+            _ = cx.update(|window, _| window.refresh());
+        }
+    }).detach();
+
+    let message = SharedString::new(rust_i18n::t!("change_version.message", old = old_version, new = new_version));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let mods = match &*compatibility.read() {
+            None => h_flex().gap_2().child(Spinner::new()).child(ts!("change_version.checking_mods")),
+            Some(Err(error)) => h_flex().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("change_version.check_failed", error = error))),
+            Some(Ok(compatibility)) => {
+                let mut mods = v_flex().gap_1();
+                if !compatibility.incompatible.is_empty() {
+                    mods = mods
+                        .child(div().text_color(theme.danger).child(SharedString::new(rust_i18n::t!(
+                            "change_version.incompatible",
+                            count = compatibility.incompatible.len(),
+                            version = new_version
+                        ))))
+                        .child(render_mod_list(&compatibility.incompatible));
+                }
+                if !compatibility.unchecked.is_empty() {
+                    mods = mods.child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(rust_i18n::t!(
+                        "change_version.unchecked",
+                        count = compatibility.unchecked.len()
+                    ))));
+                }
+                if compatibility.incompatible.is_empty() && compatibility.unchecked.is_empty() {
+                    mods = mods.child(ts!("change_version.all_compatible"));
+                }
+                h_flex().child(mods)
+            },
+        };
+
+        let change = Button::new("change-version").warning().label(ts!("change_version.change")).on_click({
+            let backup = backup.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                let modal_action = ModalAction::default();
+                backend_handle.send(MessageToBackend::ChangeInstanceMinecraftVersion {
+                    id: instance,
+                    version: new_version,
+                    backup: backup.load(Ordering::Relaxed),
+                    modal_action: modal_action.clone(),
+                });
+
+                window.close_all_dialogs(cx);
+                crate::modals::generic::show_modal(window, cx, ts!("change_version.changing"), ts!("change_version.error"), modal_action);
+            }
+        });
+        let cancel = Button::new("cancel").label(ts!("common.cancel")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(ts!("change_version.title"))
+            .child(v_flex()
+                .gap_3()
+                .child(message.clone())
+                .child(mods)
+                .child(Checkbox::new("backup")
+                    .label(ts!("change_version.backup"))
+                    .checked(backup.load(Ordering::Relaxed))
+                    .on_click({
+                        let backup = backup.clone();
+                        move |value, _, _| {
+                            backup.store(*value, Ordering::Relaxed);
+                        }
+                    }))
+                .child(h_flex().gap_2().child(change).child(cancel)))
+    });
+}
+
+fn render_mod_list(names: &[Arc<str>]) -> Div {
+    let mut list = v_flex().pl_2().text_sm();
+    for name in names.iter().take(MAX_LISTED_MODS) {
+        list = list.child(SharedString::new(format!("• {}", name)));
+    }
+    if names.len() > MAX_LISTED_MODS {
+        list = list.child(SharedString::new(rust_i18n::t!("common.and_more", count = names.len() - MAX_LISTED_MODS)));
+    }
+    list
+}
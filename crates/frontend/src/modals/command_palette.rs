@@ -0,0 +1,186 @@
+use std::sync::atomic::Ordering;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::{AtomicBridgeDataLoadState, BridgeDataLoadState, MessageToBackend}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    h_flex, input::{Input, InputEvent, InputState}, scroll::ScrollableElement, v_flex, ActiveTheme as _, WindowExt
+};
+
+use crate::{entity::{instance::InstanceEntries, search_index::{SearchIndex, SearchResult, SearchResultKind}, DataEntities}, pages::instance::instance_page::InstanceSubpageType, ts, ui::PageType};
+
+const MAX_RESULTS: usize = 50;
+
+struct CommandPalette {
+    search_index: Entity<SearchIndex>,
+    instances: Entity<InstanceEntries>,
+    backend_handle: BackendHandle,
+    input: Entity<InputState>,
+    results: Vec<SearchResult>,
+    _input_subscription: Subscription,
+    _search_index_subscription: Subscription,
+}
+
+/// Searches the names of instances and their mods, worlds and servers, opening the page a result is shown on
+pub fn open_command_palette(data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let search_index = data.search_index.clone();
+    let instances = data.instances.clone();
+    let backend_handle = data.backend_handle.clone();
+    let palette = cx.new(|cx| {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("command_palette.placeholder")));
+
+        let _input_subscription = cx.subscribe_in(&input, window, |palette: &mut CommandPalette, _, event: &InputEvent, window, cx| {
+            match event {
+                InputEvent::Change => {
+                    palette.update_results(cx);
+                },
+                InputEvent::PressEnter { .. } => {
+                    if let Some(result) = palette.results.first().cloned() {
+                        open_result(&result, window, cx);
+                    }
+                },
+                _ => {},
+            }
+        });
+        let _search_index_subscription = cx.observe(&search_index, |palette, _, cx| {
+            palette.update_results(cx);
+        });
+
+        CommandPalette {
+            search_index,
+            instances,
+            backend_handle,
+            input,
+            results: Vec::new(),
+            _input_subscription,
+            _search_index_subscription,
+        }
+    });
+
+    window.open_dialog(cx, {
+        let palette = palette.clone();
+        move |dialog, _, _| {
+            dialog.title(ts!("command_palette.title")).child(palette.clone())
+        }
+    });
+
+    let input = palette.read(cx).input.clone();
+    input.update(cx, |input, cx| input.focus(window, cx));
+}
+
+impl CommandPalette {
+    fn update_results(&mut self, cx: &mut Context<Self>) {
+        let query = self.input.read(cx).value();
+        self.results = self.search_index.read(cx).search(&query, MAX_RESULTS);
+        cx.notify();
+    }
+
+    /// Instances with content that was never loaded, so it isn't indexed yet
+    fn unloaded_instances(&self, cx: &App) -> Vec<InstanceID> {
+        let unloaded = |state: &AtomicBridgeDataLoadState| state.load(Ordering::SeqCst) == BridgeDataLoadState::Unloaded;
+        self.instances.read(cx).entries.values()
+            .map(|entry| entry.read(cx))
+            .filter(|entry| unloaded(&entry.mods_state) || unloaded(&entry.worlds_state) || unloaded(&entry.servers_state))
+            .map(|entry| entry.id)
+            .collect()
+    }
+
+    /// Loading every instance can take a while, so it's only done when asked for
+    fn load_instances(&self, ids: &[InstanceID], cx: &App) {
+        let entries = &self.instances.read(cx).entries;
+        for id in ids {
+            let Some(entry) = entries.get(id) else {
+                continue;
+            };
+            let entry = entry.read(cx);
+            let id = *id;
+            if entry.mods_state.load(Ordering::SeqCst).should_send_load_request() {
+                self.backend_handle.send(MessageToBackend::RequestLoadMods { id });
+            }
+            if entry.worlds_state.load(Ordering::SeqCst).should_send_load_request() {
+                self.backend_handle.send(MessageToBackend::RequestLoadWorlds { id });
+            }
+            if entry.servers_state.load(Ordering::SeqCst).should_send_load_request() {
+                self.backend_handle.send(MessageToBackend::RequestLoadServers { id });
+            }
+        }
+    }
+}
+
+fn open_result(result: &SearchResult, window: &mut Window, cx: &mut App) {
+    let subpage = match result.kind {
+        SearchResultKind::Mod => InstanceSubpageType::Mods,
+        SearchResultKind::Instance | SearchResultKind::World | SearchResultKind::Server => InstanceSubpageType::Quickplay,
+    };
+    window.close_all_dialogs(cx);
+    crate::root::switch_page(PageType::InstancePage(result.instance, subpage), &[PageType::Instances], window, cx);
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let has_query = !self.input.read(cx).value().trim().is_empty();
+        let unloaded = if has_query { self.unloaded_instances(cx) } else { Vec::new() };
+
+        let results = if self.results.is_empty() {
+            let message = if !has_query {
+                ts!("command_palette.empty_query")
+            } else {
+                ts!("command_palette.nothing_found")
+            };
+            div().p_2().text_sm().text_color(theme.muted_foreground).child(message).into_any_element()
+        } else {
+            let mut list = v_flex();
+            for (index, result) in self.results.iter().enumerate() {
+                let location = match result.kind {
+                    SearchResultKind::Instance => ts!("command_palette.instance"),
+                    SearchResultKind::Mod => SharedString::new(rust_i18n::t!("command_palette.mod_in", instance = result.instance_name)),
+                    SearchResultKind::World => SharedString::new(rust_i18n::t!("command_palette.world_in", instance = result.instance_name)),
+                    SearchResultKind::Server => SharedString::new(rust_i18n::t!("command_palette.server_in", instance = result.instance_name)),
+                };
+                list = list.child(h_flex()
+                    .id(("result", index))
+                    .justify_between()
+                    .gap_4()
+                    .px_2()
+                    .py_1()
+                    .rounded(theme.radius)
+                    .cursor_pointer()
+                    // Enter opens the first result
+                    .when(index == 0, |this| this.bg(theme.secondary))
+                    .hover(|this| this.bg(theme.secondary_hover))
+                    .child(div().truncate().child(result.title.clone()))
+                    .child(div().flex_shrink_0().text_sm().text_color(theme.muted_foreground).child(location))
+                    .on_click({
+                        let result = result.clone();
+                        move |_, window, cx| {
+                            open_result(&result, window, cx);
+                        }
+                    }));
+            }
+            div().max_h_96().overflow_y_scrollbar().child(list).into_any_element()
+        };
+
+        let load_more = (!unloaded.is_empty()).then(|| {
+            h_flex()
+                .id("load-unloaded")
+                .px_2()
+                .py_1()
+                .rounded(theme.radius)
+                .cursor_pointer()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .hover(|this| this.bg(theme.secondary_hover))
+                .child(SharedString::new(rust_i18n::t!("command_palette.search_unloaded", count = unloaded.len())))
+                .on_click(cx.listener(move |palette, _, _, cx| {
+                    palette.load_instances(&unloaded, cx);
+                }))
+        });
+
+        v_flex()
+            .gap_2()
+            .child(Input::new(&self.input))
+            .child(results)
+            .children(load_more)
+    }
+}
@@ -3,9 +3,11 @@ use std::sync::{atomic::{AtomicBool, AtomicU8, Ordering}, Arc};
 use bridge::{handle::BackendHandle, instance::InstanceID};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, input::{Input, InputEvent, InputState}, v_flex, Disableable, WindowExt
+    button::{Button, ButtonVariants}, checkbox::Checkbox, input::{Input, InputEvent, InputState}, v_flex, ActiveTheme as _, Disableable, WindowExt
 };
 
+use crate::ts;
+
 pub fn open_delete_instance(
     instance: InstanceID,
     instance_name: SharedString,
@@ -15,10 +17,12 @@ pub fn open_delete_instance(
 ) {
     let stage = Arc::new(AtomicU8::new(0));
     let correct_name = Arc::new(AtomicBool::new(false));
+    let permanent = Arc::new(AtomicBool::new(false));
 
-    let title = SharedString::new(format!("Delete Instance: {}", instance_name));
-    let warning_message = SharedString::new(format!("This will permanently delete the '{}' instance and associated saves, resourcepacks, mods, configuration files, and more. These files will not be recoverable", instance_name));
-    let confirm_message = SharedString::new(format!("To confirm, type '{}' in the box below", instance_name));
+    let title = SharedString::new(rust_i18n::t!("delete_instance.title", name = instance_name));
+    let warning_message = SharedString::new(rust_i18n::t!("delete_instance.trash_warning", name = instance_name));
+    let permanent_warning_message = SharedString::new(rust_i18n::t!("delete_instance.permanent_warning", name = instance_name));
+    let confirm_message = SharedString::new(rust_i18n::t!("delete_instance.confirm_prompt", name = instance_name));
 
     let input_state = cx.new(|cx| InputState::new(window, cx));
 
@@ -31,13 +35,13 @@ pub fn open_delete_instance(
         }
     });
 
-    window.open_dialog(cx, move |dialog, _, _| {
+    window.open_dialog(cx, move |dialog, _, cx| {
         let _ = &_input_subscription;
 
         let content = match stage.load(Ordering::Relaxed) {
             0 => {
                 v_flex()
-                    .child(Button::new("delete").label("I want to delete this instance").on_click({
+                    .child(Button::new("delete").label(ts!("delete_instance.want_to_delete")).on_click({
                         let stage = stage.clone();
                         move |_, _, _| {
                             stage.store(1, Ordering::Relaxed);
@@ -45,10 +49,20 @@ pub fn open_delete_instance(
                     }))
             }
             1 => {
+                let is_permanent = permanent.load(Ordering::Relaxed);
                 v_flex()
                     .gap_2()
-                    .child(warning_message.clone())
-                    .child(Button::new("confirm").label("I have read and understand these effects").on_click({
+                    .child(if is_permanent { permanent_warning_message.clone() } else { warning_message.clone() })
+                    .child(Checkbox::new("permanent")
+                        .label(ts!("delete_instance.permanent"))
+                        .checked(is_permanent)
+                        .on_click({
+                            let permanent = permanent.clone();
+                            move |value, _, _| {
+                                permanent.store(*value, Ordering::Relaxed);
+                            }
+                        }))
+                    .child(Button::new("confirm").label(ts!("delete_instance.understand")).on_click({
                         let stage = stage.clone();
                         let input_state = input_state.clone();
                         move |_, window, cx| {
@@ -61,18 +75,21 @@ pub fn open_delete_instance(
             }
             2 => {
                 let correct = correct_name.load(Ordering::Relaxed);
+                let is_permanent = permanent.load(Ordering::Relaxed);
+                let label = if is_permanent { ts!("delete_instance.delete_permanently") } else { ts!("delete_instance.move_to_trash") };
                 // .div() and .child(div().h_2()) are workarounds for a weird layout bug
                 // where the Input would be set to its minimum width when confirm_message wrapped
                 div()
                     .child(confirm_message.clone())
                     .child(div().h_2())
-                    .child(Input::new(&input_state).border_color(gpui::red()))
+                    .child(Input::new(&input_state).border_color(cx.theme().danger))
                     .child(div().h_2())
-                    .child(Button::new("confirm").label("Delete this instance").danger().disabled(!correct).on_click({
+                    .child(Button::new("confirm").label(label).danger().disabled(!correct).on_click({
                         let backend_handle = backend_handle.clone();
                         move |_, window, cx| {
                             backend_handle.send(bridge::message::MessageToBackend::DeleteInstance {
-                                id: instance
+                                id: instance,
+                                permanent: is_permanent,
                             });
                             window.close_all_dialogs(cx);
                         }
@@ -1,14 +1,17 @@
-use std::sync::Arc;
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
 
-use bridge::modal_action::{ModalAction, ProgressTrackerFinishType};
+use bridge::modal_action::{ModalAction, ModalActionQuestion, ProgressTracker, ProgressTrackerFinishType, ProgressTrackerUnit};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, dialog::DialogButtonProps, notification::Notification, v_flex, IconName, WindowExt
+    button::{Button, ButtonVariants}, dialog::DialogButtonProps, h_flex, notification::{Notification, NotificationType}, v_flex, IconName, WindowExt
 };
 
-use crate::component::{
-    error_alert::ErrorAlert,
-    progress_bar::{ProgressBar, ProgressBarColor},
+use crate::{
+    component::{
+        error_alert::ErrorAlert,
+        progress_bar::{ProgressBar, ProgressBarColor},
+    },
+    entity::notifications::NotificationCenter,
 };
 
 pub fn show_notification(
@@ -27,10 +30,15 @@ pub fn show_notification_with_note(
     modal_action: ModalAction,
     mut notification: Notification
 ) {
+    // The error is kept in the notification center once, the popup keeps rendering it until it's closed
+    let error_kept = AtomicBool::new(false);
     let notification = notification
         .autohide(false)
         .content(move |notification, window, cx| {
             if let Some(error) = &*modal_action.error.read().unwrap() {
+                if !error_kept.swap(true, Ordering::Relaxed) {
+                    NotificationCenter::push_global(NotificationType::Error, SharedString::new(format!("{}: {}", error_title, error)), cx);
+                }
                 let error_widget = ErrorAlert::new("error", error_title.clone(), error.clone().into());
                 return error_widget.into_any_element();
             }
@@ -75,11 +83,14 @@ pub fn show_notification_with_note(
                     window.request_animation_frame();
                 }
 
-                let title = tracker.get_title();
-                progress_entries.push(div().gap_3().child(SharedString::from(title)).child(progress_bar).opacity(opacity));
+                progress_entries.push(div().gap_3().child(tracker_label(tracker)).child(progress_bar).opacity(opacity));
             }
             drop(trackers);
 
+            if let Some(question) = &*modal_action.question.read().unwrap() {
+                progress_entries.push(render_question(question));
+            }
+
             if let Some(visit_url) = &*modal_action.visit_url.read().unwrap() {
                 let message = SharedString::new(Arc::clone(&visit_url.message));
                 let url = Arc::clone(&visit_url.url);
@@ -170,11 +181,14 @@ pub fn show_modal(
                 window.request_animation_frame();
             }
 
-            let title = tracker.get_title();
-            progress_entries.push(div().gap_3().child(SharedString::from(title)).child(progress_bar).opacity(opacity));
+            progress_entries.push(div().gap_3().child(tracker_label(tracker)).child(progress_bar).opacity(opacity));
         }
         drop(trackers);
 
+        if let Some(question) = &*modal_action.question.read().unwrap() {
+            progress_entries.push(render_question(question));
+        }
+
         if let Some(visit_url) = &*modal_action.visit_url.read().unwrap() {
             let message = SharedString::new(Arc::clone(&visit_url.message));
             let url = Arc::clone(&visit_url.url);
@@ -205,3 +219,39 @@ pub fn show_modal(
         }
     });
 }
+
+fn render_question(question: &Arc<ModalActionQuestion>) -> Div {
+    question.mark_seen();
+
+    let confirm = Button::new("question-confirm").primary().label(SharedString::new(Arc::clone(&question.confirm))).on_click({
+        let question = Arc::clone(question);
+        move |_, _, _| question.answer(true)
+    });
+    let deny = Button::new("question-deny").label(SharedString::new(Arc::clone(&question.deny))).on_click({
+        let question = Arc::clone(question);
+        move |_, _, _| question.answer(false)
+    });
+
+    v_flex()
+        .gap_2()
+        .p_3()
+        .child(SharedString::new(Arc::clone(&question.message)))
+        .child(h_flex().gap_2().child(confirm).child(deny))
+}
+
+pub fn tracker_label(tracker: &ProgressTracker) -> SharedString {
+    let title = tracker.get_title();
+    let (count, total) = tracker.get();
+    if tracker.get_finished_at().is_some() {
+        return SharedString::from(title);
+    }
+    match tracker.unit() {
+        ProgressTrackerUnit::Bytes if total > 0 => {
+            SharedString::new(format!("{} ({} / {})", title, crate::format_size(count as u64), crate::format_size(total as u64)))
+        },
+        ProgressTrackerUnit::BytesUnknownTotal => {
+            SharedString::new(format!("{} ({} / unknown)", title, crate::format_size(count as u64)))
+        },
+        _ => SharedString::from(title),
+    }
+}
@@ -0,0 +1,154 @@
+use std::{path::PathBuf, sync::{atomic::{AtomicBool, Ordering}, Arc}};
+
+use bridge::{
+    handle::BackendHandle, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget},
+    message::MessageToBackend, safe_path::SafePath,
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputState}, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, WindowExt
+};
+use parking_lot::RwLock;
+use schema::{content::ContentSource, loader::Loader, shared_profile::{SharedProfile, SharedProfileContent}};
+
+use crate::ts;
+
+/// Creates a new instance from a profile shared by someone else, downloading its content from Modrinth
+pub fn open_import_shared_profile(path: PathBuf, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let profile: Arc<RwLock<Option<Result<SharedProfile, Arc<str>>>>> = Arc::new(RwLock::new(None));
+    let name_input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.instance_name")));
+    // Off by default, flags from a shared file can run code when the instance is launched
+    let apply_jvm_flags = Arc::new(AtomicBool::new(false));
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::ReadSharedProfile {
+        path,
+        channel: send,
+    });
+
+    window.spawn(cx, {
+        let profile = profile.clone();
+        let name_input = name_input.clone();
+        async move |cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let name = result.as_ref().ok().map(|profile| SharedString::new(profile.name.clone()));
+            *profile.write() = Some(result);
+            _ = cx.update(|window, cx| {
+                if let Some(name) = name {
+                    name_input.update(cx, |input, cx| input.set_value(name, window, cx));
+                }
+                window.refresh();
+            });
+        }
+    }).detach();
+
+    let backend_handle = backend_handle.clone();
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let (details, ready) = match &*profile.read() {
+            None => (h_flex().gap_2().child(Spinner::new()).child(ts!("shared_profile.reading")).into_any_element(), false),
+            Some(Err(error)) => {
+                (div().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("shared_profile.read_failed", error = error))).into_any_element(), false)
+            },
+            Some(Ok(profile)) => {
+                let loader = if profile.loader == Loader::Vanilla {
+                    rust_i18n::t!("common.minecraft_version", version = profile.minecraft_version)
+                } else if let Some(loader_version) = profile.loader_version {
+                    let loader = format!("{} {}", profile.loader.name(), loader_version);
+                    rust_i18n::t!("common.minecraft_version_with_loader", version = profile.minecraft_version, loader = loader)
+                } else {
+                    rust_i18n::t!("common.minecraft_version_with_loader", version = profile.minecraft_version, loader = profile.loader.name())
+                };
+                let content = match profile.content.len() {
+                    0 => ts!("shared_profile.no_content"),
+                    1 => ts!("shared_profile.one_file"),
+                    count => SharedString::new(rust_i18n::t!("shared_profile.files", count = count)),
+                };
+                let details = v_flex()
+                    .gap_1()
+                    .child(div().font_medium().child(SharedString::new(profile.name.clone())))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(loader)))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(content))
+                    .child(crate::labelled(ts!("common.name"), Input::new(&name_input)))
+                    .when_some(profile.enabled_jvm_flags(), |details, flags| {
+                        let checked = apply_jvm_flags.load(Ordering::Relaxed);
+                        details
+                            .child(Checkbox::new("apply-jvm-flags")
+                                .checked(checked)
+                                .label(ts!("shared_profile.apply_jvm_flags"))
+                                .on_click({
+                                    let apply_jvm_flags = apply_jvm_flags.clone();
+                                    move |value, window, _| {
+                                        apply_jvm_flags.store(*value, Ordering::Relaxed);
+                                        window.refresh();
+                                    }
+                                }))
+                            .child(div()
+                                .text_sm()
+                                .font_family("Roboto Mono")
+                                .text_color(if checked { theme.warning } else { theme.muted_foreground })
+                                .child(SharedString::new(flags)))
+                            .child(div()
+                                .text_sm()
+                                .text_color(theme.muted_foreground)
+                                .child(ts!("shared_profile.jvm_flags_warning")))
+                    });
+                (details.into_any_element(), true)
+            },
+        };
+
+        let import = Button::new("import-profile").success().label(ts!("shared_profile.import")).disabled(!ready).on_click({
+            let profile = profile.clone();
+            let name_input = name_input.clone();
+            let backend_handle = backend_handle.clone();
+            let apply_jvm_flags = apply_jvm_flags.clone();
+            move |_, window, cx| {
+                let Some(Ok(profile)) = profile.read().clone() else {
+                    return;
+                };
+                let mut name = name_input.read(cx).value().trim().to_string();
+                if name.is_empty() {
+                    name = profile.name.to_string();
+                }
+
+                let content_install = ContentInstall {
+                    target: InstallTarget::NewInstance { name: name.into(), configuration: Some(profile.configuration(apply_jvm_flags.load(Ordering::Relaxed))) },
+                    loader_hint: profile.loader,
+                    version_hint: Some(profile.minecraft_version.as_str().into()),
+                    files: install_files(&profile.content).into(),
+                };
+                window.close_all_dialogs(cx);
+                crate::root::start_install(content_install, &backend_handle, window, cx);
+            }
+        });
+        let cancel = Button::new("cancel").label(ts!("common.cancel")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(ts!("shared_profile.import_title"))
+            .child(v_flex()
+                .gap_3()
+                .child(details)
+                .child(h_flex().gap_2().child(import).child(cancel)))
+    });
+}
+
+/// Profiles can come from anyone, so files are only placed directly inside the shared content folders
+fn install_files(content: &[SharedProfileContent]) -> Vec<ContentInstallFile> {
+    content.iter()
+        .filter(|content| SharedProfileContent::is_content_path(&content.path))
+        .filter_map(|content| {
+            Some(ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Safe(SafePath::new(&content.path)?),
+                download: ContentDownload::Modrinth {
+                    project_id: content.project_id.clone(),
+                    version_id: Some(content.version_id.clone()),
+                },
+                content_source: ContentSource::ModrinthProject { project: content.project_id.clone() },
+            })
+        })
+        .collect()
+}
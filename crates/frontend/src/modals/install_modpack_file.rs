@@ -0,0 +1,114 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget, ModpackFileInfo},
+    message::MessageToBackend,
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, WindowExt
+};
+use parking_lot::RwLock;
+use schema::{content::ContentSource, loader::Loader};
+
+use crate::{entity::DataEntities, ts};
+
+/// Creates a new instance from a .mrpack file that was opened with the launcher, e.g. by double-clicking it
+pub fn open_install_modpack_file(path: PathBuf, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let Some(file_name) = path.file_name().map(|name| name.to_os_string()) else {
+        return;
+    };
+
+    let info: Arc<RwLock<Option<Result<ModpackFileInfo, Arc<str>>>>> = Arc::new(RwLock::new(None));
+    let name_input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.instance_name")));
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    data.backend_handle.send(MessageToBackend::ReadModpackFile {
+        path: path.clone(),
+        channel: send,
+    });
+
+    window.spawn(cx, {
+        let info = info.clone();
+        let name_input = name_input.clone();
+        async move |cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let name = result.as_ref().ok().map(|info| SharedString::new(info.name.clone()));
+            *info.write() = Some(result);
+            _ = cx.update(|window, cx| {
+                if let Some(name) = name {
+                    name_input.update(cx, |input, cx| input.set_value(name, window, cx));
+                }
+                window.refresh();
+            });
+        }
+    }).detach();
+
+    let backend_handle = data.backend_handle.clone();
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let (details, ready) = match &*info.read() {
+            None => (h_flex().gap_2().child(Spinner::new()).child(ts!("modpack_file.reading")).into_any_element(), false),
+            Some(Err(error)) => {
+                (div().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("modpack_file.read_failed", error = error))).into_any_element(), false)
+            },
+            Some(Ok(info)) => {
+                let latest_release = rust_i18n::t!("modpack_file.latest_release");
+                let minecraft_version = info.minecraft_version.as_deref().unwrap_or(&latest_release);
+                let loader = if info.loader == Loader::Vanilla {
+                    rust_i18n::t!("common.minecraft_version", version = minecraft_version)
+                } else {
+                    rust_i18n::t!("common.minecraft_version_with_loader", version = minecraft_version, loader = info.loader.name())
+                };
+                let details = v_flex()
+                    .gap_1()
+                    .child(div().font_medium().child(SharedString::new(format!("{} {}", info.name, info.version))))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(loader)))
+                    .child(crate::labelled(ts!("common.name"), Input::new(&name_input)));
+                (details.into_any_element(), true)
+            },
+        };
+
+        let install = Button::new("install-modpack").success().label(ts!("common.install")).disabled(!ready).on_click({
+            let info = info.clone();
+            let name_input = name_input.clone();
+            let path = path.clone();
+            let file_name = file_name.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                let Some(Ok(info)) = info.read().clone() else {
+                    return;
+                };
+                let mut name = name_input.read(cx).value().trim().to_string();
+                if name.is_empty() {
+                    name = info.name.to_string();
+                }
+
+                let content_install = ContentInstall {
+                    target: InstallTarget::NewInstance { name: name.into(), configuration: None },
+                    loader_hint: info.loader,
+                    version_hint: info.minecraft_version.clone(),
+                    files: [ContentInstallFile {
+                        replace_old: None,
+                        path: ContentInstallPath::Raw(Path::new("mods").join(&file_name).into()),
+                        download: ContentDownload::File { path: path.clone() },
+                        content_source: ContentSource::Manual,
+                    }].into(),
+                };
+                window.close_all_dialogs(cx);
+                crate::root::start_install(content_install, &backend_handle, window, cx);
+            }
+        });
+        let cancel = Button::new("cancel").label(ts!("common.cancel")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(ts!("modpack_file.title"))
+            .child(v_flex()
+                .gap_3()
+                .child(details)
+                .child(h_flex().gap_2().child(install).child(cancel)))
+    });
+}
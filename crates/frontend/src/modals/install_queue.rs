@@ -0,0 +1,124 @@
+use bridge::{install::{InstallFileState, InstallQueueEntry, InstallQueueFile}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, sheet::Sheet, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, IconName, Sizable
+};
+
+use crate::{entity::DataEntities, ts};
+
+/// Lists queued installs with the state of each file, failed files can be retried one at a time
+pub fn build_install_queue_sheet(data: &DataEntities) -> impl Fn(Sheet, &mut Window, &mut App) -> Sheet + 'static {
+    let data = data.clone();
+    move |sheet, window, cx| {
+        let entries = data.install_queue.read(cx).entries.clone();
+
+        let clear_finished = Button::new("clear-finished")
+            .label(ts!("install_queue.clear_finished"))
+            .disabled(!entries.iter().any(|entry| entry.finished))
+            .on_click({
+                let data = data.clone();
+                move |_, _, cx| {
+                    for entry in data.install_queue.read(cx).entries.iter().filter(|entry| entry.finished) {
+                        data.backend_handle.send(MessageToBackend::RemoveFromInstallQueue { id: entry.id });
+                    }
+                }
+            });
+
+        let content = if entries.is_empty() {
+            v_flex().child(div().text_color(cx.theme().muted_foreground).child(ts!("install_queue.empty")))
+        } else {
+            v_flex().gap_3().children(entries.iter().rev().map(|entry| render_entry(entry, &data, cx)))
+        };
+
+        sheet
+            .title(ts!("install_queue.title"))
+            .overlay_top(crate::root::sheet_margin_top(window, cx))
+            .child(v_flex()
+                .gap_3()
+                .child(h_flex().child(clear_finished))
+                .child(content))
+    }
+}
+
+fn render_entry(entry: &InstallQueueEntry, data: &DataEntities, cx: &App) -> Div {
+    let theme = cx.theme();
+
+    let failed = entry.files.iter().filter(|file| file.state == InstallFileState::Failed).count();
+    let done = entry.files.iter().filter(|file| matches!(file.state, InstallFileState::Verified | InstallFileState::Cached)).count();
+    let running = entry.files.iter().any(|file| matches!(file.state, InstallFileState::Pending | InstallFileState::Downloading | InstallFileState::Verifying));
+
+    let status: SharedString = if entry.finished {
+        ts!("install_queue.installed")
+    } else if failed > 0 && !running {
+        rust_i18n::t!("install_queue.files_failed", failed = failed, total = entry.files.len()).into()
+    } else {
+        rust_i18n::t!("install_queue.files_done", done = done, total = entry.files.len()).into()
+    };
+
+    let mut header = h_flex()
+        .gap_2()
+        .child(div().flex_1().font_weight(FontWeight::SEMIBOLD).child(SharedString::new(entry.title.clone())))
+        .child(div().text_sm().text_color(theme.muted_foreground).child(status));
+    if !running {
+        let id = entry.id;
+        let backend_handle = data.backend_handle.clone();
+        header = header.child(Button::new(("remove", id.0 as usize)).icon(IconName::Close).small().on_click(move |_, _, _| {
+            backend_handle.send(MessageToBackend::RemoveFromInstallQueue { id });
+        }));
+    }
+
+    let files = entry.files.iter().enumerate().map(|(index, file)| render_file(entry, index, file, data, cx));
+
+    v_flex()
+        .gap_1()
+        .p_2()
+        .rounded(theme.radius)
+        .border_1()
+        .border_color(theme.border)
+        .child(header)
+        .children(files)
+}
+
+fn render_file(entry: &InstallQueueEntry, index: usize, file: &InstallQueueFile, data: &DataEntities, cx: &App) -> Div {
+    let theme = cx.theme();
+
+    let (state, color) = match file.state {
+        InstallFileState::Pending => (ts!("install_queue.pending"), theme.muted_foreground),
+        InstallFileState::Downloading => (ts!("install_queue.downloading"), theme.foreground),
+        InstallFileState::Verifying => (ts!("install_queue.verifying"), theme.foreground),
+        InstallFileState::Verified => (ts!("install_queue.verified"), theme.success),
+        InstallFileState::Cached => (ts!("install_queue.cached"), theme.success),
+        InstallFileState::Failed => (ts!("install_queue.failed"), theme.danger),
+    };
+
+    let mut row = h_flex()
+        .gap_2()
+        .text_sm()
+        .when(matches!(file.state, InstallFileState::Downloading | InstallFileState::Verifying), |row| row.child(Spinner::new().small()))
+        .child(div().flex_1().truncate().child(SharedString::new(file.name.clone())))
+        .child(div().text_color(color).child(state));
+
+    if file.state == InstallFileState::Failed {
+        let id = entry.id;
+        let backend_handle = data.backend_handle.clone();
+        row = row.child(Button::new(SharedString::new(format!("retry-{}-{}", id.0, index))).label(ts!("install_queue.retry")).small().warning().on_click(move |_, window, cx| {
+            let modal_action = ModalAction::default();
+            backend_handle.send(MessageToBackend::RetryInstallFile {
+                id,
+                file: index,
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_notification(window, cx, ts!("modrinth_install.install_failed"), modal_action);
+        }));
+    }
+
+    let mut details = v_flex().pl_2().text_xs().text_color(theme.muted_foreground);
+    if let Some(sha1) = &file.sha1 {
+        details = details.child(SharedString::new(format!("SHA1 {}", sha1)));
+    }
+    if let Some(error) = &file.error {
+        details = details.child(div().text_color(theme.danger).child(SharedString::new(error.clone())));
+    }
+
+    v_flex().child(row).child(details)
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::{LaunchPreview, MessageToBackend}, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, IconName, WindowExt
+};
+use parking_lot::RwLock;
+
+use crate::ts;
+
+/// Prepares a launch of the instance without starting the game, then shows the java command that would have run
+pub fn open_launch_command_preview(
+    instance: InstanceID,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+    let command: Arc<RwLock<Option<Result<LaunchPreview, Arc<str>>>>> = Arc::new(RwLock::new(None));
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::PreviewLaunchCommand {
+        id: instance,
+        modal_action: modal_action.clone(),
+        channel: send,
+    });
+
+    window.spawn(cx, {
+        let command = command.clone();
+        async move |cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            *command.write() = Some(result);
+            _ = cx.update(|window, _| window.refresh());
+        }
+    }).detach();
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let content = match &*command.read() {
+            None => {
+                // Downloads happen while preparing, show what's currently being done
+                let status = modal_action.trackers.trackers.read().unwrap().iter()
+                    .rev()
+                    .find(|tracker| tracker.get_finished_at().is_none())
+                    .map(|tracker| SharedString::from(tracker.get_title()))
+                    .unwrap_or(ts!("launch_command.preparing"));
+                h_flex().gap_2().child(Spinner::new()).child(status).into_any_element()
+            },
+            Some(Err(error)) => {
+                div().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("launch_command.failed", error = error))).into_any_element()
+            },
+            Some(Ok(preview)) => {
+                let command = SharedString::new(preview.command.clone());
+                let copy = Button::new("copy-command").icon(IconName::Copy).label(ts!("common.copy")).on_click({
+                    let command = command.clone();
+                    move |_, _, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(command.to_string()));
+                    }
+                });
+                v_flex()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.muted_foreground)
+                        .child(ts!("launch_command.description")))
+                    .child(div()
+                        .max_h_96()
+                        .overflow_y_scrollbar()
+                        .p_2()
+                        .rounded(theme.radius)
+                        .bg(theme.secondary)
+                        .font_family("Roboto Mono")
+                        .text_xs()
+                        .child(command))
+                    .child(h_flex().child(copy))
+                    .into_any_element()
+            },
+        };
+
+        let close = Button::new("close").label(ts!("common.close")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(ts!("launch_command.title"))
+            .child(v_flex().gap_3().child(content).child(h_flex().child(close)))
+    });
+}
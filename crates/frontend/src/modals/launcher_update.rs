@@ -0,0 +1,79 @@
+use std::{sync::atomic::Ordering, time::Duration};
+
+use bridge::{message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, v_flex, ActiveTheme as _, IconName, WindowExt
+};
+
+use crate::{component::markdown::Markdown, entity::DataEntities, ts};
+
+/// Shows the changelog of the available launcher update, with a button to install it if this build can update itself
+pub fn open_launcher_update(data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let Some(update) = data.launcher_update.read(cx).update.clone() else {
+        return;
+    };
+
+    let changelog = Markdown::parse(&update.changelog);
+    let backend_handle = data.backend_handle.clone();
+    let restart_requested = data.panic_messages.restart_requested.clone();
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let changelog = if update.changelog.trim().is_empty() {
+            div().text_color(theme.muted_foreground).child(ts!("launcher_update.no_changelog")).into_any_element()
+        } else {
+            div().max_h_96().overflow_y_scrollbar().child(changelog.render(cx)).into_any_element()
+        };
+
+        let release_page = Button::new("release-page").icon(IconName::ExternalLink).label(ts!("launcher_update.open_release_page")).on_click({
+            let release_url = update.release_url.clone();
+            move |_, _, cx| {
+                cx.open_url(&release_url);
+            }
+        });
+
+        let install = update.can_install.then(|| {
+            Button::new("install-update").success().label(ts!("launcher_update.download_and_restart")).on_click({
+                let backend_handle = backend_handle.clone();
+                let restart_requested = restart_requested.clone();
+                move |_, window, cx| {
+                    window.close_all_dialogs(cx);
+
+                    let modal_action = ModalAction::default();
+                    backend_handle.send(MessageToBackend::DownloadLauncherUpdate {
+                        modal_action: modal_action.clone(),
+                    });
+                    crate::modals::generic::show_modal(window, cx, ts!("launcher_update.updating"),
+                        ts!("launcher_update.update_failed"), modal_action.clone());
+
+                    // The downloaded update replaces the executable when the launcher starts again
+                    let restart_requested = restart_requested.clone();
+                    window.spawn(cx, async move |cx| {
+                        while modal_action.get_finished_at().is_none() {
+                            cx.background_executor().timer(Duration::from_millis(100)).await;
+                        }
+                        if modal_action.error.read().unwrap().is_none() {
+                            restart_requested.store(true, Ordering::SeqCst);
+                            _ = cx.update(|_, cx| cx.quit());
+                        }
+                    }).detach();
+                }
+            })
+        });
+
+        let note = (!update.can_install).then(|| {
+            div().text_sm().text_color(theme.muted_foreground)
+                .child(ts!("launcher_update.cannot_install"))
+        });
+
+        dialog
+            .title(SharedString::new(rust_i18n::t!("launcher_update.available", version = update.version)))
+            .child(v_flex()
+                .gap_3()
+                .child(changelog)
+                .children(note)
+                .child(h_flex().gap_2().children(install).child(release_page)))
+    });
+}
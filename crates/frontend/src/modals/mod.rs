@@ -1,5 +1,16 @@
 pub mod generic;
+pub mod background_tasks;
+pub mod command_palette;
+pub mod launch_command;
+pub mod install_queue;
+pub mod install_modpack_file;
+pub mod import_shared_profile;
+pub mod launcher_update;
 pub mod modrinth_install;
 pub mod modrinth_install_auto;
+pub mod change_version;
 pub mod delete_instance;
+pub mod modpack_update;
+pub mod notifications;
+pub mod server_edits_overwritten;
 pub mod settings;
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceContentID, InstanceID, ModpackUpdateDiff}, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, WindowExt
+};
+use parking_lot::RwLock;
+
+use crate::ts;
+
+/// Files listed by path before the rest are summarized as a count
+const MAX_LISTED_FILES: usize = 8;
+
+/// Shows which files an update of a modpack adds, removes and changes before updating it
+pub fn open_modpack_update(
+    instance: InstanceID,
+    content_id: InstanceContentID,
+    name: SharedString,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let diff: Arc<RwLock<Option<Result<ModpackUpdateDiff, Arc<str>>>>> = Arc::new(RwLock::new(None));
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::GetModpackUpdateDiff {
+        instance,
+        content_id,
+        channel: send,
+    });
+
+    window.spawn(cx, {
+        let diff = diff.clone();
+        async move |cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            *diff.write() = Some(result);
+            _ = cx.update(|window, _| window.refresh());
+        }
+    }).detach();
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let theme = cx.theme();
+
+        let (changes, ready) = match &*diff.read() {
+            None => (h_flex().gap_2().child(Spinner::new()).child(ts!("modpack_update.downloading")), false),
+            Some(Err(error)) => (h_flex().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("modpack_update.check_failed", error = error))), false),
+            Some(Ok(diff)) => {
+                let mut changes = v_flex().gap_2();
+                for (key, files) in [("modpack_update.added", &diff.added), ("modpack_update.updated", &diff.updated), ("modpack_update.removed", &diff.removed)] {
+                    if !files.is_empty() {
+                        changes = changes
+                            .child(SharedString::new(rust_i18n::t!(key, count = files.len())))
+                            .child(render_file_list(files));
+                    }
+                }
+                if diff.added.is_empty() && diff.updated.is_empty() && diff.removed.is_empty() {
+                    changes = changes.child(ts!("modpack_update.no_file_changes"));
+                }
+                if !diff.preserved.is_empty() {
+                    changes = changes
+                        .child(div().text_color(theme.warning).child(SharedString::new(rust_i18n::t!(
+                            "modpack_update.preserved",
+                            count = diff.preserved.len()
+                        ))))
+                        .child(render_file_list(&diff.preserved));
+                }
+                (h_flex().child(changes), true)
+            },
+        };
+
+        let update = Button::new("update-modpack").success().label(ts!("modpack_update.update")).disabled(!ready).on_click({
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                window.close_all_dialogs(cx);
+                crate::root::update_single_mod(instance, content_id, &backend_handle, window, cx);
+            }
+        });
+        let cancel = Button::new("cancel").label(ts!("common.cancel")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(SharedString::new(rust_i18n::t!("modpack_update.title", name = name)))
+            .child(v_flex()
+                .gap_3()
+                .child(div().max_h_96().overflow_y_scrollbar().child(changes))
+                .child(div().text_sm().text_color(theme.muted_foreground)
+                    .child(ts!("modpack_update.applied_on_launch")))
+                .child(h_flex().gap_2().child(update).child(cancel)))
+    });
+}
+
+fn render_file_list(paths: &[Arc<str>]) -> Div {
+    let mut list = v_flex().pl_2().text_sm();
+    for path in paths.iter().take(MAX_LISTED_FILES) {
+        list = list.child(SharedString::new(format!("• {}", path)));
+    }
+    if paths.len() > MAX_LISTED_FILES {
+        list = list.child(SharedString::new(rust_i18n::t!("common.and_more", count = paths.len() - MAX_LISTED_FILES)));
+    }
+    list
+}
@@ -1,15 +1,15 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::{cmp::Ordering, path::Path, sync::Arc};
 
-use bridge::{install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::InstanceID, meta::MetadataRequest, safe_path::SafePath};
+use bridge::{install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::InstanceID, message::MessageToBackend, meta::MetadataRequest, safe_path::SafePath, serial::AtomicOptionSerial};
 use enumset::EnumSet;
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, notification::NotificationType, select::{SearchableVec, Select, SelectItem, SelectState}, spinner::Spinner, v_flex, IndexPath, WindowExt
+    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, notification::NotificationType, scroll::ScrollableElement, select::{SearchableVec, Select, SelectItem, SelectState}, spinner::Spinner, v_flex, Disableable, IndexPath, WindowExt
 };
 use relative_path::RelativePath;
 use rustc_hash::FxHashMap;
 use schema::{
-    content::ContentSource, loader::Loader, modrinth::{
+    content::ContentSource, loader::{Loader, LoaderCompanion}, modrinth::{
         ModrinthDependency, ModrinthDependencyType, ModrinthLoader, ModrinthProjectType, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthVersionStatus, ModrinthVersionType
     }
 };
@@ -17,9 +17,9 @@ use schema::{
 use crate::{
     component::{error_alert::ErrorAlert, instance_dropdown::InstanceDropdown},
     entity::{
-        instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}, DataEntities
+        instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}, notifications::push_notification, DataEntities
     },
-    root,
+    root, ts,
 };
 
 struct VersionMatrixLoaders {
@@ -38,9 +38,14 @@ struct InstallDialog {
 
     version_matrix: FxHashMap<&'static str, VersionMatrixLoaders>,
     instances: Option<Entity<SelectState<InstanceDropdown>>>,
+    compatible_instances: Arc<[InstanceEntry]>,
+    /// Instances ticked in the list for installing into several at once
+    checked_instances: Vec<InstanceID>,
     unsupported_instances: usize,
 
     target: Option<InstallTarget>,
+    /// Instances that get the same files as `target`
+    extra_targets: Vec<InstanceID>,
 
     last_selected_minecraft_version: Option<SharedString>,
     last_selected_loader: Option<SharedString>,
@@ -52,6 +57,13 @@ struct InstallDialog {
     loader_select_state: Option<Entity<SelectState<Vec<SharedString>>>>,
     skip_loader_check_for_mod_version: bool,
     install_dependencies: bool,
+    install_companion: bool,
+    mods_load_serial: AtomicOptionSerial,
+    /// Mods can be installed disabled, which adds `.disabled` to the filename
+    install_enabled: bool,
+    install_folder_select_state: Option<Entity<SelectState<Vec<InstallFolderItem>>>>,
+    install_folder_count: usize,
+    worlds_load_serial: AtomicOptionSerial,
 
     mod_version_select_state: Option<Entity<SelectState<SearchableVec<ModVersionItem>>>>,
 }
@@ -99,7 +111,7 @@ fn open_from_entity(
             });
             window.open_dialog(cx, move |dialog, _, _| {
                 let _ = &_subscription;
-                dialog.title(title.clone()).child(h_flex().gap_2().child("Loading mod versions...").child(Spinner::new()))
+                dialog.title(title.clone()).child(h_flex().gap_2().child(ts!("modrinth_install.loading_versions")).child(Spinner::new()))
             });
         },
         FrontendMetadataResult::Loaded(versions) => {
@@ -148,12 +160,12 @@ fn open_from_entity(
             }
 
             if version_matrix.is_empty() {
-                open_error_dialog(title.clone(), "No mod versions found".into(), window, cx);
+                open_error_dialog(title.clone(), ts!("modrinth_install.no_versions_found"), window, cx);
                 return;
             }
             if let Some(install_for) = install_for {
                 let Some(instance) = data.instances.read(cx).entries.get(&install_for) else {
-                    open_error_dialog(title.clone(), "Unable to find instance".into(), window, cx);
+                    open_error_dialog(title.clone(), ts!("modrinth_install.instance_not_found"), window, cx);
                     return;
                 };
 
@@ -200,8 +212,11 @@ fn open_from_entity(
                     project_id,
                     version_matrix,
                     instances: None,
+                    compatible_instances: Arc::from([]),
+                    checked_instances: Vec::new(),
                     unsupported_instances: 0,
                     target: Some(InstallTarget::Instance(instance_id)),
+                    extra_targets: Vec::new(),
                     fixed_minecraft_version,
                     minecraft_version_select_state: None,
                     fixed_loader,
@@ -209,6 +224,12 @@ fn open_from_entity(
                     last_selected_minecraft_version: None,
                     skip_loader_check_for_mod_version: false,
                     install_dependencies: true,
+                    install_companion: true,
+                    mods_load_serial: AtomicOptionSerial::default(),
+                    install_enabled: true,
+                    install_folder_select_state: None,
+                    install_folder_count: 0,
+                    worlds_load_serial: AtomicOptionSerial::default(),
                     mod_version_select_state: None,
                     last_selected_loader: None,
                 };
@@ -243,7 +264,7 @@ fn open_from_entity(
 
                 let unsupported_instances = instance_entries.read(cx).entries.len().saturating_sub(entries.len());
                 let instances = if !entries.is_empty() {
-                    let dropdown = InstanceDropdown::create(entries, window, cx);
+                    let dropdown = InstanceDropdown::create(entries.clone(), window, cx);
                     dropdown.update(cx, |dropdown, cx| {
                         dropdown.set_selected_index(Some(IndexPath::default()), window, cx)
                     });
@@ -261,8 +282,11 @@ fn open_from_entity(
                     project_id,
                     version_matrix,
                     instances,
+                    compatible_instances: entries,
+                    checked_instances: Vec::new(),
                     unsupported_instances,
                     target: None,
+                    extra_targets: Vec::new(),
                     fixed_minecraft_version: None,
                     minecraft_version_select_state: None,
                     fixed_loader: None,
@@ -270,6 +294,12 @@ fn open_from_entity(
                     last_selected_minecraft_version: None,
                     skip_loader_check_for_mod_version: false,
                     install_dependencies: true,
+                    install_companion: true,
+                    mods_load_serial: AtomicOptionSerial::default(),
+                    install_enabled: true,
+                    install_folder_select_state: None,
+                    install_folder_count: 0,
+                    worlds_load_serial: AtomicOptionSerial::default(),
                     mod_version_select_state: None,
                     last_selected_loader: None,
                 };
@@ -278,7 +308,7 @@ fn open_from_entity(
         },
         FrontendMetadataResult::Error(message) => {
             window.open_dialog(cx, move |modal, _, _| {
-                modal.title(title.clone()).child(ErrorAlert::new("error", "Error requesting from Modrinth".into(), message.clone()))
+                modal.title(title.clone()).child(ErrorAlert::new("error", ts!("modrinth_project.request_failed"), message.clone()))
             });
         },
     }
@@ -303,11 +333,11 @@ impl InstallDialog {
 
         if self.target.is_none() {
             let create_instance_label = match self.project_type {
-                ModrinthProjectType::Mod => "Create new instance with this mod",
-                ModrinthProjectType::Modpack => "Create new instance with this modpack",
-                ModrinthProjectType::Resourcepack => "Create new instance with this resourcepack",
-                ModrinthProjectType::Shader => "Create new instance with this shader",
-                ModrinthProjectType::Other => "Create new instance with this file",
+                ModrinthProjectType::Mod => ts!("modrinth_install.create_instance_mod"),
+                ModrinthProjectType::Modpack => ts!("modrinth_install.create_instance_modpack"),
+                ModrinthProjectType::Resourcepack => ts!("modrinth_install.create_instance_resourcepack"),
+                ModrinthProjectType::Shader => ts!("modrinth_install.create_instance_shader"),
+                ModrinthProjectType::Other => ts!("modrinth_install.create_instance_other"),
             };
 
             let content = v_flex()
@@ -324,34 +354,31 @@ impl InstallDialog {
                                 .w_full()
                                 .gap_0p5()
                                 .child(
-                                    Select::new(instances).placeholder("Select an instance").title_prefix("Instance: "),
+                                    Select::new(instances).placeholder(ts!("modrinth_install.select_instance")).title_prefix(ts!("modrinth_install.instance_prefix")),
                                 )
                                 .when(self.unsupported_instances > 0, |content| {
                                     content
-                                        .child(format!("({} instances were incompatible)", self.unsupported_instances))
+                                        .child(SharedString::new(rust_i18n::t!("modrinth_install.incompatible_instances", count = self.unsupported_instances)))
                                 }),
                         )
                         .when_some(selected_instance, |dialog, instance| {
-                            dialog.child(Button::new("instance").success().h_full().label("Add to instance").on_click(
+                            dialog.child(Button::new("instance").success().h_full().label(ts!("modrinth_install.add_to_instance")).on_click(
                                 cx.listener(move |this, _, _, _| {
-                                    this.target = Some(InstallTarget::Instance(instance.id));
-                                    this.fixed_minecraft_version = Some(instance.configuration.minecraft_version.as_str());
-                                    if (this.project_type == ModrinthProjectType::Mod
-                                        || this.project_type == ModrinthProjectType::Modpack)
-                                        && instance.configuration.loader != Loader::Vanilla
-                                    {
-                                        this.fixed_loader = Some(instance.configuration.loader.as_modrinth_loader());
-                                    }
+                                    this.select_targets(&instance, Vec::new());
                                 }),
                             ))
                         });
 
-                    content.child(button_and_dropdown).child("— OR —")
+                    content.child(button_and_dropdown).child(ts!("modrinth_install.or"))
+                })
+                .when(self.compatible_instances.len() > 1, |content| {
+                    content.child(self.render_instance_checklist(cx)).child(ts!("modrinth_install.or"))
                 })
                 .child(Button::new("create").success().label(create_instance_label).on_click(cx.listener(
                     |this, _, _, _| {
                         this.target = Some(InstallTarget::NewInstance {
                             name: "New Instance".into(),
+                            configuration: None,
                         });
                     },
                 )));
@@ -568,11 +595,11 @@ impl InstallDialog {
             .cloned();
 
         let mod_version_prefix = match self.project_type {
-            ModrinthProjectType::Mod => "Mod Version: ",
-            ModrinthProjectType::Modpack => "Modpack version: ",
-            ModrinthProjectType::Resourcepack => "Pack version: ",
-            ModrinthProjectType::Shader => "Shader version: ",
-            ModrinthProjectType::Other => "File version: ",
+            ModrinthProjectType::Mod => ts!("modrinth_install.mod_version_prefix"),
+            ModrinthProjectType::Modpack => ts!("modrinth_install.modpack_version_prefix"),
+            ModrinthProjectType::Resourcepack => ts!("modrinth_install.pack_version_prefix"),
+            ModrinthProjectType::Shader => ts!("modrinth_install.shader_version_prefix"),
+            ModrinthProjectType::Other => ts!("modrinth_install.file_version_prefix"),
         };
 
         let required_dependencies = selected_mod_version.as_ref().and_then(|version| {
@@ -587,34 +614,86 @@ impl InstallDialog {
             })
         }).unwrap_or_default();
 
+        let mut loader_hint = Loader::Unknown;
+        if let Some(selected_loader) = &selected_loader {
+            let modrinth_loader = ModrinthLoader::from_name(selected_loader);
+            match modrinth_loader {
+                ModrinthLoader::Fabric => loader_hint = Loader::Fabric,
+                ModrinthLoader::Forge => loader_hint = Loader::Forge,
+                ModrinthLoader::NeoForge => loader_hint = Loader::NeoForge,
+                _ => {}
+            }
+        }
+
+        // Every target decides on its own whether it needs the companion, the checkbox applies to all of them
+        let targets = self.targets();
+        let missing_companions: Vec<Option<LoaderCompanion>> = targets.iter()
+            .map(|target| self.missing_companion(target, loader_hint, &required_dependencies, cx))
+            .collect();
+        let missing_companion = missing_companions.iter().flatten().next().copied();
+
+        let install_folders = self.install_folders(cx);
+        if !install_folders.is_empty() && self.install_folder_count != install_folders.len() {
+            // Rebuilt when the instance's worlds finish loading
+            let previous = self.install_folder_select_state.as_ref()
+                .and_then(|state| state.read(cx).selected_index(cx));
+            self.install_folder_count = install_folders.len();
+            self.install_folder_select_state = Some(cx.new(|cx| {
+                let mut select_state = SelectState::new(install_folders, None, window, cx);
+                select_state.set_selected_index(Some(previous.unwrap_or_default()), window, cx);
+                select_state
+            }));
+        }
+        let selected_folder = self.install_folder_select_state.as_ref()
+            .and_then(|state| state.read(cx).selected_value().cloned());
+
         let content = v_flex()
             .gap_2()
             .child(
                 Select::new(self.minecraft_version_select_state.as_ref().unwrap())
                     .disabled(self.fixed_minecraft_version.is_some())
-                    .title_prefix("Game Version: "),
+                    .title_prefix(ts!("modrinth_install.game_version_prefix")),
             )
             .child(
                 Select::new(self.loader_select_state.as_ref().unwrap())
                     .disabled(self.fixed_loader.is_some() || self.skip_loader_check_for_mod_version)
-                    .title_prefix("Loader: "),
+                    .title_prefix(ts!("modrinth_install.loader_prefix")),
             )
             .when_some(self.mod_version_select_state.as_ref(), |modal, mod_versions| {
                 modal
                     .child(Select::new(mod_versions).title_prefix(mod_version_prefix))
                     .when(!required_dependencies.is_empty(), |modal| {
                         modal.child(Checkbox::new("install_deps").checked(self.install_dependencies).label(if required_dependencies.len() == 1 {
-                            SharedString::new_static("Install 1 dependency")
+                            ts!("modrinth_install.install_one_dependency")
                         } else {
-                            SharedString::new(format!("Install {} dependencies", required_dependencies.len()))
+                            SharedString::new(rust_i18n::t!("modrinth_install.install_dependencies", count = required_dependencies.len()))
                         }).on_click(cx.listener(|dialog, value, _, _| {
                             dialog.install_dependencies = *value;
                         })))
                     })
-                    .child(Button::new("install").success().label("Install").on_click(cx.listener(
+                    .when(self.project_type == ModrinthProjectType::Mod, |modal| {
+                        modal.child(Checkbox::new("install_enabled")
+                            .checked(self.install_enabled)
+                            .label(ts!("modrinth_install.enable_after_install"))
+                            .on_click(cx.listener(|dialog, value, _, _| {
+                                dialog.install_enabled = *value;
+                            })))
+                    })
+                    .when_some(self.install_folder_select_state.as_ref(), |modal, folders| {
+                        modal.child(Select::new(folders).title_prefix(ts!("modrinth_install.install_to")))
+                    })
+                    .when_some(missing_companion, |modal, companion| {
+                        modal.child(Checkbox::new("install_companion")
+                            .checked(self.install_companion)
+                            .label(SharedString::new(rust_i18n::t!("companion.also_install", name = companion.name, loader = loader_hint.name())))
+                            .on_click(cx.listener(|dialog, value, _, _| {
+                                dialog.install_companion = *value;
+                            })))
+                    })
+                    .child(Button::new("install").success().label(ts!("common.install")).on_click(cx.listener(
                         move |this, _, window, cx| {
                             let Some(selected_mod_version) = selected_mod_version.as_ref() else {
-                                window.push_notification((NotificationType::Error, "No mod version selected"), cx);
+                                push_notification(NotificationType::Error, ts!("modrinth_install.no_version_selected"), window, cx);
                                 return;
                             };
 
@@ -624,44 +703,37 @@ impl InstallDialog {
                                 .find(|file| file.primary)
                                 .unwrap_or(selected_mod_version.files.first().unwrap());
 
-                            let path = match this.project_type {
-                                ModrinthProjectType::Mod => RelativePath::new("mods").join(&*install_file.filename),
-                                ModrinthProjectType::Modpack => RelativePath::new("mods").join(&*install_file.filename),
-                                ModrinthProjectType::Resourcepack => RelativePath::new("resourcepacks").join(&*install_file.filename),
-                                ModrinthProjectType::Shader => RelativePath::new("shaderpacks").join(&*install_file.filename),
-                                ModrinthProjectType::Other => {
-                                    window.push_notification((NotificationType::Error, "Unable to install 'other' project type"), cx);
+                            let mut replace_old = None;
+                            let path = match (this.project_type, &selected_folder) {
+                                (_, Some(InstallFolder::WorldResources { folder_name, level_path })) => {
+                                    // A world only has a single resource pack, which replaces the previous one
+                                    replace_old = Some(level_path.join("resources.zip").into());
+                                    RelativePath::new("saves").join(&**folder_name).join("resources.zip")
+                                },
+                                (_, Some(InstallFolder::Folder(folder))) => RelativePath::new(folder).join(&*install_file.filename),
+                                (ModrinthProjectType::Mod, None) if !this.install_enabled => {
+                                    RelativePath::new("mods").join(format!("{}.disabled", install_file.filename))
+                                },
+                                (ModrinthProjectType::Mod, None) => RelativePath::new("mods").join(&*install_file.filename),
+                                (ModrinthProjectType::Modpack, None) => RelativePath::new("mods").join(&*install_file.filename),
+                                (ModrinthProjectType::Resourcepack, None) => RelativePath::new("resourcepacks").join(&*install_file.filename),
+                                (ModrinthProjectType::Shader, None) => RelativePath::new("shaderpacks").join(&*install_file.filename),
+                                (ModrinthProjectType::Other, None) => {
+                                    push_notification(NotificationType::Error, ts!("modrinth_install.other_project_type"), window, cx);
                                     return;
                                 },
                             };
 
                             let Some(path) = SafePath::from_relative_path(&path) else {
-                                window.push_notification((NotificationType::Error, "Invalid/dangerous filename"), cx);
+                                push_notification(NotificationType::Error, ts!("modrinth_install.invalid_filename"), window, cx);
                                 return;
                             };
 
-                            let mut target = this.target.clone().unwrap();
-
-                            let mut loader_hint = Loader::Unknown;
-                            if let Some(selected_loader) = &selected_loader {
-                                let modrinth_loader = ModrinthLoader::from_name(selected_loader);
-                                match modrinth_loader {
-                                    ModrinthLoader::Fabric => loader_hint = Loader::Fabric,
-                                    ModrinthLoader::Forge => loader_hint = Loader::Forge,
-                                    ModrinthLoader::NeoForge => loader_hint = Loader::NeoForge,
-                                    _ => {}
-                                }
-                            }
-
                             let mut version_hint = None;
                             if let Some(selected_minecraft_version) = &selected_minecraft_version {
                                 version_hint = Some(selected_minecraft_version.as_str().into());
                             }
 
-                            if let InstallTarget::NewInstance { name } = &mut target {
-                                *name = this.name.as_str().into();
-                            }
-
                             let mut files = Vec::new();
 
                             if this.install_dependencies {
@@ -679,7 +751,7 @@ impl InstallDialog {
                             }
 
                             files.push(ContentInstallFile {
-                                replace_old: None,
+                                replace_old,
                                 path: bridge::install::ContentInstallPath::Safe(path),
                                 download: ContentDownload::Url {
                                     url: install_file.url.clone(),
@@ -691,15 +763,30 @@ impl InstallDialog {
                                 },
                             });
 
-                            let content_install = ContentInstall {
-                                target,
-                                loader_hint,
-                                version_hint,
-                                files: files.into(),
-                            };
-
                             window.close_dialog(cx);
-                            root::start_install(content_install, &this.data.backend_handle, window, cx);
+
+                            // One install per target, the downloads end up in the content library and are only fetched once
+                            let shared_files: Arc<[ContentInstallFile]> = files.into();
+                            for (mut target, missing_companion) in this.targets().into_iter().zip(missing_companions.iter().copied()) {
+                                if let InstallTarget::NewInstance { name, .. } = &mut target {
+                                    *name = this.name.as_str().into();
+                                }
+
+                                let files = match missing_companion {
+                                    Some(companion) if this.install_companion => {
+                                        std::iter::once(root::companion_install_file(companion)).chain(shared_files.iter().cloned()).collect()
+                                    },
+                                    _ => shared_files.clone(),
+                                };
+
+                                let content_install = ContentInstall {
+                                    target,
+                                    loader_hint,
+                                    version_hint: version_hint.clone(),
+                                    files,
+                                };
+                                root::start_install(content_install, &this.data.backend_handle, window, cx);
+                            }
                         },
                     )),
                 )
@@ -707,6 +794,160 @@ impl InstallDialog {
 
         modal.child(content)
     }
+
+    /// Fixes the game version and loader to the ones of the instance, the extra instances have to match them
+    fn select_targets(&mut self, instance: &InstanceEntry, extra_targets: Vec<InstanceID>) {
+        self.target = Some(InstallTarget::Instance(instance.id));
+        self.extra_targets = extra_targets;
+        self.fixed_minecraft_version = Some(instance.configuration.minecraft_version.as_str());
+        if (self.project_type == ModrinthProjectType::Mod
+            || self.project_type == ModrinthProjectType::Modpack)
+            && instance.configuration.loader != Loader::Vanilla
+        {
+            self.fixed_loader = Some(instance.configuration.loader.as_modrinth_loader());
+        }
+    }
+
+    fn targets(&self) -> Vec<InstallTarget> {
+        self.target.iter().cloned()
+            .chain(self.extra_targets.iter().map(|id| InstallTarget::Instance(*id)))
+            .collect()
+    }
+
+    fn render_instance_checklist(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let checked: Vec<&InstanceEntry> = self.compatible_instances.iter()
+            .filter(|instance| self.checked_instances.contains(&instance.id))
+            .collect();
+
+        // The chosen version is installed into all of them, so they have to agree on what it's for
+        let loader_matters = self.project_type == ModrinthProjectType::Mod || self.project_type == ModrinthProjectType::Modpack;
+        let mismatched = checked.first().is_some_and(|first| checked.iter().any(|instance| {
+            instance.configuration.minecraft_version != first.configuration.minecraft_version
+                || (loader_matters && instance.configuration.loader != first.configuration.loader)
+        }));
+
+        let list = v_flex()
+            .gap_1()
+            .children(self.compatible_instances.iter().map(|instance| {
+                let id = instance.id;
+                let label = SharedString::new(format!("{} ({} {})", instance.title, instance.configuration.loader.name(), instance.configuration.minecraft_version));
+                Checkbox::new(("install_target", id.index))
+                    .checked(self.checked_instances.contains(&id))
+                    .label(label)
+                    .on_click(cx.listener(move |this, value, _, _| {
+                        if *value {
+                            this.checked_instances.push(id);
+                        } else {
+                            this.checked_instances.retain(|checked| *checked != id);
+                        }
+                    }))
+            }));
+
+        let label = if checked.len() == 1 {
+            ts!("modrinth_install.add_to_one_instance")
+        } else {
+            SharedString::new(rust_i18n::t!("modrinth_install.add_to_instances", count = checked.len()))
+        };
+
+        v_flex()
+            .gap_1()
+            .text_left()
+            .child(ts!("modrinth_install.add_to_several"))
+            .child(div().max_h_64().overflow_y_scrollbar().child(list))
+            .when(mismatched, |content| {
+                content.child(if loader_matters {
+                    ts!("modrinth_install.mismatched_version_and_loader")
+                } else {
+                    ts!("modrinth_install.mismatched_version")
+                })
+            })
+            .child(Button::new("instances").success().label(label).disabled(checked.is_empty() || mismatched).on_click(
+                cx.listener(|this, _, _, _| {
+                    let mut checked = this.compatible_instances.iter()
+                        .filter(|instance| this.checked_instances.contains(&instance.id));
+                    let Some(first) = checked.next().cloned() else {
+                        return;
+                    };
+                    let extra_targets = checked.map(|instance| instance.id).collect();
+                    this.select_targets(&first, extra_targets);
+                }),
+            ))
+    }
+
+    /// Where resourcepacks and shaders can be installed, empty for other project types
+    fn install_folders(&self, cx: &App) -> Vec<InstallFolderItem> {
+        let mut folders = match self.project_type {
+            ModrinthProjectType::Resourcepack => vec![
+                InstallFolderItem::folder(ts!("modrinth_install.resource_packs_folder"), "resourcepacks"),
+            ],
+            ModrinthProjectType::Shader => vec![
+                InstallFolderItem::folder(ts!("modrinth_install.shader_packs_folder"), "shaderpacks"),
+                InstallFolderItem::folder(ts!("modrinth_install.canvas_shaders_folder"), "resourcepacks"),
+            ],
+            _ => return Vec::new(),
+        };
+
+        // A world only exists in one of the instances when installing into several
+        if self.project_type == ModrinthProjectType::Resourcepack
+            && self.extra_targets.is_empty()
+            && let Some(InstallTarget::Instance(id)) = &self.target
+            && let Some(instance) = self.data.instances.read(cx).entries.get(id)
+        {
+            let instance = instance.read(cx);
+            let state = instance.worlds_state.load(std::sync::atomic::Ordering::SeqCst);
+            if state.should_send_load_request() {
+                self.data.backend_handle.send_with_serial(MessageToBackend::RequestLoadWorlds { id: *id }, &self.worlds_load_serial);
+            }
+            for world in instance.worlds.read(cx).iter() {
+                let Some(folder_name) = world.level_path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                folders.push(InstallFolderItem {
+                    name: SharedString::new(rust_i18n::t!("modrinth_install.world_folder", name = world.title)),
+                    folder: InstallFolder::WorldResources {
+                        folder_name: folder_name.into(),
+                        level_path: world.level_path.clone(),
+                    },
+                });
+            }
+        }
+
+        folders
+    }
+
+    /// The library mod the loader needs when it isn't already installed or pulled in as a dependency
+    fn missing_companion(&self, target: &InstallTarget, loader: Loader, required_dependencies: &[ModrinthDependency], cx: &App) -> Option<LoaderCompanion> {
+        if self.project_type != ModrinthProjectType::Mod {
+            return None;
+        }
+        let companion = loader.companion()?;
+        if &*self.project_id == companion.modrinth_project_id {
+            return None;
+        }
+        if required_dependencies.iter().any(|dep| dep.project_id.as_deref() == Some(companion.modrinth_project_id)) {
+            return None;
+        }
+
+        match target {
+            InstallTarget::Instance(id) => {
+                let instance = self.data.instances.read(cx).entries.get(id)?.read(cx);
+                match instance.has_companion(companion, cx) {
+                    Some(true) => None,
+                    Some(false) => Some(companion),
+                    None => {
+                        // The dialog is redrawn once the mods have loaded
+                        let state = instance.mods_state.load(std::sync::atomic::Ordering::SeqCst);
+                        if state.should_send_load_request() {
+                            self.data.backend_handle.send_with_serial(MessageToBackend::RequestLoadMods { id: *id }, &self.mods_load_serial);
+                        }
+                        None
+                    },
+                }
+            },
+            InstallTarget::NewInstance { .. } => Some(companion),
+            InstallTarget::Library => None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -726,3 +967,40 @@ impl SelectItem for ModVersionItem {
         &self.version
     }
 }
+
+#[derive(Clone, Debug, PartialEq)]
+enum InstallFolder {
+    Folder(&'static str),
+    /// Replaces the world's `resources.zip`, which is only used while playing that world
+    WorldResources {
+        folder_name: Arc<str>,
+        level_path: Arc<Path>,
+    },
+}
+
+#[derive(Clone)]
+struct InstallFolderItem {
+    name: SharedString,
+    folder: InstallFolder,
+}
+
+impl InstallFolderItem {
+    fn folder(name: SharedString, folder: &'static str) -> Self {
+        Self {
+            name,
+            folder: InstallFolder::Folder(folder),
+        }
+    }
+}
+
+impl SelectItem for InstallFolderItem {
+    type Value = InstallFolder;
+
+    fn title(&self) -> SharedString {
+        self.name.clone()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.folder
+    }
+}
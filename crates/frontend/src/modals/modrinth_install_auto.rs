@@ -9,7 +9,7 @@ use gpui_component::{
 use relative_path::RelativePath;
 use rustc_hash::FxHashMap;
 use schema::{
-    content::ContentSource, loader::Loader, modrinth::{
+    content::ContentSource, instance::InstanceConfiguration, loader::Loader, modrinth::{
         ModrinthDependency, ModrinthDependencyType, ModrinthLoader, ModrinthProjectType, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthVersionStatus, ModrinthVersionType
     }
 };
@@ -18,9 +18,9 @@ use uuid::Uuid;
 use crate::{
     component::{error_alert::ErrorAlert, instance_dropdown::InstanceDropdown},
     entity::{
-        instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}, DataEntities
+        instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}, notifications::NotificationCenter, DataEntities
     },
-    root,
+    root, ts,
 };
 
 // struct VersionMatrixLoaders {
@@ -58,6 +58,7 @@ use crate::{
 
 struct AutoInstallNotificationType;
 
+/// Installs the newest version for the instance that the backend finds
 pub fn open(
     name: &str,
     project_id: Arc<str>,
@@ -67,211 +68,175 @@ pub fn open(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let project_versions = FrontendMetadata::request(
-        &data.metadata,
-        MetadataRequest::ModrinthProjectVersions(ModrinthProjectVersionsRequest {
-            project_id: project_id.clone(),
-            game_versions: None,
-            loaders: None,
-        }),
-        cx,
-    );
+    install_latest(SharedString::new(name), project_id, project_type, install_for, false, data, window, cx);
+}
 
-    let key = Uuid::new_v4();
-    let title = SharedString::new(format!("Install {}", name));
+/// Like [`open`], but opens the install dialog when there's no compatible version
+pub fn quick_install(
+    name: SharedString,
+    project_id: Arc<str>,
+    project_type: ModrinthProjectType,
+    install_for: InstanceID,
+    data: &DataEntities,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    install_latest(name, project_id, project_type, install_for, true, data, window, cx);
+}
 
-    if handle_project_versions(data, title.clone(), key, project_id.clone(), project_type, install_for, &project_versions, window, cx) {
+#[allow(clippy::too_many_arguments)]
+fn install_latest(
+    name: SharedString,
+    project_id: Arc<str>,
+    project_type: ModrinthProjectType,
+    install_for: InstanceID,
+    choose_if_missing: bool,
+    data: &DataEntities,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(configuration) = data.instances.read(cx).entries.get(&install_for).map(|instance| instance.read(cx).configuration.clone()) else {
         return;
-    }
-
-    let _subscription = window.observe(&project_versions, cx, {
-        let title = title.clone();
-        let data = data.clone();
-        move |project_versions, window, cx| {
-            handle_project_versions(&data, title.clone(), key, project_id.clone(), project_type, install_for, &project_versions, window, cx);
-        }
+    };
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    data.backend_handle.send(MessageToBackend::GetLatestCompatibleVersion {
+        instance: install_for,
+        project_id: project_id.clone(),
+        project_type,
+        channel: send,
     });
 
+    let key = Uuid::new_v4();
+    let title = SharedString::new(rust_i18n::t!("modrinth_install.install_name", name = name));
     let notification = Notification::new()
         .id1::<AutoInstallNotificationType>(key)
-        .title(title)
-        .content(move |_, _, _| {
-            _ = &_subscription;
-
+        .title(title.clone())
+        .content(|_, _, _| {
             h_flex()
                 .gap_2()
-                .child("Loading project versions from Modrinth...")
+                .child(ts!("modrinth_install.finding_latest"))
                 .child(Spinner::new())
                 .into_any_element()
         })
         .autohide(false);
-
     window.push_notification(notification, cx);
+
+    let data = data.clone();
+    window.spawn(cx, async move |cx| {
+        let Ok(result) = recv.await else {
+            return;
+        };
+        _ = cx.update(|window, cx| {
+            match result {
+                Ok(Some(version)) => {
+                    match content_install_for_version(&version, project_id, project_type, install_for, &configuration) {
+                        Ok(content_install) => {
+                            let modal_action = ModalAction::default();
+                            data.backend_handle.send(MessageToBackend::InstallContent {
+                                content: content_install,
+                                modal_action: modal_action.clone(),
+                            });
+                            crate::modals::generic::show_notification_with_note(window, cx, ts!("modrinth_install.install_failed"), modal_action,
+                                Notification::new().id1::<AutoInstallNotificationType>(key));
+                        },
+                        Err(error) => push_error(title, key, error, window, cx),
+                    }
+                },
+                Ok(None) if choose_if_missing => {
+                    let message = ts!("modrinth_install.no_compatible_version");
+                    NotificationCenter::push_global(NotificationType::Info, SharedString::new(format!("{}: {}", title, message)), cx);
+                    let notification = Notification::info(message)
+                        .id1::<AutoInstallNotificationType>(key)
+                        .title(title);
+                    window.push_notification(notification, cx);
+                    crate::modals::modrinth_install::open(name.as_str(), project_id, project_type, Some(install_for), &data, window, cx);
+                },
+                Ok(None) => push_error(title, key, ts!("modrinth_install.no_matching_version"), window, cx),
+                Err(error) => push_error(title, key, SharedString::new(error), window, cx),
+            }
+        });
+    }).detach();
 }
 
-fn handle_project_versions(
-    data: &DataEntities,
-    title: SharedString,
-    key: Uuid,
+fn content_install_for_version(
+    version: &ModrinthProjectVersion,
     project_id: Arc<str>,
     project_type: ModrinthProjectType,
     install_for: InstanceID,
-    project_versions: &Entity<FrontendMetadataState>,
-    window: &mut Window,
-    cx: &mut App
-) -> bool {
-    let result: FrontendMetadataResult<ModrinthProjectVersionsResult> = project_versions.read(cx).result();
-    match result {
-        FrontendMetadataResult::Loading => {
-            return false;
+    configuration: &InstanceConfiguration,
+) -> Result<ContentInstall, SharedString> {
+    let install_file = version
+        .files
+        .iter()
+        .find(|file| file.primary)
+        .unwrap_or(version.files.first().unwrap());
+
+    let path = match project_type {
+        ModrinthProjectType::Mod => RelativePath::new("mods").join(&*install_file.filename),
+        ModrinthProjectType::Modpack => RelativePath::new("mods").join(&*install_file.filename),
+        ModrinthProjectType::Resourcepack => RelativePath::new("resourcepacks").join(&*install_file.filename),
+        ModrinthProjectType::Shader => RelativePath::new("shaderpacks").join(&*install_file.filename),
+        ModrinthProjectType::Other => {
+            return Err(ts!("modrinth_install.other_project_type"));
         },
-        FrontendMetadataResult::Loaded(project_versions) => {
-            let Some(instance) = data.instances.read(cx).entries.get(&install_for) else {
-                return true;
-            };
-            let configuration = instance.read(cx).configuration.clone();
-            let modrinth_loader = configuration.loader.as_modrinth_loader();
-            let is_mod = project_type == ModrinthProjectType::Mod || project_type == ModrinthProjectType::Modpack;
-            let matching_versions = project_versions.0.iter().filter(|version| {
-                let Some(loaders) = version.loaders.clone() else {
-                    return false;
-                };
-                let Some(game_versions) = &version.game_versions else {
-                    return false;
-                };
-                if version.files.is_empty() {
-                    return false;
-                }
-                if !game_versions.contains(&configuration.minecraft_version) {
-                    return false;
-                }
-                if is_mod && !loaders.contains(&modrinth_loader) {
-                    return false;
-                }
-                true
-            }).collect::<Vec<_>>();
-
-            let mut highest_release = None;
-            let mut highest_beta = None;
-            let mut highest_alpha = None;
-
-            for (index, version) in matching_versions.iter().enumerate() {
-                match version.version_type {
-                    Some(ModrinthVersionType::Release) => {
-                        highest_release = Some(index);
-                        break;
-                    },
-                    Some(ModrinthVersionType::Beta) => {
-                        if highest_beta.is_none() {
-                            highest_beta = Some(index);
-                        }
-                    },
-                    Some(ModrinthVersionType::Alpha) => {
-                        if highest_alpha.is_none() {
-                            highest_alpha = Some(index);
-                        }
-                    },
-                    _ => {},
-                }
-            }
-
-            let highest = highest_release.or(highest_beta).or(highest_alpha);
-            let Some(highest) = highest else {
-                push_error(title.clone(), key, "Unable to find matching version of project".into(), window, cx);
-                return true;
-            };
-
-            let version = matching_versions[highest];
-
-            let install_file = version
-                .files
-                .iter()
-                .find(|file| file.primary)
-                .unwrap_or(version.files.first().unwrap());
-
-            let path = match project_type {
-                ModrinthProjectType::Mod => RelativePath::new("mods").join(&*install_file.filename),
-                ModrinthProjectType::Modpack => RelativePath::new("mods").join(&*install_file.filename),
-                ModrinthProjectType::Resourcepack => RelativePath::new("resourcepacks").join(&*install_file.filename),
-                ModrinthProjectType::Shader => RelativePath::new("shaderpacks").join(&*install_file.filename),
-                ModrinthProjectType::Other => {
-                    push_error(title.clone(), key, "Unable to install 'other' project type".into(), window, cx);
-                    return true;
-                },
-            };
-
-            let Some(path) = SafePath::from_relative_path(&path) else {
-                push_error(title.clone(), key, "Invalid/dangerous filename".into(), window, cx);
-                return true;
-            };
-
-            let mut files = Vec::new();
-
-            let required_dependencies = version.dependencies.as_ref().map(|deps| {
-                deps
-                    .iter()
-                    .filter(|dep| {
-                        dep.project_id.is_some() && dep.dependency_type == ModrinthDependencyType::Required
-                    })
-                    .cloned()
-                    .collect::<Vec<_>>()
-            });
-
-            if let Some(required_dependencies) = required_dependencies {
-                for dep in required_dependencies.iter() {
-                    files.push(ContentInstallFile {
-                        replace_old: None,
-                        path: bridge::install::ContentInstallPath::Automatic,
-                        download: ContentDownload::Modrinth {
-                            project_id: dep.project_id.clone().unwrap(),
-                            version_id: dep.version_id.clone()
-                        },
-                        content_source: ContentSource::ModrinthProject {
-                            project: dep.project_id.clone().unwrap()
-                        },
-                    })
-                }
-            }
+    };
+
+    let Some(path) = SafePath::from_relative_path(&path) else {
+        return Err(ts!("modrinth_install.invalid_filename"));
+    };
+
+    let mut files = Vec::new();
+
+    let required_dependencies = version.dependencies.as_ref().map(|deps| {
+        deps
+            .iter()
+            .filter(|dep| {
+                dep.project_id.is_some() && dep.dependency_type == ModrinthDependencyType::Required
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    });
 
+    if let Some(required_dependencies) = required_dependencies {
+        for dep in required_dependencies.iter() {
             files.push(ContentInstallFile {
                 replace_old: None,
-                path: bridge::install::ContentInstallPath::Safe(path),
-                download: ContentDownload::Url {
-                    url: install_file.url.clone(),
-                    sha1: install_file.hashes.sha1.clone(),
-                    size: install_file.size,
+                path: bridge::install::ContentInstallPath::Automatic,
+                download: ContentDownload::Modrinth {
+                    project_id: dep.project_id.clone().unwrap(),
+                    version_id: dep.version_id.clone()
                 },
                 content_source: ContentSource::ModrinthProject {
-                    project: project_id
+                    project: dep.project_id.clone().unwrap()
                 },
-            });
-
-            let content_install = ContentInstall {
-                target: InstallTarget::Instance(install_for),
-                loader_hint: configuration.loader,
-                version_hint: Some(configuration.minecraft_version.into()),
-                files: files.into(),
-            };
-            let modal_action = ModalAction::default();
-
-            data.backend_handle.send(MessageToBackend::InstallContent {
-                content: content_install.clone(),
-                modal_action: modal_action.clone(),
-            });
-
-            crate::modals::generic::show_notification_with_note(window, cx, "Error installing content".into(), modal_action,
-                Notification::new().id1::<AutoInstallNotificationType>(key));
+            })
+        }
+    }
 
-            return true;
+    files.push(ContentInstallFile {
+        replace_old: None,
+        path: bridge::install::ContentInstallPath::Safe(path),
+        download: ContentDownload::Url {
+            url: install_file.url.clone(),
+            sha1: install_file.hashes.sha1.clone(),
+            size: install_file.size,
         },
-        FrontendMetadataResult::Error(error) => {
-            push_error(title.clone(), key, format!("Error loading project versions from Modrinth:\n{error}").into(), window, cx);
-            return true;
+        content_source: ContentSource::ModrinthProject {
+            project: project_id
         },
-    }
+    });
+
+    Ok(ContentInstall {
+        target: InstallTarget::Instance(install_for),
+        loader_hint: configuration.loader,
+        version_hint: Some(configuration.minecraft_version.into()),
+        files: files.into(),
+    })
 }
 
 fn push_error(title: SharedString, key: Uuid, message: SharedString, window: &mut Window, cx: &mut App) {
+    NotificationCenter::push_global(NotificationType::Error, SharedString::new(format!("{}: {}", title, message)), cx);
     let notification = Notification::error(message)
         .id1::<AutoInstallNotificationType>(key)
         .title(title)
@@ -0,0 +1,103 @@
+use bridge::message::BridgeNotificationType;
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonGroup, ButtonVariants}, h_flex, sheet::Sheet, v_flex, ActiveTheme as _, Disableable, IconName, Selectable, Sizable
+};
+
+use crate::{entity::{notifications::{NotificationCenter, NotificationEntry}, DataEntities}, ts};
+
+const FILTERS: [(Option<BridgeNotificationType>, &str); 5] = [
+    (None, "notifications.all"),
+    (Some(BridgeNotificationType::Error), "notifications.errors"),
+    (Some(BridgeNotificationType::Warning), "notifications.warnings"),
+    (Some(BridgeNotificationType::Info), "notifications.info"),
+    (Some(BridgeNotificationType::Success), "notifications.success"),
+];
+
+/// Lists the notifications from the backend, newest first
+pub fn build_notifications_sheet(data: &DataEntities) -> impl Fn(Sheet, &mut Window, &mut App) -> Sheet + 'static {
+    let data = data.clone();
+    move |sheet, window, cx| {
+        let center = data.notifications.read(cx);
+        let filter = center.filter;
+
+        let filters = ButtonGroup::new("notification-filter")
+            .outline()
+            .small()
+            .children(FILTERS.iter().map(|(notification_type, key)| {
+                Button::new(*key).label(ts!(*key)).selected(filter == *notification_type)
+            }))
+            .on_click({
+                let notifications = data.notifications.clone();
+                move |clicked: &Vec<usize>, _, cx| {
+                    if let Some((notification_type, _)) = clicked.first().and_then(|index| FILTERS.get(*index)) {
+                        NotificationCenter::set_filter(&notifications, *notification_type, cx);
+                    }
+                }
+            });
+
+        let clear = Button::new("clear-notifications")
+            .label(ts!("notifications.clear_all"))
+            .small()
+            .disabled(center.entries.is_empty())
+            .on_click({
+                let notifications = data.notifications.clone();
+                move |_, _, cx| {
+                    NotificationCenter::clear(&notifications, cx);
+                }
+            });
+
+        let entries: Vec<_> = center.entries.iter()
+            .rev()
+            .filter(|entry| filter.is_none_or(|filter| entry.notification_type == filter))
+            .map(|entry| render_entry(entry, &data, cx))
+            .collect();
+
+        let content = if entries.is_empty() {
+            v_flex().child(div().text_color(cx.theme().muted_foreground).child(ts!("notifications.empty")))
+        } else {
+            v_flex().gap_2().children(entries)
+        };
+
+        sheet
+            .title(ts!("notifications.title"))
+            .overlay_top(crate::root::sheet_margin_top(window, cx))
+            .child(v_flex()
+                .gap_3()
+                .child(h_flex().gap_2().justify_between().child(filters).child(clear))
+                .child(content))
+    }
+}
+
+fn render_entry(entry: &NotificationEntry, data: &DataEntities, cx: &App) -> Div {
+    let theme = cx.theme();
+
+    let (icon, color) = match entry.notification_type {
+        BridgeNotificationType::Success => (IconName::CircleCheck, theme.success),
+        BridgeNotificationType::Info => (IconName::Info, theme.info),
+        BridgeNotificationType::Warning => (IconName::TriangleAlert, theme.warning),
+        BridgeNotificationType::Error => (IconName::CircleX, theme.danger),
+    };
+
+    let id = entry.id;
+    let notifications = data.notifications.clone();
+    let dismiss = Button::new(("dismiss", id as usize)).icon(IconName::Close).ghost().xsmall().on_click(move |_, _, cx| {
+        NotificationCenter::dismiss(&notifications, id, cx);
+    });
+
+    h_flex()
+        .gap_2()
+        .p_2()
+        .items_start()
+        .rounded(theme.radius)
+        .border_1()
+        .border_color(theme.border)
+        .child(div().text_color(color).child(icon))
+        .child(v_flex()
+            .flex_1()
+            .min_w_0()
+            .child(div().text_sm().child(entry.message.clone()))
+            .child(div().text_xs().text_color(theme.muted_foreground)
+                .child(SharedString::new(entry.time.format("%H:%M:%S").to_string()))))
+        .child(dismiss)
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, ServerEdit}, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, v_flex, ActiveTheme, WindowExt
+};
+
+use crate::ts;
+
+pub fn open_server_edits_overwritten(
+    id: InstanceID,
+    instance_name: Option<SharedString>,
+    edits: Arc<[ServerEdit]>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let message = match instance_name {
+        Some(name) => SharedString::new(rust_i18n::t!("servers.overwritten_by_instance", name = name)),
+        None => ts!("servers.overwritten_by_game"),
+    };
+
+    let changes: Vec<SharedString> = edits.iter().map(|edit| {
+        let key = match edit {
+            ServerEdit::Add { .. } => "servers.added",
+            ServerEdit::Remove { .. } => "servers.removed",
+            ServerEdit::Edit { .. } => "servers.edited",
+            ServerEdit::Move { .. } => "servers.moved",
+        };
+        SharedString::new(rust_i18n::t!(key, name = edit.name(), ip = edit.ip()))
+    }).collect();
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let retry = Button::new("retry").success().label(ts!("servers.apply_again")).on_click({
+            let edits = edits.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                for edit in edits.iter() {
+                    backend_handle.send(MessageToBackend::EditServers { id, edit: edit.clone() });
+                }
+                window.close_all_dialogs(cx);
+            }
+        });
+        let discard = Button::new("discard").label(ts!("servers.discard")).on_click(|_, window, cx| {
+            window.close_all_dialogs(cx);
+        });
+
+        dialog
+            .title(ts!("servers.overwritten"))
+            .child(v_flex()
+                .gap_2()
+                .child(message.clone())
+                .children(changes.iter().map(|change| div().pl_2().child(change.clone())))
+                .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                    .child(ts!("servers.apply_again_description")))
+                .child(h_flex().gap_2().child(retry).child(discard)))
+    });
+}
@@ -1,19 +1,81 @@
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::Arc, time::Duration};
 
-use bridge::{handle::BackendHandle, message::MessageToBackend};
+use bridge::{handle::BackendHandle, message::MessageToBackend, meta::{MetadataCacheEntry, MetadataCacheKind}};
 use gpui::*;
-use gpui_component::{button::{Button, ButtonVariants}, checkbox::Checkbox, select::{SearchableVec, Select, SelectEvent, SelectState}, sheet::Sheet, spinner::Spinner, tab::{Tab, TabBar, TabVariant}, v_flex, ActiveTheme, IconName, Sizable, ThemeRegistry};
-use schema::backend_config::BackendConfig;
+use gpui_component::{button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputState}, select::{SearchableVec, Select, SelectEvent, SelectState}, sheet::Sheet, spinner::Spinner, tab::{Tab, TabBar, TabVariant}, v_flex, notification::NotificationType, ActiveTheme, Disableable, IconName, Selectable, Sizable, ThemeRegistry};
+use schema::backend_config::{BackendConfig, BackendSettings, MetadataCacheConfig, MirrorConfig, ProxyConfig, ProxyMode};
 
-use crate::{entity::DataEntities, interface_config::InterfaceConfig};
+use crate::{entity::{backend_settings::BackendSettingsState, launcher_lock::LauncherLockState, notifications::push_notification, DataEntities}, interface_config::{GameOutputLineLimit, InstanceActivateAction, InterfaceConfig, LaunchWindowBehavior, ThemeModeSetting}, keymap::Keybind, ts};
+
+/// Choices for how long a stopped instance has to close before it's force killed
+const STOP_GRACE_PERIODS: [u32; 4] = [10, 30, 60, 120];
+const CONCURRENT_DOWNLOADS: [u32; 4] = [2, 4, 8, 16];
+const CONNECT_TIMEOUTS: [u32; 4] = [5, 15, 30, 60];
+const READ_TIMEOUTS: [u32; 4] = [15, 30, 60, 120];
 
 struct Settings {
     theme_folder: Arc<Path>,
     theme_select: Entity<SelectState<SearchableVec<SharedString>>>,
     backend_handle: BackendHandle,
+    portable: bool,
     pending_request: bool,
     backend_config: Option<BackendConfig>,
     get_configuration_task: Option<Task<()>>,
+    launcher_lock: Entity<LauncherLockState>,
+    backend_settings: Entity<BackendSettingsState>,
+    lock_secret_input: Entity<InputState>,
+    lock_idle_input: Entity<InputState>,
+    tab: SettingsTab,
+    /// The network inputs are filled from the backend config the first time the tab is shown
+    network_inputs_loaded: bool,
+    proxy: ProxySettings,
+    mirrors: MirrorSettings,
+    metadata_cache: MetadataCacheSettings,
+    keybinding_inputs: Vec<(Keybind, Entity<InputState>)>,
+}
+
+struct MirrorSettings {
+    mojang_meta_input: Entity<InputState>,
+    assets_input: Entity<InputState>,
+    libraries_input: Entity<InputState>,
+    modrinth_api_input: Entity<InputState>,
+}
+
+struct MetadataCacheSettings {
+    /// Filled from the backend config the first time the tab is shown
+    inputs_loaded: bool,
+    memory_ttl_input: Entity<InputState>,
+    disk_ttl_input: Entity<InputState>,
+    entries: Option<Arc<[MetadataCacheEntry]>>,
+    refreshing: Option<Arc<Path>>,
+    _load_task: Task<()>,
+    _refresh_task: Task<()>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SettingsTab {
+    Interface,
+    Network,
+    Metadata,
+    Keybindings,
+}
+
+enum ProxyTestState {
+    Idle,
+    Testing,
+    Success(Duration),
+    Failed(Arc<str>),
+}
+
+/// Proxy being edited, only sent to the backend when saving
+struct ProxySettings {
+    mode: ProxyMode,
+    host_input: Entity<InputState>,
+    port_input: Entity<InputState>,
+    username_input: Entity<InputState>,
+    password_input: Entity<InputState>,
+    test_state: ProxyTestState,
+    _test_task: Task<()>,
 }
 
 pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut App) -> impl Fn(Sheet, &mut Window, &mut App) -> Sheet + 'static {
@@ -33,22 +95,67 @@ pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut A
                 return;
             };
 
-            InterfaceConfig::get_mut(cx).active_theme = theme_name.clone();
+            InterfaceConfig::get_mut(cx).active_theme = theme_name;
+            crate::theme::apply(cx);
+        }).detach();
 
-            let Some(theme) = gpui_component::ThemeRegistry::global(cx).themes().get(&SharedString::new(theme_name.trim_ascii())).cloned() else {
-                return;
-            };
+        cx.observe(&data.launcher_lock, |_, _, cx| cx.notify()).detach();
+        cx.observe(&data.backend_settings, |_, _, cx| cx.notify()).detach();
 
-            gpui_component::Theme::global_mut(cx).apply_config(&theme);
-        }).detach();
+        let lock_secret_input = cx.new(|cx| InputState::new(window, cx).masked(true).placeholder(ts!("launcher_lock.secret_placeholder")));
+        let lock_idle_input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("launcher_lock.never")));
+
+        let proxy = ProxySettings {
+            mode: ProxyMode::System,
+            host_input: cx.new(|cx| InputState::new(window, cx).placeholder("proxy.example.com")),
+            port_input: cx.new(|cx| InputState::new(window, cx).placeholder("8080")),
+            username_input: cx.new(|cx| InputState::new(window, cx).placeholder(ts!("proxy.optional"))),
+            password_input: cx.new(|cx| InputState::new(window, cx).masked(true).placeholder(ts!("proxy.optional"))),
+            test_state: ProxyTestState::Idle,
+            _test_task: Task::ready(()),
+        };
+
+        let mirrors = MirrorSettings {
+            mojang_meta_input: cx.new(|cx| InputState::new(window, cx).placeholder("https://piston-meta.mojang.com")),
+            assets_input: cx.new(|cx| InputState::new(window, cx).placeholder("https://resources.download.minecraft.net")),
+            libraries_input: cx.new(|cx| InputState::new(window, cx).placeholder("https://libraries.minecraft.net")),
+            modrinth_api_input: cx.new(|cx| InputState::new(window, cx).placeholder("https://api.modrinth.com")),
+        };
+
+        let metadata_cache = MetadataCacheSettings {
+            inputs_loaded: false,
+            memory_ttl_input: cx.new(|cx| InputState::new(window, cx)),
+            disk_ttl_input: cx.new(|cx| InputState::new(window, cx)),
+            entries: None,
+            refreshing: None,
+            _load_task: Task::ready(()),
+            _refresh_task: Task::ready(()),
+        };
+
+        let keybinding_inputs = Keybind::ALL.into_iter().map(|keybind| {
+            let keystroke = keybind.keystroke(cx);
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder(keybind.default_keystroke()).default_value(keystroke));
+            (keybind, input)
+        }).collect();
 
         let mut settings = Settings {
             theme_folder,
             theme_select,
             backend_handle: data.backend_handle.clone(),
+            portable: data.portable,
             pending_request: false,
             backend_config: None,
             get_configuration_task: None,
+            launcher_lock: data.launcher_lock.clone(),
+            backend_settings: data.backend_settings.clone(),
+            lock_secret_input,
+            lock_idle_input,
+            tab: SettingsTab::Interface,
+            network_inputs_loaded: false,
+            proxy,
+            mirrors,
+            metadata_cache,
+            keybinding_inputs,
         };
 
         settings.update_backend_configuration(cx);
@@ -57,19 +164,44 @@ pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut A
     });
 
     move |sheet, window, cx| {
+        let selected_index = match settings.read(cx).tab {
+            SettingsTab::Interface => 0,
+            SettingsTab::Network => 1,
+            SettingsTab::Metadata => 2,
+            SettingsTab::Keybindings => 3,
+        };
+
         let tab_bar = TabBar::new("bar")
             .prefix(div().w_4())
-            .selected_index(0)
+            .selected_index(selected_index)
             .underline()
-            .child(Tab::new().label("Interface"))
+            .child(Tab::new().label(ts!("settings.interface")))
+            .child(Tab::new().label(ts!("settings.network")))
+            .child(Tab::new().label(ts!("settings.metadata")))
+            .child(Tab::new().label(ts!("settings.keybindings")))
             // .child(Tab::new().label("Game"))
-            .on_click(|index, window, cx| {
-                // todo: switch
+            .on_click({
+                let settings = settings.clone();
+                move |index, _, cx| {
+                    let tab = match *index {
+                        1 => SettingsTab::Network,
+                        2 => SettingsTab::Metadata,
+                        3 => SettingsTab::Keybindings,
+                        _ => SettingsTab::Interface,
+                    };
+                    settings.update(cx, |settings, cx| {
+                        settings.tab = tab;
+                        if tab == SettingsTab::Metadata {
+                            settings.load_metadata_cache_entries(cx);
+                        }
+                        cx.notify();
+                    });
+                }
             });
 
         sheet
-            .title("Settings")
-            .overlay_top(crate::root::sheet_margin_top(window))
+            .title(ts!("settings.title"))
+            .overlay_top(crate::root::sheet_margin_top(window, cx))
             .p_0()
             .child(v_flex()
                 .border_t_1()
@@ -81,6 +213,12 @@ pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut A
 }
 
 impl Settings {
+    fn update_backend_settings(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut BackendSettings)) {
+        let mut settings = self.backend_settings.read(cx).settings;
+        update(&mut settings);
+        self.backend_handle.send(MessageToBackend::SetBackendSettings { settings });
+    }
+
     pub fn update_backend_configuration(&mut self, cx: &mut Context<Self>) {
         if self.get_configuration_task.is_some() {
             self.pending_request = true;
@@ -110,56 +248,186 @@ impl Settings {
 
 impl Render for Settings {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        match self.tab {
+            SettingsTab::Interface => self.render_interface(cx),
+            SettingsTab::Network => self.render_network(window, cx),
+            SettingsTab::Metadata => self.render_metadata_cache(window, cx),
+            SettingsTab::Keybindings => self.render_keybindings(cx),
+        }
+    }
+}
+
+impl Settings {
+    fn render_interface(&mut self, cx: &mut Context<Self>) -> Div {
         let interface_config = InterfaceConfig::get(cx);
+        let activate_action = interface_config.instance_activate_action;
+        let line_limit = interface_config.game_output_line_limit;
+        let theme_mode = interface_config.theme_mode;
 
         let mut div = v_flex()
             .px_4()
             .py_3()
             .gap_3()
+            .child(crate::labelled(ts!("settings.language"), self.render_languages(cx)))
             .child(crate::labelled(
-                "Theme",
+                ts!("settings.theme"),
                 Select::new(&self.theme_select)
             ))
-            .child(Button::new("open-theme-folder").info().icon(IconName::FolderOpen).label("Open theme folder").on_click({
+            .child(crate::labelled(ts!("settings.appearance"),
+                ButtonGroup::new("theme-mode")
+                    .outline()
+                    .child(Button::new("dark").label(ts!("settings.dark")).selected(theme_mode == ThemeModeSetting::Dark))
+                    .child(Button::new("light").label(ts!("settings.light")).selected(theme_mode == ThemeModeSetting::Light))
+                    .child(Button::new("system").label(ts!("settings.system")).selected(theme_mode == ThemeModeSetting::System))
+                    .on_click(cx.listener(|_, clicked: &Vec<usize>, _, cx| {
+                        let mode = match clicked.first() {
+                            Some(0) => ThemeModeSetting::Dark,
+                            Some(1) => ThemeModeSetting::Light,
+                            Some(2) => ThemeModeSetting::System,
+                            _ => return,
+                        };
+                        InterfaceConfig::get_mut(cx).theme_mode = mode;
+                        crate::theme::apply(cx);
+                        cx.notify();
+                    }))
+            ))
+            .child(crate::labelled(ts!("settings.accent_color"), self.render_accent_colors(cx)))
+            .child(crate::labelled(ts!("settings.window"),
+                v_flex().gap_2()
+                    .child(Checkbox::new("custom-titlebar")
+                        .label(ts!("settings.custom_titlebar"))
+                        .checked(interface_config.custom_titlebar)
+                        .on_click(cx.listener(|_, value, _, cx| {
+                            InterfaceConfig::get_mut(cx).custom_titlebar = *value;
+                            cx.notify();
+                        })))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child(ts!("settings.custom_titlebar_restart")))
+                    .child(Checkbox::new("window-vibrancy")
+                        .label(ts!("settings.window_vibrancy"))
+                        .disabled(!crate::theme::supports_vibrancy())
+                        .checked(interface_config.window_vibrancy)
+                        .on_click(cx.listener(|_, value, window, cx| {
+                            InterfaceConfig::get_mut(cx).window_vibrancy = *value;
+                            crate::theme::apply_window_background(window, cx);
+                            cx.notify();
+                        })))
+            ))
+            .child(Button::new("open-theme-folder").info().icon(IconName::FolderOpen).label(ts!("settings.open_theme_folder")).on_click({
                 let theme_folder = self.theme_folder.clone();
                 move |_, window, cx| {
                     crate::open_folder(&theme_folder, window, cx);
                 }
             }))
-            .child(Button::new("open-theme-repo").info().icon(IconName::Globe).label("Open theme repository").on_click({
+            .child(Button::new("open-theme-repo").info().icon(IconName::Globe).label(ts!("settings.open_theme_repository")).on_click({
                 move |_, _, cx| {
                     cx.open_url("https://github.com/longbridge/gpui-component/tree/main/themes");
                 }
             }))
-            .child(crate::labelled("Deletion",
+            .child(crate::labelled(ts!("settings.instance_activate_action"),
+                ButtonGroup::new("instance-activate-action")
+                    .outline()
+                    .child(Button::new("open-page").label(ts!("settings.open_page")).selected(activate_action == InstanceActivateAction::OpenPage))
+                    .child(Button::new("launch").label(ts!("settings.launch")).selected(activate_action == InstanceActivateAction::Launch))
+                    .child(Button::new("open-folder").label(ts!("settings.open_folder")).selected(activate_action == InstanceActivateAction::OpenFolder))
+                    .on_click(cx.listener(|_, clicked: &Vec<usize>, _, cx| {
+                        let action = match clicked.first() {
+                            Some(0) => InstanceActivateAction::OpenPage,
+                            Some(1) => InstanceActivateAction::Launch,
+                            Some(2) => InstanceActivateAction::OpenFolder,
+                            _ => return,
+                        };
+                        InterfaceConfig::get_mut(cx).instance_activate_action = action;
+                        cx.notify();
+                    }))
+            ))
+            .child(crate::labelled(ts!("settings.deletion"),
                 v_flex().gap_2()
                     .child(Checkbox::new("confirm-delete-mods")
-                        .label("Shift+Click to skip mod delete confirmation")
+                        .label(ts!("settings.quick_delete_mods"))
                         .checked(interface_config.quick_delete_mods)
                         .on_click(|value, _, cx| {
                             InterfaceConfig::get_mut(cx).quick_delete_mods = *value;
                         }))
                     .child(Checkbox::new("confirm-delete-instance")
-                        .label("Shift+Click to skip instance delete confirmation")
+                        .label(ts!("settings.quick_delete_instance"))
                         .checked(interface_config.quick_delete_instance).on_click(|value, _, cx| {
                             InterfaceConfig::get_mut(cx).quick_delete_instance = *value;
                         }))
                     )
-            );
+            )
+            .child(crate::labelled(ts!("settings.game_output"),
+                v_flex().gap_2()
+                    .child(Checkbox::new("game-output-colors")
+                        .label(ts!("settings.game_output_colors"))
+                        .checked(!interface_config.disable_game_output_colors)
+                        .on_click(|value, _, cx| {
+                            InterfaceConfig::get_mut(cx).disable_game_output_colors = !*value;
+                        }))
+                    .child(ButtonGroup::new("game-output-line-limit")
+                        .outline()
+                        .child(Button::new("50k").label(ts!("settings.lines_50k")).selected(line_limit == GameOutputLineLimit::Lines50k))
+                        .child(Button::new("100k").label(ts!("settings.lines_100k")).selected(line_limit == GameOutputLineLimit::Lines100k))
+                        .child(Button::new("250k").label(ts!("settings.lines_250k")).selected(line_limit == GameOutputLineLimit::Lines250k))
+                        .child(Button::new("unlimited").label(ts!("settings.unlimited_lines")).selected(line_limit == GameOutputLineLimit::Unlimited))
+                        .on_click(cx.listener(|_, clicked: &Vec<usize>, _, cx| {
+                            let limit = match clicked.first() {
+                                Some(0) => GameOutputLineLimit::Lines50k,
+                                Some(1) => GameOutputLineLimit::Lines100k,
+                                Some(2) => GameOutputLineLimit::Lines250k,
+                                Some(3) => GameOutputLineLimit::Unlimited,
+                                _ => return,
+                            };
+                            InterfaceConfig::get_mut(cx).game_output_line_limit = limit;
+                            cx.notify();
+                        })))
+                    .child(Checkbox::new("spill-game-output")
+                        .label(ts!("settings.spill_game_output"))
+                        .disabled(line_limit == GameOutputLineLimit::Unlimited)
+                        .checked(interface_config.spill_game_output_to_disk)
+                        .on_click(|value, _, cx| {
+                            InterfaceConfig::get_mut(cx).spill_game_output_to_disk = *value;
+                        }))
+            ));
 
+        let backend_settings = self.backend_settings.read(cx).settings;
+        let launch_window_behavior = interface_config.launch_window_behavior;
         if let Some(backend_config) = &self.backend_config {
             div = div
                 .child(crate::labelled(
-                    "Launching",
+                    ts!("settings.launching"),
                     v_flex().gap_2()
-                        .child(Checkbox::new("hide-on-launch")
-                            .label("Hide main window on launch")
-                            .checked(interface_config.hide_main_window_on_launch)
-                            .on_click(|value, _, cx| {
-                                InterfaceConfig::get_mut(cx).hide_main_window_on_launch = *value;
-                            }))
+                        .child(v_flex()
+                            .gap_1()
+                            .child(ts!("settings.on_launch"))
+                            .child(ButtonGroup::new("launch-window-behavior")
+                                .outline()
+                                .child(Button::new("keep-open").label(ts!("settings.on_launch_keep_open"))
+                                    .selected(launch_window_behavior == LaunchWindowBehavior::KeepOpen))
+                                .child(Button::new("minimize").label(ts!("settings.on_launch_minimize"))
+                                    .selected(launch_window_behavior == LaunchWindowBehavior::Minimize))
+                                .child(Button::new("close").label(ts!("settings.on_launch_close"))
+                                    .selected(launch_window_behavior == LaunchWindowBehavior::Close))
+                                .on_click(cx.listener(|_, clicked: &Vec<usize>, _, cx| {
+                                    let behavior = match clicked.first() {
+                                        Some(0) => LaunchWindowBehavior::KeepOpen,
+                                        Some(1) => LaunchWindowBehavior::Minimize,
+                                        Some(2) => LaunchWindowBehavior::Close,
+                                        _ => return,
+                                    };
+                                    InterfaceConfig::get_mut(cx).launch_window_behavior = behavior;
+                                    cx.notify();
+                                }))))
+                        .child(Checkbox::new("quit-on-game-exit")
+                            .label(ts!("settings.quit_on_game_exit"))
+                            .disabled(launch_window_behavior != LaunchWindowBehavior::Close)
+                            .checked(backend_settings.quit_on_game_exit)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.update_backend_settings(cx, |backend_settings| {
+                                    backend_settings.quit_on_game_exit = *value;
+                                });
+                            })))
                         .child(Checkbox::new("open-game-output")
-                            .label("Open game output on launch")
+                            .label(ts!("settings.open_game_output_on_launch"))
                             .checked(backend_config.open_game_output_when_launching)
                             .on_click(cx.listener({
                                 let backend_handle = self.backend_handle.clone();
@@ -170,11 +438,661 @@ impl Render for Settings {
                                     settings.update_backend_configuration(cx);
                                 }
                             })))
-                ))
+                        .child(v_flex()
+                            .gap_1()
+                            .child(ts!("settings.stop_grace_period"))
+                            .child(ButtonGroup::new("stop-grace-period")
+                                .outline()
+                                .children(STOP_GRACE_PERIODS.iter().map(|secs| {
+                                    Button::new(("grace", *secs as usize))
+                                        .label(SharedString::new(rust_i18n::t!("common.seconds_short", seconds = secs)))
+                                        .selected(backend_config.stop_grace_period_secs() == *secs)
+                                }))
+                                .on_click(cx.listener({
+                                    let backend_handle = self.backend_handle.clone();
+                                    move |settings, clicked: &Vec<usize>, _, cx| {
+                                        let Some(secs) = clicked.first().and_then(|index| STOP_GRACE_PERIODS.get(*index)) else {
+                                            return;
+                                        };
+                                        backend_handle.send(MessageToBackend::SetStopGracePeriod { secs: *secs });
+                                        settings.update_backend_configuration(cx);
+                                    }
+                                }))))
+                ));
+
+            div = div.child(crate::labelled(
+                ts!("settings.accounts"),
+                Checkbox::new("head-overlay")
+                    .label(ts!("settings.head_overlay"))
+                    .checked(!backend_config.hide_head_overlay)
+                    .on_click(cx.listener({
+                        let backend_handle = self.backend_handle.clone();
+                        move |settings, value, _, cx| {
+                            backend_handle.send(MessageToBackend::SetHideHeadOverlay {
+                                value: !*value
+                            });
+                            settings.update_backend_configuration(cx);
+                        }
+                    }))
+            ));
+
+            div = div.child(crate::labelled(
+                ts!("settings.updates"),
+                Checkbox::new("launcher-update-check")
+                    .label(ts!("settings.check_for_launcher_updates"))
+                    .checked(!backend_config.disable_launcher_update_check)
+                    .on_click(cx.listener({
+                        let backend_handle = self.backend_handle.clone();
+                        move |settings, value, _, cx| {
+                            backend_handle.send(MessageToBackend::SetLauncherUpdateCheck {
+                                value: *value
+                            });
+                            settings.update_backend_configuration(cx);
+                        }
+                    }))
+            ));
+
+            div = div.child(crate::labelled(
+                ts!("settings.instances"),
+                v_flex().gap_2()
+                    .child(Checkbox::new("file-watching")
+                        .label(ts!("settings.file_watching"))
+                        .checked(!backend_config.disable_file_watching)
+                        .on_click(cx.listener({
+                            let backend_handle = self.backend_handle.clone();
+                            move |settings, value, _, cx| {
+                                backend_handle.send(MessageToBackend::SetFileWatching {
+                                    value: *value
+                                });
+                                settings.update_backend_configuration(cx);
+                            }
+                        })))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                        .child(ts!("settings.file_watching_description")))
+            ));
+
+            if self.portable {
+                // The saved logins are encrypted with the launcher lock secret
+                let lock_enabled = self.launcher_lock.read(cx).enabled;
+                div = div.child(crate::labelled(ts!("settings.portable"),
+                    v_flex().gap_2()
+                        .child(Checkbox::new("portable-credentials")
+                            .label(ts!("settings.portable_credentials"))
+                            .checked(backend_config.portable_credentials_consent && lock_enabled)
+                            .disabled(!lock_enabled)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, _, cx| {
+                                    backend_handle.send(MessageToBackend::SetPortableCredentialsConsent {
+                                        value: *value
+                                    });
+                                    settings.update_backend_configuration(cx);
+                                }
+                            })))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child(ts!("settings.portable_credentials_description")))
+                ));
+            }
         } else {
             div = div.child(Spinner::new().large());
         }
 
+        div = div.child(crate::labelled(ts!("settings.launcher_lock"), self.render_launcher_lock(cx)));
+
         div
     }
+
+    fn render_languages(&self, cx: &mut Context<Self>) -> ButtonGroup {
+        let mut languages = rust_i18n::available_locales!();
+        languages.sort_unstable();
+
+        let current = InterfaceConfig::get(cx).language.clone();
+        let current = if current.is_empty() { crate::DEFAULT_LANGUAGE } else { current.as_str() };
+
+        let buttons = languages.iter().map(|&language| {
+            let name = rust_i18n::t!("language_name", locale = language);
+            Button::new(language).label(SharedString::new(name)).selected(language == current)
+        }).collect::<Vec<_>>();
+
+        ButtonGroup::new("language")
+            .outline()
+            .children(buttons)
+            .on_click(cx.listener(move |_, clicked: &Vec<usize>, _, cx| {
+                let Some(&language) = clicked.first().and_then(|index| languages.get(*index)) else {
+                    return;
+                };
+                InterfaceConfig::get_mut(cx).language = SharedString::new_static(language);
+                crate::apply_language(cx);
+                cx.refresh_windows();
+            }))
+    }
+
+    fn render_accent_colors(&self, cx: &mut Context<Self>) -> Div {
+        let selected = InterfaceConfig::get(cx).accent_color;
+        let theme = cx.theme();
+
+        let swatches = crate::theme::ACCENT_COLORS.iter().enumerate().map(|(index, &color)| {
+            div()
+                .id(("accent-color", index))
+                .size_6()
+                .rounded_full()
+                .cursor_pointer()
+                .bg(rgb(color))
+                .border_2()
+                .border_color(if selected == Some(color) { theme.foreground } else { gpui::transparent_black() })
+                .on_click(cx.listener(move |_, _, _, cx| {
+                    InterfaceConfig::get_mut(cx).accent_color = Some(color);
+                    crate::theme::apply(cx);
+                    cx.notify();
+                }))
+        }).collect::<Vec<_>>();
+
+        h_flex()
+            .gap_2()
+            .child(Button::new("default-accent-color").label(ts!("settings.theme_default")).small().selected(selected.is_none()).on_click(cx.listener(|_, _, _, cx| {
+                InterfaceConfig::get_mut(cx).accent_color = None;
+                crate::theme::apply(cx);
+                cx.notify();
+            })))
+            .children(swatches)
+    }
+
+    fn render_launcher_lock(&self, cx: &mut Context<Self>) -> Div {
+        let launcher_lock = self.launcher_lock.read(cx);
+
+        if launcher_lock.enabled {
+            let description = if let Some(minutes) = launcher_lock.idle_lock_minutes {
+                SharedString::new(rust_i18n::t!("launcher_lock.locked_on_start_and_idle", minutes = minutes))
+            } else {
+                ts!("launcher_lock.locked_on_start")
+            };
+
+            return v_flex().gap_2()
+                .child(div().text_sm().text_color(cx.theme().muted_foreground).child(description))
+                .child(h_flex().gap_2()
+                    .child(Button::new("lock-now").label(ts!("launcher_lock.lock_now")).on_click({
+                        let backend_handle = self.backend_handle.clone();
+                        move |_, _, _| {
+                            backend_handle.send(MessageToBackend::LockLauncher);
+                        }
+                    }))
+                    .child(Button::new("remove-lock").label(ts!("launcher_lock.remove")).danger().on_click({
+                        let backend_handle = self.backend_handle.clone();
+                        move |_, _, _| {
+                            backend_handle.send(MessageToBackend::SetLauncherLock {
+                                secret: None,
+                                idle_lock_minutes: None,
+                            });
+                        }
+                    })));
+        }
+
+        v_flex().gap_2()
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(ts!("launcher_lock.description")))
+            .child(Input::new(&self.lock_secret_input))
+            .child(crate::labelled(ts!("launcher_lock.idle_minutes"), Input::new(&self.lock_idle_input)))
+            .child(Button::new("enable-lock").label(ts!("launcher_lock.enable")).on_click(cx.listener(|settings, _, window, cx| {
+                let secret = settings.lock_secret_input.read(cx).value();
+                if secret.trim().len() < 4 {
+                    push_notification(NotificationType::Error, ts!("launcher_lock.secret_too_short"), window, cx);
+                    return;
+                }
+
+                let idle = settings.lock_idle_input.read(cx).value();
+                let idle_lock_minutes = if idle.trim().is_empty() {
+                    None
+                } else if let Ok(minutes) = idle.trim().parse::<u32>() && minutes > 0 {
+                    Some(minutes)
+                } else {
+                    push_notification(NotificationType::Error, ts!("common.invalid_minutes"), window, cx);
+                    return;
+                };
+
+                settings.backend_handle.send(MessageToBackend::SetLauncherLock {
+                    secret: Some(secret.as_ref().into()),
+                    idle_lock_minutes,
+                });
+
+                settings.lock_secret_input.update(cx, |input, cx| input.set_value("", window, cx));
+                settings.lock_idle_input.update(cx, |input, cx| input.set_value("", window, cx));
+            })))
+    }
+
+    fn render_network(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let Some(backend_config) = &self.backend_config else {
+            return v_flex().px_4().py_3().child(Spinner::new().large());
+        };
+
+        if !self.network_inputs_loaded {
+            self.network_inputs_loaded = true;
+            let proxy = backend_config.proxy.clone();
+            self.proxy.mode = proxy.mode;
+            self.proxy.host_input.update(cx, |input, cx| input.set_value(proxy.host, window, cx));
+            if proxy.port != 0 {
+                self.proxy.port_input.update(cx, |input, cx| input.set_value(proxy.port.to_string(), window, cx));
+            }
+            self.proxy.username_input.update(cx, |input, cx| input.set_value(proxy.username.unwrap_or_default(), window, cx));
+            self.proxy.password_input.update(cx, |input, cx| input.set_value(proxy.password.unwrap_or_default(), window, cx));
+
+            let mirrors = backend_config.mirrors.clone();
+            self.set_mirror_inputs(mirrors, window, cx);
+        }
+
+        let mode = self.proxy.mode;
+        let mode_group = ButtonGroup::new("proxy-mode")
+            .outline()
+            .child(Button::new("system").label(ts!("settings.system")).selected(mode == ProxyMode::System))
+            .child(Button::new("direct").label(ts!("proxy.no_proxy")).selected(mode == ProxyMode::Direct))
+            .child(Button::new("http").label("HTTP").selected(mode == ProxyMode::Http))
+            .child(Button::new("socks5").label("SOCKS5").selected(mode == ProxyMode::Socks5))
+            .on_click(cx.listener(|settings, clicked: &Vec<usize>, _, cx| {
+                settings.proxy.mode = match clicked.first() {
+                    Some(0) => ProxyMode::System,
+                    Some(1) => ProxyMode::Direct,
+                    Some(2) => ProxyMode::Http,
+                    Some(3) => ProxyMode::Socks5,
+                    _ => return,
+                };
+                settings.proxy.test_state = ProxyTestState::Idle;
+                cx.notify();
+            }));
+
+        let description = match mode {
+            ProxyMode::System => ts!("proxy.system_description"),
+            ProxyMode::Direct => ts!("proxy.direct_description"),
+            ProxyMode::Http => ts!("proxy.http_description"),
+            ProxyMode::Socks5 => ts!("proxy.socks5_description"),
+        };
+
+        let testing = matches!(self.proxy.test_state, ProxyTestState::Testing);
+        let actions = h_flex().gap_2()
+            .child(Button::new("save-proxy").success().label(ts!("settings.save")).on_click(cx.listener(|settings, _, window, cx| {
+                settings.save_proxy(window, cx);
+            })))
+            .child(Button::new("test-proxy").label(ts!("proxy.test_connection")).loading(testing).on_click(cx.listener(|settings, _, window, cx| {
+                settings.test_proxy(window, cx);
+            })));
+
+        let theme = cx.theme();
+        let test_status = match &self.proxy.test_state {
+            ProxyTestState::Idle => None,
+            ProxyTestState::Testing => Some(div().text_sm().text_color(theme.muted_foreground).child(ts!("proxy.testing"))),
+            ProxyTestState::Success(duration) => Some(div().text_sm().text_color(theme.success)
+                .child(SharedString::new(rust_i18n::t!("proxy.connected", millis = duration.as_millis())))),
+            ProxyTestState::Failed(error) => Some(div().text_sm().text_color(theme.danger)
+                .child(SharedString::new(rust_i18n::t!("proxy.connection_failed", error = error)))),
+        };
+
+        let mut proxy = v_flex().gap_2()
+            .child(mode_group)
+            .child(div().text_sm().text_color(theme.muted_foreground).child(description));
+
+        if matches!(mode, ProxyMode::Http | ProxyMode::Socks5) {
+            proxy = proxy
+                .child(h_flex().gap_2()
+                    .child(div().flex_1().child(crate::labelled(ts!("proxy.host"), Input::new(&self.proxy.host_input))))
+                    .child(div().w_24().child(crate::labelled(ts!("proxy.port"), Input::new(&self.proxy.port_input)))))
+                .child(crate::labelled(ts!("proxy.username"), Input::new(&self.proxy.username_input)))
+                .child(crate::labelled(ts!("proxy.password"), Input::new(&self.proxy.password_input)));
+        }
+
+        proxy = proxy
+            .child(actions)
+            .children(test_status)
+            .child(div().text_sm().text_color(theme.muted_foreground)
+                .child(ts!("proxy.applies_to")));
+
+        v_flex()
+            .px_4()
+            .py_3()
+            .gap_3()
+            .child(crate::labelled(ts!("proxy.title"), proxy))
+            .child(crate::labelled(ts!("mirrors.title"), self.render_mirrors(cx)))
+            .child(crate::labelled(ts!("settings.downloads"), self.render_downloads(cx)))
+    }
+
+    fn render_downloads(&self, cx: &mut Context<Self>) -> Div {
+        let backend_settings = self.backend_settings.read(cx).settings;
+
+        let concurrent_downloads = ButtonGroup::new("concurrent-downloads")
+            .outline()
+            .children(CONCURRENT_DOWNLOADS.iter().map(|count| {
+                Button::new(("downloads", *count as usize))
+                    .label(count.to_string())
+                    .selected(backend_settings.concurrent_downloads == *count)
+            }))
+            .on_click(cx.listener(|settings, clicked: &Vec<usize>, _, cx| {
+                let Some(count) = clicked.first().and_then(|index| CONCURRENT_DOWNLOADS.get(*index)) else {
+                    return;
+                };
+                settings.update_backend_settings(cx, |backend_settings| backend_settings.concurrent_downloads = *count);
+            }));
+
+        let connect_timeout = ButtonGroup::new("connect-timeout")
+            .outline()
+            .children(CONNECT_TIMEOUTS.iter().map(|secs| {
+                Button::new(("connect", *secs as usize))
+                    .label(SharedString::new(rust_i18n::t!("common.seconds_short", seconds = secs)))
+                    .selected(backend_settings.connect_timeout_secs == *secs)
+            }))
+            .on_click(cx.listener(|settings, clicked: &Vec<usize>, _, cx| {
+                let Some(secs) = clicked.first().and_then(|index| CONNECT_TIMEOUTS.get(*index)) else {
+                    return;
+                };
+                settings.update_backend_settings(cx, |backend_settings| backend_settings.connect_timeout_secs = *secs);
+            }));
+
+        let read_timeout = ButtonGroup::new("read-timeout")
+            .outline()
+            .children(READ_TIMEOUTS.iter().map(|secs| {
+                Button::new(("read", *secs as usize))
+                    .label(SharedString::new(rust_i18n::t!("common.seconds_short", seconds = secs)))
+                    .selected(backend_settings.read_timeout_secs == *secs)
+            }))
+            .on_click(cx.listener(|settings, clicked: &Vec<usize>, _, cx| {
+                let Some(secs) = clicked.first().and_then(|index| READ_TIMEOUTS.get(*index)) else {
+                    return;
+                };
+                settings.update_backend_settings(cx, |backend_settings| backend_settings.read_timeout_secs = *secs);
+            }));
+
+        v_flex().gap_2()
+            .child(v_flex().gap_1().child(ts!("settings.concurrent_downloads")).child(concurrent_downloads))
+            .child(v_flex().gap_1().child(ts!("settings.connect_timeout")).child(connect_timeout))
+            .child(v_flex().gap_1().child(ts!("settings.read_timeout")).child(read_timeout))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(ts!("settings.downloads_description")))
+    }
+
+    fn render_mirrors(&self, cx: &mut Context<Self>) -> Div {
+        let actions = h_flex().gap_2()
+            .child(Button::new("save-mirrors").success().label(ts!("settings.save")).on_click(cx.listener(|settings, _, window, cx| {
+                let mirrors = settings.mirrors_from_inputs(cx);
+                settings.backend_handle.send(MessageToBackend::SetMirrors { mirrors });
+                settings.update_backend_configuration(cx);
+                push_notification(NotificationType::Success, ts!("mirrors.saved"), window, cx);
+            })))
+            .child(Button::new("bmclapi").label(ts!("mirrors.use_bmclapi")).on_click(cx.listener(|settings, _, window, cx| {
+                settings.set_mirror_inputs(MirrorConfig::bmclapi(), window, cx);
+            })))
+            .child(Button::new("reset-mirrors").label(ts!("mirrors.use_official")).on_click(cx.listener(|settings, _, window, cx| {
+                settings.set_mirror_inputs(MirrorConfig::default(), window, cx);
+            })));
+
+        v_flex().gap_2()
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(ts!("mirrors.description")))
+            .child(crate::labelled(ts!("mirrors.version_metadata"), Input::new(&self.mirrors.mojang_meta_input)))
+            .child(crate::labelled(ts!("mirrors.assets"), Input::new(&self.mirrors.assets_input)))
+            .child(crate::labelled(ts!("mirrors.libraries"), Input::new(&self.mirrors.libraries_input)))
+            .child(crate::labelled(ts!("mirrors.modrinth_api"), Input::new(&self.mirrors.modrinth_api_input)))
+            .child(actions)
+    }
+
+    fn set_mirror_inputs(&mut self, mirrors: MirrorConfig, window: &mut Window, cx: &mut Context<Self>) {
+        let values = [
+            (&self.mirrors.mojang_meta_input, mirrors.mojang_meta),
+            (&self.mirrors.assets_input, mirrors.assets),
+            (&self.mirrors.libraries_input, mirrors.libraries),
+            (&self.mirrors.modrinth_api_input, mirrors.modrinth_api),
+        ];
+        for (input, value) in values {
+            input.update(cx, |input, cx| input.set_value(value.unwrap_or_default(), window, cx));
+        }
+    }
+
+    fn mirrors_from_inputs(&self, cx: &App) -> MirrorConfig {
+        let read = |input: &Entity<InputState>| {
+            let value = input.read(cx).value().trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+
+        MirrorConfig {
+            mojang_meta: read(&self.mirrors.mojang_meta_input),
+            assets: read(&self.mirrors.assets_input),
+            libraries: read(&self.mirrors.libraries_input),
+            modrinth_api: read(&self.mirrors.modrinth_api_input),
+        }
+    }
+
+    fn proxy_from_inputs(&self, cx: &App) -> Result<ProxyConfig, SharedString> {
+        let mut proxy = ProxyConfig {
+            mode: self.proxy.mode,
+            ..ProxyConfig::default()
+        };
+        if !matches!(proxy.mode, ProxyMode::Http | ProxyMode::Socks5) {
+            return Ok(proxy);
+        }
+
+        proxy.host = self.proxy.host_input.read(cx).value().trim().to_string();
+        proxy.port = self.proxy.port_input.read(cx).value().trim().parse().map_err(|_| ts!("proxy.invalid_port"))?;
+
+        let username = self.proxy.username_input.read(cx).value();
+        if !username.is_empty() {
+            proxy.username = Some(username.to_string());
+            let password = self.proxy.password_input.read(cx).value();
+            proxy.password = (!password.is_empty()).then(|| password.to_string());
+        }
+
+        proxy.url().map_err(|error| SharedString::new(error.to_string()))?;
+        Ok(proxy)
+    }
+
+    fn save_proxy(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let proxy = match self.proxy_from_inputs(cx) {
+            Ok(proxy) => proxy,
+            Err(error) => {
+                push_notification(NotificationType::Error, error, window, cx);
+                return;
+            },
+        };
+
+        self.backend_handle.send(MessageToBackend::SetProxy { proxy });
+        self.update_backend_configuration(cx);
+        push_notification(NotificationType::Success, ts!("proxy.saved"), window, cx);
+    }
+
+    fn test_proxy(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let proxy = match self.proxy_from_inputs(cx) {
+            Ok(proxy) => proxy,
+            Err(error) => {
+                push_notification(NotificationType::Error, error, window, cx);
+                return;
+            },
+        };
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.proxy.test_state = ProxyTestState::Testing;
+        self.proxy._test_task = cx.spawn(async move |settings, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = settings.update(cx, move |settings, cx| {
+                settings.proxy.test_state = match result {
+                    Ok(duration) => ProxyTestState::Success(duration),
+                    Err(error) => ProxyTestState::Failed(error),
+                };
+                cx.notify();
+            });
+        });
+        cx.notify();
+
+        self.backend_handle.send(MessageToBackend::TestProxy {
+            proxy,
+            channel: send,
+        });
+    }
+
+    fn render_metadata_cache(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let Some(backend_config) = &self.backend_config else {
+            return v_flex().px_4().py_3().child(Spinner::new().large());
+        };
+
+        if !self.metadata_cache.inputs_loaded {
+            self.metadata_cache.inputs_loaded = true;
+            let cache_config = backend_config.metadata_cache;
+            self.metadata_cache.memory_ttl_input.update(cx, |input, cx| {
+                input.set_value((cache_config.memory_ttl_secs / 60).to_string(), window, cx)
+            });
+            self.metadata_cache.disk_ttl_input.update(cx, |input, cx| {
+                input.set_value((cache_config.disk_ttl_secs / 60).to_string(), window, cx)
+            });
+        }
+
+        let save = Button::new("save-metadata-ttl").success().label(ts!("settings.save")).on_click(cx.listener(|settings, _, window, cx| {
+            let read = |input: &Entity<InputState>| input.read(cx).value().trim().parse::<u64>().ok();
+            let (Some(memory_ttl_minutes), Some(disk_ttl_minutes)) = (read(&settings.metadata_cache.memory_ttl_input), read(&settings.metadata_cache.disk_ttl_input)) else {
+                push_notification(NotificationType::Error, ts!("common.invalid_minutes"), window, cx);
+                return;
+            };
+
+            settings.backend_handle.send(MessageToBackend::SetMetadataCacheConfig {
+                config: MetadataCacheConfig {
+                    memory_ttl_secs: memory_ttl_minutes * 60,
+                    disk_ttl_secs: disk_ttl_minutes * 60,
+                },
+            });
+            settings.update_backend_configuration(cx);
+            push_notification(NotificationType::Success, ts!("metadata_cache.saved"), window, cx);
+        }));
+
+        let ttl = v_flex().gap_2()
+            .child(h_flex().gap_2()
+                .child(div().flex_1().child(crate::labelled(ts!("metadata_cache.memory_ttl"), Input::new(&self.metadata_cache.memory_ttl_input))))
+                .child(div().flex_1().child(crate::labelled(ts!("metadata_cache.disk_ttl"), Input::new(&self.metadata_cache.disk_ttl_input)))))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(ts!("metadata_cache.zero_minutes")))
+            .child(save);
+
+        let entries = match &self.metadata_cache.entries {
+            None => v_flex().child(Spinner::new()),
+            Some(entries) => {
+                let mut list = v_flex().gap_1();
+                for (index, entry) in entries.iter().enumerate() {
+                    list = list.child(self.render_metadata_cache_entry(index, entry, cx));
+                }
+                list
+            },
+        };
+
+        v_flex()
+            .px_4()
+            .py_3()
+            .gap_3()
+            .child(crate::labelled(ts!("metadata_cache.duration"), ttl))
+            .child(crate::labelled(ts!("metadata_cache.entries"), entries))
+    }
+
+    fn render_keybindings(&mut self, cx: &mut Context<Self>) -> Div {
+        let rows = self.keybinding_inputs.iter().map(|(keybind, input)| {
+            let keybind = *keybind;
+            h_flex()
+                .gap_2()
+                .child(div().w_40().child(keybind.name()))
+                .child(div().flex_1().child(Input::new(input)))
+                .child(Button::new(("reset-keybind", keybind as usize)).label(ts!("settings.reset")).on_click({
+                    let input = input.clone();
+                    move |_, window, cx| {
+                        if let Err(error) = crate::keymap::rebind(&[(keybind, "")], cx) {
+                            push_notification(NotificationType::Error, error, window, cx);
+                            return;
+                        }
+                        input.update(cx, |input, cx| input.set_value(keybind.default_keystroke(), window, cx));
+                    }
+                }))
+        }).collect::<Vec<_>>();
+
+        let save = Button::new("save-keybindings").success().label(ts!("settings.save")).on_click(cx.listener(|settings, _, window, cx| {
+            let keystrokes: Vec<_> = settings.keybinding_inputs.iter()
+                .map(|(keybind, input)| (*keybind, input.read(cx).value().clone()))
+                .collect();
+            let changes: Vec<_> = keystrokes.iter().map(|(keybind, keystroke)| (*keybind, keystroke.as_str())).collect();
+            if let Err(error) = crate::keymap::rebind(&changes, cx) {
+                push_notification(NotificationType::Error, error, window, cx);
+                return;
+            }
+            push_notification(NotificationType::Success, ts!("settings.keybindings_saved"), window, cx);
+        }));
+
+        v_flex()
+            .px_4()
+            .py_3()
+            .gap_3()
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(ts!("settings.keybindings_help")))
+            .child(v_flex().gap_2().children(rows))
+            .child(save)
+    }
+
+    fn render_metadata_cache_entry(&self, index: usize, entry: &MetadataCacheEntry, cx: &mut Context<Self>) -> Div {
+        let refreshing = self.metadata_cache.refreshing.as_ref() == Some(&entry.path);
+        let label = if entry.kind == MetadataCacheKind::AssetsIndex { ts!("metadata_cache.remove") } else { ts!("storage.refresh") };
+        let refresh = Button::new(("refresh-metadata", index))
+            .small()
+            .label(label)
+            .loading(refreshing)
+            .disabled(self.metadata_cache.refreshing.is_some())
+            .on_click(cx.listener({
+                let entry = entry.clone();
+                move |settings, _, window, cx| {
+                    settings.refresh_metadata_cache_entry(&entry, window, cx);
+                }
+            }));
+
+        let details = match entry.modified {
+            Some(modified) => {
+                let modified = chrono::DateTime::<chrono::Local>::from(modified).format("%d/%m/%Y %H:%M");
+                SharedString::new(rust_i18n::t!("metadata_cache.updated", size = crate::format_size(entry.size), modified = modified))
+            },
+            None => ts!("metadata_cache.not_cached"),
+        };
+
+        let theme = cx.theme();
+        h_flex()
+            .gap_2()
+            .child(v_flex()
+                .flex_1()
+                .child(div().text_sm().child(entry.name.clone()))
+                .child(div().text_xs().text_color(theme.muted_foreground).child(details)))
+            .child(refresh)
+    }
+
+    fn load_metadata_cache_entries(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.metadata_cache._load_task = cx.spawn(async move |settings, cx| {
+            let Ok(entries) = recv.await else {
+                return;
+            };
+            let _ = settings.update(cx, move |settings, cx| {
+                settings.metadata_cache.entries = Some(entries);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetMetadataCacheEntries { channel: send });
+    }
+
+    fn refresh_metadata_cache_entry(&mut self, entry: &MetadataCacheEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        let name = entry.name.clone();
+        self.metadata_cache.refreshing = Some(entry.path.clone());
+        self.metadata_cache._refresh_task = cx.spawn_in(window, async move |settings, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = settings.update_in(cx, move |settings, window, cx| {
+                settings.metadata_cache.refreshing = None;
+                if let Err(error) = result {
+                    let message = SharedString::new(rust_i18n::t!("metadata_cache.refresh_failed", name = name, error = error));
+                    push_notification(NotificationType::Error, message, window, cx);
+                }
+                settings.load_metadata_cache_entries(cx);
+                cx.notify();
+            });
+        });
+        cx.notify();
+
+        self.backend_handle.send(MessageToBackend::RefreshMetadataCacheEntry {
+            kind: entry.kind,
+            path: entry.path.clone(),
+            channel: send,
+        });
+    }
 }
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use bridge::instance::InstanceID;
+
+use crate::ui::PageType;
+
+/// Pages kept for going back and forward, the oldest are dropped first
+const MAX_HISTORY: usize = 50;
+
+/// A page along with the pages leading to it, as shown in the breadcrumb
+#[derive(Clone)]
+pub struct HistoryEntry {
+    pub page: PageType,
+    pub breadcrumbs: Arc<[PageType]>,
+}
+
+impl HistoryEntry {
+    fn refers_to_instance(&self, id: InstanceID) -> bool {
+        self.page.refers_to_instance(id) || self.breadcrumbs.iter().any(|page| page.refers_to_instance(id))
+    }
+}
+
+/// The pages that were shown before and after the current one, like the history of a web browser
+#[derive(Default)]
+pub struct NavigationHistory {
+    back: Vec<HistoryEntry>,
+    forward: Vec<HistoryEntry>,
+}
+
+impl NavigationHistory {
+    /// Remembers the page that was shown before navigating somewhere new, the pages that could be
+    /// gone forward to are forgotten
+    pub fn push(&mut self, previous: HistoryEntry) {
+        if self.back.len() >= MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.back.push(previous);
+        self.forward.clear();
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    /// Returns the page to show instead of the current one
+    pub fn go_back(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.back.pop()?;
+        self.forward.push(current);
+        Some(entry)
+    }
+
+    /// Returns the page to show instead of the current one
+    pub fn go_forward(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.forward.pop()?;
+        self.back.push(current);
+        Some(entry)
+    }
+
+    /// Forgets the pages of an instance that has been removed
+    pub fn remove_instance(&mut self, id: InstanceID) {
+        self.back.retain(|entry| !entry.refers_to_instance(id));
+        self.forward.retain(|entry| !entry.refers_to_instance(id));
+    }
+}
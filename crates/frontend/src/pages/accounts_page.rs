@@ -0,0 +1,271 @@
+use std::{sync::Arc, time::{Duration, SystemTime}};
+
+use bridge::{account::{Account, AccountTokenState}, handle::BackendHandle, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, scroll::ScrollableElement, v_flex, ActiveTheme as _, Disableable, Icon, IconName, Sizable, WindowExt
+};
+use rand::Rng;
+use rustc_hash::FxHashMap;
+use uuid::Uuid;
+
+use crate::{entity::{account::AccountEntries, DataEntities}, png_render_cache, ts, ui};
+
+/// All stored accounts, with the state of their credentials and actions to manage them
+pub struct AccountsPage {
+    backend_handle: BackendHandle,
+    accounts: Entity<AccountEntries>,
+    /// None while the credentials are being read
+    token_states: Option<FxHashMap<Uuid, AccountTokenState>>,
+    _accounts_subscription: Subscription,
+    _get_token_states_task: Task<()>,
+    _expiry_task: Task<()>,
+}
+
+impl AccountsPage {
+    pub fn new(data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        // Adding, removing or logging in to an account changes its credentials
+        let _accounts_subscription = cx.observe(&data.accounts, |page, _, cx| page.refresh_token_states(cx));
+
+        let mut page = Self {
+            backend_handle: data.backend_handle.clone(),
+            accounts: data.accounts.clone(),
+            token_states: None,
+            _accounts_subscription,
+            _get_token_states_task: Task::ready(()),
+            _expiry_task: Task::ready(()),
+        };
+
+        page.refresh_token_states(cx);
+
+        page
+    }
+
+    fn refresh_token_states(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_token_states_task = cx.spawn(async move |page, cx| {
+            let Ok(states) = recv.await else {
+                return;
+            };
+            let _ = page.update(cx, move |page, cx| {
+                page.token_states = Some(states.into_iter().collect());
+                page.watch_expiry(cx);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetAccountTokenStates {
+            channel: send,
+        });
+    }
+
+    /// Re-renders every minute while an access token is valid so the time it's valid for doesn't go stale, and reads
+    /// the credentials again once one has expired
+    fn watch_expiry(&mut self, cx: &mut Context<Self>) {
+        let has_valid = self.token_states.iter().flat_map(|states| states.values())
+            .any(|state| matches!(state, AccountTokenState::Valid { .. }));
+        if !has_valid {
+            self._expiry_task = Task::ready(());
+            return;
+        }
+
+        self._expiry_task = cx.spawn(async move |page, cx| {
+            loop {
+                gpui::Timer::after(Duration::from_secs(60)).await;
+                let Ok(expired) = page.update(cx, |page, cx| {
+                    cx.notify();
+                    let now = SystemTime::now();
+                    page.token_states.iter().flat_map(|states| states.values())
+                        .any(|state| matches!(state, AccountTokenState::Valid { expires_at } if *expires_at <= now))
+                }) else {
+                    return;
+                };
+                if expired {
+                    _ = page.update(cx, |page, cx| page.refresh_token_states(cx));
+                    return;
+                }
+            }
+        });
+    }
+
+    fn confirm_remove(&self, account: &Account, window: &mut Window, cx: &mut Context<Self>) {
+        let uuid = account.uuid;
+        let message = SharedString::new(rust_i18n::t!("accounts.remove_confirm", name = account.username));
+        let backend_handle = self.backend_handle.clone();
+        window.open_dialog(cx, move |dialog, _, _| {
+            let backend_handle = backend_handle.clone();
+            dialog
+                .confirm()
+                .title(ts!("accounts.remove"))
+                .child(message.clone())
+                .on_ok(move |_, _, _| {
+                    backend_handle.send(MessageToBackend::DeleteAccount { uuid });
+                    true
+                })
+        });
+    }
+
+    fn render_token_state(&self, uuid: Uuid, cx: &App) -> Div {
+        let theme = cx.theme();
+        let (text, color) = match self.token_states.as_ref().map(|states| states.get(&uuid).copied()) {
+            None => (ts!("accounts.token_checking"), theme.muted_foreground),
+            Some(None) | Some(Some(AccountTokenState::Unknown)) => (ts!("accounts.token_unknown"), theme.warning),
+            Some(Some(AccountTokenState::Offline)) => (ts!("accounts.token_offline"), theme.muted_foreground),
+            Some(Some(AccountTokenState::Valid { expires_at })) => {
+                let minutes = expires_at.duration_since(SystemTime::now()).unwrap_or_default().as_secs() / 60;
+                (SharedString::new(rust_i18n::t!("accounts.token_valid", minutes = minutes)), theme.success)
+            },
+            Some(Some(AccountTokenState::Refreshable)) => (ts!("accounts.token_refreshable"), theme.muted_foreground),
+            Some(Some(AccountTokenState::LoginRequired)) => (ts!("accounts.token_login_required"), theme.danger),
+        };
+        div().text_sm().text_color(color).child(text)
+    }
+}
+
+fn show_add_offline_account_dialog(backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let name_input = cx.new(|cx| {
+        InputState::new(window, cx)
+    });
+    let uuid_input = cx.new(|cx| {
+        InputState::new(window, cx).placeholder(ts!("accounts.random"))
+    });
+    let backend_handle = backend_handle.clone();
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let username = name_input.read(cx).value();
+        let valid_name = username.len() >= 1 && username.len() <= 16 &&
+            username.as_bytes().iter().all(|c| *c > 32 && *c < 127);
+        let uuid = uuid_input.read(cx).value();
+        let valid_uuid = uuid.is_empty() || Uuid::try_parse(&uuid).is_ok();
+
+        let valid = valid_name && valid_uuid;
+
+        let backend_handle = backend_handle.clone();
+        let mut add_button = Button::new("add").label(ts!("accounts.add")).disabled(!valid).on_click(move |_, window, cx| {
+            window.close_all_dialogs(cx);
+
+            let uuid = if let Ok(uuid) = Uuid::try_parse(&uuid) {
+               uuid
+            } else {
+                let uuid: u128 = rand::thread_rng().r#gen();
+                let uuid = (uuid & !0xF0000000000000000000) | 0x30000000000000000000; // set version to 3
+                Uuid::from_u128(uuid)
+            };
+
+            backend_handle.send(MessageToBackend::AddOfflineAccount {
+                name: username.clone().into(),
+                uuid
+            });
+        });
+
+        if valid {
+            add_button = add_button.success();
+        }
+
+        dialog.title(ts!("accounts.add_offline_account"))
+            .child(v_flex()
+                .gap_2()
+                .child(crate::labelled(ts!("accounts.name"), Input::new(&name_input)))
+                .child(crate::labelled("UUID", Input::new(&uuid_input)))
+                .child(add_button)
+            )
+    });
+}
+
+impl Render for AccountsPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let add_buttons = h_flex()
+            .gap_2()
+            .child(Button::new("add-account").success().icon(IconName::Plus).label(ts!("accounts.add_account")).on_click({
+                let backend_handle = self.backend_handle.clone();
+                move |_, window, cx| {
+                    crate::root::start_new_account_login(&backend_handle, window, cx);
+                }
+            }))
+            .child(Button::new("add-offline").success().icon(IconName::Plus).label(ts!("accounts.add_offline_account")).on_click({
+                let backend_handle = self.backend_handle.clone();
+                move |_, window, cx| {
+                    show_add_offline_account_dialog(&backend_handle, window, cx);
+                }
+            }));
+        let title = h_flex().gap_8().child(ts!("accounts.title")).child(add_buttons);
+
+        let (accounts, selected_account) = {
+            let accounts = self.accounts.read(cx);
+            (accounts.accounts.clone(), accounts.selected_account_uuid)
+        };
+
+        let trash_icon = Icon::default().path("icons/trash-2.svg");
+
+        let rows = accounts.iter().enumerate().map(|(index, account)| {
+            let head = if let Some(head) = &account.head {
+                let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
+                png_render_cache::render_with_transform(Arc::clone(head), resize, cx)
+            } else {
+                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
+            };
+            let uuid = account.uuid;
+            let selected = Some(uuid) == selected_account;
+
+            let default_button = Button::new(("default", index))
+                .small()
+                .label(if selected { ts!("accounts.default") } else { ts!("accounts.set_default") })
+                .disabled(selected)
+                .when(selected, |this| this.info())
+                .on_click({
+                    let backend_handle = self.backend_handle.clone();
+                    move |_, _, _| {
+                        backend_handle.send(MessageToBackend::SelectAccount { uuid });
+                    }
+                });
+
+            let reauthenticate_button = (!account.offline).then(|| {
+                Button::new(("reauthenticate", index))
+                    .small()
+                    .icon(IconName::Redo)
+                    .label(ts!("accounts.reauthenticate"))
+                    .on_click({
+                        let backend_handle = self.backend_handle.clone();
+                        move |_, window, cx| {
+                            crate::root::start_account_reauthentication(uuid, &backend_handle, window, cx);
+                        }
+                    })
+            });
+
+            let remove_button = Button::new(("remove", index))
+                .small()
+                .danger()
+                .icon(trash_icon.clone())
+                .tooltip(ts!("accounts.remove"))
+                .on_click({
+                    let account = account.clone();
+                    cx.listener(move |page, _, window, cx| {
+                        page.confirm_remove(&account, window, cx);
+                    })
+                });
+
+            h_flex()
+                .gap_3()
+                .py_2()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .child(head.size_8().min_w_8().min_h_8())
+                .child(v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(SharedString::new(account.username.clone()))
+                    .child(div().text_xs().text_color(cx.theme().muted_foreground).child(SharedString::new(uuid.as_hyphenated().to_string()))))
+                .child(self.render_token_state(uuid, cx))
+                .child(default_button)
+                .children(reauthenticate_button)
+                .child(remove_button)
+        }).collect::<Vec<_>>();
+
+        let content = if rows.is_empty() {
+            v_flex().size_full().p_3().text_color(cx.theme().muted_foreground).child(ts!("accounts.no_accounts"))
+        } else {
+            v_flex().size_full().p_3().children(rows)
+        };
+
+        ui::page(cx, title).child(content).overflow_y_scrollbar()
+    }
+}
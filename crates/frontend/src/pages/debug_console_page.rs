@@ -0,0 +1,229 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonGroup, ButtonVariants}, h_flex, v_flex, ActiveTheme as _, Selectable, Sizable
+};
+
+use crate::{component::readonly_text_field::{ReadonlyTextField, ReadonlyTextFieldWithControls}, entity::DataEntities, ts, ui};
+
+/// Only this much of the existing log is read when the page is opened
+const INITIAL_TAIL_BYTES: u64 = 1024 * 1024;
+/// Oldest lines are dropped past this, the full log is still on disk
+const MAX_LINES: usize = 50_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+const LEVELS: [log::LevelFilter; 5] = [
+    log::LevelFilter::Error,
+    log::LevelFilter::Warn,
+    log::LevelFilter::Info,
+    log::LevelFilter::Debug,
+    log::LevelFilter::Trace,
+];
+
+/// Hidden page that tails the launcher's own log, for diagnosing problems in packaged builds
+/// where there is no terminal to read stdout from
+pub struct DebugConsolePage {
+    log_path: Arc<Path>,
+    lines: Vec<(log::Level, Arc<str>)>,
+    max_level: log::LevelFilter,
+    text_field: Entity<ReadonlyTextField>,
+    content: Entity<ReadonlyTextFieldWithControls>,
+    error: Option<SharedString>,
+    _tail_task: Task<()>,
+}
+
+impl DebugConsolePage {
+    pub fn new(data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let log_path = data.panic_messages.launcher_log.clone();
+        let (text_field, content) = Self::create_text_field(window, cx);
+
+        let tail_path = log_path.clone();
+        let _tail_task = cx.spawn(async move |page, cx| {
+            let mut tail = LogTail::default();
+            loop {
+                let result = tail.read(&tail_path);
+                let Ok(()) = page.update(cx, |page, cx| {
+                    match result {
+                        Ok(lines) => {
+                            page.error = None;
+                            page.push_lines(lines, cx);
+                        },
+                        Err(error) => {
+                            page.error = Some(SharedString::new(rust_i18n::t!("logs.read_failed", path = tail_path.display(), error = error)));
+                        },
+                    }
+                    cx.notify();
+                }) else {
+                    return;
+                };
+                cx.background_executor().timer(POLL_INTERVAL).await;
+            }
+        });
+
+        Self {
+            log_path,
+            lines: Vec::new(),
+            max_level: log::LevelFilter::Debug,
+            text_field,
+            content,
+            error: None,
+            _tail_task,
+        }
+    }
+
+    fn create_text_field(window: &mut Window, cx: &mut Context<Self>) -> (Entity<ReadonlyTextField>, Entity<ReadonlyTextFieldWithControls>) {
+        let text_field = cx.new(|_| ReadonlyTextField::default());
+        let content = cx.new({
+            let text_field = text_field.clone();
+            move |cx| ReadonlyTextFieldWithControls::new(text_field, Box::new(|div| div), window, cx)
+        });
+        (text_field, content)
+    }
+
+    fn push_lines(&mut self, lines: Vec<(log::Level, Arc<str>)>, cx: &mut Context<Self>) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let max_level = self.max_level;
+        self.text_field.update(cx, |text_field, cx| {
+            for (level, line) in &lines {
+                if *level <= max_level {
+                    text_field.add(line.clone());
+                }
+            }
+            cx.notify();
+        });
+
+        self.lines.extend(lines);
+        if self.lines.len() > MAX_LINES {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+    }
+
+    /// The text field can't remove lines, so it's replaced with one containing only the lines that pass the filter
+    fn set_max_level(&mut self, max_level: log::LevelFilter, window: &mut Window, cx: &mut Context<Self>) {
+        if self.max_level == max_level {
+            return;
+        }
+        self.max_level = max_level;
+
+        let (text_field, content) = Self::create_text_field(window, cx);
+        text_field.update(cx, |text_field, _| {
+            for (level, line) in &self.lines {
+                if *level <= max_level {
+                    text_field.add(line.clone());
+                }
+            }
+        });
+        self.text_field = text_field;
+        self.content = content;
+        cx.notify();
+    }
+}
+
+#[derive(Default)]
+struct LogTail {
+    position: u64,
+    /// Bytes after the last newline, the rest of the line hasn't been flushed yet
+    partial: Vec<u8>,
+    /// Lines without a prefix, like backtraces, belong to the line before them
+    last_level: Option<log::Level>,
+}
+
+impl LogTail {
+    fn read(&mut self, path: &Path) -> std::io::Result<Vec<(log::Level, Arc<str>)>> {
+        let mut file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+
+        let mut skip_first_line = false;
+        if len < self.position {
+            // The log was replaced, start over
+            self.position = 0;
+            self.partial.clear();
+        } else if self.position == 0 && len > INITIAL_TAIL_BYTES {
+            self.position = len - INITIAL_TAIL_BYTES;
+            skip_first_line = true;
+        }
+
+        if len == self.position {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.position))?;
+        let mut bytes = std::mem::take(&mut self.partial);
+        let read = file.take(len - self.position).read_to_end(&mut bytes)?;
+        self.position += read as u64;
+
+        let complete = match bytes.iter().rposition(|byte| *byte == b'\n') {
+            Some(newline) => {
+                self.partial = bytes.split_off(newline + 1);
+                bytes
+            },
+            None => {
+                self.partial = bytes;
+                return Ok(Vec::new());
+            },
+        };
+
+        let text = String::from_utf8_lossy(&complete);
+        let mut lines = text.lines();
+        if skip_first_line {
+            lines.next();
+        }
+
+        Ok(lines.map(|line| {
+            let level = parse_level(line).or(self.last_level).unwrap_or(log::Level::Info);
+            self.last_level = Some(level);
+            (level, Arc::from(line))
+        }).collect())
+    }
+}
+
+/// Lines are formatted as `[<time> <LEVEL> <target>] <message>`
+fn parse_level(line: &str) -> Option<log::Level> {
+    let prefix = line.strip_prefix('[')?;
+    let level = prefix.split(' ').nth(1)?;
+    log::Level::from_str(level).ok()
+}
+
+impl Render for DebugConsolePage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let levels = ButtonGroup::new("debug-console-level")
+            .outline()
+            .small()
+            .children(LEVELS.iter().map(|level| {
+                Button::new(level.as_str()).label(level.as_str()).selected(self.max_level == *level)
+            }))
+            .on_click(cx.listener(|page, clicked: &Vec<usize>, window, cx| {
+                if let Some(level) = clicked.first().and_then(|index| LEVELS.get(*index)) {
+                    page.set_max_level(*level, window, cx);
+                }
+            }));
+        let open_folder = Button::new("open-folder").small().label(ts!("logs.open_log_folder")).on_click({
+            let folder = self.log_path.parent().map(Path::to_path_buf);
+            move |_, window, cx| {
+                if let Some(folder) = &folder {
+                    crate::open_folder(folder, window, cx);
+                }
+            }
+        });
+        let title = h_flex().gap_8().child(ts!("page.debug_console")).child(levels).child(open_folder);
+
+        let content = v_flex()
+            .size_full()
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().px_3().pt_3().text_color(cx.theme().danger).child(error))
+            })
+            .child(self.content.clone());
+
+        ui::page(cx, title).child(content)
+    }
+}
@@ -0,0 +1,113 @@
+use bridge::{handle::BackendHandle, message::{FileWatchDebugInfo, FileWatchEntry, MessageToBackend}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, IconName, Sizable
+};
+
+use crate::{entity::DataEntities, ts, ui};
+
+/// Hidden page for diagnosing the "launcher might be out-of-sync" warning, lists everything the backend is watching
+pub struct FileWatchesPage {
+    backend_handle: BackendHandle,
+    info: Option<FileWatchDebugInfo>,
+    _get_file_watches_task: Task<()>,
+}
+
+impl FileWatchesPage {
+    pub fn new(data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut page = Self {
+            backend_handle: data.backend_handle.clone(),
+            info: None,
+            _get_file_watches_task: Task::ready(()),
+        };
+
+        page.refresh(cx);
+
+        page
+    }
+
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_file_watches_task = cx.spawn(async move |page, cx| {
+            let result: FileWatchDebugInfo = recv.await.unwrap_or_default();
+            let _ = page.update(cx, move |page, cx| {
+                page.info = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetFileWatches {
+            channel: send,
+        });
+    }
+
+    fn send_and_refresh(&mut self, message: MessageToBackend, cx: &mut Context<Self>) {
+        self.backend_handle.send(message);
+        self.refresh(cx);
+    }
+
+    fn render_entry(index: usize, entry: &FileWatchEntry, cx: &mut Context<Self>) -> Div {
+        let rewatch = {
+            let path = entry.path.clone();
+            Button::new(("rewatch", index)).small().label(ts!("file_watches.rewatch")).on_click(cx.listener(move |page, _, _, cx| {
+                page.send_and_refresh(MessageToBackend::RewatchPath { path: path.clone() }, cx);
+            }))
+        };
+        let unwatch = {
+            let path = entry.path.clone();
+            Button::new(("unwatch", index)).small().danger().label(ts!("file_watches.unwatch")).on_click(cx.listener(move |page, _, _, cx| {
+                page.send_and_refresh(MessageToBackend::UnwatchPath { path: path.clone() }, cx);
+            }))
+        };
+
+        let theme = cx.theme();
+        h_flex()
+            .gap_3()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.border)
+            .child(v_flex()
+                .flex_1()
+                .min_w_0()
+                .child(div().font_family("Roboto Mono").text_sm().child(SharedString::from(entry.path.to_string_lossy().into_owned())))
+                .when_some(entry.real_path.as_ref(), |this, real_path| {
+                    this.child(div().font_family("Roboto Mono").text_xs().text_color(theme.muted_foreground)
+                        .child(format!("→ {}", real_path.to_string_lossy())))
+                })
+                .child(div().text_xs().text_color(theme.muted_foreground).child(SharedString::new(entry.target.clone()))))
+            .child(div().w_20().text_sm().child(SharedString::new(rust_i18n::t!("file_watches.events", count = entry.events))))
+            .child(div().w_20().text_sm().when(entry.errors > 0, |this| this.text_color(theme.danger)).child(SharedString::new(rust_i18n::t!("file_watches.errors", count = entry.errors))))
+            .child(rewatch)
+            .child(unwatch)
+    }
+}
+
+impl Render for FileWatchesPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let refresh = Button::new("refresh").icon(IconName::Redo).label(ts!("common.refresh")).disabled(self.info.is_none()).on_click(cx.listener(|page, _, _, cx| {
+            page.refresh(cx);
+        }));
+        let title = h_flex().gap_8().child(ts!("page.file_watches")).child(refresh);
+
+        let Some(info) = &self.info else {
+            let content = v_flex().size_full().p_3().child(Spinner::new().with_size(gpui_component::Size::Large));
+            return ui::page(cx, title).child(content).overflow_y_scrollbar();
+        };
+
+        let summary = SharedString::new(rust_i18n::t!(
+            "file_watches.summary",
+            paths = info.entries.len(),
+            errors = info.unattributed_errors
+        ));
+
+        let rows: Vec<Div> = info.entries.iter().enumerate().map(|(index, entry)| {
+            Self::render_entry(index, entry, cx)
+        }).collect();
+
+        let content = v_flex().size_full().p_3().gap_1()
+            .child(div().pb_2().text_color(cx.theme().muted_foreground).child(summary))
+            .children(rows);
+
+        ui::page(cx, title).child(content).overflow_y_scrollbar()
+    }
+}
@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use bridge::{instance::{ContentUpdateStatus, InstanceContentID, InstanceID}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, skeleton::Skeleton, v_flex, ActiveTheme as _, Icon, IconName, Sizable, StyledExt
+};
+use rustc_hash::FxHashSet;
+use schema::{content::ContentSource, following::{FollowedProject, FollowedVersion}, modrinth::ModrinthProjectType};
+
+use crate::{entity::DataEntities, pages::modrinth_project_page::summarize_game_versions, ts, ui};
+
+/// New versions of followed Modrinth projects, newest first
+pub struct FollowingPage {
+    data: DataEntities,
+    /// When the feed was last seen before opening the page, so versions stay marked as new while it's open
+    seen_until: i64,
+    _followed_projects_subscription: Subscription,
+}
+
+impl FollowingPage {
+    pub fn new(data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let seen_until = data.followed_projects.read(cx).followed.seen_until;
+        data.backend_handle.send(MessageToBackend::MarkFollowedFeedSeen);
+
+        let _followed_projects_subscription = cx.observe(&data.followed_projects, |_, _, cx| cx.notify());
+
+        Self {
+            data: data.clone(),
+            seen_until,
+            _followed_projects_subscription,
+        }
+    }
+
+    fn render_version(&self, index: usize, project: &FollowedProject, version: &FollowedVersion, newest: bool, cx: &App) -> Div {
+        let theme = cx.theme();
+
+        let icon = if let Some(icon_url) = &project.icon_url && !icon_url.is_empty() {
+            img(SharedUri::from(icon_url)).with_fallback(|| Skeleton::new().rounded_lg().size_10().into_any_element())
+        } else {
+            img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+        };
+
+        let name = version.version_number.clone().or(version.name.clone()).unwrap_or(version.version_id.clone());
+
+        let mut details = Vec::new();
+        if !version.loaders.is_empty() {
+            details.push(version.loaders.iter().map(|loader| loader.pretty_name()).collect::<Vec<_>>().join(", "));
+        }
+        if !version.game_versions.is_empty() {
+            details.push(summarize_game_versions(&version.game_versions));
+        }
+        if let Some(published) = chrono::DateTime::from_timestamp_millis(version.published_at) {
+            details.push(published.format("%Y-%m-%d").to_string());
+        }
+
+        let is_new = project.is_new_since(version, self.seen_until);
+
+        let install = (project.project_type != ModrinthProjectType::Other).then(|| {
+            let data = self.data.clone();
+            let title = project.title.clone();
+            let project_id = project.project_id.clone();
+            let project_type = project.project_type;
+            Button::new(("install", index))
+                .success()
+                .small()
+                .icon(Icon::empty().path("icons/download.svg"))
+                .label(ts!("common.install"))
+                .on_click(move |_, window, cx| {
+                    crate::modals::modrinth_install::open(&title, project_id.clone(), project_type, None, &data, window, cx);
+                })
+        });
+
+        // Installed copies are updated from the newest version of the project only
+        let update = newest.then(|| self.render_update_button(index, &project.project_id, cx)).flatten();
+
+        h_flex()
+            .gap_3()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(theme.border)
+            .bg(theme.background)
+            .child(icon.rounded_lg().size_10().min_w_10().min_h_10())
+            .child(v_flex()
+                .flex_1()
+                .min_w_0()
+                .child(h_flex()
+                    .gap_2()
+                    .child(div().font_bold().child(SharedString::new(project.title.clone())))
+                    .child(SharedString::new(name))
+                    .when(is_new, |this| this.child(div().text_sm().font_bold().text_color(theme.info).child(ts!("following.new")))))
+                .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(details.join(" • ")))))
+            .children(update)
+            .children(install)
+    }
+
+    /// Updates the copies of the project that have a known update, otherwise checks the instances they're in for updates
+    fn render_update_button(&self, index: usize, project_id: &Arc<str>, cx: &App) -> Option<Button> {
+        let mut updatable: Vec<(InstanceID, InstanceContentID)> = Vec::new();
+        let mut installed_in: FxHashSet<InstanceID> = FxHashSet::default();
+
+        for entry in self.data.instances.read(cx).entries.values() {
+            let instance = entry.read(cx);
+            for content in instance.mods.read(cx).iter().chain(instance.resource_packs.read(cx).iter()) {
+                if !matches!(&content.content_source, ContentSource::ModrinthProject { project } if project == project_id) {
+                    continue;
+                }
+                installed_in.insert(instance.id);
+                if content.content_summary.update_status.load(std::sync::atomic::Ordering::Relaxed) == ContentUpdateStatus::Modrinth {
+                    updatable.push((instance.id, content.id));
+                }
+            }
+        }
+
+        if !updatable.is_empty() {
+            let backend_handle = self.data.backend_handle.clone();
+            let label = if updatable.len() == 1 {
+                ts!("following.update")
+            } else {
+                SharedString::new(rust_i18n::t!("following.update_copies", count = updatable.len()))
+            };
+            return Some(Button::new(("update", index))
+                .info()
+                .small()
+                .icon(IconName::ArrowUp)
+                .label(label)
+                .on_click(move |_, window, cx| {
+                    for (instance, content_id) in &updatable {
+                        crate::root::update_single_mod(*instance, *content_id, &backend_handle, window, cx);
+                    }
+                }));
+        }
+
+        if installed_in.is_empty() {
+            return None;
+        }
+
+        let backend_handle = self.data.backend_handle.clone();
+        Some(Button::new(("check-updates", index))
+            .outline()
+            .small()
+            .icon(IconName::Redo)
+            .label(ts!("following.check_for_updates"))
+            .on_click(move |_, window, cx| {
+                for instance in &installed_in {
+                    let modal_action = ModalAction::default();
+                    backend_handle.send(MessageToBackend::UpdateCheck {
+                        instance: *instance,
+                        modal_action: modal_action.clone(),
+                    });
+                    crate::modals::generic::show_notification(window, cx, ts!("following.check_for_updates_failed"), modal_action);
+                }
+            }))
+    }
+
+    fn render_followed_project(&self, index: usize, project: &FollowedProject, cx: &App) -> Div {
+        let project_id = project.project_id.clone();
+        let backend_handle = self.data.backend_handle.clone();
+        h_flex()
+            .gap_2()
+            .pl_3()
+            .py_1()
+            .pr_1()
+            .rounded_lg()
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(SharedString::new(project.title.clone()))
+            .child(Button::new(("unfollow", index))
+                .ghost()
+                .xsmall()
+                .icon(IconName::Close)
+                .tooltip(ts!("following.unfollow"))
+                .on_click(move |_, _, _| {
+                    backend_handle.send(MessageToBackend::UnfollowProject { project_id: project_id.clone() });
+                }))
+    }
+}
+
+impl Render for FollowingPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let followed = self.data.followed_projects.read(cx).followed.clone();
+
+        let check_now = Button::new("check-now")
+            .outline()
+            .small()
+            .icon(IconName::Redo)
+            .label(ts!("following.check_now"))
+            .on_click({
+                let backend_handle = self.data.backend_handle.clone();
+                move |_, _, _| {
+                    backend_handle.send(MessageToBackend::CheckFollowedProjects);
+                }
+            });
+        let title = h_flex().gap_8().child(ts!("sidebar.following")).when(!followed.projects.is_empty(), |this| this.child(check_now));
+
+        if followed.projects.is_empty() {
+            let content = v_flex().size_full().p_3().gap_3()
+                .child(div().text_color(cx.theme().muted_foreground)
+                    .child(ts!("following.empty")));
+            return ui::page(cx, title).child(content);
+        }
+
+        // Each project's versions are newest first, so the first one seen is its newest
+        let mut seen_projects = FxHashSet::default();
+        let feed = followed.feed().into_iter().enumerate().map(|(index, (project, version))| {
+            let newest = seen_projects.insert(project.project_id.clone());
+            self.render_version(index, project, version, newest, cx)
+        }).collect::<Vec<_>>();
+
+        let feed = if feed.is_empty() {
+            v_flex().child(div().text_color(cx.theme().muted_foreground).child(ts!("following.no_versions")))
+        } else {
+            v_flex().gap_2().children(feed)
+        };
+
+        let projects = h_flex().flex_wrap().gap_2().children(followed.projects.iter().enumerate()
+            .map(|(index, project)| self.render_followed_project(index, project, cx)));
+
+        let content = v_flex().p_3().gap_3()
+            .child(div().border_b_1().border_color(cx.theme().border).text_lg().child(ts!("following.recent_updates")))
+            .child(feed)
+            .child(div().border_b_1().border_color(cx.theme().border).text_lg().child(ts!("following.followed_projects")))
+            .child(projects);
+
+        ui::page(cx, title).child(content).overflow_y_scrollbar()
+    }
+}
+
+/// Toggles whether the project shows up in the following page
+pub fn follow_button(
+    id: impl Into<ElementId>,
+    project_id: Arc<str>,
+    title: Arc<str>,
+    project_type: ModrinthProjectType,
+    icon_url: Option<Arc<str>>,
+    data: &DataEntities,
+    cx: &App,
+) -> Button {
+    let following = data.followed_projects.read(cx).followed.is_following(&project_id);
+    let backend_handle = data.backend_handle.clone();
+    Button::new(id)
+        .icon(IconName::Star)
+        .label(if following { ts!("following.unfollow") } else { ts!("following.follow") })
+        .on_click(move |_, _, _| {
+            if following {
+                backend_handle.send(MessageToBackend::UnfollowProject { project_id: project_id.clone() });
+            } else {
+                backend_handle.send(MessageToBackend::FollowProject {
+                    project_id: project_id.clone(),
+                    title: title.clone(),
+                    project_type,
+                    icon_url: icon_url.clone(),
+                });
+            }
+        })
+}
@@ -0,0 +1,313 @@
+use std::{sync::Arc, time::SystemTime};
+
+use bridge::{
+    handle::BackendHandle, instance::InstanceID, message::{ConfigFileContents, ConfigFileEntry, ConfigFileWriteResult, MessageToBackend}
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Icon, IconName, Sizable
+};
+
+use crate::{entity::instance::InstanceEntry, ts};
+
+struct OpenConfigFile {
+    path: Arc<str>,
+    saved_contents: SharedString,
+    modified: Option<SystemTime>,
+}
+
+enum EditorStatus {
+    None,
+    Loading,
+    Saved,
+    Conflict {
+        modified: Option<SystemTime>,
+    },
+    Error(SharedString),
+}
+
+pub struct InstanceConfigSubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    files: Option<Arc<[ConfigFileEntry]>>,
+    open: Option<OpenConfigFile>,
+    status: EditorStatus,
+    editor: Entity<InputState>,
+    _list_task: Task<()>,
+    _file_task: Task<()>,
+}
+
+impl InstanceConfigSubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let instance_id = instance.read(cx).id;
+
+        let editor = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+
+        let mut this = Self {
+            instance: instance_id,
+            backend_handle,
+            files: None,
+            open: None,
+            status: EditorStatus::None,
+            editor,
+            _list_task: Task::ready(()),
+            _file_task: Task::ready(()),
+        };
+
+        this.list_files(window, cx);
+
+        this
+    }
+
+    fn list_files(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._list_task = cx.spawn_in(window, async move |page, cx| {
+            let result: Arc<[ConfigFileEntry]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+            let _ = page.update(cx, move |page, cx| {
+                page.files = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::ListConfigFiles {
+            id: self.instance,
+            channel: send,
+        });
+    }
+
+    fn open_file(&mut self, path: Arc<str>, window: &mut Window, cx: &mut Context<Self>) {
+        self.status = EditorStatus::Loading;
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._file_task = cx.spawn_in(window, async move |page, cx| {
+            let result: Result<ConfigFileContents, Arc<str>> = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = page.update_in(cx, move |page, window, cx| {
+                match result {
+                    Ok(file) => {
+                        let contents = SharedString::new(file.contents);
+                        page.editor.update(cx, |editor, cx| {
+                            editor.set_value(contents.clone(), window, cx);
+                        });
+                        page.open = Some(OpenConfigFile {
+                            path,
+                            saved_contents: contents,
+                            modified: file.modified,
+                        });
+                        page.status = EditorStatus::None;
+                    },
+                    Err(error) => {
+                        page.status = EditorStatus::Error(SharedString::new(error));
+                    },
+                }
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::ReadConfigFile {
+            id: self.instance,
+            path: path.clone(),
+            channel: send,
+        });
+    }
+
+    fn save(&mut self, overwrite: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(open) = &self.open else {
+            return;
+        };
+
+        let contents = self.editor.read(cx).value();
+        let path = open.path.clone();
+
+        // Overwriting accepts the conflicting version on disk, but still fails if it changes yet again
+        let expected_modified = match self.status {
+            EditorStatus::Conflict { modified } if overwrite => modified,
+            _ => open.modified,
+        };
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._file_task = cx.spawn_in(window, {
+            let contents = contents.clone();
+            async move |page, cx| {
+                let result = recv.await.unwrap_or_else(|_| ConfigFileWriteResult::Error(rust_i18n::t!("common.backend_no_response").into()));
+                let _ = page.update(cx, move |page, cx| {
+                    match result {
+                        ConfigFileWriteResult::Saved { modified } => {
+                            if let Some(open) = &mut page.open && open.path == path {
+                                open.saved_contents = contents;
+                                open.modified = modified;
+                            }
+                            page.status = EditorStatus::Saved;
+                        },
+                        ConfigFileWriteResult::Conflict { modified } => {
+                            page.status = EditorStatus::Conflict { modified };
+                        },
+                        ConfigFileWriteResult::Error(error) => {
+                            page.status = EditorStatus::Error(SharedString::new(error));
+                        },
+                    }
+                    cx.notify();
+                });
+            }
+        });
+
+        self.backend_handle.send(MessageToBackend::WriteConfigFile {
+            id: self.instance,
+            path: open.path.clone(),
+            contents: contents.as_str().into(),
+            expected_modified,
+            channel: send,
+        });
+    }
+
+    fn render_file_list(&self, cx: &mut Context<Self>) -> AnyElement {
+        let Some(files) = &self.files else {
+            return h_flex().justify_center().size_full().gap_3().child(ts!("common.loading")).child(Spinner::new()).into_any_element();
+        };
+
+        if files.is_empty() {
+            return h_flex().justify_center().size_full().child(ts!("config.no_files")).into_any_element();
+        }
+
+        let theme = cx.theme();
+        let open_path = self.open.as_ref().map(|open| open.path.clone());
+
+        let mut rows = Vec::with_capacity(files.len());
+        let mut last_directories: Vec<&str> = Vec::new();
+
+        for (index, file) in files.iter().enumerate() {
+            let mut components: Vec<&str> = file.path.split('/').collect();
+            let file_name = components.pop().unwrap_or_default();
+
+            // Emit a header for every directory that differs from the previous file's directories
+            let common = last_directories.iter().zip(components.iter()).take_while(|(a, b)| a == b).count();
+            for (depth, directory) in components.iter().enumerate().skip(common) {
+                rows.push(h_flex()
+                    .id(SharedString::new(format!("dir-{}-{}", index, depth)))
+                    .pl(px(8.0 + depth as f32 * 12.0))
+                    .py_0p5()
+                    .gap_1()
+                    .text_color(theme.muted_foreground)
+                    .child(Icon::new(IconName::Folder).small())
+                    .child(SharedString::new(*directory))
+                    .into_any_element());
+            }
+            last_directories = components.clone();
+
+            let path = file.path.clone();
+            let selected = open_path.as_ref() == Some(&path);
+            rows.push(h_flex()
+                .id(index)
+                .pl(px(8.0 + components.len() as f32 * 12.0))
+                .py_0p5()
+                .gap_1()
+                .cursor_pointer()
+                .when(selected, |this| this.bg(theme.secondary))
+                .hover(|this| this.bg(theme.secondary.alpha(0.5)))
+                .child(Icon::new(IconName::File).small())
+                .child(SharedString::new(file_name))
+                .on_click(cx.listener(move |page, _, window, cx| {
+                    page.open_file(path.clone(), window, cx);
+                    cx.notify();
+                }))
+                .into_any_element());
+        }
+
+        v_flex().size_full().children(rows).overflow_y_scrollbar().into_any_element()
+    }
+}
+
+impl Render for InstanceConfigSubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let file_list = self.render_file_list(cx);
+
+        let theme = cx.theme();
+
+        let dirty = self.open.as_ref().is_some_and(|open| self.editor.read(cx).value() != open.saved_contents);
+
+        let file_pane = v_flex()
+            .w_72()
+            .h_full()
+            .border_1()
+            .rounded(theme.radius)
+            .border_color(theme.border)
+            .child(h_flex()
+                .p_2()
+                .justify_between()
+                .border_b_1()
+                .border_color(theme.border)
+                .child("config/")
+                .child(Button::new("refresh").icon(IconName::Redo).small().tooltip(ts!("common.refresh")).on_click(cx.listener(|page, _, window, cx| {
+                    page.list_files(window, cx);
+                    cx.notify();
+                }))))
+            .child(file_list);
+
+        let status = match &self.status {
+            EditorStatus::None => None,
+            EditorStatus::Loading => Some(div().child(ts!("common.loading"))),
+            EditorStatus::Saved if !dirty => Some(div().text_color(theme.success).child(ts!("common.saved"))),
+            EditorStatus::Saved => None,
+            EditorStatus::Conflict { .. } => Some(div().text_color(theme.danger).child(ts!("config.conflict"))),
+            EditorStatus::Error(error) => Some(div().text_color(theme.danger).child(error.clone())),
+        };
+
+        let mut header = h_flex()
+            .gap_3()
+            .child(div().text_lg().child(match &self.open {
+                Some(open) if dirty => SharedString::new(format!("{} *", open.path)),
+                Some(open) => SharedString::new(open.path.clone()),
+                None => ts!("config.no_file_selected"),
+            }))
+            .children(status);
+
+        if let Some(open) = &self.open {
+            let path = open.path.clone();
+            header = header
+                .child(Button::new("save").success().label(ts!("common.save")).disabled(!dirty).on_click(cx.listener(|page, _, window, cx| {
+                    page.save(false, window, cx);
+                    cx.notify();
+                })))
+                .child(Button::new("reload").label(ts!("common.reload_from_disk")).on_click(cx.listener(move |page, _, window, cx| {
+                    page.open_file(path.clone(), window, cx);
+                    cx.notify();
+                })));
+
+            if let EditorStatus::Conflict { .. } = self.status {
+                header = header.child(Button::new("overwrite").danger().label(ts!("config.overwrite")).on_click(cx.listener(|page, _, window, cx| {
+                    page.save(true, window, cx);
+                    cx.notify();
+                })));
+            }
+        }
+
+        let editor = if self.open.is_some() {
+            div()
+                .size_full()
+                .font_family("Roboto Mono")
+                .child(Input::new(&self.editor).h_full())
+                .into_any_element()
+        } else {
+            h_flex()
+                .justify_center()
+                .size_full()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .text_color(theme.muted_foreground)
+                .child(ts!("config.select_file"))
+                .into_any_element()
+        };
+
+        h_flex()
+            .p_4()
+            .gap_4()
+            .size_full()
+            .child(file_pane)
+            .child(v_flex().size_full().gap_2().child(header).child(editor))
+    }
+}
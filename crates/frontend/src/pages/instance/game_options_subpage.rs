@@ -0,0 +1,254 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use bridge::{
+    handle::BackendHandle, instance::InstanceID, message::{GameOptions, MessageToBackend}
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent, StepAction}, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Sizable
+};
+
+use crate::{entity::instance::InstanceEntry, ts};
+
+const RENDER_DISTANCE_RANGE: RangeInclusive<u32> = 2..=32;
+const GUI_SCALE_RANGE: RangeInclusive<u32> = 0..=8;
+
+enum GameOptionsStatus {
+    Loading,
+    Loaded,
+    Saved,
+    Error(SharedString),
+}
+
+pub struct InstanceGameOptionsSubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    /// The values currently in options.txt, used to only write back the keys that were changed
+    loaded: Option<GameOptions>,
+    status: GameOptionsStatus,
+    render_distance_input_state: Entity<InputState>,
+    gui_scale_input_state: Entity<InputState>,
+    language_input_state: Entity<InputState>,
+    fullscreen: Option<bool>,
+    _task: Task<()>,
+}
+
+impl InstanceGameOptionsSubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let instance_id = instance.read(cx).id;
+
+        let render_distance_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("12"));
+        cx.subscribe_in(&render_distance_input_state, window, |_, state, event, window, cx| {
+            Self::on_step(state, event, RENDER_DISTANCE_RANGE, window, cx);
+        }).detach();
+        cx.subscribe(&render_distance_input_state, Self::on_input_changed).detach();
+
+        let gui_scale_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("0"));
+        cx.subscribe_in(&gui_scale_input_state, window, |_, state, event, window, cx| {
+            Self::on_step(state, event, GUI_SCALE_RANGE, window, cx);
+        }).detach();
+        cx.subscribe(&gui_scale_input_state, Self::on_input_changed).detach();
+
+        let language_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("en_us"));
+        cx.subscribe(&language_input_state, Self::on_input_changed).detach();
+
+        let mut page = Self {
+            instance: instance_id,
+            backend_handle,
+            loaded: None,
+            status: GameOptionsStatus::Loading,
+            render_distance_input_state,
+            gui_scale_input_state,
+            language_input_state,
+            fullscreen: None,
+            _task: Task::ready(()),
+        };
+
+        page.load(window, cx);
+
+        page
+    }
+
+    fn on_step(state: &Entity<InputState>, event: &NumberInputEvent, range: RangeInclusive<u32>, window: &mut Window, cx: &mut App) {
+        let NumberInputEvent::Step(step_action) = event;
+        let value = state.read(cx).value().parse::<u32>().unwrap_or(*range.start());
+        let value = match step_action {
+            StepAction::Decrement => value.saturating_sub(1),
+            StepAction::Increment => value.saturating_add(1),
+        };
+        let value = value.clamp(*range.start(), *range.end());
+        state.update(cx, |input, cx| {
+            input.set_value(value.to_string(), window, cx);
+        });
+    }
+
+    fn on_input_changed(&mut self, _: Entity<InputState>, event: &InputEvent, cx: &mut Context<Self>) {
+        if let InputEvent::Change = event {
+            cx.notify();
+        }
+    }
+
+    fn load(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.status = GameOptionsStatus::Loading;
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = page.update_in(cx, move |page, window, cx| {
+                page.apply_result(result, GameOptionsStatus::Loaded, window, cx);
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetGameOptions {
+            id: self.instance,
+            channel: send,
+        });
+    }
+
+    fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let changes = self.changes(cx);
+        if changes == GameOptions::default() {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = page.update_in(cx, move |page, window, cx| {
+                page.apply_result(result, GameOptionsStatus::Saved, window, cx);
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::SetGameOptions {
+            id: self.instance,
+            changes,
+            channel: send,
+        });
+    }
+
+    fn apply_result(&mut self, result: Result<GameOptions, Arc<str>>, status: GameOptionsStatus, window: &mut Window, cx: &mut Context<Self>) {
+        match result {
+            Ok(options) => {
+                let render_distance = options.render_distance.map(|value| value.to_string()).unwrap_or_default();
+                self.render_distance_input_state.update(cx, |input, cx| {
+                    input.set_value(render_distance, window, cx);
+                });
+                let gui_scale = options.gui_scale.map(|value| value.to_string()).unwrap_or_default();
+                self.gui_scale_input_state.update(cx, |input, cx| {
+                    input.set_value(gui_scale, window, cx);
+                });
+                let language = options.language.as_deref().unwrap_or_default().to_string();
+                self.language_input_state.update(cx, |input, cx| {
+                    input.set_value(language, window, cx);
+                });
+                self.fullscreen = options.fullscreen;
+                self.loaded = Some(options);
+                self.status = status;
+            },
+            Err(error) => {
+                self.status = GameOptionsStatus::Error(SharedString::new(error));
+            },
+        }
+        cx.notify();
+    }
+
+    /// Only values that differ from what was loaded are included
+    fn changes(&self, cx: &App) -> GameOptions {
+        let Some(loaded) = &self.loaded else {
+            return GameOptions::default();
+        };
+
+        let render_distance = self.render_distance_input_state.read(cx).value().trim().parse::<u32>().ok()
+            .map(|value| value.clamp(*RENDER_DISTANCE_RANGE.start(), *RENDER_DISTANCE_RANGE.end()));
+        let gui_scale = self.gui_scale_input_state.read(cx).value().trim().parse::<u32>().ok()
+            .map(|value| value.clamp(*GUI_SCALE_RANGE.start(), *GUI_SCALE_RANGE.end()));
+        let language = self.language_input_state.read(cx).value();
+        let language = language.trim();
+
+        GameOptions {
+            render_distance: render_distance.filter(|value| loaded.render_distance != Some(*value)),
+            fullscreen: self.fullscreen.filter(|value| loaded.fullscreen != Some(*value)),
+            gui_scale: gui_scale.filter(|value| loaded.gui_scale != Some(*value)),
+            language: (!language.is_empty() && loaded.language.as_deref() != Some(language)).then(|| language.into()),
+        }
+    }
+}
+
+impl Render for InstanceGameOptionsSubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+
+        let has_changes = self.changes(cx) != GameOptions::default();
+
+        let status = match &self.status {
+            GameOptionsStatus::Loading => Some(Spinner::new().into_any_element()),
+            GameOptionsStatus::Loaded => None,
+            GameOptionsStatus::Saved if has_changes => None,
+            GameOptionsStatus::Saved => Some(div().text_color(theme.success).child(ts!("common.saved")).into_any_element()),
+            GameOptionsStatus::Error(error) => Some(div().text_color(theme.danger).child(error.clone()).into_any_element()),
+        };
+
+        let header = h_flex()
+            .gap_3()
+            .mb_1()
+            .ml_1()
+            .child(div().text_lg().child(ts!("instance.game_options")))
+            .children(status);
+
+        let loaded = self.loaded.is_some();
+
+        let form = v_flex()
+            .gap_4()
+            .w_96()
+            .child(crate::labelled(
+                ts!("game_options.render_distance"),
+                NumberInput::new(&self.render_distance_input_state).small().suffix(ts!("game_options.chunks")).disabled(!loaded),
+            ))
+            .child(crate::labelled(
+                ts!("game_options.gui_scale"),
+                v_flex()
+                    .gap_0p5()
+                    .child(NumberInput::new(&self.gui_scale_input_state).small().disabled(!loaded))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(ts!("game_options.gui_scale_auto"))),
+            ))
+            .child(crate::labelled(
+                ts!("game_options.language"),
+                Input::new(&self.language_input_state).small().disabled(!loaded),
+            ))
+            .child(Checkbox::new("fullscreen").label(ts!("game_options.fullscreen")).checked(self.fullscreen.unwrap_or(false)).disabled(!loaded)
+                .on_click(cx.listener(|page, value, _, cx| {
+                    page.fullscreen = Some(*value);
+                    cx.notify();
+                })))
+            .child(h_flex()
+                .gap_2()
+                .child(Button::new("save").success().label(ts!("common.save")).disabled(!has_changes).on_click(cx.listener(|page, _, window, cx| {
+                    page.save(window, cx);
+                    cx.notify();
+                })))
+                .child(Button::new("reload").label(ts!("common.reload_from_disk")).on_click(cx.listener(|page, _, window, cx| {
+                    page.load(window, cx);
+                    cx.notify();
+                }))))
+            .child(div().text_sm().text_color(theme.muted_foreground)
+                .child(ts!("game_options.overwritten_on_close")));
+
+        v_flex()
+            .p_4()
+            .size_full()
+            .child(header)
+            .child(div()
+                .size_full()
+                .p_4()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .child(form)
+            )
+    }
+}
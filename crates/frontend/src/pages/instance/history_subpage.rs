@@ -0,0 +1,375 @@
+use std::sync::Arc;
+
+use bridge::{
+    handle::BackendHandle, instance::{InstanceID, InstanceStatus}, message::MessageToBackend
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonGroup, ButtonVariants}, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Selectable, Sizable
+};
+use schema::launch_history::{self, LaunchHistoryEntry, LaunchHistoryQuickPlay, LaunchOutcome};
+
+use crate::{entity::instance::InstanceEntry, ts};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HistoryFilter {
+    All,
+    Exited,
+    Crashed,
+    Killed,
+    FailedToLaunch,
+}
+
+impl HistoryFilter {
+    fn matches(self, outcome: LaunchOutcome) -> bool {
+        match self {
+            HistoryFilter::All => true,
+            HistoryFilter::Exited => outcome == LaunchOutcome::Exited,
+            HistoryFilter::Crashed => outcome == LaunchOutcome::Crashed,
+            HistoryFilter::Killed => outcome == LaunchOutcome::Killed,
+            HistoryFilter::FailedToLaunch => outcome == LaunchOutcome::FailedToLaunch,
+        }
+    }
+}
+
+pub struct InstanceHistorySubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    entries: Option<Arc<[LaunchHistoryEntry]>>,
+    filter: HistoryFilter,
+    selected: Vec<usize>,
+    last_status: InstanceStatus,
+    _get_history_task: Task<()>,
+    _instance_subscription: Subscription,
+}
+
+impl InstanceHistorySubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let instance_entry = instance.read(cx);
+        let instance_id = instance_entry.id;
+        let last_status = instance_entry.status;
+
+        let _instance_subscription = cx.observe_in(instance, window, |page, instance, window, cx| {
+            let status = instance.read(cx).status;
+            if status != page.last_status {
+                page.last_status = status;
+                page.get_history(window, cx);
+            }
+        });
+
+        let mut this = Self {
+            instance: instance_id,
+            backend_handle,
+            entries: None,
+            filter: HistoryFilter::All,
+            selected: Vec::new(),
+            last_status,
+            _get_history_task: Task::ready(()),
+            _instance_subscription,
+        };
+
+        this.get_history(window, cx);
+
+        this
+    }
+
+    pub fn get_history(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_history_task = cx.spawn_in(window, async move |page, cx| {
+            let result: Arc<[LaunchHistoryEntry]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+            let _ = page.update(cx, move |page, cx| {
+                page.entries = Some(result);
+                page.selected.clear();
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetLaunchHistory {
+            id: self.instance,
+            channel: send,
+        });
+    }
+}
+
+impl Render for InstanceHistorySubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let filter_group = ButtonGroup::new("history_filter")
+            .outline()
+            .small()
+            .child(Button::new("all").label(ts!("history.all")).selected(self.filter == HistoryFilter::All))
+            .child(Button::new("exited").label(ts!("history.exited")).selected(self.filter == HistoryFilter::Exited))
+            .child(Button::new("crashed").label(ts!("history.crashed")).selected(self.filter == HistoryFilter::Crashed))
+            .child(Button::new("killed").label(ts!("history.killed")).selected(self.filter == HistoryFilter::Killed))
+            .child(Button::new("failed").label(ts!("history.failed_to_launch")).selected(self.filter == HistoryFilter::FailedToLaunch))
+            .on_click(cx.listener(|page, clicked: &Vec<usize>, _, cx| {
+                page.filter = match clicked[0] {
+                    0 => HistoryFilter::All,
+                    1 => HistoryFilter::Exited,
+                    2 => HistoryFilter::Crashed,
+                    3 => HistoryFilter::Killed,
+                    4 => HistoryFilter::FailedToLaunch,
+                    _ => return,
+                };
+                cx.notify();
+            }));
+
+        let theme = cx.theme();
+
+        let mut header = h_flex()
+            .gap_3()
+            .mb_1()
+            .ml_1()
+            .child(div().text_lg().child(ts!("history.title")))
+            .child(filter_group);
+
+        let mut content = v_flex()
+            .size_full()
+            .border_1()
+            .rounded(theme.radius)
+            .border_color(theme.border);
+
+        let Some(entries) = &self.entries else {
+            content = content.child(h_flex().justify_center().size_full().text_lg().gap_3().child(ts!("history.loading")).child(Spinner::new()));
+            return v_flex().p_4().size_full().child(header).child(content);
+        };
+
+        if let Some(crash_rate) = crash_rate(entries) {
+            header = header.child(div().text_color(theme.muted_foreground).child(SharedString::new(rust_i18n::t!("history.crash_rate", percent = format!("{:.0}", crash_rate * 100.0)))));
+        }
+
+        let filtered = entries.iter().enumerate().filter(|(_, entry)| self.filter.matches(entry.outcome)).collect::<Vec<_>>();
+
+        if filtered.is_empty() {
+            content = content.child(h_flex().justify_center().size_full().text_lg().child(ts!("history.empty")));
+        } else {
+            let rows = filtered.into_iter().map(|(index, entry)| {
+                let started = chrono::DateTime::from_timestamp_millis(entry.started_at)
+                    .map(|date_time| date_time.with_timezone(&chrono::Local).format("%d/%m/%Y %H:%M").to_string())
+                    .unwrap_or_default();
+
+                let mut version = format!("{} {}", entry.loader.name(), entry.minecraft_version);
+                if let Some(loader_version) = entry.loader_version {
+                    version.push_str(&format!(" ({})", loader_version));
+                }
+
+                let mut details = Vec::new();
+                if let Some(account) = &entry.account {
+                    if entry.offline {
+                        details.push(rust_i18n::t!("history.offline_account", account = account).into_owned());
+                    } else {
+                        details.push(account.to_string());
+                    }
+                }
+                if let Some(duration) = entry.duration_secs {
+                    details.push(format_duration(duration));
+                }
+                if let Some(exit_code) = entry.exit_code {
+                    details.push(rust_i18n::t!("history.exit_code", code = exit_code).into_owned());
+                }
+                match &entry.quick_play {
+                    Some(LaunchHistoryQuickPlay::Singleplayer(world)) => details.push(rust_i18n::t!("history.world", name = world).into_owned()),
+                    Some(LaunchHistoryQuickPlay::Multiplayer(server)) => details.push(rust_i18n::t!("history.server", name = server).into_owned()),
+                    Some(LaunchHistoryQuickPlay::Realms(realm)) => details.push(rust_i18n::t!("history.realm", name = realm).into_owned()),
+                    None => {},
+                }
+
+                let outcome_color = match entry.outcome {
+                    LaunchOutcome::Running => theme.info,
+                    LaunchOutcome::Exited => theme.success,
+                    LaunchOutcome::Crashed | LaunchOutcome::FailedToLaunch => theme.danger,
+                    LaunchOutcome::Killed => theme.warning,
+                    LaunchOutcome::Unknown => theme.muted_foreground,
+                };
+
+                let mut row = v_flex()
+                    .id(index)
+                    .px_3()
+                    .py_2()
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .when(self.selected.contains(&index), |this| this.bg(theme.secondary))
+                    .cursor_pointer()
+                    .on_click(cx.listener(move |page, _, _, cx| {
+                        page.toggle_selected(index);
+                        cx.notify();
+                    }))
+                    .child(h_flex()
+                        .gap_3()
+                        .child(div().text_color(outcome_color).child(outcome_name(entry.outcome)))
+                        .child(started)
+                        .child(div().text_color(theme.muted_foreground).child(version)))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(details.join(" • ")));
+
+                if let Some(error) = &entry.error {
+                    row = row.child(div().text_sm().text_color(theme.danger).child(SharedString::from(error.clone())));
+                }
+
+                row
+            });
+
+            content = content.child(v_flex().size_full().children(rows).overflow_y_scrollbar());
+        }
+
+        let mut page = v_flex().p_4().gap_4().size_full().child(v_flex().size_full().child(header).child(content));
+
+        if let Some((older, newer)) = self.compared_entries(entries) {
+            page = page.child(render_mods_diff(older, newer, cx));
+        } else if !self.selected.is_empty() {
+            page = page.child(div().text_color(theme.muted_foreground).child(ts!("history.no_snapshot")));
+        }
+
+        page
+    }
+}
+
+impl InstanceHistorySubpage {
+    fn toggle_selected(&mut self, index: usize) {
+        if let Some(position) = self.selected.iter().position(|selected| *selected == index) {
+            self.selected.remove(position);
+        } else {
+            if self.selected.len() >= 2 {
+                self.selected.remove(0);
+            }
+            self.selected.push(index);
+        }
+    }
+
+    /// Returns the (older, newer) launches to compare. With a single selection, the
+    /// launch is compared against the closest earlier launch that has a mod snapshot
+    fn compared_entries<'a>(&self, entries: &'a [LaunchHistoryEntry]) -> Option<(&'a LaunchHistoryEntry, &'a LaunchHistoryEntry)> {
+        match self.selected.as_slice() {
+            [single] => {
+                let newer = entries.get(*single)?;
+                newer.mods.as_ref()?;
+                let older = entries[single+1..].iter().find(|entry| entry.mods.is_some())?;
+                Some((older, newer))
+            },
+            [first, second] => {
+                // Entries are sorted newest first, so the higher index is the older launch
+                let older = entries.get(*first.max(second))?;
+                let newer = entries.get(*first.min(second))?;
+                older.mods.as_ref()?;
+                newer.mods.as_ref()?;
+                Some((older, newer))
+            },
+            _ => None,
+        }
+    }
+}
+
+fn render_mods_diff(older: &LaunchHistoryEntry, newer: &LaunchHistoryEntry, cx: &App) -> impl IntoElement {
+    let theme = cx.theme();
+
+    let format_time = |entry: &LaunchHistoryEntry| {
+        chrono::DateTime::from_timestamp_millis(entry.started_at)
+            .map(|date_time| date_time.with_timezone(&chrono::Local).format("%d/%m/%Y %H:%M").to_string())
+            .unwrap_or_default()
+    };
+
+    let title = SharedString::new(rust_i18n::t!("history.what_changed", older = format_time(older), newer = format_time(newer)));
+
+    let diff = launch_history::diff_mods(
+        older.mods.as_deref().unwrap_or_default(),
+        newer.mods.as_deref().unwrap_or_default()
+    );
+
+    let mut lines = v_flex().gap_0p5();
+
+    if diff.is_empty() {
+        lines = lines.child(div().text_color(theme.muted_foreground).child(ts!("history.no_mod_changes")));
+    } else {
+        for added in &diff.added {
+            lines = lines.child(div().text_color(theme.success)
+                .child(format!("+ {} {}", added.display_name(), added.version)));
+        }
+        for removed in &diff.removed {
+            lines = lines.child(div().text_color(theme.danger)
+                .child(format!("- {} {}", removed.display_name(), removed.version)));
+        }
+        for (old, new) in &diff.updated {
+            lines = lines.child(div().text_color(theme.warning)
+                .child(format!("~ {} {} → {}", new.display_name(), old.version, new.version)));
+        }
+    }
+
+    v_flex()
+        .max_h_64()
+        .p_3()
+        .gap_2()
+        .border_1()
+        .rounded(theme.radius)
+        .border_color(theme.border)
+        .child(div().text_lg().child(title))
+        .child(lines)
+        .overflow_y_scrollbar()
+}
+
+fn outcome_name(outcome: LaunchOutcome) -> SharedString {
+    match outcome {
+        LaunchOutcome::Running => ts!("history.running"),
+        LaunchOutcome::Exited => ts!("history.exited"),
+        LaunchOutcome::Crashed => ts!("history.crashed"),
+        LaunchOutcome::Killed => ts!("history.killed"),
+        LaunchOutcome::FailedToLaunch => ts!("history.failed_to_launch"),
+        LaunchOutcome::Unknown => ts!("history.unknown"),
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 60*60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {}m", seconds / 60 / 60, (seconds / 60) % 60)
+    }
+}
+
+/// Fraction of the launches in the history that ended in a crash, launches that haven't finished or whose
+/// outcome is unknown aren't counted. None if no launch has finished yet
+fn crash_rate(entries: &[LaunchHistoryEntry]) -> Option<f32> {
+    let finished = entries.iter().filter(|entry| !matches!(entry.outcome, LaunchOutcome::Running | LaunchOutcome::Unknown)).count();
+    if finished == 0 {
+        return None;
+    }
+    let crashed = entries.iter().filter(|entry| entry.outcome == LaunchOutcome::Crashed).count();
+    Some(crashed as f32 / finished as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::loader::Loader;
+    use ustr::Ustr;
+
+    use super::*;
+
+    fn entry(outcome: LaunchOutcome) -> LaunchHistoryEntry {
+        LaunchHistoryEntry {
+            started_at: 0,
+            account: None,
+            offline: false,
+            minecraft_version: Ustr::from("1.21.1"),
+            loader: Loader::Vanilla,
+            loader_version: None,
+            quick_play: None,
+            outcome,
+            duration_secs: None,
+            exit_code: None,
+            error: None,
+            mods: None,
+            suspected_mods: None,
+        }
+    }
+
+    #[test]
+    fn unknown_outcomes_are_not_counted_as_finished() {
+        let entries = [entry(LaunchOutcome::Unknown), entry(LaunchOutcome::Crashed), entry(LaunchOutcome::Exited)];
+        assert_eq!(crash_rate(&entries), Some(0.5));
+        assert_eq!(crash_rate(&entries[..1]), None);
+    }
+}
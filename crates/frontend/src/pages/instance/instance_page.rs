@@ -10,7 +10,7 @@ use gpui_component::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    component::page_path::PagePath, entity::{DataEntities, instance::InstanceEntry}, pages::instance::{logs_subpage::InstanceLogsSubpage, mods_subpage::InstanceModsSubpage, quickplay_subpage::InstanceQuickplaySubpage, resource_packs_subpage::InstanceResourcePacksSubpage, settings_subpage::InstanceSettingsSubpage}, root, ui
+    component::{page_path::PagePath, resource_usage_graph::ResourceUsageGraph}, entity::{DataEntities, instance::InstanceEntry}, interface_config::InterfaceConfig, pages::instance::{config_subpage::InstanceConfigSubpage, game_options_subpage::InstanceGameOptionsSubpage, history_subpage::InstanceHistorySubpage, logs_subpage::InstanceLogsSubpage, mods_subpage::InstanceModsSubpage, quickplay_subpage::InstanceQuickplaySubpage, resource_packs_subpage::InstanceResourcePacksSubpage, settings_subpage::InstanceSettingsSubpage}, root, ts, ui, window_manager
 };
 
 pub struct InstancePage {
@@ -37,10 +37,29 @@ impl InstancePage {
     }
 
     fn set_subpage(&mut self, page_type: InstanceSubpageType, window: &mut Window, cx: &mut Context<Self>) {
-        if page_type == self.subpage.page_type() {
+        let previous = self.subpage.page_type();
+        if page_type == previous {
             return;
         }
         self.subpage = page_type.create(&self.instance, &self.data, self.backend_handle.clone(), window, cx);
+        root::instance_subpage_switched(cx.entity_id(), previous, cx);
+
+        let instance = self.instance.read(cx);
+        self.page_path.set_current(ui::PageType::InstancePage(instance.id, page_type));
+        let name = instance.name.clone();
+        InterfaceConfig::get_mut(cx).instance_subpages.insert(name, page_type);
+    }
+
+    pub fn subpage_type(&self) -> InstanceSubpageType {
+        self.subpage.page_type()
+    }
+
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        match &self.subpage {
+            InstanceSubpage::Mods(subpage) => subpage.update(cx, |subpage, cx| subpage.focus_search(window, cx)),
+            InstanceSubpage::ResourcePacks(subpage) => subpage.update(cx, |subpage, cx| subpage.focus_search(window, cx)),
+            _ => {},
+        }
     }
 }
 
@@ -51,7 +70,10 @@ impl Render for InstancePage {
             InstanceSubpage::Logs(_) => 1,
             InstanceSubpage::Mods(_) => 2,
             InstanceSubpage::ResourcePacks(_) => 3,
-            InstanceSubpage::Settings(_) => 4,
+            InstanceSubpage::Config(_) => 4,
+            InstanceSubpage::GameOptions(_) => 5,
+            InstanceSubpage::History(_) => 6,
+            InstanceSubpage::Settings(_) => 7,
         };
 
         let play_icon = Icon::empty().path("icons/play.svg");
@@ -61,30 +83,44 @@ impl Render for InstancePage {
         let name = instance.name.clone();
         let backend_handle = self.backend_handle.clone();
 
+        let force_kill_button = matches!(instance.status, InstanceStatus::Running | InstanceStatus::Stopping).then(|| {
+            let backend_handle = backend_handle.clone();
+            Button::new("force_kill_instance")
+                .danger()
+                .outline()
+                .label(ts!("instance.force_kill"))
+                .on_click(move |_, _, _| {
+                    backend_handle.send(MessageToBackend::KillInstance { id });
+                })
+        });
+
         let button = match instance.status {
             InstanceStatus::NotRunning => {
-                Button::new("start_instance").success().icon(play_icon).label("Start Instance").on_click(
+                Button::new("start_instance").success().icon(play_icon).label(ts!("instance.start")).on_click(
                     move |_, window, cx| {
                         root::start_instance(id, name.clone(), None, &backend_handle, window, cx);
                     },
                 )
             },
             InstanceStatus::Launching => {
-                Button::new("launching").warning().icon(IconName::Loader).label("Launching...")
+                Button::new("launching").warning().icon(IconName::Loader).label(ts!("instance.launching"))
             },
-            InstanceStatus::Running => Button::new("kill_instance")
+            InstanceStatus::Running => Button::new("stop_instance")
                 .danger()
                 .icon(IconName::Close)
-                .label("Kill Instance")
+                .label(ts!("instance.stop"))
                 .on_click(move |_, _, _| {
-                    backend_handle.send(MessageToBackend::KillInstance { id });
+                    backend_handle.send(MessageToBackend::StopInstance { id });
                 }),
+            InstanceStatus::Stopping => {
+                Button::new("stopping").warning().icon(IconName::Loader).label(ts!("instance.stopping"))
+            },
         };
 
         let open_dot_minecraft_button = Button::new("open_dot_minecraft")
             .info()
             .icon(IconName::FolderOpen)
-            .label("Open .minecraft folder")
+            .label(ts!("instance.open_dot_minecraft"))
             .on_click({
             let dot_minecraft = instance.dot_minecraft_folder.clone();
             move |_, window, cx| {
@@ -92,25 +128,55 @@ impl Render for InstancePage {
             }
         });
 
+        let pop_out_button = Button::new("pop_out")
+            .icon(IconName::ExternalLink)
+            .tooltip(ts!("instance.pop_out"))
+            .on_click(cx.listener(|page, _, _, cx| {
+                let id = page.instance.read(cx).id;
+                window_manager::pop_out_instance(id, page.subpage.page_type(), &page.data, cx);
+            }));
+
+        // Without watching, changes made outside the launcher only show up after the next poll
+        let refresh_button = (!instance.file_watching).then(|| {
+            Button::new("refresh_instance")
+                .icon(IconName::Redo)
+                .tooltip(ts!("instance.refresh_unwatched"))
+                .on_click(cx.listener(|page, _, _, cx| {
+                    let id = page.instance.read(cx).id;
+                    page.backend_handle.send(MessageToBackend::RefreshInstance { id });
+                }))
+        });
+
+        let resource_usage = matches!(instance.status, InstanceStatus::Running | InstanceStatus::Stopping).then(|| {
+            let max_memory = instance.configuration.effective_memory().map(|(_, max)| max);
+            ResourceUsageGraph::new(instance.resource_usage.read(cx).clone(), max_memory)
+        });
+
         let breadcrumb = self.page_path.create_breadcrumb(&self.data, cx);
-        ui::page(cx, h_flex().gap_8().child(breadcrumb).child(h_flex().gap_3().child(button).child(open_dot_minecraft_button)))
+        ui::page(cx, h_flex().gap_8().child(breadcrumb).child(h_flex().gap_3().child(button).children(force_kill_button).child(open_dot_minecraft_button).children(refresh_button).child(pop_out_button)).children(resource_usage))
             .child(
                 TabBar::new("bar")
                     .prefix(div().w_4())
                     .selected_index(selected_index)
                     .underline()
-                    .child(Tab::new().label("Quickplay"))
-                    .child(Tab::new().label("Logs"))
-                    .child(Tab::new().label("Mods"))
-                    .child(Tab::new().label("Resource Packs"))
-                    .child(Tab::new().label("Settings"))
+                    .child(Tab::new().label(ts!("instance.quickplay")))
+                    .child(Tab::new().label(ts!("instance.logs")))
+                    .child(Tab::new().label(ts!("instance.mods")))
+                    .child(Tab::new().label(ts!("instance.resource_packs")))
+                    .child(Tab::new().label(ts!("instance.config")))
+                    .child(Tab::new().label(ts!("instance.game_options")))
+                    .child(Tab::new().label(ts!("instance.history")))
+                    .child(Tab::new().label(ts!("instance.settings")))
                     .on_click(cx.listener(|page, index, window, cx| {
                         let page_type = match *index {
                             0 => InstanceSubpageType::Quickplay,
                             1 => InstanceSubpageType::Logs,
                             2 => InstanceSubpageType::Mods,
                             3 => InstanceSubpageType::ResourcePacks,
-                            4 => InstanceSubpageType::Settings,
+                            4 => InstanceSubpageType::Config,
+                            5 => InstanceSubpageType::GameOptions,
+                            6 => InstanceSubpageType::History,
+                            7 => InstanceSubpageType::Settings,
                             _ => {
                                 return;
                             },
@@ -122,17 +188,33 @@ impl Render for InstancePage {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum InstanceSubpageType {
     Quickplay,
     Logs,
     Mods,
     ResourcePacks,
+    Config,
+    GameOptions,
+    History,
     Settings,
 }
 
 impl InstanceSubpageType {
+    pub fn name(self) -> SharedString {
+        match self {
+            InstanceSubpageType::Quickplay => ts!("instance.quickplay"),
+            InstanceSubpageType::Logs => ts!("instance.logs"),
+            InstanceSubpageType::Mods => ts!("instance.mods"),
+            InstanceSubpageType::ResourcePacks => ts!("instance.resource_packs"),
+            InstanceSubpageType::Config => ts!("instance.config"),
+            InstanceSubpageType::GameOptions => ts!("instance.game_options"),
+            InstanceSubpageType::History => ts!("instance.history"),
+            InstanceSubpageType::Settings => ts!("instance.settings"),
+        }
+    }
+
     pub fn create(
         self,
         instance: &Entity<InstanceEntry>,
@@ -143,7 +225,7 @@ impl InstanceSubpageType {
     ) -> InstanceSubpage {
         match self {
             InstanceSubpageType::Quickplay => InstanceSubpage::Quickplay(cx.new(|cx| {
-                InstanceQuickplaySubpage::new(instance, backend_handle, window, cx)
+                InstanceQuickplaySubpage::new(instance, data, backend_handle, window, cx)
             })),
             InstanceSubpageType::Logs => InstanceSubpage::Logs(cx.new(|cx| {
                 InstanceLogsSubpage::new(instance, backend_handle, window, cx)
@@ -154,6 +236,15 @@ impl InstanceSubpageType {
             InstanceSubpageType::ResourcePacks => InstanceSubpage::ResourcePacks(cx.new(|cx| {
                 InstanceResourcePacksSubpage::new(instance, backend_handle, window, cx)
             })),
+            InstanceSubpageType::Config => InstanceSubpage::Config(cx.new(|cx| {
+                InstanceConfigSubpage::new(instance, backend_handle, window, cx)
+            })),
+            InstanceSubpageType::GameOptions => InstanceSubpage::GameOptions(cx.new(|cx| {
+                InstanceGameOptionsSubpage::new(instance, backend_handle, window, cx)
+            })),
+            InstanceSubpageType::History => InstanceSubpage::History(cx.new(|cx| {
+                InstanceHistorySubpage::new(instance, backend_handle, window, cx)
+            })),
             InstanceSubpageType::Settings => InstanceSubpage::Settings(cx.new(|cx| {
                 InstanceSettingsSubpage::new(instance, data, backend_handle, window, cx)
             })),
@@ -167,6 +258,9 @@ pub enum InstanceSubpage {
     Logs(Entity<InstanceLogsSubpage>),
     Mods(Entity<InstanceModsSubpage>),
     ResourcePacks(Entity<InstanceResourcePacksSubpage>),
+    Config(Entity<InstanceConfigSubpage>),
+    GameOptions(Entity<InstanceGameOptionsSubpage>),
+    History(Entity<InstanceHistorySubpage>),
     Settings(Entity<InstanceSettingsSubpage>),
 }
 
@@ -177,6 +271,9 @@ impl InstanceSubpage {
             InstanceSubpage::Logs(_) => InstanceSubpageType::Logs,
             InstanceSubpage::Mods(_) => InstanceSubpageType::Mods,
             InstanceSubpage::ResourcePacks(_) => InstanceSubpageType::ResourcePacks,
+            InstanceSubpage::Config(_) => InstanceSubpageType::Config,
+            InstanceSubpage::GameOptions(_) => InstanceSubpageType::GameOptions,
+            InstanceSubpage::History(_) => InstanceSubpageType::History,
             InstanceSubpage::Settings(_) => InstanceSubpageType::Settings,
         }
     }
@@ -187,6 +284,9 @@ impl InstanceSubpage {
             Self::Logs(entity) => entity.into_any_element(),
             Self::Mods(entity) => entity.into_any_element(),
             Self::ResourcePacks(entity) => entity.into_any_element(),
+            Self::Config(entity) => entity.into_any_element(),
+            Self::GameOptions(entity) => entity.into_any_element(),
+            Self::History(entity) => entity.into_any_element(),
             Self::Settings(entity) => entity.into_any_element(),
         }
     }
@@ -8,7 +8,7 @@ use gpui_component::{
     button::{Button, ButtonVariants}, h_flex, select::{Select, SelectEvent, SelectState}, spinner::Spinner, v_flex, ActiveTheme as _, Sizable
 };
 
-use crate::{component::{named_dropdown::{NamedDropdown, NamedDropdownItem}, readonly_text_field::{ReadonlyTextField, ReadonlyTextFieldWithControls}}, entity::instance::InstanceEntry, root};
+use crate::{component::{log_navigation::LogIdentityHasher, named_dropdown::{NamedDropdown, NamedDropdownItem}, readonly_text_field::{ReadonlyTextField, ReadonlyTextFieldWithControls}}, entity::instance::InstanceEntry, root, ts};
 
 pub struct InstanceLogsSubpage {
     instance: InstanceID,
@@ -89,17 +89,27 @@ impl InstanceLogsSubpage {
                         if let Some(selected) = selected {
                             let (send, mut recv) = tokio::sync::mpsc::channel::<Arc<str>>(256);
 
-                            let text_field = cx.new(move |_| ReadonlyTextField::default());
+                            let text_field = cx.new(|_| ReadonlyTextField::default());
 
                             let text_field2 = text_field.clone();
+                            let mut identity_hasher = Some(LogIdentityHasher::new(SharedString::new(Arc::from(selected.to_string_lossy()))));
                             page._read_log_task = Some(cx.spawn(async move |_, cx| {
                                 while let Some(message) = recv.recv().await {
-                                    let _ = cx.update_entity(&text_field2, |text_field, _| {
+                                    let identified = identity_hasher.as_mut().is_some_and(|hasher| hasher.add_line(&message));
+                                    let log = if identified { identity_hasher.take().map(LogIdentityHasher::finish) } else { None };
+                                    let _ = cx.update_entity(&text_field2, |text_field, cx| {
                                         text_field.add(message);
+                                        if let Some(log) = log {
+                                            text_field.set_bookmarked_log(log, cx);
+                                        }
                                     });
                                 }
-                                let _ = cx.update_entity(&text_field2, |text_field, _| {
+                                let _ = cx.update_entity(&text_field2, |text_field, cx| {
                                     text_field.shrink_to_fit();
+                                    // Logs shorter than the lines used to identify them
+                                    if let Some(hasher) = identity_hasher {
+                                        text_field.set_bookmarked_log(hasher.finish(), cx);
+                                    }
                                 });
                             }));
 
@@ -113,7 +123,7 @@ impl InstanceLogsSubpage {
                                 ReadonlyTextFieldWithControls::new(text_field, Box::new(move |div| {
                                     let backend_handle = backend_handle.clone();
                                     let selected = selected.clone();
-                                    div.child(Button::new("upload").label("Upload").on_click(move |_, window, cx| {
+                                    div.child(Button::new("upload").label(ts!("logs.upload")).on_click(move |_, window, cx| {
                                         root::upload_log_file(selected.clone(), &backend_handle, window, cx);
                                     }))
                                 }), window, cx)
@@ -130,17 +140,8 @@ impl InstanceLogsSubpage {
                     page.available_logs = Some(dropdown);
 
                     if result.total_gzipped_size > 0 {
-                        let bytes = result.total_gzipped_size;
-                        let string = if bytes < 1000 {
-                            format!("Cleanup old log files ({} bytes)", bytes)
-                        } else if bytes < 1000*1000 {
-                            format!("Cleanup old log files ({}kB)", bytes/1000)
-                        } else if bytes < 1000*1000*1000 {
-                            format!("Cleanup old log files ({}MB)", bytes/1000/1000)
-                        } else {
-                            format!("Cleanup old log files ({}GB)", bytes/1000/1000/1000)
-                        };
-                        page.clean_old_logs_text = Some(string.into());
+                        let size = crate::format_size(result.total_gzipped_size as u64);
+                        page.clean_old_logs_text = Some(SharedString::new(rust_i18n::t!("logs.cleanup_old", size = size)));
                     }
                 }
                 cx.notify();
@@ -162,7 +163,7 @@ impl Render for InstanceLogsSubpage {
             .gap_3()
             .mb_1()
             .ml_1()
-            .child(div().text_lg().child("Logs"));
+            .child(div().text_lg().child(ts!("instance.logs")));
 
         let mut content = div()
             .size_full()
@@ -171,18 +172,18 @@ impl Render for InstanceLogsSubpage {
             .border_color(theme.border);
 
         if self.no_available_logs {
-            content = content.child(h_flex().justify_center().size_full().text_lg().child("No available logs"));
+            content = content.child(h_flex().justify_center().size_full().text_lg().child(ts!("logs.no_logs")));
         } else {
             if let Some(available_logs) = self.available_logs.as_ref() {
-                header = header.child(Select::new(&available_logs).small().mt_0p5().placeholder("Select log file"));
+                header = header.child(Select::new(&available_logs).small().mt_0p5().placeholder(ts!("logs.select_log_file")));
             } else {
-                content = content.child(h_flex().justify_center().size_full().text_lg().gap_3().child("Loading available logs...").child(Spinner::new()));
+                content = content.child(h_flex().justify_center().size_full().text_lg().gap_3().child(ts!("logs.loading_logs")).child(Spinner::new()));
             }
 
             if let Some(log_content) = self.log_content.clone() {
                 content = content.child(log_content);
             } else if self.available_logs.is_some() {
-                content = content.child(h_flex().justify_center().size_full().text_lg().child("Select log file"));
+                content = content.child(h_flex().justify_center().size_full().text_lg().child(ts!("logs.select_log_file")));
             }
         }
 
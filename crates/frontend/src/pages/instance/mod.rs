@@ -1,3 +1,6 @@
+pub mod config_subpage;
+pub mod game_options_subpage;
+pub mod history_subpage;
 pub mod instance_page;
 pub mod logs_subpage;
 pub mod mods_subpage;
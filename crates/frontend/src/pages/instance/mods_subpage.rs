@@ -3,18 +3,18 @@ use std::{hash::{DefaultHasher, Hash, Hasher}, path::Path, sync::{
 }};
 
 use bridge::{
-    handle::BackendHandle, install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{AtomicContentUpdateStatus, InstanceID, InstanceContentID, InstanceContentSummary, ContentType, ContentSummary}, message::{AtomicBridgeDataLoadState, MessageToBackend}, serial::AtomicOptionSerial
+    handle::BackendHandle, install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{AtomicContentUpdateStatus, InstanceID, InstanceStatus, InstanceContentID, InstanceContentSummary, ContentType, ContentSummary, LoaderVersionRequirement}, message::{AtomicBridgeDataLoadState, MessageToBackend}, serial::AtomicOptionSerial
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Icon, IconName, IndexPath, Sizable, WindowExt, breadcrumb::{Breadcrumb, BreadcrumbItem}, button::{Button, ButtonVariants}, h_flex, input::SelectAll, list::{ListDelegate, ListItem, ListState}, notification::{Notification, NotificationType}, switch::Switch, v_flex
+    ActiveTheme as _, Icon, IconName, IndexPath, Selectable, Sizable, breadcrumb::{Breadcrumb, BreadcrumbItem}, button::{Button, ButtonVariants}, h_flex, input::{InputEvent, InputState, SelectAll}, list::{ListDelegate, ListItem, ListState}, notification::NotificationType, switch::Switch, v_flex
 };
 use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
-use schema::{content::ContentSource, loader::Loader, modrinth::ModrinthProjectType};
+use schema::{content::ContentSource, launch_history::{LaunchHistoryEntry, LaunchOutcome}, loader::Loader, modrinth::ModrinthProjectType};
 use ustr::Ustr;
 
-use crate::{component::content_list::ContentListDelegate, entity::instance::InstanceEntry, interface_config::InterfaceConfig, png_render_cache, root, ui::PageType};
+use crate::{component::content_list::{self, ContentListDelegate}, entity::{instance::InstanceEntry, notifications::push_notification}, interface_config::InterfaceConfig, png_render_cache, root, ts, ui::PageType};
 
 use super::instance_page::InstanceSubpageType;
 
@@ -26,8 +26,16 @@ pub struct InstanceModsSubpage {
     backend_handle: BackendHandle,
     mods_state: Arc<AtomicBridgeDataLoadState>,
     mod_list: Entity<ListState<ContentListDelegate>>,
+    search_input: Entity<InputState>,
     load_serial: AtomicOptionSerial,
+    last_status: InstanceStatus,
+    last_loader_version: Option<Ustr>,
+    loader_requirement: Option<LoaderVersionRequirement>,
     _add_from_file_task: Option<Task<()>>,
+    _get_history_task: Task<()>,
+    _get_loader_requirement_task: Task<()>,
+    _instance_subscription: Subscription,
+    _search_input_subscription: Subscription,
 }
 
 impl InstanceModsSubpage {
@@ -37,11 +45,26 @@ impl InstanceModsSubpage {
         window: &mut gpui::Window,
         cx: &mut gpui::Context<Self>,
     ) -> Self {
+        let _instance_subscription = cx.observe_in(instance, window, |page, instance, window, cx| {
+            let status = instance.read(cx).status;
+            if status != page.last_status {
+                page.last_status = status;
+                page.update_suspected_mods(window, cx);
+            }
+            let loader_version = instance.read(cx).configuration.preferred_loader_version;
+            if loader_version != page.last_loader_version {
+                page.last_loader_version = loader_version;
+                page.update_loader_requirement(window, cx);
+            }
+        });
+
         let instance = instance.read(cx);
         let instance_title = instance.title().into();
         let instance_loader = instance.configuration.loader;
         let instance_version = instance.configuration.minecraft_version;
         let instance_id = instance.id;
+        let last_status = instance.status;
+        let last_loader_version = instance.configuration.preferred_loader_version;
 
         let mods_state = Arc::clone(&instance.mods_state);
 
@@ -50,17 +73,33 @@ impl InstanceModsSubpage {
 
         let mods = instance.mods.clone();
 
-        let mod_list = cx.new(move |cx| {
+        cx.observe_in(&mods, window, |page, _, window, cx| page.update_loader_requirement(window, cx)).detach();
+
+        let mod_list = cx.new(|cx| {
             cx.observe(&mods, |list: &mut ListState<ContentListDelegate>, mods, cx| {
                 let actual_mods = mods.read(cx);
                 list.delegate_mut().set_content(actual_mods);
                 cx.notify();
             }).detach();
 
-            ListState::new(mods_list_delegate, window, cx).selectable(false).searchable(true)
+            ListState::new(mods_list_delegate, window, cx).selectable(false)
         });
 
-        Self {
+        // Re-render when the selection changes so the bulk action bar and the sticky header stay in sync
+        cx.observe(&mod_list, |_, _, cx| cx.notify()).detach();
+
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.search")).clean_on_escape());
+        let _search_input_subscription = cx.subscribe(&search_input, |page: &mut Self, input, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                let query = input.read(cx).value();
+                page.mod_list.update(cx, |list, cx| {
+                    list.delegate_mut().actual_perform_search(&query);
+                    cx.notify();
+                });
+            }
+        });
+
+        let mut page = Self {
             instance: instance_id,
             instance_title,
             instance_loader,
@@ -68,9 +107,113 @@ impl InstanceModsSubpage {
             backend_handle,
             mods_state,
             mod_list,
+            search_input,
             load_serial: AtomicOptionSerial::default(),
+            last_status,
+            last_loader_version,
+            loader_requirement: None,
             _add_from_file_task: None,
+            _get_history_task: Task::ready(()),
+            _get_loader_requirement_task: Task::ready(()),
+            _instance_subscription,
+            _search_input_subscription,
+        };
+
+        page.update_suspected_mods(window, cx);
+        page.update_loader_requirement(window, cx);
+
+        page
+    }
+
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        let focus_handle = self.search_input.read(cx).focus_handle(cx);
+        focus_handle.focus(window, cx);
+    }
+
+    /// Highlights the mods that the crash report of the most recent launch blamed, if it crashed. The
+    /// history is sent newest first
+    fn update_suspected_mods(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_history_task = cx.spawn_in(window, async move |page, cx| {
+            let entries: Arc<[LaunchHistoryEntry]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+
+            let suspected: FxHashSet<Arc<str>> = entries.first()
+                .filter(|entry| entry.outcome == LaunchOutcome::Crashed)
+                .and_then(|entry| entry.suspected_mods.as_ref())
+                .map(|suspected_mods| suspected_mods.iter().map(|suspected| suspected.id.clone()).collect())
+                .unwrap_or_default();
+
+            let _ = page.update(cx, move |page, cx| {
+                let instance_title = page.instance_title.clone();
+                page.mod_list.update(cx, |list, cx| {
+                    list.delegate_mut().set_suspected(suspected, instance_title);
+                    cx.notify();
+                });
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetLaunchHistory {
+            id: self.instance,
+            channel: send,
+        });
+    }
+
+    /// Asks the backend whether any enabled mod needs a newer loader version than the pinned one
+    fn update_loader_requirement(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.last_loader_version.is_none() {
+            self.loader_requirement = None;
+            self._get_loader_requirement_task = Task::ready(());
+            return;
         }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_loader_requirement_task = cx.spawn_in(window, async move |page, cx| {
+            let requirement = recv.await.ok().flatten();
+            let _ = page.update(cx, move |page, cx| {
+                page.loader_requirement = requirement;
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::CheckLoaderVersionRequirement {
+            id: self.instance,
+            channel: send,
+        });
+    }
+
+    fn render_loader_requirement(&self, requirement: &LoaderVersionRequirement, cx: &App) -> Div {
+        let theme = cx.theme();
+
+        let mut mods = requirement.mods.iter().take(3).map(|name| &**name).collect::<Vec<_>>().join(", ");
+        if requirement.mods.len() > 3 {
+            mods.push(' ');
+            mods.push_str(&rust_i18n::t!("common.and_more", count = requirement.mods.len() - 3));
+        }
+        let key = if requirement.mods.len() == 1 { "loader_requirement.one" } else { "loader_requirement.many" };
+        let message = SharedString::new(rust_i18n::t!(key, mods = mods, loader = self.instance_loader.name(),
+            minimum = requirement.minimum, current = requirement.current));
+
+        let use_suggested = requirement.suggested.map(|suggested| {
+            let backend_handle = self.backend_handle.clone();
+            let instance = self.instance;
+            Button::new("use-required-loader").label(SharedString::new(rust_i18n::t!("loader_requirement.use_suggested", version = suggested))).warning().compact().small().on_click(move |_, _, _| {
+                backend_handle.send(MessageToBackend::SetInstancePreferredLoaderVersion {
+                    id: instance,
+                    loader_version: Some(suggested.as_str()),
+                });
+            })
+        });
+
+        h_flex()
+            .gap_2()
+            .mb_1()
+            .p_2()
+            .rounded(theme.radius)
+            .border_1()
+            .border_color(theme.border)
+            .child(div().text_color(theme.warning).child(IconName::TriangleAlert))
+            .child(div().flex_1().child(message))
+            .children(use_suggested)
     }
 }
 
@@ -83,19 +226,43 @@ impl Render for InstanceModsSubpage {
             self.backend_handle.send_with_serial(MessageToBackend::RequestLoadMods { id: self.instance }, &self.load_serial);
         }
 
+        let list_delegate = self.mod_list.read(cx).delegate();
+        let suspected_count = list_delegate.suspected_count();
+        let only_suspected = list_delegate.only_suspected();
+        let suspected_filter = (suspected_count > 0).then(|| {
+            let mod_list = self.mod_list.clone();
+            Button::new("only-suspected").label(SharedString::new(rust_i18n::t!("suspected.only_suspected", count = suspected_count))).danger().compact().small()
+                .selected(only_suspected)
+                .tooltip(ts!("suspected.only_suspected_tooltip"))
+                .on_click(move |_, _, cx| {
+                    cx.update_entity(&mod_list, |list, cx| {
+                        list.delegate_mut().set_only_suspected(!only_suspected);
+                        cx.notify();
+                    });
+                })
+        });
+
         let header = h_flex()
             .gap_3()
             .mb_1()
             .ml_1()
-            .child(div().text_lg().child("Mods"))
-            .child(Button::new("update").label("Check for updates").success().compact().small().on_click({
+            .child(div().text_lg().child(ts!("instance.mods")))
+            .children(suspected_filter)
+            .child(Button::new("update").label(ts!("instance.check_for_updates")).success().compact().small().on_click({
                 let backend_handle = self.backend_handle.clone();
                 let instance_id = self.instance;
                 move |_, window, cx| {
                     crate::root::start_update_check(instance_id, &backend_handle, window, cx);
                 }
             }))
-            .child(Button::new("addmr").label("Add from Modrinth").success().compact().small().on_click({
+            .child(Button::new("repair").label(ts!("instance.repair_content")).compact().small().tooltip(ts!("instance.repair_content_tooltip")).on_click({
+                let backend_handle = self.backend_handle.clone();
+                let instance_id = self.instance;
+                move |_, window, cx| {
+                    crate::root::start_content_repair(instance_id, &backend_handle, window, cx);
+                }
+            }))
+            .child(Button::new("addmr").label(ts!("instance.add_from_modrinth")).success().compact().small().on_click({
                 let instance = self.instance;
                 move |_, window, cx| {
                     let page = crate::ui::PageType::Modrinth {
@@ -106,7 +273,7 @@ impl Render for InstanceModsSubpage {
                     root::switch_page(page, path, window, cx);
                 }
             }))
-            .child(Button::new("addfile").label("Add from file").success().compact().small().on_click({
+            .child(Button::new("addfile").label(ts!("instance.add_from_file")).success().compact().small().on_click({
                 let backend_handle = self.backend_handle.clone();
                 let instance = self.instance;
                 cx.listener(move |this, _, window, cx| {
@@ -114,7 +281,7 @@ impl Render for InstanceModsSubpage {
                         files: true,
                         directories: false,
                         multiple: true,
-                        prompt: Some("Select mods to install".into())
+                        prompt: Some(ts!("instance.select_mods"))
                     });
 
                     let backend_handle = backend_handle.clone();
@@ -143,12 +310,7 @@ impl Render for InstanceModsSubpage {
                                 },
                                 Ok(None) => {},
                                 Err(error) => {
-                                    let error = format!("{}", error);
-                                    let notification = Notification::new()
-                                        .autohide(false)
-                                        .with_type(NotificationType::Error)
-                                        .title(error);
-                                    window.push_notification(notification, cx);
+                                    push_notification(NotificationType::Error, format!("{}", error), window, cx);
                                 },
                             }
                         });
@@ -157,14 +319,62 @@ impl Render for InstanceModsSubpage {
                 })
             }));
 
-        v_flex().p_4().size_full().child(header).child(
+        let selected_count = self.mod_list.read(cx).delegate().selected_count();
+        let bulk_actions = (selected_count > 0).then(|| {
+            h_flex()
+                .gap_2()
+                .mb_1()
+                .ml_1()
+                .child(div().text_color(theme.muted_foreground).child(SharedString::new(rust_i18n::t!("instance.selected_count", count = selected_count))))
+                .child(Button::new("bulk-enable").label(ts!("instance.enable_selected")).compact().small().on_click({
+                    let mod_list = self.mod_list.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    let instance = self.instance;
+                    move |_, _, cx| {
+                        send_for_selected(&mod_list, &backend_handle, instance, cx, |id, content_ids| MessageToBackend::SetContentEnabled { id, content_ids, enabled: true });
+                    }
+                }))
+                .child(Button::new("bulk-disable").label(ts!("instance.disable_selected")).compact().small().on_click({
+                    let mod_list = self.mod_list.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    let instance = self.instance;
+                    move |_, _, cx| {
+                        send_for_selected(&mod_list, &backend_handle, instance, cx, |id, content_ids| MessageToBackend::SetContentEnabled { id, content_ids, enabled: false });
+                    }
+                }))
+                .child(Button::new("bulk-delete").label(SharedString::new(rust_i18n::t!("instance.delete_selected", count = selected_count))).danger().compact().small().on_click({
+                    let mod_list = self.mod_list.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    let instance = self.instance;
+                    move |_, _, cx| {
+                        send_for_selected(&mod_list, &backend_handle, instance, cx, |id, content_ids| MessageToBackend::DeleteContent { id, content_ids });
+                        cx.update_entity(&mod_list, |list, cx| {
+                            list.delegate_mut().clear_selection();
+                            cx.notify();
+                        });
+                    }
+                }))
+                .child(Button::new("bulk-clear").label(ts!("instance.clear_selection")).compact().small().on_click({
+                    let mod_list = self.mod_list.clone();
+                    move |_, _, cx| {
+                        cx.update_entity(&mod_list, |list, cx| {
+                            list.delegate_mut().clear_selection();
+                            cx.notify();
+                        });
+                    }
+                }))
+        });
+
+        let loader_requirement = self.loader_requirement.as_ref().map(|requirement| self.render_loader_requirement(requirement, cx));
+
+        v_flex().p_4().size_full().child(header).children(loader_requirement).children(bulk_actions).child(
             div()
                 .id("mod-list-area")
                 .size_full()
                 .border_1()
                 .rounded(theme.radius)
                 .border_color(theme.border)
-                .child(self.mod_list.clone())
+                .child(content_list::render_with_search(&self.mod_list, &self.search_input, cx))
                 .on_click({
                     let mod_list = self.mod_list.clone();
                     move |_, _, cx| {
@@ -187,3 +397,16 @@ impl Render for InstanceModsSubpage {
         )
     }
 }
+
+fn send_for_selected(
+    mod_list: &Entity<ListState<ContentListDelegate>>,
+    backend_handle: &BackendHandle,
+    instance: InstanceID,
+    cx: &App,
+    message: fn(InstanceID, Vec<InstanceContentID>) -> MessageToBackend,
+) {
+    let content_ids = mod_list.read(cx).delegate().selected_content_ids();
+    if !content_ids.is_empty() {
+        backend_handle.send(message(instance, content_ids));
+    }
+}
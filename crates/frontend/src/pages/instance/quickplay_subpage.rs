@@ -1,42 +1,63 @@
 use std::{
     ffi::OsString,
-    sync::{Arc, atomic::Ordering},
+    path::Path,
+    sync::{Arc, atomic::{AtomicBool, Ordering}},
 };
 
 use bridge::{
     handle::BackendHandle,
-    instance::{InstanceID, InstanceServerSummary, InstanceWorldSummary},
-    message::{AtomicBridgeDataLoadState, MessageToBackend, QuickPlayLaunch}, serial::AtomicOptionSerial,
+    instance::{InstanceID, InstanceServerSummary, InstanceStatus, InstanceWorldDetails, InstanceWorldSummary, ServerEdit, WorldDifficulty, WorldGameMode},
+    keep_alive::KeepAlive, message::{AtomicBridgeDataLoadState, LanGame, MessageToBackend, QuickPlayLaunch}, meta::MetadataRequest,
+    modal_action::ModalAction,
+    serial::AtomicOptionSerial,
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Icon, IndexPath,
+    ActiveTheme as _, Disableable, Icon, IconName, IndexPath, Sizable, WindowExt,
     button::{Button, ButtonVariants},
+    checkbox::Checkbox,
     h_flex,
+    input::{Input, InputState},
     list::{ListDelegate, ListItem, ListState},
     v_flex,
 };
 
-use crate::{entity::instance::InstanceEntry, png_render_cache, root};
+use schema::version_manifest::MinecraftVersionManifest;
+
+use crate::{
+    entity::{
+        DataEntities, instance::InstanceEntry, lan_games::LanGamesState,
+        metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState},
+    },
+    png_render_cache, root, ts,
+};
 
 pub struct InstanceQuickplaySubpage {
     instance: InstanceID,
+    instance_entry: Entity<InstanceEntry>,
     backend_handle: BackendHandle,
     worlds_state: Arc<AtomicBridgeDataLoadState>,
+    worlds_total: Entity<Option<usize>>,
     world_list: Entity<ListState<WorldsListDelegate>>,
     servers_state: Arc<AtomicBridgeDataLoadState>,
     server_list: Entity<ListState<ServersListDelegate>>,
     worlds_serial: AtomicOptionSerial,
     servers_serial: AtomicOptionSerial,
+    lan_games: Entity<LanGamesState>,
+    /// LAN games are listened for while the subpage is open, so they can be joined before starting the game
+    _lan_keep_alive: KeepAlive,
+    version_manifest: Entity<FrontendMetadataState>,
 }
 
 impl InstanceQuickplaySubpage {
     pub fn new(
         instance: &Entity<InstanceEntry>,
+        data: &DataEntities,
         backend_handle: BackendHandle,
         mut window: &mut gpui::Window,
         cx: &mut gpui::Context<Self>,
     ) -> Self {
+        let instance_entry = instance.clone();
         let instance = instance.read(cx);
         let instance_id = instance.id;
 
@@ -49,10 +70,14 @@ impl InstanceQuickplaySubpage {
             backend_handle: backend_handle.clone(),
             worlds: instance.worlds.read(cx).to_vec(),
             searched: instance.worlds.read(cx).to_vec(),
+            expanded: None,
+            details: WorldDetailsState::Loading,
+            _details_task: Task::ready(()),
         };
 
         let servers_list_delegate = ServersListDelegate {
             id: instance_id,
+            instance: instance_entry.clone(),
             name: instance.name.clone(),
             backend_handle: backend_handle.clone(),
             servers: instance.servers.read(cx).to_vec(),
@@ -60,8 +85,19 @@ impl InstanceQuickplaySubpage {
         };
 
         let worlds = instance.worlds.clone();
+        let worlds_total = instance.worlds_total.clone();
         let servers = instance.servers.clone();
 
+        cx.observe(&worlds_total, |_, _, cx| cx.notify()).detach();
+        cx.observe(&instance_entry, |_, _, cx| cx.notify()).detach();
+        cx.observe(&data.lan_games, |_, _, cx| cx.notify()).detach();
+
+        let lan_keep_alive = KeepAlive::new();
+        backend_handle.send(MessageToBackend::WatchLanGames { keep_alive: lan_keep_alive.create_handle() });
+
+        let version_manifest = FrontendMetadata::request(&data.metadata, MetadataRequest::MinecraftVersionManifest, cx);
+        cx.observe(&version_manifest, |_, _, cx| cx.notify()).detach();
+
         let window2 = &mut window;
         let world_list = cx.new(move |cx| {
             cx.observe(&worlds, |list: &mut ListState<WorldsListDelegate>, worlds, cx| {
@@ -89,15 +125,92 @@ impl InstanceQuickplaySubpage {
 
         Self {
             instance: instance_id,
+            instance_entry,
             backend_handle,
             worlds_state,
+            worlds_total,
             world_list,
             servers_state,
             server_list,
             worlds_serial: AtomicOptionSerial::default(),
             servers_serial: AtomicOptionSerial::default(),
+            lan_games: data.lan_games.clone(),
+            _lan_keep_alive: lan_keep_alive,
+            version_manifest,
         }
     }
+
+    /// Versions missing from the manifest are assumed to be new enough, the game will complain itself otherwise
+    fn supports_quick_play(&self, cx: &App) -> bool {
+        let result: FrontendMetadataResult<MinecraftVersionManifest> = self.version_manifest.read(cx).result();
+        let FrontendMetadataResult::Loaded(manifest) = result else {
+            return true;
+        };
+        let minecraft_version = self.instance_entry.read(cx).configuration.minecraft_version;
+        manifest.versions.iter()
+            .find(|version| version.id == minecraft_version)
+            .is_none_or(|version| version.supports_quick_play())
+    }
+
+    fn render_lan_games(&self, games: &[LanGame], cx: &App) -> Div {
+        let theme = cx.theme();
+        let instance = self.instance_entry.read(cx);
+        let running = instance.status != InstanceStatus::NotRunning;
+        let supports_quick_play = self.supports_quick_play(cx);
+
+        let rows = games.iter().enumerate().map(|(index, game)| {
+            let join_tooltip = if running {
+                Some(ts!("lan_games.instance_running"))
+            } else if !supports_quick_play {
+                Some(ts!("lan_games.quick_play_unsupported"))
+            } else {
+                None
+            };
+
+            let join = Button::new(("join-lan", index))
+                .success()
+                .small()
+                .icon(Icon::empty().path("icons/play.svg"))
+                .label(ts!("lan_games.join"))
+                .disabled(join_tooltip.is_some())
+                .when_some(join_tooltip, |this, tooltip| this.tooltip(tooltip))
+                .on_click({
+                    let id = self.instance;
+                    let name = instance.name.clone();
+                    let target = OsString::from(game.address.to_string());
+                    let backend_handle = self.backend_handle.clone();
+                    move |_, window, cx| {
+                        root::start_instance(id, name.clone(), Some(QuickPlayLaunch::Multiplayer(target.clone())), &backend_handle, window, cx);
+                    }
+                });
+
+            let address = game.address.clone();
+            let copy = Button::new(("copy-lan-address", index)).ghost().small().icon(IconName::Copy).tooltip(ts!("lan_games.copy_address"))
+                .on_click(move |_, _, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(address.to_string()));
+                });
+
+            h_flex()
+                .gap_2()
+                .p_1()
+                .child(div().px_2().child(join))
+                .child(v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(SharedString::from(game.motd.clone()))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::from(game.address.clone()))))
+                .child(copy.mr_2())
+        });
+
+        v_flex()
+            .child(div().mb_1().ml_1().text_lg().child(ts!("lan_games.title")))
+            .child(v_flex()
+                .text_base()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .children(rows))
+    }
 }
 
 impl Render for InstanceQuickplaySubpage {
@@ -114,8 +227,23 @@ impl Render for InstanceQuickplaySubpage {
             self.backend_handle.send_with_serial(MessageToBackend::RequestLoadServers { id: self.instance }, &self.servers_serial);
         }
 
-        let worlds_header = div().mb_1().ml_1().text_lg().child("Worlds");
-        let servers_header = div().mb_1().ml_1().text_lg().child("Servers");
+        let worlds_loaded = self.world_list.read(cx).delegate().worlds.len();
+        let worlds_header = h_flex().mb_1().ml_1().gap_2().items_center()
+            .child(div().text_lg().child(ts!("worlds.title")))
+            .when_some(*self.worlds_total.read(cx), |this, total| {
+                this.child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(rust_i18n::t!("worlds.showing_count", loaded = worlds_loaded, total = total))))
+            });
+        let add_server = Button::new("add-server").small().icon(IconName::Plus).label(ts!("servers.add")).on_click({
+            let instance = self.instance_entry.clone();
+            let backend_handle = self.backend_handle.clone();
+            move |_, window, cx| {
+                open_server_dialog(instance.clone(), None, backend_handle.clone(), window, cx);
+            }
+        });
+        let servers_header = h_flex().mb_1().ml_1().justify_between().child(div().text_lg().child(ts!("servers.title"))).child(add_server);
+
+        let lan_games = self.lan_games.read(cx).games.clone();
+        let lan_games = if lan_games.is_empty() { None } else { Some(self.render_lan_games(&lan_games, cx)) };
 
         v_flex().p_4().gap_4().size_full().child(
             h_flex()
@@ -133,15 +261,18 @@ impl Render for InstanceQuickplaySubpage {
                     ),
                 )
                 .child(
-                    v_flex().size_full().child(servers_header).child(
-                        v_flex()
-                            .text_base()
-                            .size_full()
-                            .border_1()
-                            .rounded(theme.radius)
-                            .border_color(theme.border)
-                            .child(self.server_list.clone()),
-                    ),
+                    v_flex().size_full().gap_4().child(
+                        v_flex().size_full().child(servers_header).child(
+                            v_flex()
+                                .text_base()
+                                .size_full()
+                                .border_1()
+                                .rounded(theme.radius)
+                                .border_color(theme.border)
+                                .child(self.server_list.clone()),
+                        ),
+                    )
+                    .children(lan_games),
                 ),
         )
     }
@@ -153,6 +284,328 @@ pub struct WorldsListDelegate {
     backend_handle: BackendHandle,
     worlds: Vec<InstanceWorldSummary>,
     searched: Vec<InstanceWorldSummary>,
+    /// The world whose details are shown, only one world is expanded at a time
+    expanded: Option<Arc<Path>>,
+    details: WorldDetailsState,
+    _details_task: Task<()>,
+}
+
+enum WorldDetailsState {
+    Loading,
+    Loaded(InstanceWorldDetails),
+    Error(SharedString),
+}
+
+impl WorldsListDelegate {
+    fn toggle_details(&mut self, level_path: Arc<Path>, cx: &mut Context<ListState<Self>>) {
+        if self.expanded.as_ref() == Some(&level_path) {
+            self.expanded = None;
+            self._details_task = Task::ready(());
+            cx.notify();
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::GetWorldDetails {
+            id: self.id,
+            level_path: level_path.clone(),
+            channel: send,
+        });
+
+        self.expanded = Some(level_path);
+        self.details = WorldDetailsState::Loading;
+        self._details_task = cx.spawn(async move |list, cx| {
+            let details = match recv.await {
+                Ok(Ok(details)) => WorldDetailsState::Loaded(details),
+                Ok(Err(error)) => WorldDetailsState::Error(error.into()),
+                Err(_) => WorldDetailsState::Error(ts!("worlds.details_failed")),
+            };
+            let _ = list.update(cx, |list, cx| {
+                list.delegate_mut().details = details;
+                cx.notify();
+            });
+        });
+        cx.notify();
+    }
+
+    fn render_details(&self, cx: &App) -> Div {
+        let theme = cx.theme();
+
+        let details = match &self.details {
+            WorldDetailsState::Loading => return div().pl_4().text_sm().text_color(theme.muted_foreground).child(ts!("common.loading")),
+            WorldDetailsState::Error(error) => return div().pl_4().text_sm().text_color(theme.danger).child(error.clone()),
+            WorldDetailsState::Loaded(details) => details,
+        };
+
+        let row = |label: SharedString, value: AnyElement| {
+            h_flex()
+                .gap_2()
+                .child(div().w_24().text_color(theme.muted_foreground).child(label))
+                .child(value)
+        };
+
+        let game_mode = if details.hardcore {
+            SharedString::new(rust_i18n::t!("worlds.hardcore", game_mode = game_mode_name(details.game_mode)))
+        } else {
+            game_mode_name(details.game_mode)
+        };
+
+        let seed = match details.seed {
+            Some(seed) => h_flex()
+                .gap_1()
+                .child(seed.to_string())
+                .child(Button::new("copy-seed").ghost().xsmall().icon(IconName::Copy).tooltip(ts!("worlds.copy_seed")).on_click(move |_, _, cx| {
+                    cx.stop_propagation();
+                    cx.write_to_clipboard(ClipboardItem::new_string(seed.to_string()));
+                }))
+                .into_any_element(),
+            None => ts!("worlds.unknown").into_any_element(),
+        };
+
+        // Worlds from before 1.9 don't store their version, so there's no version to create the instance on
+        let split = details.version_name.clone().zip(self.expanded.clone()).map(|(version, level_path)| {
+            let id = self.id;
+            let backend_handle = self.backend_handle.clone();
+            let title = self.worlds.iter().find(|world| world.level_path == level_path)
+                .map(|world| SharedString::from(world.title.clone()))
+                .unwrap_or_default();
+            Button::new("split-world").outline().xsmall().label(ts!("split_world.title")).on_click(move |_, window, cx| {
+                cx.stop_propagation();
+                open_split_world_dialog(id, level_path.clone(), title.clone(), version.clone(), backend_handle.clone(), window, cx);
+            })
+        });
+
+        v_flex()
+            .pl_4()
+            .pb_1()
+            .gap_0p5()
+            .text_sm()
+            .child(row(ts!("worlds.game_mode"), game_mode.into_any_element()))
+            .child(row(ts!("worlds.difficulty"), details.difficulty.map(difficulty_name).unwrap_or(ts!("worlds.unknown")).into_any_element()))
+            .child(row(ts!("worlds.seed"), seed))
+            .child(row(ts!("worlds.version"), details.version_name.as_deref().map(SharedString::new).unwrap_or(ts!("worlds.unknown")).into_any_element()))
+            .child(row(ts!("worlds.cheats"), if details.allow_commands { ts!("worlds.cheats_allowed") } else { ts!("worlds.cheats_not_allowed") }.into_any_element()))
+            .child(row(ts!("worlds.size"), crate::format_size(details.size_bytes).into_any_element()))
+            .when_some(split, |this, split| this.child(div().pt_1().child(split)))
+    }
+}
+
+fn game_mode_name(game_mode: WorldGameMode) -> SharedString {
+    match game_mode {
+        WorldGameMode::Survival => ts!("worlds.survival"),
+        WorldGameMode::Creative => ts!("worlds.creative"),
+        WorldGameMode::Adventure => ts!("worlds.adventure"),
+        WorldGameMode::Spectator => ts!("worlds.spectator"),
+        WorldGameMode::Unknown(_) => ts!("worlds.unknown"),
+    }
+}
+
+fn difficulty_name(difficulty: WorldDifficulty) -> SharedString {
+    match difficulty {
+        WorldDifficulty::Peaceful => ts!("worlds.peaceful"),
+        WorldDifficulty::Easy => ts!("worlds.easy"),
+        WorldDifficulty::Normal => ts!("worlds.normal"),
+        WorldDifficulty::Hard => ts!("worlds.hard"),
+    }
+}
+
+/// Creates an instance on the version the world was last played in and moves the world into it,
+/// so old worlds don't have to be kept in an instance that keeps getting updated
+fn open_split_world_dialog(
+    id: InstanceID,
+    level_path: Arc<Path>,
+    world_title: SharedString,
+    version: Arc<str>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let name_input = cx.new(|cx| InputState::new(window, cx).default_value(world_title.to_string()));
+    let keep_copy = Arc::new(AtomicBool::new(false));
+    let message = SharedString::new(rust_i18n::t!("split_world.description", version = version));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let name = name_input.read(cx).value();
+        let is_keep_copy = keep_copy.load(Ordering::Relaxed);
+
+        let create_button = Button::new("create").label(ts!("split_world.create")).success().disabled(name.trim().is_empty()).on_click({
+            let level_path = level_path.clone();
+            let backend_handle = backend_handle.clone();
+            move |_, window, cx| {
+                window.close_all_dialogs(cx);
+
+                let modal_action = ModalAction::default();
+                backend_handle.send(MessageToBackend::SplitWorldIntoInstance {
+                    id,
+                    level_path: level_path.clone(),
+                    name: name.trim().into(),
+                    keep_copy: is_keep_copy,
+                    modal_action: modal_action.clone(),
+                });
+                let title = if is_keep_copy { ts!("split_world.copying") } else { ts!("split_world.moving") };
+                crate::modals::generic::show_modal(window, cx, title, ts!("split_world.failed"), modal_action);
+            }
+        });
+
+        dialog.title(ts!("split_world.title"))
+            .child(v_flex()
+                .gap_2()
+                .child(message.clone())
+                .child(crate::labelled(ts!("common.name"), Input::new(&name_input)))
+                .child(Checkbox::new("keep-copy")
+                    .label(ts!("split_world.keep_copy"))
+                    .checked(is_keep_copy)
+                    .on_click({
+                        let keep_copy = keep_copy.clone();
+                        move |value, _, _| {
+                            keep_copy.store(*value, Ordering::Relaxed);
+                        }
+                    }))
+                .child(create_button))
+    });
+}
+
+/// Adds a server, or edits `existing` when given
+fn open_server_dialog(
+    instance: Entity<InstanceEntry>,
+    existing: Option<InstanceServerSummary>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let name_input = cx.new(|cx| {
+        let state = InputState::new(window, cx).placeholder(ts!("servers.default_name"));
+        match &existing {
+            Some(existing) => state.default_value(existing.name.to_string()),
+            None => state,
+        }
+    });
+    let ip_input = cx.new(|cx| {
+        let state = InputState::new(window, cx);
+        match &existing {
+            Some(existing) => state.default_value(existing.ip.to_string()),
+            None => state,
+        }
+    });
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let name = name_input.read(cx).value();
+        let ip = ip_input.read(cx).value();
+        let valid = !ip.trim().is_empty();
+
+        let instance = instance.clone();
+        let existing = existing.clone();
+        let backend_handle = backend_handle.clone();
+        let label = if existing.is_some() { ts!("servers.save_button") } else { ts!("servers.add_button") };
+        let save_button = Button::new("save").label(label).success().disabled(!valid).on_click(move |_, window, cx| {
+            window.close_all_dialogs(cx);
+
+            let name: Arc<str> = if name.trim().is_empty() { rust_i18n::t!("servers.default_name").into() } else { name.trim().into() };
+            let ip: Arc<str> = ip.trim().into();
+            let edit = match &existing {
+                Some(existing) => ServerEdit::Edit { name: existing.name.clone(), ip: existing.ip.clone(), new_name: name, new_ip: ip },
+                None => ServerEdit::Add { name, ip },
+            };
+            send_server_edit(&instance, edit, &backend_handle, window, cx);
+        });
+
+        dialog.title(if existing.is_some() { ts!("servers.edit") } else { ts!("servers.add") })
+            .child(v_flex()
+                .gap_2()
+                .child(crate::labelled(ts!("common.name"), Input::new(&name_input)))
+                .child(crate::labelled(ts!("servers.address"), Input::new(&ip_input)))
+                .child(save_button))
+    });
+}
+
+/// The game keeps its own copy of the server list while it runs and may save it over the edit,
+/// so that's confirmed first
+fn send_server_edit(instance: &Entity<InstanceEntry>, edit: ServerEdit, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let instance = instance.read(cx);
+    let id = instance.id;
+    if instance.status == InstanceStatus::NotRunning {
+        backend_handle.send(MessageToBackend::EditServers { id, edit });
+        return;
+    }
+
+    let backend_handle = backend_handle.clone();
+    window.open_dialog(cx, move |dialog, _, _| {
+        let backend_handle = backend_handle.clone();
+        let edit = edit.clone();
+        dialog
+            .confirm()
+            .title(ts!("servers.game_running"))
+            .child(ts!("servers.game_running_description"))
+            .on_ok(move |_, _, _| {
+                backend_handle.send(MessageToBackend::EditServers { id, edit: edit.clone() });
+                true
+            })
+    });
+}
+
+/// Starts the instance directly into the world, asking for confirmation first if the world was
+/// last played in a newer version since opening it in an older version can corrupt it
+pub fn start_world(id: InstanceID, name: SharedString, level_path: Arc<Path>, target: OsString, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::CheckWorldVersion {
+        id,
+        level_path,
+        channel: send,
+    });
+
+    let backend_handle = backend_handle.clone();
+    window.spawn(cx, async move |cx| {
+        // Failing to check the version shouldn't prevent playing, the game will complain itself if needed
+        let mismatch = match recv.await {
+            Ok(Ok(mismatch)) => mismatch,
+            Ok(Err(error)) => {
+                log::warn!("Unable to check world version: {}", error);
+                None
+            },
+            Err(_) => None,
+        };
+
+        _ = cx.update(move |window, cx| {
+            let quick_play = Some(QuickPlayLaunch::Singleplayer(target));
+            let Some(mismatch) = mismatch else {
+                root::start_instance(id, name, quick_play, &backend_handle, window, cx);
+                return;
+            };
+
+            let message = SharedString::new(rust_i18n::t!(
+                "worlds.older_version_warning",
+                world_version = mismatch.world_version,
+                instance_version = mismatch.instance_version
+            ));
+            let data_version = mismatch.world_data_version.map(|data_version| SharedString::new(rust_i18n::t!("worlds.data_version", data_version = data_version)));
+
+            window.open_dialog(cx, move |dialog, _, cx| {
+                let theme = cx.theme();
+                let play_anyway = Button::new("play-anyway").danger().label(ts!("worlds.play_anyway")).on_click({
+                    let name = name.clone();
+                    let quick_play = quick_play.clone();
+                    let backend_handle = backend_handle.clone();
+                    move |_, window, cx| {
+                        window.close_all_dialogs(cx);
+                        root::start_instance(id, name.clone(), quick_play.clone(), &backend_handle, window, cx);
+                    }
+                });
+                let cancel = Button::new("cancel").label(ts!("common.cancel")).on_click(|_, window, cx| {
+                    window.close_all_dialogs(cx);
+                });
+
+                dialog
+                    .title(ts!("worlds.older_version"))
+                    .child(v_flex()
+                        .gap_2()
+                        .child(message.clone())
+                        .when_some(data_version.clone(), |this, data_version| {
+                            this.child(div().text_sm().text_color(theme.muted_foreground).child(data_version))
+                        })
+                        .child(h_flex().gap_2().child(play_anyway).child(cancel)))
+            });
+        });
+    }).detach();
 }
 
 impl ListDelegate for WorldsListDelegate {
@@ -188,26 +641,35 @@ impl ListDelegate for WorldsListDelegate {
         let name = self.name.clone();
         let backend_handle = self.backend_handle.clone();
         let target = summary.level_path.file_name().unwrap().to_owned();
-        let item = ListItem::new(ix).p_1().child(
-            h_flex()
+        let level_path = summary.level_path.clone();
+        let expanded = self.expanded.as_ref() == Some(&summary.level_path);
+        let expand_icon = if expanded { IconName::ChevronUp } else { IconName::ChevronDown };
+        let row = h_flex()
+            .gap_1()
+            .child(
+                div()
+                    .child(Button::new(ix).success().icon(play_icon).on_click({
+                        let level_path = level_path.clone();
+                        move |_, window, cx| {
+                            cx.stop_propagation();
+                            start_world(id, name.clone(), level_path.clone(), target.clone(), &backend_handle, window, cx);
+                        }
+                    }))
+                    .px_2(),
+            )
+            .child(icon.size_16().min_w_16().min_h_16())
+            .child(description.flex_1())
+            .child(Icon::new(expand_icon).mr_2().text_color(cx.theme().muted_foreground));
+
+        let item = ListItem::new(ix)
+            .p_1()
+            .child(v_flex()
                 .gap_1()
-                .child(
-                    div()
-                        .child(Button::new(ix).success().icon(play_icon).on_click(move |_, window, cx| {
-                            root::start_instance(
-                                id,
-                                name.clone(),
-                                Some(QuickPlayLaunch::Singleplayer(target.clone())),
-                                &backend_handle,
-                                window,
-                                cx,
-                            );
-                        }))
-                        .px_2(),
-                )
-                .child(icon.size_16().min_w_16().min_h_16())
-                .child(description),
-        );
+                .child(row)
+                .when(expanded, |this| this.child(self.render_details(cx))))
+            .on_click(cx.listener(move |list, _: &ClickEvent, _, cx| {
+                list.delegate_mut().toggle_details(level_path.clone(), cx);
+            }));
 
         Some(item)
     }
@@ -224,6 +686,7 @@ impl ListDelegate for WorldsListDelegate {
 
 pub struct ServersListDelegate {
     id: InstanceID,
+    instance: Entity<InstanceEntry>,
     name: SharedString,
     backend_handle: BackendHandle,
     servers: Vec<InstanceServerSummary>,
@@ -252,6 +715,41 @@ impl ListDelegate for ServersListDelegate {
 
         let play_icon = Icon::empty().path("icons/play.svg");
 
+        let remove_button = Button::new(("remove", ix.row)).small().ghost().icon(Icon::default().path("icons/trash-2.svg")).on_click({
+            let edit = ServerEdit::Remove { name: summary.name.clone(), ip: summary.ip.clone() };
+            let instance = self.instance.clone();
+            let backend_handle = self.backend_handle.clone();
+            move |_, window, cx| {
+                send_server_edit(&instance, edit.clone(), &backend_handle, window, cx);
+            }
+        });
+
+        let edit_button = Button::new(("edit", ix.row)).small().ghost().label(ts!("servers.edit_button")).on_click({
+            let instance = self.instance.clone();
+            let summary = summary.clone();
+            let backend_handle = self.backend_handle.clone();
+            move |_, window, cx| {
+                open_server_dialog(instance.clone(), Some(summary.clone()), backend_handle.clone(), window, cx);
+            }
+        });
+
+        // Positions are in the full list, moving is disabled while searching since neighbours aren't visible
+        let position = self.servers.iter().position(|server| server.name == summary.name && server.ip == summary.ip);
+        let searching = self.searched.len() != self.servers.len();
+        let move_button = |id: &'static str, icon: IconName, to: Option<usize>| {
+            let edit = to.map(|index| ServerEdit::Move { name: summary.name.clone(), ip: summary.ip.clone(), index });
+            let instance = self.instance.clone();
+            let backend_handle = self.backend_handle.clone();
+            Button::new((id, ix.row)).xsmall().ghost().icon(icon).disabled(searching || edit.is_none()).on_click(move |_, window, cx| {
+                if let Some(edit) = &edit {
+                    send_server_edit(&instance, edit.clone(), &backend_handle, window, cx);
+                }
+            })
+        };
+        let move_up = move_button("move-up", IconName::ChevronUp, position.and_then(|position| position.checked_sub(1)));
+        let move_down = move_button("move-down", IconName::ChevronDown,
+            position.map(|position| position + 1).filter(|position| *position < self.servers.len()));
+
         let id = self.id;
         let name = self.name.clone();
         let backend_handle = self.backend_handle.clone();
@@ -274,7 +772,10 @@ impl ListDelegate for ServersListDelegate {
                         .px_2(),
                 )
                 .child(icon.size_16().min_w_16().min_h_16())
-                .child(description),
+                .child(description.flex_1())
+                .child(v_flex().child(move_up).child(move_down))
+                .child(edit_button)
+                .child(remove_button.mr_2()),
         );
 
         Some(item)
@@ -7,14 +7,14 @@ use bridge::{
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Icon, IconName, IndexPath, Sizable, WindowExt, breadcrumb::{Breadcrumb, BreadcrumbItem}, button::{Button, ButtonVariants}, h_flex, input::SelectAll, list::{ListDelegate, ListItem, ListState}, notification::{Notification, NotificationType}, switch::Switch, v_flex
+    ActiveTheme as _, Icon, IconName, IndexPath, Sizable, breadcrumb::{Breadcrumb, BreadcrumbItem}, button::{Button, ButtonVariants}, h_flex, input::{InputEvent, InputState, SelectAll}, list::{ListDelegate, ListItem, ListState}, notification::NotificationType, switch::Switch, v_flex
 };
 use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
 use schema::{content::ContentSource, loader::Loader, modrinth::ModrinthProjectType};
 use ustr::Ustr;
 
-use crate::{component::content_list::ContentListDelegate, entity::instance::InstanceEntry, interface_config::InterfaceConfig, png_render_cache, root, ui::PageType};
+use crate::{component::content_list::{self, ContentListDelegate}, entity::{instance::InstanceEntry, notifications::push_notification}, interface_config::InterfaceConfig, png_render_cache, root, ts, ui::PageType};
 
 use super::instance_page::InstanceSubpageType;
 
@@ -26,8 +26,11 @@ pub struct InstanceResourcePacksSubpage {
     backend_handle: BackendHandle,
     resource_packs_state: Arc<AtomicBridgeDataLoadState>,
     resource_pack_list: Entity<ListState<ContentListDelegate>>,
+    search_input: Entity<InputState>,
     load_serial: AtomicOptionSerial,
     _add_from_file_task: Option<Task<()>>,
+    _default_resource_packs_task: Task<()>,
+    _search_input_subscription: Subscription,
 }
 
 impl InstanceResourcePacksSubpage {
@@ -45,19 +48,51 @@ impl InstanceResourcePacksSubpage {
 
         let resource_packs_state = Arc::clone(&instance.resource_packs_state);
 
+        let default_resource_packs: Arc<Mutex<FxHashSet<[u8; 20]>>> = Default::default();
+
         let mut resource_packs_list_delegate = ContentListDelegate::new(instance_id, backend_handle.clone());
         resource_packs_list_delegate.set_content(instance.resource_packs.read(cx));
+        resource_packs_list_delegate.set_default_resource_packs(default_resource_packs.clone());
 
         let resource_packs = instance.resource_packs.clone();
 
-        let resource_pack_list = cx.new(move |cx| {
+        let resource_pack_list = cx.new(|cx| {
             cx.observe(&resource_packs, |list: &mut ListState<ContentListDelegate>, resource_packs, cx| {
                 let actual_resource_packs = resource_packs.read(cx);
                 list.delegate_mut().set_content(actual_resource_packs);
                 cx.notify();
             }).detach();
 
-            ListState::new(resource_packs_list_delegate, window, cx).selectable(false).searchable(true)
+            ListState::new(resource_packs_list_delegate, window, cx).selectable(false)
+        });
+
+        // Re-render when the topmost section changes so the sticky header stays in sync
+        cx.observe(&resource_pack_list, |_, _, cx| cx.notify()).detach();
+
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("common.search")).clean_on_escape());
+        let _search_input_subscription = cx.subscribe(&search_input, |page: &mut Self, input, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                let query = input.read(cx).value();
+                page.resource_pack_list.update(cx, |list, cx| {
+                    list.delegate_mut().actual_perform_search(&query);
+                    cx.notify();
+                });
+            }
+        });
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        backend_handle.send(MessageToBackend::GetBackendConfiguration { channel: send });
+        let default_resource_packs_task = cx.spawn(async move |_, _| {
+            let Ok(config) = recv.await else {
+                return;
+            };
+            let mut default_resource_packs = default_resource_packs.lock();
+            for pack in config.default_resource_packs {
+                let mut hash = [0u8; 20];
+                if hex::decode_to_slice(&pack.sha1, &mut hash).is_ok() {
+                    default_resource_packs.insert(hash);
+                }
+            }
         });
 
         Self {
@@ -68,10 +103,18 @@ impl InstanceResourcePacksSubpage {
             backend_handle,
             resource_packs_state,
             resource_pack_list,
+            search_input,
             load_serial: AtomicOptionSerial::default(),
             _add_from_file_task: None,
+            _default_resource_packs_task: default_resource_packs_task,
+            _search_input_subscription,
         }
     }
+
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        let focus_handle = self.search_input.read(cx).focus_handle(cx);
+        focus_handle.focus(window, cx);
+    }
 }
 
 impl Render for InstanceResourcePacksSubpage {
@@ -87,15 +130,22 @@ impl Render for InstanceResourcePacksSubpage {
             .gap_3()
             .mb_1()
             .ml_1()
-            .child(div().text_lg().child("Resource Packs"))
-            .child(Button::new("update").label("Check for updates").success().compact().small().on_click({
+            .child(div().text_lg().child(ts!("instance.resource_packs")))
+            .child(Button::new("update").label(ts!("instance.check_for_updates")).success().compact().small().on_click({
                 let backend_handle = self.backend_handle.clone();
                 let instance_id = self.instance;
                 move |_, window, cx| {
                     crate::root::start_update_check(instance_id, &backend_handle, window, cx);
                 }
             }))
-            .child(Button::new("addmr").label("Add from Modrinth").success().compact().small().on_click({
+            .child(Button::new("repair").label(ts!("instance.repair_content")).compact().small().tooltip(ts!("instance.repair_content_tooltip")).on_click({
+                let backend_handle = self.backend_handle.clone();
+                let instance_id = self.instance;
+                move |_, window, cx| {
+                    crate::root::start_content_repair(instance_id, &backend_handle, window, cx);
+                }
+            }))
+            .child(Button::new("addmr").label(ts!("instance.add_from_modrinth")).success().compact().small().on_click({
                 let instance = self.instance;
                 move |_, window, cx| {
                     let page = crate::ui::PageType::Modrinth {
@@ -106,7 +156,7 @@ impl Render for InstanceResourcePacksSubpage {
                     root::switch_page(page, path, window, cx);
                 }
             }))
-            .child(Button::new("addfile").label("Add from file").success().compact().small().on_click({
+            .child(Button::new("addfile").label(ts!("instance.add_from_file")).success().compact().small().on_click({
                 let backend_handle = self.backend_handle.clone();
                 let instance = self.instance;
                 cx.listener(move |this, _, window, cx| {
@@ -114,7 +164,7 @@ impl Render for InstanceResourcePacksSubpage {
                         files: true,
                         directories: false,
                         multiple: true,
-                        prompt: Some("Select resource packs to install".into())
+                        prompt: Some(ts!("instance.select_resource_packs"))
                     });
 
                     let backend_handle = backend_handle.clone();
@@ -143,12 +193,7 @@ impl Render for InstanceResourcePacksSubpage {
                                 },
                                 Ok(None) => {},
                                 Err(error) => {
-                                    let error = format!("{}", error);
-                                    let notification = Notification::new()
-                                        .autohide(false)
-                                        .with_type(NotificationType::Error)
-                                        .title(error);
-                                    window.push_notification(notification, cx);
+                                    push_notification(NotificationType::Error, format!("{}", error), window, cx);
                                 },
                             }
                         });
@@ -164,7 +209,7 @@ impl Render for InstanceResourcePacksSubpage {
                 .border_1()
                 .rounded(theme.radius)
                 .border_color(theme.border)
-                .child(self.resource_pack_list.clone())
+                .child(content_list::render_with_search(&self.resource_pack_list, &self.search_input, cx))
                 .on_click({
                     let resource_pack_list = self.resource_pack_list.clone();
                     move |_, _, cx| {
@@ -1,15 +1,26 @@
 use std::{borrow::Cow, cmp::Ordering, path::Path, sync::Arc};
 
 use bridge::{
-    handle::BackendHandle, instance::InstanceID, message::MessageToBackend, meta::MetadataRequest
+    handle::BackendHandle, instance::{InstanceID, InstanceStatus}, message::{DetectedGpus, MessageToBackend}, meta::MetadataRequest, modal_action::ModalAction
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Disableable, Selectable, Sizable, WindowExt, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent}, notification::{Notification, NotificationType}, select::{SearchableVec, Select, SelectEvent, SelectState}, spinner::Spinner, v_flex
+    ActiveTheme as _, Disableable, Selectable, Sizable, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent}, notification::NotificationType, select::{SearchableVec, Select, SelectEvent, SelectState}, spinner::Spinner, v_flex
 };
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, instance::{InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration}, loader::Loader, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, instance::{InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration, InstanceWindowConfiguration}, loader::Loader, shared_profile::SharedProfile, version_manifest::MinecraftVersionManifest};
 
-use crate::{entity::{DataEntities, instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState, TypelessFrontendMetadataResult}}, interface_config::InterfaceConfig, pages::instances_page::VersionList};
+use crate::{entity::{DataEntities, instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState, TypelessFrontendMetadataResult}, notifications::push_notification}, interface_config::InterfaceConfig, pages::instances_page::VersionList, ts};
+
+/// The JVM arguments of a prepared launch, preparing it may download the version's files first
+enum JvmArgumentsPreview {
+    Loading(ModalAction),
+    Loaded(Result<Arc<[Arc<str>]>, Arc<str>>),
+}
+
+/// Shown as the first loader version, selecting it follows the newest version
+fn latest_loader_version() -> &'static str {
+    ustr::ustr(&rust_i18n::t!("instance_settings.latest_loader_version")).as_str()
+}
 
 #[derive(PartialEq, Eq)]
 enum NewNameChangeState {
@@ -35,10 +46,19 @@ pub struct InstanceSettingsSubpage {
     jvm_flags_input_state: Entity<InputState>,
     jvm_binary_enabled: bool,
     jvm_binary_path: Option<Arc<Path>>,
+    window_enabled: bool,
+    window_title_input_state: Entity<InputState>,
+    window_icon_path: Option<Arc<Path>>,
+    discrete_gpu_enabled: bool,
+    /// Loaded once when the page is opened, only on Linux
+    gpus: Option<DetectedGpus>,
+    jvm_arguments_preview: Option<JvmArgumentsPreview>,
     new_name_change_state: NewNameChangeState,
     backend_handle: BackendHandle,
     _observe_loader_version_subscription: Option<Subscription>,
     _select_file_task: Task<()>,
+    _gpus_task: Task<()>,
+    _jvm_arguments_task: Task<()>,
 }
 
 impl InstanceSettingsSubpage {
@@ -52,11 +72,14 @@ impl InstanceSettingsSubpage {
         let entry = instance.read(cx);
         let instance_id = entry.id;
         let loader = entry.configuration.loader;
-        let preferred_loader_version = entry.configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or("Latest");
+        let preferred_loader_version = entry.configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or(latest_loader_version());
 
         let memory = entry.configuration.memory.unwrap_or_default();
         let jvm_flags = entry.configuration.jvm_flags.clone().unwrap_or_default();
         let jvm_binary = entry.configuration.jvm_binary.clone().unwrap_or_default();
+        let window_configuration = entry.configuration.window.clone().unwrap_or_default();
+        let instance_name = entry.name.clone();
+        let discrete_gpu_enabled = entry.configuration.discrete_gpu;
 
         let new_name_input_state = cx.new(|cx| InputState::new(window, cx));
         cx.subscribe(&new_name_input_state, Self::on_new_name_input).detach();
@@ -67,11 +90,19 @@ impl InstanceSettingsSubpage {
         cx.observe_in(&minecraft_versions, window, |page, versions, window, cx| {
             page.update_minecraft_versions(versions, window, cx);
         }).detach();
-        cx.subscribe(&version_select_state, Self::on_minecraft_version_selected).detach();
+        cx.subscribe_in(&version_select_state, window, Self::on_minecraft_version_selected).detach();
 
         cx.observe_in(instance, window, |page, instance, window, cx| {
+            // The version is only changed once confirmed, so the selection follows the configuration
+            let minecraft_version = SharedString::new_static(instance.read(cx).configuration.minecraft_version.as_str());
+            page.version_select_state.update(cx, |select_state, cx| {
+                if select_state.selected_value() != Some(&minecraft_version) {
+                    select_state.set_selected_value(&minecraft_version, window, cx);
+                }
+            });
+
             if page.loader_version_select_state.read(cx).selected_index(cx).is_none() {
-                let version = instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or("Latest");
+                let version = instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or(latest_loader_version());
                 page.loader_version_select_state.update(cx, |select_state, cx| {
                     select_state.set_selected_value(&version, window, cx);
                 });
@@ -101,6 +132,27 @@ impl InstanceSettingsSubpage {
         });
         cx.subscribe(&jvm_flags_input_state, Self::on_jvm_flags_changed).detach();
 
+        let window_title_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(instance_name).default_value(window_configuration.title.to_string())
+        });
+        cx.subscribe(&window_title_input_state, Self::on_window_title_changed).detach();
+
+        let gpus_task = if cfg!(target_os = "linux") {
+            let (send, recv) = tokio::sync::oneshot::channel();
+            backend_handle.send(MessageToBackend::GetGpus { channel: send });
+            cx.spawn(async move |page, cx| {
+                let Ok(gpus) = recv.await else {
+                    return;
+                };
+                let _ = page.update(cx, |page, cx| {
+                    page.gpus = Some(gpus);
+                    cx.notify();
+                });
+            })
+        } else {
+            Task::ready(())
+        };
+
         let mut page = Self {
             data: data.clone(),
             instance: instance.clone(),
@@ -117,11 +169,19 @@ impl InstanceSettingsSubpage {
             jvm_flags_input_state,
             jvm_binary_enabled: jvm_binary.enabled,
             jvm_binary_path: jvm_binary.path.clone(),
+            window_enabled: window_configuration.enabled,
+            window_title_input_state,
+            window_icon_path: window_configuration.icon.clone(),
+            discrete_gpu_enabled,
+            gpus: None,
+            jvm_arguments_preview: None,
             new_name_change_state: NewNameChangeState::NoChange,
             backend_handle,
             loader_versions_state: TypelessFrontendMetadataResult::Loading,
             _observe_loader_version_subscription: None,
-            _select_file_task: Task::ready(())
+            _select_file_task: Task::ready(()),
+            _gpus_task: gpus_task,
+            _jvm_arguments_task: Task::ready(()),
         };
         page.update_minecraft_versions(minecraft_versions, window, cx);
         page.update_loader_versions(window, cx);
@@ -189,27 +249,27 @@ impl InstanceSettingsSubpage {
             },
             Loader::Fabric => {
                 self.update_loader_versions_for_loader(MetadataRequest::FabricLoaderManifest, |manifest: &FabricLoaderManifest| {
-                    std::iter::once("Latest")
+                    std::iter::once(latest_loader_version())
                         .chain(manifest.0.iter().map(|s| s.version.as_str()))
                         .collect()
                 }, window, cx)
             },
             Loader::Forge => {
                 self.update_loader_versions_for_loader(MetadataRequest::ForgeMavenManifest, |manifest: &ForgeMavenManifest| {
-                    std::iter::once("Latest")
+                    std::iter::once(latest_loader_version())
                         .chain(manifest.0.iter().map(|s| s.as_str()))
                         .collect()
                 }, window, cx)
             },
             Loader::NeoForge => {
                 self.update_loader_versions_for_loader(MetadataRequest::NeoforgeMavenManifest, |manifest: &NeoforgeMavenManifest| {
-                    std::iter::once("Latest")
+                    std::iter::once(latest_loader_version())
                         .chain(manifest.0.iter().map(|s| s.as_str()))
                         .collect()
                 }, window, cx)
             },
         };
-        let preferred_loader_version = self.instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or("Latest");
+        let preferred_loader_version = self.instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or(latest_loader_version());
         self.loader_version_select_state.update(cx, move |select_state, cx| {
             select_state.set_items(SearchableVec::new(loader_versions), window, cx);
             select_state.set_selected_value(&preferred_loader_version, window, cx);
@@ -243,7 +303,7 @@ impl InstanceSettingsSubpage {
                 vec![]
             };
             page.loader_versions_state = result.as_typeless();
-            let preferred_loader_version = page.instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or("Latest");
+            let preferred_loader_version = page.instance.read(cx).configuration.preferred_loader_version.map(|s| s.as_str()).unwrap_or(latest_loader_version());
             page.loader_version_select_state.update(cx, move |select_state, cx| {
                 select_state.set_items(SearchableVec::new(versions), window, cx);
                 select_state.set_selected_value(&preferred_loader_version, window, cx);
@@ -282,9 +342,10 @@ impl InstanceSettingsSubpage {
 
     pub fn on_minecraft_version_selected(
         &mut self,
-        _state: Entity<SelectState<VersionList>>,
+        state: &Entity<SelectState<VersionList>>,
         event: &SelectEvent<VersionList>,
-        _cx: &mut Context<Self>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         let SelectEvent::Confirm(value) = event;
 
@@ -292,10 +353,24 @@ impl InstanceSettingsSubpage {
             return;
         };
 
-        self.backend_handle.send(MessageToBackend::SetInstanceMinecraftVersion {
-            id: self.instance_id,
-            version: value.as_str().into(),
+        let current_version = self.instance.read(cx).configuration.minecraft_version;
+        if value.as_str() == current_version.as_str() {
+            return;
+        }
+
+        // Show the current version until the change is confirmed
+        state.update(cx, |select_state, cx| {
+            select_state.set_selected_value(&SharedString::new_static(current_version.as_str()), window, cx);
         });
+
+        crate::modals::change_version::open_change_version(
+            self.instance_id,
+            current_version,
+            value.as_str().into(),
+            self.backend_handle.clone(),
+            window,
+            cx,
+        );
     }
 
     pub fn on_loader_version_selected(
@@ -306,7 +381,7 @@ impl InstanceSettingsSubpage {
     ) {
         let SelectEvent::Confirm(value) = event;
 
-        let value = if value == &Some("Latest") {
+        let value = if value == &Some(latest_loader_version()) {
             None
         } else {
             value.clone()
@@ -358,6 +433,7 @@ impl InstanceSettingsSubpage {
                 id: self.instance_id,
                 memory: self.get_memory_configuration(cx)
             });
+            cx.notify();
         }
     }
 
@@ -383,6 +459,7 @@ impl InstanceSettingsSubpage {
                 id: self.instance_id,
                 jvm_flags: self.get_jvm_flags_configuration(cx)
             });
+            cx.notify();
         }
     }
 
@@ -401,6 +478,180 @@ impl InstanceSettingsSubpage {
             path: self.jvm_binary_path.clone(),
         }
     }
+
+    fn render_gpu(&self, cx: &Context<Self>) -> impl IntoElement {
+        let note: SharedString = match &self.gpus {
+            None => ts!("instance.detecting_gpus"),
+            Some(gpus) => match (gpus.offload_gpu(), gpus.default_gpu()) {
+                (Some(offload), _) if self.discrete_gpu_enabled => SharedString::new(rust_i18n::t!("instance.runs_on_gpu", gpu = offload.name)),
+                (_, Some(default)) if gpus.gpus.len() == 1 => SharedString::new(rust_i18n::t!("instance.runs_on_only_gpu", gpu = default.name)),
+                (_, Some(default)) => SharedString::new(rust_i18n::t!("instance.runs_on_gpu", gpu = default.name)),
+                (_, None) => ts!("instance.no_gpus"),
+            },
+        };
+
+        v_flex()
+            .gap_1()
+            .child(Checkbox::new("discrete_gpu").label(ts!("instance.discrete_gpu")).checked(self.discrete_gpu_enabled).on_click(cx.listener(|page, value, _, cx| {
+                if page.discrete_gpu_enabled != *value {
+                    page.discrete_gpu_enabled = *value;
+                    page.backend_handle.send(MessageToBackend::SetInstanceDiscreteGpu {
+                        id: page.instance_id,
+                        value: *value,
+                    });
+                    cx.notify();
+                }
+            })))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground).child(note))
+    }
+
+    /// Prepares a launch without starting the game to get the JVM arguments it would use, including those
+    /// of the version and loader
+    fn request_jvm_arguments(&mut self, cx: &mut Context<Self>) {
+        let modal_action = ModalAction::default();
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::PreviewLaunchCommand {
+            id: self.instance_id,
+            modal_action: modal_action.clone(),
+            channel: send,
+        });
+        self.jvm_arguments_preview = Some(JvmArgumentsPreview::Loading(modal_action));
+
+        self._jvm_arguments_task = cx.spawn(async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+            let _ = page.update(cx, |page, cx| {
+                page.jvm_arguments_preview = Some(JvmArgumentsPreview::Loaded(result.map(|preview| preview.jvm_arguments)));
+                cx.notify();
+            });
+        });
+    }
+
+    /// Shows which runtime settings will actually be used at launch, and where they come from
+    fn render_effective_runtime(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        // The inputs are sent to the backend as they change, so use them instead of the instance's configuration
+        let mut configuration = self.instance.read(cx).configuration.clone();
+        configuration.memory = Some(self.get_memory_configuration(cx));
+        configuration.jvm_flags = Some(self.get_jvm_flags_configuration(cx));
+
+        let memory = match configuration.effective_memory() {
+            Some((min, max)) => {
+                let requested_max = self.memory_max_input_state.read(cx).value().parse::<u32>().unwrap_or(0);
+                if requested_max == max {
+                    rust_i18n::t!("instance_settings.memory_override", min = min, max = max).into_owned()
+                } else {
+                    rust_i18n::t!("instance_settings.memory_override_raised", min = min, max = max).into_owned()
+                }
+            },
+            None => rust_i18n::t!("instance_settings.memory_default").into_owned(),
+        };
+
+        let jvm_flags = match &configuration.jvm_flags {
+            Some(jvm_flags) if jvm_flags.enabled && !jvm_flags.flags.trim_ascii().is_empty() => {
+                if jvm_flags.has_valid_quoting() {
+                    rust_i18n::t!("instance_settings.jvm_flags_instance").into_owned()
+                } else {
+                    rust_i18n::t!("instance_settings.jvm_flags_split").into_owned()
+                }
+            },
+            _ => rust_i18n::t!("instance_settings.jvm_flags_default").into_owned(),
+        };
+
+        let java = match &self.jvm_binary_path {
+            Some(path) if self.jvm_binary_enabled => rust_i18n::t!("instance_settings.java_override", path = path.display()).into_owned(),
+            _ => rust_i18n::t!("instance_settings.java_default").into_owned(),
+        };
+
+        let row = |label: SharedString, value: String| {
+            v_flex()
+                .child(div().text_sm().text_color(theme.muted_foreground).child(label))
+                .child(div().text_sm().child(value))
+        };
+
+        let show_jvm_arguments = self.jvm_arguments_preview.is_some();
+
+        let mut content = v_flex()
+            .gap_2()
+            .child(div().child(ts!("instance_settings.effective_at_launch")))
+            .child(row(ts!("instance_settings.memory"), memory))
+            .child(row(ts!("instance_settings.jvm_flags"), jvm_flags))
+            .child(row(ts!("instance_settings.java"), java))
+            .child(h_flex()
+                .gap_2()
+                .child(Button::new("toggle_jvm_arguments")
+                    .small()
+                    .label(if show_jvm_arguments { ts!("instance_settings.hide_jvm_arguments") } else { ts!("instance_settings.show_jvm_arguments") })
+                    .on_click(cx.listener(|page, _, _, cx| {
+                        if page.jvm_arguments_preview.is_some() {
+                            page.jvm_arguments_preview = None;
+                            page._jvm_arguments_task = Task::ready(());
+                        } else {
+                            page.request_jvm_arguments(cx);
+                        }
+                        cx.notify();
+                    })))
+                .when(matches!(self.jvm_arguments_preview, Some(JvmArgumentsPreview::Loaded(_))), |this| {
+                    this.child(Button::new("refresh_jvm_arguments")
+                        .small()
+                        .label(ts!("storage.refresh"))
+                        .on_click(cx.listener(|page, _, _, cx| {
+                            page.request_jvm_arguments(cx);
+                            cx.notify();
+                        })))
+                }));
+
+        match &self.jvm_arguments_preview {
+            None => {},
+            Some(JvmArgumentsPreview::Loading(modal_action)) => {
+                // Downloads happen while preparing, show what's currently being done
+                let status = modal_action.trackers.trackers.read().unwrap().iter()
+                    .rev()
+                    .find(|tracker| tracker.get_finished_at().is_none())
+                    .map(|tracker| SharedString::from(tracker.get_title()))
+                    .unwrap_or(ts!("instance_settings.preparing_launch"));
+                content = content.child(h_flex().gap_2().child(Spinner::new()).child(div().text_sm().child(status)));
+            },
+            Some(JvmArgumentsPreview::Loaded(Err(error))) => {
+                content = content.child(div().text_sm().text_color(theme.danger).child(SharedString::new(rust_i18n::t!("instance_settings.prepare_launch_failed", error = error))));
+            },
+            Some(JvmArgumentsPreview::Loaded(Ok(arguments))) => {
+                content = content.child(v_flex()
+                    .p_2()
+                    .rounded(theme.radius)
+                    .bg(theme.secondary)
+                    .font_family("Roboto Mono")
+                    .text_xs()
+                    .children(arguments.iter().map(|argument| div().child(SharedString::new(argument.clone())))));
+            },
+        }
+
+        content
+    }
+
+    pub fn on_window_title_changed(
+        &mut self,
+        _: Entity<InputState>,
+        event: &InputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = event {
+            self.backend_handle.send(MessageToBackend::SetInstanceWindow {
+                id: self.instance_id,
+                window: self.get_window_configuration(cx)
+            });
+        }
+    }
+
+    fn get_window_configuration(&self, cx: &App) -> InstanceWindowConfiguration {
+        let title = self.window_title_input_state.read(cx).value();
+
+        InstanceWindowConfiguration {
+            enabled: self.window_enabled,
+            title: title.into(),
+            icon: self.window_icon_path.clone(),
+        }
+    }
 }
 
 impl Render for InstanceSettingsSubpage {
@@ -411,31 +662,41 @@ impl Render for InstanceSettingsSubpage {
             .gap_3()
             .mb_1()
             .ml_1()
-            .child(div().text_lg().child("Settings"));
+            .child(div().text_lg().child(ts!("instance.settings")));
 
         let memory_override_enabled = self.memory_override_enabled;
         let jvm_flags_enabled = self.jvm_flags_enabled;
         let jvm_binary_enabled = self.jvm_binary_enabled;
+        let window_enabled = self.window_enabled;
 
         let jvm_binary_label = if let Some(path) = &self.jvm_binary_path {
             SharedString::new(path.to_string_lossy())
         } else {
-            SharedString::new_static("<unset>")
+            ts!("instance_settings.unset")
+        };
+
+        let window_icon_label = if let Some(path) = &self.window_icon_path {
+            SharedString::new(path.to_string_lossy())
+        } else {
+            ts!("instance_settings.default_icon")
         };
 
+        // The backend refuses to rename or delete a running instance, since the game holds files open inside it
+        let running = self.instance.read(cx).status != InstanceStatus::NotRunning;
+
         let mut basic_content = v_flex()
             .gap_4()
             .size_full()
             .child(v_flex()
-                .child("Instance name")
+                .child(ts!("common.instance_name"))
                 .child(h_flex()
                     .gap_2()
-                    .child(Input::new(&self.new_name_input_state))
+                    .child(Input::new(&self.new_name_input_state).disabled(running))
                     .when(self.new_name_change_state != NewNameChangeState::NoChange, |this| {
                         if self.new_name_change_state == NewNameChangeState::InvalidName {
-                            this.child("Invalid name")
+                            this.child(ts!("instance_settings.invalid_name"))
                         } else {
-                            this.child(Button::new("setname").label("Update").on_click({
+                            this.child(Button::new("setname").label(ts!("instance_settings.rename")).disabled(running).on_click({
                                 let instance = self.instance.clone();
                                 let backend_handle = self.backend_handle.clone();
                                 let new_name = self.new_name_input_state.read(cx).value();
@@ -456,18 +717,18 @@ impl Render for InstanceSettingsSubpage {
         match self.version_state {
             TypelessFrontendMetadataResult::Loading => {
                 basic_content = basic_content.child(crate::labelled(
-                    "Version",
+                    ts!("instances.version"),
                     Spinner::new()
                 ))
             },
             TypelessFrontendMetadataResult::Loaded => {
                 basic_content = basic_content.child(crate::labelled(
-                    "Version",
+                    ts!("instances.version"),
                     Select::new(&self.version_select_state).w_full()
                 ))
             },
             TypelessFrontendMetadataResult::Error(ref error) => {
-                basic_content = basic_content.child(format!("Error loading minecraft versions: {}", error))
+                basic_content = basic_content.child(SharedString::new(rust_i18n::t!("instance_settings.versions_failed", error = error)))
             },
         }
 
@@ -476,22 +737,22 @@ impl Render for InstanceSettingsSubpage {
                 .outline()
                 .child(
                     Button::new("loader-vanilla")
-                        .label("Vanilla")
+                        .label(Loader::Vanilla.name())
                         .selected(self.loader == Loader::Vanilla),
                 )
                 .child(
                     Button::new("loader-fabric")
-                        .label("Fabric")
+                        .label(Loader::Fabric.name())
                         .selected(self.loader == Loader::Fabric),
                 )
                 .child(
                     Button::new("loader-forge")
-                        .label("Forge")
+                        .label(Loader::Forge.name())
                         .selected(self.loader == Loader::Forge),
                 )
                 .child(
                     Button::new("loader-neoforge")
-                        .label("NeoForge")
+                        .label(Loader::NeoForge.name())
                         .selected(self.loader == Loader::NeoForge),
                 )
                 .on_click(cx.listener({
@@ -521,28 +782,49 @@ impl Render for InstanceSettingsSubpage {
             match self.loader_versions_state {
                 TypelessFrontendMetadataResult::Loading => {
                     basic_content = basic_content.child(crate::labelled(
-                        "Loader Version",
+                        ts!("instance_settings.loader_version"),
                         Spinner::new()
                     ))
                 },
                 TypelessFrontendMetadataResult::Loaded => {
                     basic_content = basic_content.child(crate::labelled(
-                        "Loader Version",
+                        ts!("instance_settings.loader_version"),
                         Select::new(&self.loader_version_select_state).w_full()
                     ))
                 },
                 TypelessFrontendMetadataResult::Error(ref error) => {
-                    basic_content = basic_content.child(format!("Error loading possible loader versions: {}", error))
+                    basic_content = basic_content.child(SharedString::new(rust_i18n::t!("instance_settings.loader_versions_failed", error = error)))
                 },
             }
         }
 
+        let instance = self.instance.read(cx);
+        let file_watching_enabled = instance.configuration.file_watching.unwrap_or(!instance.network_drive);
+        let file_watching_note = if file_watching_enabled && !instance.file_watching {
+            Some(ts!("instance.file_watching_disabled_globally"))
+        } else if instance.network_drive && instance.configuration.file_watching.is_none() {
+            Some(ts!("instance.file_watching_network_drive"))
+        } else if !file_watching_enabled {
+            Some(ts!("instance.file_watching_polled"))
+        } else {
+            None
+        };
+        basic_content = basic_content.child(v_flex()
+            .gap_1()
+            .child(Checkbox::new("file_watching").label(ts!("instance.file_watching")).checked(file_watching_enabled).on_click(cx.listener(|page, value, _, _| {
+                page.backend_handle.send(MessageToBackend::SetInstanceFileWatching {
+                    id: page.instance_id,
+                    value: *value,
+                });
+            })))
+            .children(file_watching_note.map(|note| div().text_sm().text_color(theme.muted_foreground).child(note))));
+
         let runtime_content = v_flex()
             .gap_4()
             .size_full()
             .child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("memory").label("Set Memory").checked(memory_override_enabled).on_click(cx.listener(|page, value, _, cx| {
+                .child(Checkbox::new("memory").label(ts!("instance_settings.set_memory")).checked(memory_override_enabled).on_click(cx.listener(|page, value, _, cx| {
                     if page.memory_override_enabled != *value {
                         page.memory_override_enabled = *value;
                         page.backend_handle.send(MessageToBackend::SetInstanceMemory {
@@ -555,15 +837,15 @@ impl Render for InstanceSettingsSubpage {
                 .child(h_flex()
                     .gap_1()
                     .child(NumberInput::new(&self.memory_min_input_state).small().suffix("MiB").disabled(!memory_override_enabled))
-                    .child("Min"))
+                    .child(ts!("instance_settings.min")))
                 .child(h_flex()
                     .gap_1()
                     .child(NumberInput::new(&self.memory_max_input_state).small().suffix("MiB").disabled(!memory_override_enabled))
-                    .child("Max"))
+                    .child(ts!("instance_settings.max")))
                 )
             .child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("jvm_flags").label("Add JVM Flags").checked(jvm_flags_enabled).on_click(cx.listener(|page, value, _, cx| {
+                .child(Checkbox::new("jvm_flags").label(ts!("instance_settings.add_jvm_flags")).checked(jvm_flags_enabled).on_click(cx.listener(|page, value, _, cx| {
                     if page.jvm_flags_enabled != *value {
                         page.jvm_flags_enabled = *value;
                         page.backend_handle.send(MessageToBackend::SetInstanceJvmFlags {
@@ -577,7 +859,7 @@ impl Render for InstanceSettingsSubpage {
             )
             .child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("jvm_binary").label("Override JVM Binary").checked(jvm_binary_enabled).on_click(cx.listener(|page, value, _, cx| {
+                .child(Checkbox::new("jvm_binary").label(ts!("instance_settings.override_jvm_binary")).checked(jvm_binary_enabled).on_click(cx.listener(|page, value, _, cx| {
                     if page.jvm_binary_enabled != *value {
                         page.jvm_binary_enabled = *value;
                         page.backend_handle.send(MessageToBackend::SetInstanceJvmBinary {
@@ -588,11 +870,13 @@ impl Render for InstanceSettingsSubpage {
                     }
                 })))
                 .child(Button::new("select_jvm_binary").success().label(jvm_binary_label).disabled(!jvm_binary_enabled).on_click(cx.listener(|this, _, window, cx| {
+                    // The file chooser only shares the picked file with the sandbox, Java needs the rest of its installation too
+                    let sandboxed = bridge::sandbox::is_flatpak();
                     let receiver = cx.prompt_for_paths(PathPromptOptions {
-                        files: true,
-                        directories: false,
+                        files: !sandboxed,
+                        directories: sandboxed,
                         multiple: false,
-                        prompt: Some("Select JVM binary".into())
+                        prompt: Some(if sandboxed { ts!("instance.select_java_folder") } else { ts!("instance.select_jvm_binary") })
                     });
 
                     let this_entity = cx.entity();
@@ -612,24 +896,76 @@ impl Render for InstanceSettingsSubpage {
                                 },
                                 Ok(None) => {},
                                 Err(error) => {
-                                    let error = format!("{}", error);
-                                    let notification = Notification::new()
-                                        .autohide(false)
-                                        .with_type(NotificationType::Error)
-                                        .title(error);
-                                    window.push_notification(notification, cx);
+                                    push_notification(NotificationType::Error, format!("{}", error), window, cx);
                                 },
                             }
                         });
                     });
                     this._select_file_task = add_from_file_task;
                 })))
-            );
+                .when(bridge::sandbox::is_flatpak(), |this| {
+                    this.child(div().text_sm().text_color(theme.muted_foreground)
+                        .child(ts!("instance.flatpak_java")))
+                })
+            )
+            .child(v_flex()
+                .gap_1()
+                .child(Checkbox::new("window").label(ts!("instance_settings.customize_window")).checked(window_enabled).on_click(cx.listener(|page, value, _, cx| {
+                    if page.window_enabled != *value {
+                        page.window_enabled = *value;
+                        page.backend_handle.send(MessageToBackend::SetInstanceWindow {
+                            id: page.instance_id,
+                            window: page.get_window_configuration(cx)
+                        });
+                        cx.notify();
+                    }
+                })))
+                .child(Input::new(&self.window_title_input_state).disabled(!window_enabled))
+                .child(h_flex()
+                    .gap_1()
+                    .child(Button::new("select_window_icon").success().label(window_icon_label).disabled(!window_enabled).on_click(cx.listener(|this, _, window, cx| {
+                        let receiver = cx.prompt_for_paths(PathPromptOptions {
+                            files: true,
+                            directories: false,
+                            multiple: false,
+                            prompt: Some(ts!("instance_settings.select_window_icon"))
+                        });
+
+                        let this_entity = cx.entity();
+                        let select_file_task = window.spawn(cx, async move |cx| {
+                            let Ok(Ok(Some(paths))) = receiver.await else {
+                                return;
+                            };
+                            _ = cx.update_window_entity(&this_entity, move |this, _, cx| {
+                                this.window_icon_path = paths.first().map(|v| v.as_path().into());
+                                this.backend_handle.send(MessageToBackend::SetInstanceWindow {
+                                    id: this.instance_id,
+                                    window: this.get_window_configuration(cx)
+                                });
+                                cx.notify();
+                            });
+                        });
+                        this._select_file_task = select_file_task;
+                    })))
+                    .when(self.window_icon_path.is_some(), |this| {
+                        this.child(Button::new("clear_window_icon").label(ts!("settings.reset")).disabled(!window_enabled).on_click(cx.listener(|page, _, _, cx| {
+                            page.window_icon_path = None;
+                            page.backend_handle.send(MessageToBackend::SetInstanceWindow {
+                                id: page.instance_id,
+                                window: page.get_window_configuration(cx)
+                            });
+                            cx.notify();
+                        })))
+                    }))
+                .child(div().text_sm().text_color(theme.muted_foreground).child(ts!("instance_settings.window_lwjgl2_only")))
+            )
+            .when(cfg!(target_os = "linux"), |this| this.child(self.render_gpu(cx)))
+            .child(self.render_effective_runtime(cx));
 
         let actions_content = v_flex()
             .gap_4()
             .size_full()
-            .child(Button::new("shortcut").label("Create shortcut").success().on_click({
+            .child(Button::new("shortcut").label(ts!("instance_settings.create_shortcut")).success().on_click({
                 let instance = self.instance.clone();
                 let backend_handle = self.backend_handle.clone();
                 move |_: &ClickEvent, _, cx| {
@@ -657,7 +993,57 @@ impl Render for InstanceSettingsSubpage {
                     }).detach();
                 }
             }))
-            .child(Button::new("delete").label("Delete this instance").danger().on_click({
+            .child(Button::new("export_multimc").label(ts!("instance_settings.export_multimc")).info().on_click(cx.listener(|page, _: &ClickEvent, window, cx| {
+                let user_dirs = directories::UserDirs::new();
+                let directory = user_dirs.as_ref()
+                    .and_then(directories::UserDirs::download_dir).unwrap_or(Path::new("."));
+                let suggested_name = format!("{}.zip", page.instance.read(cx).name);
+
+                let receiver = cx.prompt_for_new_path(directory, Some(&suggested_name));
+                let id = page.instance_id;
+                let backend_handle = page.backend_handle.clone();
+                page._select_file_task = window.spawn(cx, async move |cx| {
+                    let Ok(Ok(Some(path))) = receiver.await else {
+                        return;
+                    };
+                    _ = cx.update(|window, cx| {
+                        crate::root::start_export_multimc(id, path, &backend_handle, window, cx);
+                    });
+                });
+            })))
+            .child(Button::new("share_profile").label(ts!("shared_profile.share")).info().tooltip(ts!("shared_profile.share_tooltip")).on_click(cx.listener(|page, _: &ClickEvent, window, cx| {
+                let user_dirs = directories::UserDirs::new();
+                let directory = user_dirs.as_ref()
+                    .and_then(directories::UserDirs::download_dir).unwrap_or(Path::new("."));
+                let suggested_name = format!("{}{}", page.instance.read(cx).name, SharedProfile::FILE_SUFFIX);
+
+                let receiver = cx.prompt_for_new_path(directory, Some(&suggested_name));
+                let id = page.instance_id;
+                let backend_handle = page.backend_handle.clone();
+                page._select_file_task = window.spawn(cx, async move |cx| {
+                    let Ok(Ok(Some(path))) = receiver.await else {
+                        return;
+                    };
+                    _ = cx.update(|window, cx| {
+                        crate::root::start_export_shared_profile(id, path, &backend_handle, window, cx);
+                    });
+                });
+            })))
+            .child(Button::new("preview_launch").label(ts!("launch_command.open")).info().on_click({
+                let instance_id = self.instance_id;
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    crate::modals::launch_command::open_launch_command_preview(instance_id, &backend_handle, window, cx);
+                }
+            }))
+            .child(Button::new("verify").label(ts!("instance.verify")).info().disabled(running).on_click({
+                let instance_id = self.instance_id;
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    crate::root::start_verify_instance(instance_id, &backend_handle, window, cx);
+                }
+            }))
+            .child(Button::new("delete").label(ts!("instance_settings.delete")).danger().disabled(running).on_click({
                 let instance = self.instance.clone();
                 let backend_handle = self.backend_handle.clone();
                 move |click: &ClickEvent, window, cx| {
@@ -667,7 +1053,8 @@ impl Render for InstanceSettingsSubpage {
 
                     if InterfaceConfig::get(cx).quick_delete_instance && click.modifiers().shift {
                         backend_handle.send(bridge::message::MessageToBackend::DeleteInstance {
-                            id
+                            id,
+                            permanent: false,
                         });
                     } else {
                         crate::modals::delete_instance::open_delete_instance(id, name, backend_handle.clone(), window, cx);
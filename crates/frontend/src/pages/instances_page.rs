@@ -3,47 +3,139 @@ use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
 };
 
-use bridge::{handle::BackendHandle, message::MessageToBackend};
+use bridge::{handle::BackendHandle, install::{ContentInstall, InstallTarget}, instance::InstanceID, message::MessageToBackend};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, IconName, IndexPath, Selectable, WindowExt,
+    ActiveTheme as _, Icon, IconName, IndexPath, Selectable, WindowExt,
     alert::Alert,
     button::{Button, ButtonGroup, ButtonVariants},
     checkbox::Checkbox,
     h_flex,
     input::{Input, InputEvent, InputState},
+    notification::NotificationType,
+    scroll::ScrollableElement,
     select::{Select, SelectDelegate, SelectItem, SelectState},
     skeleton::Skeleton,
-    table::{Table, TableState},
+    table::{Table, TableEvent, TableState},
     v_flex,
 };
-use schema::{loader::Loader, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
+use schema::{loader::Loader, version_manifest::MinecraftVersionManifest};
 
 use crate::{
-    component::{instance_list::InstanceList, page_path::PagePath},
-    entity::{DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}},
-    ui,
+    component::{instance_card::{self, InstanceCard, InstanceRow}, instance_list::InstanceList, page_path::PagePath},
+    entity::{DataEntities, instance::{InstanceAddedEvent, InstanceEntries, InstanceEntry, InstanceModifiedEvent, InstanceMovedToTopEvent, InstanceRemovedEvent}, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}, notifications::push_notification},
+    interface_config::{InstancesViewMode, InterfaceConfig, VersionFilter},
+    root, ts, ui,
 };
 
 pub struct InstancesPage {
     instance_table: Entity<TableState<InstanceList>>,
+    selected_instance: Option<InstanceID>,
+    focus_handle: FocusHandle,
 
     metadata: Entity<FrontendMetadata>,
     instances: Entity<InstanceEntries>,
 
     backend_handle: BackendHandle,
+
+    _instance_added_subscription: Subscription,
+    _instance_removed_subscription: Subscription,
+    _instance_modified_subscription: Subscription,
+    _instance_moved_to_top_subscription: Subscription,
+    _instance_table_subscription: Subscription,
 }
 
 impl InstancesPage {
     pub fn new(data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let instance_table = InstanceList::create_table(data, window, cx);
 
+        let _instance_added_subscription = cx.subscribe::<_, InstanceAddedEvent>(&data.instances, |_, _, _, cx| cx.notify());
+        let _instance_removed_subscription = cx.subscribe::<_, InstanceRemovedEvent>(&data.instances, |_, _, _, cx| cx.notify());
+        let _instance_modified_subscription = cx.subscribe::<_, InstanceModifiedEvent>(&data.instances, |_, _, _, cx| cx.notify());
+        let _instance_moved_to_top_subscription = cx.subscribe::<_, InstanceMovedToTopEvent>(&data.instances, |_, _, _, cx| cx.notify());
+        let _instance_table_subscription = cx.subscribe_in(&instance_table, window, |page, table, event: &TableEvent, window, cx| {
+            if let TableEvent::DoubleClickedRow(row_ix) = event
+                && let Some(instance) = table.read(cx).delegate().instance(*row_ix).cloned()
+            {
+                instance_card::activate_instance(&instance, &page.backend_handle, window, cx);
+            }
+        });
+
         Self {
             instance_table,
+            selected_instance: None,
+            focus_handle: cx.focus_handle(),
             metadata: data.metadata.clone(),
             instances: data.instances.clone(),
             backend_handle: data.backend_handle.clone(),
+            _instance_added_subscription,
+            _instance_removed_subscription,
+            _instance_modified_subscription,
+            _instance_moved_to_top_subscription,
+            _instance_table_subscription,
+        }
+    }
+
+    /// Activates the selected instance (or table row) when Enter is pressed, and moves the selection
+    /// in the grid and compact views with the arrow keys. The table handles its own arrow keys
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if event.keystroke.modifiers.modified() {
+            return;
+        }
+
+        match event.keystroke.key.as_str() {
+            "enter" => self.activate_selected(window, cx),
+            "up" | "left" => self.move_selection(-1, cx),
+            "down" | "right" => self.move_selection(1, cx),
+            _ => {},
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        if InterfaceConfig::get(cx).instances_view_mode == InstancesViewMode::Table {
+            return;
         }
+
+        let instances = self.pinned_first(cx);
+        if instances.is_empty() {
+            return;
+        }
+
+        let current = self.selected_instance.and_then(|id| instances.iter().position(|instance| instance.id == id));
+        let next = match current {
+            Some(index) => index.saturating_add_signed(delta).min(instances.len() - 1),
+            None => 0,
+        };
+
+        cx.stop_propagation();
+        self.selected_instance = Some(instances[next].id);
+        cx.notify();
+    }
+
+    fn activate_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let instance = match InterfaceConfig::get(cx).instances_view_mode {
+            InstancesViewMode::Table => {
+                let table = self.instance_table.read(cx);
+                table.selected_row().and_then(|row_ix| table.delegate().instance(row_ix).cloned())
+            },
+            InstancesViewMode::Grid | InstancesViewMode::Compact => {
+                let Some(id) = self.selected_instance else {
+                    return;
+                };
+                self.instances.read(cx).entries.get(&id).map(|instance| instance.read(cx).clone())
+            },
+        };
+
+        if let Some(instance) = instance {
+            cx.stop_propagation();
+            instance_card::activate_instance(&instance, &self.backend_handle, window, cx);
+        }
+    }
+
+    fn select_instance(&mut self, id: &InstanceID, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_instance = Some(*id);
+        self.focus_handle.focus(window, cx);
+        cx.notify();
     }
 }
 
@@ -52,13 +144,88 @@ impl Render for InstancesPage {
         let create_instance = Button::new("create_instance")
             .success()
             .icon(IconName::Plus)
-            .label("Create Instance")
+            .label(ts!("instances.create"))
             .on_click(cx.listener(|this, _, window, cx| {
                 this.show_create_instance_modal(window, cx);
             }));
 
-        ui::page(cx, h_flex().gap_8().child("Instances").child(create_instance))
-            .child(Table::new(&self.instance_table).bordered(false))
+        let import_profile = Button::new("import_profile")
+            .icon(Icon::empty().path("icons/download.svg"))
+            .label(ts!("instances.import_profile"))
+            .on_click({
+                let backend_handle = self.backend_handle.clone();
+                move |_, window, cx| {
+                    let receiver = cx.prompt_for_paths(PathPromptOptions {
+                        files: true,
+                        directories: false,
+                        multiple: false,
+                        prompt: Some(ts!("shared_profile.select"))
+                    });
+
+                    let backend_handle = backend_handle.clone();
+                    window.spawn(cx, async move |cx| {
+                        let Ok(Ok(Some(paths))) = receiver.await else {
+                            return;
+                        };
+                        let Some(path) = paths.into_iter().next() else {
+                            return;
+                        };
+                        _ = cx.update(move |window, cx| {
+                            crate::modals::import_shared_profile::open_import_shared_profile(path, &backend_handle, window, cx);
+                        });
+                    }).detach();
+                }
+            });
+
+        let view_mode = InterfaceConfig::get(cx).instances_view_mode;
+
+        let view_mode_group = ButtonGroup::new("view_mode")
+            .outline()
+            .child(Button::new("table").icon(Icon::empty().path("icons/layout-dashboard.svg")).tooltip(ts!("instances.table")).selected(view_mode == InstancesViewMode::Table))
+            .child(Button::new("grid").icon(Icon::empty().path("icons/grid-2x2.svg")).tooltip(ts!("instances.grid")).selected(view_mode == InstancesViewMode::Grid))
+            .child(Button::new("compact").icon(Icon::empty().path("icons/menu.svg")).tooltip(ts!("instances.compact")).selected(view_mode == InstancesViewMode::Compact))
+            .on_click(cx.listener(|_, clicked: &Vec<usize>, _, cx| {
+                let view_mode = match clicked[0] {
+                    0 => InstancesViewMode::Table,
+                    1 => InstancesViewMode::Grid,
+                    2 => InstancesViewMode::Compact,
+                    _ => return,
+                };
+                InterfaceConfig::get_mut(cx).instances_view_mode = view_mode;
+                cx.notify();
+            }));
+
+        let page = ui::page(cx, h_flex().gap_8().child(ts!("instances.title")).child(h_flex().gap_2().child(create_instance).child(import_profile)).child(view_mode_group));
+
+        let content = match view_mode {
+            InstancesViewMode::Table => Table::new(&self.instance_table).bordered(false).into_any_element(),
+            InstancesViewMode::Grid => {
+                let cards = self.pinned_first(cx).into_iter().map(|instance| {
+                    let selected = self.selected_instance == Some(instance.id);
+                    InstanceCard::new(instance, self.backend_handle.clone())
+                        .selected(selected)
+                        .on_select(cx.listener(Self::select_instance))
+                }).collect::<Vec<_>>();
+
+                h_flex().flex_wrap().items_start().p_4().gap_4().children(cards).overflow_y_scrollbar().into_any_element()
+            },
+            InstancesViewMode::Compact => {
+                let rows = self.pinned_first(cx).into_iter().map(|instance| {
+                    let selected = self.selected_instance == Some(instance.id);
+                    InstanceRow::new(instance, self.backend_handle.clone())
+                        .selected(selected)
+                        .on_select(cx.listener(Self::select_instance))
+                }).collect::<Vec<_>>();
+
+                v_flex().size_full().children(rows).overflow_y_scrollbar().into_any_element()
+            },
+        };
+
+        page.child(div()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::on_key_down))
+            .child(content))
     }
 }
 
@@ -108,12 +275,19 @@ impl SelectDelegate for VersionList {
 }
 
 impl InstancesPage {
+    /// Pinned instances go first, otherwise the order of the entries is kept
+    fn pinned_first(&self, cx: &App) -> Vec<InstanceEntry> {
+        let mut instances = self.instances.read(cx).entries.values().map(|instance| instance.read(cx).clone()).collect::<Vec<_>>();
+        instances.sort_by_key(|instance| !instance.configuration.pinned);
+        instances
+    }
+
     pub fn show_create_instance_modal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let selected_loader = Arc::new(AtomicUsize::new(0));
         let loaded_versions = Arc::new(AtomicBool::new(false));
         let error_loading_versions = Arc::new(RwLock::new(None));
-        let show_snapshots = Arc::new(AtomicBool::new(false));
         let name_invalid = Arc::new(AtomicBool::new(false));
+        let install_companion = Arc::new(AtomicBool::new(true));
 
         let instance_names: Arc<[SharedString]> =
             self.instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
@@ -121,7 +295,7 @@ impl InstancesPage {
         let minecraft_version_dropdown =
             cx.new(|cx| SelectState::new(VersionList::default(), None, window, cx).searchable(true));
 
-        let unnamed_instance_name = SharedString::new_static("Unnamed Instance");
+        let unnamed_instance_name = ts!("instances.unnamed_instance");
 
         let name_input_state = cx.new(|cx| InputState::new(window, cx).placeholder(unnamed_instance_name.clone()));
 
@@ -148,12 +322,12 @@ impl InstancesPage {
 
         let reload_version_dropdown = {
             let loaded_versions = Arc::clone(&loaded_versions);
-            let show_snapshots = Arc::clone(&show_snapshots);
             let error_loading_versions = Arc::clone(&error_loading_versions);
             let minecraft_version_dropdown = minecraft_version_dropdown.clone();
             let versions = versions.clone();
 
             move |window: &mut Window, cx: &mut App| {
+                let version_filter = InterfaceConfig::get(cx).version_filter;
                 cx.update_entity(&minecraft_version_dropdown, |dropdown, cx| {
                     let result: FrontendMetadataResult<MinecraftVersionManifest> = versions.read(cx).result();
                     let (versions, latest) = match result {
@@ -170,16 +344,12 @@ impl InstancesPage {
                             loaded_versions.store(true, Ordering::Relaxed);
                             *error_loading_versions.write().unwrap() = None;
 
-                            let versions: Vec<SharedString> = if show_snapshots.load(Ordering::Relaxed) {
-                                manifest.versions.iter().map(|v| SharedString::from(v.id.as_str())).collect()
-                            } else {
-                                manifest
-                                    .versions
-                                    .iter()
-                                    .filter(|v| !matches!(v.r#type, MinecraftVersionType::Snapshot))
-                                    .map(|v| SharedString::from(v.id.as_str()))
-                                    .collect()
-                            };
+                            let versions: Vec<SharedString> = manifest
+                                .versions
+                                .iter()
+                                .filter(|v| version_filter.allows(v))
+                                .map(|v| SharedString::from(v.id.as_str()))
+                                .collect();
 
                             (versions, Some(SharedString::from(manifest.latest.release.as_str())))
                         },
@@ -280,14 +450,14 @@ impl InstancesPage {
             if let Some(error) = error_loading_versions.read().unwrap().as_ref() {
                 let error_widget = Alert::new("error", format!("{}", error))
                     .icon(IconName::CircleX)
-                    .title("Error loading Minecraft versions");
+                    .title(ts!("instances.versions_failed"));
 
                 let error_loading_versions = Arc::clone(&error_loading_versions);
                 let metadata = metadata.clone();
                 let reload_button =
                     Button::new("reload-versions")
                         .primary()
-                        .label("Reload Versions")
+                        .label(ts!("instances.reload_versions"))
                         .on_click(move |_, _, cx| {
                             *error_loading_versions.write().unwrap() = None;
                             FrontendMetadata::force_reload(&metadata, bridge::meta::MetadataRequest::MinecraftVersionManifest, cx);
@@ -295,7 +465,7 @@ impl InstancesPage {
 
                 return modal
                     .confirm()
-                    .title("Create Instance")
+                    .title(ts!("instances.create"))
                     .child(v_flex().gap_3().child(error_widget).child(reload_button));
             }
 
@@ -308,53 +478,93 @@ impl InstancesPage {
             };
 
             let version_dropdown;
-            let show_snapshots_button;
+            let version_filter_checkboxes;
             let loader_button_group;
 
             if !loaded_versions.load(Ordering::Relaxed) {
                 version_dropdown = Select::new(&minecraft_version_dropdown)
                     .w_full()
                     .disabled(true)
-                    .placeholder("Loading Minecraft Versions...");
-                show_snapshots_button = Skeleton::new().w_full().min_h_4().max_h_4().rounded_md().into_any_element();
+                    .placeholder(ts!("instances.loading_versions"));
+                version_filter_checkboxes = Skeleton::new().w_full().min_h_4().max_h_4().rounded_md().into_any_element();
                 loader_button_group = Skeleton::new().w_full().min_h_8().max_h_8().rounded_md().into_any_element();
             } else {
                 let reload_version_dropdown = reload_version_dropdown.clone();
                 let selected_loader = selected_loader.clone();
 
-                let show_snapshots = Arc::clone(&show_snapshots);
-                let show_snapshots_value = show_snapshots.load(Ordering::Relaxed);
-
-                version_dropdown = Select::new(&minecraft_version_dropdown).title_prefix("Minecraft Version: ");
-                show_snapshots_button = Checkbox::new("show_snapshots")
-                    .checked(show_snapshots_value)
-                    .label("Show Snapshots")
-                    .on_click(move |show, window, cx| {
-                        show_snapshots.store(*show, Ordering::Relaxed);
-                        (reload_version_dropdown)(window, cx);
-                    })
+                let version_filter = InterfaceConfig::get(cx).version_filter;
+                let filter_checkbox = |id: &'static str, label: SharedString, checked: bool, set: fn(&mut VersionFilter, bool)| {
+                    let reload_version_dropdown = reload_version_dropdown.clone();
+                    Checkbox::new(id)
+                        .checked(checked)
+                        .label(label)
+                        .on_click(move |show, window, cx| {
+                            set(&mut InterfaceConfig::get_mut(cx).version_filter, *show);
+                            (reload_version_dropdown)(window, cx);
+                        })
+                };
+
+                version_dropdown = Select::new(&minecraft_version_dropdown).title_prefix(ts!("instances.minecraft_version_prefix"));
+                version_filter_checkboxes = h_flex()
+                    .gap_4()
+                    .child(filter_checkbox("show_snapshots", ts!("instances.show_snapshots"), version_filter.show_snapshots,
+                        |filter, show| filter.show_snapshots = show))
+                    .child(filter_checkbox("show_old_versions", ts!("instances.show_old_versions"), version_filter.show_old_versions,
+                        |filter, show| filter.show_old_versions = show))
+                    .child(filter_checkbox("show_april_fools", ts!("instances.show_april_fools"), version_filter.show_april_fools,
+                        |filter, show| filter.show_april_fools = show))
+                    .child(div().flex_1())
+                    .child(Button::new("add_custom_version").label(ts!("instances.add_custom_version")).compact().small().on_click({
+                        let backend_handle = backend_handle.clone();
+                        move |_, window, cx| {
+                            let receiver = cx.prompt_for_paths(PathPromptOptions {
+                                files: true,
+                                directories: false,
+                                multiple: true,
+                                prompt: Some(ts!("instances.select_custom_version"))
+                            });
+
+                            let backend_handle = backend_handle.clone();
+                            window.spawn(cx, async move |cx| {
+                                let Ok(result) = receiver.await else {
+                                    return;
+                                };
+                                _ = cx.update(move |window, cx| {
+                                    match result {
+                                        Ok(Some(paths)) => {
+                                            root::start_add_local_version(paths.into(), &backend_handle, window, cx);
+                                        },
+                                        Ok(None) => {},
+                                        Err(error) => {
+                                            push_notification(NotificationType::Error, format!("{}", error), window, cx);
+                                        },
+                                    }
+                                });
+                            }).detach();
+                        }
+                    }))
                     .into_any_element();
                 loader_button_group = ButtonGroup::new("loader")
                     .outline()
                     .h_full()
                     .child(
                         Button::new("loader-vanilla")
-                            .label("Vanilla")
+                            .label(Loader::Vanilla.name())
                             .selected(selected_loader_value == Loader::Vanilla),
                     )
                     .child(
                         Button::new("loader-fabric")
-                            .label("Fabric")
+                            .label(Loader::Fabric.name())
                             .selected(selected_loader_value == Loader::Fabric),
                     )
                     .child(
                         Button::new("loader-forge")
-                            .label("Forge")
+                            .label(Loader::Forge.name())
                             .selected(selected_loader_value == Loader::Forge),
                     )
                     .child(
                         Button::new("loader-neoforge")
-                            .label("NeoForge")
+                            .label(Loader::NeoForge.name())
                             .selected(selected_loader_value == Loader::NeoForge),
                     )
                     .on_click(move |selected, _, _| {
@@ -369,6 +579,8 @@ impl InstancesPage {
                     .into_any_element();
             };
 
+            let companion = selected_loader_value.companion();
+
             let minecraft_version_dropdown = minecraft_version_dropdown.clone();
 
             let name_is_invalid = name_invalid.load(Ordering::Relaxed);
@@ -376,15 +588,27 @@ impl InstancesPage {
             let content = v_flex()
                 .gap_3()
                 .child(crate::labelled(
-                    "Name",
+                    ts!("common.name"),
                     Input::new(&name_input_state).when(name_is_invalid, |this| this.border_color(cx.theme().danger)),
                 ))
-                .child(crate::labelled("Version", v_flex().gap_2().child(version_dropdown).child(show_snapshots_button)))
-                .child(crate::labelled("Modloader", loader_button_group));
+                .child(crate::labelled(ts!("instances.version"), v_flex().gap_2().child(version_dropdown).child(version_filter_checkboxes)))
+                .child(crate::labelled(ts!("instances.modloader"), v_flex()
+                    .gap_2()
+                    .child(loader_button_group)
+                    .when_some(companion, |this, companion| {
+                        let install_companion = Arc::clone(&install_companion);
+                        this.child(Checkbox::new("install_companion")
+                            .checked(install_companion.load(Ordering::Relaxed))
+                            .label(SharedString::new(rust_i18n::t!("companion.install", name = companion.name)))
+                            .on_click(move |value, _, _| {
+                                install_companion.store(*value, Ordering::Relaxed);
+                            }))
+                    })));
 
             let text_input_state = name_input_state.clone();
             let backend_handle = backend_handle.clone();
             let fallback_name_info = Arc::clone(&fallback_name_info);
+            let install_companion = Arc::clone(&install_companion);
 
             modal
                 .footer(move |ok, cancel, window, cx| {
@@ -398,8 +622,8 @@ impl InstancesPage {
                     }
                 })
                 .overlay_closable(false)
-                .title("Create Instance")
-                .on_ok(move |_, _, cx| {
+                .title(ts!("instances.create"))
+                .on_ok(move |_, window, cx| {
                     if name_is_invalid {
                         return false;
                     }
@@ -413,11 +637,21 @@ impl InstancesPage {
                         name = fallback_name_info.actual.clone();
                     }
 
-                    backend_handle.send(MessageToBackend::CreateInstance {
-                        name: name.as_str().into(),
-                        version: selected_version.as_str().into(),
-                        loader: selected_loader_value,
-                    });
+                    if let Some(companion) = companion && install_companion.load(Ordering::Relaxed) {
+                        let content_install = ContentInstall {
+                            target: InstallTarget::NewInstance { name: name.as_str().into(), configuration: None },
+                            loader_hint: selected_loader_value,
+                            version_hint: Some(selected_version.as_str().into()),
+                            files: [root::companion_install_file(companion)].into(),
+                        };
+                        root::start_install(content_install, &backend_handle, window, cx);
+                    } else {
+                        backend_handle.send(MessageToBackend::CreateInstance {
+                            name: name.as_str().into(),
+                            version: selected_version.as_str().into(),
+                            loader: selected_loader_value,
+                        });
+                    }
 
                     true
                 })
@@ -1,4 +1,10 @@
+pub mod accounts_page;
+pub mod debug_console_page;
+pub mod file_watches_page;
+pub mod following_page;
 pub mod instance;
 pub mod instances_page;
 pub mod modrinth_page;
+pub mod modrinth_project_page;
+pub mod storage_page;
 pub mod syncing_page;
@@ -3,17 +3,17 @@ use std::{ops::Range, sync::{atomic::AtomicBool, Arc}, time::Duration};
 use bridge::{instance::{AtomicContentUpdateStatus, ContentUpdateStatus, InstanceID, InstanceContentID, InstanceContentSummary}, message::MessageToBackend, meta::MetadataRequest, modal_action::ModalAction};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Selectable, StyledExt, WindowExt, breadcrumb::Breadcrumb, button::{Button, ButtonGroup, ButtonVariant, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, notification::NotificationType, scroll::{ScrollableElement, Scrollbar}, skeleton::Skeleton, tooltip::Tooltip, v_flex
+    ActiveTheme, Icon, IconName, IndexPath, Selectable, StyledExt, breadcrumb::Breadcrumb, button::{Button, ButtonGroup, ButtonVariant, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, notification::NotificationType, scroll::{ScrollableElement, Scrollbar}, select::{SearchableVec, Select, SelectEvent, SelectState}, skeleton::Skeleton, tooltip::Tooltip, v_flex
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use schema::{content::ContentSource, loader::Loader, modrinth::{
-    ModrinthHit, ModrinthProjectType, ModrinthSearchRequest, ModrinthSearchResult, ModrinthSideRequirement
-}};
+    MODRINTH_API_HOST, ModrinthCategoryTags, ModrinthHit, ModrinthProjectType, ModrinthSearchIndex, ModrinthSearchRequest, ModrinthSearchResult, ModrinthSideRequirement
+}, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
 
 use crate::{
     component::{error_alert::ErrorAlert, page_path::PagePath}, entity::{
-        DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}
-    }, interface_config::InterfaceConfig, ts, ui
+        DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}, notifications::push_notification
+    }, interface_config::InterfaceConfig, pages::modrinth_project_page::ModrinthProjectPage, ts, ts_or_title_case, ui
 };
 
 pub struct ModrinthSearchPage {
@@ -22,6 +22,12 @@ pub struct ModrinthSearchPage {
     page_path: PagePath,
     install_for: Option<InstanceID>,
     loading: Option<Subscription>,
+    loading_request: Option<MetadataRequest>,
+    /// Incremented whenever the search changes, results for an older generation are discarded
+    search_generation: u64,
+    /// Offset of the next page, hits that were already shown on an earlier page aren't added again
+    next_offset: usize,
+    seen_projects: FxHashSet<Arc<str>>,
     pending_clear: bool,
     total_hits: usize,
     search_state: Entity<InputState>,
@@ -30,13 +36,26 @@ pub struct ModrinthSearchPage {
     filter_project_type: ModrinthProjectType,
     filter_loaders: FxHashSet<Loader>,
     filter_categories: FxHashSet<&'static str>,
+    filter_game_version: Option<SharedString>,
+    filter_open_source: bool,
+    search_index: ModrinthSearchIndex,
+    game_version_select: Entity<SelectState<SearchableVec<SharedString>>>,
+    sort_select: Entity<SelectState<Vec<SharedString>>>,
+    _version_manifest_subscription: Subscription,
+    _rate_limit_subscription: Subscription,
+    /// Re-renders the page every second while counting down a rate limit
+    _rate_limit_countdown_task: Task<()>,
     show_categories: Arc<AtomicBool>,
+    /// Categories from Modrinth's tag API, so that categories added after this release can be filtered by
+    category_tags: Entity<FrontendMetadataState>,
     can_install_latest: bool,
     installed_mods_by_project: FxHashMap<Arc<str>, Vec<InstalledMod>>,
     last_search: Arc<str>,
     scroll_handle: UniformListScrollHandle,
     search_error: Option<SharedString>,
     image_cache: Entity<RetainAllImageCache>,
+    /// Project opened from the search results, shown instead of them until going back
+    project_page: Option<Entity<ModrinthProjectPage>>,
 }
 
 struct InstalledMod {
@@ -46,7 +65,7 @@ struct InstalledMod {
 
 impl ModrinthSearchPage {
     pub fn new(install_for: Option<InstanceID>, project_type: Option<ModrinthProjectType>, page_path: PagePath, data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder("Search mods...").clean_on_escape());
+        let search_state = cx.new(|cx| InputState::new(window, cx).placeholder(ts!("modrinth_search.search_mods")).clean_on_escape());
 
         let mut can_install_latest = false;
         let mut installed_mods_by_project: FxHashMap<Arc<str>, Vec<InstalledMod>> = FxHashMap::default();
@@ -73,6 +92,37 @@ impl ModrinthSearchPage {
 
         let _search_input_subscription = cx.subscribe_in(&search_state, window, Self::on_search_input_event);
 
+        let category_tags = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthCategoryTags, cx);
+        cx.observe(&category_tags, |_, _, cx| cx.notify()).detach();
+        cx.observe(&data.followed_projects, |_, _, cx| cx.notify()).detach();
+
+        let game_version_select = cx.new(|cx| {
+            let mut state = SelectState::new(SearchableVec::new(vec![ts!("modrinth_search.any_game_version")]), None, window, cx)
+                .searchable(true);
+            state.set_selected_index(Some(IndexPath::default()), window, cx);
+            state
+        });
+        cx.subscribe_in(&game_version_select, window, Self::on_game_version_selected).detach();
+
+        let version_manifest = FrontendMetadata::request(&data.metadata, MetadataRequest::MinecraftVersionManifest, cx);
+        Self::update_game_versions(&game_version_select, &version_manifest, window, cx);
+        let _version_manifest_subscription = cx.observe_in(&version_manifest, window, {
+            let game_version_select = game_version_select.clone();
+            move |_, version_manifest, window, cx| {
+                Self::update_game_versions(&game_version_select, &version_manifest, window, cx);
+            }
+        });
+
+        let _rate_limit_subscription = cx.observe(&data.metadata, |_, _, cx| cx.notify());
+
+        let sort_select = cx.new(|cx| {
+            let names = SORT_ORDERS.iter().map(|(_, key)| ts!(*key)).collect();
+            let mut state = SelectState::new(names, None, window, cx);
+            state.set_selected_index(Some(IndexPath::default()), window, cx);
+            state
+        });
+        cx.subscribe_in(&sort_select, window, Self::on_sort_selected).detach();
+
         let mut filter_project_type = if let Some(project_type) = project_type {
             InterfaceConfig::get_mut(cx).modrinth_page_project_type = project_type;
             project_type
@@ -89,6 +139,10 @@ impl ModrinthSearchPage {
             page_path,
             install_for,
             loading: None,
+            loading_request: None,
+            search_generation: 0,
+            next_offset: 0,
+            seen_projects: FxHashSet::default(),
             pending_clear: false,
             total_hits: 1,
             search_state,
@@ -97,18 +151,32 @@ impl ModrinthSearchPage {
             filter_project_type,
             filter_loaders: FxHashSet::default(),
             filter_categories: FxHashSet::default(),
+            filter_game_version: None,
+            filter_open_source: false,
+            search_index: ModrinthSearchIndex::Relevance,
+            game_version_select,
+            sort_select,
+            _version_manifest_subscription,
+            _rate_limit_subscription,
+            _rate_limit_countdown_task: Task::ready(()),
             show_categories: Arc::new(AtomicBool::new(false)),
+            category_tags,
             can_install_latest,
             installed_mods_by_project,
             last_search: Arc::from(""),
             scroll_handle: UniformListScrollHandle::new(),
             search_error: None,
             image_cache: RetainAllImageCache::new(cx),
+            project_page: None,
         };
         page.load_more(cx);
         page
     }
 
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        self.search_state.update(cx, |state, cx| state.focus(window, cx));
+    }
+
     fn on_search_input_event(
         &mut self,
         state: &Entity<InputState>,
@@ -141,11 +209,11 @@ impl ModrinthSearchPage {
         self.filter_categories.clear();
         self.search_state.update(cx, |state, cx| {
             let placeholder = match project_type {
-                ModrinthProjectType::Mod => "Search mods...",
-                ModrinthProjectType::Modpack => "Search modpacks...",
-                ModrinthProjectType::Resourcepack => "Search resourcepacks...",
-                ModrinthProjectType::Shader => "Search shaders...",
-                ModrinthProjectType::Other => "Search...",
+                ModrinthProjectType::Mod => ts!("modrinth_search.search_mods"),
+                ModrinthProjectType::Modpack => ts!("modrinth_search.search_modpacks"),
+                ModrinthProjectType::Resourcepack => ts!("modrinth_search.search_resourcepacks"),
+                ModrinthProjectType::Shader => ts!("modrinth_search.search_shaders"),
+                ModrinthProjectType::Other => ts!("modrinth_search.search_other"),
             };
             state.set_placeholder(placeholder, window, cx)
         });
@@ -168,17 +236,92 @@ impl ModrinthSearchPage {
         self.reload(cx);
     }
 
+    /// Fills the game version filter with the release versions from the version manifest
+    fn update_game_versions(
+        select: &Entity<SelectState<SearchableVec<SharedString>>>,
+        version_manifest: &Entity<FrontendMetadataState>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let result: FrontendMetadataResult<MinecraftVersionManifest> = version_manifest.read(cx).result();
+        let FrontendMetadataResult::Loaded(manifest) = result else {
+            return;
+        };
+
+        let versions: Vec<SharedString> = std::iter::once(ts!("modrinth_search.any_game_version"))
+            .chain(manifest.versions.iter()
+                .filter(|version| matches!(version.r#type, MinecraftVersionType::Release))
+                .map(|version| SharedString::new(version.id.as_str())))
+            .collect();
+
+        select.update(cx, |state, cx| {
+            let selected = state.selected_value().cloned();
+            state.set_items(SearchableVec::new(versions), window, cx);
+            match selected {
+                Some(selected) => state.set_selected_value(&selected, window, cx),
+                None => state.set_selected_index(Some(IndexPath::default()), window, cx),
+            }
+        });
+    }
+
+    fn on_game_version_selected(
+        &mut self,
+        _: &Entity<SelectState<SearchableVec<SharedString>>>,
+        event: &SelectEvent<SearchableVec<SharedString>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let SelectEvent::Confirm(value) = event;
+        let game_version = value.clone().filter(|version| *version != ts!("modrinth_search.any_game_version"));
+        if self.filter_game_version == game_version {
+            return;
+        }
+        self.filter_game_version = game_version;
+        self.reload(cx);
+    }
+
+    fn on_sort_selected(
+        &mut self,
+        _: &Entity<SelectState<Vec<SharedString>>>,
+        event: &SelectEvent<Vec<SharedString>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let SelectEvent::Confirm(Some(value)) = event else {
+            return;
+        };
+        let Some((index, _)) = SORT_ORDERS.iter().find(|(_, key)| *value == ts!(*key)) else {
+            return;
+        };
+        if self.search_index == *index {
+            return;
+        }
+        self.search_index = *index;
+        self.reload(cx);
+    }
+
+    fn set_filter_open_source(&mut self, open_source: bool, cx: &mut Context<Self>) {
+        if self.filter_open_source == open_source {
+            return;
+        }
+        self.filter_open_source = open_source;
+        self.reload(cx);
+    }
+
     fn reload(&mut self, cx: &mut Context<Self>) {
         self.pending_clear = true;
+        self.search_generation += 1;
         self.loading = None;
+        if let Some(request) = self.loading_request.take() {
+            FrontendMetadata::cancel(&self.data.metadata, &request, cx);
+        }
 
         self._delayed_clear_task = cx.spawn(async |page, cx| {
             gpui::Timer::after(Duration::from_millis(300)).await;
             let _ = page.update(cx, |page, cx| {
                 if page.pending_clear {
                     page.pending_clear = false;
-                    page.hits.clear();
-                    page.total_hits = 1;
+                    page.clear_hits();
                     cx.notify();
                 }
             });
@@ -187,6 +330,13 @@ impl ModrinthSearchPage {
         self.load_more(cx);
     }
 
+    fn clear_hits(&mut self) {
+        self.hits.clear();
+        self.seen_projects.clear();
+        self.next_offset = 0;
+        self.total_hits = 1;
+    }
+
     fn load_more(&mut self, cx: &mut Context<Self>) {
         if self.loading.is_some() {
             return;
@@ -206,7 +356,7 @@ impl ModrinthSearchPage {
             ModrinthProjectType::Shader => "shader",
         };
 
-        let offset = if self.pending_clear { 0 } else { self.hits.len() };
+        let offset = if self.pending_clear { 0 } else { self.next_offset };
 
         let mut facets = format!("[[\"project_type={}\"]", project_type);
 
@@ -245,38 +395,56 @@ impl ModrinthSearchPage {
             facets.push(']');
         }
 
+        if let Some(game_version) = &self.filter_game_version {
+            facets.push_str(",[\"versions:");
+            facets.push_str(game_version);
+            facets.push_str("\"]");
+        }
+
+        if self.filter_open_source {
+            facets.push_str(",[\"open_source:true\"]");
+        }
+
         facets.push(']');
 
         let request = ModrinthSearchRequest {
             query,
             facets: Some(facets.into()),
-            index: schema::modrinth::ModrinthSearchIndex::Relevance,
+            index: self.search_index,
             offset,
             limit: 20,
         };
 
-        let data = FrontendMetadata::request(&self.data.metadata, MetadataRequest::ModrinthSearch(request), cx);
+        let request = MetadataRequest::ModrinthSearch(request);
+        let data = FrontendMetadata::request(&self.data.metadata, request.clone(), cx);
 
         let result: FrontendMetadataResult<ModrinthSearchResult> = data.read(cx).result();
         match result {
             FrontendMetadataResult::Loading => {
-                let subscription = cx.observe(&data, |page, data, cx| {
+                let generation = self.search_generation;
+                let subscription = cx.observe(&data, move |page, data, cx| {
+                    if page.search_generation != generation {
+                        return;
+                    }
                     let result: FrontendMetadataResult<ModrinthSearchResult> = data.read(cx).result();
                     match result {
                         FrontendMetadataResult::Loading => {},
                         FrontendMetadataResult::Loaded(result) => {
                             page.apply_search_data(result);
                             page.loading = None;
+                            page.loading_request = None;
                             cx.notify();
                         },
                         FrontendMetadataResult::Error(shared_string) => {
                             page.search_error = Some(shared_string);
                             page.loading = None;
+                            page.loading_request = None;
                             cx.notify();
                         },
                     }
                 });
                 self.loading = Some(subscription);
+                self.loading_request = Some(request);
             },
             FrontendMetadataResult::Loaded(result) => {
                 self.apply_search_data(result);
@@ -290,19 +458,27 @@ impl ModrinthSearchPage {
     fn apply_search_data(&mut self, search_result: &ModrinthSearchResult) {
         if self.pending_clear {
             self.pending_clear = false;
-            self.hits.clear();
-            self.total_hits = 1;
+            self.clear_hits();
             self._delayed_clear_task = Task::ready(());
         }
 
-        self.hits.extend(search_result.hits.iter().map(|hit| {
+        // Results can shift between pages while paging through them, which would show a project twice
+        for hit in search_result.hits.iter() {
+            if !self.seen_projects.insert(hit.project_id.clone()) {
+                continue;
+            }
             let mut hit = hit.clone();
             if let Some(description) = hit.description {
                 hit.description = Some(description.replace("\n", " ").into());
             }
-            hit
-        }));
-        self.total_hits = search_result.total_hits;
+            self.hits.push(hit);
+        }
+        self.next_offset = search_result.offset + search_result.hits.len();
+        self.total_hits = if search_result.hits.is_empty() {
+            self.next_offset
+        } else {
+            search_result.total_hits
+        };
     }
 
     fn render_items(&mut self, visible_range: Range<usize>, _window: &mut Window, cx: &mut Context<Self>) -> Vec<Div> {
@@ -315,7 +491,7 @@ impl ModrinthSearchPage {
                         return div()
                             .pl_3()
                             .pt_3()
-                            .child(ErrorAlert::new("search_error", "Error requesting from Modrinth".into(), search_error));
+                            .child(ErrorAlert::new("search_error", ts!("modrinth_project.request_failed"), search_error));
                     } else {
                         should_load_more = true;
                         return div()
@@ -341,14 +517,14 @@ impl ModrinthSearchPage {
                     .as_ref()
                     .map(Arc::clone)
                     .map(SharedString::new)
-                    .unwrap_or(SharedString::new_static("Unnamed"));
-                let author = format!("by {}", hit.author.clone());
+                    .unwrap_or(ts!("following.unnamed"));
+                let author = SharedString::new(rust_i18n::t!("modrinth_project.by_authors", authors = hit.author));
                 let description = hit
                     .description
                     .as_ref()
                     .map(Arc::clone)
                     .map(SharedString::new)
-                    .unwrap_or(SharedString::new_static("No Description"));
+                    .unwrap_or(ts!("modrinth_search.no_description"));
 
                 const GRAY: Hsla = Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0 };
                 let author_line = div().text_color(GRAY).text_sm().pb_px().child(author);
@@ -384,7 +560,7 @@ impl ModrinthSearchPage {
                     categories.iter().map(|category| {
                         let icon = icon_for(category).unwrap_or("icons/diamond.svg");
                         let icon = Icon::empty().path(icon);
-                        let translated_category = ts!(category.as_str());
+                        let translated_category = ts_or_title_case(category.as_str());
                         h_flex().gap_0p5().child(icon).child(translated_category)
                     })
                 });
@@ -397,6 +573,34 @@ impl ModrinthSearchPage {
 
                 let primary_action = self.get_primary_action(&hit.project_id, cx);
 
+                // Installing the latest version is already the primary action when "Install Latest" is ticked
+                let quick_install = self.install_for
+                    .filter(|_| hit.project_type != ModrinthProjectType::Other)
+                    .filter(|_| matches!(primary_action, PrimaryAction::Install | PrimaryAction::Reinstall))
+                    .map(|install_for| {
+                        Button::new(("quick-install", index))
+                            .label(ts!("modrinth_install.install_latest"))
+                            .icon(Icon::empty().path("icons/zap.svg"))
+                            .tooltip(ts!("modrinth_install.install_latest_tooltip"))
+                            .on_click({
+                                let data = self.data.clone();
+                                let name = name.clone();
+                                let project_id = hit.project_id.clone();
+                                let project_type = hit.project_type;
+                                move |_, window, cx| {
+                                    crate::modals::modrinth_install_auto::quick_install(
+                                        name.clone(),
+                                        project_id.clone(),
+                                        project_type,
+                                        install_for,
+                                        &data,
+                                        window,
+                                        cx
+                                    );
+                                }
+                            })
+                    });
+
                 let buttons = ButtonGroup::new(("buttons", index))
                     .layout(Axis::Vertical)
                     .child(
@@ -443,7 +647,7 @@ impl ModrinthSearchPage {
                                                     modal_action: modal_action.clone()
                                                 });
                                                 crate::modals::generic::show_notification(window, cx,
-                                                    "Error checking for updates".into(), modal_action);
+                                                    ts!("modrinth_search.update_check_failed"), modal_action);
                                             },
                                             PrimaryAction::ErrorCheckingForUpdates => {},
                                             PrimaryAction::UpToDate => {},
@@ -456,26 +660,30 @@ impl ModrinthSearchPage {
                                                         modal_action: modal_action.clone()
                                                     });
                                                     crate::modals::generic::show_notification(window, cx,
-                                                        "Error updating mod".into(), modal_action);
+                                                        ts!("modrinth_search.update_failed"), modal_action);
                                                 }
 
                                             },
                                         }
                                     } else {
-                                        window.push_notification(
-                                            (
-                                                NotificationType::Error,
-                                                "Don't know how to handle this type of content",
-                                            ),
-                                            cx,
-                                        );
+                                        push_notification(NotificationType::Error, ts!("modrinth_install.unknown_content"), window, cx);
                                     }
                                 }
                             }),
                     )
+                    .children(quick_install)
+                    .child(crate::pages::following_page::follow_button(
+                        ("follow", index),
+                        hit.project_id.clone(),
+                        hit.title.clone().unwrap_or_else(|| rust_i18n::t!("following.unnamed").into()),
+                        hit.project_type,
+                        hit.icon_url.clone(),
+                        &self.data,
+                        cx,
+                    ))
                     .child(
                         Button::new(("open", index))
-                            .label("Open Page")
+                            .label(ts!("modrinth_search.open_page"))
                             .icon(IconName::Globe)
                             .info()
                             .on_click({
@@ -513,7 +721,18 @@ impl ModrinthSearchPage {
                                     .items_end()
                                     .line_clamp(1)
                                     .text_lg()
-                                    .child(name)
+                                    .child(div()
+                                        .id(("name", index))
+                                        .cursor_pointer()
+                                        .hover(|style| style.underline())
+                                        .child(name.clone())
+                                        .on_click(cx.listener({
+                                            let project_id = hit.project_id.clone();
+                                            let project_type = hit.project_type;
+                                            move |page, _, _, cx| {
+                                                page.open_project(project_id.clone(), name.clone(), project_type, cx);
+                                            }
+                                        })))
                                     .child(author_line),
                             )
                             .child(
@@ -542,6 +761,13 @@ impl ModrinthSearchPage {
         items
     }
 
+    fn open_project(&mut self, project_id: Arc<str>, name: SharedString, project_type: ModrinthProjectType, cx: &mut Context<Self>) {
+        let install_for = self.install_for;
+        let data = self.data.clone();
+        self.project_page = Some(cx.new(|cx| ModrinthProjectPage::new(project_id, name, project_type, install_for, &data, cx)));
+        cx.notify();
+    }
+
     fn get_primary_action(&self, project_id: &str, cx: &App) -> PrimaryAction {
         let install_latest = self.can_install_latest && !InterfaceConfig::get(cx).modrinth_install_normally;
 
@@ -598,15 +824,15 @@ enum PrimaryAction {
 }
 
 impl PrimaryAction {
-    pub fn text(&self) -> &'static str {
+    pub fn text(&self) -> SharedString {
         match self {
-            PrimaryAction::Install => "Install",
-            PrimaryAction::Reinstall => "Reinstall",
-            PrimaryAction::InstallLatest => "Install Latest",
-            PrimaryAction::CheckForUpdates => "Update Check",
-            PrimaryAction::ErrorCheckingForUpdates => "Error",
-            PrimaryAction::UpToDate => "Up-to-date",
-            PrimaryAction::Update(..) => "Update",
+            PrimaryAction::Install => ts!("common.install"),
+            PrimaryAction::Reinstall => ts!("modrinth_search.reinstall"),
+            PrimaryAction::InstallLatest => ts!("modrinth_install.install_latest"),
+            PrimaryAction::CheckForUpdates => ts!("modrinth_search.update_check"),
+            PrimaryAction::ErrorCheckingForUpdates => ts!("modrinth_search.error"),
+            PrimaryAction::UpToDate => ts!("modrinth_search.up_to_date"),
+            PrimaryAction::Update(..) => ts!("modrinth_search.update"),
         }
     }
 
@@ -637,7 +863,16 @@ impl PrimaryAction {
 
 impl Render for ModrinthSearchPage {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let can_load_more = self.total_hits > self.hits.len();
+        if let Some(project_page) = self.project_page.clone() {
+            let back = Button::new("back").outline().icon(IconName::ArrowLeft).label(ts!("modrinth_project.back_to_search")).on_click(cx.listener(|page, _, _, cx| {
+                page.project_page = None;
+                cx.notify();
+            }));
+            return ui::page(cx, self.page_path.create_breadcrumb(&self.data, cx))
+                .child(v_flex().size_full().p_3().gap_3().child(h_flex().child(back)).child(project_page));
+        }
+
+        let can_load_more = self.total_hits > self.next_offset;
         let scroll_handle = self.scroll_handle.clone();
 
         let item_count = self.hits.len() + if can_load_more || self.search_error.is_some() { 1 } else { 0 };
@@ -666,17 +901,23 @@ impl Render for ModrinthSearchPage {
         let mut top_bar = h_flex()
             .w_full()
             .gap_3()
-            .child(Input::new(&self.search_state));
+            .child(Input::new(&self.search_state))
+            .child(div().w_48().flex_shrink_0().child(Select::new(&self.game_version_select).title_prefix(ts!("modrinth_search.version_prefix"))))
+            .child(div().w_48().flex_shrink_0().child(Select::new(&self.sort_select).title_prefix(ts!("modrinth_search.sort_prefix"))))
+            .child(Checkbox::new("open-source")
+                .label(ts!("modrinth_search.open_source"))
+                .checked(self.filter_open_source)
+                .on_click(cx.listener(|page, value, _, cx| page.set_filter_open_source(*value, cx))));
 
 
         if self.can_install_latest {
             let tooltip = |window: &mut Window, cx: &mut App| {
-                Tooltip::new(SharedString::new_static("Always install the latest version. Untick to be able to choose older versions of content to install")).build(window, cx)
+                Tooltip::new(ts!("modrinth_search.install_latest_tooltip")).build(window, cx)
             };
 
             let install_latest = !InterfaceConfig::get(cx).modrinth_install_normally;
             top_bar = top_bar.child(Checkbox::new("install-latest")
-                .label("Install Latest")
+                .label(ts!("modrinth_install.install_latest"))
                 .tooltip(tooltip)
                 .checked(install_latest)
                 .on_click({
@@ -687,28 +928,41 @@ impl Render for ModrinthSearchPage {
             );
         }
 
+        let rate_limit_remaining = self.data.metadata.read(cx).rate_limit_remaining(MODRINTH_API_HOST)
+            .filter(|_| self.loading.is_some());
+        let rate_limit_message = rate_limit_remaining.map(|remaining| {
+            self._rate_limit_countdown_task = cx.spawn(async |page, cx| {
+                gpui::Timer::after(Duration::from_secs(1)).await;
+                let _ = page.update(cx, |_, cx| cx.notify());
+            });
+            SharedString::new(rust_i18n::t!("modrinth_search.rate_limited", seconds = remaining.as_secs() + 1))
+        });
+
         let theme = cx.theme();
         let content = v_flex()
             .size_full()
             .gap_3()
             .child(top_bar)
+            .when_some(rate_limit_message, |content, message| {
+                content.child(div().text_sm().text_color(theme.warning).child(message))
+            })
             .child(div().size_full().rounded_lg().border_1().border_color(theme.border).child(list));
 
         let type_button_group = ButtonGroup::new("type")
             .layout(Axis::Vertical)
             .outline()
-            .child(Button::new("mods").label("Mods").selected(self.filter_project_type == ModrinthProjectType::Mod))
+            .child(Button::new("mods").label(ts!("modrinth_search.mods")).selected(self.filter_project_type == ModrinthProjectType::Mod))
             .child(
                 Button::new("modpacks")
-                    .label("Modpacks")
+                    .label(ts!("modrinth_search.modpacks"))
                     .selected(self.filter_project_type == ModrinthProjectType::Modpack),
             )
             .child(
                 Button::new("resourcepacks")
-                    .label("Resourcepacks")
+                    .label(ts!("modrinth_search.resourcepacks"))
                     .selected(self.filter_project_type == ModrinthProjectType::Resourcepack),
             )
-            .child(Button::new("shaders").label("Shaders").selected(self.filter_project_type == ModrinthProjectType::Shader))
+            .child(Button::new("shaders").label(ts!("modrinth_search.shaders")).selected(self.filter_project_type == ModrinthProjectType::Shader))
             .on_click(cx.listener(|page, clicked: &Vec<usize>, window, cx| match clicked[0] {
                 0 => page.set_project_type(ModrinthProjectType::Mod, window, cx),
                 1 => page.set_project_type(ModrinthProjectType::Modpack, window, cx),
@@ -722,9 +976,9 @@ impl Render for ModrinthSearchPage {
                 .layout(Axis::Vertical)
                 .outline()
                 .multiple(true)
-                .child(Button::new("fabric").label("Fabric").selected(self.filter_loaders.contains(&Loader::Fabric)))
-                .child(Button::new("forge").label("Forge").selected(self.filter_loaders.contains(&Loader::Forge)))
-                .child(Button::new("neoforge").label("NeoForge").selected(self.filter_loaders.contains(&Loader::NeoForge)))
+                .child(Button::new("fabric").label(Loader::Fabric.name()).selected(self.filter_loaders.contains(&Loader::Fabric)))
+                .child(Button::new("forge").label(Loader::Forge.name()).selected(self.filter_loaders.contains(&Loader::Forge)))
+                .child(Button::new("neoforge").label(Loader::NeoForge.name()).selected(self.filter_loaders.contains(&Loader::NeoForge)))
                 .on_click(cx.listener(|page, clicked: &Vec<usize>, window, cx| {
                     page.set_filter_loaders(clicked.iter().filter_map(|index| match index {
                         0 => Some(Loader::Fabric),
@@ -737,13 +991,28 @@ impl Render for ModrinthSearchPage {
             None
         };
 
-        let categories = match self.filter_project_type {
-            ModrinthProjectType::Mod => FILTER_MOD_CATEGORIES,
-            ModrinthProjectType::Modpack => FILTER_MODPACK_CATEGORIES,
-            ModrinthProjectType::Resourcepack => FILTER_RESOURCEPACK_CATEGORIES,
-            ModrinthProjectType::Shader => FILTER_SHADERPACK_CATEGORIES,
-            ModrinthProjectType::Other => &[],
+        let category_tags: FrontendMetadataResult<ModrinthCategoryTags> = self.category_tags.read(cx).result();
+        let fetched_categories = match category_tags {
+            FrontendMetadataResult::Loaded(tags) => {
+                let mut categories: Vec<&'static str> = tags.0.iter()
+                    .filter(|tag| tag.project_type == self.filter_project_type && &*tag.header == "categories")
+                    .map(|tag| tag.name.as_str())
+                    .collect();
+                categories.sort_unstable();
+                (!categories.is_empty()).then_some(categories)
+            },
+            FrontendMetadataResult::Loading | FrontendMetadataResult::Error(_) => None,
         };
+        let categories: Arc<[&'static str]> = fetched_categories.map(Arc::from).unwrap_or_else(|| {
+            let bundled: &[&'static str] = match self.filter_project_type {
+                ModrinthProjectType::Mod => FILTER_MOD_CATEGORIES,
+                ModrinthProjectType::Modpack => FILTER_MODPACK_CATEGORIES,
+                ModrinthProjectType::Resourcepack => FILTER_RESOURCEPACK_CATEGORIES,
+                ModrinthProjectType::Shader => FILTER_SHADERPACK_CATEGORIES,
+                ModrinthProjectType::Other => &[],
+            };
+            bundled.into()
+        });
 
         let category = if self.show_categories.load(std::sync::atomic::Ordering::Relaxed) {
             ButtonGroup::new("category_group")
@@ -753,9 +1022,9 @@ impl Render for ModrinthSearchPage {
                 .children(categories.iter().map(|id| {
                     Button::new(*id)
                         .label(if id == &"worldgen" {
-                            "Worldgen".into()
+                            ts!("modrinth_search.worldgen")
                         } else {
-                            ts!(*id)
+                            ts_or_title_case(id)
                         })
                         .when_some(icon_for(id), |this, icon| {
                             this.icon(Icon::empty().path(icon))
@@ -763,12 +1032,12 @@ impl Render for ModrinthSearchPage {
                         .selected(self.filter_categories.contains(id)
                     )
                 }))
-                .on_click(cx.listener(|page, clicked: &Vec<usize>, window, cx| {
+                .on_click(cx.listener(move |page, clicked: &Vec<usize>, window, cx| {
                     page.set_filter_categories(clicked.iter().filter_map(|index| categories.get(*index).map(|s| *s)).collect(), window, cx);
                 })).into_any_element()
         } else {
             let show_categories = self.show_categories.clone();
-            Button::new("show-categories").icon(IconName::ArrowDown).label("Categories").outline().on_click(move |_, _, _| {
+            Button::new("show-categories").icon(IconName::ArrowDown).label(ts!("modrinth_search.categories")).outline().on_click(move |_, _, _| {
                 show_categories.store(true, std::sync::atomic::Ordering::Relaxed);
             }).into_any_element()
         };
@@ -783,16 +1052,26 @@ impl Render for ModrinthSearchPage {
     }
 }
 
-fn format_downloads(downloads: usize) -> String {
-    if downloads >= 1_000_000_000 {
-        format!("{}B Downloads", (downloads / 10_000_000) as f64 / 100.0)
+/// Sort orders with the translation keys of their names
+const SORT_ORDERS: &[(ModrinthSearchIndex, &str)] = &[
+    (ModrinthSearchIndex::Relevance, "modrinth_search.sort_relevance"),
+    (ModrinthSearchIndex::Downloads, "modrinth_search.sort_downloads"),
+    (ModrinthSearchIndex::Follows, "modrinth_search.sort_follows"),
+    (ModrinthSearchIndex::Newest, "modrinth_search.sort_newest"),
+    (ModrinthSearchIndex::Updated, "modrinth_search.sort_updated"),
+];
+
+pub(crate) fn format_downloads(downloads: usize) -> String {
+    let count = if downloads >= 1_000_000_000 {
+        format!("{}B", (downloads / 10_000_000) as f64 / 100.0)
     } else if downloads >= 1_000_000 {
-        format!("{}M Downloads", (downloads / 10_000) as f64 / 100.0)
+        format!("{}M", (downloads / 10_000) as f64 / 100.0)
     } else if downloads >= 10_000 {
-        format!("{}K Downloads", (downloads / 10) as f64 / 100.0)
+        format!("{}K", (downloads / 10) as f64 / 100.0)
     } else {
-        format!("{} Downloads", downloads)
-    }
+        downloads.to_string()
+    };
+    rust_i18n::t!("modrinth_search.downloads", count = count).into_owned()
 }
 
 fn icon_for(str: &str) -> Option<&'static str> {
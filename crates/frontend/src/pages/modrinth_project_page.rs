@@ -0,0 +1,373 @@
+use std::sync::Arc;
+
+use bridge::{instance::InstanceID, meta::MetadataRequest};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, StyledExt, button::{Button, ButtonVariants}, h_flex, skeleton::Skeleton, spinner::Spinner, tab::{Tab, TabBar}, v_flex
+};
+use rustc_hash::FxHashMap;
+use schema::modrinth::{
+    ModrinthProject, ModrinthProjectMembers, ModrinthProjectType, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthVersionStatus, ModrinthVersionType
+};
+
+use crate::{
+    component::{error_alert::ErrorAlert, markdown::Markdown},
+    entity::{DataEntities, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}},
+    ts,
+};
+
+/// Versions shown at once in the versions tab, more are shown on request
+const VERSIONS_PER_PAGE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProjectTab {
+    Description,
+    Gallery,
+    Versions,
+}
+
+/// Details of a Modrinth project shown inside the Modrinth page: the description, gallery, versions
+/// with their changelogs and the license and authors
+pub struct ModrinthProjectPage {
+    data: DataEntities,
+    project_id: Arc<str>,
+    name: SharedString,
+    project_type: ModrinthProjectType,
+    install_for: Option<InstanceID>,
+    project: Entity<FrontendMetadataState>,
+    versions: Entity<FrontendMetadataState>,
+    members: Entity<FrontendMetadataState>,
+    description: Option<Markdown>,
+    changelogs: FxHashMap<Arc<str>, Markdown>,
+    shown_versions: usize,
+    tab: ProjectTab,
+    scroll_handle: ScrollHandle,
+}
+
+impl ModrinthProjectPage {
+    pub fn new(
+        project_id: Arc<str>,
+        name: SharedString,
+        project_type: ModrinthProjectType,
+        install_for: Option<InstanceID>,
+        data: &DataEntities,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let project = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProject(project_id.clone()), cx);
+        let versions = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProjectVersions(ModrinthProjectVersionsRequest {
+            project_id: project_id.clone(),
+            game_versions: None,
+            loaders: None,
+        }), cx);
+        let members = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProjectMembers(project_id.clone()), cx);
+
+        cx.observe(&project, |_, _, cx| cx.notify()).detach();
+        cx.observe(&versions, |_, _, cx| cx.notify()).detach();
+        cx.observe(&members, |_, _, cx| cx.notify()).detach();
+        cx.observe(&data.followed_projects, |_, _, cx| cx.notify()).detach();
+
+        Self {
+            data: data.clone(),
+            project_id,
+            name,
+            project_type,
+            install_for,
+            project,
+            versions,
+            members,
+            description: None,
+            changelogs: FxHashMap::default(),
+            shown_versions: VERSIONS_PER_PAGE,
+            tab: ProjectTab::Description,
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn render_header(&self, project: &ModrinthProject, cx: &mut Context<Self>) -> Div {
+        let theme = cx.theme();
+
+        let icon = if let Some(icon_url) = &project.icon_url && !icon_url.is_empty() {
+            img(SharedUri::from(icon_url)).with_fallback(|| Skeleton::new().rounded_lg().size_16().into_any_element())
+        } else {
+            img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+        };
+
+        let authors: FrontendMetadataResult<ModrinthProjectMembers> = self.members.read(cx).result();
+        let authors = match authors {
+            FrontendMetadataResult::Loaded(members) if !members.0.is_empty() => {
+                let names: Vec<&str> = members.0.iter().map(|member| &*member.user.username).collect();
+                Some(SharedString::new(rust_i18n::t!("modrinth_project.by_authors", authors = names.join(", "))))
+            },
+            _ => None,
+        };
+
+        let license = project.license.as_ref().map(|license| {
+            let name = license.name.as_ref().filter(|name| !name.is_empty()).unwrap_or(&license.id);
+            let label = SharedString::new(rust_i18n::t!("modrinth_project.license", name = name));
+            match license.url.clone() {
+                Some(url) => Button::new("license").link().label(label).on_click(move |_, _, cx| cx.open_url(&url)).into_any_element(),
+                None => div().child(label).into_any_element(),
+            }
+        });
+
+        let mut stats = h_flex().gap_4().text_sm().text_color(theme.muted_foreground);
+        if let Some(downloads) = project.downloads {
+            stats = stats.child(h_flex().gap_0p5().child(Icon::empty().path("icons/download.svg")).child(super::modrinth_page::format_downloads(downloads)));
+        }
+        if let Some(followers) = project.followers {
+            stats = stats.child(SharedString::new(rust_i18n::t!("modrinth_project.followers", count = followers)));
+        }
+        if let Some(updated) = project.updated {
+            stats = stats.child(SharedString::new(rust_i18n::t!("modrinth_project.updated", date = updated.format("%Y-%m-%d"))));
+        }
+        if let Some(license) = license {
+            stats = stats.child(license);
+        }
+
+        let links = [
+            ("source", ts!("modrinth_project.source"), &project.source_url),
+            ("issues", ts!("modrinth_project.issues"), &project.issues_url),
+            ("wiki", ts!("modrinth_project.wiki"), &project.wiki_url),
+            ("discord", ts!("modrinth_project.discord"), &project.discord_url),
+        ];
+        let links = h_flex().gap_2().children(links.into_iter().filter_map(|(id, label, url)| {
+            let url = url.clone().filter(|url| !url.is_empty())?;
+            Some(Button::new(id).outline().small().label(label).on_click(move |_, _, cx| cx.open_url(&url)))
+        }));
+
+        let install = (self.project_type != ModrinthProjectType::Other).then(|| {
+            let data = self.data.clone();
+            let name = self.name.clone();
+            let project_id = self.project_id.clone();
+            let project_type = self.project_type;
+            let install_for = self.install_for;
+            Button::new("install")
+                .success()
+                .icon(Icon::empty().path("icons/download.svg"))
+                .label(ts!("common.install"))
+                .on_click(move |_, window, cx| {
+                    crate::modals::modrinth_install::open(name.as_str(), project_id.clone(), project_type, install_for, &data, window, cx);
+                })
+        });
+
+        let follow = crate::pages::following_page::follow_button(
+            "follow",
+            self.project_id.clone(),
+            project.title.clone().unwrap_or_else(|| Arc::from(self.name.as_str())),
+            self.project_type,
+            project.icon_url.clone(),
+            &self.data,
+            cx,
+        );
+
+        let open_page = Button::new("open").info().icon(IconName::Globe).label(ts!("modrinth_project.open_in_browser")).on_click({
+            let url = format!("https://modrinth.com/{}/{}", self.project_type.as_str(), project.slug.as_ref().unwrap_or(&self.project_id));
+            move |_, _, cx| cx.open_url(&url)
+        });
+
+        h_flex()
+            .gap_4()
+            .items_start()
+            .child(icon.rounded_lg().size_16().min_w_16().min_h_16())
+            .child(v_flex()
+                .flex_1()
+                .gap_1()
+                .child(h_flex()
+                    .gap_2()
+                    .items_end()
+                    .child(div().text_2xl().child(self.name.clone()))
+                    .when_some(authors, |this, authors| this.child(div().pb_0p5().text_color(theme.muted_foreground).child(authors))))
+                .when_some(project.description.clone(), |this, description| this.child(SharedString::new(description)))
+                .child(stats)
+                .child(links))
+            .child(v_flex().gap_2().when_some(install, |this, install| this.child(install)).child(follow).child(open_page))
+    }
+
+    fn render_gallery(&self, project: &ModrinthProject, cx: &App) -> Div {
+        let Some(gallery) = project.gallery.as_ref().filter(|gallery| !gallery.is_empty()) else {
+            return div().child(ts!("modrinth_project.no_gallery"));
+        };
+
+        let mut images: Vec<_> = gallery.iter().collect();
+        images.sort_by_key(|image| (!image.featured, image.ordering.unwrap_or(0)));
+
+        let theme = cx.theme();
+        h_flex().flex_wrap().gap_4().items_start().children(images.into_iter().map(|image| {
+            v_flex()
+                .w(px(360.0))
+                .gap_1()
+                .child(img(SharedUri::from(&image.url))
+                    .w_full()
+                    .rounded_lg()
+                    .with_fallback(|| Skeleton::new().w_full().h_48().rounded_lg().into_any_element()))
+                .when_some(image.title.clone(), |this, title| this.child(div().font_bold().child(SharedString::new(title))))
+                .when_some(image.description.clone(), |this, description| {
+                    this.child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(description)))
+                })
+        }))
+    }
+
+    fn render_versions(&mut self, cx: &mut Context<Self>) -> AnyElement {
+        let versions: FrontendMetadataResult<ModrinthProjectVersionsResult> = self.versions.read(cx).result();
+        let versions: Vec<ModrinthProjectVersion> = match versions {
+            FrontendMetadataResult::Loading => return Spinner::new().into_any_element(),
+            FrontendMetadataResult::Error(error) => {
+                return ErrorAlert::new("versions_error", ts!("modrinth_project.versions_failed"), error).into_any_element();
+            },
+            FrontendMetadataResult::Loaded(versions) => versions.0.iter()
+                .filter(|version| version.status.is_none_or(|status| matches!(status, ModrinthVersionStatus::Listed | ModrinthVersionStatus::Archived)))
+                .cloned()
+                .collect(),
+        };
+
+        if versions.is_empty() {
+            return div().child(ts!("modrinth_project.no_versions")).into_any_element();
+        }
+
+        let total = versions.len();
+        let theme = cx.theme();
+        let mut list = v_flex().gap_2();
+        for (index, version) in versions.into_iter().take(self.shown_versions).enumerate() {
+            let name = version.version_number.clone().or(version.name.clone()).unwrap_or(version.id.clone());
+            let version_type = match version.version_type {
+                Some(ModrinthVersionType::Beta) => Some((ts!("modrinth_project.beta"), theme.warning)),
+                Some(ModrinthVersionType::Alpha) => Some((ts!("modrinth_project.alpha"), theme.danger)),
+                _ => None,
+            };
+
+            let mut details = Vec::new();
+            if let Some(loaders) = &version.loaders && !loaders.is_empty() {
+                details.push(loaders.iter().map(|loader| loader.pretty_name()).collect::<Vec<_>>().join(", "));
+            }
+            if let Some(game_versions) = &version.game_versions && !game_versions.is_empty() {
+                details.push(summarize_game_versions(game_versions));
+            }
+            if let Some(date_published) = version.date_published {
+                details.push(date_published.format("%Y-%m-%d").to_string());
+            }
+            if let Some(downloads) = version.downloads {
+                details.push(super::modrinth_page::format_downloads(downloads));
+            }
+
+            let changelog = self.changelogs.get(&version.id).map(|changelog| {
+                changelog.render(cx)
+                    .mt_2()
+                    .pt_2()
+                    .border_t_1()
+                    .border_color(theme.border)
+            });
+            let has_changelog = version.changelog.as_ref().is_some_and(|changelog| !changelog.trim().is_empty());
+
+            list = list.child(v_flex()
+                .p_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(theme.border)
+                .bg(theme.background)
+                .child(h_flex()
+                    .gap_2()
+                    .child(v_flex()
+                        .flex_1()
+                        .child(h_flex()
+                            .gap_2()
+                            .child(div().font_bold().child(SharedString::new(name)))
+                            .when_some(version_type, |this, (label, color)| this.child(div().text_sm().text_color(color).child(label))))
+                        .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(details.join(" • ")))))
+                    .when(has_changelog, |this| {
+                        let expanded = changelog.is_some();
+                        this.child(Button::new(("changelog", index))
+                            .outline()
+                            .small()
+                            .icon(if expanded { IconName::ChevronUp } else { IconName::ChevronDown })
+                            .label(ts!("modrinth_project.changelog"))
+                            .on_click(cx.listener(move |page, _, _, cx| {
+                                if page.changelogs.remove(&version.id).is_none() {
+                                    let changelog = version.changelog.as_deref().unwrap_or_default();
+                                    page.changelogs.insert(version.id.clone(), Markdown::parse(changelog));
+                                }
+                                cx.notify();
+                            })))
+                    }))
+                .children(changelog));
+        }
+
+        if total > self.shown_versions {
+            list = list.child(Button::new("more_versions")
+                .outline()
+                .label(SharedString::new(rust_i18n::t!("modrinth_project.show_more", count = total - self.shown_versions)))
+                .on_click(cx.listener(|page, _, _, cx| {
+                    page.shown_versions += VERSIONS_PER_PAGE;
+                    cx.notify();
+                })));
+        }
+
+        list.into_any_element()
+    }
+}
+
+impl Render for ModrinthProjectPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let project: FrontendMetadataResult<ModrinthProject> = self.project.read(cx).result();
+        let project = match project {
+            FrontendMetadataResult::Loading => {
+                return v_flex().size_full().p_4().child(h_flex().gap_2().child(Spinner::new()).child(ts!("modrinth_project.loading")));
+            },
+            FrontendMetadataResult::Error(error) => {
+                return v_flex().size_full().p_4().child(ErrorAlert::new("project_error", ts!("modrinth_project.request_failed"), error));
+            },
+            FrontendMetadataResult::Loaded(project) => project.clone(),
+        };
+
+        if self.description.is_none() {
+            self.description = Some(Markdown::parse(project.body.as_deref().unwrap_or_default()));
+        }
+
+        let selected_index = match self.tab {
+            ProjectTab::Description => 0,
+            ProjectTab::Gallery => 1,
+            ProjectTab::Versions => 2,
+        };
+        let tab_bar = TabBar::new("project_tabs")
+            .selected_index(selected_index)
+            .underline()
+            .child(Tab::new().label(ts!("modrinth_project.description")))
+            .child(Tab::new().label(ts!("modrinth_project.gallery")))
+            .child(Tab::new().label(ts!("modrinth_project.versions")))
+            .on_click(cx.listener(|page, index: &usize, _, cx| {
+                page.tab = match *index {
+                    1 => ProjectTab::Gallery,
+                    2 => ProjectTab::Versions,
+                    _ => ProjectTab::Description,
+                };
+                cx.notify();
+            }));
+
+        let content = match self.tab {
+            ProjectTab::Description => self.description.as_ref().unwrap().render(cx).into_any_element(),
+            ProjectTab::Gallery => self.render_gallery(&project, cx).into_any_element(),
+            ProjectTab::Versions => self.render_versions(cx),
+        };
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(self.render_header(&project, cx))
+            .child(tab_bar)
+            .child(div()
+                .id("project_content")
+                .flex_1()
+                .pr_3()
+                .overflow_y_scroll()
+                .track_scroll(&self.scroll_handle)
+                .child(content))
+    }
+}
+
+/// Joins the game versions, collapsing long lists into the oldest and newest version
+pub fn summarize_game_versions(game_versions: &[ustr::Ustr]) -> String {
+    if game_versions.len() <= 3 {
+        game_versions.iter().map(|version| version.as_str()).collect::<Vec<_>>().join(", ")
+    } else {
+        format!("{} – {}", game_versions[0], game_versions[game_versions.len() - 1])
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, message::{CleanupResult, DiskUsage, MessageToBackend, StorageCleanup}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, notification::NotificationType, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, IconName, Sizable, WindowExt
+};
+
+use parking_lot::RwLock;
+
+use crate::{component::progress_bar::ProgressBar, entity::{notifications::push_notification, DataEntities}, ts, ui};
+
+/// Breakdown of the launcher's disk usage, with actions to remove files that are no longer needed
+pub struct StoragePage {
+    backend_handle: BackendHandle,
+    usage: Option<DiskUsage>,
+    cleaning: Option<StorageCleanup>,
+    _get_disk_usage_task: Task<()>,
+    _cleanup_task: Task<()>,
+}
+
+impl StoragePage {
+    pub fn new(data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut page = Self {
+            backend_handle: data.backend_handle.clone(),
+            usage: None,
+            cleaning: None,
+            _get_disk_usage_task: Task::ready(()),
+            _cleanup_task: Task::ready(()),
+        };
+
+        page.refresh(cx);
+
+        page
+    }
+
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.usage = None;
+        self._get_disk_usage_task = cx.spawn(async move |page, cx| {
+            let result: DiskUsage = recv.await.unwrap_or_default();
+            let _ = page.update(cx, move |page, cx| {
+                page.usage = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetDiskUsage {
+            channel: send,
+        });
+    }
+
+    fn cleanup(&mut self, cleanup: StorageCleanup, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.cleaning = Some(cleanup);
+        self._cleanup_task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Backend didn't respond".into()));
+            let _ = page.update_in(cx, move |page, window, cx| {
+                page.cleaning = None;
+                match result {
+                    Ok(result) => {
+                        let message = SharedString::new(rust_i18n::t!("storage.cleaned_up", files = result.removed_files, size = crate::format_size(result.freed_bytes)));
+                        push_notification(NotificationType::Success, message, window, cx);
+                    },
+                    Err(error) => {
+                        let message = SharedString::new(rust_i18n::t!("storage.cleanup_failed", error = error));
+                        push_notification(NotificationType::Error, message, window, cx);
+                    },
+                }
+                page.refresh(cx);
+            });
+        });
+        cx.notify();
+
+        self.backend_handle.send(MessageToBackend::CleanupStorage {
+            cleanup,
+            preview: false,
+            channel: send,
+        });
+    }
+
+    fn confirm_cleanup(&self, cleanup: StorageCleanup, window: &mut Window, cx: &mut Context<Self>) {
+        let (title, message) = match cleanup {
+            StorageCleanup::UnusedAssets => (
+                ts!("storage.remove_unused_assets"),
+                ts!("storage.remove_unused_assets_description"),
+            ),
+            StorageCleanup::OrphanedLibraries => (
+                ts!("storage.prune_libraries"),
+                ts!("storage.prune_libraries_description"),
+            ),
+            StorageCleanup::ContentLibrary => (
+                ts!("storage.clear_library_cache"),
+                ts!("storage.clear_library_cache_description"),
+            ),
+            StorageCleanup::ForgeProcessorCache => (
+                ts!("storage.clear_forge_processor_cache"),
+                ts!("storage.clear_forge_processor_cache_description"),
+            ),
+            StorageCleanup::OldLogs => (
+                ts!("storage.remove_old_logs"),
+                ts!("storage.remove_old_logs_description"),
+            ),
+        };
+
+        // What would be removed is listed before anything is deleted
+        let preview: Arc<RwLock<Option<Result<CleanupResult, Arc<str>>>>> = Arc::new(RwLock::new(None));
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::CleanupStorage {
+            cleanup,
+            preview: true,
+            channel: send,
+        });
+        window.spawn(cx, {
+            let preview = preview.clone();
+            async move |cx| {
+                let result = recv.await.unwrap_or_else(|_| Err(rust_i18n::t!("common.backend_no_response").into()));
+                *preview.write() = Some(result);
+                _ = cx.update(|window, _| window.refresh());
+            }
+        }).detach();
+
+        let page = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let page = page.clone();
+            let preview = match &*preview.read() {
+                None => h_flex().gap_2().child(Spinner::new()).child(ts!("storage.preview_loading")),
+                Some(Ok(result)) if result.removed_files == 0 => h_flex().child(ts!("storage.preview_nothing")),
+                Some(Ok(result)) => h_flex().child(SharedString::new(rust_i18n::t!("storage.preview",
+                    files = result.removed_files, size = crate::format_size(result.freed_bytes)))),
+                Some(Err(error)) => h_flex().text_color(cx.theme().danger)
+                    .child(SharedString::new(rust_i18n::t!("storage.cleanup_failed", error = error))),
+            };
+            dialog
+                .confirm()
+                .title(title.clone())
+                .child(v_flex().gap_2().child(message.clone()).child(preview))
+                .on_ok(move |_, window, cx| {
+                    _ = page.update(cx, |page, cx| page.cleanup(cleanup, window, cx));
+                    true
+                })
+        });
+    }
+
+    fn render_cleanup_button(&self, id: &'static str, label: SharedString, cleanup: StorageCleanup, cx: &mut Context<Self>) -> Button {
+        Button::new(id)
+            .label(label)
+            .loading(self.cleaning == Some(cleanup))
+            .disabled(self.cleaning.is_some())
+            .on_click(cx.listener(move |page, _, window, cx| {
+                page.confirm_cleanup(cleanup, window, cx);
+            }))
+    }
+}
+
+impl Render for StoragePage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let refresh = Button::new("refresh").icon(IconName::Redo).label(ts!("storage.refresh")).disabled(self.usage.is_none()).on_click(cx.listener(|page, _, _, cx| {
+            page.refresh(cx);
+        }));
+        let title = h_flex().gap_8().child(ts!("storage.title")).child(refresh);
+
+        let Some(usage) = self.usage else {
+            let content = v_flex().size_full().p_3().child(Spinner::new().with_size(gpui_component::Size::Large));
+            return ui::page(cx, title).child(content).overflow_y_scrollbar();
+        };
+
+        let categories = [
+            (ts!("storage.instances"), usage.instances),
+            (ts!("storage.assets"), usage.assets),
+            (ts!("storage.libraries"), usage.libraries),
+            (ts!("storage.java_runtimes"), usage.java_runtimes),
+            (ts!("storage.library_cache"), usage.content_library),
+            (ts!("storage.metadata"), usage.metadata),
+            (ts!("storage.logs"), usage.logs),
+        ];
+        let total: u64 = categories.iter().map(|(_, size)| size).sum();
+
+        let theme = cx.theme();
+        let rows = categories.into_iter().map(|(label, size)| {
+            let fraction = if total == 0 { 0.0 } else { size as f32 / total as f32 };
+            h_flex()
+                .gap_3()
+                .py_1()
+                .border_b_1()
+                .border_color(theme.border)
+                .child(div().w_32().child(label))
+                .child(div().flex_1().child(ProgressBar { amount: fraction, ..ProgressBar::new() }))
+                .child(div().w_24().child(crate::format_size(size)))
+        }).collect::<Vec<_>>();
+
+        let summary = rust_i18n::t!("storage.total", size = crate::format_size(total)).into_owned();
+        let summary = div().pb_2().text_color(theme.muted_foreground).child(summary);
+
+        let cleanup = h_flex().gap_2()
+            .child(self.render_cleanup_button("remove-unused-assets", ts!("storage.remove_unused_assets"), StorageCleanup::UnusedAssets, cx))
+            .child(self.render_cleanup_button("prune-libraries", ts!("storage.prune_libraries"), StorageCleanup::OrphanedLibraries, cx))
+            .child(self.render_cleanup_button("clear-forge-processor-cache", ts!("storage.clear_forge_processor_cache"), StorageCleanup::ForgeProcessorCache, cx))
+            .child(self.render_cleanup_button("remove-old-logs", ts!("storage.remove_old_logs"), StorageCleanup::OldLogs, cx))
+            .child(self.render_cleanup_button("clear-library-cache", ts!("storage.clear_library_cache"), StorageCleanup::ContentLibrary, cx).danger());
+
+        let content = v_flex().size_full().p_3().gap_1()
+            .child(summary)
+            .children(rows)
+            .child(div().pt_4().child(crate::labelled(ts!("storage.clean_up"), cleanup)));
+
+        ui::page(cx, title).child(content).overflow_y_scrollbar()
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 
 use atomic_time::AtomicInstant;
 use gpui::{App, RenderImage};
-use image::{imageops::FilterType, Frame};
+use image::{imageops::FilterType, Frame, RgbaImage};
 use intrusive_collections::{LinkedList, LinkedListLink, intrusive_adapter};
 use rustc_hash::FxHashMap;
 
@@ -19,13 +19,22 @@ pub enum ImageTransformation {
     }
 }
 
+enum DecodeState {
+    Decoding,
+    Decoded {
+        image: Arc<RenderImage>,
+        bytes: usize,
+    },
+    Failed,
+}
+
 struct CacheEntry {
     link: LinkedListLink,
-    ptrs: Mutex<Vec<usize>>,
-    source: Arc<[u8]>,
+    /// Every source with these contents that was rendered, kept alive so their addresses can't be reused
+    sources: Mutex<Vec<Arc<[u8]>>>,
     transform: ImageTransformation,
     expiring: AtomicInstant,
-    value: Option<Arc<RenderImage>>,
+    state: Mutex<DecodeState>,
 }
 
 intrusive_adapter!(CacheEntryAdapter = Rc<CacheEntry>: CacheEntry { link: LinkedListLink });
@@ -33,14 +42,28 @@ intrusive_adapter!(CacheEntryAdapter = Rc<CacheEntry>: CacheEntry { link: Linked
 #[derive(Default)]
 struct PngRenderCache {
     by_ptr: FxHashMap<(usize, ImageTransformation), Rc<CacheEntry>>,
-    by_arc: FxHashMap<(Arc<[u8]>, ImageTransformation), Rc<CacheEntry>>,
+    /// Identical images, e.g. the same world icon in several instances, share an entry
+    by_contents: FxHashMap<(Arc<[u8]>, ImageTransformation), Rc<CacheEntry>>,
+    /// Least recently rendered first
     expiring: LinkedList<CacheEntryAdapter>,
+    decoded_bytes: usize,
+    /// Shown while an image is decoding
+    placeholder: Option<Arc<RenderImage>>,
     submitted_cleanup: bool,
 }
 
 impl gpui::Global for PngRenderCache {}
 
-const EXPIRY_SECONDS: u64 = 1;
+const EXPIRY_SECONDS: u64 = 30;
+
+/// Decoded images are evicted, least recently rendered first, once they take up more than this
+const MAX_DECODED_BYTES: usize = 128 * 1024 * 1024;
+
+enum Lookup {
+    Cached(Option<Arc<RenderImage>>),
+    Decoding,
+    Decode,
+}
 
 pub fn render(image: Arc<[u8]>, cx: &mut App) -> gpui::Img {
     render_with_transform(image, ImageTransformation::None, cx)
@@ -49,34 +72,29 @@ pub fn render(image: Arc<[u8]>, cx: &mut App) -> gpui::Img {
 pub fn render_with_transform(image: Arc<[u8]>, transform: ImageTransformation, cx: &mut App) -> gpui::Img {
     let cache = cx.default_global::<PngRenderCache>();
 
-    let result = if let Some(result) = cache.get_or_create(image, transform) {
-        gpui::img(result)
-    } else {
-        gpui::img(gpui::ImageSource::Resource(gpui::Resource::Embedded("images/missing.png".into())))
+    let result = match cache.lookup(&image, transform) {
+        Lookup::Cached(Some(render_image)) => gpui::img(render_image),
+        Lookup::Cached(None) => gpui::img(gpui::ImageSource::Resource(gpui::Resource::Embedded("images/missing.png".into()))),
+        Lookup::Decoding => gpui::img(cache.placeholder()),
+        Lookup::Decode => {
+            let placeholder = cache.placeholder();
+            spawn_decode(image, transform, cx);
+            gpui::img(placeholder)
+        },
     };
 
+    let cache = cx.default_global::<PngRenderCache>();
     if !cache.submitted_cleanup {
         cache.submitted_cleanup = true;
         cx.spawn(async |cx| {
             let _ = cx.update_global(|cache: &mut PngRenderCache, cx| {
                 let now = Instant::now();
-                let mut cursor = cache.expiring.front_mut();
-                while let Some(entry) = cursor.get() {
-                    if now > entry.expiring.load(Ordering::Relaxed) {
-                        let entry = cursor.remove().expect("present");
-                        for ptr in entry.ptrs.lock().unwrap().iter() {
-                            cache.by_ptr.remove(&(*ptr, entry.transform)).expect("present");
-                        }
-                        cache.by_arc.remove(&(entry.source.clone(), entry.transform)).expect("present");
-
-                        debug_assert_eq!(Rc::strong_count(&entry), 1);
-
-                        if let Some(image) = &entry.value {
-                            cx.drop_image(image.clone(), None);
-                        }
-                    } else {
+                while let Some(entry) = cache.expiring.front().get() {
+                    if now <= entry.expiring.load(Ordering::Relaxed) {
                         break;
                     }
+                    let entry = cache.expiring.pop_front().expect("present");
+                    cache.remove(entry, cx);
                 }
                 cache.submitted_cleanup = false;
             });
@@ -86,83 +104,139 @@ pub fn render_with_transform(image: Arc<[u8]>, transform: ImageTransformation, c
     result
 }
 
-impl PngRenderCache {
-    fn get_or_create(&mut self, image: Arc<[u8]>, transform: ImageTransformation) -> Option<Arc<RenderImage>> {
-        let ptr = Arc::as_ptr(&image).addr();
-
-        if let Some(result) = self.by_ptr.get(&(ptr, transform)) {
-            // Update expiry
-            result.expiring.store(Instant::now() + Duration::from_secs(EXPIRY_SECONDS), Ordering::Relaxed);
-            unsafe {
-                self.expiring.cursor_mut_from_ptr(Rc::as_ptr(result)).remove();
+fn spawn_decode(image: Arc<[u8]>, transform: ImageTransformation, cx: &mut App) {
+    let decode = cx.background_spawn({
+        let image = Arc::clone(&image);
+        async move { decode(&image, transform) }
+    });
+
+    cx.spawn(async move |cx| {
+        let result = decode.await;
+        let _ = cx.update_global(|cache: &mut PngRenderCache, cx| {
+            // The entry expired while decoding, nothing is showing the image anymore
+            let Some(entry) = cache.by_contents.get(&(image, transform)).cloned() else {
+                return;
+            };
+
+            let mut state = entry.state.lock().unwrap();
+            if !matches!(*state, DecodeState::Decoding) {
+                return;
             }
-            self.expiring.push_back(result.clone());
-
-            return result.value.clone();
-        }
-
-        if let Some(result) = self.by_arc.get(&(image.clone(), transform)) {
-            // Update expiry
-            result.expiring.store(Instant::now() + Duration::from_secs(EXPIRY_SECONDS), Ordering::Relaxed);
-            unsafe {
-                self.expiring.cursor_mut_from_ptr(Rc::as_ptr(result)).remove();
+            *state = match result {
+                Some((image, bytes)) => {
+                    cache.decoded_bytes += bytes;
+                    DecodeState::Decoded { image, bytes }
+                },
+                None => DecodeState::Failed,
+            };
+            drop(state);
+            drop(entry);
+
+            while cache.decoded_bytes > MAX_DECODED_BYTES {
+                let Some(oldest) = cache.expiring.pop_front() else {
+                    break;
+                };
+                cache.remove(oldest, cx);
             }
-            self.expiring.push_back(result.clone());
 
-            // Add ptr
-            result.ptrs.lock().unwrap().push(ptr);
-            self.by_ptr.insert((ptr, transform), result.clone());
+            cx.refresh_windows();
+        });
+    }).detach();
+}
 
-            return result.value.clone();
-        }
+fn decode(image: &[u8], transform: ImageTransformation) -> Option<(Arc<RenderImage>, usize)> {
+    let mut image = match image::load_from_memory_with_format(image, image::ImageFormat::Png) {
+        Ok(image) => image,
+        Err(error) => {
+            log::warn!("Error loading png: {error:?}");
+            return None;
+        },
+    };
 
-        let result = image::load_from_memory_with_format(&image, image::ImageFormat::Png).map(|mut image| {
-            match transform {
-                ImageTransformation::None => {},
-                ImageTransformation::Resize { width, height } => {
-                    let old_width = image.width();
-                    let old_height = image.height();
-                    if old_width != width || old_height != height {
-                        let filter = if width > width || height > height {
-                            FilterType::Lanczos3
-                        } else {
-                            FilterType::Nearest
-                        };
-                        image = image.resize_exact(width, height, filter);
-                    }
-                },
+    match transform {
+        ImageTransformation::None => {},
+        ImageTransformation::Resize { width, height } => {
+            let old_width = image.width();
+            let old_height = image.height();
+            if old_width != width || old_height != height {
+                let filter = if width > old_width || height > old_height {
+                    FilterType::Lanczos3
+                } else {
+                    FilterType::Nearest
+                };
+                image = image.resize_exact(width, height, filter);
             }
+        },
+    }
 
-            let mut data = image.into_rgba8();
+    let mut data = image.into_rgba8();
 
-            // Convert from RGBA to BGRA.
-            for pixel in data.chunks_exact_mut(4) {
-                pixel.swap(0, 2);
-            }
+    // Convert from RGBA to BGRA.
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
 
-            RenderImage::new([Frame::new(data)])
-        });
+    let bytes = data.len();
+    Some((Arc::new(RenderImage::new([Frame::new(data)])), bytes))
+}
 
-        let render_image = match result {
-            Ok(render_image) => Some(Arc::new(render_image)),
-            Err(error) => {
-                log::warn!("Error loading png: {error:?}");
-                None
-            },
+impl PngRenderCache {
+    fn lookup(&mut self, image: &Arc<[u8]>, transform: ImageTransformation) -> Lookup {
+        let ptr = Arc::as_ptr(image).addr();
+
+        let entry = if let Some(entry) = self.by_ptr.get(&(ptr, transform)) {
+            entry.clone()
+        } else if let Some(entry) = self.by_contents.get(&(image.clone(), transform)) {
+            entry.sources.lock().unwrap().push(Arc::clone(image));
+            self.by_ptr.insert((ptr, transform), entry.clone());
+            entry.clone()
+        } else {
+            let entry = Rc::new(CacheEntry {
+                link: LinkedListLink::new(),
+                sources: Mutex::new(vec![Arc::clone(image)]),
+                transform,
+                expiring: AtomicInstant::new(Instant::now() + Duration::from_secs(EXPIRY_SECONDS)),
+                state: Mutex::new(DecodeState::Decoding),
+            });
+            self.by_ptr.insert((ptr, transform), entry.clone());
+            self.by_contents.insert((Arc::clone(image), transform), entry.clone());
+            self.expiring.push_back(entry);
+            return Lookup::Decode;
         };
 
-        let entry = Rc::new(CacheEntry {
-            link: LinkedListLink::new(),
-            ptrs: Mutex::new(vec![ptr]),
-            source: Arc::clone(&image),
-            transform,
-            expiring: AtomicInstant::new(Instant::now() + Duration::from_secs(EXPIRY_SECONDS)),
-            value: render_image.clone(),
-        });
-        self.by_ptr.insert((ptr, transform), entry.clone());
-        self.by_arc.insert((Arc::clone(&image), transform), entry.clone());
-        self.expiring.push_back(entry);
+        // Update expiry
+        entry.expiring.store(Instant::now() + Duration::from_secs(EXPIRY_SECONDS), Ordering::Relaxed);
+        unsafe {
+            self.expiring.cursor_mut_from_ptr(Rc::as_ptr(&entry)).remove();
+        }
+        self.expiring.push_back(entry.clone());
+
+        match &*entry.state.lock().unwrap() {
+            DecodeState::Decoding => Lookup::Decoding,
+            DecodeState::Decoded { image, .. } => Lookup::Cached(Some(image.clone())),
+            DecodeState::Failed => Lookup::Cached(None),
+        }
+    }
+
+    /// Removes an entry that was already unlinked from `expiring`
+    fn remove(&mut self, entry: Rc<CacheEntry>, cx: &mut App) {
+        let sources = std::mem::take(&mut *entry.sources.lock().unwrap());
+        for source in &sources {
+            self.by_ptr.remove(&(Arc::as_ptr(source).addr(), entry.transform)).expect("present");
+        }
+        self.by_contents.remove(&(sources[0].clone(), entry.transform)).expect("present");
+
+        debug_assert_eq!(Rc::strong_count(&entry), 1);
+
+        if let DecodeState::Decoded { image, bytes } = &*entry.state.lock().unwrap() {
+            self.decoded_bytes -= bytes;
+            cx.drop_image(image.clone(), None);
+        }
+    }
 
-        render_image
+    fn placeholder(&mut self) -> Arc<RenderImage> {
+        self.placeholder.get_or_insert_with(|| {
+            Arc::new(RenderImage::new([Frame::new(RgbaImage::new(1, 1))]))
+        }).clone()
     }
 }
@@ -1,25 +1,32 @@
-use std::{collections::HashMap, sync::{Arc, atomic::AtomicBool}};
+use std::{collections::HashMap, path::Path, sync::{Arc, atomic::AtomicBool}};
 
 use bridge::{instance::InstanceStatus, message::{BridgeNotificationType, MessageToFrontend}};
-use gpui::{px, size, AnyWindowHandle, App, AppContext, Entity, SharedString, TitlebarOptions, WindowDecorations, WindowHandle, WindowOptions};
-use gpui_component::{notification::{Notification, NotificationType}, Root, WindowExt};
+use gpui::{px, size, AnyWindowHandle, App, AppContext, Entity, IntoElement, SharedString, TitlebarOptions, WindowDecorations, WindowHandle, WindowOptions};
+use gpui_component::{button::Button, notification::{Notification, NotificationType}, Root, WindowExt};
+use schema::shared_profile::SharedProfile;
 
-use crate::{entity::{DataEntities, account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata}, game_output::{GameOutput, GameOutputRoot}, interface_config::InterfaceConfig};
+use crate::{entity::{DataEntities, account::AccountEntries, backend_settings::BackendSettingsState, following::FollowedProjectsState, install_queue::InstallQueueState, instance::InstanceEntries, lan_games::LanGamesState, launcher_lock::LauncherLockState, launcher_update::LauncherUpdateState, metadata::FrontendMetadata, notifications::{push_notification, NotificationCenter}, search_index::SearchIndex}, game_output::{GameOutput, GameOutputRoot}, interface_config::{InterfaceConfig, LaunchWindowBehavior}, ts};
 
 pub struct Processor {
     data: DataEntities,
     game_output_windows: HashMap<usize, (WindowHandle<Root>, Entity<GameOutput>)>,
     main_window_handle: Option<AnyWindowHandle>,
     main_window_hidden: Arc<AtomicBool>,
+    /// The main window was minimized when a game was launched, and is restored once no game is running
+    main_window_minimized: bool,
+    /// Where game output windows write the lines they evict
+    game_output_spill_dir: Arc<Path>,
 }
 
 impl Processor {
-    pub fn new(data: DataEntities, main_window_handle: AnyWindowHandle, main_window_hidden: Arc<AtomicBool>) -> Self {
+    pub fn new(data: DataEntities, main_window_handle: AnyWindowHandle, main_window_hidden: Arc<AtomicBool>, game_output_spill_dir: Arc<Path>) -> Self {
         Self {
             data,
             game_output_windows: HashMap::new(),
             main_window_handle: Some(main_window_handle),
             main_window_hidden,
+            main_window_minimized: false,
+            game_output_spill_dir,
         }
     }
 
@@ -31,6 +38,9 @@ impl Processor {
             } => {
                 AccountEntries::set(&self.data.accounts, accounts, selected_account, cx);
             },
+            MessageToFrontend::LauncherLockUpdated { enabled, locked, idle_lock_minutes } => {
+                LauncherLockState::set(&self.data.launcher_lock, enabled, locked, idle_lock_minutes, cx);
+            },
             MessageToFrontend::InstanceAdded {
                 id,
                 name,
@@ -41,6 +51,7 @@ impl Processor {
                 mods_state,
                 resource_packs_state,
             } => {
+                SearchIndex::set_instance(&self.data.search_index, id, name.as_str().into(), cx);
                 InstanceEntries::add(
                     &self.data.instances,
                     id,
@@ -55,6 +66,7 @@ impl Processor {
                 );
             },
             MessageToFrontend::InstanceRemoved { id } => {
+                SearchIndex::remove_instance(&self.data.search_index, id, cx);
                 InstanceEntries::remove(&self.data.instances, id, cx);
             },
             MessageToFrontend::InstanceModified {
@@ -65,21 +77,51 @@ impl Processor {
                 status,
             } => {
                 if status == InstanceStatus::Running {
-                    if InterfaceConfig::get(cx).hide_main_window_on_launch {
-                        if let Some(handle) = self.main_window_handle.take() {
-                            self.main_window_hidden.store(true, std::sync::atomic::Ordering::SeqCst);
-                            _ = handle.update(cx, |_, window, _| {
-                                window.remove_window();
-                            });
-                        }
+                    match InterfaceConfig::get(cx).launch_window_behavior {
+                        LaunchWindowBehavior::KeepOpen => {},
+                        LaunchWindowBehavior::Minimize => {
+                            if let Some(handle) = self.main_window_handle {
+                                self.main_window_minimized = true;
+                                _ = handle.update(cx, |_, window, _| {
+                                    window.minimize_window();
+                                });
+                            }
+                        },
+                        LaunchWindowBehavior::Close => {
+                            if let Some(handle) = self.main_window_handle.take() {
+                                self.main_window_hidden.store(true, std::sync::atomic::Ordering::SeqCst);
+                                _ = handle.update(cx, |_, window, _| {
+                                    window.remove_window();
+                                });
+                            }
+                        },
                     }
                 } else if status == InstanceStatus::NotRunning {
-                    if self.main_window_handle.is_none() && self.main_window_hidden.load(std::sync::atomic::Ordering::SeqCst) {
-                        self.main_window_handle = Some(crate::open_main_window(&self.data, None, cx));
-                        self.main_window_hidden.store(false, std::sync::atomic::Ordering::SeqCst);
+                    let others_running = self.data.instances.read(cx).entries.values().any(|entry| {
+                        let entry = entry.read(cx);
+                        // A stopping instance still has its game window open
+                        entry.id != id && matches!(entry.status, InstanceStatus::Running | InstanceStatus::Stopping)
+                    });
+                    if !others_running {
+                        if self.main_window_handle.is_none() && self.main_window_hidden.load(std::sync::atomic::Ordering::SeqCst) {
+                            if self.data.backend_settings.read(cx).settings.quit_on_game_exit {
+                                cx.quit();
+                                return;
+                            }
+                            self.main_window_handle = Some(crate::open_main_window(&self.data, None, cx));
+                            self.main_window_hidden.store(false, std::sync::atomic::Ordering::SeqCst);
+                        } else if self.main_window_minimized {
+                            self.main_window_minimized = false;
+                            if let Some(handle) = self.main_window_handle {
+                                _ = handle.update(cx, |_, window, _| {
+                                    window.activate_window();
+                                });
+                            }
+                        }
                     }
                 }
 
+                SearchIndex::set_instance(&self.data.search_index, id, name.as_str().into(), cx);
                 InstanceEntries::modify(
                     &self.data.instances,
                     id,
@@ -91,18 +133,66 @@ impl Processor {
                 );
             },
             MessageToFrontend::InstanceWorldsUpdated { id, worlds } => {
+                SearchIndex::set_worlds(&self.data.search_index, id, &worlds, cx);
                 InstanceEntries::set_worlds(&self.data.instances, id, worlds, cx);
             },
+            MessageToFrontend::InstanceWorldsUpdatedPartial { id, worlds, total } => {
+                SearchIndex::set_worlds(&self.data.search_index, id, &worlds, cx);
+                InstanceEntries::set_worlds_partial(&self.data.instances, id, worlds, total, cx);
+            },
             MessageToFrontend::InstanceServersUpdated { id, servers } => {
+                SearchIndex::set_servers(&self.data.search_index, id, &servers, cx);
                 InstanceEntries::set_servers(&self.data.instances, id, servers, cx);
             },
+            MessageToFrontend::ServerEditsOverwritten { id, edits } => {
+                let Some(handle) = self.main_window_handle else {
+                    return;
+                };
+                let instance_name = InstanceEntries::find_name_by_id(&self.data.instances, id, cx);
+                let backend_handle = self.data.backend_handle.clone();
+                _ = handle.update(cx, |_, window, cx| {
+                    crate::modals::server_edits_overwritten::open_server_edits_overwritten(id, instance_name, edits, backend_handle, window, cx);
+                });
+            },
             MessageToFrontend::InstanceModsUpdated { id, mods } => {
+                SearchIndex::set_mods(&self.data.search_index, id, &mods, cx);
                 InstanceEntries::set_mods(&self.data.instances, id, mods, cx);
             },
+            MessageToFrontend::InstanceFileWatchingUpdated { id, watching, network_drive } => {
+                InstanceEntries::set_file_watching(&self.data.instances, id, watching, network_drive, cx);
+            },
+            MessageToFrontend::InstanceResourceUsageUpdated { id, usage } => {
+                InstanceEntries::push_resource_usage(&self.data.instances, id, usage, cx);
+            },
             MessageToFrontend::InstanceResourcePacksUpdated { id, resource_packs } => {
                 InstanceEntries::set_resource_packs(&self.data.instances, id, resource_packs, cx);
             },
+            MessageToFrontend::OpenFiles { paths } => {
+                if self.main_window_handle.is_none() && self.main_window_hidden.load(std::sync::atomic::Ordering::SeqCst) {
+                    self.main_window_handle = Some(crate::open_main_window(&self.data, None, cx));
+                    self.main_window_hidden.store(false, std::sync::atomic::Ordering::SeqCst);
+                }
+                let Some(handle) = self.main_window_handle else {
+                    return;
+                };
+                let data = self.data.clone();
+                _ = handle.update(cx, |_, window, cx| {
+                    window.activate_window();
+                    for path in paths.iter() {
+                        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mrpack")) {
+                            crate::modals::install_modpack_file::open_install_modpack_file(path.clone(), &data, window, cx);
+                        } else if path.to_string_lossy().ends_with(SharedProfile::FILE_SUFFIX) {
+                            crate::modals::import_shared_profile::open_import_shared_profile(path.clone(), &data.backend_handle, window, cx);
+                        } else {
+                            let message = rust_i18n::t!("common.unsupported_file", path = path.display());
+                            push_notification(NotificationType::Error, SharedString::new(message), window, cx);
+                        }
+                    }
+                });
+            },
             MessageToFrontend::AddNotification { notification_type, message } => {
+                NotificationCenter::push(&self.data.notifications, notification_type, SharedString::new(message.clone()), cx);
+
                 let Some(handle) = self.main_window_handle else {
                     return;
                 };
@@ -141,14 +231,16 @@ impl Processor {
                     app_id: Some("PandoraLauncher".into()),
                     window_min_size: Some(size(px(360.0), px(240.0))),
                     titlebar: Some(TitlebarOptions {
-                        title: Some(SharedString::new_static("Minecraft Game Output")),
+                        title: Some(ts!("page.game_output")),
                         ..Default::default()
                     }),
                     window_decorations: Some(WindowDecorations::Server),
+                    window_background: crate::theme::window_background(cx),
                     ..Default::default()
                 };
+                let spill_dir = self.game_output_spill_dir.clone();
                 _ = cx.open_window(options, |window, cx| {
-                    let game_output = cx.new(|_| GameOutput::default());
+                    let game_output = cx.new(|_| GameOutput::new(spill_dir));
                     let game_output_root = cx
                         .new(|cx| GameOutputRoot::new(keep_alive, game_output.clone(), window, cx));
                     window.activate_window();
@@ -157,16 +249,11 @@ impl Processor {
                     cx.new(|cx| Root::new(game_output_root, window, cx))
                 });
             },
-            MessageToFrontend::AddGameOutput {
-                id,
-                time,
-                level,
-                text,
-            } => {
+            MessageToFrontend::AddGameOutput { id, lines } => {
                 if let Some((window, game_output)) = self.game_output_windows.get(&id) {
                     _ = window.update(cx, |_, window, cx| {
                         game_output.update(cx, |game_output, _| {
-                            game_output.add(time, level, text);
+                            game_output.add(lines);
                         });
                         window.refresh();
                     });
@@ -178,6 +265,43 @@ impl Processor {
             MessageToFrontend::MetadataResult { request, result, keep_alive_handle } => {
                 FrontendMetadata::set(&self.data.metadata, request, result, keep_alive_handle, cx);
             },
+            MessageToFrontend::InstallQueueUpdated { entries } => {
+                InstallQueueState::set(&self.data.install_queue, entries, cx);
+            },
+            MessageToFrontend::MetadataRateLimited { host, retry_in_secs } => {
+                FrontendMetadata::set_rate_limited(&self.data.metadata, host, retry_in_secs, cx);
+            },
+            MessageToFrontend::BackendSettingsUpdated { settings } => {
+                BackendSettingsState::set(&self.data.backend_settings, settings, cx);
+            },
+            MessageToFrontend::FollowedProjectsUpdated { followed } => {
+                FollowedProjectsState::set(&self.data.followed_projects, followed, cx);
+            },
+            MessageToFrontend::LanGamesUpdated { games } => {
+                LanGamesState::set(&self.data.lan_games, games, cx);
+            },
+            MessageToFrontend::LauncherUpdateAvailable { update } => {
+                let title = SharedString::new(rust_i18n::t!("launcher_update.available", version = update.version));
+                LauncherUpdateState::set(&self.data.launcher_update, update, cx);
+
+                let Some(handle) = self.main_window_handle else {
+                    return;
+                };
+                let data = self.data.clone();
+                _ = handle.update(cx, |_, window, cx| {
+                    let notification = Notification::new()
+                        .with_type(NotificationType::Info)
+                        .title(title)
+                        .content(move |_, _, _| {
+                            let data = data.clone();
+                            Button::new("view-launcher-update").label(ts!("launcher_update.view_changelog")).on_click(move |_, window, cx| {
+                                crate::modals::launcher_update::open_launcher_update(&data, window, cx);
+                            }).into_any_element()
+                        })
+                        .autohide(false);
+                    window.push_notification(notification, cx);
+                });
+            },
         }
     }
 }
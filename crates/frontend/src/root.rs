@@ -1,20 +1,26 @@
-use std::{path::Path, sync::Arc};
+use std::{path::{Path, PathBuf}, sync::{atomic::AtomicBool, Arc}, time::{Duration, Instant}};
 
 use bridge::{
     handle::BackendHandle,
-    install::ContentInstall,
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath},
     instance::{InstanceID, InstanceContentID},
-    message::{MessageToBackend, QuickPlayLaunch},
+    message::{MessageToBackend, QuickPlayLaunch, UnlockResult},
     modal_action::ModalAction,
 };
 use gpui::{prelude::*, *};
-use gpui_component::{breadcrumb::Breadcrumb, scroll::{ScrollableElement, ScrollbarAxis}, v_flex, Root, StyledExt};
+use gpui_component::{
+    breadcrumb::Breadcrumb, button::{Button, ButtonVariants}, input::{Input, InputEvent, InputState}, scroll::{ScrollableElement, ScrollbarAxis}, v_flex, ActiveTheme, Root, StyledExt, WindowExt
+};
 use parking_lot::RwLock;
+use schema::{content::ContentSource, loader::LoaderCompanion};
+use uuid::Uuid;
 
-use crate::{entity::DataEntities, modals, ui::{LauncherUI, PageType}, CloseWindow, MAIN_FONT};
+use crate::{component::{crash_bundle::{CrashBundleReview, WriteCrashBundle}, crash_screen::CrashScreen, title_bar::LauncherTitleBar}, entity::{instance::InstanceEntries, launcher_lock::LauncherLockState, DataEntities}, interface_config::{InterfaceConfig, SavedWindowBounds, ThemeModeSetting}, modals, pages::instance::instance_page::InstanceSubpageType, ts, ui::{LauncherUI, PageType}, CloseWindow, FocusSearch, NavigateBack, NavigateForward, NewInstance, OpenCommandPalette, OpenDebugConsole, OpenFileWatches, MAIN_FONT};
 
 pub struct LauncherRootGlobal {
     pub root: Entity<LauncherRoot>,
+    /// Whether the main window was opened without the titlebar of the operating system, see `has_custom_titlebar`
+    pub custom_titlebar: bool,
 }
 
 impl Global for LauncherRootGlobal {}
@@ -23,8 +29,25 @@ pub struct LauncherRoot {
     pub ui: Entity<LauncherUI>,
     pub panic_message: Arc<RwLock<Option<String>>>,
     pub deadlock_message: Arc<RwLock<Option<String>>>,
+    restart_requested: Arc<AtomicBool>,
+    launcher_log: Arc<Path>,
+    write_crash_bundle: WriteCrashBundle,
+    crash_bundle: Option<Entity<CrashBundleReview>>,
+    instances: Entity<InstanceEntries>,
     pub backend_handle: BackendHandle,
     focus_handle: FocusHandle,
+    launcher_lock: Entity<LauncherLockState>,
+    title_bar: Entity<LauncherTitleBar>,
+    unlock_input: Entity<InputState>,
+    unlock_error: Option<SharedString>,
+    last_activity: Instant,
+    _unlock_task: Task<()>,
+    _idle_task: Task<()>,
+    _launcher_lock_subscription: Subscription,
+    _unlock_input_subscription: Subscription,
+    _window_bounds_subscription: Subscription,
+    _window_appearance_subscription: Subscription,
+    _crash_bundle_subscription: Option<Subscription>,
 }
 
 impl LauncherRoot {
@@ -38,52 +61,273 @@ impl LauncherRoot {
         let focus_handle = cx.focus_handle();
         focus_handle.focus(window, cx);
 
+        let unlock_input = cx.new(|cx| InputState::new(window, cx).masked(true).placeholder(ts!("launcher_lock.secret_placeholder")));
+
+        let _unlock_input_subscription = cx.subscribe_in(&unlock_input, window, |root, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                root.try_unlock(window, cx);
+            }
+        });
+
+        let _launcher_lock_subscription = cx.observe_in(&data.launcher_lock, window, |root, launcher_lock, window, cx| {
+            if launcher_lock.read(cx).locked {
+                window.close_all_dialogs(cx);
+                window.close_sheet(cx);
+            } else {
+                root.last_activity = Instant::now();
+            }
+            cx.notify();
+        });
+
+        let title_bar = cx.new(|cx| LauncherTitleBar::new(launcher_ui.clone(), data, cx));
+
+        let _window_bounds_subscription = cx.observe_window_bounds(window, |_, window, cx| {
+            let bounds = Some(SavedWindowBounds::from_window_bounds(window.window_bounds()));
+            if InterfaceConfig::get(cx).main_window_bounds != bounds {
+                InterfaceConfig::get_mut(cx).main_window_bounds = bounds;
+            }
+        });
+
+        let _window_appearance_subscription = cx.observe_window_appearance(window, |_, _, cx| {
+            if InterfaceConfig::get(cx).theme_mode == ThemeModeSetting::System {
+                crate::theme::apply(cx);
+            }
+        });
+
+        let _idle_task = cx.spawn(async move |root, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(15)).await;
+                if root.update(cx, |root, cx| root.check_idle(cx)).is_err() {
+                    return;
+                }
+            }
+        });
+
         Self {
             ui: launcher_ui,
             panic_message: data.panic_messages.panic_message.clone(),
             deadlock_message: data.panic_messages.deadlock_message.clone(),
+            restart_requested: data.panic_messages.restart_requested.clone(),
+            launcher_log: data.panic_messages.launcher_log.clone(),
+            write_crash_bundle: data.panic_messages.write_crash_bundle,
+            crash_bundle: None,
+            instances: data.instances.clone(),
             backend_handle: data.backend_handle.clone(),
             focus_handle,
+            launcher_lock: data.launcher_lock.clone(),
+            title_bar,
+            unlock_input,
+            unlock_error: None,
+            last_activity: Instant::now(),
+            _unlock_task: Task::ready(()),
+            _idle_task,
+            _launcher_lock_subscription,
+            _unlock_input_subscription,
+            _window_bounds_subscription,
+            _window_appearance_subscription,
+            _crash_bundle_subscription: None,
+        }
+    }
+
+    fn render_crash_screen(&self, title: SharedString, details: Option<String>, cx: &mut Context<Self>) -> AnyElement {
+        let mut crash_screen = CrashScreen::new(title.clone(), self.restart_requested.clone());
+        if let Some(details) = details.clone() {
+            crash_screen = crash_screen.details(details);
+        }
+        crash_screen
+            .on_save_bundle(cx.listener(move |root, _, window, cx| {
+                root.show_crash_bundle(&title, details.as_deref(), window, cx);
+            }))
+            .into_any_element()
+    }
+
+    fn show_crash_bundle(&mut self, title: &str, details: Option<&str>, window: &mut Window, cx: &mut Context<Self>) {
+        let crash_bundle = cx.new(|cx| {
+            CrashBundleReview::new(title, details, &self.instances, &self.launcher_log, self.write_crash_bundle, window, cx)
+        });
+        self._crash_bundle_subscription = Some(cx.subscribe(&crash_bundle, |root, _, _: &DismissEvent, cx| {
+            root.crash_bundle = None;
+            root._crash_bundle_subscription = None;
+            cx.notify();
+        }));
+        self.crash_bundle = Some(crash_bundle);
+        cx.notify();
+    }
+
+    fn check_idle(&mut self, cx: &mut Context<Self>) {
+        let launcher_lock = self.launcher_lock.read(cx);
+        if !launcher_lock.enabled || launcher_lock.locked {
+            return;
+        }
+        let Some(idle_lock_minutes) = launcher_lock.idle_lock_minutes else {
+            return;
+        };
+        if self.last_activity.elapsed() >= Duration::from_secs(idle_lock_minutes as u64 * 60) {
+            self.backend_handle.send(MessageToBackend::LockLauncher);
+        }
+    }
+
+    fn try_unlock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let secret: Arc<str> = self.unlock_input.read(cx).value().as_ref().into();
+        if secret.is_empty() {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._unlock_task = cx.spawn_in(window, async move |root, cx| {
+            let result = recv.await.unwrap_or(UnlockResult::Incorrect);
+            let _ = root.update_in(cx, move |root, window, cx| {
+                root.unlock_error = match result {
+                    UnlockResult::Unlocked => None,
+                    UnlockResult::Incorrect => Some(ts!("launcher_lock.incorrect")),
+                    UnlockResult::Throttled { retry_after_secs } => {
+                        Some(SharedString::new(rust_i18n::t!("launcher_lock.too_many_attempts", seconds = retry_after_secs)))
+                    },
+                };
+                root.unlock_input.update(cx, |input, cx| {
+                    input.set_value("", window, cx);
+                });
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::UnlockLauncher {
+            secret,
+            channel: send,
+        });
+    }
+
+    fn navigate(&mut self, direction: NavigationDirection, window: &mut Window, cx: &mut Context<Self>) {
+        if self.launcher_lock.read(cx).locked {
+            return;
         }
+        self.ui.update(cx, |ui, cx| match direction {
+            NavigationDirection::Back => ui.navigate_back(window, cx),
+            NavigationDirection::Forward => ui.navigate_forward(window, cx),
+        });
+    }
+
+    fn render_lock_screen(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        v_flex()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .child(v_flex()
+                .w_80()
+                .p_4()
+                .gap_3()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .child(div().text_lg().child(ts!("launcher_lock.locked_title")))
+                .child(div().text_sm().text_color(theme.muted_foreground)
+                    .child(ts!("launcher_lock.unlock_description")))
+                .child(Input::new(&self.unlock_input))
+                .when_some(self.unlock_error.clone(), |this, unlock_error| {
+                    this.child(div().text_sm().text_color(theme.danger).child(unlock_error))
+                })
+                .child(Button::new("unlock").label(ts!("launcher_lock.unlock")).success().on_click(cx.listener(|root, _, window, cx| {
+                    root.try_unlock(window, cx);
+                }))))
     }
 }
 
 impl Render for LauncherRoot {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        if let Some(message) = &*self.deadlock_message.read() {
-            let purple = Hsla {
-                h: 0.8333333333,
-                s: 1.,
-                l: 0.25,
-                a: 1.,
-            };
-            return v_flex().size_full().bg(purple).child(message.clone()).overflow_y_scrollbar().into_any_element();
+        if let Some(crash_bundle) = &self.crash_bundle {
+            return crash_bundle.clone().into_any_element();
+        }
+        let deadlock_message = self.deadlock_message.read().clone();
+        if let Some(message) = deadlock_message {
+            return self.render_crash_screen(ts!("crash_screen.deadlocked"), Some(message), cx);
         }
-        if let Some(message) = &*self.panic_message.read() {
-            return v_flex().size_full().bg(gpui::blue()).child(message.clone()).overflow_y_scrollbar().into_any_element();
+        let panic_message = self.panic_message.read().clone();
+        if let Some(message) = panic_message {
+            return self.render_crash_screen(ts!("crash_screen.backend_crashed"), Some(message), cx);
         }
         if self.backend_handle.is_closed() {
-            return v_flex().size_full().bg(gpui::red()).child("Backend has abruptly shutdown").into_any_element();
+            return self.render_crash_screen(ts!("crash_screen.backend_shut_down"), None, cx);
         }
 
         let sheet_layer = Root::render_sheet_layer(window, cx);
         let dialog_layer = Root::render_dialog_layer(window, cx);
         let notification_layer = Root::render_notification_layer(window, cx);
 
+        let custom_titlebar = has_custom_titlebar(window, cx);
+        let title_bar = if custom_titlebar {
+            Some(self.title_bar.clone().into_any_element())
+        } else if has_csd_titlebar(window) {
+            Some(gpui_component::TitleBar::new().child(ts!("common.app_name")).into_any_element())
+        } else {
+            None
+        };
+
+        let content = if self.launcher_lock.read(cx).locked {
+            self.render_lock_screen(cx).into_any_element()
+        } else {
+            self.ui.clone().into_any_element()
+        };
+
         v_flex()
             .size_full()
             .font_family(MAIN_FONT)
-            .when(has_csd_titlebar(window), |this| {
-                this.child(gpui_component::TitleBar::new().child("Pandora"))
-            })
-            .child(self.ui.clone())
+            .children(title_bar)
+            .child(content)
             .children(sheet_layer)
             .children(dialog_layer)
             .children(notification_layer)
             .track_focus(&self.focus_handle)
+            .on_mouse_move(cx.listener(|root, _: &MouseMoveEvent, _, _| {
+                root.last_activity = Instant::now();
+            }))
+            .capture_key_down(cx.listener(|root, _: &KeyDownEvent, _, _| {
+                root.last_activity = Instant::now();
+            }))
             .on_action(|_: &CloseWindow, window, _| {
                 window.remove_window();
             })
+            .on_action(cx.listener(|root, _: &NewInstance, window, cx| {
+                if root.launcher_lock.read(cx).locked {
+                    return;
+                }
+                root.ui.update(cx, |ui, cx| ui.new_instance(window, cx));
+            }))
+            .on_action(cx.listener(|root, _: &FocusSearch, window, cx| {
+                if root.launcher_lock.read(cx).locked {
+                    return;
+                }
+                root.ui.update(cx, |ui, cx| ui.focus_search(window, cx));
+            }))
+            .on_action(cx.listener(|root, _: &OpenCommandPalette, window, cx| {
+                if root.launcher_lock.read(cx).locked {
+                    return;
+                }
+                root.ui.update(cx, |ui, cx| ui.open_command_palette(window, cx));
+            }))
+            .on_action(cx.listener(|root, _: &NavigateBack, window, cx| {
+                root.navigate(NavigationDirection::Back, window, cx);
+            }))
+            .on_action(cx.listener(|root, _: &NavigateForward, window, cx| {
+                root.navigate(NavigationDirection::Forward, window, cx);
+            }))
+            .on_mouse_down(MouseButton::Navigate(NavigationDirection::Back), cx.listener(|root, _: &MouseDownEvent, window, cx| {
+                root.navigate(NavigationDirection::Back, window, cx);
+            }))
+            .on_mouse_down(MouseButton::Navigate(NavigationDirection::Forward), cx.listener(|root, _: &MouseDownEvent, window, cx| {
+                root.navigate(NavigationDirection::Forward, window, cx);
+            }))
+            .on_action(cx.listener(|root, _: &OpenFileWatches, window, cx| {
+                root.ui.update(cx, |ui, cx| {
+                    ui.switch_page(PageType::FileWatches, &[], window, cx);
+                });
+            }))
+            .on_action(cx.listener(|root, _: &OpenDebugConsole, window, cx| {
+                root.ui.update(cx, |ui, cx| {
+                    ui.switch_page(PageType::DebugConsole, &[], window, cx);
+                });
+            }))
             .into_any_element()
     }
 }
@@ -92,8 +336,15 @@ pub fn has_csd_titlebar(window: &Window) -> bool {
     matches!(window.window_decorations(), Decorations::Client { .. })
 }
 
-pub fn sheet_margin_top(window: &Window) -> Pixels {
-    if has_csd_titlebar(window) {
+/// Linux compositors may still draw their own decorations when asked not to, the custom titlebar is only shown
+/// once it's known they don't
+pub fn has_custom_titlebar(window: &Window, cx: &App) -> bool {
+    let requested = cx.try_global::<LauncherRootGlobal>().is_some_and(|global| global.custom_titlebar);
+    requested && (cfg!(any(target_os = "macos", target_os = "windows")) || has_csd_titlebar(window))
+}
+
+pub fn sheet_margin_top(window: &Window, cx: &App) -> Pixels {
+    if has_custom_titlebar(window, cx) || has_csd_titlebar(window) {
         gpui_component::TITLE_BAR_HEIGHT
     } else {
         Pixels::ZERO
@@ -111,8 +362,23 @@ pub fn start_new_account_login(
         modal_action: modal_action.clone(),
     });
 
-    let title = SharedString::new_static("Adding new account");
-    modals::generic::show_modal(window, cx, title, "Error adding account".into(), modal_action);
+    modals::generic::show_modal(window, cx, ts!("accounts.adding"), ts!("accounts.add_failed"), modal_action);
+}
+
+pub fn start_account_reauthentication(
+    uuid: Uuid,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::ReauthenticateAccount {
+        uuid,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_modal(window, cx, ts!("accounts.reauthenticating"), ts!("accounts.reauthenticate_failed"), modal_action);
 }
 
 pub fn start_instance(
@@ -131,8 +397,22 @@ pub fn start_instance(
         modal_action: modal_action.clone(),
     });
 
-    let title: SharedString = format!("Launching {}", name).into();
-    modals::generic::show_modal(window, cx, title, "Error starting instance".into(), modal_action);
+    let title = SharedString::new(rust_i18n::t!("instance.launching_name", name = name));
+    modals::generic::show_modal(window, cx, title, ts!("instance.start_failed"), modal_action);
+}
+
+/// Installs the newest version of the companion that matches the loader and version hints of the install
+pub fn companion_install_file(companion: LoaderCompanion) -> ContentInstallFile {
+    let project_id: Arc<str> = companion.modrinth_project_id.into();
+    ContentInstallFile {
+        replace_old: None,
+        path: ContentInstallPath::Automatic,
+        download: ContentDownload::Modrinth {
+            project_id: project_id.clone(),
+            version_id: None,
+        },
+        content_source: ContentSource::ModrinthProject { project: project_id },
+    }
 }
 
 pub fn start_install(
@@ -148,7 +428,43 @@ pub fn start_install(
         modal_action: modal_action.clone(),
     });
 
-    modals::generic::show_notification(window, cx, "Error installing content".into(), modal_action);
+    modals::generic::show_notification(window, cx, ts!("modrinth_install.install_failed"), modal_action);
+}
+
+pub fn start_export_multimc(
+    id: InstanceID,
+    path: std::path::PathBuf,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::ExportInstanceMultiMC {
+        id,
+        path,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_modal(window, cx, ts!("instance.exporting"), ts!("instance.export_failed"), modal_action);
+}
+
+pub fn start_export_shared_profile(
+    id: InstanceID,
+    path: std::path::PathBuf,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::ExportSharedProfile {
+        id,
+        path,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_notification(window, cx, ts!("shared_profile.export_failed"), modal_action);
 }
 
 pub fn start_update_check(
@@ -164,8 +480,55 @@ pub fn start_update_check(
         modal_action: modal_action.clone(),
     });
 
-    let title: SharedString = "Checking for updates".into();
-    modals::generic::show_modal(window, cx, title, "Error checking for updates".into(), modal_action);
+    modals::generic::show_modal(window, cx, ts!("instance.checking_for_updates"), ts!("following.check_for_updates_failed"), modal_action);
+}
+
+pub fn start_add_local_version(
+    paths: Arc<[PathBuf]>,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::AddLocalVersion {
+        paths,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_modal(window, cx, ts!("instances.adding_custom_version"), ts!("instances.add_custom_version_failed"), modal_action);
+}
+
+pub fn start_verify_instance(
+    id: InstanceID,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::VerifyInstance {
+        id,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_modal(window, cx, ts!("instance.verifying"), ts!("instance.verify_failed"), modal_action);
+}
+
+pub fn start_content_repair(
+    instance: InstanceID,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+
+    backend_handle.send(MessageToBackend::RepairInstanceContent {
+        instance,
+        modal_action: modal_action.clone(),
+    });
+
+    modals::generic::show_modal(window, cx, ts!("instance.repairing_content"), ts!("instance.repair_content_failed"), modal_action);
 }
 
 pub fn update_single_mod(
@@ -183,7 +546,7 @@ pub fn update_single_mod(
         modal_action: modal_action.clone(),
     });
 
-    modals::generic::show_notification(window, cx, "Error downloading update".into(), modal_action);
+    modals::generic::show_notification(window, cx, ts!("instance.update_download_failed"), modal_action);
 }
 
 pub fn upload_log_file(
@@ -199,8 +562,7 @@ pub fn upload_log_file(
         modal_action: modal_action.clone(),
     });
 
-    let title: SharedString = "Uploading log file".into();
-    modals::generic::show_modal(window, cx, title, "Error uploading log file".into(), modal_action);
+    modals::generic::show_modal(window, cx, ts!("logs.uploading"), ts!("logs.upload_failed"), modal_action);
 }
 
 pub fn switch_page(
@@ -217,3 +579,11 @@ pub fn switch_page(
         });
     });
 }
+
+pub fn instance_subpage_switched(page: EntityId, previous: InstanceSubpageType, cx: &mut App) {
+    let Some(global) = cx.try_global::<LauncherRootGlobal>() else {
+        return;
+    };
+    let ui = global.root.read(cx).ui.clone();
+    ui.update(cx, |ui, cx| ui.instance_subpage_switched(page, previous, cx));
+}
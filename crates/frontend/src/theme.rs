@@ -0,0 +1,86 @@
+use gpui::{rgb, App, Hsla, SharedString, Window, WindowAppearance, WindowBackgroundAppearance};
+use gpui_component::{Theme, ThemeMode, ThemeRegistry};
+
+use crate::{interface_config::{InterfaceConfig, ThemeModeSetting}, MAIN_FONT};
+
+/// Accent colors offered in the interface settings
+pub const ACCENT_COLORS: &[u32] = &[
+    0x3b82f6, // blue-500
+    0x8b5cf6, // violet-500
+    0xec4899, // pink-500
+    0xef4444, // red-500
+    0xf97316, // orange-500
+    0xeab308, // yellow-500
+    0x22c55e, // green-500
+    0x14b8a6, // teal-500
+];
+
+/// Applies the theme mode, custom theme and accent color from the interface config
+pub fn apply(cx: &mut App) {
+    let config = InterfaceConfig::get(cx);
+    let mode = match config.theme_mode {
+        ThemeModeSetting::Dark => ThemeMode::Dark,
+        ThemeModeSetting::Light => ThemeMode::Light,
+        ThemeModeSetting::System => match cx.window_appearance() {
+            WindowAppearance::Light | WindowAppearance::VibrantLight => ThemeMode::Light,
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => ThemeMode::Dark,
+        },
+    };
+    let active_theme = config.active_theme.clone();
+    let accent_color = config.accent_color;
+    let vibrancy = config.window_vibrancy && supports_vibrancy();
+
+    Theme::change(mode, None, cx);
+
+    if !active_theme.is_empty()
+        && let Some(theme_config) = ThemeRegistry::global(cx).themes().get(&SharedString::new(active_theme.trim_ascii())).cloned()
+    {
+        Theme::global_mut(cx).apply_config(&theme_config);
+    }
+
+    let theme = Theme::global_mut(cx);
+    theme.font_family = SharedString::new_static(MAIN_FONT);
+    theme.scrollbar_show = gpui_component::scroll::ScrollbarShow::Always;
+
+    if let Some(accent_color) = accent_color {
+        let accent: Hsla = rgb(accent_color).into();
+        theme.primary = accent;
+        theme.primary_hover = Hsla { l: (accent.l + 0.05).min(1.0), ..accent };
+        theme.primary_active = Hsla { l: (accent.l - 0.05).max(0.0), ..accent };
+        theme.primary_foreground = if accent.l > 0.6 { gpui::black() } else { gpui::white() };
+        theme.ring = accent;
+    }
+
+    if vibrancy {
+        // The blurred desktop only shows through where the theme isn't opaque
+        theme.background = theme.background.opacity(0.8);
+        theme.sidebar = theme.sidebar.opacity(0.6);
+        theme.title_bar = theme.title_bar.opacity(0.6);
+    }
+
+    cx.refresh_windows();
+}
+
+/// Blurring the window background is only reliable on macOS and Windows, on Linux it depends on the compositor
+pub fn supports_vibrancy() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+pub fn window_background(cx: &App) -> WindowBackgroundAppearance {
+    if InterfaceConfig::get(cx).window_vibrancy && supports_vibrancy() {
+        WindowBackgroundAppearance::Blurred
+    } else {
+        WindowBackgroundAppearance::Opaque
+    }
+}
+
+/// Applies the vibrancy setting to the windows that are already open. The window that's currently
+/// being updated can't be updated again from here, so it's passed in separately
+pub fn apply_window_background(current_window: &mut Window, cx: &mut App) {
+    let background = window_background(cx);
+    current_window.set_background_appearance(background);
+    for window in cx.windows() {
+        _ = window.update(cx, |_, window, _| window.set_background_appearance(background));
+    }
+    apply(cx);
+}
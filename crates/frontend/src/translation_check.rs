@@ -0,0 +1,188 @@
+//! Fails when a view passes text to the UI as a string literal instead of going through `ts!`/`t!`,
+//! so untranslated strings are caught before they're merged. Only the files in [`VIEW_PATHS`] are
+//! checked. Text that is the same in every language can be let through by ending its line with
+//! `// untranslated`, or by adding it to [`ALLOWED`] when it's used in many places
+
+use std::path::Path;
+
+/// Directories and files under `src` that build views
+const VIEW_PATHS: &[&str] = &["component", "modals", "pages", "root.rs", "ui.rs"];
+
+/// Trailing comment for a literal that shouldn't be translated
+const OPT_OUT: &str = "// untranslated";
+
+/// Calls whose arguments end up as visible text
+const SINKS: &[&str] = &[
+    ".child(",
+    ".label(",
+    ".tooltip(",
+    ".title(",
+    ".title_prefix(",
+    ".placeholder(",
+    ".prefix(",
+    ".suffix(",
+    "labelled(",
+    "PopupMenuItem::new(",
+    "push_notification(",
+    "show_modal(",
+    "show_notification(",
+    "show_notification_with_note(",
+    "set_window_title(",
+    "render_crash_screen(",
+    "SharedString::new_static(",
+];
+
+/// Literals that are shown as they are in every language
+const ALLOWED: &[&str] = &["Roboto Mono", "MiB"];
+
+/// Whether a literal reads as text rather than an id, path, url or acronym
+fn is_text(literal: &str) -> bool {
+    if !literal.chars().any(|c| c.is_ascii_alphabetic()) || ALLOWED.contains(&literal) {
+        return false;
+    }
+    if literal.contains(char::is_whitespace) || literal.ends_with("...") {
+        return true;
+    }
+    literal.starts_with(|c: char| c.is_ascii_uppercase()) && literal.chars().any(|c| c.is_ascii_lowercase())
+}
+
+/// String literals that are direct arguments of the call whose arguments start at `args`,
+/// literals inside nested calls like `ts!(..)` or `Button::new(..)` aren't included
+fn direct_literals(args: &str) -> Vec<&str> {
+    let mut literals = Vec::new();
+    let mut depth = 0;
+    let mut argument_start = true;
+    let mut chars = args.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => {
+                let start = index + 1;
+                let mut end = args.len();
+                while let Some((index, c)) = chars.next() {
+                    match c {
+                        '\\' => _ = chars.next(),
+                        '"' => {
+                            end = index;
+                            break;
+                        },
+                        _ => {},
+                    }
+                }
+                if depth == 0 && argument_start {
+                    literals.push(&args[start..end]);
+                }
+                argument_start = false;
+            },
+            '(' | '[' | '{' => {
+                // `format!("..", ..)` is still a literal argument
+                let is_format = depth == 0 && argument_start && args[..index].trim_end().ends_with("format!");
+                if !is_format {
+                    depth += 1;
+                    argument_start = false;
+                }
+            },
+            ')' | ']' | '}' => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            },
+            ',' if depth == 0 => argument_start = true,
+            c if c.is_whitespace() || (argument_start && "format!".contains(c)) => {},
+            _ => argument_start = false,
+        }
+    }
+    literals
+}
+
+/// Text literals passed to a sink, with the byte offset of each
+fn untranslated(source: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut check = |start: usize| {
+        for literal in direct_literals(&source[start..]) {
+            if is_text(literal) {
+                found.push((literal.as_ptr() as usize - source.as_ptr() as usize, literal));
+            }
+        }
+    };
+    for sink in SINKS {
+        for (index, _) in source.match_indices(sink) {
+            check(index + sink.len());
+        }
+    }
+    // Match arms that pick a label
+    for (index, _) in source.match_indices("=> \"") {
+        check(index + 3);
+    }
+    found
+}
+
+/// Comments are blanked out and everything from the test module on is dropped,
+/// keeping line numbers intact
+fn strip_source(source: &str) -> String {
+    let mut stripped = String::new();
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("#[cfg(test)]") {
+            break;
+        }
+        if !trimmed.starts_with("//") {
+            stripped.push_str(line);
+        }
+        stripped.push('\n');
+    }
+    stripped
+}
+
+/// Text literals that weren't opted out, with the line number of each
+fn check_source(source: &str) -> Vec<(usize, &str)> {
+    let lines: Vec<&str> = source.lines().collect();
+    untranslated(source).into_iter().filter_map(|(offset, literal)| {
+        let line = source[..offset].lines().count();
+        (!lines[line - 1].trim_end().ends_with(OPT_OUT)).then_some((line, literal))
+    }).collect()
+}
+
+fn check_path(path: &Path, found: &mut Vec<String>) {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path).unwrap().flatten() {
+            check_path(&entry.path(), found);
+        }
+    } else if path.extension().is_some_and(|extension| extension == "rs") {
+        let source = strip_source(&std::fs::read_to_string(path).unwrap());
+        for (line, literal) in check_source(&source) {
+            found.push(format!("{}:{}: \"{}\"", path.display(), line, literal));
+        }
+    }
+}
+
+#[test]
+fn ui_strings_are_translated() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut found = Vec::new();
+    for view_path in VIEW_PATHS {
+        let path = src.join(view_path);
+        assert!(path.exists(), "{} doesn't exist, update VIEW_PATHS", path.display());
+        check_path(&path, &mut found);
+    }
+    assert!(found.is_empty(), "Pass these strings through ts! and add them to locales.yml, or end the line with {OPT_OUT}:\n{}", found.join("\n"));
+}
+
+#[test]
+fn literals_are_found() {
+    let literals = |source| untranslated(source).into_iter().map(|(_, literal)| literal).collect::<Vec<_>>();
+    assert_eq!(literals(r#".child(Button::new("save").label("Save"))"#), ["Save"]);
+    assert_eq!(literals(r#".child(format!("{} selected", count))"#), ["{} selected"]);
+    assert_eq!(literals("push_notification(\n    NotificationType::Error,\n    \"Invalid name\",\n    window, cx)"), ["Invalid name"]);
+    assert_eq!(literals(r#"Mode::Http => "Send traffic through a proxy","#), ["Send traffic through a proxy"]);
+    assert!(literals(r#".child(Button::new("save").label(ts!("settings.save")))"#).is_empty());
+    assert!(literals(r#".placeholder("https://api.modrinth.com")"#).is_empty());
+    assert!(literals(r#".child(div().font_family("Roboto Mono").child(details))"#).is_empty());
+    assert!(literals(r#"Keybind::Quit => "secondary-q","#).is_empty());
+}
+
+#[test]
+fn literals_can_opt_out() {
+    let source = ".child(\"Pandora\") // untranslated\n.child(\"Launch game\")\n";
+    assert_eq!(check_source(source), [(2, "Launch game")]);
+}
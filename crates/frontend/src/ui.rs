@@ -3,30 +3,39 @@ use std::sync::Arc;
 use bridge::{instance::InstanceID, message::MessageToBackend};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Disableable, Icon, IconName, WindowExt, button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, resizable::{ResizableState, h_resizable, resizable_panel}, scroll::ScrollableElement, sidebar::SidebarFooter, v_flex
+    ActiveTheme as _, Icon, IconName, Sizable, WindowExt, h_flex, spinner::Spinner, resizable::{ResizableState, h_resizable, resizable_panel}, scroll::ScrollableElement, sidebar::SidebarFooter, v_flex
 };
-use rand::Rng;
 use schema::modrinth::ModrinthProjectType;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::{
     component::{menu::{MenuGroup, MenuGroupItem}, page_path::PagePath}, entity::{
-        DataEntities, instance::{InstanceAddedEvent, InstanceEntries, InstanceModifiedEvent, InstanceMovedToTopEvent, InstanceRemovedEvent}
-    }, interface_config::InterfaceConfig, modals, pages::{instance::instance_page::{InstancePage, InstanceSubpageType}, instances_page::InstancesPage, modrinth_page::ModrinthSearchPage, syncing_page::SyncingPage}, png_render_cache, root
+        DataEntities, instance::{InstanceAddedEvent, InstanceEntries, InstanceModifiedEvent, InstanceMovedToTopEvent, InstanceRemovedEvent}, notifications::NotificationCenter
+    }, interface_config::InterfaceConfig, modals, navigation::{HistoryEntry, NavigationHistory}, pages::{accounts_page::AccountsPage, debug_console_page::DebugConsolePage, file_watches_page::FileWatchesPage, following_page::FollowingPage, instance::instance_page::{InstancePage, InstanceSubpageType}, instances_page::InstancesPage, modrinth_page::ModrinthSearchPage, storage_page::StoragePage, syncing_page::SyncingPage}, png_render_cache, root, ts
 };
 
 pub struct LauncherUI {
     data: DataEntities,
     page: LauncherPage,
+    breadcrumbs: Arc<[PageType]>,
+    history: NavigationHistory,
     sidebar_state: Entity<ResizableState>,
     recent_instances: heapless::Vec<(InstanceID, SharedString), 3>,
     _instance_added_subscription: Subscription,
     _instance_modified_subscription: Subscription,
     _instance_removed_subscription: Subscription,
     _instance_moved_to_top_subscription: Subscription,
+    _install_queue_subscription: Subscription,
+    _launcher_update_subscription: Subscription,
+    _notifications_subscription: Subscription,
+    _followed_projects_subscription: Subscription,
 }
 
+/// Emitted when another page is shown, or the title of the page or the history changes
+pub struct PageChangedEvent;
+
+impl EventEmitter<PageChangedEvent> for LauncherUI {}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum PageType {
     Instances,
@@ -36,9 +45,47 @@ pub enum PageType {
         project_type: Option<ModrinthProjectType>,
     },
     InstancePage(InstanceID, InstanceSubpageType),
+    Following,
+    Storage,
+    Accounts,
+    /// Debug page, only reachable through a keybind
+    FileWatches,
+    /// Debug page, only reachable through a keybind
+    DebugConsole,
 }
 
 impl PageType {
+    pub fn title(&self, data: &DataEntities, cx: &App) -> SharedString {
+        match self {
+            PageType::Instances => ts!("instances.title"),
+            PageType::Syncing => ts!("sidebar.syncing"),
+            PageType::Modrinth { installing_for, .. } => {
+                if installing_for.is_some() {
+                    ts!("page.add_from_modrinth")
+                } else {
+                    "Modrinth".into()
+                }
+            },
+            PageType::InstancePage(instance_id, _) => {
+                InstanceEntries::find_title_by_id(&data.instances, *instance_id, cx)
+                    .unwrap_or("<instance name>".into())
+            },
+            PageType::Following => ts!("sidebar.following"),
+            PageType::Storage => ts!("storage.title"),
+            PageType::Accounts => ts!("accounts.title"),
+            PageType::FileWatches => ts!("page.file_watches"),
+            PageType::DebugConsole => ts!("page.debug_console"),
+        }
+    }
+
+    pub fn refers_to_instance(&self, id: InstanceID) -> bool {
+        match self {
+            PageType::InstancePage(instance_id, _) => *instance_id == id,
+            PageType::Modrinth { installing_for, .. } => *installing_for == Some(id),
+            _ => false,
+        }
+    }
+
     fn to_serialized(&self, data: &DataEntities, cx: &App) -> SerializedPageType {
         match self {
             PageType::Instances => SerializedPageType::Instances,
@@ -58,6 +105,10 @@ impl PageType {
                     SerializedPageType::Instances
                 }
             },
+            PageType::Following => SerializedPageType::Following,
+            PageType::Storage => SerializedPageType::Storage,
+            PageType::Accounts => SerializedPageType::Accounts,
+            PageType::FileWatches | PageType::DebugConsole => SerializedPageType::Instances,
         }
     }
 
@@ -65,6 +116,9 @@ impl PageType {
         match serialized {
             SerializedPageType::Instances => PageType::Instances,
             SerializedPageType::Syncing => PageType::Syncing,
+            SerializedPageType::Following => PageType::Following,
+            SerializedPageType::Storage => PageType::Storage,
+            SerializedPageType::Accounts => PageType::Accounts,
             SerializedPageType::Modrinth { installing_for } => {
                 if let Some(installing_for) = installing_for {
                     if let Some(id) = InstanceEntries::find_id_by_name(&data.instances, installing_for, cx) {
@@ -75,7 +129,8 @@ impl PageType {
             },
             SerializedPageType::InstancePage(name) => {
                 if let Some(id) = InstanceEntries::find_id_by_name(&data.instances, name, cx) {
-                    PageType::InstancePage(id, InstanceSubpageType::Quickplay)
+                    let subpage = InterfaceConfig::get(cx).instance_subpages.get(name).copied().unwrap_or(InstanceSubpageType::Quickplay);
+                    PageType::InstancePage(id, subpage)
                 } else {
                     PageType::Instances
                 }
@@ -94,6 +149,9 @@ pub enum SerializedPageType {
         installing_for: Option<SharedString>,
     },
     InstancePage(SharedString),
+    Following,
+    Storage,
+    Accounts,
 }
 
 #[derive(Clone)]
@@ -105,6 +163,11 @@ pub enum LauncherPage {
         page: Entity<ModrinthSearchPage>,
     },
     InstancePage(InstanceID, InstanceSubpageType, Entity<InstancePage>),
+    Following(Entity<FollowingPage>),
+    Storage(Entity<StoragePage>),
+    Accounts(Entity<AccountsPage>),
+    FileWatches(Entity<FileWatchesPage>),
+    DebugConsole(Entity<DebugConsolePage>),
 }
 
 impl LauncherPage {
@@ -114,6 +177,11 @@ impl LauncherPage {
             LauncherPage::Syncing(entity) => entity.into_any_element(),
             LauncherPage::Modrinth { page, .. } => page.into_any_element(),
             LauncherPage::InstancePage(_, _, entity) => entity.into_any_element(),
+            LauncherPage::Following(entity) => entity.into_any_element(),
+            LauncherPage::Storage(entity) => entity.into_any_element(),
+            LauncherPage::Accounts(entity) => entity.into_any_element(),
+            LauncherPage::FileWatches(entity) => entity.into_any_element(),
+            LauncherPage::DebugConsole(entity) => entity.into_any_element(),
         }
     }
 
@@ -123,6 +191,19 @@ impl LauncherPage {
             LauncherPage::Syncing(_) => PageType::Syncing,
             LauncherPage::Modrinth { installing_for, .. } => PageType::Modrinth { installing_for: *installing_for, project_type: None },
             LauncherPage::InstancePage(id, subpage, _) => PageType::InstancePage(*id, *subpage),
+            LauncherPage::Following(_) => PageType::Following,
+            LauncherPage::Storage(_) => PageType::Storage,
+            LauncherPage::Accounts(_) => PageType::Accounts,
+            LauncherPage::FileWatches(_) => PageType::FileWatches,
+            LauncherPage::DebugConsole(_) => PageType::DebugConsole,
+        }
+    }
+
+    /// Like `page_type`, but with the subpage the instance page has been switched to since it was opened
+    fn current_page_type(&self, cx: &App) -> PageType {
+        match self {
+            LauncherPage::InstancePage(id, _, page) => PageType::InstancePage(*id, page.read(cx).subpage_type()),
+            _ => self.page_type(),
         }
     }
 }
@@ -154,6 +235,7 @@ impl LauncherUI {
                     *name = event.instance.name.clone();
                     cx.notify();
                 }
+                cx.emit(PageChangedEvent);
                 cx.notify();
             });
         let _instance_removed_subscription =
@@ -164,6 +246,8 @@ impl LauncherUI {
                 {
                     this.switch_page(PageType::Instances, &[], window, cx);
                 }
+                this.history.remove_instance(event.id);
+                cx.emit(PageChangedEvent);
                 cx.notify();
             });
         let _instance_moved_to_top_subscription =
@@ -176,6 +260,11 @@ impl LauncherUI {
                 cx.notify();
             });
 
+        let _install_queue_subscription = cx.observe(&data.install_queue, |_, _, cx| cx.notify());
+        let _launcher_update_subscription = cx.observe(&data.launcher_update, |_, _, cx| cx.notify());
+        let _notifications_subscription = cx.observe(&data.notifications, |_, _, cx| cx.notify());
+        let _followed_projects_subscription = cx.observe(&data.followed_projects, |_, _, cx| cx.notify());
+
         let config = InterfaceConfig::get(cx);
         let page_type = PageType::from_serialized(&config.main_page, data, cx);
         let page_path: Vec<PageType> = config.page_path.iter().map(|page| PageType::from_serialized(page, data, cx)).collect();
@@ -183,12 +272,18 @@ impl LauncherUI {
         Self {
             data: data.clone(),
             page: Self::create_page(&data, page_type, &page_path, window, cx),
+            breadcrumbs: page_path.into(),
+            history: NavigationHistory::default(),
             sidebar_state,
             recent_instances,
             _instance_added_subscription,
             _instance_modified_subscription,
             _instance_removed_subscription,
             _instance_moved_to_top_subscription,
+            _install_queue_subscription,
+            _launcher_update_subscription,
+            _notifications_subscription,
+            _followed_projects_subscription,
         }
     }
 
@@ -215,46 +310,203 @@ impl LauncherUI {
                     InstancePage::new(id, subpage, path, data, window, cx)
                 }))
             },
+            PageType::Following => {
+                LauncherPage::Following(cx.new(|cx| FollowingPage::new(data, window, cx)))
+            },
+            PageType::Storage => {
+                LauncherPage::Storage(cx.new(|cx| StoragePage::new(data, window, cx)))
+            },
+            PageType::Accounts => {
+                LauncherPage::Accounts(cx.new(|cx| AccountsPage::new(data, window, cx)))
+            },
+            PageType::FileWatches => {
+                LauncherPage::FileWatches(cx.new(|cx| FileWatchesPage::new(data, window, cx)))
+            },
+            PageType::DebugConsole => {
+                LauncherPage::DebugConsole(cx.new(|cx| DebugConsolePage::new(data, window, cx)))
+            },
         }
     }
 
     pub fn switch_page(&mut self, page: PageType, breadcrumbs: &[PageType], window: &mut Window, cx: &mut Context<Self>) {
-        if self.page.page_type() == page {
+        if self.page.current_page_type(cx) == page {
+            return;
+        }
+
+        let current = self.current_history_entry(cx);
+        self.history.push(current);
+
+        self.show_page(page, breadcrumbs, window, cx);
+    }
+
+    /// Called by an instance page after switching to another subpage, so going back returns to the previous subpage
+    pub fn instance_subpage_switched(&mut self, page: EntityId, previous: InstanceSubpageType, cx: &mut Context<Self>) {
+        // Instance pages can also be popped out into their own window, those don't have a history
+        let LauncherPage::InstancePage(id, _, instance_page) = &self.page else {
             return;
+        };
+        if instance_page.entity_id() != page {
+            return;
+        }
+
+        self.history.push(HistoryEntry {
+            page: PageType::InstancePage(*id, previous),
+            breadcrumbs: self.breadcrumbs.clone(),
+        });
+        cx.emit(PageChangedEvent);
+        cx.notify();
+    }
+
+    fn current_history_entry(&self, cx: &App) -> HistoryEntry {
+        HistoryEntry {
+            page: self.page.current_page_type(cx),
+            breadcrumbs: self.breadcrumbs.clone(),
         }
+    }
+
+    pub fn can_navigate_back(&self) -> bool {
+        self.history.can_go_back()
+    }
 
+    pub fn can_navigate_forward(&self) -> bool {
+        self.history.can_go_forward()
+    }
+
+    pub fn navigate_back(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.current_history_entry(cx);
+        if let Some(entry) = self.history.go_back(current) {
+            self.show_page(entry.page, &entry.breadcrumbs, window, cx);
+        }
+    }
+
+    pub fn navigate_forward(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.current_history_entry(cx);
+        if let Some(entry) = self.history.go_forward(current) {
+            self.show_page(entry.page, &entry.breadcrumbs, window, cx);
+        }
+    }
+
+    pub fn page_title(&self, cx: &App) -> SharedString {
+        self.page.page_type().title(&self.data, cx)
+    }
+
+    fn show_page(&mut self, page: PageType, breadcrumbs: &[PageType], window: &mut Window, cx: &mut Context<Self>) {
         let main_page = page.to_serialized(&self.data, cx);
         let page_path = breadcrumbs.iter().map(|page| page.to_serialized(&self.data, cx)).collect();
         let config = InterfaceConfig::get_mut(cx);
         config.main_page = main_page;
         config.page_path = page_path;
 
+        // Scans for the instance that was shown aren't needed anymore, unless the new page still uses it
+        if let PageType::InstancePage(previous, _) = self.page.page_type()
+            && !page.refers_to_instance(previous)
+            && !breadcrumbs.iter().any(|page| page.refers_to_instance(previous))
+        {
+            self.data.backend_handle.send(MessageToBackend::CancelInstanceLoads { id: previous });
+        }
+
         self.page = Self::create_page(&self.data, page, breadcrumbs, window, cx);
+        self.breadcrumbs = breadcrumbs.into();
+        cx.emit(PageChangedEvent);
         cx.notify();
     }
+
+    pub fn new_instance(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.switch_page(PageType::Instances, &[], window, cx);
+        if let LauncherPage::Instances(page) = &self.page {
+            page.update(cx, |page, cx| page.show_create_instance_modal(window, cx));
+        }
+    }
+
+    pub fn open_command_palette(&self, window: &mut Window, cx: &mut App) {
+        modals::command_palette::open_command_palette(&self.data, window, cx);
+    }
+
+    /// Focuses the search field of the current page, if it has one
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        match &self.page {
+            LauncherPage::Modrinth { page, .. } => page.update(cx, |page, cx| page.focus_search(window, cx)),
+            LauncherPage::InstancePage(_, _, page) => page.update(cx, |page, cx| page.focus_search(window, cx)),
+            _ => {},
+        }
+    }
+
+    /// Summarizes the background tasks of the backend, hidden while there are none
+    fn render_status_bar(&self, cx: &App) -> Option<Stateful<Div>> {
+        let tasks = self.data.background_tasks.get();
+        let first = tasks.first()?;
+
+        let mut summary = modals::generic::tracker_label(&first.tracker).to_string();
+        if let Some(progress) = first.tracker.get_float() {
+            summary.push_str(&format!(" {}%", (progress * 100.0).round() as u32));
+        }
+        if tasks.len() > 1 {
+            summary.push(' ');
+            summary.push_str(&rust_i18n::t!("background_tasks.more", count = tasks.len() - 1));
+        }
+
+        Some(h_flex()
+            .id("status-bar")
+            .gap_2()
+            .px_3()
+            .py_1()
+            .w_full()
+            .text_xs()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().sidebar)
+            .text_color(cx.theme().sidebar_foreground)
+            .hover(|this| this.bg(cx.theme().sidebar_accent))
+            .child(Spinner::new().xsmall())
+            .child(div().flex_1().min_w_0().overflow_hidden().text_ellipsis().child(SharedString::new(summary)))
+            .on_click({
+                let data = self.data.clone();
+                move |_, window, cx| {
+                    let build = modals::background_tasks::build_background_tasks_sheet(&data);
+                    window.open_sheet_at(gpui_component::Placement::Left, cx, build);
+                }
+            }))
+    }
 }
 
 impl Render for LauncherUI {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let page_type = self.page.page_type();
 
-        let library_group = MenuGroup::new("Play")
-            .child(MenuGroupItem::new("Instances")
+        let library_group = MenuGroup::new(ts!("sidebar.play"))
+            .child(MenuGroupItem::new(ts!("sidebar.instances"))
                 .active(page_type == PageType::Instances)
                 .on_click(cx.listener(|launcher, _, window, cx| {
                     launcher.switch_page(PageType::Instances, &[], window, cx);
                 })));
 
-        let launcher_group = MenuGroup::new("Content")
+        let unseen = self.data.followed_projects.read(cx).followed.unseen_count();
+        let following_title = if unseen > 0 {
+            SharedString::new(format!("{} ({})", ts!("sidebar.following"), unseen))
+        } else {
+            ts!("sidebar.following")
+        };
+
+        let launcher_group = MenuGroup::new(ts!("sidebar.content"))
             .child(MenuGroupItem::new("Modrinth")
                 .active(page_type == PageType::Modrinth { installing_for: None, project_type: None })
                 .on_click(cx.listener(|launcher, _, window, cx| {
                     launcher.switch_page(PageType::Modrinth { installing_for: None, project_type: None }, &[], window, cx);
                 })))
-            .child(MenuGroupItem::new("Syncing")
+            .child(MenuGroupItem::new(following_title)
+                .active(page_type == PageType::Following)
+                .on_click(cx.listener(|launcher, _, window, cx| {
+                    launcher.switch_page(PageType::Following, &[], window, cx);
+                })))
+            .child(MenuGroupItem::new(ts!("sidebar.syncing"))
                 .active(page_type == PageType::Syncing)
                 .on_click(cx.listener(|launcher, _, window, cx| {
                     launcher.switch_page(PageType::Syncing, &[], window, cx);
+                })))
+            .child(MenuGroupItem::new(ts!("sidebar.storage"))
+                .active(page_type == PageType::Storage)
+                .on_click(cx.listener(|launcher, _, window, cx| {
+                    launcher.switch_page(PageType::Storage, &[], window, cx);
                 })));
 
         let mut groups: heapless::Vec<MenuGroup, 3> = heapless::Vec::new();
@@ -263,7 +515,7 @@ impl Render for LauncherUI {
         let _ = groups.push(launcher_group);
 
         if !self.recent_instances.is_empty() {
-            let mut recent_instances_group = MenuGroup::new("Recent Instances");
+            let mut recent_instances_group = MenuGroup::new(ts!("sidebar.recent_instances"));
 
             for (id, name) in &self.recent_instances {
                 let name = name.clone();
@@ -297,7 +549,7 @@ impl Render for LauncherUI {
         } else {
             (
                 gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into()))),
-                "No Account".into(),
+                ts!("accounts.no_account"),
             )
         };
 
@@ -309,139 +561,13 @@ impl Render for LauncherUI {
             .text_size(rems(0.9375))
             .child(account_head.size_8().min_w_8().min_h_8())
             .child(account_name))
-            .on_click({
-                let accounts = self.data.accounts.clone();
-                let backend_handle = self.data.backend_handle.clone();
-                move |_, window, cx| {
-                    if accounts.read(cx).accounts.is_empty() {
-                        crate::root::start_new_account_login(&backend_handle, window, cx);
-                        return;
-                    }
-
-                    let accounts = accounts.clone();
-                    let backend_handle = backend_handle.clone();
-                    window.open_sheet_at(gpui_component::Placement::Left, cx, move |sheet, window, cx| {
-                        let (accounts, selected_account) = {
-                            let accounts = accounts.read(cx);
-                            (accounts.accounts.clone(), accounts.selected_account_uuid)
-                        };
-
-                        let trash_icon = Icon::default().path("icons/trash-2.svg");
-
-                        let items = accounts.iter().map(|account| {
-                            let head = if let Some(head) = &account.head {
-                                let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
-                                png_render_cache::render_with_transform(Arc::clone(head), resize, cx)
-                            } else {
-                                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
-                            };
-                            let account_name = SharedString::new(account.username.clone());
-
-                            let selected = Some(account.uuid) == selected_account;
-
-                            h_flex()
-                                .gap_2()
-                                .w_full()
-                                .child(Button::new(account_name.clone())
-                                    .flex_grow()
-                                    .when(selected, |this| {
-                                        this.info()
-                                    })
-                                    .h_10()
-                                    .child(head.size_8().min_w_8().min_h_8())
-                                    .child(account_name.clone())
-                                    .when(!selected, |this| {
-                                        this.on_click({
-                                            let backend_handle = backend_handle.clone();
-                                            let uuid = account.uuid;
-                                            move |_, _, _| {
-                                                backend_handle.send(MessageToBackend::SelectAccount { uuid });
-                                            }
-                                        })
-                                    }))
-                                .child(Button::new((account_name.clone(), 1))
-                                    .icon(trash_icon.clone())
-                                    .h_10()
-                                    .w_10()
-                                    .danger()
-                                    .on_click({
-                                        let backend_handle = backend_handle.clone();
-                                        let uuid = account.uuid;
-                                        move |_, _, _| {
-                                            backend_handle.send(MessageToBackend::DeleteAccount { uuid });
-                                        }
-                                    }))
-
-                        });
-
-                        sheet
-                            .title("Accounts")
-                            .overlay_top(crate::root::sheet_margin_top(window))
-                            .child(v_flex()
-                                .gap_2()
-                                .child(Button::new("add-account").h_10().success().icon(IconName::Plus).label("Add account").on_click({
-                                    let backend_handle = backend_handle.clone();
-                                    move |_, window, cx| {
-                                        crate::root::start_new_account_login(&backend_handle, window, cx);
-                                    }
-                                }))
-                                .child(Button::new("add-offline").h_10().success().icon(IconName::Plus).label("Add offline account").on_click({
-                                    let backend_handle = backend_handle.clone();
-                                    move |_, window, cx| {
-                                        let name_input = cx.new(|cx| {
-                                            InputState::new(window, cx)
-                                        });
-                                        let uuid_input = cx.new(|cx| {
-                                            InputState::new(window, cx).placeholder("Random")
-                                        });
-                                        let backend_handle = backend_handle.clone();
-                                        window.open_dialog(cx, move |dialog, _, cx| {
-                                            let username = name_input.read(cx).value();
-                                            let valid_name = username.len() >= 1 && username.len() <= 16 &&
-                                                username.as_bytes().iter().all(|c| *c > 32 && *c < 127);
-                                            let uuid = uuid_input.read(cx).value();
-                                            let valid_uuid = uuid.is_empty() || Uuid::try_parse(&uuid).is_ok();
-
-                                            let valid = valid_name && valid_uuid;
-
-                                            let backend_handle = backend_handle.clone();
-                                            let mut add_button = Button::new("add").label("Add").disabled(!valid).on_click(move |_, window, cx| {
-                                                window.close_all_dialogs(cx);
-
-                                                let uuid = if let Ok(uuid) = Uuid::try_parse(&uuid) {
-                                                   uuid
-                                                } else {
-                                                    let uuid: u128 = rand::thread_rng().r#gen();
-                                                    let uuid = (uuid & !0xF0000000000000000000) | 0x30000000000000000000; // set version to 3
-                                                    Uuid::from_u128(uuid)
-                                                };
-
-                                                backend_handle.send(MessageToBackend::AddOfflineAccount {
-                                                    name: username.clone().into(),
-                                                    uuid
-                                                });
-                                            });
-
-                                            if valid {
-                                                add_button = add_button.success();
-                                            }
-
-                                            dialog.title("Add offline account")
-                                                .child(v_flex()
-                                                    .gap_2()
-                                                    .child(crate::labelled("Name", Input::new(&name_input)))
-                                                    .child(crate::labelled("UUID", Input::new(&uuid_input)))
-                                                    .child(add_button)
-                                                )
-                                        });
-                                    }
-                                }))
-                                .children(items)
-                            )
-
-                    });
+            .on_click(cx.listener(|launcher, _, window, cx| {
+                if launcher.data.accounts.read(cx).accounts.is_empty() {
+                    crate::root::start_new_account_login(&launcher.data.backend_handle, window, cx);
+                    return;
                 }
-            });
+                launcher.switch_page(PageType::Accounts, &[], window, cx);
+            }));
 
         let settings_button = div()
             .id("settings-button")
@@ -461,6 +587,70 @@ impl Render for LauncherUI {
                 }
             });
 
+        let install_queue = self.data.install_queue.read(cx);
+        let install_queue_button = (!install_queue.entries.is_empty()).then(|| {
+            let unfinished = install_queue.unfinished_count();
+            div()
+                .id("install-queue-button")
+                .flex()
+                .items_center()
+                .gap_1()
+                .p_2()
+                .rounded(cx.theme().radius)
+                .hover(|this| {
+                    this.bg(cx.theme().sidebar_accent)
+                        .text_color(cx.theme().sidebar_accent_foreground)
+                })
+                .child(Icon::default().path("icons/download.svg"))
+                .when(unfinished > 0, |this| this.child(SharedString::new(unfinished.to_string())))
+                .on_click({
+                    let data = self.data.clone();
+                    move |_, window, cx| {
+                        let build = modals::install_queue::build_install_queue_sheet(&data);
+                        window.open_sheet_at(gpui_component::Placement::Left, cx, build);
+                    }
+                })
+        });
+
+        let unseen_errors = self.data.notifications.read(cx).unseen_errors;
+        let notifications_button = div()
+            .id("notifications-button")
+            .relative()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .hover(|this| {
+                this.bg(cx.theme().sidebar_accent)
+                    .text_color(cx.theme().sidebar_accent_foreground)
+            })
+            .child(IconName::Bell)
+            .when(unseen_errors, |this| {
+                this.child(div().absolute().top_1().right_1().size_2().rounded_full().bg(cx.theme().danger))
+            })
+            .on_click({
+                let data = self.data.clone();
+                move |_, window, cx| {
+                    NotificationCenter::mark_seen(&data.notifications, cx);
+                    let build = modals::notifications::build_notifications_sheet(&data);
+                    window.open_sheet_at(gpui_component::Placement::Left, cx, build);
+                }
+            });
+
+        let launcher_update_button = self.data.launcher_update.read(cx).update.is_some().then(|| {
+            div()
+                .id("launcher-update-button")
+                .p_2()
+                .rounded(cx.theme().radius)
+                .text_color(cx.theme().success)
+                .hover(|this| this.bg(cx.theme().sidebar_accent))
+                .child(IconName::ArrowUp)
+                .on_click({
+                    let data = self.data.clone();
+                    move |_, window, cx| {
+                        modals::launcher_update::open_launcher_update(&data, window, cx);
+                    }
+                })
+        });
+
         let header = h_flex()
             .pt_5()
             .px_5()
@@ -470,8 +660,8 @@ impl Render for LauncherUI {
             .justify_center()
             .text_size(rems(0.9375))
             .child(pandora_icon.size_8().min_w_8().min_h_8())
-            .child("Pandora");
-        let footer = h_flex().pb_3().px_3().flex_wrap().justify_center().w_full().child(settings_button).child(account_button);
+            .child(ts!("common.app_name"));
+        let footer = h_flex().pb_3().px_3().flex_wrap().justify_center().w_full().child(settings_button).child(notifications_button).children(install_queue_button).children(launcher_update_button).child(account_button);
         let sidebar = v_flex()
             .w_full()
             .bg(cx.theme().sidebar)
@@ -486,10 +676,15 @@ impl Render for LauncherUI {
                 .overflow_y_scrollbar())
             .child(footer);
 
-        h_resizable("container")
-            .with_state(&self.sidebar_state)
-            .child(resizable_panel().size(px(150.)).size_range(px(130.)..px(200.)).child(sidebar))
-            .child(self.page.clone().into_any_element())
+        let status_bar = self.render_status_bar(cx);
+
+        v_flex()
+            .size_full()
+            .child(h_resizable("container")
+                .with_state(&self.sidebar_state)
+                .child(resizable_panel().size(px(150.)).size_range(px(130.)..px(200.)).child(sidebar))
+                .child(self.page.clone().into_any_element()))
+            .children(status_bar)
     }
 }
 
@@ -0,0 +1,122 @@
+use bridge::instance::InstanceID;
+use gpui::{prelude::*, *};
+use gpui_component::{v_flex, ActiveTheme as _, Root};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    component::page_path::PagePath, entity::{instance::{InstanceEntries, InstanceRemovedEvent}, launcher_lock::LauncherLockState, DataEntities},
+    pages::instance::instance_page::{InstancePage, InstanceSubpageType}, root::has_csd_titlebar, ts, ui::PageType, CloseWindow, MAIN_FONT
+};
+
+/// Windows that pages have been popped out into, so popping out the same page again focuses
+/// the existing window instead of opening another
+#[derive(Default)]
+pub struct PopoutWindows {
+    instances: FxHashMap<InstanceID, WindowHandle<Root>>,
+}
+
+impl Global for PopoutWindows {}
+
+/// Opens the instance page in its own window, e.g. to keep an eye on it from a second monitor
+pub fn pop_out_instance(id: InstanceID, subpage: InstanceSubpageType, data: &DataEntities, cx: &mut App) {
+    if let Some(handle) = cx.default_global::<PopoutWindows>().instances.get(&id).copied() {
+        // The window may have been closed since, in which case a new one is opened
+        if handle.update(cx, |_, window, _| window.activate_window()).is_ok() {
+            return;
+        }
+    }
+
+    let Some(title) = InstanceEntries::find_title_by_id(&data.instances, id, cx) else {
+        return;
+    };
+
+    let options = WindowOptions {
+        app_id: Some("PandoraLauncher".into()),
+        window_min_size: Some(size(px(360.0), px(240.0))),
+        titlebar: Some(TitlebarOptions {
+            title: Some(title.clone()),
+            ..Default::default()
+        }),
+        window_decorations: Some(WindowDecorations::Server),
+        window_background: crate::theme::window_background(cx),
+        ..Default::default()
+    };
+    let data = data.clone();
+    let handle = cx.open_window(options, |window, cx| {
+        let page_path = PagePath::new([PageType::InstancePage(id, subpage)].into());
+        let page = cx.new(|cx| InstancePage::new(id, subpage, page_path, &data, window, cx));
+        let popout = cx.new(|cx| PopoutRoot::new(id, title, page.into(), &data, window, cx));
+        window.activate_window();
+        cx.new(|cx| Root::new(popout, window, cx))
+    });
+
+    match handle {
+        Ok(handle) => {
+            cx.default_global::<PopoutWindows>().instances.insert(id, handle);
+        },
+        Err(error) => log::error!("Unable to open window: {:?}", error),
+    }
+}
+
+/// Like the main window's root, but for a single popped out page
+struct PopoutRoot {
+    title: SharedString,
+    content: AnyView,
+    launcher_lock: Entity<LauncherLockState>,
+    focus_handle: FocusHandle,
+    _instance_removed_subscription: Subscription,
+}
+
+impl PopoutRoot {
+    fn new(id: InstanceID, title: SharedString, content: AnyView, data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let _instance_removed_subscription = cx.subscribe_in::<_, InstanceRemovedEvent>(&data.instances, window, move |_, _, event, window, cx| {
+            if event.id == id {
+                cx.default_global::<PopoutWindows>().instances.remove(&id);
+                window.remove_window();
+            }
+        });
+
+        Self {
+            title,
+            content,
+            launcher_lock: data.launcher_lock.clone(),
+            focus_handle: cx.focus_handle(),
+            _instance_removed_subscription,
+        }
+    }
+}
+
+impl Render for PopoutRoot {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let sheet_layer = Root::render_sheet_layer(window, cx);
+        let dialog_layer = Root::render_dialog_layer(window, cx);
+        let notification_layer = Root::render_notification_layer(window, cx);
+
+        let content = if self.launcher_lock.read(cx).locked {
+            v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .text_color(cx.theme().muted_foreground)
+                .child(ts!("window.locked"))
+                .into_any_element()
+        } else {
+            self.content.clone().into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .font_family(MAIN_FONT)
+            .when(has_csd_titlebar(window), |this| {
+                this.child(gpui_component::TitleBar::new().child(self.title.clone()))
+            })
+            .child(content)
+            .children(sheet_layer)
+            .children(dialog_layer)
+            .children(notification_layer)
+            .track_focus(&self.focus_handle)
+            .on_action(|_: &CloseWindow, window, _| {
+                window.remove_window();
+            })
+    }
+}
@@ -1,13 +1,22 @@
-use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read};
 
 use super::*;
 use anyhow::bail;
-use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+use byteorder::{BigEndian, ReadBytesExt};
 
 const DECODE_CAPACITY: usize = 2_097_152;
 
 pub fn read_protocol(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
-    let type_id: u8 = bytes.read_u8()?;
+    read_protocol_from(bytes)
+}
+
+pub fn read_named(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
+    read_named_from(bytes)
+}
+
+/// Like `read_protocol`, but decodes directly from a reader instead of a buffer
+pub fn read_protocol_from<R: Read>(reader: &mut R) -> anyhow::Result<NBT> {
+    let type_id: u8 = reader.read_u8()?;
     if type_id == TAG_END_ID.0 {
         return Ok(NBT::new());
     }
@@ -15,7 +24,7 @@ pub fn read_protocol(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
     let mut size = 0;
 
     let mut nodes = Slab::new();
-    let root_index = read_node(bytes, &mut nodes, type_id, 0, &mut size)?;
+    let root_index = read_node(reader, &mut nodes, type_id, 0, &mut size)?;
 
     Ok(NBT {
         root_name: String::new(),
@@ -24,8 +33,9 @@ pub fn read_protocol(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
     })
 }
 
-pub fn read_named(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
-    let type_id: u8 = bytes.read_u8()?;
+/// Like `read_named`, but decodes directly from a reader instead of a buffer
+pub fn read_named_from<R: Read>(reader: &mut R) -> anyhow::Result<NBT> {
+    let type_id: u8 = reader.read_u8()?;
     if type_id == TAG_END_ID.0 {
         return Ok(NBT::new());
     } else if type_id != TAG_COMPOUND_ID.0 {
@@ -35,20 +45,40 @@ pub fn read_named(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
     let mut size = 0;
 
     let mut nodes = Slab::new();
-    let name = read_string(bytes, &mut size)?;
-    let children = read_compound(bytes, &mut nodes, 0, &mut size)?;
+    let name = read_string(reader, &mut size)?;
+    let children = read_compound(reader, &mut nodes, 0, &mut size)?;
     let root_index = nodes.insert(NBTNode::Compound(children));
 
     Ok(NBT {
-        root_name: name.into_owned(),
+        root_name: name,
         root_index,
         nodes,
     })
 }
 
+/// Reads named NBT that may be gzip compressed (level.dat), zlib compressed (region chunks)
+/// or uncompressed (servers.dat). The input is decompressed while decoding, never buffered whole
+pub fn read_maybe_compressed<R: Read>(reader: R) -> anyhow::Result<NBT> {
+    let mut reader = BufReader::new(reader);
+
+    let header = match *reader.fill_buf()? {
+        [first, second, ..] => [first, second],
+        _ => [0; 2],
+    };
+
+    match header {
+        [0x1f, 0x8b] => read_named_from(&mut flate2::bufread::GzDecoder::new(reader)),
+        // CMF must use deflate (low nibble 8) and CMF/FLG together must be a multiple of 31
+        [cmf, flg] if cmf & 0x0f == 8 && ((u16::from(cmf) << 8) | u16::from(flg)) % 31 == 0 => {
+            read_named_from(&mut flate2::bufread::ZlibDecoder::new(reader))
+        },
+        _ => read_named_from(&mut reader),
+    }
+}
+
 #[inline]
-fn read_node(
-    bytes: &mut &[u8],
+fn read_node<R: Read>(
+    reader: &mut R,
     nodes: &mut Slab<NBTNode>,
     type_id: u8,
     depth: usize,
@@ -59,36 +89,36 @@ fn read_node(
     let node = match TagType(type_id) {
         TAG_BYTE_ID => {
             *size += 1;
-            NBTNode::Byte(bytes.read_i8()?)
+            NBTNode::Byte(reader.read_i8()?)
         },
         TAG_SHORT_ID => {
             *size += 2;
-            NBTNode::Short(bytes.read_i16::<BigEndian>()?)
+            NBTNode::Short(reader.read_i16::<BigEndian>()?)
         },
         TAG_INT_ID => {
             *size += 4;
-            NBTNode::Int(bytes.read_i32::<BigEndian>()?)
+            NBTNode::Int(reader.read_i32::<BigEndian>()?)
         },
         TAG_LONG_ID => {
             *size += 8;
-            NBTNode::Long(bytes.read_i64::<BigEndian>()?)
+            NBTNode::Long(reader.read_i64::<BigEndian>()?)
         },
         TAG_FLOAT_ID => {
             *size += 4;
-            NBTNode::Float(bytes.read_f32::<BigEndian>()?)
+            NBTNode::Float(reader.read_f32::<BigEndian>()?)
         },
         TAG_DOUBLE_ID => {
             *size += 8;
-            NBTNode::Double(bytes.read_f64::<BigEndian>()?)
+            NBTNode::Double(reader.read_f64::<BigEndian>()?)
         },
-        TAG_BYTE_ARRAY_ID => NBTNode::ByteArray(read_byte_array(bytes, size)?),
-        TAG_STRING_ID => NBTNode::String(read_string(bytes, size)?.into_owned()),
+        TAG_BYTE_ARRAY_ID => NBTNode::ByteArray(read_byte_array(reader, size)?),
+        TAG_STRING_ID => NBTNode::String(read_string(reader, size)?),
         TAG_LIST_ID => {
             if depth > 512 {
                 bail!("tried to read NBT tag with too high complexity, depth > 512")
             }
 
-            let (type_id, children) = read_list(bytes, nodes, depth + 1, size)?;
+            let (type_id, children) = read_list(reader, nodes, depth + 1, size)?;
             NBTNode::List {
                 type_id: TagType(type_id),
                 children,
@@ -99,18 +129,18 @@ fn read_node(
                 bail!("tried to read NBT tag with too high complexity, depth > 512")
             }
 
-            NBTNode::Compound(read_compound(bytes, nodes, depth + 1, size)?)
+            NBTNode::Compound(read_compound(reader, nodes, depth + 1, size)?)
         },
-        TAG_INT_ARRAY_ID => NBTNode::IntArray(read_int_array(bytes, size)?),
-        TAG_LONG_ARRAY_ID => NBTNode::LongArray(read_long_array(bytes, size)?),
+        TAG_INT_ARRAY_ID => NBTNode::IntArray(read_int_array(reader, size)?),
+        TAG_LONG_ARRAY_ID => NBTNode::LongArray(read_long_array(reader, size)?),
         _ => bail!("unknown type id: {}", type_id),
     };
     let idx = nodes.insert(node);
     Ok(idx)
 }
 
-fn read_compound(
-    bytes: &mut &[u8],
+fn read_compound<R: Read>(
+    reader: &mut R,
     nodes: &mut Slab<NBTNode>,
     depth: usize,
     size: &mut usize,
@@ -118,84 +148,83 @@ fn read_compound(
     let mut children = NBTCompound(Vec::new());
 
     loop {
-        let type_id: u8 = bytes.read_u8()?;
+        let type_id: u8 = reader.read_u8()?;
         if type_id == TAG_END_ID.0 {
             break Ok(children);
         } else {
             *size += 8;
 
-            let name = read_string(bytes, size)?;
-            let node = read_node(bytes, nodes, type_id, depth, size)?;
+            let name = read_string(reader, size)?;
+            let node = read_node(reader, nodes, type_id, depth, size)?;
 
             match children.binary_search(name.as_ref()) {
                 Ok(_) => bail!("read_compound: duplicate key"),
                 Err(index) => {
-                    children.0.insert(index, (name.into(), node));
+                    children.0.insert(index, (name, node));
                 },
             }
         }
     }
 }
 
+/// Reads a length-prefixed array. The length is checked against the capacity before allocating,
+/// so a corrupt length can't cause a huge allocation when the reader isn't a buffer
 #[inline]
-fn read_byte_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i8>> {
-    let length: i32 = bytes.read_i32::<BigEndian>()?;
+fn read_array_length<R: Read>(reader: &mut R, element_size: usize, size: &mut usize, name: &str) -> anyhow::Result<usize> {
+    let length: i32 = reader.read_i32::<BigEndian>()?;
     if length < 0 {
-        bail!("read_byte_array: length cannot be negative");
-    } else if bytes.len() < length as _ {
-        bail!("read_byte_array: not enough bytes to read byte array");
+        bail!("{name}: length cannot be negative");
     }
     let length = length as usize;
 
-    *size += length;
+    *size += length * element_size;
     if *size > DECODE_CAPACITY {
-        bail!("read_byte_array: nbt too large, capacity reached")
+        bail!("{name}: nbt too large, capacity reached")
     }
 
-    let (arr_bytes, rest_bytes) = bytes.split_at(length);
-    *bytes = rest_bytes;
+    Ok(length)
+}
+
+#[inline]
+fn read_byte_array<R: Read>(reader: &mut R, size: &mut usize) -> anyhow::Result<Vec<i8>> {
+    let length = read_array_length(reader, 1, size, "read_byte_array")?;
 
-    let arr_bytes: &[i8] = unsafe { std::mem::transmute(arr_bytes) };
-    Ok(arr_bytes.into())
+    let mut values = vec![0; length];
+    reader.read_i8_into(&mut values)?;
+    Ok(values)
 }
 
 #[inline]
-fn read_string<'a>(bytes: &mut &'a [u8], size: &mut usize) -> anyhow::Result<Cow<'a, str>> {
-    let length: u16 = bytes.read_u16::<BigEndian>()?;
-    if bytes.len() < length as _ {
-        bail!(
-            "read_string: not enough bytes ({} remaining) to read string of length {}",
-            bytes.len(),
-            length
-        );
-    }
-    let length = length as usize;
+fn read_string<R: Read>(reader: &mut R, size: &mut usize) -> anyhow::Result<String> {
+    let length = reader.read_u16::<BigEndian>()? as usize;
 
     *size += length + 24;
     if *size > DECODE_CAPACITY {
         bail!("read_string: nbt too large, capacity reached")
     }
 
-    let (str_bytes, rest_bytes) = bytes.split_at(length);
-    *bytes = rest_bytes;
+    let mut str_bytes = vec![0; length];
+    reader.read_exact(&mut str_bytes)?;
 
-    Ok(cesu8::from_java_cesu8(str_bytes)?)
+    // Most strings are also valid UTF-8, which avoids copying them
+    match String::from_utf8(str_bytes) {
+        Ok(string) => Ok(string),
+        Err(err) => Ok(cesu8::from_java_cesu8(err.as_bytes())?.into_owned()),
+    }
 }
 
-fn read_list(
-    bytes: &mut &[u8],
+fn read_list<R: Read>(
+    reader: &mut R,
     nodes: &mut Slab<NBTNode>,
     depth: usize,
     size: &mut usize,
 ) -> anyhow::Result<(u8, Vec<usize>)> {
-    let type_id: u8 = bytes.read_u8()?;
+    let type_id: u8 = reader.read_u8()?;
 
-    let length: i32 = bytes.read_i32::<BigEndian>()?;
+    let length: i32 = reader.read_i32::<BigEndian>()?;
 
     if length <= 0 {
         Ok((type_id, Vec::new()))
-    } else if bytes.len() < length as _ {
-        bail!("read_list: not enough bytes to read list");
     } else if type_id == TAG_END_ID.0 {
         bail!("read_list: type cannot be TAG_END for non-zero length list");
     } else {
@@ -209,7 +238,7 @@ fn read_list(
         let mut children = Vec::with_capacity(length);
 
         for _ in 0..length {
-            children.push(read_node(bytes, nodes, type_id, depth, size)?);
+            children.push(read_node(reader, nodes, type_id, depth, size)?);
         }
 
         Ok((type_id, children))
@@ -217,48 +246,58 @@ fn read_list(
 }
 
 #[inline]
-fn read_int_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i32>> {
-    let length: i32 = bytes.read_i32::<BigEndian>()?;
-    if length < 0 {
-        bail!("read_int_array: length cannot be negative");
-    } else if bytes.len() < (length as usize) * 4 {
-        bail!("read_int_array: not enough bytes to read int array");
-    }
-    let length = length as usize;
+fn read_int_array<R: Read>(reader: &mut R, size: &mut usize) -> anyhow::Result<Vec<i32>> {
+    let length = read_array_length(reader, 4, size, "read_int_array")?;
 
-    *size += length * 4;
-    if *size > DECODE_CAPACITY {
-        bail!("read_int_array: nbt too large, capacity reached")
-    }
+    let mut values = vec![0; length];
+    reader.read_i32_into::<BigEndian>(&mut values)?;
+    Ok(values)
+}
 
-    let (arr_bytes, rest_bytes) = bytes.split_at(length * 4);
-    *bytes = rest_bytes;
+#[inline]
+fn read_long_array<R: Read>(reader: &mut R, size: &mut usize) -> anyhow::Result<Vec<i64>> {
+    let length = read_array_length(reader, 8, size, "read_long_array")?;
 
     let mut values = vec![0; length];
-    byteorder::BigEndian::read_i32_into(arr_bytes, values.as_mut_slice());
+    reader.read_i64_into::<BigEndian>(&mut values)?;
     Ok(values)
 }
 
-#[inline]
-fn read_long_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i64>> {
-    let length: i32 = bytes.read_i32::<BigEndian>()?;
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
 
-    if length < 0 {
-        bail!("read_long_array: length cannot be negative");
-    } else if bytes.len() < (length as usize) * 8 {
-        bail!("read_long_array: not enough bytes to read long array");
-    }
-    let length = length as usize;
+    use flate2::{write::{GzEncoder, ZlibEncoder}, Compression};
 
-    *size += length * 8;
-    if *size > DECODE_CAPACITY {
-        bail!("read_long_array: nbt too large, capacity reached")
+    use crate::*;
+
+    #[test]
+    fn read_maybe_compressed() {
+        let nbt = NBT::from_snbt(r#"{Data: {LastPlayed: 5L, LevelName: "World"}, ints: [I;1,2,3]}"#).unwrap();
+        let uncompressed = encode::write_named(&nbt);
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&uncompressed).unwrap();
+        let gzip = gzip.finish().unwrap();
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(&uncompressed).unwrap();
+        let zlib = zlib.finish().unwrap();
+
+        for bytes in [&uncompressed, &gzip, &zlib] {
+            assert_eq!(decode::read_maybe_compressed(bytes.as_slice()).unwrap(), nbt);
+        }
+
+        assert!(decode::read_maybe_compressed(&gzip[..gzip.len() / 2]).is_err());
     }
 
-    let (arr_bytes, rest_bytes) = bytes.split_at(length * 8);
-    *bytes = rest_bytes;
+    #[test]
+    fn java_strings() {
+        // Java encodes NUL as two bytes, which isn't valid UTF-8
+        let bytes = [10, 0, 0, 8, 0, 1, b'a', 0, 4, b'x', 0xc0, 0x80, b'y', 0];
+        let nbt = decode::read_named(&mut bytes.as_slice()).unwrap();
+        assert_eq!(nbt.as_compound().unwrap().find_string("a").map(String::as_str), Some("x\0y"));
 
-    let mut values = vec![0; length];
-    byteorder::BigEndian::read_i64_into(arr_bytes, values.as_mut_slice());
-    Ok(values)
+        assert!(decode::read_named(&mut [10, 0, 0, 8, 0, 1, b'a', 0, 9].as_slice()).is_err());
+    }
 }
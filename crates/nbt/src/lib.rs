@@ -5,6 +5,7 @@ use std::{fmt::Debug, ptr::NonNull, result};
 pub mod decode;
 pub mod encode;
 mod pretty;
+pub mod serialize;
 pub mod stringified;
 
 mod reference;
@@ -164,6 +165,15 @@ impl NBT {
         }
     }
 
+    /// Parses stringified NBT, as used by commands
+    pub fn from_snbt(snbt: &str) -> anyhow::Result<NBT> {
+        stringified::from_snbt(snbt)
+    }
+
+    pub fn to_snbt(&self) -> String {
+        stringified::to_snbt_string(self)
+    }
+
     pub fn as_compound(&self) -> Option<CompoundRef<'_>> {
         match &self.nodes[self.root_index] {
             NBTNode::Compound(_) => Some(CompoundRef {
@@ -193,7 +203,7 @@ impl NBT {
     }
 
     fn remove_node(&mut self, idx: usize) {
-        if idx == 0 {
+        if idx == self.root_index {
             panic!("Cannot remove root node");
         }
         match self.nodes.remove(idx) {
@@ -719,6 +719,12 @@ impl<'a> ListRefMut<'a> {
         Some(self.nbt.get_reference(*idx))
     }
 
+    pub fn get_mut(&mut self, index: usize) -> Option<NBTRefMut<'_>> {
+        let (_, children) = self.get_self_node();
+        let idx = *children.get(index)?;
+        Some(self.nbt.get_reference_mut(idx))
+    }
+
     super::enumerate_basic_types!(super::get_list);
 
     pub fn get_numeric<T: num::FromPrimitive>(&self, index: usize) -> Option<T> {
@@ -746,6 +752,28 @@ impl<'a> ListRefMut<'a> {
     super::enumerate_basic_types!(super::insert_list);
     super::enumerate_basic_types!(super::set_list_at);
 
+    /// Removes the element at the index, shifting the following elements down
+    pub fn remove(&mut self, index: usize) -> bool {
+        let (_, children) = self.get_self_node_mut();
+        if index >= children.len() {
+            return false;
+        }
+        let idx = children.remove(index);
+        self.nbt.remove_node(idx);
+        true
+    }
+
+    /// Moves the element at `from` to `to`, shifting the elements in between
+    pub fn move_element(&mut self, from: usize, to: usize) -> bool {
+        let (_, children) = self.get_self_node_mut();
+        if from >= children.len() || to >= children.len() {
+            return false;
+        }
+        let idx = children.remove(from);
+        children.insert(to, idx);
+        true
+    }
+
     pub fn create_compound(&mut self) -> CompoundRefMut<'_> {
         let idx = self.insert_node(NBTNode::Compound(Default::default()));
 
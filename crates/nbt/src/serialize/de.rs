@@ -0,0 +1,161 @@
+use serde::de::{
+    value::{BorrowedStrDeserializer, SeqDeserializer}, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor
+};
+use serde::Deserialize;
+
+use super::Error;
+use crate::*;
+
+/// Deserializes the root tag, usually into a struct. Strings are borrowed from the NBT
+pub fn from_nbt<'a, T: Deserialize<'a>>(nbt: &'a NBT) -> Result<T, Error> {
+    T::deserialize(NodeDeserializer {
+        nodes: &nbt.nodes,
+        node: &nbt.nodes[nbt.root_index],
+    })
+}
+
+pub fn from_compound<'a, T: Deserialize<'a>>(compound: CompoundRef<'a>) -> Result<T, Error> {
+    let nodes = &compound.nbt.nodes;
+    T::deserialize(NodeDeserializer {
+        nodes,
+        node: &nodes[compound.node_idx],
+    })
+}
+
+#[derive(Clone, Copy)]
+struct NodeDeserializer<'a> {
+    nodes: &'a Slab<NBTNode>,
+    node: &'a NBTNode,
+}
+
+fn visit_array<'de, V, T>(values: &'de [T], visitor: V) -> Result<V::Value, Error>
+where
+    V: Visitor<'de>,
+    T: IntoDeserializer<'de, Error> + Copy,
+{
+    let mut seq = SeqDeserializer::<_, Error>::new(values.iter().copied());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.node {
+            NBTNode::Byte(value) => visitor.visit_i8(*value),
+            NBTNode::Short(value) => visitor.visit_i16(*value),
+            NBTNode::Int(value) => visitor.visit_i32(*value),
+            NBTNode::Long(value) => visitor.visit_i64(*value),
+            NBTNode::Float(value) => visitor.visit_f32(*value),
+            NBTNode::Double(value) => visitor.visit_f64(*value),
+            NBTNode::ByteArray(values) => visit_array(values, visitor),
+            NBTNode::String(value) => visitor.visit_borrowed_str(value),
+            NBTNode::List { type_id: _, children } => visitor.visit_seq(ListAccess {
+                nodes: self.nodes,
+                children: children.iter(),
+            }),
+            NBTNode::Compound(compound) => visitor.visit_map(CompoundAccess {
+                nodes: self.nodes,
+                entries: compound.0.iter(),
+                value: None,
+            }),
+            NBTNode::IntArray(values) => visit_array(values, visitor),
+            NBTNode::LongArray(values) => visit_array(values, visitor),
+        }
+    }
+
+    // Booleans are stored as bytes
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.node {
+            NBTNode::Byte(value) => visitor.visit_bool(*value != 0),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    // Missing values are handled by serde, a value that is present is always Some
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // Only unit variants are supported, stored as a string
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.node {
+            NBTNode::String(value) => visitor.visit_enum(value.as_str().into_deserializer()),
+            _ => Err(serde::de::Error::custom("expected string for enum")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct ListAccess<'a> {
+    nodes: &'a Slab<NBTNode>,
+    children: std::slice::Iter<'a, usize>,
+}
+
+impl<'de> SeqAccess<'de> for ListAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        let Some(child) = self.children.next() else {
+            return Ok(None);
+        };
+
+        seed.deserialize(NodeDeserializer {
+            nodes: self.nodes,
+            node: &self.nodes[*child],
+        }).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.children.len())
+    }
+}
+
+struct CompoundAccess<'a> {
+    nodes: &'a Slab<NBTNode>,
+    entries: std::slice::Iter<'a, (String, usize)>,
+    value: Option<usize>,
+}
+
+impl<'de> MapAccess<'de> for CompoundAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        let Some((key, child)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(*child);
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let Some(child) = self.value.take() else {
+            return Err(serde::de::Error::custom("value requested before key"));
+        };
+
+        seed.deserialize(NodeDeserializer {
+            nodes: self.nodes,
+            node: &self.nodes[child],
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+mod de;
+pub use de::*;
+
+mod ser;
+pub use ser::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ServerEntry {
+        name: String,
+        ip: String,
+        #[serde(default)]
+        hidden: bool,
+        icon: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ServersDat {
+        servers: Vec<ServerEntry>,
+    }
+
+    #[test]
+    fn deserialize_from_snbt() {
+        let nbt = NBT::from_snbt(r#"{servers: [{name: "Local", ip: "localhost", hidden: 1b}, {name: "Other", ip: "example.com", icon: "aWNvbg=="}]}"#).unwrap();
+        let servers: ServersDat = serialize::from_nbt(&nbt).unwrap();
+        assert_eq!(servers.servers.len(), 2);
+        assert!(servers.servers[0].hidden);
+        assert_eq!(servers.servers[0].icon, None);
+        assert!(!servers.servers[1].hidden);
+        assert_eq!(servers.servers[1].icon.as_deref(), Some("aWNvbg=="));
+    }
+
+    #[test]
+    fn round_trip() {
+        let servers = ServersDat {
+            servers: vec![ServerEntry {
+                name: "Local".into(),
+                ip: "localhost".into(),
+                hidden: false,
+                icon: None,
+            }],
+        };
+
+        let nbt = serialize::to_nbt(&servers).unwrap();
+        assert_eq!(nbt.to_snbt(), r#"{servers: [{hidden: 0b, ip: "localhost", name: "Local"}]}"#);
+        assert_eq!(serialize::from_nbt::<ServersDat>(&nbt).unwrap(), servers);
+    }
+
+    #[test]
+    fn numeric_conversions() {
+        #[derive(Deserialize)]
+        struct Data {
+            #[serde(rename = "LastPlayed")]
+            last_played: i64,
+            values: Vec<i32>,
+        }
+
+        let nbt = NBT::from_snbt("{LastPlayed: 5, values: [I;1,2,3]}").unwrap();
+        let data: Data = serialize::from_nbt(&nbt).unwrap();
+        assert_eq!(data.last_played, 5);
+        assert_eq!(data.values, [1, 2, 3]);
+
+        assert!(serialize::to_nbt(&5_i32).is_err());
+        assert!(serialize::to_nbt(&vec![Some(1), None]).is_err());
+    }
+}
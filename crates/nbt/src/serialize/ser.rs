@@ -0,0 +1,531 @@
+use serde::ser::{
+    Error as _, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer
+};
+
+use super::Error;
+use crate::*;
+
+/// Serializes the value into a new NBT, the value must serialize to a compound (e.g. a struct or map)
+pub fn to_nbt<T: Serialize + ?Sized>(value: &T) -> Result<NBT, Error> {
+    let mut nodes = Slab::new();
+
+    let Some(root_index) = value.serialize(NodeSerializer { nodes: &mut nodes })? else {
+        return Err(Error::custom("root must not be None"));
+    };
+    if !matches!(nodes[root_index], NBTNode::Compound(_)) {
+        return Err(Error::custom("root must be a compound"));
+    }
+
+    Ok(NBT {
+        root_name: String::new(),
+        root_index,
+        nodes,
+    })
+}
+
+/// Inserts each serialized value as a node and returns its index. `None` is returned for values
+/// that have no NBT representation (`None` and `()`), these are skipped when they're a field
+struct NodeSerializer<'a> {
+    nodes: &'a mut Slab<NBTNode>,
+}
+
+impl<'a> NodeSerializer<'a> {
+    fn insert(self, node: NBTNode) -> Result<Option<usize>, Error> {
+        Ok(Some(self.nodes.insert(node)))
+    }
+}
+
+/// Enum variants with data are stored as a compound with a single entry, like serde_json does
+fn wrap_variant(nodes: &mut Slab<NBTNode>, variant: &'static str, value: usize) -> usize {
+    let mut compound = NBTCompound::default();
+    compound.insert(variant, value);
+    nodes.insert(NBTNode::Compound(compound))
+}
+
+impl<'a> Serializer for NodeSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer<'a>;
+    type SerializeTuple = ListSerializer<'a>;
+    type SerializeTupleStruct = ListSerializer<'a>;
+    type SerializeTupleVariant = ListSerializer<'a>;
+    type SerializeMap = CompoundSerializer<'a>;
+    type SerializeStruct = CompoundSerializer<'a>;
+    type SerializeStructVariant = CompoundSerializer<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Byte(v as i8))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Byte(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Short(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Int(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Long(v))
+    }
+
+    // NBT has no unsigned types, use the next larger signed type so that no values are lost
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Short(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Int(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Long(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+        let v = i64::try_from(v).map_err(|_| Error::custom("u64 value is out of range"))?;
+        self.insert(NBTNode::Long(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Double(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::String(v.into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::ByteArray(v.iter().map(|byte| *byte as i8).collect()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::Compound(NBTCompound::default()))
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Error> {
+        self.insert(NBTNode::String(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        let nodes = self.nodes;
+        let Some(value) = value.serialize(NodeSerializer { nodes: &mut *nodes })? else {
+            return Ok(Some(nodes.insert(NBTNode::String(variant.into()))));
+        };
+        Ok(Some(wrap_variant(nodes, variant, value)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(ListSerializer {
+            nodes: self.nodes,
+            children: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(ListSerializer {
+            nodes: self.nodes,
+            children: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(CompoundSerializer {
+            nodes: self.nodes,
+            compound: NBTCompound::default(),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(CompoundSerializer {
+            nodes: self.nodes,
+            compound: NBTCompound::default(),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+struct ListSerializer<'a> {
+    nodes: &'a mut Slab<NBTNode>,
+    children: Vec<usize>,
+    variant: Option<&'static str>,
+}
+
+impl<'a> ListSerializer<'a> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let Some(child) = value.serialize(NodeSerializer { nodes: &mut *self.nodes })? else {
+            return Err(Error::custom("lists can't contain None or ()"));
+        };
+
+        if let Some(first) = self.children.first()
+            && self.nodes[*first].get_type() != self.nodes[child].get_type()
+        {
+            return Err(Error::custom("elements in list have different types"));
+        }
+
+        self.children.push(child);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<usize>, Error> {
+        let type_id = match self.children.first() {
+            Some(first) => self.nodes[*first].get_type(),
+            None => TAG_END_ID,
+        };
+
+        let list = self.nodes.insert(NBTNode::List {
+            type_id,
+            children: self.children,
+        });
+
+        match self.variant {
+            Some(variant) => Ok(Some(wrap_variant(self.nodes, variant, list))),
+            None => Ok(Some(list)),
+        }
+    }
+}
+
+impl<'a> SerializeSeq for ListSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTuple for ListSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleStruct for ListSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleVariant for ListSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+struct CompoundSerializer<'a> {
+    nodes: &'a mut Slab<NBTNode>,
+    compound: NBTCompound,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl<'a> CompoundSerializer<'a> {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        if self.compound.find(key).is_some() {
+            return Err(Error::custom(format!("duplicate key: {}", key)));
+        }
+
+        if let Some(child) = value.serialize(NodeSerializer { nodes: &mut *self.nodes })? {
+            self.compound.insert(key, child);
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<usize>, Error> {
+        let compound = self.nodes.insert(NBTNode::Compound(self.compound));
+
+        match self.variant {
+            Some(variant) => Ok(Some(wrap_variant(self.nodes, variant, compound))),
+            None => Ok(Some(compound)),
+        }
+    }
+}
+
+impl<'a> SerializeMap for CompoundSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let Some(key) = self.key.take() else {
+            return Err(Error::custom("value serialized before key"));
+        };
+        self.insert(&key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStruct for CompoundSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStructVariant for CompoundSerializer<'a> {
+    type Ok = Option<usize>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+/// Compound keys must be strings, numbers are converted like serde_json does
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported() -> Error {
+        Error::custom("compound keys must be strings")
+    }
+}
+
+impl Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<String, Error> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Self::unsupported())
+    }
+}
@@ -7,16 +7,8 @@ use crate::*;
 pub fn from_snbt(mut snbt: &str) -> anyhow::Result<NBT> {
     let mut nodes = Slab::new();
 
-    // Make sure snbt starts with an opening brace
-    let next_char = peek_non_whitespace(&mut snbt)?;
-    if next_char == '{' {
-        snbt = &snbt[1..];
-    } else {
-        bail!("from_snbt: snbt must start with opening brace ({{)")
-    }
-
-    // Parse the root compound
-    let children = read_compound(&mut snbt, &mut nodes)?;
+    // Parse the root value, usually a compound
+    let (root_index, _type_id) = read_node(&mut snbt, &mut nodes, 0)?;
 
     // Make sure there is no more input
     for c in snbt.chars() {
@@ -25,8 +17,6 @@ pub fn from_snbt(mut snbt: &str) -> anyhow::Result<NBT> {
         }
     }
 
-    let root_index = nodes.insert(NBTNode::Compound(children));
-
     Ok(NBT {
         root_name: String::new(),
         root_index,
@@ -34,33 +24,26 @@ pub fn from_snbt(mut snbt: &str) -> anyhow::Result<NBT> {
     })
 }
 
-fn read_node(snbt: &mut &str, nodes: &mut Slab<NBTNode>) -> anyhow::Result<(usize, TagType)> {
+fn read_node(snbt: &mut &str, nodes: &mut Slab<NBTNode>, depth: usize) -> anyhow::Result<(usize, TagType)> {
     let (node, type_id) = match peek_non_whitespace(snbt)? {
-        '0'..='9' | '.' | '-' => read_numeric_node(snbt)?,
         '{' => {
+            if depth > 512 {
+                bail!("tried to read NBT tag with too high complexity, depth > 512")
+            }
+
             *snbt = &snbt[1..];
-            (NBTNode::Compound(read_compound(snbt, nodes)?), TAG_COMPOUND_ID)
+            (NBTNode::Compound(read_compound(snbt, nodes, depth + 1)?), TAG_COMPOUND_ID)
         },
         '[' => {
-            *snbt = &snbt[1..];
-            read_array_node(snbt, nodes)?
-        },
-        '"' => (NBTNode::String(read_string(snbt)?), TAG_STRING_ID),
-        't' => {
-            if snbt.len() >= 4 && &snbt[..4] == "true" {
-                (NBTNode::Byte(1), TAG_BYTE_ID)
-            } else {
-                bail!("unknown start of type: t");
-            }
-        },
-        'f' => {
-            if snbt.len() >= 5 && &snbt[..5] == "false" {
-                (NBTNode::Byte(0), TAG_BYTE_ID)
-            } else {
-                bail!("unknown start of type: f");
+            if depth > 512 {
+                bail!("tried to read NBT tag with too high complexity, depth > 512")
             }
+
+            *snbt = &snbt[1..];
+            read_array_node(snbt, nodes, depth + 1)?
         },
-        c => bail!("unknown start of type: {}", c),
+        '"' | '\'' => (NBTNode::String(read_string(snbt)?), TAG_STRING_ID),
+        _ => parse_unquoted_node(read_unquoted(snbt)?),
     };
 
     let idx = nodes.insert(node);
@@ -79,7 +62,59 @@ fn peek_non_whitespace(snbt: &mut &str) -> anyhow::Result<char> {
     bail!("next_char: unexpected end of input");
 }
 
-fn read_compound(snbt: &mut &str, nodes: &mut Slab<NBTNode>) -> anyhow::Result<NBTCompound> {
+fn is_unquoted_char(c: char) -> bool {
+    matches!(c, '0'..='9' | 'A'..='Z' | 'a'..='z' | '.' | '_' | '+' | '-')
+}
+
+/// Reads a token matching `[A-Za-z0-9._+-]+`
+fn read_unquoted<'a>(snbt: &mut &'a str) -> anyhow::Result<&'a str> {
+    let end = snbt.find(|c: char| !is_unquoted_char(c)).unwrap_or(snbt.len());
+    if end == 0 {
+        match snbt.chars().next() {
+            Some(c) => bail!("read_unquoted: unexpected character: {}", c),
+            None => bail!("read_unquoted: unexpected end of input"),
+        }
+    }
+
+    let token = &snbt[..end];
+    *snbt = &snbt[end..];
+    Ok(token)
+}
+
+/// Unquoted values are booleans, numbers (with an optional type suffix) or otherwise strings
+fn parse_unquoted_node(token: &str) -> (NBTNode, TagType) {
+    match token {
+        "true" => return (NBTNode::Byte(1), TAG_BYTE_ID),
+        "false" => return (NBTNode::Byte(0), TAG_BYTE_ID),
+        _ => {},
+    }
+
+    parse_numeric_node(token).unwrap_or_else(|| (NBTNode::String(token.into()), TAG_STRING_ID))
+}
+
+fn parse_numeric_node(token: &str) -> Option<(NBTNode, TagType)> {
+    if !token.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.')) {
+        return None;
+    }
+
+    let (number, suffix) = token.split_at(token.len() - 1);
+    // Rust accepts "inf" and "nan" when parsing floats, snbt doesn't
+    let is_number = |number: &str| number.chars().all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+
+    match suffix {
+        "b" | "B" => number.parse().ok().map(|value| (NBTNode::Byte(value), TAG_BYTE_ID)),
+        "s" | "S" => number.parse().ok().map(|value| (NBTNode::Short(value), TAG_SHORT_ID)),
+        "l" | "L" => number.parse().ok().map(|value| (NBTNode::Long(value), TAG_LONG_ID)),
+        "f" | "F" if is_number(number) => number.parse().ok().map(|value| (NBTNode::Float(value), TAG_FLOAT_ID)),
+        "d" | "D" if is_number(number) => number.parse().ok().map(|value| (NBTNode::Double(value), TAG_DOUBLE_ID)),
+        _ if !is_number(token) => None,
+        // Like vanilla, a number without a suffix is only a double if it has a decimal point, "1e3" is a string
+        _ if token.contains('.') => token.parse().ok().map(|value| (NBTNode::Double(value), TAG_DOUBLE_ID)),
+        _ => token.parse().ok().map(|value| (NBTNode::Int(value), TAG_INT_ID)),
+    }
+}
+
+fn read_compound(snbt: &mut &str, nodes: &mut Slab<NBTNode>, depth: usize) -> anyhow::Result<NBTCompound> {
     let mut children = NBTCompound(Vec::new());
 
     // Special case for empty compound `{}`
@@ -98,7 +133,7 @@ fn read_compound(snbt: &mut &str, nodes: &mut Slab<NBTNode>) -> anyhow::Result<N
             bail!("read_compound: key must be followed by a colon (:)")
         }
 
-        let (idx, _type_id) = read_node(snbt, nodes)?;
+        let (idx, _type_id) = read_node(snbt, nodes, depth)?;
 
         match children.binary_search(name.as_ref()) {
             Ok(_) => bail!("read_compound: duplicate key"),
@@ -119,278 +154,126 @@ fn read_compound(snbt: &mut &str, nodes: &mut Slab<NBTNode>) -> anyhow::Result<N
 }
 
 fn read_key(snbt: &mut &str) -> anyhow::Result<String> {
-    let first_char = peek_non_whitespace(snbt)?;
-
-    if first_char == '"' {
-        read_string(snbt)
-    } else {
-        for (index, c) in snbt.char_indices() {
-            match c {
-                '0'..='9' | 'A'..='Z' | 'a'..='z' | '.' | '_' | '+' | '-' => continue,
-                ':' => {
-                    let string = snbt[..index].into();
-                    *snbt = &snbt[index..];
-                    return Ok(string);
-                },
-                c => bail!("read_key: invalid character: {}", c),
-            }
-        }
-        bail!("read_key: unexpected end of input");
+    match peek_non_whitespace(snbt)? {
+        '"' | '\'' => read_string(snbt),
+        _ => Ok(read_unquoted(snbt)?.into()),
     }
 }
 
+/// Reads a string quoted with either double or single quotes. Only backslashes and quotes can be escaped
 fn read_string(snbt: &mut &str) -> anyhow::Result<String> {
-    let first_char = peek_non_whitespace(snbt)?;
-
-    if first_char != '"' {
-        bail!("read_string: first character must be quote literal (\")");
-    } else {
-        *snbt = &snbt[1..];
+    let quote = peek_non_whitespace(snbt)?;
+    if quote != '"' && quote != '\'' {
+        bail!("read_string: first character must be a quote literal (\" or ')");
+    }
+    *snbt = &snbt[1..];
 
-        let mut string = String::new();
-        let mut start = 0;
-        let mut escaping = false;
+    let mut string = String::new();
+    let mut escaping = false;
 
-        for (index, c) in snbt.char_indices() {
+    for (index, c) in snbt.char_indices() {
+        if escaping {
             match c {
-                '\\' => {
-                    if escaping {
-                        escaping = false;
-                    } else {
-                        string.push_str(&snbt[start..index]);
-                        start = index + 1;
-                    }
-                },
-                '"' => {
-                    if escaping {
-                        escaping = false;
-                    } else {
-                        string.push_str(&snbt[start..index]);
-                        *snbt = &snbt[(index + 1)..];
-                        return Ok(string);
-                    }
-                },
-                c => {
-                    if escaping {
-                        bail!("read_string: unknown escape sequence: \\{}", c);
-                    } else {
-                        continue;
-                    }
-                },
+                '\\' | '"' | '\'' => string.push(c),
+                c => bail!("read_string: unknown escape sequence: \\{}", c),
             }
+            escaping = false;
+        } else if c == '\\' {
+            escaping = true;
+        } else if c == quote {
+            *snbt = &snbt[(index + 1)..];
+            return Ok(string);
+        } else {
+            string.push(c);
         }
-        bail!("read_string: unexpected end of input");
     }
+    bail!("read_string: unexpected end of input");
 }
 
-fn read_numeric_node(snbt: &mut &str) -> anyhow::Result<(NBTNode, TagType)> {
-    let mut has_decimal = false;
-    for (index, c) in snbt.char_indices() {
-        match c {
-            '-' => {
-                if index != 0 {
-                    bail!("read_numeric_node: minus literal (-) is only valid at the beginning")
-                }
-            },
-            '0'..='9' => {
-                continue;
-            },
-            '.' => {
-                if has_decimal {
-                    bail!("read_numeric_node: found multiple decimal points while parsing number");
-                } else {
-                    has_decimal = true;
-                }
-                continue;
-            },
-            'b' | 'B' => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[(index + 1)..];
-                return Ok((NBTNode::Byte(number_string.parse()?), TAG_BYTE_ID));
-            },
-            's' | 'S' => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[(index + 1)..];
-                return Ok((NBTNode::Short(number_string.parse()?), TAG_SHORT_ID));
-            },
-            'l' | 'L' => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[(index + 1)..];
-                return Ok((NBTNode::Long(number_string.parse()?), TAG_LONG_ID));
-            },
-            'f' | 'F' => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[(index + 1)..];
-                return Ok((NBTNode::Float(number_string.parse()?), TAG_FLOAT_ID));
-            },
-            'd' | 'D' => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[(index + 1)..];
-                return Ok((NBTNode::Double(number_string.parse()?), TAG_DOUBLE_ID));
-            },
-            _ => {
-                let number_string = &snbt[..index];
-                *snbt = &snbt[index..];
-                if has_decimal {
-                    return Ok((NBTNode::Double(number_string.parse()?), TAG_DOUBLE_ID));
-                } else {
-                    return Ok((NBTNode::Int(number_string.parse()?), TAG_INT_ID));
-                }
-            },
-        }
-    }
-    bail!("read_numeric_node: unexpected end of input");
-}
+fn read_array_node(snbt: &mut &str, nodes: &mut Slab<NBTNode>, depth: usize) -> anyhow::Result<(NBTNode, TagType)> {
+    let next_char = peek_non_whitespace(snbt)?;
 
-enum PrimArrParseState {
-    WaitingForNumber,
-    WaitingForComma,
-    InNumber { start: usize },
-}
+    // Typed arrays have the semicolon directly after the type, otherwise `[B]` would be a list with the string B
+    if snbt[next_char.len_utf8()..].starts_with(';') {
+        let prefix = next_char;
+        *snbt = &snbt[(prefix.len_utf8() + 1)..];
+        return match prefix {
+            'B' => Ok((NBTNode::ByteArray(read_primitive_array(snbt, &['b', 'B'])?), TAG_BYTE_ARRAY_ID)),
+            'I' => Ok((NBTNode::IntArray(read_primitive_array(snbt, &[])?), TAG_INT_ARRAY_ID)),
+            'L' => Ok((NBTNode::LongArray(read_primitive_array(snbt, &['l', 'L'])?), TAG_LONG_ARRAY_ID)),
+            c => bail!("read_array_node: unknown array type: {}", c),
+        };
+    }
 
-fn read_array_node(snbt: &mut &str, nodes: &mut Slab<NBTNode>) -> anyhow::Result<(NBTNode, TagType)> {
-    let next_char = peek_non_whitespace(snbt)?;
-    match next_char {
-        // Primitive ByteArray
-        'B' => {
-            *snbt = &snbt[1..];
-            match peek_non_whitespace(snbt)? {
-                ';' => *snbt = &snbt[1..],
-                _ => bail!("read_array_node: expect semicolon (;) after B"),
-            }
+    // Special case for empty list `[]`
+    if next_char == ']' {
+        *snbt = &snbt[1..];
+        return Ok((
+            NBTNode::List {
+                type_id: TAG_END_ID,
+                children: Vec::new(),
+            },
+            TAG_LIST_ID,
+        ));
+    }
 
-            Ok((NBTNode::ByteArray(read_primitive_array(snbt)?), TAG_BYTE_ARRAY_ID))
-        },
-        // Primitive IntArray
-        'I' => {
-            *snbt = &snbt[1..];
-            match peek_non_whitespace(snbt)? {
-                ';' => *snbt = &snbt[1..],
-                _ => bail!("read_array_node: expect semicolon (;) after I"),
-            }
+    // Normal list
+    let mut children = Vec::new();
 
-            Ok((NBTNode::IntArray(read_primitive_array(snbt)?), TAG_INT_ARRAY_ID))
-        },
-        // Primitive LongArray
-        'L' => {
-            *snbt = &snbt[1..];
-            match peek_non_whitespace(snbt)? {
-                ';' => *snbt = &snbt[1..],
-                _ => bail!("read_array_node: expect semicolon (;) after L"),
-            }
+    let (idx, first_type_id) = read_node(snbt, nodes, depth)?;
+    children.push(idx);
 
-            Ok((NBTNode::LongArray(read_primitive_array(snbt)?), TAG_LONG_ARRAY_ID))
-        },
-        // Special case for empty list `[]`
-        ']' => {
-            *snbt = &snbt[1..];
-            Ok((
-                NBTNode::List {
-                    type_id: TAG_END_ID,
-                    children: Vec::new(),
-                },
-                TAG_LIST_ID,
-            ))
-        },
-        // Normal list
-        _ => {
-            let mut children = Vec::new();
-
-            let (idx, first_type_id) = read_node(snbt, nodes)?;
-            children.push(idx);
-
-            loop {
-                match peek_non_whitespace(snbt)? {
-                    ']' => {
-                        *snbt = &snbt[1..];
-                        return Ok((
-                            NBTNode::List {
-                                type_id: first_type_id,
-                                children,
-                            },
-                            TAG_LIST_ID,
-                        ));
+    loop {
+        match peek_non_whitespace(snbt)? {
+            ']' => {
+                *snbt = &snbt[1..];
+                return Ok((
+                    NBTNode::List {
+                        type_id: first_type_id,
+                        children,
                     },
-                    ',' => *snbt = &snbt[1..],
-                    c => bail!("read_array_node: unknown continuation: {}", c),
-                }
+                    TAG_LIST_ID,
+                ));
+            },
+            ',' => *snbt = &snbt[1..],
+            c => bail!("read_array_node: unknown continuation: {}", c),
+        }
 
-                let (idx, type_id) = read_node(snbt, nodes)?;
-                children.push(idx);
+        let (idx, type_id) = read_node(snbt, nodes, depth)?;
+        children.push(idx);
 
-                if type_id != first_type_id {
-                    bail!("read_array_node: elements in array have different type")
-                }
-            }
-        },
+        if type_id != first_type_id {
+            bail!("read_array_node: elements in array have different type")
+        }
     }
 }
 
-fn read_primitive_array<T: FromStr>(snbt: &mut &str) -> anyhow::Result<Vec<T>> {
+/// Reads the elements of a typed array, each element may have one of the given suffixes
+fn read_primitive_array<T: FromStr>(snbt: &mut &str, suffixes: &[char]) -> anyhow::Result<Vec<T>> {
     let mut values = Vec::new();
-    let mut state = PrimArrParseState::WaitingForNumber;
-    for (index, c) in snbt.char_indices() {
-        match c {
-            ']' => {
-                match state {
-                    PrimArrParseState::WaitingForComma => (),
-                    PrimArrParseState::WaitingForNumber => {
-                        bail!("read_primitive_array: expected numeric character, got ]")
-                    },
-                    PrimArrParseState::InNumber { start } => {
-                        let value: T = snbt[start..index]
-                            .parse()
-                            .map_err(|_| anyhow::anyhow!("read_primitive_array: failed to parse"))?;
-                        values.push(value);
-                    },
-                }
 
-                *snbt = &snbt[(index + 1)..];
+    // Special case for empty array `[I;]`
+    if peek_non_whitespace(snbt)? == ']' {
+        *snbt = &snbt[1..];
+        return Ok(values);
+    }
+
+    loop {
+        peek_non_whitespace(snbt)?;
+        let token = read_unquoted(snbt)?;
+        let number = token.strip_suffix(suffixes).unwrap_or(token);
+        let value: T = number
+            .parse()
+            .map_err(|_| anyhow::anyhow!("read_primitive_array: failed to parse `{}`", token))?;
+        values.push(value);
+
+        match peek_non_whitespace(snbt)? {
+            ']' => {
+                *snbt = &snbt[1..];
                 return Ok(values);
             },
-            '0'..='9' | '-' => match state {
-                PrimArrParseState::WaitingForNumber => state = PrimArrParseState::InNumber { start: index },
-                PrimArrParseState::InNumber { start: _ } => continue,
-                PrimArrParseState::WaitingForComma => {
-                    bail!("read_primitive_array: expected comma, got numeric character")
-                },
-            },
-            ',' => {
-                match state {
-                    PrimArrParseState::WaitingForComma => (),
-                    PrimArrParseState::WaitingForNumber => {
-                        bail!("read_primitive_array: expected numeric character, got comma")
-                    },
-                    PrimArrParseState::InNumber { start } => {
-                        let value: T = snbt[start..index]
-                            .parse()
-                            .map_err(|_| anyhow::anyhow!("read_primitive_array: failed to parse"))?;
-                        values.push(value);
-                    },
-                }
-                state = PrimArrParseState::WaitingForNumber;
-            },
-            ' ' => continue,
-            c => {
-                // todo: this is very permissive
-                // this should only allow b/B (for byte arrays), l/L (for long arrays) and nothing for int arrays
-                match state {
-                    PrimArrParseState::WaitingForComma => {
-                        bail!("read_primitive_array: expected comma, got `{}`", c)
-                    },
-                    PrimArrParseState::WaitingForNumber => {
-                        bail!("read_primitive_array: expected numeric character, got `{}`", c)
-                    },
-                    PrimArrParseState::InNumber { start } => {
-                        let value: T = snbt[start..index]
-                            .parse()
-                            .map_err(|_| anyhow::anyhow!("read_primitive_array: failed to parse"))?;
-                        values.push(value);
-                    },
-                }
-                state = PrimArrParseState::WaitingForComma;
-            },
+            ',' => *snbt = &snbt[1..],
+            c => bail!("read_primitive_array: unknown continuation: {}", c),
         }
     }
-    bail!("read_array_node: unexpected end of input");
 }
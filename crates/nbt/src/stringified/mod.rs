@@ -3,3 +3,80 @@ pub use to_snbt::*;
 
 mod from_snbt;
 pub use from_snbt::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn round_trip() {
+        let snbt = r#"{"a b": 'say "hi"', bytes: [B;1b,-2b], count: 3b, id: "minecraft:stone", ints: [I;1,2,3], longs: [L;5L], nested: {list: [1.5d, 2.0d], short: 7s}, value: 1.5f}"#;
+        let nbt = NBT::from_snbt(snbt).unwrap();
+        assert_eq!(nbt.to_snbt(), r#"{"a b": 'say "hi"', bytes: [B;1b,-2b], count: 3b, id: "minecraft:stone", ints: [I;1,2,3], longs: [L;5L], nested: {list: [1.5d, 2d], short: 7s}, value: 1.5f}"#);
+        assert_eq!(NBT::from_snbt(&nbt.to_snbt()).unwrap(), nbt);
+    }
+
+    #[test]
+    fn unquoted_values() {
+        let nbt = NBT::from_snbt("{ flag : true, name: Steve, version: 1.20.1, big: 3000000000, exp: 1.5e3, neg: -4 }").unwrap();
+        let compound = nbt.as_compound().unwrap();
+        assert_eq!(compound.find_byte("flag"), Some(&1));
+        assert_eq!(compound.find_string("name").map(String::as_str), Some("Steve"));
+        assert_eq!(compound.find_string("version").map(String::as_str), Some("1.20.1"));
+        assert_eq!(compound.find_string("big").map(String::as_str), Some("3000000000"));
+        assert_eq!(compound.find_double("exp"), Some(&1500.0));
+        assert_eq!(compound.find_int("neg"), Some(&-4));
+    }
+
+    #[test]
+    fn exponents() {
+        let nbt = NBT::from_snbt("{ a: 1e3, b: 1e3d, c: 2E-1f, d: 1.5e3, e: .5e1, f: 1.e2, g: -1e3D }").unwrap();
+        let compound = nbt.as_compound().unwrap();
+        assert_eq!(compound.find_string("a").map(String::as_str), Some("1e3"));
+        assert_eq!(compound.find_double("b"), Some(&1000.0));
+        assert_eq!(compound.find_float("c"), Some(&0.2));
+        assert_eq!(compound.find_double("d"), Some(&1500.0));
+        assert_eq!(compound.find_double("e"), Some(&5.0));
+        assert_eq!(compound.find_double("f"), Some(&100.0));
+        assert_eq!(compound.find_double("g"), Some(&-1000.0));
+    }
+
+    #[test]
+    fn escapes_and_arrays() {
+        let nbt = NBT::from_snbt(r#"{text: "a\"b\\c", list: [B], empty: [I; ], longs: [L; 1l, -2L]}"#).unwrap();
+        let compound = nbt.as_compound().unwrap();
+        assert_eq!(compound.find_string("text").map(String::as_str), Some(r#"a"b\c"#));
+        assert_eq!(compound.find_list("list", TAG_STRING_ID).map(|list| list.len()), Some(1));
+        assert_eq!(compound.find_int_array("empty"), Some(&Vec::new()));
+        assert_eq!(compound.find_long_array("longs"), Some(&vec![1, -2]));
+
+        assert!(NBT::from_snbt("{a: [1, 2b]}").is_err());
+        assert!(NBT::from_snbt("{a: \"unterminated}").is_err());
+        assert!(NBT::from_snbt("{a: 1} trailing").is_err());
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let nbt = NBT::from_snbt(&nested(512)).unwrap();
+        assert_eq!(NBT::from_snbt(&nbt.to_snbt()).unwrap(), nbt);
+
+        assert!(NBT::from_snbt(&nested(100_000)).is_err());
+        assert!(NBT::from_snbt(&format!("{}{}", "{a:".repeat(100_000), "}".repeat(100_000))).is_err());
+    }
+
+    #[test]
+    fn non_finite_values() {
+        let mut nbt = NBT::new();
+        let mut compound = nbt.as_compound_mut().unwrap();
+        compound.insert_float("nan", f32::NAN);
+        compound.insert_float("inf", f32::INFINITY);
+        compound.insert_double("neg_inf", f64::NEG_INFINITY);
+
+        let round_trip = NBT::from_snbt(&nbt.to_snbt()).unwrap();
+        let compound = round_trip.as_compound().unwrap();
+        assert_eq!(compound.find_float("nan"), Some(&0.0));
+        assert_eq!(compound.find_float("inf"), Some(&f32::MAX));
+        assert_eq!(compound.find_double("neg_inf"), Some(&f64::MIN));
+    }
+}
@@ -53,30 +53,33 @@ fn write_compound<T: Write>(writer: &mut T, nodes: &Slab<NBTNode>, children: &NB
 
 fn write_key<T: Write>(writer: &mut T, value: &str) -> std::fmt::Result {
     // String must match `[A-Za-z0-9._+-]+` to be unquoted
-    for c in value.chars() {
-        if matches!(c, '0'..='9' | 'A'..='Z' | 'a'..='z' | '.' | '_' | '+' | '-') {
-            // Contains invalid character, write a quoted string instead
-            return write_string(writer, value);
-        }
+    let valid_unquoted = !value.is_empty() && value.chars().all(|c| {
+        matches!(c, '0'..='9' | 'A'..='Z' | 'a'..='z' | '.' | '_' | '+' | '-')
+    });
+
+    if valid_unquoted {
+        writer.write_str(value)
+    } else {
+        write_string(writer, value)
     }
-
-    // All good to go - write the unquoted string
-    writer.write_str(value)
 }
 
 fn write_string<T: Write>(writer: &mut T, value: &str) -> std::fmt::Result {
-    writer.write_char('"')?;
+    // Prefer double quotes, use single quotes if that avoids having to escape
+    let quote = if value.contains('"') && !value.contains('\'') { '\'' } else { '"' };
+
+    writer.write_char(quote)?;
 
     for c in value.chars() {
         // Escape backslashes and quotes
-        if c == '\\' || c == '"' {
+        if c == '\\' || c == quote {
             writer.write_char('\\')?;
         }
         // Push the char
         writer.write_char(c)?;
     }
 
-    writer.write_char('"')
+    writer.write_char(quote)
 }
 
 // Note: doing write!("{}") and then push('b')
@@ -101,12 +104,15 @@ fn write_long<T: Write>(writer: &mut T, value: i64) -> std::fmt::Result {
     writer.write_char('L')
 }
 
+/// NaN and infinity have no snbt syntax, so they are written as 0 and the largest finite value
 fn write_float<T: Write>(writer: &mut T, value: f32) -> std::fmt::Result {
+    let value = if value.is_nan() { 0.0 } else { value.clamp(f32::MIN, f32::MAX) };
     write!(writer, "{}", value)?;
     writer.write_char('f')
 }
 
 fn write_double<T: Write>(writer: &mut T, value: f64) -> std::fmt::Result {
+    let value = if value.is_nan() { 0.0 } else { value.clamp(f64::MIN, f64::MAX) };
     write!(writer, "{}", value)?;
     writer.write_char('d')
 }
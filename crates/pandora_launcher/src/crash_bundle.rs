@@ -0,0 +1,12 @@
+use std::{fs::File, io::{BufWriter, Write}, path::Path};
+
+use backend::zip_writer::ZipWriter;
+
+/// Writes the files the user chose to include in a crash report into a zip
+pub fn write(path: &Path, files: &[(String, String)]) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+    for (name, contents) in files {
+        zip.add_bytes(name, contents.as_bytes()).map_err(std::io::Error::other)?;
+    }
+    zip.finish().map_err(std::io::Error::other)?.flush()
+}
@@ -0,0 +1,96 @@
+use std::{
+    io::{Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use bridge::{handle::FrontendHandle, message::MessageToFrontend};
+
+/// Holds the port the running launcher listens on for launchers that are started later
+const PORT_FILENAME: &str = "launcher.port";
+
+/// Sent by the running launcher first, so a port that was reused by another program isn't mistaken for it
+const GREETING: &[u8] = b"PandoraLauncher\n";
+
+/// Hands the files to open over to a launcher that's already running with the same data directory.
+/// Returns false if there isn't one, in which case this launcher should start as usual
+pub fn forward_to_running(launcher_dir: &Path, paths: &[PathBuf]) -> bool {
+    let Ok(port) = std::fs::read_to_string(launcher_dir.join(PORT_FILENAME)) else {
+        return false;
+    };
+    let Ok(port) = port.trim().parse::<u16>() else {
+        return false;
+    };
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, Duration::from_secs(1)) else {
+        return false;
+    };
+    _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+    _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+
+    let mut greeting = [0; GREETING.len()];
+    if stream.read_exact(&mut greeting).is_err() || greeting != GREETING {
+        return false;
+    }
+
+    let mut message = String::new();
+    for path in paths {
+        message.push_str(&path.to_string_lossy());
+        message.push('\n');
+    }
+    if let Err(error) = stream.write_all(message.as_bytes()) {
+        log::warn!("Unable to forward to the running launcher: {}", error);
+        return false;
+    }
+
+    log::info!("Launcher is already running, forwarded {} file(s) to it", paths.len());
+    true
+}
+
+/// Opens the files that launchers started later forward to this one
+pub fn listen(launcher_dir: &Path, frontend_handle: FrontendHandle) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::warn!("Unable to listen for other launchers: {}", error);
+            return;
+        },
+    };
+    let port = match listener.local_addr() {
+        Ok(address) => address.port(),
+        Err(error) => {
+            log::warn!("Unable to listen for other launchers: {}", error);
+            return;
+        },
+    };
+    if let Err(error) = std::fs::write(launcher_dir.join(PORT_FILENAME), port.to_string()) {
+        log::warn!("Unable to write {}: {}", PORT_FILENAME, error);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            if stream.write_all(GREETING).is_err() {
+                continue;
+            }
+
+            let mut message = String::new();
+            if stream.read_to_string(&mut message).is_err() {
+                continue;
+            }
+            let paths: Arc<[PathBuf]> = message.lines().filter(|line| !line.is_empty()).map(PathBuf::from).collect();
+            frontend_handle.send(MessageToFrontend::OpenFiles { paths });
+        }
+    });
+}
+
+/// Called once the launcher is closing, so later launchers don't try to reach it
+pub fn stop_listening(launcher_dir: &Path) {
+    _ = std::fs::remove_file(launcher_dir.join(PORT_FILENAME));
+}
@@ -2,7 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::fmt::Write;
 use std::time::SystemTime;
@@ -20,14 +20,24 @@ struct Args {
     /// Instance to launch, instead of opening the launcher
     #[arg(long)]
     run_instance: Option<String>,
+    /// Keep all data next to the executable, the same as placing a portable.txt file there
+    #[arg(long)]
+    portable: bool,
+    /// Modrinth modpacks (.mrpack) to install. If the launcher is already running, they're opened there
+    paths: Vec<PathBuf>,
 }
 
+pub mod crash_bundle;
+pub mod ipc;
 pub mod panic;
 
 fn main() {
     let args = Args::parse();
 
-    let data_dir = if let Some(portable_dir) = get_portable_dir() {
+    let portable_dir = get_portable_dir(args.portable);
+    let portable = portable_dir.is_some();
+
+    let data_dir = if let Some(portable_dir) = portable_dir {
         portable_dir
     } else {
         let base_dirs = directories::BaseDirs::new().unwrap();
@@ -35,14 +45,17 @@ fn main() {
     };
 
     let launcher_dir = data_dir.join("PandoraLauncher");
-    _ = std::env::set_current_dir(&launcher_dir);
 
-    let log_path = launcher_dir.join("launcher.log");
-    if log_path.exists() {
-        let old_log_path = launcher_dir.join("launcher.log.old");
-        _ = std::fs::rename(log_path, old_log_path);
+    // Relative paths are relative to where the launcher was started from, not the launcher directory
+    let paths: Vec<PathBuf> = args.paths.iter().map(|path| std::path::absolute(path).unwrap_or_else(|_| path.clone())).collect();
+    if args.run_instance.is_none() && ipc::forward_to_running(&launcher_dir, &paths) {
+        return;
     }
 
+    _ = std::env::set_current_dir(&launcher_dir);
+
+    rotate_logs(&launcher_dir);
+
     if let Err(error) = setup_logging(log::LevelFilter::Debug) {
         eprintln!("Unable to enable logging: {error:?}");
     }
@@ -52,10 +65,14 @@ fn main() {
 
     panic::install_logging_hook();
 
+    if args.run_instance.is_none() && backend::apply_staged_update() {
+        return;
+    }
+
     if let Some(run_instance) = args.run_instance {
         let (backend_recv, backend_handle, mut frontend_recv, frontend_handle) = bridge::handle::create_pair();
 
-        backend::start(launcher_dir.clone(), frontend_handle, backend_handle.clone(), backend_recv);
+        backend::start(launcher_dir.clone(), portable, frontend_handle, backend_handle.clone(), backend_recv);
 
         while let Some(message) = frontend_recv.try_recv() {
             if let MessageToFrontend::InstanceAdded { id, name, .. } = message {
@@ -69,6 +86,7 @@ fn main() {
                     });
                     run_modal_action(modal_action);
                     std::thread::sleep(std::time::Duration::from_millis(100));
+                    backend::flush_pending_writes();
                     return;
                 }
             }
@@ -77,7 +95,7 @@ fn main() {
         show_error(format!("Unable to find instance {}", run_instance));
         std::process::exit(1);
     } else {
-        run_gui(launcher_dir);
+        run_gui(launcher_dir, portable, paths);
     }
 }
 
@@ -162,7 +180,7 @@ fn run_modal_action(modal_action: ModalAction) {
     }
 }
 
-fn run_gui(launcher_dir: PathBuf) {
+fn run_gui(launcher_dir: PathBuf, portable: bool, paths: Vec<PathBuf>) {
     let panic_message = Arc::new(RwLock::new(None));
     let deadlock_message = Arc::new(RwLock::new(None));
 
@@ -200,8 +218,58 @@ fn run_gui(launcher_dir: PathBuf) {
         }
     });
 
-    backend::start(launcher_dir.clone(), frontend_handle, backend_handle.clone(), backend_recv);
-    frontend::start(launcher_dir.clone(), panic_message, deadlock_message, backend_handle, frontend_recv);
+    let (proxy, proxy_password) = backend::start(launcher_dir.clone(), portable, frontend_handle.clone(), backend_handle.clone(), backend_recv);
+
+    ipc::listen(&launcher_dir, frontend_handle.clone());
+    if !paths.is_empty() {
+        frontend_handle.send(MessageToFrontend::OpenFiles { paths: paths.into() });
+    }
+
+    let restart = frontend::start(launcher_dir.clone(), portable, panic_message, deadlock_message, crash_bundle::write,
+        backend_handle, frontend_handle, frontend_recv, proxy, proxy_password);
+    ipc::stop_listening(&launcher_dir);
+    backend::flush_pending_writes();
+
+    if restart {
+        restart_launcher();
+    }
+}
+
+fn restart_launcher() {
+    let current_exe = match std::env::current_exe() {
+        Ok(current_exe) => current_exe,
+        Err(error) => {
+            log::error!("Unable to restart launcher, couldn't find current executable: {}", error);
+            return;
+        },
+    };
+
+    // Files to open were already opened, only the flags are kept
+    let flags = std::env::args_os().skip(1).filter(|arg| arg.to_string_lossy().starts_with("--"));
+    if let Err(error) = std::process::Command::new(current_exe).args(flags).spawn() {
+        log::error!("Unable to restart launcher: {}", error);
+    }
+}
+
+/// How many logs from previous launches are kept, as launcher.log.1 (the most recent) to launcher.log.N
+const KEPT_LOGS: usize = 5;
+
+fn rotate_logs(launcher_dir: &Path) {
+    let log_path = launcher_dir.join("launcher.log");
+    if !log_path.exists() {
+        return;
+    }
+
+    // Logs used to only be kept for one launch
+    _ = std::fs::remove_file(launcher_dir.join("launcher.log.old"));
+
+    for index in (1..KEPT_LOGS).rev() {
+        let from = launcher_dir.join(format!("launcher.log.{}", index));
+        if from.exists() {
+            _ = std::fs::rename(from, launcher_dir.join(format!("launcher.log.{}", index + 1)));
+        }
+    }
+    _ = std::fs::rename(log_path, launcher_dir.join("launcher.log.1"));
 }
 
 fn setup_logging(level: log::LevelFilter) -> Result<(), fern::InitError> {
@@ -246,12 +314,15 @@ fn setup_logging(level: log::LevelFilter) -> Result<(), fern::InitError> {
     Ok(())
 }
 
-fn get_portable_dir() -> Option<PathBuf> {
+/// Portable installs keep their data next to the executable. They're enabled with the `--portable` flag,
+/// a `portable.txt` file next to the executable or an executable name containing "portable"
+fn get_portable_dir(portable_flag: bool) -> Option<PathBuf> {
     let current_exe = std::env::current_exe().ok()?;
+    let exe_dir = current_exe.parent()?;
     let file_name = current_exe.file_name()?;
     let file_name = file_name.to_string_lossy();
-    if file_name.to_lowercase().contains("portable") {
-        Some(current_exe.parent()?.into())
+    if portable_flag || exe_dir.join("portable.txt").exists() || file_name.to_lowercase().contains("portable") {
+        Some(exe_dir.into())
     } else {
         None
     }
@@ -45,6 +45,47 @@ impl ReqwestClient {
         Ok(client.into())
     }
 
+    /// Like `user_agent`, but never uses a proxy, even if one is configured by the system
+    pub fn no_proxy_and_user_agent(agent: &str) -> anyhow::Result<Self> {
+        let mut map = HeaderMap::new();
+        map.insert(http::header::USER_AGENT, HeaderValue::from_str(agent)?);
+        let client = Self::builder().default_headers(map).no_proxy().build()?;
+        Ok(client.into())
+    }
+
+    /// Like `proxy_and_user_agent`, but the proxy password is looked up for every request so it can be provided
+    /// after the client was created
+    pub fn proxy_with_password_and_user_agent(
+        proxy: Url,
+        password: impl Fn() -> Option<String> + Send + Sync + 'static,
+        user_agent: &str,
+    ) -> anyhow::Result<Self> {
+        let user_agent = HeaderValue::from_str(user_agent)?;
+
+        let mut map = HeaderMap::new();
+        map.insert(http::header::USER_AGENT, user_agent.clone());
+
+        let proxy_url = proxy.clone();
+        let reqwest_proxy = reqwest::Proxy::custom(move |_| {
+            let mut url = proxy_url.clone();
+            if !url.username().is_empty() && let Some(password) = password() {
+                _ = url.set_password(Some(&password));
+            }
+            Some(url)
+        });
+
+        let client = Self::builder()
+            .default_headers(map)
+            // Respect NO_PROXY env var
+            .proxy(reqwest_proxy.no_proxy(reqwest::NoProxy::from_env()))
+            .use_preconfigured_tls(http_client_tls::tls_config())
+            .build()?;
+        let mut client: ReqwestClient = client.into();
+        client.proxy = Some(proxy);
+        client.user_agent = Some(user_agent);
+        Ok(client)
+    }
+
     pub fn proxy_and_user_agent(proxy: Option<Url>, user_agent: &str) -> anyhow::Result<Self> {
         let user_agent = HeaderValue::from_str(user_agent)?;
 
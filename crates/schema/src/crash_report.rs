@@ -0,0 +1,114 @@
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A mod that Forge/NeoForge attributed a crash to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuspectedMod {
+    pub id: Arc<str>,
+    #[serde(default)]
+    pub name: Option<Arc<str>>,
+    #[serde(default)]
+    pub version: Option<Arc<str>>,
+}
+
+/// Mods that are part of the game itself, these can't be disabled so suggesting them isn't useful
+const IGNORED_MOD_IDS: &[&str] = &["minecraft", "forge", "neoforge", "fml", "mcp"];
+
+static SUSPECTED_MOD_ENTRY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([^,()\t]+?) \(([a-z][a-z0-9_.\-]{1,63})\)(?:, Version: ([^\s,]+))?").unwrap()
+});
+
+/// Parses the `Suspected Mod(s):` section of a crash report. Entries are either on the same line
+/// (older Forge) or on the following, further indented lines (newer Forge and NeoForge)
+pub fn parse_suspected_mods(report: &str) -> Vec<SuspectedMod> {
+    let mut suspected: Vec<SuspectedMod> = Vec::new();
+
+    let mut add_entries = |text: &str| {
+        for captures in SUSPECTED_MOD_ENTRY.captures_iter(text) {
+            let id = &captures[2];
+            if IGNORED_MOD_IDS.contains(&id) || suspected.iter().any(|existing| &*existing.id == id) {
+                continue;
+            }
+            suspected.push(SuspectedMod {
+                id: id.into(),
+                name: Some(captures[1].trim().into()),
+                version: captures.get(3).map(|version| version.as_str().into()),
+            });
+        }
+    };
+
+    let lines: Vec<&str> = report.lines().collect();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+
+        let trimmed = line.trim_start();
+        let Some(rest) = trimmed.strip_prefix("Suspected Mods:").or_else(|| trimmed.strip_prefix("Suspected Mod:")) else {
+            continue;
+        };
+        add_entries(rest);
+
+        let indent = line.len() - trimmed.len();
+        while index < lines.len() {
+            let next = lines[index];
+            let next_trimmed = next.trim_start();
+            if next_trimmed.is_empty() || next.len() - next_trimmed.len() <= indent {
+                break;
+            }
+            index += 1;
+
+            if next_trimmed.starts_with("Issue tracker URL:") || next_trimmed.starts_with("at ") {
+                continue;
+            }
+            add_entries(next_trimmed);
+        }
+    }
+
+    suspected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neoforge_multiline() {
+        let report = "---- Minecraft Crash Report ----\n\
+            -- Head --\n\
+            Thread: Render thread\n\
+            Suspected Mods: \n\
+            \tSodium (sodium), Version: 0.5.8\n\
+            \t\tIssue tracker URL: https://github.com/CaffeineMC/sodium/issues\n\
+            \t\tat TRANSFORMER/sodium@0.5.8/me.jellysquid.mods.sodium.Foo.bar(Foo.java:10)\n\
+            \tIris (iris), Version: 1.7.0\n\
+            Stacktrace:\n\
+            \tat Other (other), Version: 1\n";
+
+        let suspected = parse_suspected_mods(report);
+        assert_eq!(suspected.len(), 2);
+        assert_eq!(&*suspected[0].id, "sodium");
+        assert_eq!(suspected[0].name.as_deref(), Some("Sodium"));
+        assert_eq!(suspected[0].version.as_deref(), Some("0.5.8"));
+        assert_eq!(&*suspected[1].id, "iris");
+    }
+
+    #[test]
+    fn forge_inline() {
+        let report = "Description: Ticking entity\n\nSuspected Mod: Create (create), Version: mc1.16.5_v0.3.2e\n";
+        let suspected = parse_suspected_mods(report);
+        assert_eq!(suspected.len(), 1);
+        assert_eq!(&*suspected[0].id, "create");
+        assert_eq!(suspected[0].version.as_deref(), Some("mc1.16.5_v0.3.2e"));
+    }
+
+    #[test]
+    fn none_or_ignored() {
+        assert!(parse_suspected_mods("Suspected Mods: NONE\n").is_empty());
+        assert!(parse_suspected_mods("Suspected Mods: UNKNOWN\n").is_empty());
+        assert!(parse_suspected_mods("Suspected Mod: \n\tMinecraft (minecraft), Version: 1.21.1\n").is_empty());
+        assert!(parse_suspected_mods("Description: Unexpected error\n").is_empty());
+    }
+}
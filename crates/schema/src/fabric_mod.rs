@@ -8,20 +8,34 @@ pub struct FabricModJson {
     pub id: Arc<str>,
     pub version: Arc<str>,
     pub name: Option<Arc<str>>,
-    // pub description: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
     pub authors: Option<Vec<Person>>,
     pub icon: Option<Icon>,
-    // #[serde(alias = "requires")]
-    // pub depends: Option<HashMap<Arc<str>, Dependency>>,
+    pub contact: Option<Contact>,
+    #[serde(default, alias = "requires")]
+    pub depends: HashMap<Arc<str>, Dependency>,
     // pub breaks: Option<HashMap<Arc<str>, Dependency>>,
 }
 
-// #[derive(Deserialize, Debug)]
-// #[serde(untagged)]
-// enum Dependency {
-//     Single(Arc<str>),
-//     Multiple(Vec<Arc<str>>)
-// }
+/// Version predicates such as `>=0.15.0`, any one of several is enough
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Dependency {
+    Single(Arc<str>),
+    Multiple(Vec<Arc<str>>),
+    /// Not allowed by the spec, but shouldn't stop the rest of the file from being read
+    Invalid(serde::de::IgnoredAny),
+}
+
+impl Dependency {
+    pub fn predicates(&self) -> &[Arc<str>] {
+        match self {
+            Dependency::Single(predicate) => std::slice::from_ref(predicate),
+            Dependency::Multiple(predicates) => predicates,
+            Dependency::Invalid(_) => &[],
+        }
+    }
+}
 
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
@@ -30,6 +44,12 @@ pub enum Icon {
     Sizes(HashMap<usize, Arc<str>>),
 }
 
+#[derive(Deserialize, Debug)]
+pub struct Contact {
+    pub homepage: Option<Arc<str>>,
+    pub sources: Option<Arc<str>>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
 pub enum Person {
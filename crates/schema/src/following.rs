@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::modrinth::{ModrinthLoader, ModrinthProjectType, ModrinthProjectVersion, ModrinthVersionStatus};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FollowedProjects {
+    #[serde(default)]
+    pub projects: Vec<FollowedProject>,
+    /// Versions discovered up to this time (ms since epoch) have been seen in the feed
+    #[serde(default)]
+    pub seen_until: i64,
+}
+
+impl FollowedProjects {
+    pub fn is_following(&self, project_id: &str) -> bool {
+        self.projects.iter().any(|project| &*project.project_id == project_id)
+    }
+
+    pub fn get_mut(&mut self, project_id: &str) -> Option<&mut FollowedProject> {
+        self.projects.iter_mut().find(|project| &*project.project_id == project_id)
+    }
+
+    pub fn follow(&mut self, project: FollowedProject) {
+        if !self.is_following(&project.project_id) {
+            self.projects.push(project);
+        }
+    }
+
+    pub fn unfollow(&mut self, project_id: &str) {
+        self.projects.retain(|project| &*project.project_id != project_id);
+    }
+
+    /// Versions of every followed project, newest first
+    pub fn feed(&self) -> Vec<(&FollowedProject, &FollowedVersion)> {
+        let mut feed: Vec<_> = self.projects.iter()
+            .flat_map(|project| project.versions.iter().map(move |version| (project, version)))
+            .collect();
+        feed.sort_by_key(|(_, version)| std::cmp::Reverse(version.published_at));
+        feed
+    }
+
+    pub fn is_unseen(&self, project: &FollowedProject, version: &FollowedVersion) -> bool {
+        project.is_new_since(version, self.seen_until)
+    }
+
+    pub fn unseen_count(&self) -> usize {
+        self.projects.iter()
+            .flat_map(|project| project.versions.iter().map(move |version| (project, version)))
+            .filter(|(project, version)| self.is_unseen(project, version))
+            .count()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FollowedProject {
+    pub project_id: Arc<str>,
+    pub title: Arc<str>,
+    pub project_type: ModrinthProjectType,
+    #[serde(default)]
+    pub icon_url: Option<Arc<str>>,
+    /// When the project was followed, in ms since epoch
+    #[serde(default)]
+    pub followed_at: i64,
+    /// Newest versions first, at most `MAX_VERSIONS`
+    #[serde(default)]
+    pub versions: Vec<FollowedVersion>,
+}
+
+impl FollowedProject {
+    pub const MAX_VERSIONS: usize = 5;
+
+    /// Whether the version was discovered after `seen_until`. Versions are compared by when the launcher first saw
+    /// them rather than their publish date, since a version can be listed long after it was published. Versions
+    /// published before following the project never are new
+    pub fn is_new_since(&self, version: &FollowedVersion, seen_until: i64) -> bool {
+        version.discovered_at > seen_until && version.published_at > self.followed_at
+    }
+
+    /// Replaces the remembered versions with the newest listed ones, returns whether a version wasn't known before.
+    /// Versions that weren't known before are discovered at `now`
+    pub fn set_versions(&mut self, versions: &[ModrinthProjectVersion], now: i64) -> bool {
+        let mut newest: Vec<FollowedVersion> = versions.iter()
+            .filter(|version| matches!(version.status, None | Some(ModrinthVersionStatus::Listed)))
+            .filter_map(FollowedVersion::from_modrinth)
+            .collect();
+        newest.sort_by_key(|version| std::cmp::Reverse(version.published_at));
+        newest.truncate(Self::MAX_VERSIONS);
+
+        let mut added = false;
+        for version in &mut newest {
+            match self.versions.iter().find(|known| known.version_id == version.version_id) {
+                Some(known) => version.discovered_at = known.discovered_at,
+                None => {
+                    version.discovered_at = now;
+                    added = true;
+                },
+            }
+        }
+        self.versions = newest;
+        added
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FollowedVersion {
+    pub version_id: Arc<str>,
+    #[serde(default)]
+    pub name: Option<Arc<str>>,
+    #[serde(default)]
+    pub version_number: Option<Arc<str>>,
+    /// In ms since epoch
+    pub published_at: i64,
+    /// When the launcher first saw the version, in ms since epoch
+    #[serde(default)]
+    pub discovered_at: i64,
+    #[serde(default)]
+    pub game_versions: Arc<[Ustr]>,
+    #[serde(default)]
+    pub loaders: Arc<[ModrinthLoader]>,
+}
+
+impl FollowedVersion {
+    fn from_modrinth(version: &ModrinthProjectVersion) -> Option<Self> {
+        Some(Self {
+            version_id: version.id.clone(),
+            name: version.name.clone(),
+            version_number: version.version_number.clone(),
+            published_at: version.date_published?.timestamp_millis(),
+            discovered_at: 0,
+            game_versions: version.game_versions.clone().unwrap_or_default(),
+            loaders: version.loaders.clone().unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, date: &str, status: &str) -> ModrinthProjectVersion {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "project_id": "project",
+            "game_versions": ["1.21.1"],
+            "loaders": ["fabric"],
+            "status": status,
+            "files": [],
+            "date_published": date,
+        })).unwrap()
+    }
+
+    fn project() -> FollowedProject {
+        FollowedProject {
+            project_id: "project".into(),
+            title: "Project".into(),
+            project_type: ModrinthProjectType::Mod,
+            icon_url: None,
+            followed_at: 0,
+            versions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_newest_listed_versions() {
+        let mut project = project();
+        let mut versions: Vec<_> = (0..8)
+            .map(|day| version(&format!("v{day}"), &format!("2024-01-0{}T00:00:00Z", day + 1), "listed"))
+            .collect();
+        versions.push(version("draft", "2024-02-01T00:00:00Z", "draft"));
+
+        assert!(project.set_versions(&versions, 1));
+        let ids: Vec<&str> = project.versions.iter().map(|version| &*version.version_id).collect();
+        assert_eq!(ids, ["v7", "v6", "v5", "v4", "v3"]);
+
+        assert!(!project.set_versions(&versions, 2));
+        assert!(project.versions.iter().all(|version| version.discovered_at == 1));
+    }
+
+    #[test]
+    fn unseen_versions_are_counted() {
+        let mut project = project();
+        project.set_versions(&[version("old", "2024-01-01T00:00:00Z", "listed")], 10);
+        project.set_versions(&[
+            version("old", "2024-01-01T00:00:00Z", "listed"),
+            version("new", "2024-03-01T00:00:00Z", "listed"),
+        ], 30);
+        let followed = FollowedProjects {
+            projects: vec![project],
+            seen_until: 20,
+        };
+
+        assert_eq!(followed.unseen_count(), 1);
+        assert_eq!(&*followed.feed()[0].1.version_id, "new");
+    }
+
+    #[test]
+    fn late_listed_versions_are_unseen() {
+        let mut project = project();
+        project.set_versions(&[version("new", "2024-03-01T00:00:00Z", "listed")], 10);
+        // Published before the feed was seen, but only listed afterwards
+        project.set_versions(&[
+            version("new", "2024-03-01T00:00:00Z", "listed"),
+            version("moderated", "2024-02-01T00:00:00Z", "listed"),
+        ], 30);
+        let followed = FollowedProjects {
+            projects: vec![project],
+            seen_until: 20,
+        };
+
+        let unseen: Vec<&str> = followed.feed().into_iter()
+            .filter(|(project, version)| followed.is_unseen(project, version))
+            .map(|(_, version)| &*version.version_id)
+            .collect();
+        assert_eq!(unseen, ["moderated"]);
+    }
+}
@@ -1,10 +1,14 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
 pub struct ModsToml {
-    pub mods: Vec<ModsTomlMod>
+    pub mods: Vec<ModsTomlMod>,
+    /// Dependencies of each mod in the file, by mod id. Only used to find loader requirements, so a malformed
+    /// table mustn't stop the rest of the file from being read
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub dependencies: HashMap<Arc<str>, Vec<ModsTomlDependency>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -15,6 +19,32 @@ pub struct ModsTomlMod {
     pub logo_file: Option<Arc<str>>,
     pub version: Option<Arc<str>>,
     pub authors: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    #[serde(rename = "displayURL")]
+    pub display_url: Option<Arc<str>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModsTomlDependency {
+    pub mod_id: Option<Arc<str>>,
+    /// A Maven version range such as `[47.1,)`
+    pub version_range: Option<Arc<str>>,
+    /// Forge and older NeoForge versions
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub mandatory: Option<bool>,
+    /// Newer NeoForge versions, `required` by default
+    #[serde(rename = "type")]
+    pub kind: Option<Arc<str>>,
+}
+
+impl ModsTomlDependency {
+    pub fn is_required(&self) -> bool {
+        match &self.kind {
+            Some(kind) => kind.eq_ignore_ascii_case("required"),
+            None => self.mandatory.unwrap_or(true),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -26,3 +56,68 @@ pub struct JarJarMetadata {
 pub struct JarJarMetadataJar {
     pub path: Arc<str>,
 }
+
+/// Legacy Forge (1.12.2 and older) mcmod.info, either a plain list of mods or the version 2 object
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum McModInfo {
+    List(Vec<McModInfoMod>),
+    V2 {
+        #[serde(rename = "modList")]
+        mod_list: Vec<McModInfoMod>,
+    },
+}
+
+impl McModInfo {
+    pub fn mods(&self) -> &[McModInfoMod] {
+        match self {
+            McModInfo::List(mods) => mods,
+            McModInfo::V2 { mod_list } => mod_list,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct McModInfoMod {
+    pub modid: Arc<str>,
+    pub name: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    pub version: Option<Arc<str>>,
+    pub url: Option<Arc<str>>,
+    pub logo_file: Option<Arc<str>>,
+    #[serde(default, alias = "authors")]
+    pub author_list: Vec<Arc<str>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_dependencies_dont_fail_the_file() {
+        let mods_toml: ModsToml = toml::from_str(r#"
+            [[mods]]
+            modId = "example"
+
+            [dependencies.example]
+            modId = "forge"
+            mandatory = "true"
+        "#).unwrap();
+        assert_eq!(&*mods_toml.mods[0].mod_id, "example");
+        assert!(mods_toml.dependencies.is_empty());
+
+        let mods_toml: ModsToml = toml::from_str(r#"
+            [[mods]]
+            modId = "example"
+
+            [[dependencies.example]]
+            modId = "forge"
+            mandatory = "true"
+            versionRange = "[47,)"
+        "#).unwrap();
+        let dependency = &mods_toml.dependencies["example"][0];
+        assert!(dependency.mandatory.is_none());
+        assert!(dependency.is_required());
+    }
+}
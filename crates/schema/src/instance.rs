@@ -3,7 +3,7 @@ use std::{path::Path, sync::Arc};
 use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
-use crate::loader::Loader;
+use crate::{launch_history::LaunchHistoryQuickPlay, loader::Loader};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InstanceConfiguration {
@@ -17,6 +17,60 @@ pub struct InstanceConfiguration {
     pub jvm_flags: Option<InstanceJvmFlagsConfiguration>,
     #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_jvm_binary_configuration")]
     pub jvm_binary: Option<InstanceJvmBinaryConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_window_configuration")]
+    pub window: Option<InstanceWindowConfiguration>,
+    /// Pinned instances are shown before all other instances
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Whether the folders of the instance are watched for changes, by default they are unless they're on a network drive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_watching: Option<bool>,
+    /// Render on the discrete GPU using PRIME render offload, only used on Linux
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub discrete_gpu: bool,
+    /// The world or server the instance was last quick-played into, used to resume it
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "Option::is_none")]
+    pub last_quick_play: Option<LaunchHistoryQuickPlay>,
+}
+
+impl InstanceConfiguration {
+    pub fn new(minecraft_version: Ustr, loader: Loader) -> Self {
+        Self {
+            minecraft_version,
+            loader,
+            preferred_loader_version: None,
+            memory: None,
+            jvm_flags: None,
+            jvm_binary: None,
+            window: None,
+            pinned: false,
+            file_watching: None,
+            discrete_gpu: false,
+            last_quick_play: None,
+        }
+    }
+
+    /// The min and max memory in MiB passed to the JVM, `None` if the JVM picks its own defaults
+    pub fn effective_memory(&self) -> Option<(u32, u32)> {
+        let memory = self.memory.filter(|memory| memory.enabled)?;
+        Some((memory.min, memory.max.max(memory.min).max(128)))
+    }
+
+    /// JVM arguments added by the runtime settings. These are passed after the arguments
+    /// from the version and loader, so they win when both set the same option
+    pub fn runtime_jvm_arguments(&self) -> Vec<String> {
+        let mut arguments = Vec::new();
+
+        if let Some((min, max)) = self.effective_memory() {
+            arguments.push(format!("-Xms{}m", min));
+            arguments.push(format!("-Xmx{}m", max));
+        }
+        if let Some(jvm_flags) = &self.jvm_flags && jvm_flags.enabled {
+            arguments.extend(jvm_flags.split());
+        }
+
+        arguments
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -57,6 +111,17 @@ pub struct InstanceJvmFlagsConfiguration {
     pub flags: Arc<str>,
 }
 
+impl InstanceJvmFlagsConfiguration {
+    /// Splits the flags like a shell would, or on whitespace if the quoting is invalid
+    pub fn split(&self) -> Vec<String> {
+        shell_words::split(&self.flags).unwrap_or_else(|_| self.flags.split_whitespace().map(str::to_string).collect())
+    }
+
+    pub fn has_valid_quoting(&self) -> bool {
+        shell_words::split(&self.flags).is_ok()
+    }
+}
+
 fn is_default_jvm_flags_configuration(config: &Option<InstanceJvmFlagsConfiguration>) -> bool {
     if let Some(config) = config {
         !config.enabled && config.flags.trim_ascii().is_empty()
@@ -78,3 +143,19 @@ fn is_default_jvm_binary_configuration(config: &Option<InstanceJvmBinaryConfigur
         true
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InstanceWindowConfiguration {
+    pub enabled: bool,
+    /// Title of the game window, the instance name is used when empty
+    pub title: Arc<str>,
+    pub icon: Option<Arc<Path>>,
+}
+
+fn is_default_window_configuration(config: &Option<InstanceWindowConfiguration>) -> bool {
+    if let Some(config) = config {
+        !config.enabled && config.title.trim_ascii().is_empty() && config.icon.is_none()
+    } else {
+        true
+    }
+}
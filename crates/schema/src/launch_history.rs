@@ -0,0 +1,261 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::{crash_report::SuspectedMod, loader::Loader};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LaunchHistory {
+    #[serde(default)]
+    pub entries: Vec<LaunchHistoryEntry>,
+}
+
+impl LaunchHistory {
+    pub const MAX_ENTRIES: usize = 256;
+
+    pub fn push(&mut self, entry: LaunchHistoryEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > Self::MAX_ENTRIES {
+            let excess = self.entries.len() - Self::MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn last_running_mut(&mut self) -> Option<&mut LaunchHistoryEntry> {
+        self.entries.iter_mut().rev().find(|entry| entry.outcome == LaunchOutcome::Running)
+    }
+
+    /// Number of entries whose game hasn't been seen exiting yet
+    pub fn running_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.outcome == LaunchOutcome::Running).count()
+    }
+
+    /// Marks running entries whose game is no longer tracked, e.g. because the launcher was closed before
+    /// it exited, as having an unknown outcome. The newest one is left alone if `keep_newest` is set
+    pub fn clear_stale_running(&mut self, keep_newest: bool) {
+        let mut keep = keep_newest;
+        for entry in self.entries.iter_mut().rev().filter(|entry| entry.outcome == LaunchOutcome::Running) {
+            if std::mem::take(&mut keep) {
+                continue;
+            }
+            entry.outcome = LaunchOutcome::Unknown;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LaunchHistoryEntry {
+    /// Unix timestamp in milliseconds
+    pub started_at: i64,
+    #[serde(default)]
+    pub account: Option<Arc<str>>,
+    #[serde(default)]
+    pub offline: bool,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    #[serde(default)]
+    pub loader_version: Option<Ustr>,
+    #[serde(default)]
+    pub quick_play: Option<LaunchHistoryQuickPlay>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub outcome: LaunchOutcome,
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub error: Option<Arc<str>>,
+    /// Enabled mods at the time of the launch, None for entries recorded before snapshots existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mods: Option<Arc<[LaunchHistoryMod]>>,
+    /// Mods blamed by the crash report of a crashed launch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspected_mods: Option<Arc<[SuspectedMod]>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LaunchHistoryMod {
+    pub filename: Arc<str>,
+    #[serde(default)]
+    pub id: Option<Arc<str>>,
+    #[serde(default)]
+    pub name: Option<Arc<str>>,
+    pub version: Arc<str>,
+    pub sha1: Arc<str>,
+}
+
+impl LaunchHistoryMod {
+    fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.filename)
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.filename)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ModsDiff {
+    pub added: Vec<LaunchHistoryMod>,
+    pub removed: Vec<LaunchHistoryMod>,
+    pub updated: Vec<(LaunchHistoryMod, LaunchHistoryMod)>,
+}
+
+impl ModsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Compares two mod snapshots, matching mods by their mod id (or filename if the id is unknown)
+pub fn diff_mods(old: &[LaunchHistoryMod], new: &[LaunchHistoryMod]) -> ModsDiff {
+    let mut diff = ModsDiff::default();
+
+    for new_mod in new {
+        match old.iter().find(|old_mod| old_mod.key() == new_mod.key()) {
+            Some(old_mod) => {
+                if old_mod.sha1 != new_mod.sha1 {
+                    diff.updated.push((old_mod.clone(), new_mod.clone()));
+                }
+            },
+            None => diff.added.push(new_mod.clone()),
+        }
+    }
+
+    for old_mod in old {
+        if !new.iter().any(|new_mod| new_mod.key() == old_mod.key()) {
+            diff.removed.push(old_mod.clone());
+        }
+    }
+
+    diff
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchHistoryQuickPlay {
+    Singleplayer(Arc<str>),
+    Multiplayer(Arc<str>),
+    Realms(Arc<str>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchOutcome {
+    #[default]
+    Running,
+    Exited,
+    Crashed,
+    Killed,
+    FailedToLaunch,
+    /// The launcher stopped keeping track of the game before it exited
+    Unknown,
+}
+
+impl LaunchOutcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            LaunchOutcome::Running => "Running",
+            LaunchOutcome::Exited => "Exited",
+            LaunchOutcome::Crashed => "Crashed",
+            LaunchOutcome::Killed => "Killed",
+            LaunchOutcome::FailedToLaunch => "Failed to launch",
+            LaunchOutcome::Unknown => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(outcome: LaunchOutcome) -> LaunchHistoryEntry {
+        LaunchHistoryEntry {
+            started_at: 0,
+            account: None,
+            offline: false,
+            minecraft_version: Ustr::from("1.21.1"),
+            loader: Loader::Vanilla,
+            loader_version: None,
+            quick_play: None,
+            outcome,
+            duration_secs: None,
+            exit_code: None,
+            error: None,
+            mods: None,
+            suspected_mods: None,
+        }
+    }
+
+    fn outcomes(history: &LaunchHistory) -> Vec<LaunchOutcome> {
+        history.entries.iter().map(|entry| entry.outcome).collect()
+    }
+
+    #[test]
+    fn stale_running_entries_are_cleared() {
+        let mut history = LaunchHistory {
+            entries: vec![entry(LaunchOutcome::Running), entry(LaunchOutcome::Exited), entry(LaunchOutcome::Running)],
+        };
+        history.clear_stale_running(false);
+        assert_eq!(outcomes(&history), [LaunchOutcome::Unknown, LaunchOutcome::Exited, LaunchOutcome::Unknown]);
+        assert_eq!(history.running_count(), 0);
+    }
+
+    #[test]
+    fn newest_running_entry_is_kept() {
+        let mut history = LaunchHistory {
+            entries: vec![entry(LaunchOutcome::Running), entry(LaunchOutcome::Crashed), entry(LaunchOutcome::Running)],
+        };
+        history.clear_stale_running(true);
+        assert_eq!(outcomes(&history), [LaunchOutcome::Unknown, LaunchOutcome::Crashed, LaunchOutcome::Running]);
+    }
+
+    fn snapshot_mod(filename: &str, id: Option<&str>, sha1: &str) -> LaunchHistoryMod {
+        LaunchHistoryMod {
+            filename: filename.into(),
+            id: id.map(Arc::from),
+            name: None,
+            version: "1.0".into(),
+            sha1: sha1.into(),
+        }
+    }
+
+    #[test]
+    fn diff_mods_added_removed_updated() {
+        let old = [
+            snapshot_mod("sodium-0.5.jar", Some("sodium"), "aa"),
+            snapshot_mod("iris-1.6.jar", Some("iris"), "bb"),
+            snapshot_mod("lithium.jar", Some("lithium"), "cc"),
+        ];
+        let new = [
+            snapshot_mod("sodium-0.6.jar", Some("sodium"), "dd"),
+            snapshot_mod("lithium.jar", Some("lithium"), "cc"),
+            snapshot_mod("modmenu.jar", Some("modmenu"), "ee"),
+        ];
+
+        let diff = diff_mods(&old, &new);
+        assert_eq!(diff.added, [new[2].clone()]);
+        assert_eq!(diff.removed, [old[1].clone()]);
+        assert_eq!(diff.updated, [(old[0].clone(), new[0].clone())]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn diff_mods_without_ids_matches_filenames() {
+        let old = [snapshot_mod("a.jar", None, "aa"), snapshot_mod("b.jar", None, "bb")];
+        let new = [snapshot_mod("a.jar", None, "aa"), snapshot_mod("b-renamed.jar", None, "bb")];
+
+        let diff = diff_mods(&old, &new);
+        assert_eq!(diff.added, [new[1].clone()]);
+        assert_eq!(diff.removed, [old[1].clone()]);
+        assert!(diff.updated.is_empty());
+    }
+
+    #[test]
+    fn diff_mods_identical_is_empty() {
+        let mods = [snapshot_mod("a.jar", Some("a"), "aa"), snapshot_mod("b.jar", None, "bb")];
+        assert!(diff_mods(&mods, &mods).is_empty());
+        assert!(diff_mods(&[], &[]).is_empty());
+    }
+}
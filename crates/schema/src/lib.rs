@@ -3,20 +3,27 @@ use serde::Deserialize;
 pub mod assets_index;
 pub mod backend_config;
 pub mod content;
+pub mod crash_report;
 pub mod fabric_launch;
 pub mod fabric_loader_manifest;
 pub mod fabric_mod;
+pub mod following;
 pub mod forge;
 pub mod forge_mod;
 pub mod instance;
 pub mod java_runtime_component;
 pub mod java_runtimes;
+pub mod launch_history;
 pub mod loader;
 pub mod maven;
 pub mod modification;
 pub mod modrinth;
 pub mod mrpack;
+pub mod quilt_mod;
+pub mod redaction;
 pub mod resourcepack;
+pub mod rules;
+pub mod shared_profile;
 pub mod version;
 pub mod version_manifest;
 
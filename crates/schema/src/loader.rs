@@ -3,6 +3,21 @@ use serde::{Deserialize, Serialize};
 
 use crate::modrinth::ModrinthLoader;
 
+/// Library mod that most mods for a loader require, offered when the loader is installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoaderCompanion {
+    pub name: &'static str,
+    /// Id from the mod's metadata, used to tell if it's already installed
+    pub mod_id: &'static str,
+    pub modrinth_project_id: &'static str,
+}
+
+pub const FABRIC_API: LoaderCompanion = LoaderCompanion {
+    name: "Fabric API",
+    mod_id: "fabric-api",
+    modrinth_project_id: "P7dR8mSH",
+};
+
 #[derive(EnumSetType, Serialize, Deserialize, Debug, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Loader {
@@ -48,4 +63,12 @@ impl Loader {
             Loader::Unknown => ModrinthLoader::Unknown,
         }
     }
+
+    /// Forge and NeoForge ship their APIs as part of the loader, so only Fabric has a companion
+    pub fn companion(self) -> Option<LoaderCompanion> {
+        match self {
+            Loader::Fabric => Some(FABRIC_API),
+            _ => None,
+        }
+    }
 }
@@ -1,9 +1,12 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
+pub const MODRINTH_API_HOST: &str = "api.modrinth.com";
 pub const MODRINTH_SEARCH_URL: &str = "https://api.modrinth.com/v2/search";
+pub const MODRINTH_CATEGORY_TAGS_URL: &str = "https://api.modrinth.com/v2/tag/category";
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct ModrinthSearchRequest {
@@ -21,7 +24,7 @@ pub struct ModrinthProjectVersionsRequest {
     pub loaders: Option<Arc<[ModrinthLoader]>>,
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ModrinthSearchIndex {
     Relevance,
@@ -66,6 +69,17 @@ pub struct ModrinthHit {
     // pub featured_gallery: Option<Arc<str>>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthCategoryTags(pub Arc<[ModrinthCategoryTag]>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthCategoryTag {
+    pub name: Ustr,
+    pub project_type: ModrinthProjectType,
+    /// The group the category is shown under, e.g. "categories", "features" or "resolutions"
+    pub header: Arc<str>,
+}
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ModrinthSideRequirement {
@@ -115,6 +129,9 @@ pub struct ModrinthProjectVersion {
     pub version_type: Option<ModrinthVersionType>,
     pub status: Option<ModrinthVersionStatus>,
     pub files: Arc<[ModrinthFile]>,
+    pub changelog: Option<Arc<str>>,
+    pub date_published: Option<DateTime<Utc>>,
+    pub downloads: Option<usize>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -241,3 +258,72 @@ pub struct ModrinthHashes {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModrinthVersionFileUpdateResult(pub ModrinthProjectVersion);
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
+pub struct ModrinthVersionFilesRequest {
+    pub hashes: Arc<[Arc<str>]>,
+    pub algorithm: Arc<str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthVersionFilesResult(pub HashMap<Arc<str>, ModrinthProjectVersion>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthProject {
+    pub id: Arc<str>,
+    pub slug: Option<Arc<str>>,
+    pub title: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    pub team: Arc<str>,
+    pub project_type: Option<ModrinthProjectType>,
+    /// Long description in markdown
+    pub body: Option<Arc<str>>,
+    pub icon_url: Option<Arc<str>>,
+    pub downloads: Option<usize>,
+    pub followers: Option<usize>,
+    pub license: Option<ModrinthLicense>,
+    pub gallery: Option<Arc<[ModrinthGalleryImage]>>,
+    pub source_url: Option<Arc<str>>,
+    pub issues_url: Option<Arc<str>>,
+    pub wiki_url: Option<Arc<str>>,
+    pub discord_url: Option<Arc<str>>,
+    pub published: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthLicense {
+    pub id: Arc<str>,
+    pub name: Option<Arc<str>>,
+    pub url: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthGalleryImage {
+    pub url: Arc<str>,
+    pub featured: bool,
+    pub title: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    pub ordering: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthProjectsResult(pub Arc<[ModrinthProject]>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthTeamMember {
+    pub team_id: Arc<str>,
+    pub user: ModrinthUser,
+    pub role: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthUser {
+    pub username: Arc<str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthProjectMembers(pub Arc<[ModrinthTeamMember]>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthTeamsResult(pub Arc<[Arc<[ModrinthTeamMember]>]>);
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::Deserialize;
 
@@ -10,6 +10,10 @@ pub struct ModrinthIndexJson {
     pub version_id: Arc<str>,
     pub name: Arc<str>,
     pub files: Arc<[ModrinthModpackFileDownload]>,
+    pub summary: Option<Arc<str>>,
+    /// The Minecraft version under `minecraft` and the loader version under e.g. `fabric-loader`
+    #[serde(default)]
+    pub dependencies: HashMap<Arc<str>, Arc<str>>,
 
     // Unofficial
     #[serde(default, deserialize_with = "crate::try_deserialize")]
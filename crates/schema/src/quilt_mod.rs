@@ -0,0 +1,37 @@
+use std::{collections::HashMap, sync::Arc};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::fabric_mod::Icon;
+
+#[derive(Deserialize, Debug)]
+pub struct QuiltModJson {
+    pub quilt_loader: QuiltLoader,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QuiltLoader {
+    pub id: Arc<str>,
+    pub version: Arc<str>,
+    #[serde(default)]
+    pub metadata: QuiltMetadata,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct QuiltMetadata {
+    pub name: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    #[serde(default)]
+    pub contributors: IndexMap<Arc<str>, QuiltRoles>,
+    #[serde(default)]
+    pub contact: HashMap<Arc<str>, Arc<str>>,
+    pub icon: Option<Icon>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum QuiltRoles {
+    Single(Arc<str>),
+    Multiple(Vec<Arc<str>>),
+}
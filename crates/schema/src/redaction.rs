@@ -0,0 +1,44 @@
+use std::{borrow::Cow, sync::LazyLock};
+
+use regex::Regex;
+
+static REPLACEMENTS: LazyLock<[(Regex, &'static str); 10]> = LazyLock::new(|| {
+    [
+        // Access token replacements
+        (regex::Regex::new(r#"SignedJWT: [^\s]+"#).unwrap(), "SignedJWT: *****"),
+        (regex::Regex::new(r#"Session ID is [^\s)]+"#).unwrap(), "Session ID is *****"),
+        (regex::Regex::new(r#"--accessToken, [^\s,]+"#).unwrap(), "--accessToken, *****"),
+        (regex::Regex::new(r#"--accessToken [^\s,]+"#).unwrap(), "--accessToken *****"),
+        // Legacy session argument, token:<access token>:<uuid>
+        (regex::Regex::new(r#"token:[^\s:]+:"#).unwrap(), "token:*****:"),
+        // Access tokens are JWTs, which are caught wherever else they're printed
+        (regex::Regex::new(r#"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+"#).unwrap(), "*****"),
+        // Computer username replacements
+        (regex::Regex::new(r#"\/home\/[^/]+\/"#).unwrap(), "/home/*****/"),
+        (regex::Regex::new(r#"\/Users\/[^/]+\/"#).unwrap(), "/Users/*****/"),
+        (regex::Regex::new(r#"\\Users\\[^\\]+\\"#).unwrap(), "\\Users\\*****\\"),
+        (regex::Regex::new(r#"\\\\Users\\\\[^/]+\\\\"#).unwrap(), "\\\\Users\\\\*****\\\\"),
+    ]
+});
+
+pub fn redact(string: &str) -> Cow<'_, str> {
+    let mut replaced = Cow::Borrowed(string);
+    for (regex, replacement) in &*REPLACEMENTS {
+        if let Cow::Owned(new) = regex.replace_all(&replaced, *replacement) {
+            replaced = Cow::Owned(new);
+        }
+    }
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_and_usernames_are_hidden() {
+        let redacted = redact("--accessToken eyJhbGciOi.eyJzdWIi.c2lnbmF0dXJl at /home/alex/.minecraft and C:\\Users\\alex\\AppData");
+        assert_eq!(redacted, "--accessToken ***** at /home/*****/.minecraft and C:\\Users\\*****\\AppData");
+        assert!(matches!(redact("nothing to hide"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,286 @@
+use regex::Regex;
+
+use crate::version::{OsArch, OsName, Rule, RuleAction, RuleFeatures};
+
+/// The operating system that rules are evaluated against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleOsEnvironment {
+    pub name: Option<OsName>,
+    pub arch: Option<OsArch>,
+    pub pointer_width: u32,
+    /// Only needed for rules from 23w17a and below, which match the OS version with a regex
+    pub version: Option<String>,
+}
+
+impl RuleOsEnvironment {
+    /// The environment of the running process. The OS version is left empty since
+    /// it requires querying the system, use [`RuleOsEnvironment::with_version`] to set it
+    pub fn current() -> Self {
+        Self::from_consts(std::env::consts::OS, std::env::consts::ARCH)
+    }
+
+    pub fn from_consts(os: &str, arch: &str) -> Self {
+        let name = match os {
+            "linux" => Some(OsName::Linux),
+            "macos" => Some(OsName::Osx),
+            "windows" => Some(OsName::Windows),
+            _ => None,
+        };
+        let (arch, pointer_width) = match arch {
+            "x86" => (Some(OsArch::X86), 32),
+            "aarch64" => (Some(OsArch::Arm64), 64),
+            "arm" => (None, 32),
+            _ => (None, 64),
+        };
+        Self {
+            name,
+            arch,
+            pointer_width,
+            version: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Legacy native classifiers contain an `${arch}` placeholder, e.g. `natives-windows-${arch}`
+    pub fn expand_natives_classifier(&self, classifier: &str) -> String {
+        classifier.replace("${arch}", &self.pointer_width.to_string())
+    }
+}
+
+/// Launcher features that rules can be gated on, mirrors the `features` object of a rule
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RuleFeatureSet {
+    pub is_demo_user: bool,
+    pub has_custom_resolution: bool,
+    pub has_quick_plays_support: bool,
+    pub is_quick_play_singleplayer: bool,
+    pub is_quick_play_multiplayer: bool,
+    pub is_quick_play_realms: bool,
+}
+
+impl RuleFeatureSet {
+    fn matches(&self, features: &RuleFeatures) -> bool {
+        fn matches(expected: Option<bool>, actual: bool) -> bool {
+            expected.is_none_or(|expected| expected == actual)
+        }
+
+        matches(features.is_demo_user, self.is_demo_user)
+            && matches(features.has_custom_resolution, self.has_custom_resolution)
+            && matches(features.has_quick_plays_support, self.has_quick_plays_support)
+            && matches(features.is_quick_play_singleplayer, self.is_quick_play_singleplayer)
+            && matches(features.is_quick_play_multiplayer, self.is_quick_play_multiplayer)
+            && matches(features.is_quick_play_realms, self.is_quick_play_realms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleContext {
+    pub os: RuleOsEnvironment,
+    pub features: RuleFeatureSet,
+}
+
+impl RuleContext {
+    /// Evaluates a list of rules the same way the vanilla launcher does: everything is
+    /// disallowed by default and the last matching rule decides the outcome
+    pub fn is_allowed(&self, rules: &[Rule]) -> bool {
+        let mut allowed = false;
+        for rule in rules {
+            if self.matches(rule) {
+                allowed = rule.action == RuleAction::Allow;
+            }
+        }
+        allowed
+    }
+
+    /// Like [`RuleContext::is_allowed`], but a missing rule list allows everything
+    pub fn is_allowed_optional(&self, rules: Option<&[Rule]>) -> bool {
+        rules.is_none_or(|rules| self.is_allowed(rules))
+    }
+
+    pub fn matches(&self, rule: &Rule) -> bool {
+        if let Some(features) = &rule.features && !self.features.matches(features) {
+            return false;
+        }
+
+        if let Some(os) = &rule.os {
+            if let Some(name) = os.name && self.os.name != Some(name) {
+                return false;
+            }
+            if let Some(arch) = os.arch && self.os.arch != Some(arch) {
+                return false;
+            }
+            if let Some(version) = &os.version {
+                // An unknown OS version can't be matched, an invalid regex is ignored like the vanilla launcher does
+                let Some(os_version) = &self.os.version else {
+                    return false;
+                };
+                if let Ok(regex) = Regex::new(version.as_str()) && !regex.is_match(os_version) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::RuleOs;
+
+    fn context(os: &str, arch: &str) -> RuleContext {
+        RuleContext {
+            os: RuleOsEnvironment::from_consts(os, arch),
+            features: RuleFeatureSet::default(),
+        }
+    }
+
+    fn rule(action: RuleAction, os: Option<RuleOs>, features: Option<RuleFeatures>) -> Rule {
+        Rule { action, features, os }
+    }
+
+    fn os(name: Option<OsName>, arch: Option<OsArch>, version: Option<&str>) -> Option<RuleOs> {
+        Some(RuleOs { name, arch, version: version.map(Into::into) })
+    }
+
+    fn features(f: impl FnOnce(&mut RuleFeatures)) -> Option<RuleFeatures> {
+        let mut features = RuleFeatures::default();
+        f(&mut features);
+        Some(features)
+    }
+
+    #[test]
+    fn empty_rules_disallow() {
+        assert!(!context("linux", "x86_64").is_allowed(&[]));
+        assert!(context("linux", "x86_64").is_allowed_optional(None));
+        assert!(!context("linux", "x86_64").is_allowed_optional(Some(&[])));
+    }
+
+    #[test]
+    fn allow_except_osx() {
+        // Used by older LWJGL 2 libraries
+        let rules = [
+            rule(RuleAction::Allow, None, None),
+            rule(RuleAction::Disallow, os(Some(OsName::Osx), None, None), None),
+        ];
+        assert!(context("linux", "x86_64").is_allowed(&rules));
+        assert!(context("windows", "x86_64").is_allowed(&rules));
+        assert!(!context("macos", "aarch64").is_allowed(&rules));
+    }
+
+    #[test]
+    fn only_osx() {
+        let rules = [rule(RuleAction::Allow, os(Some(OsName::Osx), None, None), None)];
+        assert!(!context("linux", "x86_64").is_allowed(&rules));
+        assert!(context("macos", "x86_64").is_allowed(&rules));
+    }
+
+    #[test]
+    fn arch() {
+        let rules = [rule(RuleAction::Allow, os(None, Some(OsArch::X86), None), None)];
+        assert!(context("windows", "x86").is_allowed(&rules));
+        assert!(!context("windows", "x86_64").is_allowed(&rules));
+        assert!(!context("windows", "aarch64").is_allowed(&rules));
+
+        let rules = [rule(RuleAction::Allow, os(Some(OsName::Windows), Some(OsArch::Arm64), None), None)];
+        assert!(context("windows", "aarch64").is_allowed(&rules));
+        assert!(!context("linux", "aarch64").is_allowed(&rules));
+    }
+
+    #[test]
+    fn os_version() {
+        let rules = [
+            rule(RuleAction::Allow, None, None),
+            rule(RuleAction::Disallow, os(Some(OsName::Osx), None, Some("^10\\.5\\.\\d$")), None),
+        ];
+        let mut context = context("macos", "x86_64");
+        assert!(context.is_allowed(&rules));
+
+        context.os = context.os.with_version("10.5.8");
+        assert!(!context.is_allowed(&rules));
+
+        context.os = context.os.with_version("14.1.2");
+        assert!(context.is_allowed(&rules));
+    }
+
+    #[test]
+    fn invalid_os_version_regex_is_ignored() {
+        let rules = [rule(RuleAction::Allow, os(None, None, Some("(")), None)];
+        let context = RuleContext {
+            os: RuleOsEnvironment::from_consts("linux", "x86_64").with_version("6.1"),
+            features: RuleFeatureSet::default(),
+        };
+        assert!(context.is_allowed(&rules));
+    }
+
+    #[test]
+    fn features_toggle_arguments() {
+        let demo = [rule(RuleAction::Allow, None, features(|f| f.is_demo_user = Some(true)))];
+        let resolution = [rule(RuleAction::Allow, None, features(|f| f.has_custom_resolution = Some(true)))];
+        let quick_play_support = [rule(RuleAction::Allow, None, features(|f| f.has_quick_plays_support = Some(true)))];
+        let singleplayer = [rule(RuleAction::Allow, None, features(|f| f.is_quick_play_singleplayer = Some(true)))];
+        let multiplayer = [rule(RuleAction::Allow, None, features(|f| f.is_quick_play_multiplayer = Some(true)))];
+        let realms = [rule(RuleAction::Allow, None, features(|f| f.is_quick_play_realms = Some(true)))];
+
+        let mut context = context("linux", "x86_64");
+        for rules in [&demo, &resolution, &quick_play_support, &singleplayer, &multiplayer, &realms] {
+            assert!(!context.is_allowed(rules));
+        }
+
+        context.features.is_demo_user = true;
+        context.features.has_custom_resolution = true;
+        context.features.is_quick_play_multiplayer = true;
+        assert!(context.is_allowed(&demo));
+        assert!(context.is_allowed(&resolution));
+        assert!(context.is_allowed(&multiplayer));
+        assert!(!context.is_allowed(&quick_play_support));
+        assert!(!context.is_allowed(&singleplayer));
+        assert!(!context.is_allowed(&realms));
+    }
+
+    #[test]
+    fn feature_explicitly_false() {
+        let rules = [rule(RuleAction::Allow, None, features(|f| f.is_demo_user = Some(false)))];
+        let mut context = context("linux", "x86_64");
+        assert!(context.is_allowed(&rules));
+        context.features.is_demo_user = true;
+        assert!(!context.is_allowed(&rules));
+    }
+
+    #[test]
+    fn features_and_os_combined() {
+        let rules = [rule(RuleAction::Allow, os(Some(OsName::Windows), None, None), features(|f| f.has_custom_resolution = Some(true)))];
+        let mut context = context("windows", "x86_64");
+        assert!(!context.is_allowed(&rules));
+        context.features.has_custom_resolution = true;
+        assert!(context.is_allowed(&rules));
+        context.os = RuleOsEnvironment::from_consts("linux", "x86_64");
+        assert!(!context.is_allowed(&rules));
+    }
+
+    #[test]
+    fn deserialize_vanilla_rules() {
+        let json = r#"[
+            {"action": "allow"},
+            {"action": "disallow", "os": {"name": "osx"}},
+            {"action": "allow", "features": {"has_custom_resolution": true}}
+        ]"#;
+        let rules: Vec<Rule> = serde_json::from_str(json).unwrap();
+        assert_eq!(rules[2].features.as_ref().unwrap().has_custom_resolution, Some(true));
+        assert_eq!(rules[2].features.as_ref().unwrap().is_demo_user, None);
+        assert!(context("linux", "x86_64").is_allowed(&rules));
+        assert!(!context("macos", "x86_64").is_allowed(&rules));
+    }
+
+    #[test]
+    fn natives_classifier() {
+        assert_eq!(RuleOsEnvironment::from_consts("windows", "x86").expand_natives_classifier("natives-windows-${arch}"), "natives-windows-32");
+        assert_eq!(RuleOsEnvironment::from_consts("windows", "x86_64").expand_natives_classifier("natives-windows-${arch}"), "natives-windows-64");
+        assert_eq!(RuleOsEnvironment::from_consts("linux", "x86_64").expand_natives_classifier("natives-linux"), "natives-linux");
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::{instance::{InstanceConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration}, loader::Loader};
+
+/// The settings of an instance and the Modrinth versions of its content, shared without any of the files themselves
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SharedProfile {
+    pub format_version: u32,
+    pub name: Arc<str>,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    /// Not set when the instance uses the latest loader version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<Ustr>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "Option::is_none")]
+    pub memory: Option<InstanceMemoryConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "Option::is_none")]
+    pub jvm_flags: Option<InstanceJvmFlagsConfiguration>,
+    #[serde(default)]
+    pub content: Vec<SharedProfileContent>,
+}
+
+impl SharedProfile {
+    pub const FORMAT_VERSION: u32 = 1;
+    pub const FILE_SUFFIX: &str = ".profile.json";
+
+    /// JVM flags can run arbitrary code (e.g. -javaagent), so they're only applied when the user explicitly
+    /// accepted the flags shown to them
+    pub fn configuration(&self, apply_jvm_flags: bool) -> InstanceConfiguration {
+        let mut configuration = InstanceConfiguration::new(self.minecraft_version, self.loader);
+        configuration.preferred_loader_version = self.loader_version;
+        configuration.memory = self.memory;
+        if apply_jvm_flags {
+            configuration.jvm_flags = self.jvm_flags.clone();
+        }
+        configuration
+    }
+
+    /// The JVM flags that would be applied, if they're enabled and non-empty
+    pub fn enabled_jvm_flags(&self) -> Option<&str> {
+        self.jvm_flags.as_ref()
+            .filter(|jvm_flags| jvm_flags.enabled && !jvm_flags.flags.trim().is_empty())
+            .map(|jvm_flags| &*jvm_flags.flags)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SharedProfileContent {
+    /// Path inside .minecraft with forward slashes
+    pub path: Arc<str>,
+    pub project_id: Arc<str>,
+    pub version_id: Arc<str>,
+}
+
+impl SharedProfileContent {
+    /// Folders inside .minecraft whose content is shared, profiles can't place files anywhere else
+    pub const FOLDERS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+
+    /// Whether the path is a file directly inside one of the shared folders
+    pub fn is_content_path(path: &str) -> bool {
+        let Some((folder, file_name)) = path.split_once('/') else {
+            return false;
+        };
+        Self::FOLDERS.contains(&folder) && !file_name.is_empty() && !file_name.contains(['/', '\\']) && file_name != ".."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_profile_uses_defaults() {
+        let profile: SharedProfile = serde_json::from_value(serde_json::json!({
+            "format_version": 1,
+            "name": "Friends",
+            "minecraft_version": "1.21.1",
+            "loader": "fabric",
+            "memory": "invalid",
+            "content": [{ "path": "mods/sodium.jar", "project_id": "AANobbMI", "version_id": "abc" }],
+        })).unwrap();
+
+        let configuration = profile.configuration(false);
+        assert_eq!(configuration.loader, Loader::Fabric);
+        assert!(configuration.preferred_loader_version.is_none());
+        assert!(configuration.memory.is_none());
+        assert_eq!(&*profile.content[0].version_id, "abc");
+    }
+
+    #[test]
+    fn jvm_flags_are_not_applied_silently() {
+        let profile: SharedProfile = serde_json::from_value(serde_json::json!({
+            "format_version": 1,
+            "name": "Friends",
+            "minecraft_version": "1.21.1",
+            "loader": "fabric",
+            "jvm_flags": { "enabled": true, "flags": "-XX:OnOutOfMemoryError=calc.exe" },
+        })).unwrap();
+
+        assert_eq!(profile.enabled_jvm_flags(), Some("-XX:OnOutOfMemoryError=calc.exe"));
+        assert!(profile.configuration(false).jvm_flags.is_none());
+        assert!(profile.configuration(true).jvm_flags.is_some_and(|jvm_flags| jvm_flags.enabled));
+    }
+
+    #[test]
+    fn content_paths_stay_in_shared_folders() {
+        assert!(SharedProfileContent::is_content_path("mods/sodium.jar"));
+        assert!(SharedProfileContent::is_content_path("shaderpacks/complementary.zip"));
+        assert!(!SharedProfileContent::is_content_path("config/sodium.json"));
+        assert!(!SharedProfileContent::is_content_path("mods/../options.txt"));
+        assert!(!SharedProfileContent::is_content_path("mods/"));
+        assert!(!SharedProfileContent::is_content_path("sodium.jar"));
+    }
+}
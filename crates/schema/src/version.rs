@@ -84,21 +84,16 @@ pub enum RuleAction {
     Disallow,
 }
 
-#[derive(Deserialize, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// Each present feature must equal the launcher's value for the rule to match
+#[derive(Deserialize, Default, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct RuleFeatures {
-    #[serde(default)]
-    pub is_demo_user: bool,
-    #[serde(default)]
-    pub has_custom_resolution: bool,
-    #[serde(default)]
-    pub has_quick_plays_support: bool,
-    #[serde(default)]
-    pub is_quick_play_singleplayer: bool,
-    #[serde(default)]
-    pub is_quick_play_multiplayer: bool,
-    #[serde(default)]
-    pub is_quick_play_realms: bool,
+    pub is_demo_user: Option<bool>,
+    pub has_custom_resolution: Option<bool>,
+    pub has_quick_plays_support: Option<bool>,
+    pub is_quick_play_singleplayer: Option<bool>,
+    pub is_quick_play_multiplayer: Option<bool>,
+    pub is_quick_play_realms: Option<bool>,
 }
 
 #[derive(Deserialize, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
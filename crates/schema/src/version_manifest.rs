@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::Deserialize;
 use ustr::Ustr;
 
@@ -39,3 +39,91 @@ pub enum MinecraftVersionType {
     OldBeta,
     OldAlpha,
 }
+
+/// April fools versions are listed as snapshots, so they're recognized by id
+const APRIL_FOOLS_VERSIONS: &[&str] = &[
+    "15w14a",
+    "1.RV-Pre1",
+    "3D Shareware v1.34",
+    "20w14infinite",
+    "22w13oneblockatatime",
+    "23w13a_or_b",
+    "24w14potato",
+    "25w14craftmine",
+];
+
+impl MinecraftVersionLink {
+    /// Joke versions released for april fools. Future ones are guessed from being a snapshot released on
+    /// the first of april without the usual `YYwWWa` snapshot id
+    pub fn is_april_fools(&self) -> bool {
+        if !matches!(self.r#type, MinecraftVersionType::Snapshot) {
+            return false;
+        }
+        if APRIL_FOOLS_VERSIONS.contains(&self.id.as_str()) {
+            return true;
+        }
+        self.release_time.month() == 4 && self.release_time.day() == 1 && !is_weekly_snapshot_id(&self.id)
+    }
+
+    /// Quick play arguments were added in 23w14a, older versions ignore them and open the title screen
+    pub fn supports_quick_play(&self) -> bool {
+        self.release_time >= QUICK_PLAY_RELEASE_TIME.parse::<DateTime<Utc>>().unwrap()
+    }
+}
+
+const QUICK_PLAY_RELEASE_TIME: &str = "2023-04-05T00:00:00Z";
+
+fn is_weekly_snapshot_id(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    bytes.len() == 6
+        && bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2] == b'w'
+        && bytes[3..5].iter().all(u8::is_ascii_digit)
+        && bytes[5].is_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, r#type: MinecraftVersionType, release_time: &str) -> MinecraftVersionLink {
+        let release_time: DateTime<Utc> = release_time.parse().unwrap();
+        MinecraftVersionLink {
+            id: id.into(),
+            r#type,
+            url: "".into(),
+            time: release_time,
+            release_time,
+            sha1: "".into(),
+            compliance_level: 0,
+        }
+    }
+
+    #[test]
+    fn known_april_fools() {
+        assert!(version("20w14infinite", MinecraftVersionType::Snapshot, "2020-04-01T12:00:00Z").is_april_fools());
+        assert!(version("1.RV-Pre1", MinecraftVersionType::Snapshot, "2016-03-31T14:00:00Z").is_april_fools());
+        assert!(version("15w14a", MinecraftVersionType::Snapshot, "2015-04-01T09:00:00Z").is_april_fools());
+    }
+
+    #[test]
+    fn future_april_fools() {
+        assert!(version("26w14spaceship", MinecraftVersionType::Snapshot, "2026-04-01T12:00:00Z").is_april_fools());
+    }
+
+    #[test]
+    fn regular_versions() {
+        assert!(!version("21w13a", MinecraftVersionType::Snapshot, "2021-03-31T12:00:00Z").is_april_fools());
+        assert!(!version("26w14a", MinecraftVersionType::Snapshot, "2026-04-01T12:00:00Z").is_april_fools());
+        assert!(!version("1.21.5-pre1", MinecraftVersionType::Snapshot, "2025-03-12T12:00:00Z").is_april_fools());
+        assert!(!version("1.21.5", MinecraftVersionType::Release, "2025-04-01T12:00:00Z").is_april_fools());
+    }
+
+    #[test]
+    fn quick_play_support() {
+        assert!(version("23w14a", MinecraftVersionType::Snapshot, "2023-04-05T12:05:17Z").supports_quick_play());
+        assert!(version("1.21.1", MinecraftVersionType::Release, "2024-08-08T12:24:45Z").supports_quick_play());
+        assert!(!version("23w13a", MinecraftVersionType::Snapshot, "2023-03-29T12:00:00Z").supports_quick_play());
+        assert!(!version("1.19.4", MinecraftVersionType::Release, "2023-03-14T12:56:18Z").supports_quick_play());
+    }
+}
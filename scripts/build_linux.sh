@@ -8,7 +8,7 @@ fi
 version=${1#v}
 
 sudo apt-get update --yes && sudo apt-get install --yes libssl-dev libdbus-1-dev libx11-xcb1 libxkbcommon-x11-dev pkg-config
-cargo build --release --target x86_64-unknown-linux-gnu
+PANDORA_RELEASE_VERSION="$version" cargo build --release --target x86_64-unknown-linux-gnu
 strip target/x86_64-unknown-linux-gnu/release/pandora_launcher
 mkdir -p dist
 mv target/x86_64-unknown-linux-gnu/release/pandora_launcher dist/PandoraLauncher-Linux
@@ -22,6 +22,7 @@ cargo packager --config '{'\
 '  "version": "'"$version"'",'\
 '  "identifier": "com.moulberry.pandoralauncher",'\
 '  "resources": [],'\
+'  "fileAssociations": [{ "extensions": ["mrpack"], "mimeType": "application/x-modrinth-modpack+zip", "description": "Modrinth Modpack", "role": "Viewer" }],'\
 '  "binaries": [{ "path": "PandoraLauncher-Linux", "main": true }],'\
 '  "icons": ["package/windows.ico"]'\
 '}'
@@ -7,8 +7,8 @@ fi
 
 version=${1#v}
 
-cargo build --release --target aarch64-apple-darwin
-cargo build --release --target x86_64-apple-darwin
+PANDORA_RELEASE_VERSION="$version" cargo build --release --target aarch64-apple-darwin
+PANDORA_RELEASE_VERSION="$version" cargo build --release --target x86_64-apple-darwin
 
 strip target/aarch64-apple-darwin/release/pandora_launcher
 strip target/x86_64-apple-darwin/release/pandora_launcher
@@ -26,6 +26,7 @@ cargo packager --config '{'\
 '  "version": "'"$version"'",'\
 '  "identifier": "com.moulberry.pandoralauncher",'\
 '  "resources": [],'\
+'  "fileAssociations": [{ "extensions": ["mrpack"], "mimeType": "application/x-modrinth-modpack+zip", "description": "Modrinth Modpack", "role": "Viewer" }],'\
 '  "binaries": [{ "path": "PandoraLauncher-macOS", "main": true }],'\
 '  "icons": ["package/mac.icns"]'\
 '}'
@@ -7,7 +7,7 @@ fi
 
 version=${1#v}
 
-cargo build --release --target x86_64-pc-windows-msvc
+PANDORA_RELEASE_VERSION="$version" cargo build --release --target x86_64-pc-windows-msvc
 strip target/x86_64-pc-windows-msvc/release/pandora_launcher.exe
 
 mkdir -p dist
@@ -22,6 +22,7 @@ cargo packager --config '{'\
 '  "version": "'"$version"'",'\
 '  "identifier": "com.moulberry.pandoralauncher",'\
 '  "resources": [],'\
+'  "fileAssociations": [{ "extensions": ["mrpack"], "mimeType": "application/x-modrinth-modpack+zip", "description": "Modrinth Modpack", "role": "Viewer" }],'\
 '  "binaries": [{ "path": "PandoraLauncher-Windows.exe", "main": true }],'\
 '  "icons": ["package/windows.ico"]'\
 '}'
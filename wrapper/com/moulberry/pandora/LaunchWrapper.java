@@ -1,13 +1,24 @@
 package com.moulberry.pandora;
 
+import java.awt.Graphics2D;
+import java.awt.RenderingHints;
+import java.awt.image.BufferedImage;
+import java.io.File;
+import java.lang.reflect.Field;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Proxy;
+import java.nio.ByteBuffer;
 import java.util.Scanner;
 import java.util.List;
 import java.util.ArrayList;
+import javax.imageio.ImageIO;
 
 public class LaunchWrapper {
     public static void main(String[] args) throws Throwable {
         Scanner scanner = new Scanner(System.in);
         List<String> arguments = new ArrayList<>();
+        String windowTitle = null;
+        String windowIcon = null;
         while (true) {
             String command = scanner.nextLine();
 
@@ -24,11 +35,91 @@ public class LaunchWrapper {
                 System.setProperty(value, propertyValue);
             } else if (command.equals("printproperty")) {
                 System.out.println(System.getProperty(value));
+            } else if (command.equals("windowtitle")) {
+                windowTitle = value;
+            } else if (command.equals("windowicon")) {
+                windowIcon = value;
             } else if (command.equals("launch")) {
+                if (windowTitle != null || windowIcon != null) {
+                    customizeLegacyWindow(windowTitle, windowIcon);
+                }
                 String[] argumentsArray = arguments.toArray(new String[0]);
                 Class.forName(value).getDeclaredMethod("main", String[].class).invoke(null, (Object) argumentsArray);
                 return;
             }
         }
     }
+
+    // LWJGL 2 exposes the game window through the static org.lwjgl.opengl.Display class. Its methods have to
+    // be called on the thread that owns the window, so instead of calling them from here the display
+    // implementation is wrapped and the game's own setTitle and setIcon calls are replaced with the
+    // customized ones as the game makes them. LWJGL 3 versions are skipped
+    private static void customizeLegacyWindow(String title, String icon) {
+        try {
+            Class<?> display = Class.forName("org.lwjgl.opengl.Display", true, ClassLoader.getSystemClassLoader());
+            Class<?> implementationClass = Class.forName("org.lwjgl.opengl.DisplayImplementation", false, display.getClassLoader());
+            Field implementationField = display.getDeclaredField("display_impl");
+            implementationField.setAccessible(true);
+            Object implementation = implementationField.get(null);
+
+            ByteBuffer[] icons = null;
+            if (icon != null) {
+                BufferedImage image = ImageIO.read(new File(icon));
+                if (image != null) {
+                    icons = new ByteBuffer[] { toRgba(image, 16), toRgba(image, 32) };
+                }
+            }
+            ByteBuffer[] customIcons = icons;
+
+            Object wrapped = Proxy.newProxyInstance(implementationClass.getClassLoader(), new Class<?>[] { implementationClass }, (proxy, method, args) -> {
+                if (title != null && method.getName().equals("setTitle") && args != null && args.length == 1) {
+                    args = new Object[] { title };
+                } else if (customIcons != null && method.getName().equals("setIcon") && args != null && args.length == 1) {
+                    for (ByteBuffer buffer : customIcons) {
+                        buffer.rewind();
+                    }
+                    args = new Object[] { customIcons };
+                }
+                try {
+                    method.setAccessible(true);
+                    return method.invoke(implementation, args);
+                } catch (InvocationTargetException e) {
+                    throw e.getCause();
+                }
+            });
+
+            // The field is final, so it's replaced through Unsafe before the game has used the display
+            Field unsafeField = Class.forName("sun.misc.Unsafe").getDeclaredField("theUnsafe");
+            unsafeField.setAccessible(true);
+            Object unsafe = unsafeField.get(null);
+            Class<?> unsafeClass = unsafe.getClass();
+            Object base = unsafeClass.getMethod("staticFieldBase", Field.class).invoke(unsafe, implementationField);
+            long offset = (Long) unsafeClass.getMethod("staticFieldOffset", Field.class).invoke(unsafe, implementationField);
+            unsafeClass.getMethod("putObjectVolatile", Object.class, long.class, Object.class).invoke(unsafe, base, offset, wrapped);
+        } catch (ClassNotFoundException ignored) {
+        } catch (Throwable t) {
+            System.err.println("Pandora: unable to customize game window: " + t);
+        }
+    }
+
+    private static ByteBuffer toRgba(BufferedImage image, int size) {
+        BufferedImage scaled = new BufferedImage(size, size, BufferedImage.TYPE_INT_ARGB);
+        Graphics2D graphics = scaled.createGraphics();
+        graphics.setRenderingHint(RenderingHints.KEY_INTERPOLATION, RenderingHints.VALUE_INTERPOLATION_BILINEAR);
+        graphics.drawImage(image, 0, 0, size, size, null);
+        graphics.dispose();
+
+        ByteBuffer buffer = ByteBuffer.allocateDirect(size * size * 4);
+        for (int y = 0; y < size; y++) {
+            for (int x = 0; x < size; x++) {
+                int argb = scaled.getRGB(x, y);
+                buffer.put((byte) (argb >> 16));
+                buffer.put((byte) (argb >> 8));
+                buffer.put((byte) argb);
+                buffer.put((byte) (argb >> 24));
+            }
+        }
+        buffer.flip();
+        return buffer;
+    }
 }